@@ -0,0 +1,47 @@
+//! Key lookup benchmarks for several node orders.
+//!
+//! Nodes with less than `Storage::LINEAR_SEARCH_THRESHOLD` items are
+//! searched linearly, larger nodes with a binary search.
+//! Comparing the results for different orders, or for different values of
+//! `util::LINEAR_SEARCH_THRESHOLD`, shows where a linear scan stops being
+//! faster than a binary search.
+//!
+//! Run with `cargo bench --bench search`.
+use generic_btree::slab::AugmentedMap;
+use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
+use std::{hint::black_box, time::Instant};
+
+const SEED: &[u8; 16] = b"testseedtestseed";
+const LEN: usize = 4_096;
+const ROUNDS: usize = 200;
+
+fn bench<const M: usize>(keys: &[usize]) {
+    let map: AugmentedMap<usize, usize, (), M> = keys.iter().map(|k| (*k, *k)).collect();
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for key in keys {
+            black_box(map.get(black_box(key)));
+        }
+    }
+
+    let elapsed = start.elapsed();
+    println!(
+        "order {:>3}: {:>6.1} ns/lookup",
+        M,
+        elapsed.as_nanos() as f64 / (ROUNDS * keys.len()) as f64
+    );
+}
+
+fn main() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..LEN).collect();
+    keys.shuffle(&mut rng);
+
+    bench::<8>(&keys);
+    bench::<12>(&keys);
+    bench::<16>(&keys);
+    bench::<24>(&keys);
+    bench::<32>(&keys);
+    bench::<64>(&keys);
+}
@@ -0,0 +1,186 @@
+use crate::{
+    map::Binding,
+    slab::{
+        Node, OccupiedSlots, RawSlots, SlotCapacity, SlotIndex, Storage, VacantSlots, DEFAULT_ORDER,
+    },
+};
+use alloc::vec::Vec;
+
+pub type AugmentedMapStorage<K, V, A, const N: usize, const M: usize = DEFAULT_ORDER> =
+    Storage<Binding<K, V>, ArraySlab<Node<Binding<K, V>, M>, N>, A, M>;
+pub type MapStorage<K, V, const N: usize> = AugmentedMapStorage<K, V, (), N>;
+pub type Map<K, V, const N: usize> = crate::Map<MapStorage<K, V, N>>;
+pub type AugmentedMap<K, V, A, const N: usize, const M: usize = DEFAULT_ORDER> =
+    crate::Map<AugmentedMapStorage<K, V, A, N, M>>;
+pub type CounterMap<K, const N: usize> = crate::map::CounterMap<MapStorage<K, usize, N>>;
+
+/// Slot.
+enum Slot<T> {
+    /// Vacant slot, holding the index of the next vacant slot.
+    Vacant(usize),
+
+    /// Occupied slot.
+    Occupied(T),
+}
+
+/// Fixed-capacity slab, storing at most `N` values.
+///
+/// Values are stored inline in an array, so the slab never allocates.
+/// Inserting a value in a full slab panics:
+/// use [`Map::insert_within_capacity`](crate::Map::insert_within_capacity) to get an error instead.
+///
+/// Note that the augmentations of a [`Storage`] are not stored in the slab:
+/// only storages without augmentation are free of heap allocations.
+///
+/// # Example
+///
+/// ```
+/// use generic_btree::array_storage::Map;
+///
+/// let mut map: Map<usize, usize, 4> = Map::new();
+///
+/// let mut i = 0;
+/// while map.insert_within_capacity(i, i).is_ok() {
+///     i += 1
+/// }
+///
+/// assert_eq!(map.len(), i);
+/// assert_eq!(map.insert_within_capacity(0, 1), Ok(Some(0)));
+/// ```
+pub struct ArraySlab<T, const N: usize> {
+    /// Slots.
+    slots: [Slot<T>; N],
+
+    /// Index of the first vacant slot, or `N` if the slab is full.
+    next_vacant: usize,
+
+    /// Number of occupied slots.
+    len: usize,
+}
+
+impl<T, const N: usize> ArraySlab<T, N> {
+    /// Create a new empty slab.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            slots: core::array::from_fn(|i| Slot::Vacant(i + 1)),
+            next_vacant: 0,
+            len: 0,
+        }
+    }
+}
+
+impl<T, const N: usize> Default for ArraySlab<T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> SlotIndex for ArraySlab<T, N> {}
+
+impl<T, const N: usize> SlotCapacity for ArraySlab<T, N> {}
+
+impl<T, const N: usize> RawSlots for ArraySlab<T, N> {}
+
+impl<T, const N: usize> VacantSlots for ArraySlab<T, N> {
+    #[inline]
+    fn vacant_slots(&self) -> Option<usize> {
+        Some(N - self.len)
+    }
+}
+
+impl<T, const N: usize> OccupiedSlots for ArraySlab<T, N> {
+    #[inline]
+    fn occupied_keys(&self) -> Option<Vec<usize>> {
+        Some(
+            self.slots
+                .iter()
+                .enumerate()
+                .filter(|(_, slot)| matches!(slot, Slot::Occupied(_)))
+                .map(|(key, _)| key)
+                .collect(),
+        )
+    }
+}
+
+impl<T, const N: usize> cc_traits::Collection for ArraySlab<T, N> {
+    type Item = T;
+}
+
+impl<T, const N: usize> cc_traits::Len for ArraySlab<T, N> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T, const N: usize> cc_traits::Get<usize> for ArraySlab<T, N> {
+    #[inline]
+    fn get(&self, key: usize) -> Option<&T> {
+        match self.slots.get(key) {
+            Some(Slot::Occupied(value)) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl<T, const N: usize> cc_traits::GetMut<usize> for ArraySlab<T, N> {
+    #[inline]
+    fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        match self.slots.get_mut(key) {
+            Some(Slot::Occupied(value)) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl<T, const N: usize> cc_traits::Insert for ArraySlab<T, N> {
+    type Output = usize;
+
+    /// Inserts a value in the slab and returns its key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slab is full.
+    #[inline]
+    fn insert(&mut self, value: T) -> usize {
+        let key = self.next_vacant;
+        match self.slots.get_mut(key) {
+            Some(slot) => match core::mem::replace(slot, Slot::Occupied(value)) {
+                Slot::Vacant(next) => {
+                    self.next_vacant = next;
+                    self.len += 1;
+                    key
+                }
+                Slot::Occupied(_) => unreachable!(),
+            },
+            None => panic!("array slab capacity exceeded"),
+        }
+    }
+}
+
+impl<T, const N: usize> cc_traits::Remove<usize> for ArraySlab<T, N> {
+    #[inline]
+    fn remove(&mut self, key: usize) -> Option<T> {
+        let slot = self.slots.get_mut(key)?;
+        match core::mem::replace(slot, Slot::Vacant(self.next_vacant)) {
+            Slot::Occupied(value) => {
+                self.next_vacant = key;
+                self.len -= 1;
+                Some(value)
+            }
+            vacant => {
+                *slot = vacant;
+                None
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> cc_traits::Clear for ArraySlab<T, N> {
+    #[inline]
+    fn clear(&mut self) {
+        *self = Self::new()
+    }
+}
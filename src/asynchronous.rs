@@ -0,0 +1,687 @@
+use crate::{map::Binding, slab::DEFAULT_ORDER};
+use alloc::vec::Vec;
+use core::{borrow::Borrow, convert::Infallible, future::Future};
+
+/// Owned node snapshot.
+///
+/// Asynchronous storages cannot lend references to their nodes across
+/// `await` points, so nodes are fetched and written back by value.
+/// A node is a leaf if it has no children.
+/// Otherwise it has exactly one more child than items.
+pub struct AsyncNode<K, V> {
+    /// Items, sorted by key.
+    pub items: Vec<Binding<K, V>>,
+
+    /// Children identifiers.
+    pub children: Vec<usize>,
+}
+
+impl<K, V> AsyncNode<K, V> {
+    /// Creates a new leaf node.
+    #[inline]
+    pub fn leaf(items: Vec<Binding<K, V>>) -> Self {
+        Self {
+            items,
+            children: Vec::new(),
+        }
+    }
+
+    /// Checks if this node is a leaf.
+    #[inline]
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Searches for the given key in this node.
+    ///
+    /// Returns `Ok(offset)` if an item with the given key is found,
+    /// or `Err(offset)` with the offset of the child that may contain it.
+    #[inline]
+    fn search<Q: ?Sized + Ord>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+    {
+        self.items
+            .binary_search_by(|item| item.key.borrow().cmp(key))
+    }
+}
+
+impl<K: Clone, V: Clone> Clone for AsyncNode<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            items: self
+                .items
+                .iter()
+                .map(|item| Binding::new(item.key.clone(), item.value.clone()))
+                .collect(),
+            children: self.children.clone(),
+        }
+    }
+}
+
+/// Asynchronous storage.
+///
+/// Storage whose nodes are fetched asynchronously,
+/// for instance from an object storage or a network key-value store.
+pub trait AsyncStorage {
+    /// Key type.
+    type Key;
+
+    /// Value type.
+    type Value;
+
+    /// Error type.
+    type Error;
+
+    /// Returns the identifier of the root node, if any.
+    fn root(&self) -> Option<usize>;
+
+    /// Returns the number of items in the tree.
+    fn len(&self) -> usize;
+
+    /// Checks if the tree is empty.
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Fetches the node with the given identifier.
+    fn node(
+        &self,
+        id: usize,
+    ) -> impl Future<Output = Result<AsyncNode<Self::Key, Self::Value>, Self::Error>>;
+}
+
+/// Mutable asynchronous storage.
+pub trait AsyncStorageMut: AsyncStorage {
+    /// Sets the identifier of the root node.
+    fn set_root(&mut self, root: Option<usize>);
+
+    /// Sets the number of items in the tree.
+    fn set_len(&mut self, len: usize);
+
+    /// Stores a new node and returns its identifier.
+    fn allocate_node(
+        &mut self,
+        node: AsyncNode<Self::Key, Self::Value>,
+    ) -> impl Future<Output = Result<usize, Self::Error>>;
+
+    /// Replaces the node with the given identifier.
+    fn node_mut(
+        &mut self,
+        id: usize,
+        node: AsyncNode<Self::Key, Self::Value>,
+    ) -> impl Future<Output = Result<(), Self::Error>>;
+
+    /// Releases the node with the given identifier.
+    fn release_node(&mut self, id: usize) -> impl Future<Output = Result<(), Self::Error>>;
+}
+
+/// In-memory asynchronous storage.
+///
+/// Its futures are always immediately ready.
+/// It is mostly useful to test code written against [`AsyncStorageMut`].
+pub struct MemoryStorage<K, V> {
+    /// Nodes.
+    nodes: Vec<Option<AsyncNode<K, V>>>,
+
+    /// Released node identifiers.
+    vacant: Vec<usize>,
+
+    /// Root node.
+    root: Option<usize>,
+
+    /// Number of items.
+    len: usize,
+}
+
+impl<K, V> MemoryStorage<K, V> {
+    /// Creates a new empty storage.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of stored nodes.
+    #[inline]
+    pub fn node_count(&self) -> usize {
+        self.nodes.len() - self.vacant.len()
+    }
+}
+
+impl<K, V> Default for MemoryStorage<K, V> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            vacant: Vec::new(),
+            root: None,
+            len: 0,
+        }
+    }
+}
+
+impl<K: Clone, V: Clone> AsyncStorage for MemoryStorage<K, V> {
+    type Key = K;
+    type Value = V;
+    type Error = Infallible;
+
+    #[inline]
+    fn root(&self) -> Option<usize> {
+        self.root
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    async fn node(&self, id: usize) -> Result<AsyncNode<K, V>, Infallible> {
+        Ok(self.nodes[id].clone().expect("unknown node"))
+    }
+}
+
+impl<K: Clone, V: Clone> AsyncStorageMut for MemoryStorage<K, V> {
+    #[inline]
+    fn set_root(&mut self, root: Option<usize>) {
+        self.root = root
+    }
+
+    #[inline]
+    fn set_len(&mut self, len: usize) {
+        self.len = len
+    }
+
+    async fn allocate_node(&mut self, node: AsyncNode<K, V>) -> Result<usize, Infallible> {
+        match self.vacant.pop() {
+            Some(id) => {
+                self.nodes[id] = Some(node);
+                Ok(id)
+            }
+            None => {
+                self.nodes.push(Some(node));
+                Ok(self.nodes.len() - 1)
+            }
+        }
+    }
+
+    async fn node_mut(&mut self, id: usize, node: AsyncNode<K, V>) -> Result<(), Infallible> {
+        self.nodes[id] = Some(node);
+        Ok(())
+    }
+
+    async fn release_node(&mut self, id: usize) -> Result<(), Infallible> {
+        self.nodes[id] = None;
+        self.vacant.push(id);
+        Ok(())
+    }
+}
+
+/// Node on the path from the root to the node being processed.
+struct PathNode<K, V> {
+    /// Identifier.
+    id: usize,
+
+    /// Content.
+    node: AsyncNode<K, V>,
+
+    /// Offset of the child leading to the node being processed.
+    child: usize,
+
+    /// Whether the node has been modified.
+    dirty: bool,
+}
+
+/// Asynchronous map.
+///
+/// Drives the B-tree logic over an [`AsyncStorage`],
+/// fetching nodes only when they are needed.
+///
+/// # Example
+///
+/// ```
+/// use generic_btree::asynchronous::{AsyncMap, MemoryStorage};
+///
+/// async fn example() {
+///     let mut map = AsyncMap::new(MemoryStorage::new());
+///     map.insert(1, "one").await.unwrap();
+///     map.insert(2, "two").await.unwrap();
+///     assert_eq!(map.get(&1).await.unwrap(), Some("one"));
+///
+///     let mut iter = map.iter();
+///     while let Some(entry) = iter.next().await {
+///         let (key, value) = entry.unwrap();
+///         println!("{} => {}", key, value)
+///     }
+/// }
+/// ```
+pub struct AsyncMap<S> {
+    /// Storage.
+    storage: S,
+
+    /// Maximum number of children per node.
+    order: usize,
+}
+
+impl<S> AsyncMap<S> {
+    /// Creates a new map using the default order.
+    #[inline]
+    pub fn new(storage: S) -> Self {
+        Self::with_order(storage, DEFAULT_ORDER)
+    }
+
+    /// Creates a new map whose nodes hold at most `order - 1` items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is less than 3.
+    #[inline]
+    pub fn with_order(storage: S, order: usize) -> Self {
+        assert!(order >= 3, "order must be at least 3");
+        Self { storage, order }
+    }
+
+    /// Returns the order of the tree.
+    #[inline]
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Returns a reference to the storage.
+    #[inline]
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Consumes the map and returns the storage.
+    #[inline]
+    pub fn into_storage(self) -> S {
+        self.storage
+    }
+
+    /// Maximum number of items per node.
+    #[inline]
+    fn max_items(&self) -> usize {
+        self.order - 1
+    }
+
+    /// Minimum number of items per non-root node.
+    #[inline]
+    fn min_items(&self) -> usize {
+        (self.order - 1) / 2
+    }
+}
+
+impl<S: AsyncStorage> AsyncMap<S> {
+    /// Returns the number of items in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.storage.len()
+    }
+
+    /// Checks if the map is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+    }
+
+    /// Returns the value associated to the given key, if any.
+    pub async fn get<Q: ?Sized + Ord>(&self, key: &Q) -> Result<Option<S::Value>, S::Error>
+    where
+        S::Key: Borrow<Q>,
+    {
+        let mut id = match self.storage.root() {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+
+        loop {
+            let mut node = self.storage.node(id).await?;
+            match node.search(key) {
+                Ok(offset) => return Ok(Some(node.items.swap_remove(offset).value)),
+                Err(offset) => match node.children.get(offset) {
+                    Some(child) => id = *child,
+                    None => return Ok(None),
+                },
+            }
+        }
+    }
+
+    /// Checks if the map contains the given key.
+    #[inline]
+    pub async fn contains_key<Q: ?Sized + Ord>(&self, key: &Q) -> Result<bool, S::Error>
+    where
+        S::Key: Borrow<Q>,
+    {
+        Ok(self.get(key).await?.is_some())
+    }
+
+    /// Returns an iterator over the bindings of the map, in key order.
+    ///
+    /// Nodes are fetched as the iteration progresses.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, S> {
+        Iter {
+            storage: &self.storage,
+            stack: Vec::new(),
+            pending: self.storage.root(),
+        }
+    }
+}
+
+impl<S: AsyncStorageMut> AsyncMap<S>
+where
+    S::Key: Ord,
+{
+    /// Inserts a binding in the map.
+    ///
+    /// Returns the previous value associated to the key, if any.
+    pub async fn insert(
+        &mut self,
+        key: S::Key,
+        value: S::Value,
+    ) -> Result<Option<S::Value>, S::Error> {
+        let mut id = match self.storage.root() {
+            Some(id) => id,
+            None => {
+                let id = self
+                    .storage
+                    .allocate_node(AsyncNode::leaf(alloc::vec![Binding::new(key, value)]))
+                    .await?;
+                self.storage.set_root(Some(id));
+                self.storage.set_len(1);
+                return Ok(None);
+            }
+        };
+
+        let mut path = Vec::new();
+        let mut node = loop {
+            let mut node = self.storage.node(id).await?;
+            match node.search(&key) {
+                Ok(offset) => {
+                    let old_value = node.items[offset].replace_value(value);
+                    self.storage.node_mut(id, node).await?;
+                    return Ok(Some(old_value));
+                }
+                Err(offset) => {
+                    if node.is_leaf() {
+                        node.items.insert(offset, Binding::new(key, value));
+                        break node;
+                    }
+
+                    let child = node.children[offset];
+                    path.push(PathNode {
+                        id,
+                        node,
+                        child: offset,
+                        dirty: false,
+                    });
+                    id = child
+                }
+            }
+        };
+
+        self.storage.set_len(self.storage.len() + 1);
+
+        // Split overflowing nodes, from the leaf up to the root.
+        while node.items.len() > self.max_items() {
+            let median_offset = node.items.len() / 2;
+            let right_items = node.items.split_off(median_offset + 1);
+            let median = node.items.pop().unwrap();
+            let right_children = if node.is_leaf() {
+                Vec::new()
+            } else {
+                node.children.split_off(median_offset + 1)
+            };
+
+            let right_id = self
+                .storage
+                .allocate_node(AsyncNode {
+                    items: right_items,
+                    children: right_children,
+                })
+                .await?;
+            self.storage.node_mut(id, node).await?;
+
+            match path.pop() {
+                Some(parent) => {
+                    id = parent.id;
+                    node = parent.node;
+                    node.items.insert(parent.child, median);
+                    node.children.insert(parent.child + 1, right_id);
+                }
+                None => {
+                    let root = AsyncNode {
+                        items: alloc::vec![median],
+                        children: alloc::vec![id, right_id],
+                    };
+                    let root_id = self.storage.allocate_node(root).await?;
+                    self.storage.set_root(Some(root_id));
+                    return Ok(None);
+                }
+            }
+        }
+
+        self.storage.node_mut(id, node).await?;
+        Ok(None)
+    }
+
+    /// Removes the binding with the given key from the map.
+    ///
+    /// Returns the value associated to the key, if any.
+    pub async fn remove<Q: ?Sized + Ord>(&mut self, key: &Q) -> Result<Option<S::Value>, S::Error>
+    where
+        S::Key: Borrow<Q>,
+    {
+        let mut id = match self.storage.root() {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+
+        let mut path: Vec<PathNode<S::Key, S::Value>> = Vec::new();
+        let (mut node, removed) = loop {
+            let mut node = self.storage.node(id).await?;
+            match node.search(key) {
+                Ok(offset) if node.is_leaf() => {
+                    let removed = node.items.remove(offset);
+                    break (node, removed);
+                }
+                Ok(offset) => {
+                    // Replace the item with its predecessor,
+                    // the rightmost item of the left subtree.
+                    let found = path.len();
+                    let mut child_id = node.children[offset];
+                    path.push(PathNode {
+                        id,
+                        node,
+                        child: offset,
+                        dirty: true,
+                    });
+
+                    let mut leaf = loop {
+                        let child = self.storage.node(child_id).await?;
+                        if child.is_leaf() {
+                            break child;
+                        }
+
+                        let last = child.children.len() - 1;
+                        let next_id = child.children[last];
+                        path.push(PathNode {
+                            id: child_id,
+                            node: child,
+                            child: last,
+                            dirty: false,
+                        });
+                        child_id = next_id
+                    };
+
+                    id = child_id;
+                    let predecessor = leaf.items.pop().unwrap();
+                    let removed =
+                        core::mem::replace(&mut path[found].node.items[offset], predecessor);
+                    break (leaf, removed);
+                }
+                Err(offset) => {
+                    let child = match node.children.get(offset) {
+                        Some(child) => *child,
+                        None => return Ok(None),
+                    };
+
+                    path.push(PathNode {
+                        id,
+                        node,
+                        child: offset,
+                        dirty: false,
+                    });
+                    id = child
+                }
+            }
+        };
+
+        self.storage.set_len(self.storage.len() - 1);
+
+        // Rebalance underflowing nodes, from the leaf up to the root.
+        let mut dirty = true;
+        while let Some(mut parent) = path.pop() {
+            if node.items.len() < self.min_items() {
+                let offset = parent.child;
+                parent.dirty = true;
+
+                let left = match offset.checked_sub(1) {
+                    Some(left_offset) => {
+                        let left_id = parent.node.children[left_offset];
+                        Some((left_id, self.storage.node(left_id).await?))
+                    }
+                    None => None,
+                };
+
+                match left {
+                    Some((left_id, mut left)) if left.items.len() > self.min_items() => {
+                        // Rotate right.
+                        let item = left.items.pop().unwrap();
+                        let separator =
+                            core::mem::replace(&mut parent.node.items[offset - 1], item);
+                        node.items.insert(0, separator);
+                        if let Some(child) = left.children.pop() {
+                            node.children.insert(0, child)
+                        }
+
+                        self.storage.node_mut(left_id, left).await?;
+                        self.storage.node_mut(id, node).await?
+                    }
+                    Some((left_id, mut left)) if offset + 1 == parent.node.children.len() => {
+                        // Merge into the left sibling.
+                        left.items.push(parent.node.items.remove(offset - 1));
+                        left.items.append(&mut node.items);
+                        left.children.append(&mut node.children);
+                        parent.node.children.remove(offset);
+
+                        self.storage.node_mut(left_id, left).await?;
+                        self.storage.release_node(id).await?
+                    }
+                    _ => {
+                        let right_id = parent.node.children[offset + 1];
+                        let mut right = self.storage.node(right_id).await?;
+                        if right.items.len() > self.min_items() {
+                            // Rotate left.
+                            let item = right.items.remove(0);
+                            let separator =
+                                core::mem::replace(&mut parent.node.items[offset], item);
+                            node.items.push(separator);
+                            if !right.children.is_empty() {
+                                node.children.push(right.children.remove(0))
+                            }
+
+                            self.storage.node_mut(right_id, right).await?
+                        } else {
+                            // Merge the right sibling.
+                            node.items.push(parent.node.items.remove(offset));
+                            node.items.append(&mut right.items);
+                            node.children.append(&mut right.children);
+                            parent.node.children.remove(offset + 1);
+
+                            self.storage.release_node(right_id).await?
+                        }
+
+                        self.storage.node_mut(id, node).await?
+                    }
+                }
+            } else if dirty {
+                self.storage.node_mut(id, node).await?
+            }
+
+            id = parent.id;
+            node = parent.node;
+            dirty = parent.dirty;
+        }
+
+        // `node` is now the root.
+        if node.items.is_empty() {
+            self.storage.release_node(id).await?;
+            self.storage.set_root(node.children.first().copied())
+        } else if dirty {
+            self.storage.node_mut(id, node).await?
+        }
+
+        Ok(Some(removed.value))
+    }
+}
+
+/// Iterator frame.
+struct Frame<K, V> {
+    /// Remaining items of the node.
+    items: alloc::vec::IntoIter<Binding<K, V>>,
+
+    /// Remaining children of the node.
+    children: alloc::vec::IntoIter<usize>,
+}
+
+/// Asynchronous iterator over the bindings of an [`AsyncMap`].
+///
+/// This is a stream: bindings are produced by the [`next`](Iter::next) method,
+/// fetching nodes as needed.
+pub struct Iter<'a, S: AsyncStorage> {
+    /// Storage.
+    storage: &'a S,
+
+    /// Nodes being visited.
+    stack: Vec<Frame<S::Key, S::Value>>,
+
+    /// Subtree to visit before resuming with the top of the stack.
+    pending: Option<usize>,
+}
+
+impl<'a, S: AsyncStorage> Iter<'a, S> {
+    /// Returns the next binding, or `None` if the iteration is over.
+    ///
+    /// If fetching a node fails, the error is returned and the
+    /// iteration can be resumed by calling this method again.
+    pub async fn next(&mut self) -> Option<Result<(S::Key, S::Value), S::Error>> {
+        while let Some(id) = self.pending {
+            let node = match self.storage.node(id).await {
+                Ok(node) => node,
+                Err(e) => return Some(Err(e)),
+            };
+
+            let mut children = node.children.into_iter();
+            self.pending = children.next();
+            self.stack.push(Frame {
+                items: node.items.into_iter(),
+                children,
+            })
+        }
+
+        loop {
+            let frame = self.stack.last_mut()?;
+            match frame.items.next() {
+                Some(item) => {
+                    self.pending = frame.children.next();
+                    return Some(Ok(item.into_pair()));
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,985 @@
+use crate::{node::Address, slab::DEFAULT_ORDER, ValidationError};
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::{
+    borrow::Borrow,
+    iter::{FromIterator, FusedIterator},
+    ops::{Bound, RangeBounds},
+};
+
+/// Node.
+enum Node<K, V> {
+    /// Internal node, holding separator keys only.
+    ///
+    /// Every key of the subtree `children[i]` is less than `keys[i]`,
+    /// and every key of the subtree `children[i + 1]` is greater than or
+    /// equal to `keys[i]`.
+    Internal { keys: Vec<K>, children: Vec<usize> },
+
+    /// Leaf node, holding the bindings.
+    Leaf {
+        keys: Vec<K>,
+        values: Vec<V>,
+
+        /// Previous leaf, in key order.
+        prev: Option<usize>,
+
+        /// Next leaf, in key order.
+        next: Option<usize>,
+    },
+}
+
+impl<K, V> Node<K, V> {
+    #[inline]
+    fn keys(&self) -> &Vec<K> {
+        match self {
+            Self::Internal { keys, .. } => keys,
+            Self::Leaf { keys, .. } => keys,
+        }
+    }
+}
+
+/// B+-tree map.
+///
+/// Alternative node organization where internal nodes only store
+/// separator keys, and every binding lives in a leaf.
+/// Leaves are linked to their neighbors, so that iterating over a range
+/// of bindings only walks through the leaves after locating the first one.
+/// This makes range scans faster than with [`Map`](crate::Map),
+/// at the cost of duplicating some keys as separators.
+///
+/// Separators are chosen by a [`SeparatorPolicy`] when a leaf is split.
+/// The default [`FullKey`] policy copies the first key of the right leaf,
+/// while [`SuffixTruncation`] stores the shortest byte string separating
+/// the two leaves, which keeps internal nodes small with long keys.
+///
+/// # Example
+///
+/// ```
+/// use generic_btree::bplus::BPlusMap;
+///
+/// let mut map = BPlusMap::new();
+/// for i in 0..100 {
+///     map.insert(i, i * 2);
+/// }
+///
+/// assert_eq!(map.get(&42), Some(&84));
+/// assert!(map.range(10..20).map(|(k, _)| *k).eq(10..20));
+/// ```
+pub struct BPlusMap<K, V, P = FullKey> {
+    /// Nodes.
+    nodes: Vec<Option<Node<K, V>>>,
+
+    /// Released node identifiers.
+    vacant: Vec<usize>,
+
+    /// Root node.
+    root: Option<usize>,
+
+    /// Number of bindings.
+    len: usize,
+
+    /// Maximum number of children of internal nodes,
+    /// and one more than the maximum number of bindings of leaves.
+    order: usize,
+
+    /// Separator policy.
+    policy: P,
+}
+
+impl<K, V> BPlusMap<K, V> {
+    /// Creates a new empty map using the default order.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_order(DEFAULT_ORDER)
+    }
+
+    /// Creates a new empty map whose nodes hold at most `order - 1` keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is less than 4.
+    #[inline]
+    pub fn with_order(order: usize) -> Self {
+        Self::with_policy(order, FullKey)
+    }
+}
+
+impl<K, V, P> BPlusMap<K, V, P> {
+    /// Creates a new empty map with the given order and separator policy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is less than 4.
+    #[inline]
+    pub fn with_policy(order: usize, policy: P) -> Self {
+        if order < 4 {
+            panic!("B-Tree order must be at least 4")
+        }
+
+        Self {
+            nodes: Vec::new(),
+            vacant: Vec::new(),
+            root: None,
+            len: 0,
+            order,
+            policy,
+        }
+    }
+
+    /// Returns the separator policy of the tree.
+    #[inline]
+    pub fn policy(&self) -> &P {
+        &self.policy
+    }
+
+    /// Returns the order of the tree.
+    #[inline]
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Returns the number of bindings in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if the map is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes every binding of the map.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.vacant.clear();
+        self.root = None;
+        self.len = 0
+    }
+
+    /// Returns an iterator over the bindings of the map, in key order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V, P> {
+        Iter {
+            map: self,
+            front: self.first_leaf().map(|id| (id, 0)),
+            back: self.last_leaf().and_then(|id| self.last_position(id)),
+        }
+    }
+
+    /// Returns an iterator over the keys of the map, in order.
+    #[inline]
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over the values of the map, in key order.
+    #[inline]
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Returns the first binding of the map, if any.
+    #[inline]
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.iter().next()
+    }
+
+    /// Returns the last binding of the map, if any.
+    #[inline]
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.iter().next_back()
+    }
+
+    #[inline]
+    fn node(&self, id: usize) -> &Node<K, V> {
+        self.nodes[id].as_ref().expect("missing node")
+    }
+
+    #[inline]
+    fn node_mut(&mut self, id: usize) -> &mut Node<K, V> {
+        self.nodes[id].as_mut().expect("missing node")
+    }
+
+    fn allocate(&mut self, node: Node<K, V>) -> usize {
+        match self.vacant.pop() {
+            Some(id) => {
+                self.nodes[id] = Some(node);
+                id
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn release(&mut self, id: usize) -> Node<K, V> {
+        self.vacant.push(id);
+        self.nodes[id].take().expect("missing node")
+    }
+
+    /// Maximum number of keys per node.
+    #[inline]
+    fn max_keys(&self) -> usize {
+        self.order - 1
+    }
+
+    /// Minimum number of keys per non-root node.
+    #[inline]
+    fn min_keys(&self) -> usize {
+        (self.order - 1) / 2
+    }
+
+    /// Returns the leftmost leaf.
+    fn first_leaf(&self) -> Option<usize> {
+        let mut id = self.root?;
+        while let Node::Internal { children, .. } = self.node(id) {
+            id = children[0]
+        }
+
+        Some(id)
+    }
+
+    /// Returns the rightmost leaf.
+    fn last_leaf(&self) -> Option<usize> {
+        let mut id = self.root?;
+        while let Node::Internal { children, .. } = self.node(id) {
+            id = *children.last().unwrap()
+        }
+
+        Some(id)
+    }
+
+    /// Returns the position of the last binding of the given leaf, if any.
+    #[inline]
+    fn last_position(&self, id: usize) -> Option<(usize, usize)> {
+        self.node(id).keys().len().checked_sub(1).map(|i| (id, i))
+    }
+
+    /// Returns the position following the given position.
+    fn next_position(&self, (id, i): (usize, usize)) -> Option<(usize, usize)> {
+        match self.node(id) {
+            Node::Leaf { keys, next, .. } => {
+                if i + 1 < keys.len() {
+                    Some((id, i + 1))
+                } else {
+                    next.map(|next| (next, 0))
+                }
+            }
+            Node::Internal { .. } => unreachable!(),
+        }
+    }
+
+    /// Returns the position preceding the given position.
+    fn previous_position(&self, (id, i): (usize, usize)) -> Option<(usize, usize)> {
+        match self.node(id) {
+            Node::Leaf { prev, .. } => {
+                if i > 0 {
+                    Some((id, i - 1))
+                } else {
+                    prev.and_then(|prev| self.last_position(prev))
+                }
+            }
+            Node::Internal { .. } => unreachable!(),
+        }
+    }
+
+    #[inline]
+    fn binding(&self, (id, i): (usize, usize)) -> (&K, &V) {
+        match self.node(id) {
+            Node::Leaf { keys, values, .. } => (&keys[i], &values[i]),
+            Node::Internal { .. } => unreachable!(),
+        }
+    }
+}
+
+impl<K: Ord, V, P> BPlusMap<K, V, P> {
+    /// Returns the leaf that may contain the given key,
+    /// along with the path leading to it.
+    ///
+    /// Each element of the path is an internal node identifier
+    /// and the offset of the child taken.
+    fn find_leaf<Q: ?Sized + Ord>(&self, key: &Q, path: &mut Vec<(usize, usize)>) -> Option<usize>
+    where
+        K: Borrow<Q>,
+    {
+        let mut id = self.root?;
+        while let Node::Internal { keys, children } = self.node(id) {
+            let offset = keys.partition_point(|k| k.borrow() <= key);
+            path.push((id, offset));
+            id = children[offset]
+        }
+
+        Some(id)
+    }
+
+    /// Returns the position of the first binding whose key is greater than
+    /// (or equal to, if `inclusive` is `true`) the given key.
+    fn lower_bound<Q: ?Sized + Ord>(&self, key: &Q, inclusive: bool) -> Option<(usize, usize)>
+    where
+        K: Borrow<Q>,
+    {
+        let id = self.find_leaf(key, &mut Vec::new())?;
+        let keys = self.node(id).keys();
+        let i = if inclusive {
+            keys.partition_point(|k| k.borrow() < key)
+        } else {
+            keys.partition_point(|k| k.borrow() <= key)
+        };
+
+        if i < keys.len() {
+            Some((id, i))
+        } else {
+            match self.node(id) {
+                Node::Leaf { next, .. } => next.map(|next| (next, 0)),
+                Node::Internal { .. } => unreachable!(),
+            }
+        }
+    }
+
+    /// Returns the position of the last binding whose key is less than
+    /// (or equal to, if `inclusive` is `true`) the given key.
+    fn upper_bound<Q: ?Sized + Ord>(&self, key: &Q, inclusive: bool) -> Option<(usize, usize)>
+    where
+        K: Borrow<Q>,
+    {
+        let id = self.find_leaf(key, &mut Vec::new())?;
+        let keys = self.node(id).keys();
+        let i = if inclusive {
+            keys.partition_point(|k| k.borrow() <= key)
+        } else {
+            keys.partition_point(|k| k.borrow() < key)
+        };
+
+        match i.checked_sub(1) {
+            Some(i) => Some((id, i)),
+            None => match self.node(id) {
+                Node::Leaf { prev, .. } => prev.and_then(|prev| self.last_position(prev)),
+                Node::Internal { .. } => unreachable!(),
+            },
+        }
+    }
+
+    /// Returns the value associated to the given key, if any.
+    pub fn get<Q: ?Sized + Ord>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        let id = self.find_leaf(key, &mut Vec::new())?;
+        match self.node(id) {
+            Node::Leaf { keys, values, .. } => keys
+                .binary_search_by(|k| k.borrow().cmp(key))
+                .ok()
+                .map(|i| &values[i]),
+            Node::Internal { .. } => unreachable!(),
+        }
+    }
+
+    /// Returns a mutable reference to the value associated to the given key, if any.
+    pub fn get_mut<Q: ?Sized + Ord>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        let id = self.find_leaf(key, &mut Vec::new())?;
+        match self.node_mut(id) {
+            Node::Leaf { keys, values, .. } => keys
+                .binary_search_by(|k| k.borrow().cmp(key))
+                .ok()
+                .map(move |i| &mut values[i]),
+            Node::Internal { .. } => unreachable!(),
+        }
+    }
+
+    /// Checks if the map contains the given key.
+    #[inline]
+    pub fn contains_key<Q: ?Sized + Ord>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Returns an iterator over the bindings whose key is in the given range.
+    ///
+    /// Only the first and last bindings of the range are searched from the root:
+    /// the iteration itself follows the leaf links.
+    pub fn range<Q: ?Sized + Ord, R: RangeBounds<Q>>(&self, range: R) -> Iter<'_, K, V, P>
+    where
+        K: Borrow<Q>,
+    {
+        let front = match range.start_bound() {
+            Bound::Included(key) => self.lower_bound(key, true),
+            Bound::Excluded(key) => self.lower_bound(key, false),
+            Bound::Unbounded => self.first_leaf().map(|id| (id, 0)),
+        };
+
+        let back = match range.end_bound() {
+            Bound::Included(key) => self.upper_bound(key, true),
+            Bound::Excluded(key) => self.upper_bound(key, false),
+            Bound::Unbounded => self.last_leaf().and_then(|id| self.last_position(id)),
+        };
+
+        match (front, back) {
+            (Some(f), Some(b)) if self.binding(f).0 <= self.binding(b).0 => Iter {
+                map: self,
+                front,
+                back,
+            },
+            _ => Iter {
+                map: self,
+                front: None,
+                back: None,
+            },
+        }
+    }
+
+    /// Checks the invariants of the tree.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let root = match self.root {
+            Some(root) => root,
+            None => return Ok(()),
+        };
+
+        let mut leaves = Vec::new();
+        let mut len = 0;
+        let mut depth = None;
+        let mut stack = alloc::vec![(root, 0, None::<&K>, None::<&K>)];
+        while let Some((id, level, min, max)) = stack.pop() {
+            let node = self.nodes.get(id).and_then(Option::as_ref);
+            let node = node.ok_or(ValidationError::MissingNode(id))?;
+            let keys = node.keys();
+            if keys.len() > self.max_keys() {
+                return Err(ValidationError::Overflow(id));
+            }
+
+            if id != root && keys.len() < self.min_keys() {
+                return Err(ValidationError::Underflow(id));
+            }
+
+            if let Some(i) = keys.windows(2).position(|w| w[0] >= w[1]) {
+                return Err(ValidationError::UnsortedNode(Address::new(
+                    id,
+                    (i + 1).into(),
+                )));
+            }
+
+            if let (Some(min), Some(first)) = (min, keys.first()) {
+                if first < min {
+                    return Err(ValidationError::UnsortedFromLeft(Address::new(
+                        id,
+                        0.into(),
+                    )));
+                }
+            }
+
+            if let (Some(max), Some(last)) = (max, keys.last()) {
+                if last >= max {
+                    let offset = keys.len() - 1;
+                    return Err(ValidationError::UnsortedFromRight(Address::new(
+                        id,
+                        offset.into(),
+                    )));
+                }
+            }
+
+            match node {
+                Node::Internal { keys, children } => {
+                    if children.len() != keys.len() + 1 {
+                        return Err(ValidationError::UnsortedNode(Address::new(
+                            id,
+                            keys.len().into(),
+                        )));
+                    }
+
+                    // Push the children in reverse order to visit the leaves in order.
+                    for (i, child) in children.iter().enumerate().rev() {
+                        let child_min = if i == 0 { min } else { Some(&keys[i - 1]) };
+                        let child_max = if i == keys.len() { max } else { Some(&keys[i]) };
+                        stack.push((*child, level + 1, child_min, child_max))
+                    }
+                }
+                Node::Leaf { keys, values, .. } => {
+                    if keys.len() != values.len() {
+                        return Err(ValidationError::UnsortedNode(Address::new(
+                            id,
+                            keys.len().min(values.len()).into(),
+                        )));
+                    }
+
+                    match depth {
+                        Some(depth) if depth != level => return Err(ValidationError::NotBalanced),
+                        _ => depth = Some(level),
+                    }
+
+                    len += keys.len();
+                    leaves.push(id)
+                }
+            }
+        }
+
+        if len != self.len {
+            return Err(ValidationError::MissingNode(root));
+        }
+
+        // Check the leaf links.
+        for (i, id) in leaves.iter().enumerate() {
+            if let Node::Leaf { prev, next, .. } = self.node(*id) {
+                let expected_prev = i.checked_sub(1).map(|i| leaves[i]);
+                let expected_next = leaves.get(i + 1).copied();
+                if *prev != expected_prev || *next != expected_next {
+                    return Err(ValidationError::BrokenLink(*id));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<K: Ord, V, P: SeparatorPolicy<K>> BPlusMap<K, V, P> {
+    /// Inserts a binding in the map.
+    ///
+    /// Returns the previous value associated to the key, if any.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let mut path = Vec::new();
+        let mut id = match self.find_leaf(&key, &mut path) {
+            Some(id) => id,
+            None => {
+                let id = self.allocate(Node::Leaf {
+                    keys: alloc::vec![key],
+                    values: alloc::vec![value],
+                    prev: None,
+                    next: None,
+                });
+                self.root = Some(id);
+                self.len = 1;
+                return None;
+            }
+        };
+
+        match self.node_mut(id) {
+            Node::Leaf { keys, values, .. } => match keys.binary_search(&key) {
+                Ok(i) => return Some(core::mem::replace(&mut values[i], value)),
+                Err(i) => {
+                    keys.insert(i, key);
+                    values.insert(i, value)
+                }
+            },
+            Node::Internal { .. } => unreachable!(),
+        }
+
+        self.len += 1;
+
+        // Split overflowing nodes, from the leaf up to the root.
+        while self.node(id).keys().len() > self.max_keys() {
+            let (separator, right) = match self.node_mut(id) {
+                Node::Leaf {
+                    keys, values, next, ..
+                } => {
+                    let mid = keys.len() / 2;
+                    let right_keys = keys.split_off(mid);
+                    let right_values = values.split_off(mid);
+                    let right = Node::Leaf {
+                        keys: right_keys,
+                        values: right_values,
+                        prev: Some(id),
+                        next: *next,
+                    };
+                    (None, right)
+                }
+                Node::Internal { keys, children } => {
+                    let mid = keys.len() / 2;
+                    let right_keys = keys.split_off(mid + 1);
+                    let separator = keys.pop().unwrap();
+                    let right_children = children.split_off(mid + 1);
+                    let right = Node::Internal {
+                        keys: right_keys,
+                        children: right_children,
+                    };
+                    (Some(separator), right)
+                }
+            };
+
+            let separator = separator.unwrap_or_else(|| {
+                let left = self.node(id).keys().last().unwrap();
+                self.policy.separator(left, &right.keys()[0])
+            });
+
+            let right_id = self.allocate(right);
+            if let Node::Leaf { next, .. } = self.node_mut(id) {
+                let old_next = next.replace(right_id);
+                if let Some(old_next) = old_next {
+                    if let Node::Leaf { prev, .. } = self.node_mut(old_next) {
+                        *prev = Some(right_id)
+                    }
+                }
+            }
+
+            match path.pop() {
+                Some((parent_id, offset)) => {
+                    if let Node::Internal { keys, children } = self.node_mut(parent_id) {
+                        keys.insert(offset, separator);
+                        children.insert(offset + 1, right_id);
+                    }
+
+                    id = parent_id
+                }
+                None => {
+                    let root = self.allocate(Node::Internal {
+                        keys: alloc::vec![separator],
+                        children: alloc::vec![id, right_id],
+                    });
+                    self.root = Some(root);
+                    break;
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Removes the binding with the given key from the map.
+    ///
+    /// Returns the associated value, if any.
+    pub fn remove<Q: ?Sized + Ord>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        let mut path = Vec::new();
+        let mut id = self.find_leaf(key, &mut path)?;
+        let value = match self.node_mut(id) {
+            Node::Leaf { keys, values, .. } => {
+                let i = keys.binary_search_by(|k| k.borrow().cmp(key)).ok()?;
+                keys.remove(i);
+                values.remove(i)
+            }
+            Node::Internal { .. } => unreachable!(),
+        };
+
+        self.len -= 1;
+
+        // Rebalance underflowing nodes, from the leaf up to the root.
+        while let Some((parent_id, offset)) = path.pop() {
+            if self.node(id).keys().len() >= self.min_keys() {
+                return Some(value);
+            }
+
+            self.rebalance(parent_id, offset);
+            id = parent_id
+        }
+
+        // `id` is the root.
+        let root = self.node(id);
+        if root.keys().is_empty() {
+            match root {
+                Node::Internal { children, .. } => {
+                    self.root = Some(children[0]);
+                    self.release(id);
+                }
+                Node::Leaf { .. } => {
+                    self.root = None;
+                    self.release(id);
+                }
+            }
+        }
+
+        Some(value)
+    }
+
+    /// Fixes the underflowing child at the given offset of the given internal node,
+    /// by borrowing a key from a sibling, or merging it with a sibling.
+    fn rebalance(&mut self, parent_id: usize, offset: usize) {
+        let min = self.min_keys();
+        let mut parent = self.nodes[parent_id].take().expect("missing node");
+        if let Node::Internal {
+            keys: separators,
+            children,
+        } = &mut parent
+        {
+            let id = children[offset];
+            let left_id = offset.checked_sub(1).map(|i| children[i]);
+            let right_id = children.get(offset + 1).copied();
+
+            if let Some(left_id) = left_id.filter(|l| self.node(*l).keys().len() > min) {
+                // Borrow from the left sibling.
+                let mut left = self.nodes[left_id].take().unwrap();
+                let node = self.node_mut(id);
+                match (&mut left, node) {
+                    (
+                        Node::Leaf {
+                            keys: lk,
+                            values: lv,
+                            ..
+                        },
+                        Node::Leaf { keys, values, .. },
+                    ) => {
+                        keys.insert(0, lk.pop().unwrap());
+                        values.insert(0, lv.pop().unwrap());
+                    }
+                    (
+                        Node::Internal {
+                            keys: lk,
+                            children: lc,
+                        },
+                        Node::Internal { keys, children: c },
+                    ) => {
+                        let separator =
+                            core::mem::replace(&mut separators[offset - 1], lk.pop().unwrap());
+                        keys.insert(0, separator);
+                        c.insert(0, lc.pop().unwrap());
+                    }
+                    _ => unreachable!(),
+                }
+
+                if let Node::Leaf { keys, .. } = &left {
+                    let right = &self.node(id).keys()[0];
+                    separators[offset - 1] = self.policy.separator(keys.last().unwrap(), right)
+                }
+
+                self.nodes[left_id] = Some(left);
+            } else if let Some(right_id) = right_id.filter(|r| self.node(*r).keys().len() > min) {
+                // Borrow from the right sibling.
+                let mut right = self.nodes[right_id].take().unwrap();
+                let node = self.node_mut(id);
+                match (&mut right, node) {
+                    (
+                        Node::Leaf {
+                            keys: rk,
+                            values: rv,
+                            ..
+                        },
+                        Node::Leaf { keys, values, .. },
+                    ) => {
+                        keys.push(rk.remove(0));
+                        values.push(rv.remove(0));
+                    }
+                    (
+                        Node::Internal {
+                            keys: rk,
+                            children: rc,
+                        },
+                        Node::Internal { keys, children: c },
+                    ) => {
+                        let separator = core::mem::replace(&mut separators[offset], rk.remove(0));
+                        keys.push(separator);
+                        c.push(rc.remove(0));
+                    }
+                    _ => unreachable!(),
+                }
+
+                if let Node::Leaf { keys, .. } = &right {
+                    let left = self.node(id).keys().last().unwrap();
+                    separators[offset] = self.policy.separator(left, &keys[0])
+                }
+
+                self.nodes[right_id] = Some(right);
+            } else {
+                // Merge with a sibling.
+                let (left_offset, left_id, right_id) = match left_id {
+                    Some(left_id) => (offset - 1, left_id, id),
+                    None => (offset, id, right_id.unwrap()),
+                };
+
+                let separator = separators.remove(left_offset);
+                children.remove(left_offset + 1);
+                let right = self.release(right_id);
+                let mut next_leaf = None;
+                match (self.node_mut(left_id), right) {
+                    (
+                        Node::Leaf {
+                            keys, values, next, ..
+                        },
+                        Node::Leaf {
+                            keys: mut rk,
+                            values: mut rv,
+                            next: rn,
+                            ..
+                        },
+                    ) => {
+                        keys.append(&mut rk);
+                        values.append(&mut rv);
+                        *next = rn;
+                        next_leaf = rn
+                    }
+                    (
+                        Node::Internal { keys, children },
+                        Node::Internal {
+                            keys: mut rk,
+                            children: mut rc,
+                        },
+                    ) => {
+                        keys.push(separator);
+                        keys.append(&mut rk);
+                        children.append(&mut rc);
+                    }
+                    _ => unreachable!(),
+                }
+
+                if let Some(next_id) = next_leaf {
+                    if let Node::Leaf { prev, .. } = self.node_mut(next_id) {
+                        *prev = Some(left_id)
+                    }
+                }
+            }
+        }
+
+        self.nodes[parent_id] = Some(parent)
+    }
+}
+
+impl<K, V> Default for BPlusMap<K, V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V, P: SeparatorPolicy<K> + Default> FromIterator<(K, V)> for BPlusMap<K, V, P> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::with_policy(DEFAULT_ORDER, P::default());
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Ord, V, P: SeparatorPolicy<K>> Extend<(K, V)> for BPlusMap<K, V, P> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<'a, K, V, P> IntoIterator for &'a BPlusMap<K, V, P> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V, P>;
+
+    #[inline]
+    fn into_iter(self) -> Iter<'a, K, V, P> {
+        self.iter()
+    }
+}
+
+/// Iterator over the bindings of a [`BPlusMap`], in key order.
+pub struct Iter<'a, K, V, P = FullKey> {
+    /// Map.
+    map: &'a BPlusMap<K, V, P>,
+
+    /// Position of the next binding from the front.
+    front: Option<(usize, usize)>,
+
+    /// Position of the next binding from the back.
+    back: Option<(usize, usize)>,
+}
+
+impl<'a, K, V, P> Iterator for Iter<'a, K, V, P> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let position = self.front?;
+        if self.front == self.back {
+            self.front = None;
+            self.back = None
+        } else {
+            self.front = self.map.next_position(position)
+        }
+
+        Some(self.map.binding(position))
+    }
+}
+
+impl<'a, K, V, P> DoubleEndedIterator for Iter<'a, K, V, P> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let position = self.back?;
+        if self.front == self.back {
+            self.front = None;
+            self.back = None
+        } else {
+            self.back = self.map.previous_position(position)
+        }
+
+        Some(self.map.binding(position))
+    }
+}
+
+impl<'a, K, V, P> FusedIterator for Iter<'a, K, V, P> {}
+
+impl<K: PartialEq, V: PartialEq, P> PartialEq for BPlusMap<K, V, P> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+/// Separator policy.
+///
+/// Decides which key is stored in the parent node when a leaf is split,
+/// or when a binding moves between two sibling leaves.
+pub trait SeparatorPolicy<K> {
+    /// Returns a separator between the last key `left` of a leaf
+    /// and the first key `right` of the next leaf.
+    ///
+    /// The returned key `k` must satisfy `left < k <= right`.
+    fn separator(&self, left: &K, right: &K) -> K;
+}
+
+/// Default separator policy, copying the first key of the right leaf.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct FullKey;
+
+impl<K: Clone> SeparatorPolicy<K> for FullKey {
+    #[inline]
+    fn separator(&self, _left: &K, right: &K) -> K {
+        right.clone()
+    }
+}
+
+/// Suffix truncation separator policy, for byte-comparable keys.
+///
+/// Stores the shortest prefix of the right key
+/// that is still greater than the left key.
+/// With long keys sharing few leading bytes
+/// (such as paths or URLs), separators are only a few bytes long.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct SuffixTruncation;
+
+/// Returns the length of the shortest prefix of `right` greater than `left`,
+/// assuming `left < right`.
+#[inline]
+fn separator_len(left: &[u8], right: &[u8]) -> usize {
+    let common = left.iter().zip(right).take_while(|(a, b)| a == b).count();
+    (common + 1).min(right.len())
+}
+
+impl SeparatorPolicy<Vec<u8>> for SuffixTruncation {
+    #[inline]
+    fn separator(&self, left: &Vec<u8>, right: &Vec<u8>) -> Vec<u8> {
+        right[..separator_len(left, right)].to_vec()
+    }
+}
+
+impl SeparatorPolicy<Box<[u8]>> for SuffixTruncation {
+    #[inline]
+    fn separator(&self, left: &Box<[u8]>, right: &Box<[u8]>) -> Box<[u8]> {
+        right[..separator_len(left, right)].into()
+    }
+}
+
+impl SeparatorPolicy<String> for SuffixTruncation {
+    #[inline]
+    fn separator(&self, left: &String, right: &String) -> String {
+        let mut len = separator_len(left.as_bytes(), right.as_bytes());
+        while !right.is_char_boundary(len) {
+            len += 1
+        }
+
+        right[..len].into()
+    }
+}
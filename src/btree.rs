@@ -1,18 +1,66 @@
-use std::{
+use alloc::vec::Vec;
+use core::{
     cmp::Ordering,
+    fmt,
     hash::{Hash, Hasher},
-    ops::RangeBounds,
+    ops::{Bound, ControlFlow, RangeBounds},
+    ptr::NonNull,
 };
+use smallvec::SmallVec;
 
+/// Subtree augmentation.
+///
+/// An augmentation is a value maintained for each node of the tree,
+/// summarizing the content of the whole subtree rooted at this node
+/// (number of items, sum of the values, greatest key, hash, etc.).
+/// It is computed from the items of the node and the augmentations of its
+/// children, in order, by folding them into an initially empty
+/// ([`Default`]) augmentation.
+///
+/// Storages maintaining augmentations implement the [`Augment`] trait,
+/// and override the [`StorageMut::recompute`](crate::StorageMut::recompute) hook,
+/// called by the tree algorithms every time a node is modified.
+///
+/// # Correctness
+///
+/// The tree algorithms only know when the structure of the tree changes.
+/// Modifying an item in place (for instance through
+/// [`StorageMut::item_mut`](crate::StorageMut::item_mut)) in a way that changes
+/// its contribution to the augmentation requires calling
+/// [`StorageMut::recompute_path`](crate::StorageMut::recompute_path) on the node containing the item.
+pub mod augment;
+mod finger;
+mod hole;
 mod iter;
+mod links;
 pub mod node;
-
+mod observer;
+pub mod policy;
+mod set;
+mod stats;
+#[cfg(any(debug_assertions, feature = "validation"))]
+mod validation;
+mod visit;
+
+pub use augment::{Augment, Augmentation, SubtreeLen};
+pub use finger::Finger;
+use hole::Hole;
 pub(crate) use iter::DrainFilterInner;
-pub use iter::{DrainFilter, IntoIter, Iter, IterMut, Range, RangeMut};
+pub use iter::{
+    Addresses, Chunk, Chunks, Drain, DrainFilter, IntoIter, Iter, IterMut, Leaves, Range, RangeMut,
+};
+pub use links::LeafLinks;
 use node::{
     item::{Mut as ItemMut, Read, Replace, Write},
-    Address, Balance, Offset, WouldUnderflow,
+    Address, Balance, Position, StampedAddress, WouldUnderflow,
 };
+pub use observer::Observer;
+pub use policy::{CapacityPolicy, InvalidCapacityPolicy};
+pub use set::{Difference, Intersection, Merge, Merged, SymmetricDifference, Union};
+pub use stats::{LevelStats, Stats};
+#[cfg(any(debug_assertions, feature = "validation"))]
+pub use validation::{ValidationIssue, ValidationReport};
+pub use visit::{NodeInfo, Traversal};
 
 /// Updated entry.
 ///
@@ -25,8 +73,21 @@ pub enum UpdateEntry<Q, I> {
     Occupied(I),
 }
 
+/// Detached subtree, given by the id and height of its root.
+///
+/// Used by the [StorageMut::split_node] and [StorageMut::join] functions.
+pub type Subtree = (usize, usize);
+
+/// Capacity error.
+///
+/// Returned when an item cannot be inserted because the storage
+/// may not be able to allocate the nodes required by the insertion.
+/// Holds the item that was not inserted.
+#[derive(PartialEq, Eq, Debug)]
+pub struct CapacityError<T>(pub T);
+
 /// B-Tree validation error.
-#[derive(Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum ValidationError {
     /// Some node is missing.
     MissingNode(usize),
@@ -47,14 +108,149 @@ pub enum ValidationError {
     /// The given node is underflowing.
     Underflow(usize),
 
-    /// The items inside the given node are not sorted.
-    UnsortedNode(usize),
+    /// The items inside a node are not sorted.
+    ///
+    /// The parameter is the address of the first item
+    /// that is smaller than its predecessor.
+    UnsortedNode(Address),
+
+    /// The smallest item key of a node is smaller than the left separator of the node.
+    ///
+    /// The parameter is the address of the smallest item.
+    UnsortedFromLeft(Address),
+
+    /// The greatest item key of a node is greater than the right separator of the node.
+    ///
+    /// The parameter is the address of the greatest item.
+    UnsortedFromRight(Address),
+
+    /// The links between the given leaf and its neighbors do not follow the key order.
+    BrokenLink(usize),
+
+    /// The given node is referenced more than once in the tree.
+    DuplicateNode(usize),
+
+    /// The number of items in the tree differs from its declared length.
+    ///
+    /// The first parameter is the number of items found,
+    /// then the declared length.
+    WrongLength(usize, usize),
+}
+
+impl ValidationError {
+    /// Returns the identifier of the offending node, if any.
+    #[inline]
+    pub fn node(&self) -> Option<usize> {
+        match self {
+            Self::MissingNode(id)
+            | Self::WrongParent(id, _, _)
+            | Self::Overflow(id)
+            | Self::Underflow(id)
+            | Self::BrokenLink(id)
+            | Self::DuplicateNode(id) => Some(*id),
+            Self::UnsortedNode(addr)
+            | Self::UnsortedFromLeft(addr)
+            | Self::UnsortedFromRight(addr) => Some(addr.id),
+            Self::NotBalanced | Self::WrongLength(_, _) => None,
+        }
+    }
+
+    /// Returns the address of the offending item, if any.
+    #[inline]
+    pub fn address(&self) -> Option<Address> {
+        match self {
+            Self::UnsortedNode(addr)
+            | Self::UnsortedFromLeft(addr)
+            | Self::UnsortedFromRight(addr) => Some(*addr),
+            _ => None,
+        }
+    }
+
+    /// Writes the given tree in the DOT graph description language,
+    /// highlighting the offending node and item, if any.
+    ///
+    /// Contrarily to [`Storage::dot_write`], missing nodes are rendered as placeholders,
+    /// and nodes referenced more than once are only written once,
+    /// so that invalid trees can be rendered.
+    ///
+    /// Requires the `dot` feature.
+    #[cfg(feature = "dot")]
+    pub fn render_dot<S: Storage, W: std::io::Write>(
+        &self,
+        storage: &S,
+        f: &mut W,
+    ) -> std::io::Result<()>
+    where
+        for<'r> S::ItemRef<'r>: crate::dot::Display,
+    {
+        write!(f, "digraph tree {{\n\tnode [shape=record];\n")?;
+        writeln!(
+            f,
+            "\terror [shape=note, style=filled, fillcolor=salmon, label=\"{}\"];",
+            self
+        )?;
+
+        if let Some(id) = storage.root() {
+            crate::dot::write_nodes(storage, f, id, self.node(), self.address())?;
+        }
+
+        match (self.node(), self.address()) {
+            (_, Some(addr)) => writeln!(f, "\terror -> n{}:bad", addr.id)?,
+            (Some(id), None) => writeln!(f, "\terror -> n{}", id)?,
+            (None, None) => (),
+        }
+
+        write!(f, "}}")
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingNode(id) => write!(f, "missing node {}", id),
+            Self::NotBalanced => write!(f, "leaves have different depths"),
+            Self::WrongParent(id, found, expected) => {
+                write!(f, "node {} is declared with parent ", id)?;
+                fmt_node_id(f, *found)?;
+                write!(f, " but is a child of ")?;
+                fmt_node_id(f, *expected)
+            }
+            Self::Overflow(id) => write!(f, "node {} is overflowing", id),
+            Self::Underflow(id) => write!(f, "node {} is underflowing", id),
+            Self::UnsortedNode(addr) => {
+                write!(f, "item {} is smaller than its predecessor", addr)
+            }
+            Self::UnsortedFromLeft(addr) => write!(
+                f,
+                "item {} is not greater than the left separator of its node",
+                addr
+            ),
+            Self::UnsortedFromRight(addr) => write!(
+                f,
+                "item {} is not smaller than the right separator of its node",
+                addr
+            ),
+            Self::BrokenLink(id) => {
+                write!(f, "leaf {} is not linked to its neighbors in key order", id)
+            }
+            Self::DuplicateNode(id) => write!(f, "node {} is referenced more than once", id),
+            Self::WrongLength(found, declared) => write!(
+                f,
+                "found {} items but the tree declares {}",
+                found, declared
+            ),
+        }
+    }
+}
 
-    /// The smallest item key of the node is smaller than the left separator of the node.
-    UnsortedFromLeft(usize),
+#[cfg(feature = "std")]
+impl std::error::Error for ValidationError {}
 
-    /// The greatest item key of the node is greater than the right separator of the node.
-    UnsortedFromRight(usize),
+fn fmt_node_id(f: &mut fmt::Formatter, id: Option<usize>) -> fmt::Result {
+    match id {
+        Some(id) => write!(f, "{}", id),
+        None => write!(f, "none"),
+    }
 }
 
 /// Key-based items partial ordering function.
@@ -62,6 +258,16 @@ pub trait KeyPartialOrd<T: ?Sized>: Storage {
     fn key_partial_cmp<'r>(item: &Self::ItemRef<'r>, other: &T) -> Option<Ordering>
     where
         Self: 'r;
+
+    /// Converts the given key into a primitive integer, if possible.
+    ///
+    /// Along with [`ItemAccess::raw_keys`](node::ItemAccess::raw_keys),
+    /// this allows searching nodes with SIMD instructions (see the `simd` feature).
+    /// The default implementation returns `None`.
+    #[inline]
+    fn raw_key(_key: &T) -> Option<node::RawKey> {
+        None
+    }
 }
 
 /// Key-based items ordering function.
@@ -106,6 +312,14 @@ pub trait Storage: Sized {
     where
         Self: 'r;
 
+    /// Number of items under which keys are searched in a node
+    /// with a linear scan instead of a binary search.
+    ///
+    /// On small nodes, a linear scan is faster than a binary search
+    /// since its branches are easier to predict.
+    /// Storages with expensive key comparisons may lower this value.
+    const LINEAR_SEARCH_THRESHOLD: usize = crate::util::LINEAR_SEARCH_THRESHOLD;
+
     /// Get the root node id.
     ///
     /// Returns `None` if the tree is empty.
@@ -120,9 +334,229 @@ pub trait Storage: Sized {
         self.root().is_none()
     }
 
+    /// Returns the height of the tree, that is the number of nodes
+    /// on the path from the root to any leaf.
+    ///
+    /// Returns `0` if the tree is empty.
+    #[inline]
+    fn height(&self) -> usize {
+        self.root().map(|id| self.node_height(id)).unwrap_or(0)
+    }
+
+    /// Returns the policy deciding how many items the nodes must hold.
+    ///
+    /// Returns the [`Standard`](policy::Standard) policy by default,
+    /// using the minimum capacity of each node.
+    #[inline]
+    fn capacity_policy(&self) -> &dyn CapacityPolicy {
+        &policy::Standard
+    }
+
+    /// Returns the minimum capacity of the node `id`,
+    /// under which it underflows,
+    /// as decided by the [`capacity_policy`](Storage::capacity_policy).
+    ///
+    /// # Panics
+    ///
+    /// This function panics if no node has the given `id`,
+    /// or if the policy requires an invalid minimum capacity
+    /// (see [`policy::min_capacity`]).
+    #[inline]
+    fn node_min_capacity(&self, id: usize) -> usize {
+        let node = self.node(id).unwrap();
+        match policy::min_capacity(
+            self.capacity_policy(),
+            node.max_capacity(),
+            node.min_capacity(),
+        ) {
+            Ok(min_capacity) => min_capacity,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Returns the current balance of the node `id`,
+    /// according to its [`node_min_capacity`](Storage::node_min_capacity).
+    ///
+    /// # Panics
+    ///
+    /// This function panics if no node has the given `id`.
+    #[inline]
+    fn node_balance(&self, id: usize) -> Balance {
+        self.node(id)
+            .unwrap()
+            .balance_with(self.node_min_capacity(id))
+    }
+
+    /// Checks if the node `id` underflows,
+    /// according to its [`node_min_capacity`](Storage::node_min_capacity).
+    ///
+    /// # Panics
+    ///
+    /// This function panics if no node has the given `id`.
+    #[inline]
+    fn is_node_underflowing(&self, id: usize) -> bool {
+        self.node(id).unwrap().item_count() < self.node_min_capacity(id)
+    }
+
+    /// Returns the height of the subtree rooted at the node `id`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if no node has the given `id`.
+    #[inline]
+    fn node_height(&self, id: usize) -> usize {
+        let mut height = 0;
+        let mut id = Some(id);
+        while let Some(current_id) = id {
+            height += 1;
+            id = self.node(current_id).unwrap().child_id(0)
+        }
+
+        height
+    }
+
     /// Returns the node with the given id, if any.
     fn node(&self, id: usize) -> Option<node::Ref<'_, Self>>;
 
+    /// Returns the number of items in the subtree rooted at the node `id`, if known.
+    ///
+    /// Storages maintaining the length of each subtree
+    /// (for instance with the [`Count`](augment::Count) augmentation)
+    /// override this function to allow counting items in logarithmic time.
+    /// The default implementation returns `None`.
+    #[inline]
+    fn subtree_len(&self, _id: usize) -> Option<usize> {
+        None
+    }
+
+    /// Returns the links of the leaf `id` to its neighbor leaves, if known.
+    ///
+    /// Storages maintaining leaf sibling links override this function
+    /// (and the [`StorageMut::link_leaf`] and [`StorageMut::relink_leaves`] hooks)
+    /// so that iterators can hop from one leaf to the next.
+    /// The default implementation returns `None`.
+    #[inline]
+    fn leaf_links(&self, _id: usize) -> Option<LeafLinks> {
+        None
+    }
+
+    /// Returns the number of items located before the given address,
+    /// or `None` if subtree lengths are not known.
+    ///
+    /// The given address must be valid.
+    fn items_before(&self, addr: Address) -> Option<usize> {
+        if addr.is_nowhere() {
+            return Some(0);
+        }
+
+        let node = self.node(addr.id).unwrap();
+        let mut count = 0;
+        if let Some(offset) = addr.offset.value() {
+            count += offset;
+            for i in 0..=offset {
+                if let Some(child_id) = node.child_id(i) {
+                    count += self.subtree_len(child_id)?
+                }
+            }
+        }
+
+        let mut id = addr.id;
+        while let Some(parent_id) = self.node(id).unwrap().parent() {
+            let parent = self.node(parent_id).unwrap();
+            let index = parent.child_index(id).unwrap();
+            count += index;
+            for i in 0..index {
+                count += self.subtree_len(parent.child_id(i).unwrap())?
+            }
+
+            id = parent_id
+        }
+
+        Some(count)
+    }
+
+    /// Returns the address of the `n`-th item of the tree (starting from `0`), if any.
+    ///
+    /// This takes a logarithmic time when the storage knows the length of each subtree
+    /// (see [`Storage::subtree_len`]), by descending from the root
+    /// into the subtree containing the item.
+    /// Otherwise, the first `n` items are visited.
+    fn nth_address(&self, mut n: usize) -> Option<Address> {
+        if n >= self.len() {
+            return None;
+        }
+
+        let mut id = self.root()?;
+        if self.subtree_len(id).is_none() {
+            let mut addr = self.first_item_address();
+            for _ in 0..n {
+                addr = self.next_item_address(addr?)
+            }
+
+            return addr;
+        }
+
+        'descent: loop {
+            let node = self.node(id).unwrap();
+            let item_count = node.item_count();
+            if node.child_id(0).is_none() {
+                return Some(Address::new(id, n.into()));
+            }
+
+            for i in 0..=item_count {
+                let child_id = node.child_id(i).unwrap();
+                let len = self.subtree_len(child_id).unwrap();
+                if n < len {
+                    id = child_id;
+                    continue 'descent;
+                }
+
+                n -= len;
+                if i < item_count {
+                    if n == 0 {
+                        return Some(Address::new(id, i.into()));
+                    }
+
+                    n -= 1
+                }
+            }
+
+            return None;
+        }
+    }
+
+    /// Returns the number of items whose key is strictly less than the given key.
+    ///
+    /// Returns `Ok(rank)` if the key is used in the tree,
+    /// and `Err(rank)` otherwise.
+    ///
+    /// This takes a logarithmic time when the storage knows the length of each subtree
+    /// (see [`Storage::subtree_len`]).
+    /// Otherwise, every item before the key is visited.
+    fn rank_of<Q: ?Sized>(&self, key: &Q) -> Result<usize, usize>
+    where
+        Self: KeyPartialOrd<Q>,
+    {
+        let rank = |addr: Address| {
+            self.items_before(addr).unwrap_or_else(|| {
+                let end = self.normalize(addr);
+                let mut count = 0;
+                let mut current = self.first_item_address();
+                while current != end {
+                    count += 1;
+                    current = self.next_item_address(current.unwrap())
+                }
+
+                count
+            })
+        };
+
+        match self.address_of(key) {
+            Ok(addr) => Ok(rank(addr)),
+            Err(addr) => Err(rank(addr)),
+        }
+    }
+
     /// Returns a reference to the item identified by the supplied key.
     #[inline]
     fn get<Q: ?Sized>(&self, key: &Q) -> Option<Self::ItemRef<'_>>
@@ -135,6 +569,42 @@ pub trait Storage: Sized {
         }
     }
 
+    /// Returns a reference to the item identified by the supplied key,
+    /// starting the search around the given hint address.
+    ///
+    /// If `hint` is the address of an item of this tree, the key is first searched among the
+    /// items surrounding it (see [`address_of_with_hint`](Self::address_of_with_hint)).
+    /// Contrarily to `address_of_with_hint`, the hint may be stale:
+    /// it is checked beforehand (see [`contains_item_address`](Self::contains_item_address)),
+    /// and ignored if it does not designate an item of the tree anymore,
+    /// in which case the search starts from the root.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{slab::Map, Storage};
+    ///
+    /// let map: Map<usize, usize> = (0..100).map(|i| (i, i * 2)).collect();
+    /// let btree = map.btree();
+    /// let hint = btree.address_of(&50).unwrap();
+    ///
+    /// assert_eq!(btree.get_with_hint(hint, &51).map(|binding| binding.value), Some(102));
+    /// assert_eq!(btree.get_with_hint(hint, &1000), None);
+    /// ```
+    #[inline]
+    fn get_with_hint<Q: ?Sized>(&self, hint: Address, key: &Q) -> Option<Self::ItemRef<'_>>
+    where
+        Self: KeyPartialOrd<Q>,
+    {
+        let addr = if self.contains_item_address(hint) {
+            self.address_of_with_hint(hint, key)
+        } else {
+            self.address_of(key)
+        };
+
+        addr.ok().map(|addr| self.item(addr).unwrap())
+    }
+
     /// Returns a reference to the item associated to the given `key` in the node `id`, if any.
     #[inline]
     fn get_in<Q: ?Sized>(&self, key: &Q, mut id: usize) -> Option<Self::ItemRef<'_>>
@@ -142,6 +612,9 @@ pub trait Storage: Sized {
         Self: KeyPartialOrd<Q>,
     {
         loop {
+            #[cfg(feature = "tracing")]
+            crate::trace::emit(self, id, |depth| crate::trace::Event::Descend { id, depth });
+
             let node = self.node(id).unwrap();
             match node.get(key) {
                 Ok(value_opt) => return value_opt,
@@ -151,12 +624,145 @@ pub trait Storage: Sized {
     }
 
     /// Returns a reference to the item at the given address, if any.
+    ///
+    /// Returns `None` if the address is not an item address
+    /// (see [`is_item_address`](Self::is_item_address)),
+    /// although the node is not checked to be part of the tree.
     fn item(&self, addr: Address) -> Option<Self::ItemRef<'_>> {
+        if addr.offset.is_before() {
+            return None;
+        }
+
         self.node(addr.id)
             .map(|node| node.item(addr.offset))
             .flatten()
     }
 
+    /// Returns the current generation of the tree, if the storage tracks it.
+    ///
+    /// The generation changes every time items are inserted or removed,
+    /// or nodes are moved,
+    /// which may make previously computed addresses point to other items.
+    /// It does not change when items are modified in place.
+    ///
+    /// Returns `None` by default, when the storage does not track generations.
+    #[inline]
+    fn generation(&self) -> Option<usize> {
+        None
+    }
+
+    /// Stamps the given address with the current generation of the tree.
+    #[inline]
+    fn stamp(&self, addr: Address) -> StampedAddress {
+        StampedAddress {
+            addr,
+            generation: self.generation(),
+        }
+    }
+
+    /// Checks that the given address was stamped in the current generation of the tree.
+    ///
+    /// Always returns `true` if the storage does not track generations.
+    #[inline]
+    fn is_current(&self, stamped: StampedAddress) -> bool {
+        stamped.generation == self.generation()
+    }
+
+    /// Returns a reference to the item at the given stamped address, if any.
+    ///
+    /// Returns `None` if the address is stale (see [`is_current`](Self::is_current))
+    /// or is not an item address.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{slab::MapStorage, Map, Storage};
+    ///
+    /// let mut btree: MapStorage<usize, usize> = MapStorage::default();
+    /// btree.set_generations(true);
+    ///
+    /// let mut map = Map::from_btree(btree);
+    /// map.insert(1, 10);
+    ///
+    /// let addr = map.btree().stamp(map.btree().address_of(&1).unwrap());
+    /// assert_eq!(map.btree().item_stamped(addr).unwrap().value, 10);
+    ///
+    /// map.insert(0, 0);
+    /// assert!(map.btree().item_stamped(addr).is_none());
+    /// ```
+    #[inline]
+    fn item_stamped(&self, stamped: StampedAddress) -> Option<Self::ItemRef<'_>> {
+        if self.is_current(stamped) {
+            self.item(stamped.addr)
+        } else {
+            None
+        }
+    }
+
+    /// Checks that the given address is valid in this tree,
+    /// and designates an item (see [`contains_address`](Self::contains_address)).
+    ///
+    /// Contrarily to [`contains_address`](Self::contains_address),
+    /// this rejects addresses before the first item of a node (at offset `-1`),
+    /// after its last item, or [nowhere](Address::nowhere).
+    #[inline]
+    fn contains_item_address(&self, addr: Address) -> bool {
+        self.contains_address(addr) && self.item(addr).is_some()
+    }
+
+    /// Checks that the given address is valid in this tree.
+    ///
+    /// The address is valid if it is [nowhere](Address::nowhere) and the tree is empty,
+    /// or if its node is part of the tree and its offset is between `-1`
+    /// and the number of items in the node (included).
+    /// See [`Address`] for a detailed definition.
+    ///
+    /// Addresses are invalidated by most mutations of the tree.
+    /// This can be used to check an address stored across mutations
+    /// before using it, instead of panicking.
+    /// The node is checked to be part of the tree by following its ancestors
+    /// up to the root, which takes a logarithmic time.
+    fn contains_address(&self, addr: Address) -> bool {
+        if addr.is_nowhere() {
+            return self.is_empty();
+        }
+
+        let node = match self.node(addr.id) {
+            Some(node) => node,
+            None => return false,
+        };
+
+        if addr.offset > node.item_count() {
+            return false;
+        }
+
+        let mut id = addr.id;
+        let mut parent = node.parent();
+        while let Some(parent_id) = parent {
+            match self.node(parent_id) {
+                Some(parent_node) if parent_node.child_index(id).is_some() => {
+                    id = parent_id;
+                    parent = parent_node.parent()
+                }
+                _ => return false,
+            }
+        }
+
+        self.root() == Some(id)
+    }
+
+    /// Checks that the given address refers to an item of this tree.
+    ///
+    /// This is a [valid](Self::contains_address) address whose offset is at least `0`
+    /// and less than the number of items in the node.
+    fn is_item_address(&self, addr: Address) -> bool {
+        self.contains_address(addr)
+            && match self.node(addr.id) {
+                Some(node) => addr.offset >= 0 && addr.offset < node.item_count(),
+                None => false,
+            }
+    }
+
     /// Returns a reference to the first item in the tree.
     #[inline]
     fn first_item(&self) -> Option<Self::ItemRef<'_>> {
@@ -282,13 +888,19 @@ pub trait Storage: Sized {
         if !addr.is_nowhere() {
             loop {
                 let node = self.node(addr.id).unwrap();
-                match node.child_id(addr.offset.unwrap()) {
-                    // TODO unwrap may fail here!
-                    Some(child_id) => {
-                        addr.id = child_id;
-                        addr.offset = self.node(child_id).unwrap().item_count().into()
-                    }
-                    None => break,
+                match addr.offset {
+                    Position::At(offset) => match node.child_id(offset) {
+                        Some(child_id) => {
+                            addr.id = child_id;
+                            addr.offset = self.node(child_id).unwrap().item_count().into()
+                        }
+                        None => break,
+                    },
+                    // Before the whole subtree, hence before its first leaf.
+                    Position::Before => match node.child_id(0) {
+                        Some(child_id) => addr.id = child_id,
+                        None => break,
+                    },
                 }
             }
         }
@@ -306,8 +918,8 @@ pub trait Storage: Sized {
         loop {
             let node = self.node(addr.id).unwrap();
 
-            match node.child_id(addr.offset.unwrap()) {
-                // TODO unwrap may fail here.
+            // Nothing precedes the `Before` position in its subtree.
+            match addr.offset.value().and_then(|offset| node.child_id(offset)) {
                 Some(child_id) => {
                     addr.offset = self.node(child_id).unwrap().item_count().into();
                     addr.id = child_id;
@@ -400,8 +1012,8 @@ pub trait Storage: Sized {
         loop {
             let node = self.node(addr.id).unwrap();
 
-            match node.child_id(addr.offset.unwrap()) {
-                // unwrap may fail here.
+            // The offset is never `Before` once incremented.
+            match addr.offset.value().and_then(|offset| node.child_id(offset)) {
                 Some(child_id) => {
                     addr.offset = 0.into();
                     addr.id = child_id;
@@ -452,7 +1064,7 @@ pub trait Storage: Sized {
             if index <= node.item_count() {
                 match node.child_id(index) {
                     Some(child_id) => {
-                        addr.offset = Offset::before();
+                        addr.offset = Position::Before;
                         addr.id = child_id;
                     }
                     None => {
@@ -499,8 +1111,8 @@ pub trait Storage: Sized {
         loop {
             let node = self.node(addr.id).unwrap();
 
-            match node.child_id(addr.offset.unwrap()) {
-                // TODO unwrap may fail here.
+            // The offset is never `Before` once incremented.
+            match addr.offset.value().and_then(|offset| node.child_id(offset)) {
                 Some(child_id) => {
                     addr.offset = 0.into();
                     addr.id = child_id;
@@ -544,247 +1156,787 @@ pub trait Storage: Sized {
         }
     }
 
-    fn address_in<Q: ?Sized>(&self, mut id: usize, key: &Q) -> Result<Address, Address>
+    /// Get the address of the given key, starting the search around the given hint.
+    ///
+    /// Same as [`address_of`](Self::address_of),
+    /// but the key is first compared with the items surrounding the `hint` address:
+    /// the item at (or right after) `hint`, the item before it, and the item after it.
+    /// If the key is located among these items, no descent from the root is performed.
+    /// Otherwise, this falls back to [`address_of`](Self::address_of).
+    /// A hint before the first item of a node (at offset `-1`)
+    /// designates the item right after it.
+    ///
+    /// The hint must be a valid address in this tree.
+    fn address_of_with_hint<Q: ?Sized>(&self, hint: Address, key: &Q) -> Result<Address, Address>
     where
         Self: KeyPartialOrd<Q>,
     {
-        loop {
-            match self.node(id).unwrap().offset_of(key) {
-                Ok(offset) => return Ok(Address { id, offset }),
-                Err((offset, None)) => return Err(Address::new(id, offset.into())),
-                Err((_, Some(child_id))) => {
-                    id = child_id;
-                }
-            }
-        }
-    }
+        let cmp = |addr| {
+            self.item(addr)
+                .and_then(|item| Self::key_partial_cmp(&item, key))
+        };
 
-    /// Gets an iterator over the entries of the map, sorted by key.
-    #[inline]
-    fn iter(&self) -> Iter<Self> {
-        Iter::new(self)
+        let hint = if hint.offset.is_before() {
+            Address::new(self.leaf_address(hint).id, 0.into())
+        } else {
+            hint
+        };
+
+        // Address of the item right after the key, if the key is near the hint.
+        let next = match self.normalize(hint) {
+            Some(addr) => match cmp(addr) {
+                Some(Ordering::Equal) => return Ok(addr),
+                Some(Ordering::Greater) => Some(addr),
+                Some(Ordering::Less) => {
+                    return match self.next_item_address(addr) {
+                        Some(next) => match cmp(next) {
+                            Some(Ordering::Equal) => Ok(next),
+                            Some(Ordering::Greater) => Err(self.leaf_address(next)),
+                            _ => self.address_of(key),
+                        },
+                        None => Err(self.last_valid_address()),
+                    }
+                }
+                None => return self.address_of(key),
+            },
+            None => None,
+        };
+
+        let previous = match next {
+            Some(addr) => self.previous_item_address(addr),
+            None => self.last_item_address(),
+        };
+
+        match (previous, next) {
+            (Some(previous), _) => match cmp(previous) {
+                Some(Ordering::Equal) => Ok(previous),
+                Some(Ordering::Less) => match next {
+                    Some(next) => Err(self.leaf_address(next)),
+                    None => Err(self.last_valid_address()),
+                },
+                _ => self.address_of(key),
+            },
+            (None, Some(next)) => Err(self.leaf_address(next)),
+            (None, None) => self.address_of(key),
+        }
     }
 
-    /// Constructs a mutable double-ended iterator over a sub-range of elements in the map.
-    /// The simplest way is to use the range syntax `min..max`, thus `range(min..max)` will
-    /// yield elements from min (inclusive) to max (exclusive).
-    /// The range may also be entered as `(Bound<T>, Bound<T>)`, so for example
-    /// `range((Excluded(4), Included(10)))` will yield a left-exclusive, right-inclusive
-    /// range from 4 to 10.
+    /// Get the address of the given key, starting the search from the given finger.
     ///
-    /// # Panics
+    /// Same as [`address_of`](Self::address_of),
+    /// but the search starts from the node cached by the [`Finger`]:
+    /// the tree is climbed from this node until the key falls between
+    /// the first and last items of a node, then descended from there.
+    /// If the finger is empty or stale, this falls back to [`address_of`](Self::address_of).
     ///
-    /// Panics if range `start > end`.
-    /// Panics if range `start == end` and both bounds are `Excluded`.
-    #[inline]
-    fn range<T: ?Sized, R>(&self, range: R) -> Range<Self>
-    where
-        T: Ord,
-        R: RangeBounds<T>,
-        Self: KeyPartialOrd<T>,
-    {
-        Range::new(self, range)
-    }
-
-    #[inline]
-    fn eq<S: Storage>(&self, other: &S) -> bool
+    /// The finger is then updated with the returned address.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{slab::Map, Finger, Storage};
+    ///
+    /// let map: Map<usize, usize> = (0..100).map(|i| (i, i)).collect();
+    /// let mut finger = Finger::new();
+    /// for i in 0..100 {
+    ///     let addr = map.btree().address_of_with_finger(&mut finger, &i);
+    ///     assert_eq!(addr, map.btree().address_of(&i));
+    ///     assert_eq!(finger.address(), addr.ok());
+    /// }
+    /// ```
+    fn address_of_with_finger<Q: ?Sized>(
+        &self,
+        finger: &mut Finger,
+        key: &Q,
+    ) -> Result<Address, Address>
     where
-        S: ItemPartialOrd<Self>,
+        Self: KeyPartialOrd<Q>,
     {
-        if self.len() == other.len() {
-            let mut it1 = self.iter();
-            let mut it2 = other.iter();
+        let result = match finger.address() {
+            Some(addr) if self.contains_address(addr) => {
+                let mut id = addr.id;
+                loop {
+                    let node = self.node(id).unwrap();
+                    let in_range = node.item_count() > 0
+                        && Self::key_partial_cmp(&node.first_item().unwrap(), key)
+                            .map(Ordering::is_le)
+                            .unwrap_or(false)
+                        && Self::key_partial_cmp(&node.last_item().unwrap(), key)
+                            .map(Ordering::is_ge)
+                            .unwrap_or(false);
 
-            loop {
-                match (it1.next(), it2.next()) {
-                    (None, None) => break,
-                    (Some(item1), Some(item2)) => {
-                        if S::item_partial_cmp(&item2, &item1)
-                            .map(Ordering::is_ne)
-                            .unwrap_or(true)
-                        {
-                            return false;
-                        }
+                    match node.parent() {
+                        Some(parent_id) if !in_range => id = parent_id,
+                        _ => break self.address_in(id, key),
                     }
-                    _ => return false,
                 }
             }
+            _ => self.address_of(key),
+        };
 
-            true
-        } else {
-            false
+        match result {
+            Ok(addr) | Err(addr) => finger.set_address(addr),
         }
+
+        result
     }
 
-    #[inline]
-    fn partial_cmp<S: Storage>(&self, other: &S) -> Option<Ordering>
+    /// Returns the address of the first item above the given bound, if any.
+    ///
+    /// This is the first item whose key is greater or equal to an `Included` bound,
+    /// the first item whose key is strictly greater than an `Excluded` bound,
+    /// or the first item of the tree if the bound is `Unbounded`.
+    fn lower_bound<Q: ?Sized>(&self, bound: Bound<&Q>) -> Option<Address>
     where
-        S: ItemPartialOrd<Self>,
+        Self: KeyPartialOrd<Q>,
     {
-        let mut it1 = self.iter();
-        let mut it2 = other.iter();
+        match bound {
+            Bound::Included(key) => match self.address_of(key) {
+                Ok(addr) => Some(addr),
+                Err(addr) => self.normalize(addr),
+            },
+            Bound::Excluded(key) => match self.address_of(key) {
+                Ok(addr) => self.next_item_address(addr),
+                Err(addr) => self.normalize(addr),
+            },
+            Bound::Unbounded => self.first_item_address(),
+        }
+    }
+
+    /// Returns the address of the first item above the items below the given bound, if any.
+    ///
+    /// This is the first item whose key is strictly greater than an `Included` bound,
+    /// or the first item whose key is greater or equal to an `Excluded` bound.
+    /// If the bound is `Unbounded`, there is no such item.
+    ///
+    /// The item preceding the returned address (or the last item if `None` is returned)
+    /// is the last item below the bound.
+    fn upper_bound<Q: ?Sized>(&self, bound: Bound<&Q>) -> Option<Address>
+    where
+        Self: KeyPartialOrd<Q>,
+    {
+        match bound {
+            Bound::Included(key) => match self.address_of(key) {
+                Ok(addr) => self.next_item_address(addr),
+                Err(addr) => self.normalize(addr),
+            },
+            Bound::Excluded(key) => match self.address_of(key) {
+                Ok(addr) => Some(addr),
+                Err(addr) => self.normalize(addr),
+            },
+            Bound::Unbounded => None,
+        }
+    }
 
+    fn address_in<Q: ?Sized>(&self, mut id: usize, key: &Q) -> Result<Address, Address>
+    where
+        Self: KeyPartialOrd<Q>,
+    {
         loop {
-            match (it1.next(), it2.next()) {
-                (None, None) => return Some(Ordering::Equal),
-                (_, None) => return Some(Ordering::Greater),
-                (None, _) => return Some(Ordering::Less),
-                (Some(item1), Some(item2)) => match S::item_partial_cmp(&item2, &item1) {
-                    Some(Ordering::Greater) => return Some(Ordering::Less),
-                    Some(Ordering::Less) => return Some(Ordering::Greater),
-                    Some(Ordering::Equal) => match S::item_partial_cmp(&item2, &item1) {
-                        Some(Ordering::Greater) => return Some(Ordering::Less),
-                        Some(Ordering::Less) => return Some(Ordering::Greater),
-                        Some(Ordering::Equal) => (),
-                        None => return None,
-                    },
-                    None => return None,
-                },
+            #[cfg(feature = "tracing")]
+            crate::trace::emit(self, id, |depth| crate::trace::Event::Descend { id, depth });
+
+            match self.node(id).unwrap().offset_of(key) {
+                Ok(offset) => return Ok(Address { id, offset }),
+                Err((offset, None)) => return Err(Address::new(id, offset.into())),
+                Err((_, Some(child_id))) => {
+                    id = child_id;
+                }
             }
         }
     }
 
+    /// Gets an iterator over the entries of the map, sorted by key.
     #[inline]
-    fn cmp(&self, other: &Self) -> Ordering
+    fn iter(&self) -> Iter<Self> {
+        Iter::new(self)
+    }
+
+    /// Gets an iterator over the items of the tree along with their address, in order.
+    ///
+    /// The addresses remain valid as long as the tree is not modified,
+    /// which allows building side tables of positions
+    /// without searching for every item again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{slab::Map, Storage};
+    ///
+    /// let map: Map<usize, usize> = (0..100).map(|i| (i, i)).collect();
+    /// for (addr, binding) in map.btree().addresses() {
+    ///     assert_eq!(map.btree().address_of(&binding.key), Ok(addr));
+    /// }
+    /// ```
+    #[inline]
+    fn addresses(&self) -> Addresses<'_, Self> {
+        Addresses::new(self)
+    }
+
+    /// Gets an iterator over the contiguous item runs of the tree, in order.
+    ///
+    /// Each leaf node is visited at once, yielding a [`Chunk`] with all its items,
+    /// while the items of internal nodes are yielded as single-item chunks.
+    /// Walking the chunks avoids computing the address of every item,
+    /// which makes it suitable for bulk exports or hashing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{slab::Map, Storage};
+    ///
+    /// let map: Map<usize, usize> = (0..100).map(|i| (i, i)).collect();
+    /// let mut count = 0;
+    /// for chunk in map.btree().chunks() {
+    ///     for binding in chunk {
+    ///         assert_eq!(binding.key, count);
+    ///         count += 1
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(count, 100);
+    /// ```
+    #[inline]
+    fn chunks(&self) -> Chunks<'_, Self> {
+        Chunks::new(self)
+    }
+
+    /// Gets an iterator over the leaf nodes of the tree, in order.
+    ///
+    /// Each leaf is yielded as a [`Chunk`] containing all its items.
+    /// The next leaf is found by navigating through the parent nodes,
+    /// one step per node instead of one step per item.
+    /// Note that the items of internal nodes are not visited
+    /// (see [`chunks`](Storage::chunks) to visit every item).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{slab::Map, Storage};
+    ///
+    /// let map: Map<usize, usize> = (0..100).map(|i| (i, i)).collect();
+    /// let leaf_items: usize = map.btree().leaves().map(|leaf| leaf.len()).sum();
+    /// assert!(leaf_items <= 100);
+    /// assert!(map.btree().leaves().all(|leaf| leaf.is_leaf()));
+    /// ```
+    #[inline]
+    fn leaves(&self) -> Leaves<'_, Self> {
+        Leaves::new(self)
+    }
+
+    /// Visits the nodes of the tree in the given order.
+    ///
+    /// For each node, `f` is called with a [`NodeInfo`] reporting the node id,
+    /// depth, parent, item count and children.
+    /// The traversal stops as soon as `f` returns [`ControlFlow::Break`],
+    /// in which case the break value is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use core::ops::ControlFlow;
+    /// use generic_btree::{slab::Map, Storage, Traversal};
+    ///
+    /// let map: Map<usize, usize> = (0..100).map(|i| (i, i)).collect();
+    ///
+    /// let mut height = 0;
+    /// let _ = map.btree().visit_nodes(Traversal::DepthFirst, |node| -> ControlFlow<()> {
+    ///     height = height.max(node.depth() + 1);
+    ///     ControlFlow::Continue(())
+    /// });
+    ///
+    /// let first_leaf = map.btree().visit_nodes(Traversal::DepthFirst, |node| {
+    ///     if node.is_leaf() {
+    ///         ControlFlow::Break(node.id())
+    ///     } else {
+    ///         ControlFlow::Continue(())
+    ///     }
+    /// });
+    ///
+    /// assert!(height > 1);
+    /// assert_eq!(first_leaf, ControlFlow::Break(map.btree().first_item_address().unwrap().id));
+    /// ```
+    #[inline]
+    fn visit_nodes<B, F>(&self, order: Traversal, f: F) -> ControlFlow<B>
     where
-        Self: ItemOrd,
+        F: FnMut(NodeInfo<'_, Self>) -> ControlFlow<B>,
     {
-        let mut it1 = self.iter();
-        let mut it2 = other.iter();
+        visit::visit_nodes(self, order, f)
+    }
 
-        loop {
-            match (it1.next(), it2.next()) {
-                (None, None) => return Ordering::Equal,
-                (_, None) => return Ordering::Greater,
-                (None, _) => return Ordering::Less,
-                (Some(item1), Some(item2)) => match Self::item_cmp(&item2, &item1) {
-                    Ordering::Greater => return Ordering::Less,
-                    Ordering::Less => return Ordering::Greater,
-                    Ordering::Equal => match Self::item_cmp(&item2, &item1) {
-                        Ordering::Greater => return Ordering::Less,
-                        Ordering::Less => return Ordering::Greater,
-                        Ordering::Equal => (),
-                    },
-                },
+    /// Returns the estimated size in memory of the node `id`, in bytes, if known.
+    ///
+    /// This includes the node itself and any heap allocation it owns,
+    /// but not the heap allocations owned by its items.
+    /// Used by [`stats`](Storage::stats).
+    /// The default implementation returns `None`.
+    #[inline]
+    fn node_bytes(&self, _id: usize) -> Option<usize> {
+        None
+    }
+
+    /// Computes statistics about the shape of the tree.
+    ///
+    /// This visits every node of the tree, and can be used to monitor
+    /// how well the order of the nodes fits the workload.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{slab::Map, Storage};
+    ///
+    /// let map: Map<usize, usize> = (0..100).map(|i| (i, i)).collect();
+    /// let stats = map.btree().stats();
+    /// assert_eq!(stats.items, 100);
+    /// assert_eq!(stats.height, map.btree().height());
+    /// assert_eq!(stats.levels.len(), stats.height);
+    /// assert!(stats.occupancy() > 0.0 && stats.occupancy() <= 1.0);
+    /// ```
+    fn stats(&self) -> Stats {
+        let mut stats = Stats {
+            bytes: Some(0),
+            ..Stats::default()
+        };
+
+        let _ = self.visit_nodes(Traversal::BreadthFirst, |node| -> ControlFlow<()> {
+            let items = node.item_count();
+            let capacity = node.node().max_capacity() - 1;
+
+            if node.is_leaf() {
+                stats.leaf_nodes += 1
+            } else {
+                stats.internal_nodes += 1
             }
+
+            stats.items += items;
+            stats.capacity += capacity;
+            stats.bytes = stats
+                .bytes
+                .and_then(|bytes| Some(bytes + self.node_bytes(node.id())?));
+
+            if stats.levels.len() <= node.depth() {
+                stats.levels.push(LevelStats::default())
+            }
+
+            let level = &mut stats.levels[node.depth()];
+            level.nodes += 1;
+            level.items += items;
+            level.capacity += capacity;
+
+            ControlFlow::Continue(())
+        });
+
+        stats.height = stats.levels.len();
+        if stats.height == 0 {
+            stats.bytes = None
         }
+
+        stats
     }
 
+    /// Visits the items of this tree that are not in `other`, in order.
+    ///
+    /// Both trees are traversed side by side, comparing their items with [`ItemPartialOrd`].
+    /// Items that cannot be compared are considered distinct.
     #[inline]
-    fn hash<'r, H: Hasher>(&'r self, h: &mut H)
+    fn difference<'a, S: Storage>(&'a self, other: &'a S) -> Difference<'a, Self, S>
     where
-        Self::ItemRef<'r>: Hash,
+        Self: ItemPartialOrd<S>,
     {
-        for item in self.iter() {
-            item.hash(h);
-        }
+        Difference::new(self, other)
     }
 
-    /// Write the tree in the DOT graph description language.
+    /// Visits the items of this tree that are also in `other`, in order.
     ///
-    /// Requires the `dot` feature.
-    #[cfg(feature = "dot")]
+    /// Both trees are traversed side by side, comparing their items with [`ItemPartialOrd`].
+    /// Items that cannot be compared are considered distinct.
     #[inline]
-    fn dot_write<W: std::io::Write>(&self, f: &mut W) -> std::io::Result<()>
+    fn intersection<'a, S: Storage>(&'a self, other: &'a S) -> Intersection<'a, Self, S>
     where
-        for<'r> Self::ItemRef<'r>: crate::dot::Display,
+        Self: ItemPartialOrd<S>,
     {
-        write!(f, "digraph tree {{\n\tnode [shape=record];\n")?;
-        if let Some(id) = self.root() {
-            self.dot_write_node(f, id)?
-        }
-        write!(f, "}}")
+        Intersection::new(self, other)
     }
 
-    /// Write the given node in the DOT graph description language.
+    /// Visits the items of this tree and `other`, in order and without duplicates.
     ///
-    /// Requires the `dot` feature.
-    #[cfg(feature = "dot")]
+    /// Both trees are traversed side by side, comparing their items with [`ItemPartialOrd`].
+    /// When an item is in both trees, only the item of this tree is visited.
+    /// Items that cannot be compared are considered distinct.
     #[inline]
-    fn dot_write_node<W: std::io::Write>(&self, f: &mut W, id: usize) -> std::io::Result<()>
+    fn union<'a>(&'a self, other: &'a Self) -> Union<'a, Self>
     where
-        for<'r> Self::ItemRef<'r>: crate::dot::Display,
+        Self: ItemPartialOrd<Self>,
     {
-        let name = format!("n{}", id);
-        let node = self.node(id).unwrap();
+        Union::new(self, other)
+    }
+
+    /// Visits the items of this tree and `other`, merged in order.
+    ///
+    /// Both trees are traversed side by side, comparing their items with [`ItemPartialOrd`].
+    /// Each item is wrapped in a [`Merged`] value telling from which tree it comes.
+    /// Equivalent items present in both trees are visited together,
+    /// leaving it to the caller to resolve the pair.
+    /// Items that cannot be compared are considered distinct.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{slab::Map, Merged, Storage};
+    ///
+    /// let a: Map<u32, ()> = vec![(1, ()), (2, ())].into_iter().collect();
+    /// let b: Map<u32, ()> = vec![(2, ()), (3, ())].into_iter().collect();
+    ///
+    /// let merged: Vec<_> = a
+    ///     .btree()
+    ///     .merge_iter(b.btree())
+    ///     .map(|item| match item {
+    ///         Merged::Left(a) => (a.key, "left"),
+    ///         Merged::Right(b) => (b.key, "right"),
+    ///         Merged::Both(a, _) => (a.key, "both"),
+    ///     })
+    ///     .collect();
+    ///
+    /// assert_eq!(merged, [(1, "left"), (2, "both"), (3, "right")]);
+    /// ```
+    #[inline]
+    fn merge_iter<'a, S: Storage>(&'a self, other: &'a S) -> Merge<'a, Self, S>
+    where
+        Self: ItemPartialOrd<S>,
+    {
+        Merge::new(self, other)
+    }
+
+    /// Visits the items that are in this tree or in `other`, but not in both, in order.
+    ///
+    /// Both trees are traversed side by side, comparing their items with [`ItemPartialOrd`].
+    /// Items that cannot be compared are considered distinct.
+    #[inline]
+    fn symmetric_difference<'a>(&'a self, other: &'a Self) -> SymmetricDifference<'a, Self>
+    where
+        Self: ItemPartialOrd<Self>,
+    {
+        SymmetricDifference::new(self, other)
+    }
+
+    /// Checks that every item of this tree is also in `other`.
+    ///
+    /// Returns early if this tree has more items than `other`,
+    /// or if its first or last item is outside of the items of `other`.
+    /// Otherwise, both trees are traversed side by side until a missing item is found.
+    #[inline]
+    fn is_subset<S: Storage>(&self, other: &S) -> bool
+    where
+        Self: ItemPartialOrd<S>,
+    {
+        if self.len() > other.len() {
+            return false;
+        }
 
-        write!(f, "\t{} [label=\"", name)?;
-        if let Some(parent) = node.parent() {
-            write!(f, "({})|", parent)?;
+        if let (Some(first), Some(last)) = (self.first_item(), self.last_item()) {
+            let other_first = other.first_item().unwrap();
+            let other_last = other.last_item().unwrap();
+            let within = matches!(
+                Self::item_partial_cmp(&first, &other_first),
+                Some(Ordering::Greater | Ordering::Equal)
+            ) && matches!(
+                Self::item_partial_cmp(&last, &other_last),
+                Some(Ordering::Less | Ordering::Equal)
+            );
+
+            if !within {
+                return false;
+            }
         }
 
-        // node.dot_write_label(f)?;
-        use crate::dot::Display;
-        writeln!(f, "{}({})\"];", node.dot(), id)?;
+        let mut others = other.iter();
+        'items: for item in self.iter() {
+            for other_item in &mut others {
+                match Self::item_partial_cmp(&item, &other_item) {
+                    Some(Ordering::Equal) => continue 'items,
+                    Some(Ordering::Greater) => (),
+                    _ => return false,
+                }
+            }
 
-        for child_id in node.children() {
-            self.dot_write_node(f, child_id)?;
-            let child_name = format!("n{}", child_id);
-            writeln!(f, "\t{} -> {}", name, child_name)?;
+            return false;
         }
 
-        Ok(())
+        true
     }
 
-    #[cfg(debug_assertions)]
-    fn validate(&self) -> Result<(), ValidationError>
+    /// Checks that every item of `other` is also in this tree.
+    ///
+    /// See [`is_subset`](Self::is_subset).
+    #[inline]
+    fn is_superset<S>(&self, other: &S) -> bool
     where
-        Self: KeyOrd,
+        S: ItemPartialOrd<Self>,
     {
-        if let Some(id) = self.root() {
-            self.validate_node(id, None, None, None)?;
-        }
-
-        Ok(())
+        other.is_subset(self)
     }
 
-    /// Validate the given node and returns the depth of the node.
-    #[cfg(debug_assertions)]
-    fn validate_node<'a>(
-        &'a self,
-        id: usize,
-        parent: Option<usize>,
-        min: Option<Self::ItemRef<'a>>,
-        max: Option<Self::ItemRef<'a>>,
-    ) -> Result<usize, ValidationError>
+    /// Checks that this tree and `other` have no item in common.
+    ///
+    /// Returns early if all the items of one tree are before the items of the other.
+    /// Otherwise, both trees are traversed side by side until a common item is found.
+    #[inline]
+    fn is_disjoint<S: Storage>(&self, other: &S) -> bool
     where
-        Self: KeyOrd,
+        Self: ItemPartialOrd<S>,
     {
-        let node = self.node(id).ok_or(ValidationError::MissingNode(id))?;
-        let (mut min, mut max) = node.validate(id, parent, min, max)?;
-
-        let mut depth = None;
-        for (i, child_id) in node.children().enumerate() {
-            let (child_min, child_max) = node.separators(i);
-            let min = child_min.or_else(|| min.take());
-            let max = child_max.or_else(|| max.take());
-
-            let child_depth = self.validate_node(child_id, Some(id), min, max)?;
-            match depth {
-                None => depth = Some(child_depth),
-                Some(depth) => {
-                    if depth != child_depth {
-                        return Err(ValidationError::NotBalanced);
-                    }
+        match (self.first_item(), other.last_item()) {
+            (Some(first), Some(other_last)) => {
+                if Self::item_partial_cmp(&first, &other_last) == Some(Ordering::Greater) {
+                    return true;
                 }
             }
+            _ => return true,
         }
 
-        Ok(match depth {
-            Some(depth) => depth + 1,
-            None => 0,
-        })
+        let last = self.last_item().unwrap();
+        let other_first = other.first_item().unwrap();
+        if Self::item_partial_cmp(&last, &other_first) == Some(Ordering::Less) {
+            return true;
+        }
+
+        self.intersection(other).next().is_none()
     }
-}
 
-/// Mutable data storage.
-///
-/// # Correctness
-///
-/// When using a mutable reference to an item of the B-Tree,
-/// the user is responsible of keeping the well sortedness of the
-/// items.
-///
+    /// Constructs a mutable double-ended iterator over a sub-range of elements in the map.
+    /// The simplest way is to use the range syntax `min..max`, thus `range(min..max)` will
+    /// yield elements from min (inclusive) to max (exclusive).
+    /// The range may also be entered as `(Bound<T>, Bound<T>)`, so for example
+    /// `range((Excluded(4), Included(10)))` will yield a left-exclusive, right-inclusive
+    /// range from 4 to 10.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    #[inline]
+    fn range<T: ?Sized, R>(&self, range: R) -> Range<Self>
+    where
+        T: Ord,
+        R: RangeBounds<T>,
+        Self: KeyPartialOrd<T>,
+    {
+        Range::new(self, range)
+    }
+
+    /// Returns the number of items in the given range.
+    ///
+    /// This takes a logarithmic time when the storage knows the length of each subtree
+    /// (see [`Storage::subtree_len`]).
+    /// Otherwise, every item of the range is visited.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    #[inline]
+    fn range_count<T, R>(&self, range: R) -> usize
+    where
+        T: ?Sized + Ord,
+        R: RangeBounds<T>,
+        Self: KeyPartialOrd<T>,
+    {
+        self.range(range).remaining()
+    }
+
+    #[inline]
+    fn eq<S: Storage>(&self, other: &S) -> bool
+    where
+        S: ItemPartialOrd<Self>,
+    {
+        self.eq_by(other, |item1, item2| {
+            S::item_partial_cmp(&item2, &item1) == Some(Ordering::Equal)
+        })
+    }
+
+    /// Tests if the items of this tree are equal to the items of `other`, in order,
+    /// using the given equality function.
+    ///
+    /// Contrarily to [`eq`](Storage::eq), this does not require the storages
+    /// to implement [`ItemPartialOrd`],
+    /// so that trees with different item types can be compared.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{slab::Map, Storage};
+    ///
+    /// let a: Map<usize, usize> = (0..10).map(|i| (i, i)).collect();
+    /// let b: Map<String, usize> = (0..10).map(|i| (i.to_string(), i)).collect();
+    /// assert!(a.btree().eq_by(b.btree(), |a, b| a.as_pair().1 == b.as_pair().1));
+    /// ```
+    #[inline]
+    fn eq_by<'a, 'b, S: Storage, F>(&'a self, other: &'b S, mut eq: F) -> bool
+    where
+        F: FnMut(Self::ItemRef<'a>, S::ItemRef<'b>) -> bool,
+    {
+        self.len() == other.len()
+            && self
+                .iter()
+                .zip(other.iter())
+                .all(|(item1, item2)| eq(item1, item2))
+    }
+
+    #[inline]
+    fn partial_cmp<S: Storage>(&self, other: &S) -> Option<Ordering>
+    where
+        S: ItemPartialOrd<Self>,
+    {
+        let mut it1 = self.iter();
+        let mut it2 = other.iter();
+
+        loop {
+            match (it1.next(), it2.next()) {
+                (None, None) => return Some(Ordering::Equal),
+                (_, None) => return Some(Ordering::Greater),
+                (None, _) => return Some(Ordering::Less),
+                (Some(item1), Some(item2)) => match S::item_partial_cmp(&item2, &item1)? {
+                    Ordering::Equal => (),
+                    ordering => return Some(ordering.reverse()),
+                },
+            }
+        }
+    }
+
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering
+    where
+        Self: ItemOrd,
+    {
+        self.cmp_by(other, |item1, item2| Self::item_cmp(&item1, &item2))
+    }
+
+    /// Lexicographically compares the items of this tree with the items of `other`,
+    /// using the given comparison function.
+    ///
+    /// Contrarily to [`cmp`](Storage::cmp), this does not require the storages
+    /// to implement [`ItemOrd`],
+    /// so that trees with different item types can be compared.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{slab::Map, Storage};
+    /// use std::cmp::Ordering;
+    ///
+    /// let a: Map<usize, usize> = (0..10).map(|i| (i, i)).collect();
+    /// let b: Map<String, usize> = (0..10).map(|i| (i.to_string(), i * 2)).collect();
+    /// assert_eq!(
+    ///     a.btree().cmp_by(b.btree(), |a, b| a.as_pair().1.cmp(b.as_pair().1)),
+    ///     Ordering::Less
+    /// );
+    /// ```
+    #[inline]
+    fn cmp_by<'a, 'b, S: Storage, F>(&'a self, other: &'b S, mut cmp: F) -> Ordering
+    where
+        F: FnMut(Self::ItemRef<'a>, S::ItemRef<'b>) -> Ordering,
+    {
+        let mut it1 = self.iter();
+        let mut it2 = other.iter();
+
+        loop {
+            match (it1.next(), it2.next()) {
+                (None, None) => return Ordering::Equal,
+                (_, None) => return Ordering::Greater,
+                (None, _) => return Ordering::Less,
+                (Some(item1), Some(item2)) => match cmp(item1, item2) {
+                    Ordering::Equal => (),
+                    ordering => return ordering,
+                },
+            }
+        }
+    }
+
+    #[inline]
+    fn hash<'r, H: Hasher>(&'r self, h: &mut H)
+    where
+        Self::ItemRef<'r>: Hash,
+    {
+        for item in self.iter() {
+            item.hash(h);
+        }
+    }
+
+    /// Write the tree in the DOT graph description language.
+    ///
+    /// Requires the `dot` feature.
+    #[cfg(feature = "dot")]
+    #[inline]
+    fn dot_write<W: std::io::Write>(&self, f: &mut W) -> std::io::Result<()>
+    where
+        for<'r> Self::ItemRef<'r>: crate::dot::Display,
+    {
+        write!(f, "digraph tree {{\n\tnode [shape=record];\n")?;
+        if let Some(id) = self.root() {
+            self.dot_write_node(f, id)?
+        }
+        write!(f, "}}")
+    }
+
+    /// Write the given node and its descendants in the DOT graph description language.
+    ///
+    /// Nodes are written in depth-first order, using an explicit worklist
+    /// so that the stack usage does not depend on the depth of the subtree.
+    ///
+    /// Requires the `dot` feature.
+    #[cfg(feature = "dot")]
+    #[inline]
+    fn dot_write_node<W: std::io::Write>(&self, f: &mut W, id: usize) -> std::io::Result<()>
+    where
+        for<'r> Self::ItemRef<'r>: crate::dot::Display,
+    {
+        crate::dot::write_nodes(self, f, id, None, None)
+    }
+
+    /// Validates the tree, and returns the first issue found, if any.
+    ///
+    /// Requires debug assertions or the `validation` feature.
+    /// See [`validation_report`](Storage::validation_report) to find every issue.
+    #[cfg(any(debug_assertions, feature = "validation"))]
+    fn validate(&self) -> Result<(), ValidationError>
+    where
+        Self: KeyOrd,
+    {
+        self.validation_report().into_result()
+    }
+
+    /// Validates the whole tree, and reports every issue found
+    /// with the path from the root to the offending node.
+    ///
+    /// Requires debug assertions or the `validation` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{slab::Map, Storage};
+    ///
+    /// let map: Map<usize, usize> = (0..100).map(|i| (i, i)).collect();
+    /// let report = map.btree().validation_report();
+    /// assert!(report.is_valid());
+    /// assert_eq!(report.item_count, 100);
+    /// ```
+    #[cfg(any(debug_assertions, feature = "validation"))]
+    fn validation_report(&self) -> ValidationReport
+    where
+        Self: KeyOrd,
+    {
+        validation::Validator::new(self).run()
+    }
+}
+
+/// Mutable data storage.
+///
+/// # Correctness
+///
+/// When using a mutable reference to an item of the B-Tree,
+/// the user is responsible of keeping the well sortedness of the
+/// items.
+///
 /// # Safety
 ///
 /// Implementations of this trait must ensure that
@@ -843,15 +1995,218 @@ pub unsafe trait StorageMut: Storage {
     /// This funciton panics if the node does not exists.
     fn release_node(&mut self, id: usize) -> node::Buffer<Self>;
 
+    /// Records that the newly allocated node `id`, if it is a leaf,
+    /// directly follows the leaf `previous` in key order,
+    /// or is the only leaf of the tree if `previous` is `None`.
+    ///
+    /// This hook is called by the tree algorithms every time a leaf is created
+    /// by splitting another leaf, or as the first leaf of an empty tree.
+    /// Storages maintaining leaf sibling links (see [`Storage::leaf_links`])
+    /// must also unlink every released leaf.
+    /// The default implementation does nothing.
+    #[inline]
+    fn link_leaf(&mut self, _previous: Option<usize>, _id: usize) {}
+
+    /// Recomputes the leaf sibling links of the whole tree.
+    ///
+    /// This hook is called by the tree algorithms after reorganizing the leaves
+    /// in a way not covered by [`link_leaf`](StorageMut::link_leaf),
+    /// such as building, splitting or concatenating trees.
+    /// The default implementation does nothing.
+    #[inline]
+    fn relink_leaves(&mut self) {}
+
     /// Returns the node with the given id, if any.
     fn node_mut(&mut self, id: usize) -> Option<node::Mut<'_, Self>>;
 
+    /// Returns the number of nodes that can still be allocated,
+    /// or `None` if the storage is not bounded.
+    #[inline]
+    fn remaining_node_capacity(&self) -> Option<usize> {
+        None
+    }
+
+    /// Checks that the storage can allocate every node an insertion may require.
+    ///
+    /// An insertion may split every node from a leaf to the root, and create a new root.
+    #[inline]
+    fn can_insert(&self) -> bool {
+        match self.remaining_node_capacity() {
+            Some(capacity) => capacity > self.height(),
+            None => true,
+        }
+    }
+
+    /// Checks that the storage can allocate every node required to insert
+    /// an item at the given leaf address.
+    ///
+    /// Unlike [`can_insert`](StorageMut::can_insert), only the nodes that would
+    /// actually be split by the insertion are taken into account.
+    fn can_insert_at(&self, addr: Address) -> bool {
+        match self.remaining_node_capacity() {
+            Some(capacity) => {
+                let mut required = 0;
+                if addr.is_nowhere() {
+                    required = 1
+                } else {
+                    let mut id = Some(addr.id);
+                    while let Some(node) = id.and_then(|id| self.node(id)) {
+                        if node.item_count() + 1 < node.max_capacity() {
+                            break;
+                        }
+
+                        required += 1;
+                        id = node.parent();
+                        if id.is_none() {
+                            // A new root is created.
+                            required += 1
+                        }
+                    }
+                }
+
+                capacity >= required
+            }
+            None => true,
+        }
+    }
+
+    /// Checks if the storage maintains an augmentation for each node.
+    ///
+    /// If not, calls to [`recompute_path`](StorageMut::recompute_path) are skipped.
+    #[inline]
+    fn is_augmented(&self) -> bool {
+        false
+    }
+
+    /// Returns the observer of the structural modifications of the tree, if any.
+    ///
+    /// The rebalancing algorithms notify it every time nodes are split,
+    /// merged or rotated, and items move between nodes.
+    /// Returns `None` by default.
+    #[inline]
+    fn observer(&mut self) -> Option<&mut dyn Observer> {
+        None
+    }
+
+    /// Recompute the augmentation of the node with the given `id`.
+    ///
+    /// This hook is called by the tree algorithms every time the items or children
+    /// of a node are modified, after the augmentations of its children
+    /// have been recomputed.
+    /// Storages implementing [`Augment`] should override it to update
+    /// the augmentation of the node.
+    /// The default implementation does nothing.
+    #[inline]
+    fn recompute(&mut self, _id: usize) {}
+
+    /// Recompute the augmentation of the node with the given `id` and of all its ancestors.
+    ///
+    /// This must be called after modifying an item in place
+    /// (for instance using [`item_mut`](StorageMut::item_mut))
+    /// in a way that changes its contribution to the augmentation.
+    #[inline]
+    fn recompute_path(&mut self, mut id: usize) {
+        if self.is_augmented() {
+            loop {
+                self.recompute(id);
+                match self.node(id).unwrap().parent() {
+                    Some(parent_id) => id = parent_id,
+                    None => break,
+                }
+            }
+        }
+    }
+
+    /// Returns a mutable reference to the item at the given address, if any.
+    ///
+    /// Returns `None` if the address is not an item address
+    /// (see [`is_item_address`](Storage::is_item_address)),
+    /// although the node is not checked to be part of the tree.
     fn item_mut(&mut self, addr: Address) -> Option<Self::ItemMut<'_>> {
+        if addr.offset.is_before() {
+            return None;
+        }
+
         self.node_mut(addr.id)
             .map(|node| node.into_item_mut(addr.offset))
             .flatten()
     }
 
+    /// Returns a mutable reference to the item at the given stamped address, if any.
+    ///
+    /// Returns `None` if the address is stale (see [`is_current`](Storage::is_current))
+    /// or is not an item address.
+    #[inline]
+    fn item_mut_stamped(&mut self, stamped: StampedAddress) -> Option<Self::ItemMut<'_>> {
+        if self.is_current(stamped) {
+            self.item_mut(stamped.addr)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a raw handle on the node with the given id, if any,
+    /// from a raw pointer to the storage.
+    ///
+    /// This is used by the mutable iterators ([`IterMut`] and [`RangeMut`])
+    /// to navigate the tree and emit items without holding a `&mut Self` reference
+    /// that would alias the items already emitted.
+    /// Each node is entered once through this method,
+    /// and only accessed through [`raw_child_id`](StorageMut::raw_child_id)
+    /// and [`raw_item_mut`](StorageMut::raw_item_mut) afterward.
+    ///
+    /// The default implementations of these three methods go through
+    /// [`node`](Storage::node) and [`item_mut`](StorageMut::item_mut),
+    /// borrowing the whole storage every time.
+    /// Storages should override them to only borrow a node when it is entered,
+    /// and reach its children and items through raw pointers afterward.
+    ///
+    /// # Safety
+    ///
+    /// The storage pointed to by `this` must be valid and mutably borrowed
+    /// for as long as the raw node is used, and must not be modified in the meantime.
+    /// No item of the node may be borrowed.
+    unsafe fn raw_node(this: NonNull<Self>, id: usize) -> Option<node::Raw> {
+        let node = (*this.as_ptr()).node(id)?;
+        Some(node::Raw {
+            id,
+            item_count: node.item_count(),
+            first_child_id: node.child_id(0),
+            ptr: this.cast(),
+        })
+    }
+
+    /// Returns the identifier of the child at the given index of a raw node, if any.
+    ///
+    /// # Safety
+    ///
+    /// The raw node must have been returned by [`raw_node`](StorageMut::raw_node)
+    /// for the storage pointed to by `this`, and still be valid.
+    unsafe fn raw_child_id(this: NonNull<Self>, node: &node::Raw, index: usize) -> Option<usize> {
+        (*this.as_ptr()).node(node.id)?.child_id(index)
+    }
+
+    /// Returns a mutable reference to the item at the given offset of a raw node.
+    ///
+    /// # Safety
+    ///
+    /// The raw node must have been returned by [`raw_node`](StorageMut::raw_node)
+    /// for the storage pointed to by `this`, and still be valid for the lifetime `'r`.
+    /// The offset must be less than the number of items in the node,
+    /// and the caller must not emit two references to the same item at the same time.
+    unsafe fn raw_item_mut<'r>(
+        this: NonNull<Self>,
+        node: &node::Raw,
+        offset: usize,
+    ) -> Self::ItemMut<'r>
+    where
+        Self: 'r,
+    {
+        (*this.as_ptr())
+            .item_mut(Address::new(node.id, offset.into()))
+            .unwrap()
+    }
+
     /// Returns a mutable reference to the value corresponding to the key.
     ///
     /// The key may be any borrowed form of the map's key type, but the ordering
@@ -896,26 +2251,387 @@ pub unsafe trait StorageMut: Storage {
     }
 
     /// Insert an item in the tree.
+    ///
+    /// The tree is searched once, from the root to the leaf receiving the item,
+    /// recording the path and which of its nodes are full.
+    /// If the item must be inserted, the full nodes of the path are then split from
+    /// the top down (see [`split_child`](StorageMut::split_child)),
+    /// so that every node can receive the median of its split child without overflowing,
+    /// and no rebalancing walk back up the tree is required.
+    /// Replacing an existing item leaves the structure of the tree untouched.
     #[inline]
-    fn insert<'a, T>(
-        &'a mut self,
-        item: T,
-    ) -> Option<<Self::ItemMut<'a> as Replace<Self, T>>::Output>
+    fn insert<T>(&mut self, item: T) -> Option<<Self as Replace<T>>::Output>
     where
-        Self: Insert<T> + KeyPartialOrd<T>,
-        Self::ItemMut<'a>: Replace<Self, T>,
+        Self: Insert<T> + KeyPartialOrd<T> + Replace<T>,
     {
-        match self.address_of(&item) {
-            Ok(addr) => Some(self.replace_at(addr, item)),
-            Err(addr) => {
+        let mut id = match self.root() {
+            Some(root_id) => root_id,
+            None => {
                 let allocated_item = self.allocate_item(item);
-                self.insert_exactly_at(addr, allocated_item, None);
-                None
+                self.insert_exactly_at(Address::nowhere(), allocated_item, None);
+                return None;
             }
-        }
-    }
+        };
 
-    fn insert_at<T>(&mut self, addr: Address, item: T) -> Address
+        // Nodes of the path, with the index of the next child (or the insertion offset in the leaf),
+        // and whether the node is full.
+        let mut path: SmallVec<[(usize, usize, bool); 16]> = SmallVec::new();
+        loop {
+            #[cfg(feature = "tracing")]
+            crate::trace::emit(self, id, |depth| crate::trace::Event::Descend { id, depth });
+
+            let (full, offset) = {
+                let node = self.node(id).unwrap();
+                (node.is_full(), node.offset_of(&item))
+            };
+
+            match offset {
+                Ok(offset) => return Some(self.replace_at(Address::new(id, offset), item)),
+                Err((index, child_id)) => {
+                    path.push((id, index, full));
+                    match child_id {
+                        Some(child_id) => id = child_id,
+                        None => break,
+                    }
+                }
+            }
+        }
+
+        // Split the full nodes, from the top down.
+        // Splitting a node does not change the identifier of its children,
+        // only the node holding the rest of the path, and the index of the next child in it.
+        let mut parent: Option<(usize, usize)> = None;
+        for (mut id, mut index, full) in path {
+            if full {
+                let right_id = match parent {
+                    Some((parent_id, parent_index)) => self.split_child(parent_id, parent_index),
+                    None => {
+                        let root_id = self.split_root(id);
+                        self.node(root_id).unwrap().child_id(1).unwrap()
+                    }
+                };
+
+                let left_len = self.node(id).unwrap().item_count();
+                if index > left_len {
+                    id = right_id;
+                    index -= left_len + 1
+                }
+            }
+
+            parent = Some((id, index))
+        }
+
+        let (leaf_id, offset) = parent.unwrap();
+        let allocated_item = self.allocate_item(item);
+        self.node_mut(leaf_id)
+            .unwrap()
+            .insert(offset.into(), allocated_item, None);
+        self.recompute_path(leaf_id);
+        self.incr_len();
+        None
+    }
+
+    /// Splits the root node `id` around its median item,
+    /// which is moved to a new root node.
+    ///
+    /// The root must be full (or overflowing).
+    ///
+    /// Returns the identifier of the new root.
+    fn split_root(&mut self, id: usize) -> usize {
+        let (median_offset, median, right_node) = self.node_mut(id).unwrap().split();
+        let right_id = self.insert_node(right_node);
+        self.link_leaf(Some(id), right_id);
+        self.recompute(id);
+        self.recompute(right_id);
+
+        #[cfg(feature = "tracing")]
+        crate::trace::emit(self, id, |depth| crate::trace::Event::Split {
+            id,
+            right: right_id,
+            depth,
+        });
+
+        let new_root = node::Buffer::binary(None, id, median, right_id);
+        let root_id = self.insert_node(new_root);
+        self.set_root(Some(root_id));
+        self.recompute(root_id);
+
+        if let Some(observer) = self.observer() {
+            observer.on_split(id, right_id, median_offset.into());
+            observer.on_move(
+                Address::new(id, median_offset.into()),
+                Address::new(root_id, 0.into()),
+            )
+        }
+
+        root_id
+    }
+
+    /// Splits the child number `index` of the node `id` around its median item,
+    /// which is inserted in the node `id` at offset `index`.
+    ///
+    /// The node `id` must not be full, and the child must be full (or overflowing).
+    /// Returns the identifier of the new right sibling of the child.
+    fn split_child(&mut self, id: usize, index: usize) -> usize {
+        let child_id = self.node(id).unwrap().child_id(index).unwrap();
+        let (median_offset, median, right_node) = self.node_mut(child_id).unwrap().split();
+        let right_id = self.insert_node(right_node);
+        self.link_leaf(Some(child_id), right_id);
+        self.recompute(child_id);
+        self.recompute(right_id);
+
+        self.node_mut(id)
+            .unwrap()
+            .insert(index.into(), median, Some(right_id));
+        self.recompute(id);
+
+        if let Some(observer) = self.observer() {
+            observer.on_split(child_id, right_id, median_offset.into());
+            observer.on_move(
+                Address::new(child_id, median_offset.into()),
+                Address::new(id, index.into()),
+            )
+        }
+
+        #[cfg(feature = "tracing")]
+        crate::trace::emit(self, child_id, |depth| crate::trace::Event::Split {
+            id: child_id,
+            right: right_id,
+            depth,
+        });
+
+        right_id
+    }
+
+    /// Insert an item in the tree, starting the search around the given hint.
+    ///
+    /// The position of the item is found with
+    /// [`address_of_with_hint`](Storage::address_of_with_hint):
+    /// if the item belongs right before, at, or right after the item at `hint`,
+    /// it is inserted without descending from the root.
+    /// Passing the address of the previously inserted item
+    /// hence inserts sorted items without comparing keys from the root.
+    /// If `hint` does not designate an item of the tree
+    /// (see [`contains_item_address`](Storage::contains_item_address)),
+    /// the position is found with [`address_of`](Storage::address_of).
+    ///
+    /// Returns the address of the item, along with the replaced item if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{map::Inserted, node::Address, slab::MapStorage, Storage, StorageMut};
+    ///
+    /// let mut btree: MapStorage<usize, usize> = MapStorage::default();
+    /// let mut hint = Address::nowhere();
+    /// for i in 0..100 {
+    ///     let (addr, replaced) = btree.insert_with_hint(hint, Inserted(i, i));
+    ///     assert!(replaced.is_none());
+    ///     hint = addr
+    /// }
+    ///
+    /// assert_eq!(btree.len(), 100);
+    /// btree.validate().unwrap();
+    /// ```
+    #[inline]
+    fn insert_with_hint<T>(
+        &mut self,
+        hint: Address,
+        item: T,
+    ) -> (Address, Option<<Self as Replace<T>>::Output>)
+    where
+        Self: Insert<T> + KeyPartialOrd<T> + Replace<T>,
+    {
+        let addr = if self.contains_item_address(hint) {
+            self.address_of_with_hint(hint, &item)
+        } else {
+            self.address_of(&item)
+        };
+
+        match addr {
+            Ok(addr) => (addr, Some(self.replace_at(addr, item))),
+            Err(addr) => {
+                let allocated_item = self.allocate_item(item);
+                (self.insert_exactly_at(addr, allocated_item, None), None)
+            }
+        }
+    }
+
+    /// Insert an item in the tree, starting the search from the given finger.
+    ///
+    /// Same as [`insert`](Self::insert), but the insertion position is found with
+    /// [`address_of_with_finger`](Storage::address_of_with_finger).
+    /// The finger is then updated with the address of the inserted item.
+    #[inline]
+    fn insert_with_finger<T>(
+        &mut self,
+        finger: &mut Finger,
+        item: T,
+    ) -> Option<<Self as Replace<T>>::Output>
+    where
+        Self: Insert<T> + KeyPartialOrd<T> + Replace<T>,
+    {
+        match self.address_of_with_finger(finger, &item) {
+            Ok(addr) => Some(self.replace_at(addr, item)),
+            Err(addr) => {
+                let allocated_item = self.allocate_item(item);
+                let addr = self.insert_exactly_at(addr, allocated_item, None);
+                finger.set_address(addr);
+                None
+            }
+        }
+    }
+
+    /// Inserts a batch of items sorted by key into the tree.
+    ///
+    /// The items are merged into the tree in a single left-to-right pass,
+    /// and existing items with the same key are replaced.
+    /// New items are grouped into runs of items falling between two consecutive
+    /// items of the tree.
+    /// A run at least as long as a node is built into a separate subtree bottom-up
+    /// (see [`build_from_sorted`](Self::build_from_sorted)),
+    /// and spliced in place of the gap by splitting the tree around it
+    /// (see [`split_off`](Self::split_off)) and joining back the three parts
+    /// (see [`join`](Self::join)), which moves whole nodes.
+    /// Shorter runs are inserted item by item, using the address of the previous
+    /// item as a hint (see [`insert_with_hint`](Self::insert_with_hint)).
+    /// The remaining items coming after the last item of the tree
+    /// are spliced to the right of the tree (see [`concat_with`](Self::concat_with)).
+    ///
+    /// The items must be sorted by key, without duplicates,
+    /// otherwise the resulting tree is not valid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{map::Inserted, slab::MapStorage, Storage, StorageMut};
+    ///
+    /// let mut btree: MapStorage<usize, usize> = MapStorage::default();
+    /// btree.insert_sorted_batch((0..100).step_by(2).map(|i| Inserted(i, i)));
+    /// btree.insert_sorted_batch((50..200).map(|i| Inserted(i, i + 1)));
+    ///
+    /// assert_eq!(btree.len(), 175);
+    /// assert_eq!(btree.get(&48).unwrap().value, 48);
+    /// assert_eq!(btree.get(&50).unwrap().value, 51);
+    /// btree.validate().unwrap();
+    /// ```
+    fn insert_sorted_batch<T, I>(&mut self, items: I)
+    where
+        Self: Default + Insert<T> + KeyPartialOrd<T> + Replace<T>,
+        I: IntoIterator<Item = T>,
+    {
+        let mut items = items.into_iter();
+
+        // Address of the last inserted item.
+        let mut hint = None;
+        let mut next = items.next();
+
+        while let Some(item) = next.take() {
+            let addr = match hint {
+                Some(hint) => self.address_of_with_hint(hint, &item),
+                None => self.address_of(&item),
+            };
+
+            let addr = match addr {
+                Ok(addr) => {
+                    self.replace_at(addr, item);
+                    hint = Some(addr);
+                    next = items.next();
+                    continue;
+                }
+                Err(addr) => addr,
+            };
+
+            // Items falling before the next item of the tree.
+            let mut run = Vec::new();
+            run.push(item);
+            match self.normalize(addr) {
+                Some(bound) => {
+                    let bound = self.item(bound).unwrap();
+                    for item in items.by_ref() {
+                        if Self::key_partial_cmp(&bound, &item) == Some(Ordering::Greater) {
+                            run.push(item)
+                        } else {
+                            next = Some(item);
+                            break;
+                        }
+                    }
+                }
+                None => {
+                    // Every remaining item comes after the last item of the tree.
+                    let separator = self.allocate_item(run.pop().unwrap());
+                    let mut other = self.empty();
+                    other.build_from_sorted(items);
+                    self.concat_with(separator, other, false);
+                    return;
+                }
+            }
+
+            let capacity = self.node(addr.id).unwrap().max_capacity();
+            if run.len() < capacity {
+                let mut addr = addr;
+                for (i, item) in run.into_iter().enumerate() {
+                    if i > 0 {
+                        // The items of the run are not in the tree.
+                        addr = match self.address_of_with_hint(hint.unwrap(), &item) {
+                            Ok(addr) | Err(addr) => addr,
+                        }
+                    }
+
+                    let allocated_item = self.allocate_item(item);
+                    hint = Some(self.insert_exactly_at(addr, allocated_item, None))
+                }
+            } else {
+                let len = self.len() + run.len();
+                let root_id = self.root().unwrap();
+                let height = self.node_height(root_id);
+                let (left, right) = self.split_node(root_id, height, &run[0], false);
+
+                let mut run = run.into_iter();
+                let first = self.allocate_item(run.next().unwrap());
+                let last = self.allocate_item(run.next_back().unwrap());
+                let mut middle = self.empty();
+                middle.build_from_sorted(run);
+                let middle = middle.root().map(|id| {
+                    let height = middle.node_height(id);
+                    (middle.transfer_node(id, self).0, height)
+                });
+
+                let left = self.join(left, first, Address::nowhere(), middle);
+                let (root_id, _) = self.join(Some(left), last, Address::nowhere(), right);
+                self.set_root(Some(root_id));
+                self.set_len(len);
+                self.relink_leaves();
+                hint = None
+            }
+        }
+    }
+
+    /// Insert an item in the tree, if the storage has enough capacity.
+    ///
+    /// Replacing an existing item never fails.
+    /// Otherwise, if the storage may not be able to allocate the nodes
+    /// required by the insertion, the item is returned in a [`CapacityError`].
+    #[inline]
+    fn insert_within_capacity<T>(
+        &mut self,
+        item: T,
+    ) -> Result<Option<<Self as Replace<T>>::Output>, CapacityError<T>>
+    where
+        Self: Insert<T> + KeyPartialOrd<T> + Replace<T>,
+    {
+        match self.address_of(&item) {
+            Ok(addr) => Ok(Some(self.replace_at(addr, item))),
+            Err(addr) if !self.can_insert_at(addr) => Err(CapacityError(item)),
+            Err(addr) => {
+                let allocated_item = self.try_allocate_item(item)?;
+                self.insert_exactly_at(addr, allocated_item, None);
+                Ok(None)
+            }
+        }
+    }
+
+    fn insert_at<T>(&mut self, addr: Address, item: T) -> Address
     where
         Self: Insert<T>,
     {
@@ -933,7 +2649,9 @@ pub unsafe trait StorageMut: Storage {
             if self.is_empty() {
                 let new_root = node::Buffer::leaf(None, item);
                 let id = self.insert_node(new_root);
+                self.link_leaf(None, id);
                 self.set_root(Some(id));
+                self.recompute(id);
                 self.incr_len();
                 Address {
                     id,
@@ -954,19 +2672,20 @@ pub unsafe trait StorageMut: Storage {
         }
     }
 
-    fn replace_at<'a, T>(
-        &'a mut self,
-        addr: Address,
-        item: T,
-    ) -> <Self::ItemMut<'a> as Replace<Self, T>>::Output
+    fn replace_at<T>(&mut self, addr: Address, item: T) -> <Self as Replace<T>>::Output
     where
-        Self::ItemMut<'a>: Replace<Self, T>,
+        Self: Replace<T>,
     {
-        self.node_mut(addr.id)
-            .unwrap()
-            .into_item_mut(addr.offset)
-            .unwrap()
-            .replace(item)
+        let old_item = Self::replace(
+            &mut self
+                .node_mut(addr.id)
+                .unwrap()
+                .into_item_mut(addr.offset)
+                .unwrap(),
+            item,
+        );
+        self.recompute_path(addr.id);
+        old_item
     }
 
     /// Removes and returns the item from the tree.
@@ -1015,6 +2734,40 @@ pub unsafe trait StorageMut: Storage {
             .map(|addr| self.remove_at(addr).unwrap().0)
     }
 
+    /// Removes and returns the first item from the tree
+    /// if it satisfies the given predicate.
+    ///
+    /// The item is only looked up once.
+    #[inline]
+    fn pop_first_if<F>(&mut self, pred: F) -> Option<Self::Item>
+    where
+        F: FnOnce(Self::ItemMut<'_>) -> bool,
+    {
+        let addr = self.first_item_address()?;
+        if pred(self.item_mut(addr).unwrap()) {
+            Some(self.remove_at(addr).unwrap().0)
+        } else {
+            None
+        }
+    }
+
+    /// Removes and returns the last item from the tree
+    /// if it satisfies the given predicate.
+    ///
+    /// The item is only looked up once.
+    #[inline]
+    fn pop_last_if<F>(&mut self, pred: F) -> Option<Self::Item>
+    where
+        F: FnOnce(Self::ItemMut<'_>) -> bool,
+    {
+        let addr = self.last_item_address()?;
+        if pred(self.item_mut(addr).unwrap()) {
+            Some(self.remove_at(addr).unwrap().0)
+        } else {
+            None
+        }
+    }
+
     /// Removes the item identified by the given key in the tree.
     ///
     /// # Example
@@ -1041,6 +2794,26 @@ pub unsafe trait StorageMut: Storage {
         }
     }
 
+    /// Removes the item with the given key, starting the search from the given finger.
+    ///
+    /// Same as [`remove`](Self::remove), but the item is found with
+    /// [`address_of_with_finger`](Storage::address_of_with_finger).
+    /// The finger is then updated with the address following the removed item.
+    #[inline]
+    fn remove_with_finger<Q: ?Sized>(&mut self, finger: &mut Finger, key: &Q) -> Option<Self::Item>
+    where
+        Self: KeyPartialOrd<Q>,
+    {
+        match self.address_of_with_finger(finger, key) {
+            Ok(addr) => {
+                let (item, next) = self.remove_at(addr).unwrap();
+                finger.set_address(next);
+                Some(item)
+            }
+            Err(_) => None,
+        }
+    }
+
     #[inline]
     fn remove_at(&mut self, addr: Address) -> Option<(Self::Item, Address)> {
         self.decr_len();
@@ -1059,6 +2832,11 @@ pub unsafe trait StorageMut: Storage {
                     .node_mut(addr.id)
                     .unwrap()
                     .replace(addr.offset, separator);
+                let leaf_offset = self.node(leaf_id).unwrap().item_count();
+                if let Some(observer) = self.observer() {
+                    observer.on_move(Address::new(leaf_id, leaf_offset.into()), addr)
+                }
+
                 let addr = self.rebalance(leaf_id, new_addr);
                 Some((item, addr))
             }
@@ -1078,6 +2856,210 @@ pub unsafe trait StorageMut: Storage {
         }
     }
 
+    /// Removes every item matching one of the given keys, with deferred rebalancing.
+    ///
+    /// Items are removed from their node without any rotation or merge,
+    /// leaving nodes temporarily underflowing (even empty).
+    /// An item removed from an internal node is replaced by its predecessor
+    /// (or successor) taken from a leaf, so that only leaves underflow.
+    /// Once every key has been removed, a single bottom-up pass restores the balance
+    /// of the modified nodes (see [`fix_underflows`](StorageMut::fix_underflows)),
+    /// and recomputes their augmentations.
+    /// This is faster than removing the keys one by one when many keys are removed.
+    ///
+    /// Returns the number of removed items.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{slab::Map, Storage, StorageMut};
+    ///
+    /// let mut map: Map<usize, usize> = (0..1000).map(|i| (i, i)).collect();
+    /// let keys: Vec<usize> = (0..1000).filter(|i| i % 3 != 0).collect();
+    ///
+    /// let removed = map.btree_mut().bulk_remove(&keys);
+    /// assert_eq!(removed, keys.len());
+    /// assert_eq!(map.len(), 334);
+    /// map.btree().validate().unwrap();
+    /// ```
+    fn bulk_remove<'q, Q: 'q + ?Sized, I>(&mut self, keys: I) -> usize
+    where
+        Self: KeyPartialOrd<Q>,
+        I: IntoIterator<Item = &'q Q>,
+    {
+        let mut modified = Vec::new();
+        let mut count = 0;
+
+        for key in keys {
+            let addr = match self.address_of(key) {
+                Ok(addr) => addr,
+                Err(_) => continue,
+            };
+
+            let removed = self.node_mut(addr.id).unwrap().leaf_remove(addr.offset);
+            match removed {
+                Some(Ok(_)) => modified.push(addr.id),
+                Some(Err(left_child_id)) => {
+                    // Replace the item with its predecessor or successor, unless the
+                    // neighbor leaves are already empty.
+                    let right_child_id = self
+                        .node(addr.id)
+                        .unwrap()
+                        .child_id(addr.offset.unwrap() + 1)
+                        .unwrap();
+                    match self.take_leaf_neighbor(left_child_id, right_child_id) {
+                        Some((neighbor, from)) => {
+                            self.node_mut(addr.id)
+                                .unwrap()
+                                .replace(addr.offset, neighbor);
+                            if let Some(observer) = self.observer() {
+                                observer.on_move(from, addr)
+                            }
+
+                            modified.push(from.id);
+                            modified.push(addr.id)
+                        }
+                        None => {
+                            // Restore the balance first, then remove the item normally.
+                            self.fix_underflows(core::mem::take(&mut modified));
+                            let addr = self.address_of(key).ok().unwrap();
+                            self.remove_at(addr);
+                            count += 1;
+                            continue;
+                        }
+                    }
+                }
+                None => unreachable!(),
+            }
+
+            self.decr_len();
+            count += 1
+        }
+
+        self.fix_underflows(modified);
+        count
+    }
+
+    /// Removes the last item of the rightmost leaf of the subtree `left_id`,
+    /// or else the first item of the leftmost leaf of the subtree `right_id`.
+    ///
+    /// Returns the removed item and its address before removal,
+    /// or `None` if both leaves are empty.
+    fn take_leaf_neighbor(
+        &mut self,
+        mut left_id: usize,
+        mut right_id: usize,
+    ) -> Option<(Self::Item, Address)> {
+        loop {
+            let node = self.node(left_id).unwrap();
+            match node.child_id(node.item_count()) {
+                Some(child_id) => left_id = child_id,
+                None => break,
+            }
+        }
+
+        let count = self.node(left_id).unwrap().item_count();
+        if count > 0 {
+            let offset = (count - 1).into();
+            let (item, _) = self.node_mut(left_id).unwrap().remove(offset);
+            return Some((item, Address::new(left_id, offset)));
+        }
+
+        while let Some(child_id) = self.node(right_id).unwrap().child_id(0) {
+            right_id = child_id
+        }
+
+        if self.node(right_id).unwrap().item_count() > 0 {
+            let (item, _) = self.node_mut(right_id).unwrap().remove(0.into());
+            return Some((item, Address::new(right_id, 0.into())));
+        }
+
+        None
+    }
+
+    /// Restores the balance of the given nodes, and of their ancestors.
+    ///
+    /// The nodes may be underflowing by any number of items, or be empty:
+    /// each node still in the tree is repeatedly rotated with, or merged into, its siblings
+    /// until it stops underflowing, then its parent is checked.
+    /// The augmentations of the modified nodes and of their ancestors are then recomputed.
+    fn fix_underflows(&mut self, mut worklist: Vec<usize>) {
+        let mut modified = Vec::new();
+        let mut addr = Address::nowhere();
+
+        while let Some(mut id) = worklist.pop() {
+            if !self.contains_address(Address::new(id, 0.into())) {
+                continue; // the node has been merged into a sibling.
+            }
+
+            loop {
+                #[cfg(feature = "tracing")]
+                crate::trace::emit(self, id, |depth| crate::trace::Event::Rebalance {
+                    id,
+                    depth,
+                });
+
+                let underflowing = self.is_node_underflowing(id);
+                let (parent, item_count, first_child_id) = {
+                    let node = self.node(id).unwrap();
+                    (node.parent(), node.item_count(), node.child_id(0))
+                };
+
+                if !underflowing {
+                    break;
+                }
+
+                match parent {
+                    Some(parent_id) if self.node(parent_id).unwrap().item_count() == 0 => {
+                        // The node has no sibling: the parent must be fixed first.
+                        worklist.push(id);
+                        worklist.push(parent_id);
+                        break;
+                    }
+                    Some(parent_id) => {
+                        let index = self.node(parent_id).unwrap().child_index(id).unwrap();
+                        if !self.try_rotate_left(parent_id, index, &mut addr)
+                            && !self.try_rotate_right(parent_id, index, &mut addr)
+                        {
+                            // The node is merged into its left sibling, if any.
+                            self.merge(parent_id, index, addr);
+                            let merged_index = index.saturating_sub(1);
+                            id = self
+                                .node(parent_id)
+                                .unwrap()
+                                .child_id(merged_index)
+                                .unwrap();
+                            worklist.push(parent_id)
+                        }
+                    }
+                    None if item_count == 0 => {
+                        // Empty root.
+                        self.set_root(first_child_id);
+                        self.release_node(id);
+                        match first_child_id {
+                            Some(child_id) => {
+                                self.node_mut(child_id).unwrap().set_parent(None);
+                                id = child_id
+                            }
+                            None => break,
+                        }
+                    }
+                    None => break,
+                }
+            }
+
+            modified.push(id)
+        }
+
+        if self.is_augmented() {
+            for id in modified {
+                if self.contains_address(Address::new(id, 0.into())) {
+                    self.recompute_path(id)
+                }
+            }
+        }
+    }
+
     /// Removes and returns the item matching the given key in the tree, if any.
     #[inline]
     fn take<Q: ?Sized>(&mut self, key: &Q) -> Option<Self::Item>
@@ -1106,6 +3088,11 @@ pub unsafe trait StorageMut: Storage {
     /// `new_item` is the item to be associated to `key`
     /// (if it is `None` any previous binding is removed) and
     /// `result` is the value returned by the entire `update` function call.
+    ///
+    /// If `action` panics after receiving an `UpdateEntry::Occupied` item,
+    /// or if allocating the new item panics,
+    /// the item is removed from the tree without being dropped,
+    /// since it has been moved out to `action`.
     #[inline]
     fn update<T, F, Q, I>(&mut self, key: Q, action: F) -> T
     where
@@ -1122,7 +3109,9 @@ pub unsafe trait StorageMut: Storage {
                     let item = self.allocate_item(t);
                     let new_root = node::Buffer::leaf(None, item);
                     let root_id = self.insert_node(new_root);
+                    self.link_leaf(None, root_id);
                     self.set_root(Some(root_id));
+                    self.recompute(root_id);
                     self.incr_len()
                 }
 
@@ -1138,29 +3127,18 @@ pub unsafe trait StorageMut: Storage {
         for<'r> Self::ItemMut<'r>: Read<Self> + Write<Self>,
     {
         loop {
+            #[cfg(feature = "tracing")]
+            crate::trace::emit(self, id, |depth| crate::trace::Event::Descend { id, depth });
+
             let offset = self.node(id).unwrap().offset_of(&key);
             match offset {
                 Ok(offset) => {
-                    let result = {
-                        let entry = {
-                            let item = self.node_mut(id).unwrap().into_item_mut(offset).unwrap();
-                            unsafe { item.read() }
-                        };
-                        let (opt_new_item, result) = action(UpdateEntry::Occupied(entry));
-                        if let Some(t) = opt_new_item {
-                            let new_item = self.allocate_item(t);
-                            let mut item =
-                                self.node_mut(id).unwrap().into_item_mut(offset).unwrap();
-                            unsafe { item.write(new_item) };
-                            return result;
-                        }
-
-                        result
-                    };
-
-                    let (item, _) = self.remove_at(Address::new(id, offset)).unwrap();
-                    // item has been moved, it must not be dropped again.
-                    std::mem::forget(item);
+                    let (hole, item) = Hole::new(self, Address::new(id, offset));
+                    let (opt_new_item, result) = action(UpdateEntry::Occupied(item));
+                    match opt_new_item {
+                        Some(t) => hole.fill(t),
+                        None => hole.remove(),
+                    }
 
                     return result;
                 }
@@ -1181,31 +3159,30 @@ pub unsafe trait StorageMut: Storage {
         }
     }
 
-    fn update_at<T, F>(&mut self, addr: Address, action: F) -> T
+    /// General-purpose update function, for the item at the given address.
+    ///
+    /// The `action` function is called once with the item at `addr`,
+    /// and must return a pair (`new_item`, `result`) where
+    /// `new_item` is the item replacing it
+    /// (if it is `None` the item is removed) and
+    /// `result` is the value returned by the entire `update_at` function call.
+    ///
+    /// As with [`update`](Self::update), the new item is allocated with the
+    /// [`Insert`] implementation of the storage,
+    /// and the item is removed from the tree without being dropped
+    /// if `action` or the allocation panics.
+    fn update_at<T, F, I>(&mut self, addr: Address, action: F) -> T
     where
-        F: FnOnce(Self::Item) -> (Option<Self::Item>, T),
+        Self: Insert<I>,
+        F: FnOnce(Self::Item) -> (Option<I>, T),
         for<'r> Self::ItemMut<'r>: Read<Self> + Write<Self>,
     {
-        let result = {
-            let mut item_mut = self
-                .node_mut(addr.id)
-                .unwrap()
-                .into_item_mut(addr.offset)
-                .unwrap();
-            let item = unsafe { item_mut.read() };
-            let (opt_new_item, result) = action(item);
-
-            if let Some(new_item) = opt_new_item {
-                unsafe { item_mut.write(new_item) };
-                return result;
-            }
-
-            result
-        };
-
-        let (item, _) = self.remove_at(addr).unwrap();
-        // item has been moved, it must not be dropped again.
-        std::mem::forget(item);
+        let (hole, item) = Hole::new(self, addr);
+        let (opt_new_item, result) = action(item);
+        match opt_new_item {
+            Some(t) => hole.fill(t),
+            None => hole.remove(),
+        }
 
         result
     }
@@ -1222,9 +3199,13 @@ pub unsafe trait StorageMut: Storage {
     /// If the iterator is only partially consumed or not consumed at all, each of the remaining
     /// items will still be subjected to the closure and removed and dropped if it returns true.
     ///
+    /// If the closure panics, the item it was called on and every unvisited item
+    /// remain in the map, and the closure is never called again,
+    /// even when the iterator is dropped during unwinding.
+    /// Likewise, if a panic occurs while dropping an item
+    /// removed when the iterator is dropped, the unvisited items remain in the map.
     /// It is unspecified how many more items will be subjected to the closure
-    /// if a panic occurs in the closure, or a panic occurs while dropping an item,
-    /// or if the `DrainFilter` value is leaked.
+    /// if the `DrainFilter` value is leaked.
     #[inline]
     fn drain_filter<F>(&mut self, pred: F) -> DrainFilter<Self, F>
     where
@@ -1233,6 +3214,49 @@ pub unsafe trait StorageMut: Storage {
         DrainFilter::new(self, pred)
     }
 
+    /// Creates an iterator which uses a closure to determine if an item of the given range
+    /// should be removed.
+    ///
+    /// This behaves like [`drain_filter`](Self::drain_filter),
+    /// but only the items of the range are subjected to the closure.
+    /// The items outside of the range are never visited.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    #[inline]
+    fn extract_if<T, R, F>(&mut self, range: R, pred: F) -> DrainFilter<'_, Self, F>
+    where
+        T: ?Sized + Ord,
+        R: RangeBounds<T>,
+        Self: KeyPartialOrd<T>,
+        F: FnMut(Self::ItemMut<'_>) -> bool,
+    {
+        DrainFilter::with_range(self, range, pred)
+    }
+
+    /// Removes the items of the given range, and returns them in an iterator.
+    ///
+    /// The tree is rebalanced after each removal,
+    /// and the items outside of the range are left untouched.
+    /// If the iterator is only partially consumed or not consumed at all,
+    /// the remaining items of the range are removed when it is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    #[inline]
+    fn drain<T, R>(&mut self, range: R) -> Drain<'_, Self>
+    where
+        T: ?Sized + Ord,
+        R: RangeBounds<T>,
+        Self: KeyPartialOrd<T>,
+    {
+        Drain::new(self, range)
+    }
+
     /// Retains only the items specified by the predicate.
     #[inline]
     fn retain<F>(&mut self, mut f: F)
@@ -1244,14 +3268,22 @@ pub unsafe trait StorageMut: Storage {
 
     /// Rebalance the node with the given id.
     ///
+    /// The augmentations of every modified node and of their ancestors are recomputed.
+    ///
     /// # Panics
     ///
     /// This function panics if no node has the given `id`.
     #[inline]
     fn rebalance(&mut self, mut id: usize, mut addr: Address) -> Address {
-        let mut balance = self.node(id).unwrap().balance();
+        let mut balance = self.node_balance(id);
 
         loop {
+            #[cfg(feature = "tracing")]
+            crate::trace::emit(self, id, |depth| crate::trace::Event::Rebalance {
+                id,
+                depth,
+            });
+
             match balance {
                 Balance::Balanced => break,
                 Balance::Overflow => {
@@ -1259,13 +3291,35 @@ pub unsafe trait StorageMut: Storage {
 
                     let (median_offset, median, right_node) = self.node_mut(id).unwrap().split();
                     let right_id = self.insert_node(right_node);
+                    self.link_leaf(Some(id), right_id);
+                    self.recompute(id);
+                    self.recompute(right_id);
+
+                    #[cfg(feature = "tracing")]
+                    crate::trace::emit(self, id, |depth| crate::trace::Event::Split {
+                        id,
+                        right: right_id,
+                        depth,
+                    });
 
                     let parent = self.node(id).unwrap().parent();
                     match parent {
                         Some(parent_id) => {
-                            let mut parent = self.node_mut(parent_id).unwrap();
-                            let offset = parent.child_index(id).unwrap().into();
-                            parent.insert(offset, median, Some(right_id));
+                            let offset = {
+                                let mut parent = self.node_mut(parent_id).unwrap();
+                                let offset = parent.child_index(id).unwrap().into();
+                                parent.insert(offset, median, Some(right_id));
+                                offset
+                            };
+                            let parent_balance = self.node_balance(parent_id);
+
+                            if let Some(observer) = self.observer() {
+                                observer.on_split(id, right_id, median_offset.into());
+                                observer.on_move(
+                                    Address::new(id, median_offset.into()),
+                                    Address::new(parent_id, offset),
+                                )
+                            }
 
                             // new address.
                             if addr.id == id {
@@ -1290,7 +3344,7 @@ pub unsafe trait StorageMut: Storage {
                             }
 
                             id = parent_id;
-                            balance = parent.balance()
+                            balance = parent_balance
                         }
                         None => {
                             let left_id = id;
@@ -1301,6 +3355,14 @@ pub unsafe trait StorageMut: Storage {
                             self.node_mut(left_id).unwrap().set_parent(Some(root_id));
                             self.node_mut(right_id).unwrap().set_parent(Some(root_id));
 
+                            if let Some(observer) = self.observer() {
+                                observer.on_split(left_id, right_id, median_offset.into());
+                                observer.on_move(
+                                    Address::new(left_id, median_offset.into()),
+                                    Address::new(root_id, 0.into()),
+                                )
+                            }
+
                             // new address.
                             if addr.id == id {
                                 match addr.offset.partial_cmp(&median_offset) {
@@ -1321,6 +3383,7 @@ pub unsafe trait StorageMut: Storage {
                                 }
                             }
 
+                            id = root_id;
                             break;
                         }
                     };
@@ -1335,6 +3398,7 @@ pub unsafe trait StorageMut: Storage {
                             if self.try_rotate_left(parent_id, index, &mut addr)
                                 || self.try_rotate_right(parent_id, index, &mut addr)
                             {
+                                id = parent_id;
                                 break;
                             } else {
                                 // Rotation didn't work.
@@ -1368,6 +3432,11 @@ pub unsafe trait StorageMut: Storage {
                                 }
 
                                 self.release_node(id);
+
+                                match self.root() {
+                                    Some(root_id) => id = root_id,
+                                    None => return addr,
+                                }
                             }
 
                             break;
@@ -1377,6 +3446,7 @@ pub unsafe trait StorageMut: Storage {
             }
         }
 
+        self.recompute_path(id);
         addr
     }
 
@@ -1406,7 +3476,11 @@ pub unsafe trait StorageMut: Storage {
             )
         };
 
-        let left = self.node_mut(right_sibling_id).unwrap().pop_left();
+        let min_capacity = self.node_min_capacity(right_sibling_id);
+        let left = self
+            .node_mut(right_sibling_id)
+            .unwrap()
+            .pop_left_with(min_capacity);
         match left {
             Ok((opt_child_id, mut value)) => {
                 self.node_mut(id)
@@ -1426,6 +3500,30 @@ pub unsafe trait StorageMut: Storage {
                         .set_parent(Some(deficient_child_id))
                 }
 
+                self.recompute(deficient_child_id);
+                self.recompute(right_sibling_id);
+
+                if let Some(observer) = self.observer() {
+                    observer.on_rotate(right_sibling_id, deficient_child_id);
+                    observer.on_move(
+                        Address::new(id, pivot_offset),
+                        Address::new(deficient_child_id, left_offset),
+                    );
+                    observer.on_move(
+                        Address::new(right_sibling_id, 0.into()),
+                        Address::new(id, pivot_offset),
+                    )
+                }
+
+                #[cfg(feature = "tracing")]
+                crate::trace::emit(self, deficient_child_id, |depth| {
+                    crate::trace::Event::Rotate {
+                        from: right_sibling_id,
+                        to: deficient_child_id,
+                        depth,
+                    }
+                });
+
                 // update address.
                 if addr.id == right_sibling_id {
                     // addressed item is in the right node.
@@ -1473,7 +3571,11 @@ pub unsafe trait StorageMut: Storage {
                     node.child_id(deficient_child_index).unwrap(),
                 )
             };
-            let right = self.node_mut(left_sibling_id).unwrap().pop_right();
+            let min_capacity = self.node_min_capacity(left_sibling_id);
+            let right = self
+                .node_mut(left_sibling_id)
+                .unwrap()
+                .pop_right_with(min_capacity);
             match right {
                 Ok((left_offset, mut value, opt_child_id)) => {
                     self.node_mut(id)
@@ -1492,6 +3594,30 @@ pub unsafe trait StorageMut: Storage {
                             .set_parent(Some(deficient_child_id))
                     }
 
+                    self.recompute(deficient_child_id);
+                    self.recompute(left_sibling_id);
+
+                    if let Some(observer) = self.observer() {
+                        observer.on_rotate(left_sibling_id, deficient_child_id);
+                        observer.on_move(
+                            Address::new(id, pivot_offset),
+                            Address::new(deficient_child_id, 0.into()),
+                        );
+                        observer.on_move(
+                            Address::new(left_sibling_id, left_offset),
+                            Address::new(id, pivot_offset),
+                        )
+                    }
+
+                    #[cfg(feature = "tracing")]
+                    crate::trace::emit(self, deficient_child_id, |depth| {
+                        crate::trace::Event::Rotate {
+                            from: left_sibling_id,
+                            to: deficient_child_id,
+                            depth,
+                        }
+                    });
+
                     // update address.
                     if addr.id == deficient_child_id {
                         // addressed item is in the right (deficient) node.
@@ -1529,7 +3655,7 @@ pub unsafe trait StorageMut: Storage {
         deficient_child_index: usize,
         mut addr: Address,
     ) -> (Balance, Address) {
-        let offset: Offset = if deficient_child_index > 0 {
+        let offset: Position = if deficient_child_index > 0 {
             // merge with left sibling
             (deficient_child_index - 1).into()
         } else {
@@ -1537,13 +3663,13 @@ pub unsafe trait StorageMut: Storage {
             deficient_child_index.into()
         };
 
-        let (left_id, separator, right_id, balance) = {
+        let (left_id, separator, right_id) = {
             let mut node = self.node_mut(id).unwrap();
             let left_id = node.child_id(offset.unwrap()).unwrap();
             let (item, right_id) = node.remove(offset);
-            let balance = node.balance();
-            (left_id, item, right_id.unwrap(), balance)
+            (left_id, item, right_id.unwrap())
         };
+        let balance = self.node_balance(id);
 
         // update children's parent.
         let right_node = self.release_node(right_id);
@@ -1558,6 +3684,19 @@ pub unsafe trait StorageMut: Storage {
             .node_mut(left_id)
             .unwrap()
             .append(separator, right_node);
+        self.recompute(left_id);
+
+        if let Some(observer) = self.observer() {
+            observer.on_merge(left_id, right_id, left_offset);
+            observer.on_move(Address::new(id, offset), Address::new(left_id, left_offset))
+        }
+
+        #[cfg(feature = "tracing")]
+        crate::trace::emit(self, left_id, |depth| crate::trace::Event::Merge {
+            left: left_id,
+            right: right_id,
+            depth,
+        });
 
         // update addr.
         if addr.id == id {
@@ -1571,7 +3710,7 @@ pub unsafe trait StorageMut: Storage {
             }
         } else if addr.id == right_id {
             addr.id = left_id;
-            addr.offset = (addr.offset.unwrap() + left_offset.unwrap() + 1).into();
+            addr.offset = left_offset + 1 + addr.offset;
         }
 
         (balance, addr)
@@ -1587,10 +3726,15 @@ pub unsafe trait StorageMut: Storage {
         self.set_len(0)
     }
 
+    /// Releases and drops every node of the subtree rooted at the node `id`.
+    ///
+    /// The subtree is traversed using an explicit worklist,
+    /// so that the stack usage does not depend on the depth of the subtree.
     fn clear_node(&mut self, id: usize) {
-        let node = self.release_node(id);
-        for child_id in node.children() {
-            self.clear_node(child_id)
+        let mut worklist = alloc::vec![id];
+        while let Some(id) = worklist.pop() {
+            let node = self.release_node(id);
+            worklist.extend(node.children())
         }
     }
 
@@ -1604,35 +3748,836 @@ pub unsafe trait StorageMut: Storage {
         self.set_len(0)
     }
 
+    /// Releases every node of the subtree rooted at the node `id`
+    /// without dropping the items.
+    ///
+    /// Like [`clear_node`](StorageMut::clear_node), this does not recurse.
     fn forget_node(&mut self, id: usize) {
-        let node = self.release_node(id);
-        for child_id in node.children() {
-            self.forget_node(child_id)
+        let mut worklist = alloc::vec![id];
+        while let Some(id) = worklist.pop() {
+            let node = self.release_node(id);
+            worklist.extend(node.children());
+            node.forget()
         }
-        node.forget()
     }
 
     /// Moves all elements from `other` into `Self`, leaving `other` empty.
+    ///
+    /// If the key ranges of both trees do not overlap,
+    /// the trees are joined using [`try_concat`](StorageMut::try_concat).
+    /// Otherwise, every item of `other` is inserted in this tree.
     #[inline]
     fn append(&mut self, other: &mut Self)
     where
         for<'r> Self::ItemRef<'r>: Read<Self>,
-        Self: Default + Insert<<Self as StorageMut>::Item> + KeyPartialOrd<Self::Item>,
+        Self: Default + KeyOrd + Insert<<Self as StorageMut>::Item> + KeyPartialOrd<Self::Item>,
+    {
+        if !self.try_concat(other) {
+            let empty = other.empty();
+            let other = core::mem::replace(other, empty);
+            for item in other.into_iter() {
+                self.insert(item);
+            }
+        }
+    }
+
+    /// Moves all elements from `other` into `Self`, leaving `other` empty,
+    /// if the key ranges of both trees do not overlap.
+    ///
+    /// Both trees are joined by gluing the smallest one into the other
+    /// at the right height, without comparing or rebalancing more than
+    /// a logarithmic number of items and nodes
+    /// (the nodes of `other` are still moved to this storage).
+    ///
+    /// Returns `false`, leaving both trees untouched,
+    /// if the key ranges overlap,
+    /// or if the nodes of both trees do not have the same capacity
+    /// (for instance storages with different runtime orders).
+    fn try_concat(&mut self, other: &mut Self) -> bool
+    where
+        Self: Default + KeyOrd,
     {
         // Do we have to append anything at all?
         if other.is_empty() {
-            return;
+            return true;
         }
 
         // We can just swap `self` and `other` if `self` is empty.
         if self.is_empty() {
-            std::mem::swap(self, other);
-            return;
+            core::mem::swap(self, other);
+            return true;
+        }
+
+        // The nodes of `other` are moved as they are.
+        if !self.has_same_capacities(other) {
+            return false;
+        }
+
+        let after = Self::key_cmp(&self.last_item().unwrap(), &other.first_item().unwrap())
+            == Ordering::Less;
+        let before = !after
+            && Self::key_cmp(&self.first_item().unwrap(), &other.last_item().unwrap())
+                == Ordering::Greater;
+
+        if after || before {
+            let empty = other.empty();
+            let mut other = core::mem::replace(other, empty);
+            let separator = if after {
+                other.pop_first()
+            } else {
+                other.pop_last()
+            };
+
+            self.concat_with(separator.unwrap(), other, before);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Checks that the leaves, and the roots, of both non-empty trees
+    /// have the same maximum capacity,
+    /// so that the nodes of one tree can be moved into the other.
+    fn has_same_capacities(&self, other: &Self) -> bool {
+        let capacity = |btree: &Self, id: usize| btree.node(id).unwrap().max_capacity();
+        let (leaf, other_leaf) = (
+            self.first_item_address().unwrap().id,
+            other.first_item_address().unwrap().id,
+        );
+        let (root, other_root) = (self.root().unwrap(), other.root().unwrap());
+
+        capacity(self, leaf) == capacity(other, other_leaf)
+            && (root == leaf
+                || other_root == other_leaf
+                || capacity(self, root) == capacity(other, other_root))
+    }
+
+    /// Moves every item of `other` into this tree, along with the given `separator`.
+    ///
+    /// Every item of `other` must be greater than `separator`,
+    /// itself greater than every item of this tree,
+    /// or, if `before` is `true`, lower than `separator`,
+    /// itself lower than every item of this tree.
+    ///
+    /// The nodes of `other` are moved into this storage,
+    /// then both trees are joined by gluing the smallest one into the other
+    /// at the right height (see [`join`](StorageMut::join)).
+    fn concat_with(&mut self, separator: Self::Item, mut other: Self, before: bool) {
+        let len = self.len() + other.len() + 1;
+
+        let this = self.root().map(|id| (id, self.node_height(id)));
+        let other_tree = other.root().map(|id| {
+            let height = other.node_height(id);
+            (other.transfer_node(id, self).0, height)
+        });
+        other.set_root(None);
+        other.set_len(0);
+
+        let (root_id, _) = if before {
+            self.join(other_tree, separator, Address::nowhere(), this)
+        } else {
+            self.join(this, separator, Address::nowhere(), other_tree)
+        };
+
+        self.set_root(Some(root_id));
+        self.set_len(len);
+        self.relink_leaves()
+    }
+
+    /// Creates a new empty storage, configured like this one.
+    ///
+    /// This is used to create the storage returned by
+    /// [`split_off`](StorageMut::split_off).
+    /// Storages with a runtime configuration (such as the order of the nodes)
+    /// should override this function to preserve it.
+    /// The default implementation returns [`Default::default`].
+    #[inline]
+    fn empty(&self) -> Self
+    where
+        Self: Default,
+    {
+        Self::default()
+    }
+
+    /// Reserves capacity for at least `additional` more items to be inserted in the tree.
+    ///
+    /// Storages that grow as needed can use this to pre-allocate the nodes
+    /// required to hold the given number of items,
+    /// avoiding repeated reallocations during large insertions.
+    /// Does nothing by default.
+    #[inline]
+    fn reserve(&mut self, _additional: usize) {}
+
+    /// Shrinks the capacity of the underlying storage as much as possible.
+    ///
+    /// Nodes are not moved, so the storage may still hold vacant slots
+    /// between live nodes (see [`compact`](StorageMut::compact)).
+    /// Does nothing by default.
+    #[inline]
+    fn shrink_to_fit(&mut self) {}
+
+    /// Re-packs the nodes of the tree into the lowest node identifiers,
+    /// and shrinks the underlying storage.
+    ///
+    /// After many removals, the storage may retain its high-water-mark capacity,
+    /// with live nodes scattered among vacant slots.
+    /// This moves every node into a new storage (see [`empty`](StorageMut::empty)),
+    /// in depth-first order, fixing the parent and children references,
+    /// then calls [`shrink_to_fit`](StorageMut::shrink_to_fit).
+    ///
+    /// Node identifiers change, hence every previously computed [`Address`]
+    /// becomes invalid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{slab::Map, Storage, StorageMut};
+    ///
+    /// let mut map: Map<usize, usize> = (0..1000).map(|i| (i, i)).collect();
+    /// map.retain(|key, _| key % 100 == 0);
+    ///
+    /// let mut btree = map.into_btree();
+    /// btree.compact();
+    ///
+    /// btree.validate().unwrap();
+    /// assert!(btree.addresses().all(|(addr, _)| addr.id < btree.len()));
+    /// ```
+    fn compact(&mut self)
+    where
+        Self: Default,
+    {
+        let mut target = self.empty();
+        if let Some(root_id) = self.root() {
+            let (new_root_id, len) = self.transfer_node(root_id, &mut target);
+            target.set_root(Some(new_root_id));
+            target.set_len(len);
+            target.relink_leaves();
+            self.set_root(None);
+            self.set_len(0);
+        }
+
+        *self = target;
+        self.shrink_to_fit()
+    }
+
+    /// Splits the tree in two at the given key.
+    /// Returns everything after the given key, including the key.
+    ///
+    /// The tree is split along the path from the root to the key,
+    /// by joining together the subtrees found on each side of the path.
+    /// This only requires a logarithmic number of comparisons and rebalancing
+    /// operations.
+    /// The nodes of the returned tree are then moved to the new storage.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut a = Map::new();
+    /// a.insert(1, "a");
+    /// a.insert(2, "b");
+    /// a.insert(3, "c");
+    ///
+    /// let b = a.split_off(&2);
+    /// assert_eq!(a.len(), 1);
+    /// assert_eq!(b.len(), 2);
+    /// ```
+    fn split_off<Q: ?Sized>(&mut self, key: &Q) -> Self
+    where
+        Self: Default + KeyPartialOrd<Q>,
+    {
+        self.split_off_at(key, false)
+    }
+
+    /// Removes the items of the given range, and returns them in a new tree.
+    ///
+    /// The tree is split at both ends of the range (see [`split_off`](StorageMut::split_off)),
+    /// then the items before and after the range are joined back together.
+    /// Whole subtrees are detached and moved instead of removing the items one by one,
+    /// which only requires a logarithmic number of comparisons and rebalancing operations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    fn split_off_range<T, R>(&mut self, range: R) -> Self
+    where
+        T: ?Sized + Ord,
+        R: RangeBounds<T>,
+        Self: Default + KeyPartialOrd<T>,
+    {
+        if !iter::is_valid_range(&range) {
+            panic!("Invalid range")
+        }
+
+        let mut tail = match range.end_bound() {
+            Bound::Included(end) => self.split_off_at(end, true),
+            Bound::Excluded(end) => self.split_off_at(end, false),
+            Bound::Unbounded => self.empty(),
+        };
+
+        let middle = match range.start_bound() {
+            Bound::Included(start) => self.split_off_at(start, false),
+            Bound::Excluded(start) => self.split_off_at(start, true),
+            Bound::Unbounded => {
+                let empty = self.empty();
+                core::mem::replace(self, empty)
+            }
+        };
+
+        if self.is_empty() {
+            core::mem::swap(self, &mut tail)
+        } else if let Some(separator) = tail.pop_first() {
+            self.concat_with(separator, tail, false)
+        }
+
+        middle
+    }
+
+    /// Splits the tree in two at the given key,
+    /// and returns everything after the given key.
+    ///
+    /// The item matching the key, if any, is returned with the rest of the items
+    /// if `after` is `false`, and kept in this tree otherwise.
+    /// See [`split_off`](StorageMut::split_off) for details.
+    fn split_off_at<Q: ?Sized>(&mut self, key: &Q, after: bool) -> Self
+    where
+        Self: Default + KeyPartialOrd<Q>,
+    {
+        let mut other = self.empty();
+
+        if let Some(root_id) = self.root() {
+            let height = self.node_height(root_id);
+            let (left, right) = self.split_node(root_id, height, key, after);
+
+            match (left, right) {
+                (None, right) => {
+                    // Every item is moved.
+                    self.set_root(right.map(|(id, _)| id));
+                    core::mem::swap(self, &mut other)
+                }
+                (Some((left_id, _)), right) => {
+                    self.set_root(Some(left_id));
+
+                    if let Some((right_id, _)) = right {
+                        let (id, len) = self.transfer_node(right_id, &mut other);
+                        other.set_root(Some(id));
+                        other.set_len(len);
+                        self.set_len(self.len() - len)
+                    }
+                }
+            }
+
+            self.relink_leaves();
+            other.relink_leaves()
+        }
+
+        other
+    }
+
+    /// Splits the subtree rooted at the node `id` of the given `height`
+    /// into the subtree of items lower than `key`, and the subtree of the other items.
+    /// If `after` is `true`, the item matching `key` (if any) goes to the first subtree.
+    ///
+    /// Returns both subtrees, if not empty.
+    /// The roots of both subtrees are detached from any parent.
+    fn split_node<Q: ?Sized>(
+        &mut self,
+        id: usize,
+        height: usize,
+        key: &Q,
+        after: bool,
+    ) -> (Option<Subtree>, Option<Subtree>)
+    where
+        Self: KeyPartialOrd<Q>,
+    {
+        use node::buffer::{Internal as _, Leaf as _};
+
+        self.node_mut(id).unwrap().set_parent(None);
+        let (count, offset, found) = {
+            let node = self.node(id).unwrap();
+            match node.offset_of(key) {
+                Ok(offset) if after => (node.item_count(), offset.unwrap() + 1, false),
+                Ok(offset) => (node.item_count(), offset.unwrap(), true),
+                Err((index, _)) => (node.item_count(), index, false),
+            }
+        };
+
+        if height == 1 {
+            if offset == 0 {
+                return (None, Some((id, height)));
+            }
+
+            if offset == count {
+                return (Some((id, height)), None);
+            }
+
+            let mut right = Self::LeafNode::default();
+            {
+                let mut node = self.node_mut(id).unwrap();
+                for _ in offset..count {
+                    let (item, _) = node.remove(offset.into());
+                    right.push_right(item)
+                }
+            }
+
+            let right_id = self.allocate_node(node::Buffer::Leaf(right));
+            self.recompute(id);
+            self.recompute(right_id);
+            self.report_moves(id, offset, right_id, count - offset);
+            return (Some((id, height)), Some((right_id, height)));
+        }
+
+        // Items greater than the split item, with their children.
+        let right = if offset < count {
+            let (item, first_child_id) = self.node_mut(id).unwrap().remove(offset.into());
+            let first_child_id = first_child_id.unwrap();
+            if offset + 1 < count {
+                let mut right = Self::InternalNode::default();
+                right.set_first_child_id(first_child_id);
+                {
+                    let mut node = self.node_mut(id).unwrap();
+                    for _ in (offset + 1)..count {
+                        let (item, child_id) = node.remove(offset.into());
+                        right.push_right(item, child_id.unwrap())
+                    }
+                }
+
+                let right_id = self.insert_node(node::Buffer::Internal(right));
+                self.recompute(right_id);
+                self.report_moves(id, offset + 1, right_id, count - offset - 1);
+                Some((item, Address::new(id, offset.into()), (right_id, height)))
+            } else {
+                self.node_mut(first_child_id).unwrap().set_parent(None);
+                Some((
+                    item,
+                    Address::new(id, offset.into()),
+                    (first_child_id, height - 1),
+                ))
+            }
+        } else {
+            None
+        };
+
+        // Child containing the key (if not found in this node),
+        // with the item preceding it.
+        let middle = if found {
+            None
+        } else {
+            let mut node = self.node_mut(id).unwrap();
+            if offset > 0 {
+                let separator_offset = (offset - 1).into();
+                let (item, child_id) = node.remove(separator_offset);
+                Some((
+                    Some((item, Address::new(id, separator_offset))),
+                    child_id.unwrap(),
+                ))
+            } else {
+                Some((None, node.child_id(0).unwrap()))
+            }
+        };
+
+        // Remaining items lower than the key.
+        let (item_count, first_child_id) = {
+            let node = self.node(id).unwrap();
+            (node.item_count(), node.child_id(0).unwrap())
+        };
+
+        let left = if item_count > 0 {
+            self.recompute(id);
+            Some((id, height))
+        } else {
+            self.release_node(id);
+            match middle {
+                Some((None, _)) => None,
+                _ => {
+                    self.node_mut(first_child_id).unwrap().set_parent(None);
+                    Some((first_child_id, height - 1))
+                }
+            }
+        };
+
+        match middle {
+            Some((separator, middle_id)) => {
+                let (middle_left, middle_right) =
+                    self.split_node(middle_id, height - 1, key, after);
+
+                let left = match separator {
+                    Some((separator, from)) => Some(self.join(left, separator, from, middle_left)),
+                    None => middle_left,
+                };
+
+                let right = match right {
+                    Some((item, from, right)) => {
+                        Some(self.join(middle_right, item, from, Some(right)))
+                    }
+                    None => middle_right,
+                };
+
+                (left, right)
+            }
+            None => {
+                // The item matching the key is the first of the right subtree.
+                let (item, from, right) = right.unwrap();
+                (left, Some(self.join(None, item, from, Some(right))))
+            }
+        }
+    }
+
+    /// Joins the given detached subtrees, with the given separator.
+    ///
+    /// Every item of the `left` subtree must be lower than `separator`,
+    /// itself lower than every item of the `right` subtree.
+    /// The subtrees are glued together at the height of the smallest one,
+    /// which only requires rebalancing the nodes along this path.
+    ///
+    /// The separator move from its previous address `from` is reported to the observer,
+    /// unless `from` is [`Address::nowhere`] (when the separator comes from another tree).
+    ///
+    /// Returns the resulting subtree.
+    fn join(
+        &mut self,
+        left: Option<Subtree>,
+        separator: Self::Item,
+        from: Address,
+        right: Option<Subtree>,
+    ) -> Subtree {
+        let (root_id, height, id) = match (left, right) {
+            (None, None) => {
+                let id = self.allocate_node(node::Buffer::leaf(None, separator));
+                self.recompute(id);
+                self.report_move(from, Address::new(id, 0.into()));
+                return (id, 1);
+            }
+            (Some((left_id, left_height)), None) => {
+                let mut id = left_id;
+                for _ in 1..left_height {
+                    let node = self.node(id).unwrap();
+                    id = node.child_id(node.child_count() - 1).unwrap()
+                }
+
+                let offset = self.node_mut(id).unwrap().push_right(separator, None);
+                self.report_move(from, Address::new(id, offset));
+                (left_id, left_height, id)
+            }
+            (None, Some((right_id, right_height))) => {
+                let mut id = right_id;
+                for _ in 1..right_height {
+                    id = self.node(id).unwrap().child_id(0).unwrap()
+                }
+
+                self.node_mut(id).unwrap().push_left(None, separator);
+                self.report_move(from, Address::new(id, 0.into()));
+                (right_id, right_height, id)
+            }
+            (Some((left_id, left_height)), Some((right_id, right_height))) => {
+                if left_height == right_height {
+                    let right_node = self.release_node(right_id);
+                    for child_id in right_node.children() {
+                        self.node_mut(child_id).unwrap().set_parent(Some(left_id));
+                    }
+
+                    let offset = self
+                        .node_mut(left_id)
+                        .unwrap()
+                        .append(separator, right_node);
+                    if let Some(observer) = self.observer() {
+                        observer.on_merge(left_id, right_id, offset)
+                    }
+
+                    self.report_move(from, Address::new(left_id, offset));
+                    (left_id, left_height, left_id)
+                } else if left_height > right_height {
+                    // Attach the right subtree on the right side of the left subtree.
+                    let mut id = left_id;
+                    for _ in (right_height + 1)..left_height {
+                        let node = self.node(id).unwrap();
+                        id = node.child_id(node.child_count() - 1).unwrap()
+                    }
+
+                    let offset = self
+                        .node_mut(id)
+                        .unwrap()
+                        .push_right(separator, Some(right_id));
+                    self.node_mut(right_id).unwrap().set_parent(Some(id));
+                    self.report_move(from, Address::new(id, offset));
+
+                    if self.is_node_underflowing(right_id) {
+                        let index = self.node(id).unwrap().child_count() - 1;
+                        self.merge(id, index, Address::nowhere());
+                        id = self.node(id).unwrap().child_id(index - 1).unwrap()
+                    }
+
+                    (left_id, left_height, id)
+                } else {
+                    // Attach the left subtree on the left side of the right subtree.
+                    let mut id = right_id;
+                    for _ in (left_height + 1)..right_height {
+                        id = self.node(id).unwrap().child_id(0).unwrap()
+                    }
+
+                    self.node_mut(id)
+                        .unwrap()
+                        .push_left(Some(left_id), separator);
+                    self.node_mut(left_id).unwrap().set_parent(Some(id));
+                    self.report_move(from, Address::new(id, 0.into()));
+
+                    if self.is_node_underflowing(left_id) {
+                        self.merge(id, 0, Address::nowhere());
+                        id = left_id
+                    }
+
+                    (right_id, right_height, id)
+                }
+            }
+        };
+
+        self.rebalance(id, Address::nowhere());
+
+        // The root may have been split.
+        let (mut root_id, mut height) = (root_id, height);
+        while let Some(parent_id) = self.node(root_id).unwrap().parent() {
+            root_id = parent_id;
+            height += 1
+        }
+
+        (root_id, height)
+    }
+
+    /// Reports to the observer that the item at address `from` moved to `to`,
+    /// unless `from` is [`Address::nowhere`].
+    #[inline]
+    fn report_move(&mut self, from: Address, to: Address) {
+        if !from.is_nowhere() {
+            if let Some(observer) = self.observer() {
+                observer.on_move(from, to)
+            }
+        }
+    }
+
+    /// Reports to the observer that the `count` items starting at offset `offset`
+    /// in the node `id` moved, in order, to the node `target` starting at offset `0`.
+    #[inline]
+    fn report_moves(&mut self, id: usize, offset: usize, target: usize, count: usize) {
+        if let Some(observer) = self.observer() {
+            for k in 0..count {
+                observer.on_move(
+                    Address::new(id, (offset + k).into()),
+                    Address::new(target, k.into()),
+                )
+            }
+        }
+    }
+
+    /// Moves the detached subtree rooted at the node `id` into the `target` storage.
+    ///
+    /// Returns the id of the subtree root in `target`,
+    /// and the number of moved items.
+    fn transfer_node(&mut self, id: usize, target: &mut Self) -> (usize, usize) {
+        let node = self.release_node(id);
+        let child_count = node.child_count();
+        let new_id = target.allocate_node(node);
+        let mut len = target.node(new_id).unwrap().item_count();
+
+        for index in 0..child_count {
+            let child_id = target.node(new_id).unwrap().child_id(index).unwrap();
+            let (new_child_id, child_len) = self.transfer_node(child_id, target);
+            target
+                .node_mut(new_child_id)
+                .unwrap()
+                .set_parent(Some(new_id));
+
+            let mut node = target.node_mut(new_id).unwrap();
+            if index == 0 {
+                node.set_first_child_id(Some(new_child_id))
+            } else {
+                let offset = (index - 1).into();
+                let (item, _) = node.remove(offset);
+                node.insert(offset, item, Some(new_child_id))
+            }
+
+            len += child_len
+        }
+
+        target.node_mut(new_id).unwrap().set_parent(None);
+        target.recompute(new_id);
+        (new_id, len)
+    }
+
+    /// Removes every item from the tree, and builds a new tree from the given items.
+    ///
+    /// The items must be sorted by key, without duplicates,
+    /// otherwise the resulting tree is not valid.
+    ///
+    /// The tree is built bottom-up in linear time:
+    /// items are pushed into the rightmost leaf until it is full,
+    /// without any rebalancing.
+    /// Every node is then filled to its maximum capacity,
+    /// except for the nodes on the right border of the tree
+    /// and their left sibling, which are rebalanced once all the items are inserted.
+    fn build_from_sorted<T, I>(&mut self, items: I)
+    where
+        Self: Insert<T>,
+        I: IntoIterator<Item = T>,
+    {
+        self.clear();
+
+        let mut len = 0;
+        let mut leaf_id = None;
+        for item in items {
+            let item = self.allocate_item(item);
+            len += 1;
+
+            match leaf_id {
+                None => {
+                    let id = self.allocate_node(node::Buffer::leaf(None, item));
+                    self.set_root(Some(id));
+                    leaf_id = Some(id)
+                }
+                Some(id) => {
+                    if self.has_room(id) {
+                        self.node_mut(id).unwrap().push_right(item, None);
+                    } else {
+                        leaf_id = Some(self.push_right_border(id, item))
+                    }
+                }
+            }
+        }
+
+        self.set_len(len);
+
+        if let Some(root_id) = self.root() {
+            self.recompute_subtree(root_id);
+            self.fix_right_border(root_id)
+        }
+
+        self.relink_leaves()
+    }
+
+    /// Pushes the given item on the right of the first ancestor of the
+    /// full leaf `id` that is not full itself,
+    /// and opens a new right border of empty nodes below it.
+    ///
+    /// A new root is created if every ancestor is full.
+    /// Returns the id of the new rightmost (empty) leaf.
+    fn push_right_border(&mut self, id: usize, item: Self::Item) -> usize {
+        let mut child_id = id;
+        let mut height = 1;
+
+        loop {
+            let parent = self.node(child_id).unwrap().parent();
+            match parent {
+                Some(parent_id) => {
+                    if self.has_room(parent_id) {
+                        let (right_id, leaf_id) = self.allocate_border(height);
+                        self.node_mut(parent_id)
+                            .unwrap()
+                            .push_right(item, Some(right_id));
+                        self.node_mut(right_id).unwrap().set_parent(Some(parent_id));
+                        break leaf_id;
+                    }
+
+                    child_id = parent_id;
+                    height += 1
+                }
+                None => {
+                    let (right_id, leaf_id) = self.allocate_border(height);
+                    let root_id =
+                        self.insert_node(node::Buffer::binary(None, child_id, item, right_id));
+                    self.set_root(Some(root_id));
+                    break leaf_id;
+                }
+            }
         }
+    }
+
+    /// Checks if an item can be added to the node `id` without making it overflow.
+    fn has_room(&self, id: usize) -> bool {
+        let node = self.node(id).unwrap();
+        node.item_count() + 1 < node.max_capacity()
+    }
 
-        let other = std::mem::take(other);
-        for item in other.into_iter() {
-            self.insert(item);
+    /// Allocates a detached chain of empty nodes of the given height,
+    /// each node being the only child of the previous one.
+    ///
+    /// Returns the ids of the top node and of the bottom leaf.
+    fn allocate_border(&mut self, height: usize) -> (usize, usize) {
+        use node::buffer::Internal as _;
+
+        let leaf_id = self.allocate_node(node::Buffer::Leaf(Self::LeafNode::default()));
+        let mut id = leaf_id;
+        for _ in 1..height {
+            let mut node = Self::InternalNode::default();
+            node.set_first_child_id(id);
+            id = self.insert_node(node::Buffer::Internal(node))
+        }
+
+        (id, leaf_id)
+    }
+
+    /// Rebalances the right border of the subtree rooted at node `id`,
+    /// in which every node not on the right border is full.
+    ///
+    /// Every underflowing node of the border is merged with its left sibling,
+    /// and the result split in two.
+    ///
+    /// The augmentations of the subtree must be up to date,
+    /// and are recomputed along the way.
+    fn fix_right_border(&mut self, mut id: usize) {
+        loop {
+            let (index, child_id) = match self.node(id).unwrap().child_count() {
+                0 => break,
+                count => (
+                    count - 1,
+                    self.node(id).unwrap().child_id(count - 1).unwrap(),
+                ),
+            };
+
+            if index > 0 && self.is_node_underflowing(child_id) {
+                let left_id = self.node(id).unwrap().child_id(index - 1).unwrap();
+                self.merge(id, index, Address::nowhere());
+
+                if self.node(left_id).unwrap().is_overflowing() {
+                    let (median_offset, median, right_node) =
+                        self.node_mut(left_id).unwrap().split();
+                    let right_id = self.insert_node(right_node);
+                    self.link_leaf(Some(left_id), right_id);
+                    let offset = self
+                        .node_mut(id)
+                        .unwrap()
+                        .push_right(median, Some(right_id));
+                    self.node_mut(right_id).unwrap().set_parent(Some(id));
+                    self.recompute(left_id);
+                    self.recompute(right_id);
+
+                    if let Some(observer) = self.observer() {
+                        observer.on_split(left_id, right_id, median_offset.into());
+                        observer.on_move(
+                            Address::new(left_id, median_offset.into()),
+                            Address::new(id, offset),
+                        )
+                    }
+                }
+            }
+
+            let child_count = self.node(id).unwrap().child_count();
+            id = self.node(id).unwrap().child_id(child_count - 1).unwrap()
+        }
+
+        self.recompute_path(id)
+    }
+
+    /// Recompute the augmentation of every node of the subtree rooted at node `id`.
+    fn recompute_subtree(&mut self, id: usize) {
+        if self.is_augmented() {
+            let child_count = self.node(id).unwrap().child_count();
+            for index in 0..child_count {
+                let child_id = self.node(id).unwrap().child_id(index).unwrap();
+                self.recompute_subtree(child_id)
+            }
+
+            self.recompute(id)
         }
     }
 
@@ -1647,4 +4592,68 @@ pub trait Insert<T>: StorageMut {
     /// Converts an item of type `T` into an item of type `Self::Item`
     /// that is suited to be inserted in a node.
     fn allocate_item(&mut self, item: T) -> Self::Item;
+
+    /// Converts an item of type `T` into an item of type `Self::Item`,
+    /// if the storage has enough capacity.
+    ///
+    /// The default implementation never fails.
+    #[inline]
+    fn try_allocate_item(&mut self, item: T) -> Result<Self::Item, CapacityError<T>> {
+        Ok(self.allocate_item(item))
+    }
 }
+
+/// Fallible mutation operations.
+///
+/// Counterparts of the [`StorageMut`] operations that report a
+/// [`CapacityError`] instead of panicking when the storage is full.
+/// The capacity of a storage is given by
+/// [`StorageMut::remaining_node_capacity`] and [`Insert::try_allocate_item`].
+///
+/// This trait is implemented for every [`StorageMut`].
+pub trait TryStorageMut: StorageMut {
+    /// Allocates the given node, if the storage has enough capacity.
+    #[inline]
+    fn try_allocate_node(
+        &mut self,
+        node: node::Buffer<Self>,
+    ) -> Result<usize, CapacityError<node::Buffer<Self>>> {
+        if self.remaining_node_capacity() == Some(0) {
+            Err(CapacityError(node))
+        } else {
+            Ok(self.allocate_node(node))
+        }
+    }
+
+    /// Allocates the given node and setup its children parent id,
+    /// if the storage has enough capacity.
+    #[inline]
+    fn try_insert_node(
+        &mut self,
+        node: node::Buffer<Self>,
+    ) -> Result<usize, CapacityError<node::Buffer<Self>>> {
+        if self.remaining_node_capacity() == Some(0) {
+            Err(CapacityError(node))
+        } else {
+            Ok(self.insert_node(node))
+        }
+    }
+
+    /// Inserts an item at the given address, if the storage has enough capacity.
+    ///
+    /// Returns the address of the inserted item.
+    fn try_insert_at<T>(&mut self, addr: Address, item: T) -> Result<Address, CapacityError<T>>
+    where
+        Self: Insert<T>,
+    {
+        let addr = self.leaf_address(addr);
+        if !self.can_insert_at(addr) {
+            return Err(CapacityError(item));
+        }
+
+        let allocated_item = self.try_allocate_item(item)?;
+        Ok(self.insert_exactly_at(addr, allocated_item, None))
+    }
+}
+
+impl<S: StorageMut> TryStorageMut for S {}
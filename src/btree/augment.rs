@@ -0,0 +1,654 @@
+use super::{iter::is_valid_range, KeyPartialOrd, Storage};
+use core::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::{AddAssign, Bound, RangeBounds},
+};
+
+/// Subtree length.
+///
+/// Augmentations knowing the number of items in the subtree they summarize
+/// expose it through this trait, which allows storages to implement
+/// [`Storage::subtree_len`] and count items in logarithmic time.
+pub trait SubtreeLen {
+    /// Returns the number of items in the subtree, if known.
+    #[inline]
+    fn subtree_len(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Subtree augmentation.
+///
+/// The augmentation of a node is computed by adding to the default (empty) augmentation
+/// its first child augmentation (if any), then its first item,
+/// then its second child augmentation (if any), then its second item, etc.
+pub trait Augmentation<S: Storage>: Default + SubtreeLen {
+    /// Adds an item of the node to the augmentation.
+    fn add_item<'r>(&mut self, item: &S::ItemRef<'r>)
+    where
+        S: 'r;
+
+    /// Adds the augmentation of a child node to the augmentation.
+    fn add_child(&mut self, child: &Self);
+}
+
+impl SubtreeLen for () {}
+
+/// Empty augmentation.
+///
+/// This is the augmentation of storages that do not maintain any.
+impl<S: Storage> Augmentation<S> for () {
+    #[inline]
+    fn add_item<'r>(&mut self, _item: &S::ItemRef<'r>)
+    where
+        S: 'r,
+    {
+    }
+
+    #[inline]
+    fn add_child(&mut self, _child: &Self) {}
+}
+
+impl<A: SubtreeLen, B: SubtreeLen> SubtreeLen for (A, B) {
+    #[inline]
+    fn subtree_len(&self) -> Option<usize> {
+        self.0.subtree_len().or_else(|| self.1.subtree_len())
+    }
+}
+
+/// Pair of augmentations.
+impl<S: Storage, A: Augmentation<S>, B: Augmentation<S>> Augmentation<S> for (A, B) {
+    #[inline]
+    fn add_item<'r>(&mut self, item: &S::ItemRef<'r>)
+    where
+        S: 'r,
+    {
+        self.0.add_item(item);
+        self.1.add_item(item)
+    }
+
+    #[inline]
+    fn add_child(&mut self, child: &Self) {
+        self.0.add_child(&child.0);
+        self.1.add_child(&child.1)
+    }
+}
+
+/// Number of items in the subtree.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Count(pub usize);
+
+impl SubtreeLen for Count {
+    #[inline]
+    fn subtree_len(&self) -> Option<usize> {
+        Some(self.0)
+    }
+}
+
+impl<S: Storage> Augmentation<S> for Count {
+    #[inline]
+    fn add_item<'r>(&mut self, _item: &S::ItemRef<'r>)
+    where
+        S: 'r,
+    {
+        self.0 += 1
+    }
+
+    #[inline]
+    fn add_child(&mut self, child: &Self) {
+        self.0 += child.0
+    }
+}
+
+/// Storage whose items can be measured with a value of type `T`.
+///
+/// This is used by the [`Sum`], [`Min`] and [`Max`] augmentations.
+pub trait Measure<T>: Storage {
+    /// Returns the measure of the given item.
+    fn measure<'r>(item: &Self::ItemRef<'r>) -> T
+    where
+        Self: 'r;
+}
+
+/// Sum of the item measures in the subtree.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Sum<T>(pub T);
+
+impl<T> SubtreeLen for Sum<T> {}
+
+impl<T: Default + Clone + AddAssign, S: Measure<T>> Augmentation<S> for Sum<T> {
+    #[inline]
+    fn add_item<'r>(&mut self, item: &S::ItemRef<'r>)
+    where
+        S: 'r,
+    {
+        self.0 += S::measure(item)
+    }
+
+    #[inline]
+    fn add_child(&mut self, child: &Self) {
+        self.0 += child.0.clone()
+    }
+}
+
+/// Smallest item measure in the subtree, if any.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Min<T>(pub Option<T>);
+
+impl<T> Default for Min<T> {
+    #[inline]
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<T> Min<T> {
+    fn add(&mut self, value: T)
+    where
+        T: Ord,
+    {
+        match &self.0 {
+            Some(min) if *min <= value => (),
+            _ => self.0 = Some(value),
+        }
+    }
+}
+
+impl<T> SubtreeLen for Min<T> {}
+
+impl<T: Clone + Ord, S: Measure<T>> Augmentation<S> for Min<T> {
+    #[inline]
+    fn add_item<'r>(&mut self, item: &S::ItemRef<'r>)
+    where
+        S: 'r,
+    {
+        self.add(S::measure(item))
+    }
+
+    #[inline]
+    fn add_child(&mut self, child: &Self) {
+        if let Some(value) = &child.0 {
+            self.add(value.clone())
+        }
+    }
+}
+
+/// Greatest item measure in the subtree, if any.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Max<T>(pub Option<T>);
+
+impl<T> Default for Max<T> {
+    #[inline]
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<T> Max<T> {
+    fn add(&mut self, value: T)
+    where
+        T: Ord,
+    {
+        match &self.0 {
+            Some(max) if *max >= value => (),
+            _ => self.0 = Some(value),
+        }
+    }
+}
+
+impl<T> SubtreeLen for Max<T> {}
+
+impl<T: Clone + Ord, S: Measure<T>> Augmentation<S> for Max<T> {
+    #[inline]
+    fn add_item<'r>(&mut self, item: &S::ItemRef<'r>)
+    where
+        S: 'r,
+    {
+        self.add(S::measure(item))
+    }
+
+    #[inline]
+    fn add_child(&mut self, child: &Self) {
+        if let Some(value) = &child.0 {
+            self.add(value.clone())
+        }
+    }
+}
+
+/// Storage whose items can be hashed.
+///
+/// This is used by the [`MerkleHash`] augmentation.
+pub trait HashItem: Storage {
+    /// Feeds the given item into the given hasher state.
+    fn hash_item<'r, H: Hasher>(item: &Self::ItemRef<'r>, state: &mut H)
+    where
+        Self: 'r;
+}
+
+/// Merkle hash function.
+///
+/// Data is fed to the hash function through the [`Hasher`] interface.
+/// Items are hashed to field elements derived from [`Hasher::finish`],
+/// which must then depend on all the data written so far,
+/// and the hasher is consumed to produce the final digest of a subtree
+/// (see [`MerkleHash`]).
+pub trait MerkleHasher: Default + Hasher {
+    /// Digest type.
+    type Digest: Clone + Default + Eq + Hash;
+
+    /// Consumes the hasher and returns the digest of the data written so far.
+    fn digest(self) -> Self::Digest;
+}
+
+/// Non-cryptographic Merkle hash function.
+///
+/// Suitable to efficiently compare trees, but not to detect malicious tampering.
+#[cfg(feature = "std")]
+impl MerkleHasher for std::collections::hash_map::DefaultHasher {
+    type Digest = u64;
+
+    #[inline]
+    fn digest(self) -> u64 {
+        self.finish()
+    }
+}
+
+/// SHA-256 Merkle hash function.
+#[cfg(feature = "sha2")]
+#[derive(Clone, Default)]
+pub struct Sha256(sha2::Sha256);
+
+#[cfg(feature = "sha2")]
+impl Hasher for Sha256 {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        sha2::Digest::update(&mut self.0, bytes)
+    }
+
+    /// Returns the first 8 bytes of the SHA-256 digest of the data written so far.
+    #[inline]
+    fn finish(&self) -> u64 {
+        let digest = sha2::Digest::finalize(self.0.clone());
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&digest[..8]);
+        u64::from_le_bytes(bytes)
+    }
+}
+
+#[cfg(feature = "sha2")]
+impl MerkleHasher for Sha256 {
+    type Digest = [u8; 32];
+
+    #[inline]
+    fn digest(self) -> [u8; 32] {
+        sha2::Digest::finalize(self.0).into()
+    }
+}
+
+/// Arithmetic in the prime field of order `2^127 - 1`.
+mod field {
+    /// Order of the field.
+    pub const P: u128 = (1 << 127) - 1;
+
+    /// Base of the polynomial hash.
+    pub const BASE: u128 = 0x2b99_2ddf_a232_49d6_1b4f_7c5e_8d03_a6e5;
+
+    /// Reduces `x` modulo `P`.
+    #[inline]
+    pub fn reduce(x: u128) -> u128 {
+        let r = (x & P) + (x >> 127);
+        if r >= P {
+            r - P
+        } else {
+            r
+        }
+    }
+
+    #[inline]
+    pub fn add(a: u128, b: u128) -> u128 {
+        reduce(a + b)
+    }
+
+    #[inline]
+    pub fn mul(a: u128, b: u128) -> u128 {
+        let (a1, a0) = (a >> 64, a as u64 as u128);
+        let (b1, b0) = (b >> 64, b as u64 as u128);
+
+        // 2^128 = 2 (mod P).
+        let high = reduce(a1 * b1 * 2);
+        let middle = a1 * b0 + a0 * b1;
+        let middle = add(
+            reduce((middle >> 64) * 2),
+            reduce((middle as u64 as u128) << 64),
+        );
+        let low = reduce(a0 * b0);
+        add(add(high, middle), low)
+    }
+
+    /// Computes `BASE^n`.
+    #[inline]
+    pub fn base_pow(mut n: usize) -> u128 {
+        let mut result = 1;
+        let mut base = BASE;
+        while n > 0 {
+            if n & 1 == 1 {
+                result = mul(result, base)
+            }
+
+            base = mul(base, base);
+            n >>= 1
+        }
+
+        result
+    }
+}
+
+/// Merkle hash of the subtree.
+///
+/// The hash of a subtree only depends on the sequence of its items, in order,
+/// and not on the shape of the tree:
+/// two trees with the same items have the same root hash,
+/// whatever the order of the operations performed on them.
+///
+/// Each item `x` is hashed with `H` to an element `h(x)` of the prime field
+/// of order `2^127 - 1`, and the sequence `x1, ..., xn` is summarized by
+/// the polynomial hash `h(x1) * B^(n-1) + ... + h(xn)` for a fixed base `B`,
+/// along with its length `n`.
+/// Those summaries are combined associatively, so that the augmentation of a node
+/// can be computed from the summaries of its children and items.
+/// The digest of a subtree (see [`digest`](Self::digest))
+/// is the `H` digest of its summary.
+///
+/// Using a cryptographic hash function for `H` (such as [`Sha256`],
+/// with the `sha2` feature) makes item hashes hard to forge,
+/// but the polynomial combination is not itself collision resistant:
+/// an adversary choosing many items may find two sequences with the same hash.
+pub struct MerkleHash<H: MerkleHasher> {
+    /// Polynomial hash of the items.
+    value: u128,
+
+    /// Number of items.
+    len: usize,
+
+    hasher: PhantomData<H>,
+}
+
+impl<H: MerkleHasher> MerkleHash<H> {
+    /// Returns the digest of the subtree.
+    #[inline]
+    pub fn digest(&self) -> H::Digest {
+        let mut hasher = H::default();
+        self.value.hash(&mut hasher);
+        self.len.hash(&mut hasher);
+        hasher.digest()
+    }
+
+    /// Appends the summary `(value, len)` of a sequence of items.
+    #[inline]
+    fn append(&mut self, value: u128, len: usize) {
+        self.value = field::add(field::mul(self.value, field::base_pow(len)), value);
+        self.len += len
+    }
+}
+
+impl<H: MerkleHasher> Default for MerkleHash<H> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            value: 0,
+            len: 0,
+            hasher: PhantomData,
+        }
+    }
+}
+
+impl<H: MerkleHasher> Clone for MerkleHash<H> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value,
+            len: self.len,
+            hasher: PhantomData,
+        }
+    }
+}
+
+impl<H: MerkleHasher> PartialEq for MerkleHash<H> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value && self.len == other.len
+    }
+}
+
+impl<H: MerkleHasher> Eq for MerkleHash<H> {}
+
+impl<H: MerkleHasher> fmt::Debug for MerkleHash<H>
+where
+    H::Digest: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("MerkleHash").field(&self.digest()).finish()
+    }
+}
+
+impl<H: MerkleHasher> AsRef<MerkleHash<H>> for MerkleHash<H> {
+    #[inline]
+    fn as_ref(&self) -> &Self {
+        self
+    }
+}
+
+impl<H: MerkleHasher> SubtreeLen for MerkleHash<H> {
+    #[inline]
+    fn subtree_len(&self) -> Option<usize> {
+        Some(self.len)
+    }
+}
+
+impl<H: MerkleHasher, S: HashItem> Augmentation<S> for MerkleHash<H> {
+    #[inline]
+    fn add_item<'r>(&mut self, item: &S::ItemRef<'r>)
+    where
+        S: 'r,
+    {
+        let mut hasher = H::default();
+        S::hash_item(item, &mut hasher);
+        let low = hasher.finish();
+        hasher.write_u8(0xff);
+        let high = hasher.finish();
+        self.append(field::reduce((high as u128) << 64 | low as u128), 1)
+    }
+
+    #[inline]
+    fn add_child(&mut self, child: &Self) {
+        self.append(child.value, child.len)
+    }
+}
+
+/// Augmented storage.
+///
+/// Storage maintaining an augmentation for each node.
+pub trait Augment: Storage {
+    /// Augmentation type.
+    type Augmentation: Augmentation<Self>;
+
+    /// Returns the augmentation of the subtree rooted at the node `id`, if any.
+    fn augmentation(&self, id: usize) -> Option<&Self::Augmentation>;
+
+    /// Returns the augmentation of the whole tree.
+    ///
+    /// Returns `None` if the tree is empty.
+    #[inline]
+    fn root_augmentation(&self) -> Option<&Self::Augmentation> {
+        self.root().and_then(|id| self.augmentation(id))
+    }
+
+    /// Returns the Merkle hash of the whole tree.
+    ///
+    /// Returns `None` if the tree is empty.
+    #[inline]
+    fn root_hash<H>(&self) -> Option<H::Digest>
+    where
+        H: MerkleHasher,
+        Self::Augmentation: AsRef<MerkleHash<H>>,
+    {
+        self.root_augmentation().map(|a| a.as_ref().digest())
+    }
+
+    /// Computes the augmentation of the node `id` from its items and
+    /// the augmentations of its children.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the node or the augmentation of one of its children does not exist.
+    fn compute_augmentation(&self, id: usize) -> Self::Augmentation {
+        let node = self.node(id).unwrap();
+        let mut augmentation = Self::Augmentation::default();
+
+        for i in 0..node.item_count() {
+            if let Some(child_id) = node.child_id(i) {
+                augmentation.add_child(self.augmentation(child_id).unwrap())
+            }
+
+            augmentation.add_item(&node.item(i.into()).unwrap())
+        }
+
+        if let Some(child_id) = node.child_id(node.item_count()) {
+            augmentation.add_child(self.augmentation(child_id).unwrap())
+        }
+
+        augmentation
+    }
+
+    /// Computes the augmentation of the items whose key is in the given range.
+    ///
+    /// Only the nodes at the boundaries of the range are visited,
+    /// the augmentation of every subtree fully included in the range is reused,
+    /// making this function logarithmic in the size of the tree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    fn range_aggregate<T, R>(&self, range: R) -> Self::Augmentation
+    where
+        T: ?Sized + Ord,
+        R: RangeBounds<T>,
+        Self: KeyPartialOrd<T>,
+    {
+        if !is_valid_range(&range) {
+            panic!("Invalid range")
+        }
+
+        let mut augmentation = Self::Augmentation::default();
+
+        if let Some(id) = self.root() {
+            self.range_aggregate_in(id, &range, false, false, &mut augmentation)
+        }
+
+        augmentation
+    }
+
+    /// Adds the augmentation of the items of the subtree `id` whose key is in the given range.
+    ///
+    /// If `after_start` is true, every item of the subtree is known to be after the start
+    /// of the range.
+    /// If `before_end` is true, every item of the subtree is known to be before the end
+    /// of the range.
+    fn range_aggregate_in<T, R>(
+        &self,
+        id: usize,
+        range: &R,
+        after_start: bool,
+        before_end: bool,
+        augmentation: &mut Self::Augmentation,
+    ) where
+        T: ?Sized + Ord,
+        R: RangeBounds<T>,
+        Self: KeyPartialOrd<T>,
+    {
+        if after_start && before_end {
+            augmentation.add_child(self.augmentation(id).unwrap());
+            return;
+        }
+
+        let node = self.node(id).unwrap();
+        let item_count = node.item_count();
+
+        // items of the next child are after the start of the range.
+        let mut child_after_start = after_start;
+        for i in 0..=item_count {
+            let item = node.item(i.into());
+
+            let (item_after_start, item_before_end) = match &item {
+                Some(item) => (
+                    after_start || is_after_start::<Self, T>(item, range.start_bound()),
+                    before_end || is_before_end::<Self, T>(item, range.end_bound()),
+                ),
+                None => (true, before_end),
+            };
+
+            if let Some(child_id) = node.child_id(i) {
+                // if the item is before the start of the range, so is every item of the child.
+                if item_after_start {
+                    self.range_aggregate_in(
+                        child_id,
+                        range,
+                        child_after_start,
+                        item_before_end,
+                        augmentation,
+                    )
+                }
+            }
+
+            if let Some(item) = &item {
+                if !item_before_end {
+                    // every other item is after the end of the range.
+                    break;
+                }
+
+                if item_after_start {
+                    augmentation.add_item(item)
+                }
+            }
+
+            child_after_start = item_after_start;
+        }
+    }
+}
+
+/// Checks that the given item is after the given range start bound.
+fn is_after_start<'r, S: 'r + KeyPartialOrd<T>, T: ?Sized>(
+    item: &S::ItemRef<'r>,
+    bound: Bound<&T>,
+) -> bool {
+    match bound {
+        Bound::Included(start) => matches!(
+            S::key_partial_cmp(item, start),
+            Some(Ordering::Greater | Ordering::Equal)
+        ),
+        Bound::Excluded(start) => {
+            matches!(S::key_partial_cmp(item, start), Some(Ordering::Greater))
+        }
+        Bound::Unbounded => true,
+    }
+}
+
+/// Checks that the given item is before the given range end bound.
+fn is_before_end<'r, S: 'r + KeyPartialOrd<T>, T: ?Sized>(
+    item: &S::ItemRef<'r>,
+    bound: Bound<&T>,
+) -> bool {
+    match bound {
+        Bound::Included(end) => matches!(
+            S::key_partial_cmp(item, end),
+            Some(Ordering::Less | Ordering::Equal)
+        ),
+        Bound::Excluded(end) => matches!(S::key_partial_cmp(item, end), Some(Ordering::Less)),
+        Bound::Unbounded => true,
+    }
+}
@@ -0,0 +1,48 @@
+use super::node::Address;
+
+/// Cached position in a tree, speeding up accesses with locality.
+///
+/// A finger remembers the address of the last item accessed through it.
+/// The next search starting from this finger first looks for the key in the
+/// cached node, then climbs up the tree until the key falls within the range
+/// of a node, before descending again.
+/// Sequential or clustered accesses hence skip most of the walk from the root.
+///
+/// A finger can be reused after the tree has been modified by other means,
+/// in which case it is validated before use
+/// (see [`Storage::contains_address`](crate::Storage::contains_address))
+/// and ignored if stale.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct Finger {
+    addr: Option<Address>,
+}
+
+impl Finger {
+    /// Creates a new finger, not pointing anywhere yet.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cached address, if any.
+    #[inline]
+    pub fn address(&self) -> Option<Address> {
+        self.addr
+    }
+
+    /// Sets the cached address.
+    #[inline]
+    pub fn set_address(&mut self, addr: Address) {
+        if addr.is_nowhere() {
+            self.addr = None
+        } else {
+            self.addr = Some(addr)
+        }
+    }
+
+    /// Forgets the cached address.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.addr = None
+    }
+}
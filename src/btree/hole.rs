@@ -0,0 +1,64 @@
+use super::{
+    node::item::{Read, Write},
+    Address, Insert, StorageMut,
+};
+
+/// Item moved out of the tree.
+///
+/// The item slot is left uninitialized until the hole is either
+/// [filled](Hole::fill) or [removed](Hole::remove).
+/// If the hole is dropped before that (for instance because the code
+/// using the moved item panicked), the slot is removed from the tree
+/// without dropping its content, so that the item is never dropped twice.
+pub(crate) struct Hole<'a, S: StorageMut> {
+    storage: &'a mut S,
+    addr: Address,
+}
+
+impl<'a, S: StorageMut> Hole<'a, S> {
+    /// Moves out the item at the given address.
+    ///
+    /// Panics if `addr` is not an item address.
+    pub fn new(storage: &'a mut S, addr: Address) -> (Self, S::Item)
+    where
+        for<'r> S::ItemMut<'r>: Read<S>,
+    {
+        let item = {
+            let item_mut = storage.item_mut(addr).unwrap();
+            // the item slot is guarded by the hole from now on.
+            unsafe { item_mut.read() }
+        };
+        (Self { storage, addr }, item)
+    }
+
+    /// Fills the hole with a new item.
+    pub fn fill<I>(self, item: I)
+    where
+        S: Insert<I>,
+        for<'r> S::ItemMut<'r>: Write<S>,
+    {
+        // The hole is still armed while allocating.
+        let new_item = self.storage.allocate_item(item);
+
+        let addr = self.addr;
+        let mut this = core::mem::ManuallyDrop::new(self);
+        let mut item_mut = this.storage.item_mut(addr).unwrap();
+        // the slot content has been moved out, it must not be dropped.
+        unsafe { item_mut.write(new_item) };
+        core::mem::drop(item_mut);
+        this.storage.recompute_path(addr.id);
+    }
+
+    /// Removes the hole from the tree.
+    pub fn remove(self) {
+        // removal is done by `drop`.
+    }
+}
+
+impl<'a, S: StorageMut> Drop for Hole<'a, S> {
+    fn drop(&mut self) {
+        let (item, _) = self.storage.remove_at(self.addr).unwrap();
+        // item has been moved, it must not be dropped again.
+        core::mem::forget(item);
+    }
+}
@@ -1,7 +1,13 @@
-use super::{node::item::Read, Address, KeyPartialOrd, Storage, StorageMut};
-use std::{
+use super::{
+    node::{self, item::Read},
+    Address, KeyPartialOrd, Storage, StorageMut,
+};
+use alloc::vec::Vec;
+use core::{
     iter::{DoubleEndedIterator, ExactSizeIterator, FusedIterator},
+    marker::PhantomData,
     ops::{Bound, RangeBounds},
+    ptr::NonNull,
 };
 
 /// B-Tree items iterator.
@@ -12,9 +18,15 @@ pub struct Iter<'a, S> {
     /// Address of the next item.
     addr: Option<Address>,
 
+    /// Leaf preceding `addr`, when `addr` is the separator following it.
+    leaf: Option<usize>,
+
     /// Address of the iterator's end.
     end: Option<Address>,
 
+    /// Leaf following `end`, when `end` is the separator preceding it.
+    back_leaf: Option<usize>,
+
     /// Number of items left to iterate.
     len: usize,
 }
@@ -27,7 +39,9 @@ impl<'a, S: Storage> Iter<'a, S> {
         Self {
             storage,
             addr,
+            leaf: None,
             end: None,
+            back_leaf: None,
             len,
         }
     }
@@ -49,7 +63,8 @@ impl<'a, S: Storage> Iterator for Iter<'a, S> {
                     self.len -= 1;
 
                     let item = self.storage.item(addr).unwrap();
-                    self.addr = self.storage.next_item_address(addr);
+                    self.addr = next_linked(self.storage, addr, &mut self.leaf)
+                        .or_else(|| self.storage.next_item_address(addr));
                     Some(item)
                 } else {
                     None
@@ -68,7 +83,9 @@ impl<'a, S: Storage> DoubleEndedIterator for Iter<'a, S> {
     fn next_back(&mut self) -> Option<Self::Item> {
         if self.len > 0 {
             let addr = match self.end {
-                Some(addr) => self.storage.previous_item_address(addr).unwrap(),
+                Some(addr) => previous_linked(self.storage, addr, &mut self.back_leaf)
+                    .or_else(|| self.storage.previous_item_address(addr))
+                    .unwrap(),
                 None => self.storage.last_item_address().unwrap(),
             };
 
@@ -83,6 +100,92 @@ impl<'a, S: Storage> DoubleEndedIterator for Iter<'a, S> {
     }
 }
 
+/// Iterator over the items of a `Storage` along with their address.
+///
+/// This `struct` is created by the [`addresses`](Storage::addresses) method on [`Storage`].
+pub struct Addresses<'a, S> {
+    /// BTree reference.
+    storage: &'a S,
+
+    /// Address of the next item.
+    addr: Option<Address>,
+
+    /// Leaf preceding `addr`, when `addr` is the separator following it.
+    leaf: Option<usize>,
+
+    /// Address of the iterator's end.
+    end: Option<Address>,
+
+    /// Leaf following `end`, when `end` is the separator preceding it.
+    back_leaf: Option<usize>,
+
+    /// Number of items left to iterate.
+    len: usize,
+}
+
+impl<'a, S: Storage> Addresses<'a, S> {
+    #[inline]
+    pub(crate) fn new(storage: &'a S) -> Self {
+        Self {
+            storage,
+            addr: storage.first_item_address(),
+            leaf: None,
+            end: None,
+            back_leaf: None,
+            len: storage.len(),
+        }
+    }
+}
+
+impl<'a, S: Storage> Iterator for Addresses<'a, S> {
+    type Item = (Address, S::ItemRef<'a>);
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.addr {
+            Some(addr) if self.len > 0 => {
+                self.len -= 1;
+
+                let item = self.storage.item(addr).unwrap();
+                self.addr = next_linked(self.storage, addr, &mut self.leaf)
+                    .or_else(|| self.storage.next_item_address(addr));
+                Some((addr, item))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'a, S: Storage> FusedIterator for Addresses<'a, S> {}
+impl<'a, S: Storage> ExactSizeIterator for Addresses<'a, S> {}
+
+impl<'a, S: Storage> DoubleEndedIterator for Addresses<'a, S> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len > 0 {
+            let addr = match self.end {
+                Some(addr) => previous_linked(self.storage, addr, &mut self.back_leaf)
+                    .or_else(|| self.storage.previous_item_address(addr))
+                    .unwrap(),
+                None => self.storage.last_item_address().unwrap(),
+            };
+
+            self.len -= 1;
+
+            let item = self.storage.item(addr).unwrap();
+            self.end = Some(addr);
+            Some((addr, item))
+        } else {
+            None
+        }
+    }
+}
+
 /// An owning iterator over the entries of a `Storage`.
 ///
 /// This `struct` is created by the [`into_iter`] method on [`Storage`]
@@ -259,31 +362,55 @@ where
 /// Note that it is a logical error to
 /// mutate the items in a ways that changes their relative ordering.
 pub struct IterMut<'a, S> {
-    /// BTree mutable reference.
-    storage: &'a mut S,
+    /// Mutably borrowed storage.
+    ///
+    /// The storage is never accessed through a reference once the iterator is created,
+    /// only through the raw node hooks of [`StorageMut`],
+    /// so that emitted items are not invalidated.
+    storage: NonNull<S>,
 
-    /// Address of the next item.
-    addr: Option<Address>,
+    /// Path to the next item.
+    front: RawPath,
 
-    end: Option<Address>,
+    /// Path to the last emitted item from the back.
+    back: RawPath,
 
+    /// Number of items left to iterate.
     len: usize,
+
+    marker: PhantomData<&'a mut S>,
 }
 
 impl<'a, S: StorageMut> IterMut<'a, S> {
     #[inline]
     pub(crate) fn new(storage: &'a mut S) -> Self {
-        let addr = storage.first_item_address();
         let len = storage.len();
+        let root = storage.root();
+        let storage = NonNull::from(storage);
+        let mut front = RawPath::new();
+        let mut back = RawPath::new();
+        if let Some(root) = root {
+            // this is safe because no item has been emitted yet.
+            unsafe {
+                descend_front(storage, &mut front, &back, root);
+                descend_back(storage, &mut back, &front, root)
+            }
+        }
+
         Self {
             storage,
-            addr,
-            end: None,
+            front,
+            back,
             len,
+            marker: PhantomData,
         }
     }
 }
 
+// `IterMut` behaves like a `&'a mut S`.
+unsafe impl<'a, S: Send> Send for IterMut<'a, S> {}
+unsafe impl<'a, S: Sync> Sync for IterMut<'a, S> {}
+
 impl<'a, S: StorageMut> Iterator for IterMut<'a, S> {
     type Item = S::ItemMut<'a>;
 
@@ -294,24 +421,15 @@ impl<'a, S: StorageMut> Iterator for IterMut<'a, S> {
 
     #[inline]
     fn next(&mut self) -> Option<S::ItemMut<'a>> {
-        match self.addr {
-            Some(addr) => {
-                if self.len > 0 {
-                    self.len -= 1;
-
-                    self.addr = self.storage.next_item_address(addr);
-
-                    // this is safe because only one mutable reference to the same item can be emitted.
-                    unsafe {
-                        let ptr = self.storage as *mut S;
-                        let storage: &'a mut S = &mut *ptr;
-                        storage.item_mut(addr)
-                    }
-                } else {
-                    None
-                }
+        if self.len > 0 {
+            self.len -= 1;
+            // this is safe because each item is emitted once.
+            unsafe {
+                let (node, offset) = step_front(self.storage, &mut self.front, &self.back)?;
+                Some(S::raw_item_mut(self.storage, &node, offset))
             }
-            None => None,
+        } else {
+            None
         }
     }
 }
@@ -323,18 +441,11 @@ impl<'a, S: StorageMut> DoubleEndedIterator for IterMut<'a, S> {
     #[inline]
     fn next_back(&mut self) -> Option<Self::Item> {
         if self.len > 0 {
-            let addr = match self.end {
-                Some(addr) => self.storage.previous_item_address(addr).unwrap(),
-                None => self.storage.last_item_address().unwrap(),
-            };
-
             self.len -= 1;
-
-            // this is safe because only one mutable reference to the same item can be emitted.
+            // this is safe because each item is emitted once.
             unsafe {
-                let ptr = self.storage as *mut S;
-                let storage: &'a mut S = &mut *ptr;
-                storage.item_mut(addr)
+                let (node, offset) = step_back(self.storage, &mut self.back, &self.front)?;
+                Some(S::raw_item_mut(self.storage, &node, offset))
             }
         } else {
             None
@@ -342,7 +453,245 @@ impl<'a, S: StorageMut> DoubleEndedIterator for IterMut<'a, S> {
     }
 }
 
-fn is_valid_range<T, R>(range: &R) -> bool
+/// Path from the root of a mutably borrowed storage to a position in the tree,
+/// used by the mutable iterators.
+///
+/// Each frame is a node of the path, entered through [`StorageMut::raw_node`],
+/// along with an offset in the node.
+/// For the front of an iterator, the offset is the offset of the next item,
+/// the child preceding this item being already visited.
+/// For the back of an iterator, it is the offset following the previous item,
+/// the child following this item being already visited.
+///
+/// Nodes are never entered twice, so that the items they emitted stay valid:
+/// both ends of an iterator share the nodes of their common prefix.
+type RawPath = Vec<(node::Raw, usize)>;
+
+/// Enters the node `id` at the given depth of a path,
+/// reusing the node of the `other` path at this depth if it is the same.
+///
+/// # Safety
+///
+/// The node must not have been entered before by another path.
+#[inline]
+unsafe fn enter<S: StorageMut>(
+    storage: NonNull<S>,
+    other: &RawPath,
+    depth: usize,
+    id: usize,
+) -> node::Raw {
+    match other.get(depth) {
+        Some((node, _)) if node.id == id => *node,
+        _ => S::raw_node(storage, id).unwrap(),
+    }
+}
+
+/// Extends the front path from the node `id` down to the first item of its subtree.
+///
+/// # Safety
+///
+/// No node of the subtree may have been entered before by another path
+/// than `other`.
+unsafe fn descend_front<S: StorageMut>(
+    storage: NonNull<S>,
+    path: &mut RawPath,
+    other: &RawPath,
+    mut id: usize,
+) {
+    loop {
+        let node = enter(storage, other, path.len(), id);
+        path.push((node, 0));
+        match S::raw_child_id(storage, &node, 0) {
+            Some(child_id) => id = child_id,
+            None => break,
+        }
+    }
+}
+
+/// Extends the back path from the node `id` down to the last item of its subtree.
+///
+/// # Safety
+///
+/// No node of the subtree may have been entered before by another path
+/// than `other`.
+unsafe fn descend_back<S: StorageMut>(
+    storage: NonNull<S>,
+    path: &mut RawPath,
+    other: &RawPath,
+    mut id: usize,
+) {
+    loop {
+        let node = enter(storage, other, path.len(), id);
+        path.push((node, node.item_count));
+        match S::raw_child_id(storage, &node, node.item_count) {
+            Some(child_id) => id = child_id,
+            None => break,
+        }
+    }
+}
+
+/// Steps the front path over its next item, and returns the node and offset of this item.
+///
+/// # Safety
+///
+/// The item must not have been visited by the `other` path.
+unsafe fn step_front<S: StorageMut>(
+    storage: NonNull<S>,
+    path: &mut RawPath,
+    other: &RawPath,
+) -> Option<(node::Raw, usize)> {
+    loop {
+        let (node, offset) = *path.last()?;
+        if offset < node.item_count {
+            path.last_mut().unwrap().1 = offset + 1;
+            if let Some(child_id) = S::raw_child_id(storage, &node, offset + 1) {
+                descend_front(storage, path, other, child_id)
+            }
+
+            return Some((node, offset));
+        }
+
+        path.pop();
+    }
+}
+
+/// Steps the back path over its previous item, and returns the node and offset of this item.
+///
+/// # Safety
+///
+/// The item must not have been visited by the `other` path.
+unsafe fn step_back<S: StorageMut>(
+    storage: NonNull<S>,
+    path: &mut RawPath,
+    other: &RawPath,
+) -> Option<(node::Raw, usize)> {
+    loop {
+        let (node, end) = *path.last()?;
+        if end > 0 {
+            let offset = end - 1;
+            path.last_mut().unwrap().1 = offset;
+            if let Some(child_id) = S::raw_child_id(storage, &node, offset) {
+                descend_back(storage, path, other, child_id)
+            }
+
+            return Some((node, offset));
+        }
+
+        path.pop();
+    }
+}
+
+/// Returns the node identifier and offset of the item at the position of a path,
+/// or `None` if the path is at the end of the tree.
+///
+/// Both ends of an iterator meet when they are at the same position.
+#[inline]
+fn path_position(path: &RawPath) -> Option<(usize, usize)> {
+    path.iter()
+        .rev()
+        .find(|(node, offset)| *offset < node.item_count)
+        .map(|(node, offset)| (node.id, *offset))
+}
+
+/// Returns the identifiers of the nodes from the root to the given address,
+/// along with the offset of the address, or the child index, in each of them.
+fn address_path<S: Storage>(storage: &S, addr: Address) -> Vec<(usize, usize)> {
+    let mut path = Vec::new();
+    if !addr.is_nowhere() {
+        path.push((addr.id, addr.offset.unwrap()));
+        let mut id = addr.id;
+        while let Some(parent_id) = storage.node(id).unwrap().parent() {
+            let index = storage.node(parent_id).unwrap().child_index(id).unwrap();
+            path.push((parent_id, index));
+            id = parent_id
+        }
+
+        path.reverse()
+    }
+
+    path
+}
+
+/// Enters the nodes of the given address path.
+///
+/// # Safety
+///
+/// No node of the path may have been entered before by another path
+/// than `other`.
+unsafe fn enter_path<S: StorageMut>(
+    storage: NonNull<S>,
+    other: &RawPath,
+    ids: Vec<(usize, usize)>,
+) -> RawPath {
+    ids.into_iter()
+        .enumerate()
+        .map(|(depth, (id, offset))| (enter(storage, other, depth, id), offset))
+        .collect()
+}
+
+/// Steps forward from the item at `addr` through the leaf sibling links.
+///
+/// If `leaf` is set, `addr` is the separator following this leaf,
+/// and the next item is the first item of the next leaf, if the leaves are linked.
+/// Otherwise, `leaf` is set if `addr` is the last item of a leaf,
+/// to be used in the next step.
+///
+/// Returns `None` if the next address must be found by navigating the tree.
+#[inline]
+fn next_linked<S: Storage>(
+    storage: &S,
+    addr: Address,
+    leaf: &mut Option<usize>,
+) -> Option<Address> {
+    match leaf.take() {
+        Some(id) => {
+            let next_id = storage.leaf_links(id)?.next?;
+            Some(Address::new(next_id, 0.into()))
+        }
+        None => {
+            if let Some(offset) = addr.offset.value() {
+                let node = storage.node(addr.id).unwrap();
+                if !node.is_internal() && offset + 1 >= node.item_count() {
+                    *leaf = Some(addr.id)
+                }
+            }
+
+            None
+        }
+    }
+}
+
+/// Steps backward from the address `addr` through the leaf sibling links.
+///
+/// If `leaf` is set, `addr` is the separator preceding this leaf,
+/// and the previous item is the last item of the previous leaf, if the leaves are linked.
+/// Otherwise, `leaf` is set if `addr` is the first item of a leaf,
+/// to be used in the next step.
+///
+/// Returns `None` if the previous address must be found by navigating the tree.
+#[inline]
+fn previous_linked<S: Storage>(
+    storage: &S,
+    addr: Address,
+    leaf: &mut Option<usize>,
+) -> Option<Address> {
+    match leaf.take() {
+        Some(id) => {
+            let previous_id = storage.leaf_links(id)?.previous?;
+            let item_count = storage.node(previous_id).unwrap().item_count();
+            Some(Address::new(previous_id, (item_count - 1).into()))
+        }
+        None => {
+            if addr.offset.value() == Some(0) && !storage.node(addr.id).unwrap().is_internal() {
+                *leaf = Some(addr.id)
+            }
+
+            None
+        }
+    }
+}
+
+pub(crate) fn is_valid_range<T, R>(range: &R) -> bool
 where
     T: Ord + ?Sized,
     R: RangeBounds<T>,
@@ -358,6 +707,206 @@ where
     }
 }
 
+/// Iterator over the contiguous item runs of a `Storage`, in order.
+///
+/// Each leaf node yields a single chunk containing all its items,
+/// while each item of an internal node (separating two leaves) is
+/// yielded as a chunk of its own.
+///
+/// This `struct` is created by the [`chunks`](Storage::chunks) method on [`Storage`].
+pub struct Chunks<'a, S> {
+    /// BTree reference.
+    storage: &'a S,
+
+    /// Address of the first item of the next chunk.
+    addr: Option<Address>,
+}
+
+impl<'a, S: Storage> Chunks<'a, S> {
+    #[inline]
+    pub(crate) fn new(storage: &'a S) -> Self {
+        Self {
+            storage,
+            addr: storage.first_item_address(),
+        }
+    }
+}
+
+impl<'a, S: Storage> Iterator for Chunks<'a, S> {
+    type Item = Chunk<'a, S>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let addr = self.addr?;
+        let node = self.storage.node(addr.id).unwrap();
+        let start = addr.offset.unwrap();
+        let end = if node.is_internal() {
+            start + 1
+        } else {
+            node.item_count()
+        };
+
+        self.addr = self
+            .storage
+            .next_item_address(Address::new(addr.id, (end - 1).into()));
+
+        Some(Chunk {
+            id: addr.id,
+            node,
+            start,
+            end,
+        })
+    }
+}
+
+impl<'a, S: Storage> FusedIterator for Chunks<'a, S> {}
+
+/// Iterator over the leaf nodes of a `Storage`, in order.
+///
+/// This `struct` is created by the [`leaves`](Storage::leaves) method on [`Storage`].
+pub struct Leaves<'a, S> {
+    /// BTree reference.
+    storage: &'a S,
+
+    /// Identifier of the next leaf.
+    next: Option<usize>,
+}
+
+impl<'a, S: Storage> Leaves<'a, S> {
+    #[inline]
+    pub(crate) fn new(storage: &'a S) -> Self {
+        Self {
+            storage,
+            next: storage.first_item_address().map(|addr| addr.id),
+        }
+    }
+
+    /// Finds the leaf following the leaf `id`,
+    /// using the leaf sibling links if any, or parent navigation.
+    fn next_leaf(&self, mut id: usize) -> Option<usize> {
+        if let Some(links) = self.storage.leaf_links(id) {
+            return links.next;
+        }
+
+        loop {
+            let parent_id = self.storage.node(id).unwrap().parent()?;
+            let parent = self.storage.node(parent_id).unwrap();
+            let index = parent.child_index(id).unwrap();
+
+            if let Some(mut child_id) = parent.child_id(index + 1) {
+                while let Some(first_child_id) =
+                    self.storage.node(child_id).unwrap().first_child_id()
+                {
+                    child_id = first_child_id
+                }
+
+                return Some(child_id);
+            }
+
+            id = parent_id
+        }
+    }
+}
+
+impl<'a, S: Storage> Iterator for Leaves<'a, S> {
+    type Item = Chunk<'a, S>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = self.next?;
+        let node = self.storage.node(id).unwrap();
+        self.next = self.next_leaf(id);
+
+        Some(Chunk {
+            id,
+            start: 0,
+            end: node.item_count(),
+            node,
+        })
+    }
+}
+
+impl<'a, S: Storage> FusedIterator for Leaves<'a, S> {}
+
+/// Contiguous run of items stored in the same node.
+///
+/// This is itself an iterator over the items of the chunk.
+pub struct Chunk<'a, S: 'a + Storage> {
+    /// Node identifier.
+    id: usize,
+
+    /// Node reference.
+    node: super::node::Ref<'a, S>,
+
+    /// Offset of the next item in the node.
+    start: usize,
+
+    /// Offset following the last item in the node.
+    end: usize,
+}
+
+impl<'a, S: Storage> Chunk<'a, S> {
+    /// Returns the identifier of the node containing the chunk.
+    #[inline]
+    pub fn node_id(&self) -> usize {
+        self.id
+    }
+
+    /// Returns the address of the next item of the chunk.
+    #[inline]
+    pub fn address(&self) -> Address {
+        Address::new(self.id, self.start.into())
+    }
+
+    /// Returns a reference to the node containing the chunk.
+    #[inline]
+    pub fn node(&self) -> &super::node::Ref<'a, S> {
+        &self.node
+    }
+
+    /// Checks if the chunk is a whole leaf node.
+    #[inline]
+    pub fn is_leaf(&self) -> bool {
+        !self.node.is_internal()
+    }
+}
+
+impl<'a, S: Storage> Iterator for Chunk<'a, S> {
+    type Item = S::ItemRef<'a>;
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start < self.end {
+            let item = self.node.item(self.start.into());
+            self.start += 1;
+            item
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, S: Storage> FusedIterator for Chunk<'a, S> {}
+impl<'a, S: Storage> ExactSizeIterator for Chunk<'a, S> {}
+
+impl<'a, S: Storage> DoubleEndedIterator for Chunk<'a, S> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.start < self.end {
+            self.end -= 1;
+            self.node.item(self.end.into())
+        } else {
+            None
+        }
+    }
+}
+
 /// Range iterator.
 pub struct Range<'a, S> {
     /// The tree reference.
@@ -366,7 +915,13 @@ pub struct Range<'a, S> {
     /// Address of the next item or last back address.
     addr: Address,
 
+    /// Leaf preceding `addr`, when `addr` is the separator following it.
+    leaf: Option<usize>,
+
     end: Address,
+
+    /// Leaf following `end`, when `end` is the separator preceding it.
+    back_leaf: Option<usize>,
 }
 
 impl<'a, S: Storage> Range<'a, S> {
@@ -401,10 +956,34 @@ impl<'a, S: Storage> Range<'a, S> {
                 Ok(addr) => addr,
                 Err(addr) => addr,
             },
-            Bound::Unbounded => btree.first_back_address(),
+            Bound::Unbounded => btree.last_valid_address(),
         };
 
-        Range { btree, addr, end }
+        // normalize the addresses so that they can be compared.
+        let addr = btree
+            .normalize(addr)
+            .unwrap_or_else(|| btree.last_valid_address());
+        let end = btree
+            .normalize(end)
+            .unwrap_or_else(|| btree.last_valid_address());
+
+        Range {
+            btree,
+            addr,
+            leaf: None,
+            end,
+            back_leaf: None,
+        }
+    }
+
+    /// Returns the number of items remaining in the range.
+    ///
+    /// This takes a logarithmic time when the storage knows the length of each subtree
+    /// (see [`Storage::subtree_len`]).
+    /// Otherwise, every remaining item of the range is visited.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        remaining(self.btree, self.addr, self.end)
     }
 }
 
@@ -415,12 +994,28 @@ impl<'a, S: Storage> Iterator for Range<'a, S> {
     fn next(&mut self) -> Option<Self::Item> {
         if self.addr != self.end {
             let item = self.btree.item(self.addr).unwrap();
-            self.addr = self.btree.next_item_or_back_address(self.addr).unwrap();
+            self.addr = next_linked(self.btree, self.addr, &mut self.leaf)
+                .or_else(|| self.btree.next_item_or_back_address(self.addr))
+                .unwrap();
             Some(item)
         } else {
             None
         }
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match (
+            self.btree.items_before(self.addr),
+            self.btree.items_before(self.end),
+        ) {
+            (Some(start), Some(end)) => {
+                let len = end.saturating_sub(start);
+                (len, Some(len))
+            }
+            _ => (0, Some(self.btree.len())),
+        }
+    }
 }
 
 impl<'a, S: Storage> FusedIterator for Range<'a, S> {}
@@ -429,7 +1024,9 @@ impl<'a, S: Storage> DoubleEndedIterator for Range<'a, S> {
     #[inline]
     fn next_back(&mut self) -> Option<Self::Item> {
         if self.addr != self.end {
-            let addr = self.btree.previous_item_address(self.addr).unwrap();
+            let addr = previous_linked(self.btree, self.end, &mut self.back_leaf)
+                .or_else(|| self.btree.previous_item_address(self.end))
+                .unwrap();
             let item = self.btree.item(addr).unwrap();
             self.end = addr;
             Some(item)
@@ -439,18 +1036,47 @@ impl<'a, S: Storage> DoubleEndedIterator for Range<'a, S> {
     }
 }
 
+/// Counts the items between the two given normalized addresses.
+fn remaining<S: Storage>(btree: &S, mut addr: Address, end: Address) -> usize {
+    match (btree.items_before(addr), btree.items_before(end)) {
+        (Some(start), Some(end)) => end.saturating_sub(start),
+        _ => {
+            let mut len = 0;
+            while addr != end {
+                addr = btree.next_item_or_back_address(addr).unwrap();
+                len += 1
+            }
+
+            len
+        }
+    }
+}
+
 /// Mutable range iterator.
 ///
 /// Note that it is a logical error to mutate the items
 /// in a ways that changes their relative ordering.
 pub struct RangeMut<'a, S> {
-    /// The tree reference.
-    btree: &'a mut S,
+    /// Mutably borrowed storage.
+    ///
+    /// The storage is never accessed through a reference once the iterator is created,
+    /// only through the raw node hooks of [`StorageMut`],
+    /// so that emitted items are not invalidated.
+    btree: NonNull<S>,
 
-    /// Address of the next item or last back address.
-    addr: Address,
+    /// Path to the next item.
+    front: RawPath,
 
-    end: Address,
+    /// Path to the item following the range.
+    back: RawPath,
+
+    /// Number of items left in the range, if known.
+    len: Option<usize>,
+
+    /// Length of the tree, bounding the number of items left in the range.
+    tree_len: usize,
+
+    marker: PhantomData<&'a mut S>,
 }
 
 impl<'a, S: StorageMut> RangeMut<'a, S> {
@@ -485,30 +1111,109 @@ impl<'a, S: StorageMut> RangeMut<'a, S> {
                 Ok(addr) => addr,
                 Err(addr) => addr,
             },
-            Bound::Unbounded => btree.first_back_address(),
+            Bound::Unbounded => btree.last_valid_address(),
+        };
+
+        // normalize the addresses so that they can be compared.
+        let addr = btree
+            .normalize(addr)
+            .unwrap_or_else(|| btree.last_valid_address());
+        let end = btree
+            .normalize(end)
+            .unwrap_or_else(|| btree.last_valid_address());
+
+        // the tree is navigated through references before any node is entered.
+        let len = match (btree.items_before(addr), btree.items_before(end)) {
+            (Some(start), Some(end)) => Some(end.saturating_sub(start)),
+            _ => None,
+        };
+        let tree_len = btree.len();
+        let front_ids = address_path(&*btree, addr);
+        let back_ids = address_path(&*btree, end);
+
+        let btree = NonNull::from(btree);
+        // this is safe because no item has been emitted yet.
+        let (front, back) = unsafe {
+            let front = enter_path(btree, &RawPath::new(), front_ids);
+            let mut back = enter_path(btree, &front, back_ids);
+            if let Some(&(node, offset)) = back.last() {
+                if let Some(child_id) = S::raw_child_id(btree, &node, offset) {
+                    descend_back(btree, &mut back, &front, child_id)
+                }
+            }
+
+            (front, back)
         };
 
-        RangeMut { btree, addr, end }
+        RangeMut {
+            btree,
+            front,
+            back,
+            len,
+            tree_len,
+            marker: PhantomData,
+        }
+    }
+
+    /// Checks if every item of the range has been emitted.
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        path_position(&self.front) == path_position(&self.back)
+    }
+
+    /// Returns the number of items remaining in the range.
+    ///
+    /// This takes a constant time when the storage knows the length of each subtree
+    /// (see [`Storage::subtree_len`]).
+    /// Otherwise, every remaining item of the range is visited.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        match self.len {
+            Some(len) => len,
+            None => {
+                let mut front = self.front.clone();
+                let mut len = 0;
+                while path_position(&front) != path_position(&self.back) {
+                    // this is safe because the remaining items have not been emitted.
+                    unsafe { step_front(self.btree, &mut front, &self.back) };
+                    len += 1
+                }
+
+                len
+            }
+        }
     }
 }
 
+// `RangeMut` behaves like a `&'a mut S`.
+unsafe impl<'a, S: Send> Send for RangeMut<'a, S> {}
+unsafe impl<'a, S: Sync> Sync for RangeMut<'a, S> {}
+
 impl<'a, S: StorageMut> Iterator for RangeMut<'a, S> {
     type Item = S::ItemMut<'a>;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        if self.addr != self.end {
-            let addr = self.addr;
-            self.addr = self.btree.next_item_or_back_address(addr).unwrap();
+        if self.is_exhausted() {
+            None
+        } else {
+            if let Some(len) = &mut self.len {
+                *len -= 1
+            }
 
-            // this is safe because only one mutable reference to the same item can be emitted.
+            // this is safe because each item is emitted once.
             unsafe {
-                let btree: &'a mut S = std::ptr::read(&self.btree);
-                let item = btree.item_mut(addr).unwrap();
-                Some(item)
+                let (node, offset) = step_front(self.btree, &mut self.front, &self.back)?;
+                Some(S::raw_item_mut(self.btree, &node, offset))
             }
-        } else {
-            None
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.len {
+            Some(len) => (len, Some(len)),
+            None => (0, Some(self.tree_len)),
         }
     }
 }
@@ -518,17 +1223,18 @@ impl<'a, S: StorageMut> FusedIterator for RangeMut<'a, S> {}
 impl<'a, S: StorageMut> DoubleEndedIterator for RangeMut<'a, S> {
     #[inline]
     fn next_back(&mut self) -> Option<Self::Item> {
-        if self.addr != self.end {
-            let addr = self.btree.previous_item_address(self.addr).unwrap();
+        if self.is_exhausted() {
+            None
+        } else {
+            if let Some(len) = &mut self.len {
+                *len -= 1
+            }
 
-            // this is safe because only one mutable reference to the same item can be emitted.
+            // this is safe because each item is emitted once.
             unsafe {
-                let btree: &'a mut S = std::ptr::read(&self.btree);
-                let item = btree.item_mut(addr).unwrap();
-                Some(item)
+                let (node, offset) = step_back(self.btree, &mut self.back, &self.front)?;
+                Some(S::raw_item_mut(self.btree, &node, offset))
             }
-        } else {
-            None
         }
     }
 }
@@ -537,18 +1243,126 @@ pub(crate) struct DrainFilterInner<'a, S> {
     /// The tree reference.
     btree: &'a mut S,
 
-    /// Address of the next item, or last valid address.
-    addr: Address,
+    /// Address of the next item visited from the front,
+    /// or `None` if it must be recomputed.
+    front: Option<Address>,
+
+    /// Address of the next item visited from the back,
+    /// or `None` if it must be recomputed.
+    back: Option<Address>,
+
+    /// Number of items kept before the unvisited items,
+    /// or `None` if unknown.
+    front_kept: Option<usize>,
+
+    /// Number of items kept after the unvisited items,
+    /// or `None` if unknown.
+    back_kept: Option<usize>,
 
+    /// Number of unvisited items.
     len: usize,
+
+    /// Set while the predicate is called, so that it stays set
+    /// if the predicate panics.
+    panicked: bool,
 }
 
 impl<'a, S: StorageMut> DrainFilterInner<'a, S> {
     #[inline]
     pub fn new(btree: &'a mut S) -> Self {
-        let addr = btree.first_back_address();
+        let front = btree.first_item_address();
+        let back = btree.last_item_address();
         let len = btree.len();
-        DrainFilterInner { btree, addr, len }
+        DrainFilterInner {
+            btree,
+            front,
+            back,
+            front_kept: Some(0),
+            back_kept: Some(0),
+            len,
+            panicked: false,
+        }
+    }
+
+    /// Creates a drain filter visiting only the items of the given range.
+    ///
+    /// The number of items outside of the range is only computed
+    /// when the storage knows the length of each subtree,
+    /// so that the items before the range are never visited.
+    #[inline]
+    pub fn with_range<T, R>(btree: &'a mut S, range: R) -> Self
+    where
+        T: ?Sized + Ord,
+        R: RangeBounds<T>,
+        S: KeyPartialOrd<T>,
+    {
+        let len = btree.range_count((range.start_bound(), range.end_bound()));
+        let front = btree.lower_bound(range.start_bound());
+        let end = btree.upper_bound(range.end_bound());
+        let back = match end {
+            Some(end) => btree.previous_item_address(end),
+            None => btree.last_item_address(),
+        };
+
+        let front_kept = match front {
+            Some(front) => btree.items_before(front),
+            None => Some(btree.len()),
+        };
+
+        let back_kept = match end {
+            Some(end) => btree.items_before(end).map(|before| btree.len() - before),
+            None => Some(0),
+        };
+
+        DrainFilterInner {
+            btree,
+            front,
+            back,
+            front_kept,
+            back_kept,
+            len,
+            panicked: false,
+        }
+    }
+
+    /// Returns the address of the next item visited from the front.
+    ///
+    /// Must only be called when there are unvisited items.
+    #[inline]
+    fn front_address(&self) -> Address {
+        match (self.front, self.front_kept) {
+            (Some(addr), _) => addr,
+            (None, Some(kept)) => self.btree.nth_address(kept).unwrap(),
+            (None, None) => {
+                // The back address is always up to date when the front one is not.
+                let mut addr = self.back.unwrap();
+                for _ in 1..self.len {
+                    addr = self.btree.previous_item_address(addr).unwrap()
+                }
+
+                addr
+            }
+        }
+    }
+
+    /// Returns the address of the next item visited from the back.
+    ///
+    /// Must only be called when there are unvisited items.
+    #[inline]
+    fn back_address(&self) -> Address {
+        match (self.back, self.back_kept) {
+            (Some(addr), _) => addr,
+            (None, Some(kept)) => self.btree.nth_address(self.btree.len() - kept - 1).unwrap(),
+            (None, None) => {
+                // The front address is always up to date when the back one is not.
+                let mut addr = self.front.unwrap();
+                for _ in 1..self.len {
+                    addr = self.btree.next_item_address(addr).unwrap()
+                }
+
+                addr
+            }
+        }
     }
 
     #[inline]
@@ -556,57 +1370,102 @@ impl<'a, S: StorageMut> DrainFilterInner<'a, S> {
         (0, Some(self.len))
     }
 
+    /// Calls the predicate on the item at the given address,
+    /// recording whether it panics.
+    #[inline]
+    fn test<F>(&mut self, addr: Address, pred: &mut F) -> bool
+    where
+        F: FnMut(S::ItemMut<'_>) -> bool,
+    {
+        self.panicked = true;
+        let remove = (*pred)(self.btree.item_mut(addr).unwrap());
+        self.panicked = false;
+        remove
+    }
+
+    /// Removes the remaining items matching the predicate,
+    /// when the iterator is dropped.
+    ///
+    /// Does nothing if the predicate previously panicked:
+    /// the remaining items are kept in the tree,
+    /// and the predicate is not called again.
+    #[inline]
+    pub fn finish<F>(&mut self, mut pred: F)
+    where
+        F: FnMut(S::ItemMut<'_>) -> bool,
+    {
+        while !self.panicked && self.next(&mut pred).is_some() {}
+    }
+
     #[inline]
     pub fn next<F>(&mut self, pred: &mut F) -> Option<S::Item>
     where
         F: FnMut(S::ItemMut<'_>) -> bool,
     {
-        loop {
-            let remove = self.btree.item_mut(self.addr).map(|item| (*pred)(item));
+        while self.len > 0 {
+            // Removing an item from the back may move the front item.
+            let addr = self.front_address();
 
-            eprintln!("remove: {:?}", remove);
+            let remove = self.test(addr, pred);
+            self.len -= 1;
 
-            match remove {
-                Some(true) => {
-                    let (item, next_addr) = self.btree.remove_at(self.addr).unwrap();
-                    self.len -= 1;
-                    self.addr = next_addr;
-                    return Some(item);
-                }
-                Some(false) => {
-                    self.len -= 1;
-                    self.addr = self.btree.next_item_or_back_address(self.addr).unwrap();
-                }
-                None => return None,
+            if remove {
+                let (item, next_addr) = self.btree.remove_at(addr).unwrap();
+                self.front = self.btree.normalize(next_addr);
+                self.back = None;
+                return Some(item);
             }
+
+            self.front_kept = self.front_kept.map(|kept| kept + 1);
+            self.front = self.btree.next_item_address(addr);
         }
+
+        None
     }
 
     #[inline]
-    pub fn next_consume<F>(&mut self, mut pred: F) -> Option<S::Item>
+    pub fn next_back<F>(&mut self, pred: &mut F) -> Option<S::Item>
     where
         F: FnMut(S::ItemMut<'_>) -> bool,
     {
-        eprintln!("next_consume");
-        loop {
-            let remove = self.btree.item_mut(self.addr).map(|item| pred(item));
+        while self.len > 0 {
+            // Removing an item from the front may move the back item.
+            let addr = self.back_address();
 
-            eprintln!("remove: {:?}", remove);
+            let remove = self.test(addr, pred);
+            self.len -= 1;
 
-            match remove {
-                Some(true) => {
-                    let (item, next_addr) = self.btree.remove_at(self.addr).unwrap();
-                    self.len -= 1;
-                    self.addr = next_addr;
-                    return Some(item);
-                }
-                Some(false) => {
-                    self.len -= 1;
-                    self.addr = self.btree.next_item_or_back_address(self.addr).unwrap();
-                }
-                None => return None,
+            if remove {
+                let (item, next_addr) = self.btree.remove_at(addr).unwrap();
+                self.back = match self.btree.normalize(next_addr) {
+                    Some(next_addr) => self.btree.previous_item_address(next_addr),
+                    None => self.btree.last_item_address(),
+                };
+                self.front = None;
+                return Some(item);
             }
+
+            self.back_kept = self.back_kept.map(|kept| kept + 1);
+            self.back = self.btree.previous_item_address(addr);
         }
+
+        None
+    }
+
+    #[inline]
+    pub fn next_consume<F>(&mut self, mut pred: F) -> Option<S::Item>
+    where
+        F: FnMut(S::ItemMut<'_>) -> bool,
+    {
+        self.next(&mut pred)
+    }
+
+    #[inline]
+    pub fn next_back_consume<F>(&mut self, mut pred: F) -> Option<S::Item>
+    where
+        F: FnMut(S::ItemMut<'_>) -> bool,
+    {
+        self.next_back(&mut pred)
     }
 }
 
@@ -631,6 +1490,19 @@ where
             inner: DrainFilterInner::new(btree),
         }
     }
+
+    #[inline]
+    pub(crate) fn with_range<T, R>(btree: &'a mut S, range: R, pred: F) -> Self
+    where
+        T: ?Sized + Ord,
+        R: RangeBounds<T>,
+        S: KeyPartialOrd<T>,
+    {
+        DrainFilter {
+            pred,
+            inner: DrainFilterInner::with_range(btree, range),
+        }
+    }
 }
 
 impl<'a, S: StorageMut, F> FusedIterator for DrainFilter<'a, S, F> where
@@ -655,16 +1527,82 @@ where
     }
 }
 
+impl<'a, S: StorageMut, F> DoubleEndedIterator for DrainFilter<'a, S, F>
+where
+    F: FnMut(S::ItemMut<'_>) -> bool,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back(&mut self.pred)
+    }
+}
+
 impl<'a, S: StorageMut, F> Drop for DrainFilter<'a, S, F>
 where
     F: FnMut(S::ItemMut<'_>) -> bool,
 {
     #[inline]
     fn drop(&mut self) {
-        loop {
-            if self.next().is_none() {
-                break;
-            }
+        self.inner.finish(&mut self.pred)
+    }
+}
+
+/// Range draining iterator.
+///
+/// Removes and yields the items of a range, in order.
+/// The items of the range not yet yielded are removed when the iterator is dropped.
+pub struct Drain<'a, S: StorageMut> {
+    /// The tree reference.
+    btree: &'a mut S,
+
+    /// Address of the next item to remove.
+    addr: Option<Address>,
+
+    /// Number of items left to remove.
+    len: usize,
+}
+
+impl<'a, S: StorageMut> Drain<'a, S> {
+    pub(crate) fn new<T, R>(btree: &'a mut S, range: R) -> Self
+    where
+        T: Ord + ?Sized,
+        R: RangeBounds<T>,
+        S: KeyPartialOrd<T>,
+    {
+        let len = btree.range_count::<T, _>((range.start_bound(), range.end_bound()));
+        let addr = btree.lower_bound(range.start_bound());
+        Drain { btree, addr, len }
+    }
+}
+
+impl<'a, S: StorageMut> Iterator for Drain<'a, S> {
+    type Item = S::Item;
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<S::Item> {
+        if self.len > 0 {
+            let (item, addr) = self.btree.remove_at(self.addr?)?;
+            self.addr = self.btree.normalize(addr);
+            self.len -= 1;
+            Some(item)
+        } else {
+            None
         }
     }
 }
+
+impl<'a, S: StorageMut> FusedIterator for Drain<'a, S> {}
+
+impl<'a, S: StorageMut> ExactSizeIterator for Drain<'a, S> {}
+
+impl<'a, S: StorageMut> Drop for Drain<'a, S> {
+    #[inline]
+    fn drop(&mut self) {
+        for _ in self {}
+    }
+}
@@ -0,0 +1,15 @@
+/// Links of a leaf node to its neighbor leaves.
+///
+/// Storages may maintain links between consecutive leaves
+/// (see [`Storage::leaf_links`](crate::Storage::leaf_links)).
+/// Since the items separating two leaves are stored in their common ancestor,
+/// iterators still climb up the tree to visit these separators,
+/// but then hop directly to the next leaf instead of descending again from the separator.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct LeafLinks {
+    /// Previous leaf, in key order.
+    pub previous: Option<usize>,
+
+    /// Next leaf, in key order.
+    pub next: Option<usize>,
+}
@@ -1,5 +1,5 @@
 use super::{KeyOrd, KeyPartialOrd, Storage, StorageMut, ValidationError};
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
 mod addr;
 mod balance;
@@ -7,15 +7,17 @@ pub mod buffer;
 mod internal;
 pub mod item;
 mod leaf;
-mod offset;
+mod position;
+mod raw;
 
-pub use addr::Address;
+pub use addr::{Address, StampedAddress};
 pub use balance::Balance;
 pub use buffer::Buffer;
 pub use internal::{InternalConst, InternalMut, InternalRef};
-pub use item::ItemAccess;
+pub use item::{ItemAccess, RawKey, RawKeys};
 pub use leaf::{LeafConst, LeafMut, LeafRef};
-pub use offset::Offset;
+pub use position::Position;
+pub use raw::Raw;
 
 /// Node type.
 pub enum Type {
@@ -114,7 +116,7 @@ impl<S: Storage, L: LeafRef<S>, I: InternalRef<S>> Reference<S, L, I> {
     }
 
     /// Returns a reference to the item with the given offset in the node.
-    pub fn borrow_item(&self, offset: Offset) -> Option<S::ItemRef<'_>> {
+    pub fn borrow_item(&self, offset: Position) -> Option<S::ItemRef<'_>> {
         match &self.desc {
             Desc::Internal(node) => node.borrow_item(offset),
             Desc::Leaf(node) => node.borrow_item(offset),
@@ -145,7 +147,7 @@ impl<S: Storage, L: LeafRef<S>, I: InternalRef<S>> Reference<S, L, I> {
     /// this funtion returns the index and id of the child that may match the key,
     /// or `Err(None)` if it is a leaf.
     #[inline]
-    pub fn offset_of<Q: ?Sized>(&self, key: &Q) -> Result<Offset, (usize, Option<usize>)>
+    pub fn offset_of<Q: ?Sized>(&self, key: &Q) -> Result<Position, (usize, Option<usize>)>
     where
         S: KeyPartialOrd<Q>,
     {
@@ -233,6 +235,14 @@ impl<S: Storage, L: LeafRef<S>, I: InternalRef<S>> Reference<S, L, I> {
         self.item_count() >= self.max_capacity()
     }
 
+    /// Checks if the node is full.
+    ///
+    /// A full node overflows as soon as an item is inserted into it.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.item_count() + 1 >= self.max_capacity()
+    }
+
     /// Checks if the node is underflowing.
     #[inline]
     pub fn is_underflowing(&self) -> bool {
@@ -242,16 +252,26 @@ impl<S: Storage, L: LeafRef<S>, I: InternalRef<S>> Reference<S, L, I> {
     /// Returns the current balance of the node.
     #[inline]
     pub fn balance(&self) -> Balance {
+        self.balance_with(self.min_capacity())
+    }
+
+    /// Returns the current balance of the node,
+    /// considering it underflowing if it contains less than `min_capacity` items.
+    #[inline]
+    pub fn balance_with(&self, min_capacity: usize) -> Balance {
         if self.is_overflowing() {
             Balance::Overflow
-        } else if self.is_underflowing() {
+        } else if self.item_count() < min_capacity {
             Balance::Underflow(self.item_count() == 0)
         } else {
             Balance::Balanced
         }
     }
 
-    #[cfg(debug_assertions)]
+    /// Validates the node `id`, given its expected parent and the separators around it.
+    ///
+    /// Returns the first issue found, see [`validate_into`](Reference::validate_into).
+    #[cfg(any(debug_assertions, feature = "validation"))]
     pub fn validate<'a>(
         &self,
         id: usize,
@@ -259,38 +279,83 @@ impl<S: Storage, L: LeafRef<S>, I: InternalRef<S>> Reference<S, L, I> {
         min: Option<S::ItemRef<'a>>,
         max: Option<S::ItemRef<'a>>,
     ) -> Result<ValidationBounds<'a, S>, ValidationError>
+    where
+        S: KeyOrd,
+    {
+        let mut errors = alloc::vec::Vec::new();
+        let bounds = self.validate_into(id, parent, min, max, &mut errors);
+        match errors.into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(bounds),
+        }
+    }
+
+    /// Validates the node `id`, given its expected parent and the separators around it,
+    /// and pushes every issue found into `errors`.
+    ///
+    /// Returns the separators around the node, to be used to validate its children.
+    #[cfg(any(debug_assertions, feature = "validation"))]
+    pub fn validate_into<'a>(
+        &self,
+        id: usize,
+        parent: Option<usize>,
+        min: Option<S::ItemRef<'a>>,
+        max: Option<S::ItemRef<'a>>,
+        errors: &mut alloc::vec::Vec<ValidationError>,
+    ) -> ValidationBounds<'a, S>
+    where
+        S: KeyOrd,
+    {
+        self.validate_into_with(id, parent, self.min_capacity(), min, max, errors)
+    }
+
+    /// Validates the node `id` like [`validate_into`](Reference::validate_into),
+    /// considering it underflowing if it contains less than `min_capacity` items.
+    #[cfg(any(debug_assertions, feature = "validation"))]
+    pub fn validate_into_with<'a>(
+        &self,
+        id: usize,
+        parent: Option<usize>,
+        min_capacity: usize,
+        min: Option<S::ItemRef<'a>>,
+        max: Option<S::ItemRef<'a>>,
+        errors: &mut alloc::vec::Vec<ValidationError>,
+    ) -> ValidationBounds<'a, S>
     where
         S: KeyOrd,
     {
         if self.parent() != parent {
-            return Err(ValidationError::WrongParent(id, self.parent(), parent));
+            errors.push(ValidationError::WrongParent(id, self.parent(), parent));
         }
 
         if min.is_some() || max.is_some() {
             // not root
-            match self.balance() {
-                Balance::Overflow => return Err(ValidationError::Overflow(id)),
-                Balance::Underflow(_) => return Err(ValidationError::Underflow(id)),
+            match self.balance_with(min_capacity) {
+                Balance::Overflow => errors.push(ValidationError::Overflow(id)),
+                Balance::Underflow(_) => errors.push(ValidationError::Underflow(id)),
                 _ => (),
             }
         }
 
-        for i in 1..self.item_count() {
+        let unsorted = (1..self.item_count()).find(|i| {
             let prev = i - 1;
-            if S::key_cmp(
-                &self.borrow_item(i.into()).unwrap(),
+            S::key_cmp(
+                &self.borrow_item((*i).into()).unwrap(),
                 &self.borrow_item(prev.into()).unwrap(),
             )
             .is_lt()
-            {
-                return Err(ValidationError::UnsortedNode(id));
-            }
+        });
+        if let Some(i) = unsorted {
+            errors.push(ValidationError::UnsortedNode(Address::new(id, i.into())));
         }
 
         if let Some(min) = &min {
             if let Some(item) = self.borrow_first_item() {
                 if S::key_cmp(min, &item).is_ge() {
-                    return Err(ValidationError::UnsortedFromLeft(id));
+                    errors.push(ValidationError::UnsortedFromLeft(Address::new(
+                        id,
+                        0.into(),
+                    )));
                 }
             }
         }
@@ -298,12 +363,16 @@ impl<S: Storage, L: LeafRef<S>, I: InternalRef<S>> Reference<S, L, I> {
         if let Some(max) = &max {
             if let Some(item) = self.borrow_last_item() {
                 if S::key_cmp(max, &item).is_le() {
-                    return Err(ValidationError::UnsortedFromRight(id));
+                    let offset = self.item_count() - 1;
+                    errors.push(ValidationError::UnsortedFromRight(Address::new(
+                        id,
+                        offset.into(),
+                    )));
                 }
             }
         }
 
-        Ok((min, max))
+        (min, max)
     }
 }
 
@@ -313,7 +382,7 @@ where
     for<'r> S::ItemRef<'r>: crate::dot::Display,
 {
     #[inline]
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         match &self.desc {
             Desc::Leaf(node) => {
                 for item in node.items() {
@@ -339,7 +408,7 @@ pub type Ref<'a, S> = Reference<S, <S as Storage>::LeafRef<'a>, <S as Storage>::
 
 impl<'a, S: 'a + Storage, L: LeafConst<'a, S>, I: InternalConst<'a, S>> Reference<S, L, I> {
     /// Returns a reference to the item with the given offset in the node.
-    pub fn item(&self, offset: Offset) -> Option<S::ItemRef<'a>> {
+    pub fn item(&self, offset: Position) -> Option<S::ItemRef<'a>> {
         match &self.desc {
             Desc::Internal(node) => node.item(offset),
             Desc::Leaf(node) => node.item(offset),
@@ -408,7 +477,7 @@ impl<'a, S: 'a + StorageMut, L: LeafMut<'a, S>, I: InternalMut<'a, S>> Reference
     }
 
     /// Returns a mutable reference to the item at the given offset, if any.
-    pub fn into_item_mut(self, offset: Offset) -> Option<S::ItemMut<'a>> {
+    pub fn into_item_mut(self, offset: Position) -> Option<S::ItemMut<'a>> {
         match self.desc {
             Desc::Leaf(node) => node.into_item_mut(offset),
             Desc::Internal(node) => node.into_item_mut(offset),
@@ -438,7 +507,7 @@ impl<'a, S: 'a + StorageMut, L: LeafMut<'a, S>, I: InternalMut<'a, S>> Reference
     ///
     /// This may panics if the offset if greater than the current item count or
     /// if this is an internal node and `right_child_id` is `None`.
-    pub fn insert(&mut self, offset: Offset, item: S::Item, right_child_id: Option<usize>) {
+    pub fn insert(&mut self, offset: Position, item: S::Item, right_child_id: Option<usize>) {
         match &mut self.desc {
             Desc::Leaf(node) => node.insert(offset, item),
             Desc::Internal(node) => node.insert(offset, item, right_child_id.unwrap()),
@@ -448,7 +517,7 @@ impl<'a, S: 'a + StorageMut, L: LeafMut<'a, S>, I: InternalMut<'a, S>> Reference
     /// Removes the item at the given offset and returns it
     /// along with the identifier of its associated right child
     /// if the node is an internal node.
-    pub fn remove(&mut self, offset: Offset) -> (S::Item, Option<usize>) {
+    pub fn remove(&mut self, offset: Position) -> (S::Item, Option<usize>) {
         match &mut self.desc {
             Desc::Leaf(node) => {
                 let item = node.remove(offset);
@@ -462,7 +531,7 @@ impl<'a, S: 'a + StorageMut, L: LeafMut<'a, S>, I: InternalMut<'a, S>> Reference
     }
 
     #[inline]
-    pub fn leaf_remove(&mut self, offset: Offset) -> Option<Result<S::Item, usize>> {
+    pub fn leaf_remove(&mut self, offset: Position) -> Option<Result<S::Item, usize>> {
         match &mut self.desc {
             Desc::Internal(node) => {
                 if offset < node.item_count() {
@@ -503,7 +572,17 @@ impl<'a, S: 'a + StorageMut, L: LeafMut<'a, S>, I: InternalMut<'a, S>> Reference
     /// Remove the first item of the node unless it would undeflow.
     #[inline]
     pub fn pop_left(&mut self) -> Result<(Option<usize>, S::Item), WouldUnderflow> {
-        if self.item_count() <= self.min_capacity() {
+        self.pop_left_with(self.min_capacity())
+    }
+
+    /// Remove the first item of the node
+    /// unless it would then contain less than `min_capacity` items.
+    #[inline]
+    pub fn pop_left_with(
+        &mut self,
+        min_capacity: usize,
+    ) -> Result<(Option<usize>, S::Item), WouldUnderflow> {
+        if self.item_count() <= min_capacity {
             Err(WouldUnderflow)
         } else {
             let first_child_id = self.first_child_id();
@@ -514,18 +593,29 @@ impl<'a, S: 'a + StorageMut, L: LeafMut<'a, S>, I: InternalMut<'a, S>> Reference
     }
 
     #[inline]
-    pub fn push_right(&mut self, item: S::Item, child_id: Option<usize>) -> Offset {
-        let offset: Offset = self.item_count().into();
+    pub fn push_right(&mut self, item: S::Item, child_id: Option<usize>) -> Position {
+        let offset: Position = self.item_count().into();
         self.insert(offset, item, child_id);
         offset
     }
 
+    /// Remove the last item of the node unless it would undeflow.
+    #[inline]
+    pub fn pop_right(&mut self) -> Result<(Position, S::Item, Option<usize>), WouldUnderflow> {
+        self.pop_right_with(self.min_capacity())
+    }
+
+    /// Remove the last item of the node
+    /// unless it would then contain less than `min_capacity` items.
     #[inline]
-    pub fn pop_right(&mut self) -> Result<(Offset, S::Item, Option<usize>), WouldUnderflow> {
-        if self.item_count() <= self.min_capacity() {
+    pub fn pop_right_with(
+        &mut self,
+        min_capacity: usize,
+    ) -> Result<(Position, S::Item, Option<usize>), WouldUnderflow> {
+        if self.item_count() <= min_capacity {
             Err(WouldUnderflow)
         } else {
-            let offset: Offset = (self.item_count() - 1).into();
+            let offset: Position = (self.item_count() - 1).into();
             let (item, right_child_id) = self.remove(offset);
             Ok((offset, item, right_child_id))
         }
@@ -536,7 +626,7 @@ impl<'a, S: 'a + StorageMut, L: LeafMut<'a, S>, I: InternalMut<'a, S>> Reference
     /// # Panic
     ///
     /// This function panics if no item is at the given offset.
-    pub fn replace(&mut self, offset: Offset, item: S::Item) -> S::Item {
+    pub fn replace(&mut self, offset: Position, item: S::Item) -> S::Item {
         match &mut self.desc {
             Desc::Leaf(node) => node.replace(offset, item),
             Desc::Internal(node) => node.replace(offset, item),
@@ -562,7 +652,7 @@ impl<'a, S: 'a + StorageMut, L: LeafMut<'a, S>, I: InternalMut<'a, S>> Reference
     ///
     /// Returns the new offset of the `separator`.
     #[inline]
-    pub fn append(&mut self, separator: S::Item, other: Buffer<S>) -> Offset {
+    pub fn append(&mut self, separator: S::Item, other: Buffer<S>) -> Position {
         match (&mut self.desc, other) {
             (Desc::Internal(node), Buffer::Internal(other)) => node.append(separator, other),
             (Desc::Leaf(node), Buffer::Leaf(other)) => node.append(separator, other),
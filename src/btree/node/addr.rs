@@ -1,5 +1,5 @@
-use super::Offset;
-use std::fmt;
+use super::Position;
+use core::fmt;
 
 /// Item/entry location in a B-Tree.
 ///
@@ -25,7 +25,8 @@ use std::fmt;
 ///
 /// ## Validity
 /// An item adress `addr` is *valid* in a given B-Tree if it `addr.id` refers to an existing
-/// node and if `addr.offset` is comprised between `-1` and the number of items in the node (included).
+/// node and if `addr.offset` is comprised between `-1` and the number of items in the node (included),
+/// where `-1` is the [`Before`](Position::Before) position.
 /// We say that `addr` is *occupied* if it points to an actual item
 /// (`addr.offset` at least 0 and less than the number of items in the node).
 ///
@@ -91,14 +92,14 @@ pub struct Address {
     /// Identifier of the node.
     pub id: usize,
 
-    /// Offset in the node.
-    pub offset: Offset,
+    /// Position in the node.
+    pub offset: Position,
 }
 
 impl Address {
     /// Creates a new address from the identifier of the node and the offset in the node.
     #[inline]
-    pub fn new(id: usize, offset: Offset) -> Address {
+    pub fn new(id: usize, offset: Position) -> Address {
         Address { id, offset }
     }
 
@@ -109,7 +110,7 @@ impl Address {
     #[inline]
     pub fn nowhere() -> Address {
         Address {
-            id: std::usize::MAX,
+            id: usize::MAX,
             offset: 0.into(),
         }
     }
@@ -117,7 +118,7 @@ impl Address {
     /// Checks if the address is nowhere.
     #[inline]
     pub fn is_nowhere(&self) -> bool {
-        self.id == std::usize::MAX
+        self.id == usize::MAX
     }
 }
 
@@ -132,3 +133,32 @@ impl fmt::Debug for Address {
         write!(f, "@{}:{}", self.id, self.offset)
     }
 }
+
+/// Address stamped with the generation of the tree it was computed in.
+///
+/// Since node slots are reused, an address may silently point to another item
+/// after the tree has been modified.
+/// Stamped addresses, created by [`Storage::stamp`](crate::Storage::stamp),
+/// are rejected by [`Storage::item_stamped`](crate::Storage::item_stamped)
+/// and [`StorageMut::item_mut_stamped`](crate::StorageMut::item_mut_stamped)
+/// once the tree generation has changed,
+/// provided the storage tracks generations
+/// (see [`Storage::generation`](crate::Storage::generation)).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct StampedAddress {
+    /// Address.
+    pub addr: Address,
+
+    /// Generation of the tree when the address was stamped,
+    /// or `None` if the storage does not track generations.
+    pub generation: Option<usize>,
+}
+
+impl fmt::Display for StampedAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.generation {
+            Some(generation) => write!(f, "{}#{}", self.addr, generation),
+            None => self.addr.fmt(f),
+        }
+    }
+}
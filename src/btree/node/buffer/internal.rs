@@ -1,5 +1,5 @@
 use super::StorageMut;
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
 /// Internal buffer node.
 pub trait Internal<S: StorageMut>: Default {
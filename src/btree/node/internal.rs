@@ -1,6 +1,6 @@
-use super::{ItemAccess, KeyPartialOrd, Offset, Storage, StorageMut};
+use super::{ItemAccess, KeyPartialOrd, Position, Storage, StorageMut};
 use crate::util::binary_search_min;
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
 /// Internal node reference.
 pub trait InternalRef<S: Storage>: ItemAccess<S> {
@@ -12,7 +12,7 @@ pub trait InternalRef<S: Storage>: ItemAccess<S> {
     /// If the key matches no item in this node,
     /// this funtion returns the index and id of the child that may match the key.
     #[inline]
-    fn offset_of<Q: ?Sized>(&self, key: &Q) -> Result<Offset, (usize, usize)>
+    fn offset_of<Q: ?Sized>(&self, key: &Q) -> Result<Position, (usize, usize)>
     where
         S: KeyPartialOrd<Q>,
     {
@@ -104,7 +104,7 @@ pub trait InternalRef<S: Storage>: ItemAccess<S> {
 /// implementing this trait requires implementing the
 /// [`InternalRef`] trait.
 pub trait InternalConst<'a, S: 'a + Storage>: InternalRef<S> {
-    fn item(&self, offset: Offset) -> Option<S::ItemRef<'a>>;
+    fn item(&self, offset: Position) -> Option<S::ItemRef<'a>>;
 
     #[inline]
     fn get<Q: ?Sized>(&self, key: &Q) -> Result<S::ItemRef<'a>, usize>
@@ -155,26 +155,32 @@ pub trait InternalMut<'a, S: 'a + StorageMut>: Sized + InternalRef<S> {
     fn set_first_child_id(&mut self, id: usize);
 
     /// Returns a mutable reference to the item with the given offset in the node.
-    fn into_item_mut(self, offset: Offset) -> Option<S::ItemMut<'a>>;
+    fn into_item_mut(self, offset: Position) -> Option<S::ItemMut<'a>>;
 
     /// Inserts an item at the given offset in the node,
     /// separated with the next item by the given child node.
-    fn insert(&mut self, offset: Offset, item: S::Item, right_child_id: usize);
+    fn insert(&mut self, offset: Position, item: S::Item, right_child_id: usize);
 
     /// Removes the item at the given offset and the reference to its right child.
     ///
     /// Returns the item and the identifier of the right child.
-    fn remove(&mut self, offset: Offset) -> (S::Item, usize);
+    fn remove(&mut self, offset: Position) -> (S::Item, usize);
 
     /// Replaces the item at the given offset.
     ///
     /// Returns the old item.
-    fn replace(&mut self, offset: Offset, item: S::Item) -> S::Item;
+    fn replace(&mut self, offset: Position, item: S::Item) -> S::Item;
 
     /// Appends the separator and all the branches of `other` into this node.
     ///
     /// Returns the offset of the separator.
-    fn append(&mut self, separator: S::Item, other: S::InternalNode) -> Offset;
+    fn append(&mut self, separator: S::Item, other: S::InternalNode) -> Position;
+
+    /// Removes all the items from the given offset to the end of the node,
+    /// along with their right children, and moves them into a new internal buffer node.
+    ///
+    /// The parent and first child of the returned node are left unset.
+    fn drain_from(&mut self, offset: Position) -> S::InternalNode;
 
     /// Returns a mutable reference to the item matching the given key in this node.
     ///
@@ -209,32 +215,19 @@ pub trait InternalMut<'a, S: 'a + StorageMut>: Sized + InternalRef<S> {
     #[inline]
     fn split(&mut self) -> (usize, S::Item, S::InternalNode) {
         use crate::btree::node::buffer::Internal;
-        assert!(self.is_overflowing()); // implies self.other_children.len() >= 4
+        assert!(self.item_count() + 1 >= self.max_capacity()); // full or overflowing, implies self.other_children.len() >= 3
 
         // Index of the median-key item in `other_children`.
         let median_i = (self.item_count() - 1) / 2; // Since the knuth-order is at least 3, `median_i` is at least 1.
 
-        // Put all the branches on the right of the median pivot in `right_branches`.
-        let right_len = self.item_count() - median_i - 1;
-        let mut right_branches = Vec::new(); // Note: branches are stored in reverse order.
-        for i in 0..right_len {
-            let offset: Offset = (median_i + right_len - i).into();
-            let (item, right_child_id) = self.remove(offset);
-            right_branches.push((item, right_child_id));
-        }
-
-        let mut right_node = S::InternalNode::default();
+        // Move all the branches on the right of the median pivot to the new node.
+        let mut right_node = self.drain_from((median_i + 1).into());
         right_node.set_parent(self.parent());
 
         // Remove the median pivot.
         let (median_item, median_right_child) = self.remove(median_i.into());
         right_node.set_first_child_id(median_right_child);
 
-        // Move the right branches to the other node.
-        for (item, child_id) in right_branches.into_iter().rev() {
-            right_node.push_right(item, child_id);
-        }
-
         assert!(!self.is_underflowing());
         // assert!(!right_node.is_underflowing());
 
@@ -264,7 +257,7 @@ impl<'b, S: Storage, R: InternalRef<S>> Iterator for Children<'b, S, R> {
 
 pub struct Items<'b, S, R: ?Sized> {
     node: &'b R,
-    offset: Offset,
+    offset: Position,
     storage: PhantomData<S>,
 }
 
@@ -1,4 +1,4 @@
-use super::{Offset, Storage, StorageMut};
+use super::{Position, Storage, StorageMut};
 
 /// Type that can access an item by offset.
 ///
@@ -13,7 +13,66 @@ pub trait ItemAccess<S: Storage> {
     }
 
     /// Borrow the item at the given offset, if any.
-    fn borrow_item(&self, offset: Offset) -> Option<S::ItemRef<'_>>;
+    fn borrow_item(&self, offset: Position) -> Option<S::ItemRef<'_>>;
+
+    /// Returns the keys of the node as a contiguous slice of primitive integers, if possible.
+    ///
+    /// Along with [`KeyPartialOrd::raw_key`](crate::KeyPartialOrd::raw_key),
+    /// this allows searching the node with SIMD instructions (see the `simd` feature).
+    /// The default implementation returns `None`.
+    #[inline]
+    fn raw_keys(&self) -> Option<RawKeys<'_>> {
+        None
+    }
+}
+
+macro_rules! raw_keys {
+    ($($id:ident: $ty:ty),*) => {
+        /// Contiguous slice of primitive integer keys.
+        ///
+        /// See [`ItemAccess::raw_keys`].
+        #[derive(Clone, Copy, Debug)]
+        pub enum RawKeys<'a> {
+            $($id(&'a [$ty])),*
+        }
+
+        /// Primitive integer key.
+        ///
+        /// See [`KeyPartialOrd::raw_key`](crate::KeyPartialOrd::raw_key).
+        #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+        pub enum RawKey {
+            $($id($ty)),*
+        }
+
+        $(
+            impl<'a> From<&'a [$ty]> for RawKeys<'a> {
+                #[inline]
+                fn from(keys: &'a [$ty]) -> Self {
+                    Self::$id(keys)
+                }
+            }
+
+            impl From<$ty> for RawKey {
+                #[inline]
+                fn from(key: $ty) -> Self {
+                    Self::$id(key)
+                }
+            }
+        )*
+    };
+}
+
+raw_keys! {
+    U8: u8,
+    U16: u16,
+    U32: u32,
+    U64: u64,
+    Usize: usize,
+    I8: i8,
+    I16: i16,
+    I32: i32,
+    I64: i64,
+    Isize: isize
 }
 
 /// Item reference.
@@ -49,24 +108,21 @@ pub unsafe trait Write<S: StorageMut> {
     unsafe fn write(&mut self, item: S::Item);
 }
 
-/// Item mutable reference that can be replaced using a value of type `T`.
-pub trait Replace<S: StorageMut, T> {
+/// Storage whose items can be replaced using a value of type `T`.
+pub trait Replace<T>: StorageMut {
     type Output;
 
     /// Replace the item (or part of the item) using the given value of type `T`.
     ///
     /// Returns a `Self::Output` representing the replaced value.
-    fn replace(&mut self, item: T) -> Self::Output;
+    fn replace(item: &mut Self::ItemMut<'_>, value: T) -> Self::Output;
 }
 
-impl<S: StorageMut, T> Replace<S, S::Item> for T
-where
-    T: Mut<S>,
-{
+impl<S: StorageMut> Replace<S::Item> for S {
     type Output = S::Item;
 
-    fn replace(&mut self, mut item: S::Item) -> S::Item {
-        self.swap(&mut item);
-        item
+    fn replace(item: &mut S::ItemMut<'_>, mut value: S::Item) -> S::Item {
+        item.swap(&mut value);
+        value
     }
 }
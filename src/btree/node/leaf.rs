@@ -1,6 +1,6 @@
-use super::{item::Replace, ItemAccess, KeyPartialOrd, Offset, Storage, StorageMut};
+use super::{item::Replace, ItemAccess, KeyPartialOrd, Position, Storage, StorageMut};
 use crate::util::binary_search_min;
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
 /// Leaf node reference.
 pub trait LeafRef<S: Storage>: ItemAccess<S> {
@@ -9,7 +9,7 @@ pub trait LeafRef<S: Storage>: ItemAccess<S> {
 
     /// Find the offset of the item matching the given key.
     #[inline]
-    fn offset_of<Q: ?Sized>(&self, key: &Q) -> Result<Offset, Offset>
+    fn offset_of<Q: ?Sized>(&self, key: &Q) -> Result<Position, Position>
     where
         S: KeyPartialOrd<Q>,
     {
@@ -70,7 +70,7 @@ pub trait LeafRef<S: Storage>: ItemAccess<S> {
 /// implementing this trait requires implementing the
 /// [`LeafRef`] trait.
 pub trait LeafConst<'a, S: 'a + Storage>: LeafRef<S> {
-    fn item(&self, offset: Offset) -> Option<S::ItemRef<'a>>;
+    fn item(&self, offset: Position) -> Option<S::ItemRef<'a>>;
 
     #[inline]
     fn get<Q: ?Sized>(&self, key: &Q) -> Option<S::ItemRef<'a>>
@@ -101,16 +101,16 @@ pub trait LeafMut<'a, S: 'a + StorageMut>: Sized + LeafRef<S> {
     fn set_parent(&mut self, parent: Option<usize>);
 
     /// Returns a mutable reference to the item with the given offset in the node.
-    fn item_mut(&mut self, offset: Offset) -> Option<S::ItemMut<'_>>;
+    fn item_mut(&mut self, offset: Position) -> Option<S::ItemMut<'_>>;
 
     /// Turns this node reference int a mutable reference to the item at the given offset.
-    fn into_item_mut(self, offset: Offset) -> Option<S::ItemMut<'a>>;
+    fn into_item_mut(self, offset: Position) -> Option<S::ItemMut<'a>>;
 
     /// Inserts an item at the given offset in the node.
-    fn insert(&mut self, offset: Offset, item: S::Item);
+    fn insert(&mut self, offset: Position, item: S::Item);
 
     /// Removes and returns the item at the given offset.
-    fn remove(&mut self, offset: Offset) -> S::Item;
+    fn remove(&mut self, offset: Position) -> S::Item;
 
     #[inline]
     fn remove_last(&mut self) -> S::Item {
@@ -121,14 +121,20 @@ pub trait LeafMut<'a, S: 'a + StorageMut>: Sized + LeafRef<S> {
     /// Replaces the item at the given offset.
     ///
     /// Returns the old item.
-    fn replace(&mut self, offset: Offset, item: S::Item) -> S::Item {
-        self.item_mut(offset).unwrap().replace(item)
+    fn replace(&mut self, offset: Position, item: S::Item) -> S::Item {
+        S::replace(&mut self.item_mut(offset).unwrap(), item)
     }
 
     /// Appends the separator and all the items of `other` to this node.
     ///
     /// Returns the offset of the separator.
-    fn append(&mut self, separator: S::Item, other: S::LeafNode) -> Offset;
+    fn append(&mut self, separator: S::Item, other: S::LeafNode) -> Position;
+
+    /// Removes all the items from the given offset to the end of the node
+    /// and moves them into a new leaf buffer node.
+    ///
+    /// The parent of the returned node is left unset.
+    fn drain_from(&mut self, offset: Position) -> S::LeafNode;
 
     /// Returns a mutable reference to the item matching the given key in this node, if any.
     #[inline]
@@ -159,31 +165,18 @@ pub trait LeafMut<'a, S: 'a + StorageMut>: Sized + LeafRef<S> {
     #[inline]
     fn split(&mut self) -> (usize, S::Item, S::LeafNode) {
         use crate::btree::node::buffer::Leaf;
-        assert!(self.is_overflowing());
+        assert!(self.item_count() + 1 >= self.max_capacity()); // full or overflowing
 
         // Index of the median-key item in `other_children`.
         let median_i = (self.item_count() - 1) / 2; // Since the knuth-order is at least 3, `median_i` is at least 1.
 
-        // Put all the branches on the right of the median pivot in `right_branches`.
-        let right_len = self.item_count() - median_i - 1;
-        let mut right_branches = Vec::new(); // Note: branches are stored in reverse order.
-        for i in 0..right_len {
-            let offset: Offset = (median_i + right_len - i).into();
-            let item = self.remove(offset);
-            right_branches.push(item);
-        }
-
-        let mut right_node = S::LeafNode::default();
+        // Move all the items on the right of the median pivot to the new node.
+        let mut right_node = self.drain_from((median_i + 1).into());
         right_node.set_parent(self.parent());
 
         // Remove the median pivot.
         let median_item = self.remove(median_i.into());
 
-        // Move the right branches to the other node.
-        for item in right_branches.into_iter().rev() {
-            right_node.push_right(item);
-        }
-
         assert!(!self.is_underflowing());
         // assert!(!right_node.is_underflowing());
 
@@ -194,7 +187,7 @@ pub trait LeafMut<'a, S: 'a + StorageMut>: Sized + LeafRef<S> {
 /// Iterator to the items of a leaf node.
 pub struct Items<'b, S, R: ?Sized> {
     node: &'b R,
-    offset: Offset,
+    offset: Position,
     storage: PhantomData<S>,
 }
 
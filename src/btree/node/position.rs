@@ -0,0 +1,208 @@
+use core::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, Div, Sub},
+};
+
+/// Position in a node.
+///
+/// A position is either the offset of an item in the node,
+/// or the position before the first item of the node,
+/// which we write `-1`.
+///
+/// Positions are ordered, `Before` being less than any offset.
+/// Arithmetic operations treat `Before` as `-1`,
+/// and panic if the result is less than `-1`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Position {
+    /// Before the first item of the node.
+    Before,
+
+    /// Offset of an item in the node.
+    At(usize),
+}
+
+impl Position {
+    /// Checks if this is the `Before` position.
+    #[inline]
+    pub fn is_before(&self) -> bool {
+        matches!(self, Self::Before)
+    }
+
+    /// Get the offset of this position if any.
+    ///
+    /// Returns `None` if this is the `Before` position.
+    #[inline]
+    pub fn value(&self) -> Option<usize> {
+        match self {
+            Self::Before => None,
+            Self::At(offset) => Some(*offset),
+        }
+    }
+
+    /// Turns this position into its offset.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if this is the `Before` position.
+    #[inline]
+    pub fn unwrap(self) -> usize {
+        match self {
+            Self::Before => panic!("position out of bounds"),
+            Self::At(offset) => offset,
+        }
+    }
+
+    /// Increment the position by one.
+    ///
+    /// The `Before` position is turned into
+    /// the offset 0.
+    #[inline]
+    pub fn incr(&mut self) {
+        *self = match *self {
+            Self::Before => Self::At(0),
+            Self::At(offset) => Self::At(offset + 1),
+        }
+    }
+
+    /// Decrement the position by one.
+    ///
+    /// The offset 0 becomes the `Before` position.
+    /// The `Before` position is unchanged.
+    #[inline]
+    pub fn decr(&mut self) {
+        *self = match *self {
+            Self::Before | Self::At(0) => Self::Before,
+            Self::At(offset) => Self::At(offset - 1),
+        }
+    }
+
+    /// Returns the position `n` items after `before`, `-1 + n`.
+    #[inline]
+    fn after_before(n: usize) -> Self {
+        match n.checked_sub(1) {
+            Some(offset) => Self::At(offset),
+            None => Self::Before,
+        }
+    }
+
+    /// Returns the position `offset - n`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the result is less than `-1`.
+    #[inline]
+    fn at_minus(offset: usize, n: usize) -> Self {
+        match offset.checked_sub(n) {
+            Some(offset) => Self::At(offset),
+            None if offset + 1 == n => Self::Before,
+            None => panic!("position underflow"),
+        }
+    }
+}
+
+impl PartialEq<usize> for Position {
+    #[inline]
+    fn eq(&self, offset: &usize) -> bool {
+        *self == Self::At(*offset)
+    }
+}
+
+impl PartialOrd<usize> for Position {
+    #[inline]
+    fn partial_cmp(&self, offset: &usize) -> Option<Ordering> {
+        self.partial_cmp(&Self::At(*offset))
+    }
+}
+
+impl Add for Position {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        match (self, rhs) {
+            (Self::Before, Self::Before) => panic!("position underflow"),
+            (Self::Before, Self::At(n)) | (Self::At(n), Self::Before) => Self::after_before(n),
+            (Self::At(a), Self::At(b)) => Self::At(a + b),
+        }
+    }
+}
+
+impl Add<usize> for Position {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: usize) -> Self {
+        match self {
+            Self::Before => Self::after_before(rhs),
+            Self::At(offset) => Self::At(offset + rhs),
+        }
+    }
+}
+
+impl Sub for Position {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        match (self, rhs) {
+            (Self::Before, Self::Before) => Self::At(0),
+            (Self::Before, Self::At(0)) => Self::Before,
+            (Self::Before, Self::At(_)) => panic!("position underflow"),
+            (mut position @ Self::At(_), Self::Before) => {
+                position.incr();
+                position
+            }
+            (Self::At(a), Self::At(b)) => Self::at_minus(a, b),
+        }
+    }
+}
+
+impl Sub<usize> for Position {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: usize) -> Self {
+        match self {
+            Self::Before if rhs == 0 => Self::Before,
+            Self::Before => panic!("position underflow"),
+            Self::At(offset) => Self::at_minus(offset, rhs),
+        }
+    }
+}
+
+impl Div<usize> for Position {
+    type Output = Self;
+
+    /// Divides the offset of the position.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if this is the `Before` position.
+    #[inline]
+    fn div(self, rhs: usize) -> Self {
+        Self::At(self.unwrap() / rhs)
+    }
+}
+
+impl From<usize> for Position {
+    #[inline]
+    fn from(offset: usize) -> Position {
+        Self::At(offset)
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Before => write!(f, "-1"),
+            Self::At(offset) => offset.fmt(f),
+        }
+    }
+}
+
+impl fmt::Debug for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
@@ -0,0 +1,32 @@
+use core::ptr::NonNull;
+
+/// Raw handle on a node of a mutably borrowed storage.
+///
+/// Raw nodes are returned by [`StorageMut::raw_node`](crate::StorageMut::raw_node)
+/// when the mutable iterators enter a node.
+/// The node is then only accessed through [`StorageMut::raw_child_id`](crate::StorageMut::raw_child_id)
+/// and [`StorageMut::raw_item_mut`](crate::StorageMut::raw_item_mut),
+/// which must not borrow it again,
+/// so that the items already emitted from the node stay valid.
+#[derive(Clone, Copy, Debug)]
+pub struct Raw {
+    /// Identifier of the node.
+    pub id: usize,
+
+    /// Number of items in the node.
+    pub item_count: usize,
+
+    /// Identifier of the first child of the node, or `None` if it is a leaf.
+    pub first_child_id: Option<usize>,
+
+    /// Storage-specific pointer to the slots of the node.
+    pub ptr: NonNull<u8>,
+}
+
+impl Raw {
+    /// Checks if the node is internal.
+    #[inline]
+    pub fn is_internal(&self) -> bool {
+        self.first_child_id.is_some()
+    }
+}
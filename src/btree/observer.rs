@@ -0,0 +1,45 @@
+use super::node::{Address, Position};
+
+/// Observer of the structural modifications of a tree.
+///
+/// A storage may provide an observer (see [`StorageMut::observer`](crate::StorageMut::observer)),
+/// notified by the rebalancing algorithms every time nodes are split, merged or rotated,
+/// and every time an item moves from one node to another.
+/// This lets external invariant checkers, metrics,
+/// or secondary structures tracking item locations follow the tree.
+///
+/// Items shifting inside a node, when an item is inserted or removed before them,
+/// are not reported.
+/// Nodes moved from one storage to another,
+/// by [`split_off`](crate::StorageMut::split_off) or [`append`](crate::StorageMut::append),
+/// are not reported either: their items leave the tree or enter it from another tree.
+///
+/// Every method does nothing by default.
+pub trait Observer {
+    /// The node `id` has been split around the item at offset `median`.
+    ///
+    /// The items of `id` after `median` have moved, in order,
+    /// to the new node `right` starting at offset `0`,
+    /// and the median item moved to the parent node (reported with [`on_move`](Self::on_move)).
+    fn on_split(&mut self, _id: usize, _right: usize, _median: Position) {}
+
+    /// The node `right` has been merged into its left sibling `left`.
+    ///
+    /// The separator item moved from the parent node to `left` at offset `separator`
+    /// (reported with [`on_move`](Self::on_move)),
+    /// followed, in order, by the items of `right`, which is released.
+    fn on_merge(&mut self, _left: usize, _right: usize, _separator: Position) {}
+
+    /// An item has been rotated from the node `from` to its sibling `to`, through their parent.
+    ///
+    /// The two item moves are reported with [`on_move`](Self::on_move).
+    fn on_rotate(&mut self, _from: usize, _to: usize) {}
+
+    /// The item at address `from` has moved to the address `to`, in another node.
+    ///
+    /// This is also reported on its own when an item removed from an internal node
+    /// is replaced by an item of a leaf,
+    /// and when items are moved by [`split_off`](crate::StorageMut::split_off)
+    /// or [`append`](crate::StorageMut::append) within the storage.
+    fn on_move(&mut self, _from: Address, _to: Address) {}
+}
@@ -0,0 +1,157 @@
+use core::fmt;
+
+/// Policy deciding how many items the nodes of a tree must hold.
+///
+/// A node underflows when it holds less items than its minimum capacity,
+/// in which case the rebalancing algorithms rotate items from one of its siblings,
+/// or merge it with a sibling.
+/// A storage may choose a policy
+/// (see [`Storage::capacity_policy`](crate::Storage::capacity_policy))
+/// to trade space for fewer rebalancing operations on removal.
+///
+/// Since nodes are split in two and merged two at a time,
+/// the minimum capacity returned by the policy must be at least `1`,
+/// so that empty nodes are always removed,
+/// and at most `max_capacity / 2 - 1` (see [`max_min_capacity`]),
+/// so that split nodes do not underflow and merged nodes do not overflow.
+/// Other values are rejected by [`min_capacity`].
+/// In particular, the fill factors of B*-trees, which split two nodes into three,
+/// are not supported.
+pub trait CapacityPolicy: Send + Sync {
+    /// Returns the minimum capacity of a node holding at most `max_capacity` items,
+    /// given the `default` minimum capacity of the node
+    /// (see [`Reference::min_capacity`](crate::node::Reference::min_capacity)).
+    fn min_capacity(&self, max_capacity: usize, default: usize) -> usize;
+}
+
+/// Returns the greatest valid minimum capacity of a node
+/// holding at most `max_capacity` items, `max_capacity / 2 - 1`.
+#[inline]
+pub fn max_min_capacity(max_capacity: usize) -> usize {
+    (max_capacity / 2).saturating_sub(1)
+}
+
+/// Returns the minimum capacity required by `policy`
+/// for a node holding at most `max_capacity` items,
+/// given the `default` minimum capacity of the node.
+///
+/// Returns an error if the minimum capacity is not between `1`
+/// and [`max_min_capacity(max_capacity)`](max_min_capacity).
+#[inline]
+pub fn min_capacity(
+    policy: &dyn CapacityPolicy,
+    max_capacity: usize,
+    default: usize,
+) -> Result<usize, InvalidCapacityPolicy> {
+    let min_capacity = policy.min_capacity(max_capacity, default);
+    if (1..=max_min_capacity(max_capacity)).contains(&min_capacity) {
+        Ok(min_capacity)
+    } else {
+        Err(InvalidCapacityPolicy {
+            max_capacity,
+            min_capacity,
+        })
+    }
+}
+
+/// Error raised when a capacity policy requires an invalid minimum capacity.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct InvalidCapacityPolicy {
+    /// Maximum capacity of the node.
+    pub max_capacity: usize,
+
+    /// Minimum capacity required by the policy.
+    pub min_capacity: usize,
+}
+
+impl fmt::Display for InvalidCapacityPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid minimum capacity {} for nodes of capacity {} (expected 1 to {})",
+            self.min_capacity,
+            self.max_capacity,
+            max_min_capacity(self.max_capacity)
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidCapacityPolicy {}
+
+/// Standard policy, using the default minimum capacity of each node,
+/// about half its maximum capacity.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct Standard;
+
+impl CapacityPolicy for Standard {
+    #[inline]
+    fn min_capacity(&self, _max_capacity: usize, default: usize) -> usize {
+        default
+    }
+}
+
+/// Lazy deletion policy, where nodes only underflow
+/// when they hold less than the given number of items.
+///
+/// `Lazy(1)` only removes empty nodes: removals almost never rebalance the tree,
+/// at the cost of sparse nodes after many removals.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Lazy(pub usize);
+
+impl CapacityPolicy for Lazy {
+    #[inline]
+    fn min_capacity(&self, _max_capacity: usize, _default: usize) -> usize {
+        self.0
+    }
+}
+
+/// Fill policy, where nodes underflow when they are less than
+/// `numerator / denominator` full.
+///
+/// The fill factor must keep the minimum capacity
+/// under [`max_min_capacity`], slightly less than half the capacity:
+/// higher fill factors, such as the 2/3 fill of B*-trees, are rejected
+/// (see [`CapacityPolicy`]).
+///
+/// The fill factor is checked by [`Fill::new`], which is the only way to create this policy.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Fill {
+    numerator: usize,
+    denominator: usize,
+}
+
+impl Fill {
+    /// Creates a new fill policy.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `denominator` is zero.
+    #[inline]
+    pub fn new(numerator: usize, denominator: usize) -> Self {
+        assert!(denominator > 0, "denominator must not be zero");
+        Self {
+            numerator,
+            denominator,
+        }
+    }
+
+    /// Returns the numerator of the fill factor.
+    #[inline]
+    pub fn numerator(&self) -> usize {
+        self.numerator
+    }
+
+    /// Returns the denominator of the fill factor, never zero.
+    #[inline]
+    pub fn denominator(&self) -> usize {
+        self.denominator
+    }
+}
+
+impl CapacityPolicy for Fill {
+    #[inline]
+    fn min_capacity(&self, max_capacity: usize, _default: usize) -> usize {
+        max_capacity * self.numerator / self.denominator
+    }
+}
@@ -0,0 +1,307 @@
+use super::{ItemPartialOrd, Iter, Storage};
+use core::{
+    cmp::{self, Ordering},
+    iter::{FusedIterator, Peekable},
+};
+
+/// Compares the next items of two iterators.
+///
+/// Items that cannot be compared are considered distinct,
+/// the item of `a` coming first.
+/// Returns `None` if both iterators are exhausted.
+#[inline]
+fn next_ordering<'a, A: ItemPartialOrd<B>, B: Storage>(
+    a: &mut Peekable<Iter<'a, A>>,
+    b: &mut Peekable<Iter<'a, B>>,
+) -> Option<Ordering> {
+    match (a.peek(), b.peek()) {
+        (Some(a), Some(b)) => Some(A::item_partial_cmp(a, b).unwrap_or(Ordering::Less)),
+        (Some(_), None) => Some(Ordering::Less),
+        (None, Some(_)) => Some(Ordering::Greater),
+        (None, None) => None,
+    }
+}
+
+/// Lazy iterator over the items of a tree that are not in another tree.
+///
+/// This structure is created by the [`difference`](crate::Storage::difference) method.
+pub struct Difference<'a, A: Storage, B: Storage> {
+    a: Peekable<Iter<'a, A>>,
+    b: Peekable<Iter<'a, B>>,
+}
+
+impl<'a, A: Storage, B: Storage> Difference<'a, A, B> {
+    #[inline]
+    pub(crate) fn new(a: &'a A, b: &'a B) -> Self {
+        Self {
+            a: a.iter().peekable(),
+            b: b.iter().peekable(),
+        }
+    }
+}
+
+impl<'a, A: ItemPartialOrd<B>, B: Storage> Iterator for Difference<'a, A, B> {
+    type Item = A::ItemRef<'a>;
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (len, _) = self.a.size_hint();
+        let (other_len, _) = self.b.size_hint();
+        (len.saturating_sub(other_len), Some(len))
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match next_ordering(&mut self.a, &mut self.b)? {
+                Ordering::Less => return self.a.next(),
+                Ordering::Equal => {
+                    self.a.next();
+                    self.b.next();
+                }
+                Ordering::Greater => {
+                    self.b.next();
+                }
+            }
+        }
+    }
+}
+
+impl<'a, A: ItemPartialOrd<B>, B: Storage> FusedIterator for Difference<'a, A, B> {}
+
+/// Lazy iterator over the items of a tree that are also in another tree.
+///
+/// This structure is created by the [`intersection`](crate::Storage::intersection) method.
+pub struct Intersection<'a, A: Storage, B: Storage> {
+    a: Peekable<Iter<'a, A>>,
+    b: Peekable<Iter<'a, B>>,
+}
+
+impl<'a, A: Storage, B: Storage> Intersection<'a, A, B> {
+    #[inline]
+    pub(crate) fn new(a: &'a A, b: &'a B) -> Self {
+        Self {
+            a: a.iter().peekable(),
+            b: b.iter().peekable(),
+        }
+    }
+}
+
+impl<'a, A: ItemPartialOrd<B>, B: Storage> Iterator for Intersection<'a, A, B> {
+    type Item = A::ItemRef<'a>;
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (len, _) = self.a.size_hint();
+        let (other_len, _) = self.b.size_hint();
+        (0, Some(cmp::min(len, other_len)))
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match next_ordering(&mut self.a, &mut self.b)? {
+                Ordering::Less => {
+                    self.a.next()?;
+                }
+                Ordering::Equal => {
+                    self.b.next();
+                    return self.a.next();
+                }
+                Ordering::Greater => {
+                    self.b.next()?;
+                }
+            }
+        }
+    }
+}
+
+impl<'a, A: ItemPartialOrd<B>, B: Storage> FusedIterator for Intersection<'a, A, B> {}
+
+/// Lazy iterator over the items of two trees, without duplicates.
+///
+/// This structure is created by the [`union`](crate::Storage::union) method.
+pub struct Union<'a, S: Storage> {
+    a: Peekable<Iter<'a, S>>,
+    b: Peekable<Iter<'a, S>>,
+}
+
+impl<'a, S: Storage> Union<'a, S> {
+    #[inline]
+    pub(crate) fn new(a: &'a S, b: &'a S) -> Self {
+        Self {
+            a: a.iter().peekable(),
+            b: b.iter().peekable(),
+        }
+    }
+}
+
+impl<'a, S: ItemPartialOrd<S>> Iterator for Union<'a, S> {
+    type Item = S::ItemRef<'a>;
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (len, _) = self.a.size_hint();
+        let (other_len, _) = self.b.size_hint();
+        (cmp::max(len, other_len), Some(len + other_len))
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match next_ordering(&mut self.a, &mut self.b)? {
+            Ordering::Less => self.a.next(),
+            Ordering::Equal => {
+                self.b.next();
+                self.a.next()
+            }
+            Ordering::Greater => self.b.next(),
+        }
+    }
+}
+
+impl<'a, S: ItemPartialOrd<S>> FusedIterator for Union<'a, S> {}
+
+/// Lazy iterator over the items of two trees that are in only one of them.
+///
+/// This structure is created by the
+/// [`symmetric_difference`](crate::Storage::symmetric_difference) method.
+pub struct SymmetricDifference<'a, S: Storage> {
+    a: Peekable<Iter<'a, S>>,
+    b: Peekable<Iter<'a, S>>,
+}
+
+impl<'a, S: Storage> SymmetricDifference<'a, S> {
+    #[inline]
+    pub(crate) fn new(a: &'a S, b: &'a S) -> Self {
+        Self {
+            a: a.iter().peekable(),
+            b: b.iter().peekable(),
+        }
+    }
+}
+
+impl<'a, S: ItemPartialOrd<S>> Iterator for SymmetricDifference<'a, S> {
+    type Item = S::ItemRef<'a>;
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (len, _) = self.a.size_hint();
+        let (other_len, _) = self.b.size_hint();
+        (0, Some(len + other_len))
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match next_ordering(&mut self.a, &mut self.b)? {
+                Ordering::Less => return self.a.next(),
+                Ordering::Equal => {
+                    self.a.next();
+                    self.b.next();
+                }
+                Ordering::Greater => return self.b.next(),
+            }
+        }
+    }
+}
+
+impl<'a, S: ItemPartialOrd<S>> FusedIterator for SymmetricDifference<'a, S> {}
+
+/// Item of a [`Merge`] iterator.
+///
+/// Tells from which tree(s) the visited item comes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Merged<A, B> {
+    /// The item is only in the first tree.
+    Left(A),
+
+    /// The item is only in the second tree.
+    Right(B),
+
+    /// Equivalent items are in both trees.
+    Both(A, B),
+}
+
+impl<A, B> Merged<A, B> {
+    /// Returns the item of the first tree, if any.
+    #[inline]
+    pub fn left(self) -> Option<A> {
+        match self {
+            Self::Left(a) | Self::Both(a, _) => Some(a),
+            Self::Right(_) => None,
+        }
+    }
+
+    /// Returns the item of the second tree, if any.
+    #[inline]
+    pub fn right(self) -> Option<B> {
+        match self {
+            Self::Right(b) | Self::Both(_, b) => Some(b),
+            Self::Left(_) => None,
+        }
+    }
+}
+
+impl<T> Merged<T, T> {
+    /// Returns the item of the first tree if any, or the item of the second tree.
+    #[inline]
+    pub fn prefer_left(self) -> T {
+        match self {
+            Self::Left(a) | Self::Both(a, _) => a,
+            Self::Right(b) => b,
+        }
+    }
+
+    /// Returns the item of the second tree if any, or the item of the first tree.
+    #[inline]
+    pub fn prefer_right(self) -> T {
+        match self {
+            Self::Right(b) | Self::Both(_, b) => b,
+            Self::Left(a) => a,
+        }
+    }
+}
+
+/// Lazy iterator over the items of two trees, merged in order.
+///
+/// This structure is created by the [`merge_iter`](crate::Storage::merge_iter) method.
+pub struct Merge<'a, A: Storage, B: Storage> {
+    a: Peekable<Iter<'a, A>>,
+    b: Peekable<Iter<'a, B>>,
+}
+
+impl<'a, A: Storage, B: Storage> Merge<'a, A, B> {
+    #[inline]
+    pub(crate) fn new(a: &'a A, b: &'a B) -> Self {
+        Self {
+            a: a.iter().peekable(),
+            b: b.iter().peekable(),
+        }
+    }
+}
+
+impl<'a, A: ItemPartialOrd<B>, B: Storage> Iterator for Merge<'a, A, B> {
+    type Item = Merged<A::ItemRef<'a>, B::ItemRef<'a>>;
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (len, _) = self.a.size_hint();
+        let (other_len, _) = self.b.size_hint();
+        (cmp::max(len, other_len), Some(len + other_len))
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match next_ordering(&mut self.a, &mut self.b)? {
+            Ordering::Less => self.a.next().map(Merged::Left),
+            Ordering::Equal => {
+                let a = self.a.next().unwrap();
+                let b = self.b.next().unwrap();
+                Some(Merged::Both(a, b))
+            }
+            Ordering::Greater => self.b.next().map(Merged::Right),
+        }
+    }
+}
+
+impl<'a, A: ItemPartialOrd<B>, B: Storage> FusedIterator for Merge<'a, A, B> {}
@@ -0,0 +1,87 @@
+use alloc::vec::Vec;
+
+/// Tree statistics, returned by [`Storage::stats`](crate::Storage::stats).
+///
+/// Useful to check how well the order of the nodes fits the workload:
+/// a low occupancy means that nodes are often split or merged
+/// and waste space, while a high tree means slower lookups.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Stats {
+    /// Height of the tree, `0` if the tree is empty.
+    pub height: usize,
+
+    /// Number of internal nodes.
+    pub internal_nodes: usize,
+
+    /// Number of leaf nodes.
+    pub leaf_nodes: usize,
+
+    /// Number of items.
+    pub items: usize,
+
+    /// Maximum number of items the nodes can hold without overflowing.
+    pub capacity: usize,
+
+    /// Statistics of each level of the tree, starting from the root.
+    pub levels: Vec<LevelStats>,
+
+    /// Estimated size of the nodes in memory, in bytes,
+    /// or `None` if the tree is empty or the storage does not provide an estimate
+    /// (see [`Storage::node_bytes`](crate::Storage::node_bytes)).
+    pub bytes: Option<usize>,
+}
+
+impl Stats {
+    /// Returns the total number of nodes.
+    #[inline]
+    pub fn node_count(&self) -> usize {
+        self.internal_nodes + self.leaf_nodes
+    }
+
+    /// Returns the average number of items per node.
+    #[inline]
+    pub fn average_items(&self) -> f64 {
+        ratio(self.items, self.node_count())
+    }
+
+    /// Returns the ratio of used node capacity, between `0` and `1`.
+    #[inline]
+    pub fn occupancy(&self) -> f64 {
+        ratio(self.items, self.capacity)
+    }
+}
+
+/// Statistics of a single level of a tree.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct LevelStats {
+    /// Number of nodes in the level.
+    pub nodes: usize,
+
+    /// Number of items in the level.
+    pub items: usize,
+
+    /// Maximum number of items the nodes of the level can hold without overflowing.
+    pub capacity: usize,
+}
+
+impl LevelStats {
+    /// Returns the average number of items per node of the level.
+    #[inline]
+    pub fn average_items(&self) -> f64 {
+        ratio(self.items, self.nodes)
+    }
+
+    /// Returns the ratio of used node capacity in the level, between `0` and `1`.
+    #[inline]
+    pub fn occupancy(&self) -> f64 {
+        ratio(self.items, self.capacity)
+    }
+}
+
+fn ratio(a: usize, b: usize) -> f64 {
+    if b == 0 {
+        0.0
+    } else {
+        a as f64 / b as f64
+    }
+}
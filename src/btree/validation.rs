@@ -0,0 +1,218 @@
+use super::{KeyOrd, Storage, ValidationError};
+use alloc::{collections::BTreeSet, vec::Vec};
+
+/// Issue found while validating a tree.
+#[derive(Debug)]
+pub struct ValidationIssue {
+    /// Error.
+    pub error: ValidationError,
+
+    /// Identifiers of the nodes on the path from the root to the offending node,
+    /// including it.
+    pub path: Vec<usize>,
+}
+
+impl ValidationIssue {
+    /// Returns the identifier of the offending node.
+    #[inline]
+    pub fn node(&self) -> usize {
+        *self.path.last().unwrap()
+    }
+
+    /// Returns the depth of the offending node, the root having depth `0`.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.path.len() - 1
+    }
+}
+
+/// Tree validation report, returned by [`Storage::validation_report`].
+///
+/// Contrarily to [`Storage::validate`], which stops at the first error,
+/// the whole tree is visited and every issue is reported.
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    /// Issues found, in depth-first order.
+    pub issues: Vec<ValidationIssue>,
+
+    /// Number of visited nodes.
+    pub node_count: usize,
+
+    /// Number of visited items.
+    pub item_count: usize,
+
+    /// Smallest and greatest depth of a leaf, or `None` if the tree is empty.
+    pub leaf_depths: Option<(usize, usize)>,
+}
+
+impl ValidationReport {
+    /// Checks that no issue has been found.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Returns the first error found, if any.
+    #[inline]
+    pub fn into_result(self) -> Result<(), ValidationError> {
+        match self.issues.into_iter().next() {
+            Some(issue) => Err(issue.error),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Depth-first tree validator.
+pub(crate) struct Validator<'a, S> {
+    storage: &'a S,
+
+    /// Path from the root to the current node.
+    path: Vec<usize>,
+
+    /// Visited nodes.
+    visited: BTreeSet<usize>,
+
+    /// Last visited leaf.
+    previous_leaf: Option<usize>,
+
+    report: ValidationReport,
+}
+
+impl<'a, S: Storage + KeyOrd> Validator<'a, S> {
+    pub fn new(storage: &'a S) -> Self {
+        Self {
+            storage,
+            path: Vec::new(),
+            visited: BTreeSet::new(),
+            previous_leaf: None,
+            report: ValidationReport::default(),
+        }
+    }
+
+    /// Validates the whole tree.
+    pub fn run(mut self) -> ValidationReport {
+        if let Some(id) = self.storage.root() {
+            self.node(id, None, None, None);
+        }
+
+        if let Some(id) = self.previous_leaf {
+            self.check_links(id, |links| links.next.is_none())
+        }
+
+        if self.report.item_count != self.storage.len() {
+            self.report.issues.push(ValidationIssue {
+                error: ValidationError::WrongLength(self.report.item_count, self.storage.len()),
+                path: self.storage.root().into_iter().collect(),
+            })
+        }
+
+        self.report
+    }
+
+    fn issue(&mut self, error: ValidationError) {
+        self.report.issues.push(ValidationIssue {
+            error,
+            path: self.path.clone(),
+        })
+    }
+
+    /// Checks the leaf sibling links of the leaf `id`, if any.
+    fn check_links(&mut self, id: usize, f: impl FnOnce(super::LeafLinks) -> bool) {
+        if let Some(links) = self.storage.leaf_links(id) {
+            if !f(links) {
+                self.issue(ValidationError::BrokenLink(id))
+            }
+        }
+    }
+
+    /// Validates the subtree rooted at node `id`, and returns its height,
+    /// or `None` if the node is missing.
+    fn node(
+        &mut self,
+        id: usize,
+        parent: Option<usize>,
+        min: Option<S::ItemRef<'a>>,
+        max: Option<S::ItemRef<'a>>,
+    ) -> Option<usize> {
+        self.path.push(id);
+        let height = self.node_in_path(id, parent, min, max);
+        self.path.pop();
+        height
+    }
+
+    fn node_in_path(
+        &mut self,
+        id: usize,
+        parent: Option<usize>,
+        min: Option<S::ItemRef<'a>>,
+        max: Option<S::ItemRef<'a>>,
+    ) -> Option<usize> {
+        if !self.visited.insert(id) {
+            self.issue(ValidationError::DuplicateNode(id));
+            return None;
+        }
+
+        let node = match self.storage.node(id) {
+            Some(node) => node,
+            None => {
+                self.issue(ValidationError::MissingNode(id));
+                return None;
+            }
+        };
+
+        self.report.node_count += 1;
+        self.report.item_count += node.item_count();
+
+        let mut errors = Vec::new();
+        let min_capacity = self.storage.node_min_capacity(id);
+        let (mut min, mut max) =
+            node.validate_into_with(id, parent, min_capacity, min, max, &mut errors);
+        for e in errors {
+            self.issue(e)
+        }
+
+        if !node.is_internal() {
+            let previous = self.previous_leaf;
+            if let Some(previous_id) = previous {
+                self.check_links(previous_id, |links| links.next == Some(id))
+            }
+
+            self.check_links(id, |links| links.previous == previous);
+            self.previous_leaf = Some(id);
+
+            let depth = self.depth();
+            self.report.leaf_depths = Some(match self.report.leaf_depths {
+                Some((min, max)) => (min.min(depth), max.max(depth)),
+                None => (depth, depth),
+            });
+
+            return Some(0);
+        }
+
+        let mut height = None;
+        let mut balanced = true;
+        for (i, child_id) in node.children().enumerate() {
+            let (child_min, child_max) = node.separators(i);
+            let min = child_min.or_else(|| min.take());
+            let max = child_max.or_else(|| max.take());
+
+            if let Some(child_height) = self.node(child_id, Some(id), min, max) {
+                match height {
+                    None => height = Some(child_height),
+                    Some(height) => balanced &= height == child_height,
+                }
+            }
+        }
+
+        if !balanced {
+            self.issue(ValidationError::NotBalanced)
+        }
+
+        height.map(|height| height + 1)
+    }
+
+    /// Depth of the current node.
+    fn depth(&self) -> usize {
+        self.path.len() - 1
+    }
+}
@@ -0,0 +1,127 @@
+use super::{node, Storage};
+use alloc::{collections::VecDeque, vec::Vec};
+use core::ops::ControlFlow;
+
+/// Node traversal order.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Traversal {
+    /// Depth-first (pre-order) traversal.
+    ///
+    /// A node is visited before its children,
+    /// and the children are visited from left to right.
+    DepthFirst,
+
+    /// Breadth-first traversal.
+    ///
+    /// Nodes are visited level by level, from the root,
+    /// and from left to right in each level.
+    BreadthFirst,
+}
+
+/// Node information reported by [`Storage::visit_nodes`].
+pub struct NodeInfo<'a, S: 'a + Storage> {
+    /// Node identifier.
+    id: usize,
+
+    /// Depth of the node (the root has depth `0`).
+    depth: usize,
+
+    /// Node reference.
+    node: node::Ref<'a, S>,
+}
+
+impl<'a, S: 'a + Storage> NodeInfo<'a, S> {
+    /// Returns the identifier of the node.
+    #[inline]
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Returns the depth of the node.
+    ///
+    /// The root node has depth `0`.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the identifier of the parent node, if any.
+    #[inline]
+    pub fn parent(&self) -> Option<usize> {
+        self.node.parent()
+    }
+
+    /// Checks if the node is a leaf.
+    #[inline]
+    pub fn is_leaf(&self) -> bool {
+        !self.node.is_internal()
+    }
+
+    /// Returns the number of items stored in the node.
+    #[inline]
+    pub fn item_count(&self) -> usize {
+        self.node.item_count()
+    }
+
+    /// Returns the number of children of the node.
+    #[inline]
+    pub fn child_count(&self) -> usize {
+        self.node.child_count()
+    }
+
+    /// Returns an iterator over the identifiers of the node children.
+    #[inline]
+    pub fn children(&self) -> node::Children<'_, S, S::InternalRef<'a>> {
+        self.node.children()
+    }
+
+    /// Returns a reference to the node.
+    #[inline]
+    pub fn node(&self) -> &node::Ref<'a, S> {
+        &self.node
+    }
+}
+
+/// Visits every node of `storage` in the given order,
+/// until `f` returns `ControlFlow::Break`.
+pub(crate) fn visit_nodes<S: Storage, B, F>(
+    storage: &S,
+    order: Traversal,
+    mut f: F,
+) -> ControlFlow<B>
+where
+    F: FnMut(NodeInfo<'_, S>) -> ControlFlow<B>,
+{
+    let root = match storage.root() {
+        Some(root) => root,
+        None => return ControlFlow::Continue(()),
+    };
+
+    match order {
+        Traversal::DepthFirst => {
+            let mut stack = Vec::new();
+            stack.push((root, 0));
+            while let Some((id, depth)) = stack.pop() {
+                let node = storage.node(id).unwrap();
+
+                // Children are pushed in reverse so that the leftmost is visited first.
+                let len = stack.len();
+                stack.extend(node.children().map(|child_id| (child_id, depth + 1)));
+                stack[len..].reverse();
+
+                f(NodeInfo { id, depth, node })?
+            }
+        }
+        Traversal::BreadthFirst => {
+            let mut queue = VecDeque::new();
+            queue.push_back((root, 0));
+            while let Some((id, depth)) = queue.pop_front() {
+                let node = storage.node(id).unwrap();
+                queue.extend(node.children().map(|child_id| (child_id, depth + 1)));
+                f(NodeInfo { id, depth, node })?
+            }
+        }
+    }
+
+    ControlFlow::Continue(())
+}
@@ -6,6 +6,12 @@ pub trait Display {
     }
 }
 
+impl<T: Display + ?Sized> Display for &T {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
 pub struct Displayed<'a, T: ?Sized>(&'a T);
 
 impl<'a, T: Display> std::fmt::Display for Displayed<'a, T> {
@@ -13,3 +19,81 @@ impl<'a, T: Display> std::fmt::Display for Displayed<'a, T> {
         self.0.fmt(f)
     }
 }
+
+/// Writes the given node and its descendants in the DOT graph description language.
+///
+/// Nodes are written in depth-first order, using an explicit worklist
+/// so that the stack usage does not depend on the depth of the subtree.
+/// Missing nodes are written as placeholders,
+/// and nodes referenced more than once are only written once.
+///
+/// The `highlight` node is drawn in red,
+/// and the item at address `bad` is given the `bad` port.
+pub(crate) fn write_nodes<S: crate::Storage, W: std::io::Write>(
+    storage: &S,
+    f: &mut W,
+    id: usize,
+    highlight: Option<usize>,
+    bad: Option<crate::node::Address>,
+) -> std::io::Result<()>
+where
+    for<'r> S::ItemRef<'r>: Display,
+{
+    let mut visited = std::collections::BTreeSet::new();
+    let mut worklist = vec![id];
+    while let Some(id) = worklist.pop() {
+        if !visited.insert(id) {
+            continue;
+        }
+
+        let name = format!("n{}", id);
+        let style = if highlight == Some(id) {
+            ", color=red, style=bold"
+        } else {
+            ""
+        };
+
+        let node = match storage.node(id) {
+            Some(node) => node,
+            None => {
+                writeln!(
+                    f,
+                    "\t{} [label=\"missing ({})\", style=dashed{}];",
+                    name, id, style
+                )?;
+                continue;
+            }
+        };
+
+        write!(f, "\t{} [label=\"", name)?;
+        if let Some(parent) = node.parent() {
+            write!(f, "({})|", parent)?;
+        }
+
+        if node.is_internal() {
+            write!(f, "<c0> |")?;
+        }
+
+        for offset in 0..node.item_count() {
+            let item = node.item(offset.into()).unwrap();
+            if bad.map(|addr| addr.id == id && addr.offset == offset) == Some(true) {
+                write!(f, "{{<bad> {}}}|", item.dot())?
+            } else {
+                write!(f, "{{{}}}|", item.dot())?
+            }
+        }
+
+        writeln!(f, "({})\"{}];", id, style)?;
+
+        for child_id in node.children() {
+            writeln!(f, "\t{} -> n{}", name, child_id)?;
+        }
+
+        // Children are pushed in reverse so that the leftmost is written first.
+        let len = worklist.len();
+        worklist.extend(node.children());
+        worklist[len..].reverse();
+    }
+
+    Ok(())
+}
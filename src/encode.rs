@@ -0,0 +1,358 @@
+use std::io::{self, Write};
+
+/// Streaming encoder of a self-describing binary format.
+///
+/// Values are written directly to the underlying writer,
+/// without building any intermediate representation.
+pub trait Encoder {
+    /// Encodes an unsigned integer.
+    fn encode_u64(&mut self, value: u64) -> io::Result<()>;
+
+    /// Encodes a signed integer.
+    fn encode_i64(&mut self, value: i64) -> io::Result<()>;
+
+    /// Encodes a single-precision float.
+    fn encode_f32(&mut self, value: f32) -> io::Result<()>;
+
+    /// Encodes a double-precision float.
+    fn encode_f64(&mut self, value: f64) -> io::Result<()>;
+
+    /// Encodes a boolean.
+    fn encode_bool(&mut self, value: bool) -> io::Result<()>;
+
+    /// Encodes the null value.
+    fn encode_null(&mut self) -> io::Result<()>;
+
+    /// Encodes a UTF-8 string.
+    fn encode_str(&mut self, value: &str) -> io::Result<()>;
+
+    /// Encodes a byte string.
+    fn encode_bytes(&mut self, value: &[u8]) -> io::Result<()>;
+
+    /// Starts an array of `len` elements.
+    ///
+    /// The elements must then be encoded one after the other.
+    fn encode_array_header(&mut self, len: usize) -> io::Result<()>;
+
+    /// Starts a map of `len` entries.
+    ///
+    /// The entries must then be encoded one after the other,
+    /// each key followed by its value.
+    fn encode_map_header(&mut self, len: usize) -> io::Result<()>;
+}
+
+/// Value that can be written with an [`Encoder`].
+pub trait Encode {
+    /// Encodes the value.
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> io::Result<()>;
+}
+
+impl<T: ?Sized + Encode> Encode for &T {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> io::Result<()> {
+        T::encode(self, encoder)
+    }
+}
+
+impl<T: ?Sized + Encode> Encode for &mut T {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> io::Result<()> {
+        T::encode(self, encoder)
+    }
+}
+
+macro_rules! encode_int {
+    ($method:ident as $target:ty: $($ty:ty),*) => {
+        $(
+            impl Encode for $ty {
+                fn encode<E: Encoder>(&self, encoder: &mut E) -> io::Result<()> {
+                    encoder.$method(*self as $target)
+                }
+            }
+        )*
+    };
+}
+
+encode_int!(encode_u64 as u64: u8, u16, u32, u64, usize);
+encode_int!(encode_i64 as i64: i8, i16, i32, i64, isize);
+
+impl Encode for f32 {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> io::Result<()> {
+        encoder.encode_f32(*self)
+    }
+}
+
+impl Encode for f64 {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> io::Result<()> {
+        encoder.encode_f64(*self)
+    }
+}
+
+impl Encode for bool {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> io::Result<()> {
+        encoder.encode_bool(*self)
+    }
+}
+
+impl Encode for () {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> io::Result<()> {
+        encoder.encode_null()
+    }
+}
+
+impl Encode for str {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> io::Result<()> {
+        encoder.encode_str(self)
+    }
+}
+
+impl Encode for String {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> io::Result<()> {
+        encoder.encode_str(self)
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> io::Result<()> {
+        match self {
+            Some(value) => value.encode(encoder),
+            None => encoder.encode_null(),
+        }
+    }
+}
+
+impl<T: Encode> Encode for [T] {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> io::Result<()> {
+        encoder.encode_array_header(self.len())?;
+        for item in self {
+            item.encode(encoder)?
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> io::Result<()> {
+        self.as_slice().encode(encoder)
+    }
+}
+
+/// CBOR encoder ([RFC 8949](https://www.rfc-editor.org/rfc/rfc8949)).
+///
+/// Lengths are always known in advance, so arrays and maps use the definite-length encoding.
+pub struct Cbor<W> {
+    writer: W,
+}
+
+impl<W: Write> Cbor<W> {
+    /// Creates a new encoder writing to the given writer.
+    #[inline]
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Consumes the encoder and returns the underlying writer.
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Writes the head of a data item with the given major type and argument.
+    fn head(&mut self, major: u8, arg: u64) -> io::Result<()> {
+        let major = major << 5;
+        if arg < 24 {
+            self.writer.write_all(&[major | arg as u8])
+        } else if arg <= u8::MAX as u64 {
+            self.writer.write_all(&[major | 24, arg as u8])
+        } else if arg <= u16::MAX as u64 {
+            self.writer.write_all(&[major | 25])?;
+            self.writer.write_all(&(arg as u16).to_be_bytes())
+        } else if arg <= u32::MAX as u64 {
+            self.writer.write_all(&[major | 26])?;
+            self.writer.write_all(&(arg as u32).to_be_bytes())
+        } else {
+            self.writer.write_all(&[major | 27])?;
+            self.writer.write_all(&arg.to_be_bytes())
+        }
+    }
+}
+
+impl<W: Write> Encoder for Cbor<W> {
+    fn encode_u64(&mut self, value: u64) -> io::Result<()> {
+        self.head(0, value)
+    }
+
+    fn encode_i64(&mut self, value: i64) -> io::Result<()> {
+        if value < 0 {
+            self.head(1, !value as u64)
+        } else {
+            self.head(0, value as u64)
+        }
+    }
+
+    fn encode_f32(&mut self, value: f32) -> io::Result<()> {
+        self.writer.write_all(&[0xfa])?;
+        self.writer.write_all(&value.to_be_bytes())
+    }
+
+    fn encode_f64(&mut self, value: f64) -> io::Result<()> {
+        self.writer.write_all(&[0xfb])?;
+        self.writer.write_all(&value.to_be_bytes())
+    }
+
+    fn encode_bool(&mut self, value: bool) -> io::Result<()> {
+        self.writer.write_all(&[if value { 0xf5 } else { 0xf4 }])
+    }
+
+    fn encode_null(&mut self) -> io::Result<()> {
+        self.writer.write_all(&[0xf6])
+    }
+
+    fn encode_str(&mut self, value: &str) -> io::Result<()> {
+        self.head(3, value.len() as u64)?;
+        self.writer.write_all(value.as_bytes())
+    }
+
+    fn encode_bytes(&mut self, value: &[u8]) -> io::Result<()> {
+        self.head(2, value.len() as u64)?;
+        self.writer.write_all(value)
+    }
+
+    fn encode_array_header(&mut self, len: usize) -> io::Result<()> {
+        self.head(4, len as u64)
+    }
+
+    fn encode_map_header(&mut self, len: usize) -> io::Result<()> {
+        self.head(5, len as u64)
+    }
+}
+
+/// MessagePack encoder ([specification](https://github.com/msgpack/msgpack/blob/master/spec.md)).
+///
+/// Integers and lengths use the most compact representation available.
+/// Strings, byte strings, arrays and maps are limited to `u32::MAX` elements.
+pub struct MessagePack<W> {
+    writer: W,
+}
+
+impl<W: Write> MessagePack<W> {
+    /// Creates a new encoder writing to the given writer.
+    #[inline]
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Consumes the encoder and returns the underlying writer.
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Writes a length-prefixed header.
+    ///
+    /// If `fix` is given, lengths lower than its upper bound are encoded
+    /// in the marker itself.
+    /// The 8-bit length marker is optional, the 16 and 32-bit ones are required.
+    fn header(
+        &mut self,
+        len: usize,
+        fix: Option<(u8, usize)>,
+        marker8: Option<u8>,
+        marker16: u8,
+        marker32: u8,
+    ) -> io::Result<()> {
+        match fix {
+            Some((marker, bound)) if len < bound => self.writer.write_all(&[marker | len as u8]),
+            _ => match marker8 {
+                Some(marker) if len <= u8::MAX as usize => {
+                    self.writer.write_all(&[marker, len as u8])
+                }
+                _ if len <= u16::MAX as usize => {
+                    self.writer.write_all(&[marker16])?;
+                    self.writer.write_all(&(len as u16).to_be_bytes())
+                }
+                _ if len <= u32::MAX as usize => {
+                    self.writer.write_all(&[marker32])?;
+                    self.writer.write_all(&(len as u32).to_be_bytes())
+                }
+                _ => Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "MessagePack length overflow",
+                )),
+            },
+        }
+    }
+}
+
+impl<W: Write> Encoder for MessagePack<W> {
+    fn encode_u64(&mut self, value: u64) -> io::Result<()> {
+        if value < 0x80 {
+            self.writer.write_all(&[value as u8])
+        } else if value <= u8::MAX as u64 {
+            self.writer.write_all(&[0xcc, value as u8])
+        } else if value <= u16::MAX as u64 {
+            self.writer.write_all(&[0xcd])?;
+            self.writer.write_all(&(value as u16).to_be_bytes())
+        } else if value <= u32::MAX as u64 {
+            self.writer.write_all(&[0xce])?;
+            self.writer.write_all(&(value as u32).to_be_bytes())
+        } else {
+            self.writer.write_all(&[0xcf])?;
+            self.writer.write_all(&value.to_be_bytes())
+        }
+    }
+
+    fn encode_i64(&mut self, value: i64) -> io::Result<()> {
+        if value >= 0 {
+            self.encode_u64(value as u64)
+        } else if value >= -32 {
+            self.writer.write_all(&[value as u8])
+        } else if value >= i8::MIN as i64 {
+            self.writer.write_all(&[0xd0, value as u8])
+        } else if value >= i16::MIN as i64 {
+            self.writer.write_all(&[0xd1])?;
+            self.writer.write_all(&(value as i16).to_be_bytes())
+        } else if value >= i32::MIN as i64 {
+            self.writer.write_all(&[0xd2])?;
+            self.writer.write_all(&(value as i32).to_be_bytes())
+        } else {
+            self.writer.write_all(&[0xd3])?;
+            self.writer.write_all(&value.to_be_bytes())
+        }
+    }
+
+    fn encode_f32(&mut self, value: f32) -> io::Result<()> {
+        self.writer.write_all(&[0xca])?;
+        self.writer.write_all(&value.to_be_bytes())
+    }
+
+    fn encode_f64(&mut self, value: f64) -> io::Result<()> {
+        self.writer.write_all(&[0xcb])?;
+        self.writer.write_all(&value.to_be_bytes())
+    }
+
+    fn encode_bool(&mut self, value: bool) -> io::Result<()> {
+        self.writer.write_all(&[if value { 0xc3 } else { 0xc2 }])
+    }
+
+    fn encode_null(&mut self) -> io::Result<()> {
+        self.writer.write_all(&[0xc0])
+    }
+
+    fn encode_str(&mut self, value: &str) -> io::Result<()> {
+        self.header(value.len(), Some((0xa0, 32)), Some(0xd9), 0xda, 0xdb)?;
+        self.writer.write_all(value.as_bytes())
+    }
+
+    fn encode_bytes(&mut self, value: &[u8]) -> io::Result<()> {
+        self.header(value.len(), None, Some(0xc4), 0xc5, 0xc6)?;
+        self.writer.write_all(value)
+    }
+
+    fn encode_array_header(&mut self, len: usize) -> io::Result<()> {
+        self.header(len, Some((0x90, 16)), None, 0xdc, 0xdd)
+    }
+
+    fn encode_map_header(&mut self, len: usize) -> io::Result<()> {
+        self.header(len, Some((0x80, 16)), None, 0xde, 0xdf)
+    }
+}
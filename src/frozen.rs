@@ -0,0 +1,541 @@
+use crate::Map;
+use alloc::vec::Vec;
+use core::{
+    borrow::Borrow,
+    cmp::Ordering,
+    iter::{FromIterator, FusedIterator},
+    ops::{Bound, RangeBounds},
+};
+
+/// Frozen map.
+///
+/// Immutable, read-optimized map built once from a collection of bindings.
+/// Keys and values are stored in two contiguous arrays without any spare
+/// capacity or node metadata, following the Eytzinger layout
+/// (the breadth-first order of a complete binary search tree).
+/// This layout makes lookups walk a single array from its beginning,
+/// which is much more cache friendly than a sorted array,
+/// and uses less memory than a [`Map`].
+///
+/// A frozen map can be converted back into a mutable map with
+/// [`FrozenMap::into_map`].
+///
+/// # Example
+///
+/// ```
+/// use generic_btree::{frozen::FrozenMap, slab::Map};
+///
+/// let mut map = Map::new();
+/// for i in 0..100 {
+///     map.insert(i, i * 2);
+/// }
+///
+/// let frozen: FrozenMap<_, _> = map.into();
+/// assert_eq!(frozen.get(&42), Some(&84));
+/// assert!(frozen.iter().map(|(k, _)| *k).eq(0..100));
+///
+/// let map: Map<_, _> = frozen.into_map();
+/// assert_eq!(map.len(), 100);
+/// ```
+pub struct FrozenMap<K, V> {
+    /// Keys, in Eytzinger order.
+    keys: Vec<K>,
+
+    /// Values, in the same order as the keys.
+    values: Vec<V>,
+}
+
+impl<K, V> FrozenMap<K, V> {
+    /// Creates a new empty frozen map.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            keys: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Builds a frozen map from a vector of bindings sorted by key,
+    /// without duplicate keys.
+    fn from_sorted_vec(bindings: Vec<(K, V)>) -> Self {
+        let n = bindings.len();
+        let mut keys: Vec<Option<K>> = Vec::with_capacity(n);
+        let mut values: Vec<Option<V>> = Vec::with_capacity(n);
+        keys.resize_with(n, || None);
+        values.resize_with(n, || None);
+
+        let mut index = first_index(n);
+        for (key, value) in bindings {
+            let i = index.unwrap();
+            keys[i] = Some(key);
+            values[i] = Some(value);
+            index = next_index(n, i)
+        }
+
+        Self {
+            keys: keys.into_iter().map(Option::unwrap).collect(),
+            values: values.into_iter().map(Option::unwrap).collect(),
+        }
+    }
+
+    /// Returns the number of bindings in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Checks if the map is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the first binding of the map, if any.
+    #[inline]
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        first_index(self.len()).map(|i| self.binding(i))
+    }
+
+    /// Returns the last binding of the map, if any.
+    #[inline]
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        last_index(self.len()).map(|i| self.binding(i))
+    }
+
+    /// Returns an iterator over the bindings of the map, in key order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let n = self.len();
+        Iter {
+            map: self,
+            front: first_index(n),
+            back: last_index(n),
+            len: n,
+        }
+    }
+
+    /// Returns an iterator over the keys of the map, in order.
+    #[inline]
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + ExactSizeIterator {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over the values of the map, in key order.
+    #[inline]
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + ExactSizeIterator {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Converts the frozen map into a mutable map.
+    #[inline]
+    pub fn into_map<S>(self) -> Map<S>
+    where
+        Map<S>: FromIterator<(K, V)>,
+    {
+        self.into_iter().collect()
+    }
+
+    #[inline]
+    fn binding(&self, i: usize) -> (&K, &V) {
+        (&self.keys[i], &self.values[i])
+    }
+
+    /// Returns the number of bindings before the binding at index `i`.
+    fn rank(&self, i: usize) -> usize {
+        let n = self.len();
+        let mut rank = subtree_len(n, 2 * i + 1);
+        let mut j = i;
+        while j > 0 {
+            let parent = (j - 1) / 2;
+            if j == 2 * parent + 2 {
+                rank += subtree_len(n, 2 * parent + 1) + 1
+            }
+
+            j = parent
+        }
+
+        rank
+    }
+}
+
+impl<K: Ord, V> FrozenMap<K, V> {
+    /// Returns the index of the binding with the given key, if any.
+    #[inline]
+    fn index_of<Q: ?Sized + Ord>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+    {
+        let mut i = 0;
+        while let Some(k) = self.keys.get(i) {
+            match key.cmp(k.borrow()) {
+                Ordering::Equal => return Some(i),
+                Ordering::Less => i = 2 * i + 1,
+                Ordering::Greater => i = 2 * i + 2,
+            }
+        }
+
+        None
+    }
+
+    /// Returns the index of the first binding whose key is greater than
+    /// (or equal to, if `inclusive` is `true`) the given key.
+    fn lower_bound<Q: ?Sized + Ord>(&self, key: &Q, inclusive: bool) -> Option<usize>
+    where
+        K: Borrow<Q>,
+    {
+        let mut result = None;
+        let mut i = 0;
+        while let Some(k) = self.keys.get(i) {
+            match k.borrow().cmp(key) {
+                Ordering::Greater => {
+                    result = Some(i);
+                    i = 2 * i + 1
+                }
+                Ordering::Equal if inclusive => {
+                    result = Some(i);
+                    i = 2 * i + 1
+                }
+                _ => i = 2 * i + 2,
+            }
+        }
+
+        result
+    }
+
+    /// Returns the index of the last binding whose key is less than
+    /// (or equal to, if `inclusive` is `true`) the given key.
+    fn upper_bound<Q: ?Sized + Ord>(&self, key: &Q, inclusive: bool) -> Option<usize>
+    where
+        K: Borrow<Q>,
+    {
+        let mut result = None;
+        let mut i = 0;
+        while let Some(k) = self.keys.get(i) {
+            match k.borrow().cmp(key) {
+                Ordering::Less => {
+                    result = Some(i);
+                    i = 2 * i + 2
+                }
+                Ordering::Equal if inclusive => {
+                    result = Some(i);
+                    i = 2 * i + 2
+                }
+                _ => i = 2 * i + 1,
+            }
+        }
+
+        result
+    }
+
+    /// Returns the value associated to the given key, if any.
+    #[inline]
+    pub fn get<Q: ?Sized + Ord>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.index_of(key).map(|i| &self.values[i])
+    }
+
+    /// Returns the binding associated to the given key, if any.
+    #[inline]
+    pub fn get_key_value<Q: ?Sized + Ord>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        self.index_of(key).map(|i| self.binding(i))
+    }
+
+    /// Checks if the map contains the given key.
+    #[inline]
+    pub fn contains_key<Q: ?Sized + Ord>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.index_of(key).is_some()
+    }
+
+    /// Returns an iterator over the bindings whose key is in the given range.
+    pub fn range<Q: ?Sized + Ord, R: RangeBounds<Q>>(&self, range: R) -> Iter<'_, K, V>
+    where
+        K: Borrow<Q>,
+    {
+        let n = self.len();
+        let front = match range.start_bound() {
+            Bound::Included(key) => self.lower_bound(key, true),
+            Bound::Excluded(key) => self.lower_bound(key, false),
+            Bound::Unbounded => first_index(n),
+        };
+
+        let back = match range.end_bound() {
+            Bound::Included(key) => self.upper_bound(key, true),
+            Bound::Excluded(key) => self.upper_bound(key, false),
+            Bound::Unbounded => last_index(n),
+        };
+
+        match (front, back) {
+            (Some(front), Some(back)) if self.rank(front) <= self.rank(back) => Iter {
+                map: self,
+                front: Some(front),
+                back: Some(back),
+                len: self.rank(back) - self.rank(front) + 1,
+            },
+            _ => Iter {
+                map: self,
+                front: None,
+                back: None,
+                len: 0,
+            },
+        }
+    }
+}
+
+impl<K, V> Default for FrozenMap<K, V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> FromIterator<(K, V)> for FrozenMap<K, V> {
+    /// Builds a frozen map from the given bindings.
+    ///
+    /// If a key appears more than once, the last value is kept.
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut bindings: Vec<(K, V)> = iter.into_iter().collect();
+        bindings.sort_by(|a, b| a.0.cmp(&b.0));
+
+        // Keep the last value of each key.
+        let mut deduped: Vec<(K, V)> = Vec::with_capacity(bindings.len());
+        for binding in bindings {
+            match deduped.last_mut() {
+                Some(last) if last.0 == binding.0 => *last = binding,
+                _ => deduped.push(binding),
+            }
+        }
+
+        Self::from_sorted_vec(deduped)
+    }
+}
+
+impl<K: Ord, V, S> From<Map<S>> for FrozenMap<K, V>
+where
+    Map<S>: IntoIterator<Item = (K, V)>,
+{
+    /// Freezes the given map.
+    #[inline]
+    fn from(map: Map<S>) -> Self {
+        Self::from_sorted_vec(map.into_iter().collect())
+    }
+}
+
+impl<K, V> IntoIterator for FrozenMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    /// Returns an iterator over the bindings of the map, in key order.
+    fn into_iter(self) -> IntoIter<K, V> {
+        let n = self.len();
+        let mut order = Vec::with_capacity(n);
+        let mut index = first_index(n);
+        while let Some(i) = index {
+            order.push(i);
+            index = next_index(n, i)
+        }
+
+        let mut keys: Vec<Option<K>> = self.keys.into_iter().map(Some).collect();
+        let mut values: Vec<Option<V>> = self.values.into_iter().map(Some).collect();
+        let bindings: Vec<(K, V)> = order
+            .into_iter()
+            .map(|i| (keys[i].take().unwrap(), values[i].take().unwrap()))
+            .collect();
+
+        IntoIter {
+            inner: bindings.into_iter(),
+        }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a FrozenMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+/// Iterator over the bindings of a [`FrozenMap`], in key order.
+pub struct Iter<'a, K, V> {
+    /// Map.
+    map: &'a FrozenMap<K, V>,
+
+    /// Index of the next binding from the front.
+    front: Option<usize>,
+
+    /// Index of the next binding from the back.
+    back: Option<usize>,
+
+    /// Number of remaining bindings.
+    len: usize,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let i = self.front?;
+        self.len -= 1;
+        self.front = next_index(self.map.len(), i);
+        Some(self.map.binding(i))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let i = self.back?;
+        self.len -= 1;
+        self.back = previous_index(self.map.len(), i);
+        Some(self.map.binding(i))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+impl<'a, K, V> FusedIterator for Iter<'a, K, V> {}
+
+/// Owning iterator over the bindings of a [`FrozenMap`], in key order.
+pub struct IntoIter<K, V> {
+    inner: alloc::vec::IntoIter<(K, V)>,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(K, V)> {
+        self.inner.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<(K, V)> {
+        self.inner.next_back()
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+impl<K, V> FusedIterator for IntoIter<K, V> {}
+
+/// Returns the index of the leftmost node of the subtree rooted at `i`.
+#[inline]
+fn leftmost(n: usize, mut i: usize) -> usize {
+    while 2 * i + 1 < n {
+        i = 2 * i + 1
+    }
+
+    i
+}
+
+/// Returns the index of the rightmost node of the subtree rooted at `i`.
+#[inline]
+fn rightmost(n: usize, mut i: usize) -> usize {
+    while 2 * i + 2 < n {
+        i = 2 * i + 2
+    }
+
+    i
+}
+
+/// Returns the index of the first node in order, if any.
+#[inline]
+fn first_index(n: usize) -> Option<usize> {
+    if n == 0 {
+        None
+    } else {
+        Some(leftmost(n, 0))
+    }
+}
+
+/// Returns the index of the last node in order, if any.
+#[inline]
+fn last_index(n: usize) -> Option<usize> {
+    if n == 0 {
+        None
+    } else {
+        Some(rightmost(n, 0))
+    }
+}
+
+/// Returns the index of the node following `i` in order, if any.
+#[inline]
+fn next_index(n: usize, mut i: usize) -> Option<usize> {
+    if 2 * i + 2 < n {
+        return Some(leftmost(n, 2 * i + 2));
+    }
+
+    // Climb up while `i` is a right child.
+    while i > 0 && i.is_multiple_of(2) {
+        i = (i - 1) / 2
+    }
+
+    if i == 0 {
+        None
+    } else {
+        Some((i - 1) / 2)
+    }
+}
+
+/// Returns the index of the node preceding `i` in order, if any.
+#[inline]
+fn previous_index(n: usize, mut i: usize) -> Option<usize> {
+    if 2 * i + 1 < n {
+        return Some(rightmost(n, 2 * i + 1));
+    }
+
+    // Climb up while `i` is a left child.
+    while !i.is_multiple_of(2) {
+        i = (i - 1) / 2
+    }
+
+    if i == 0 {
+        None
+    } else {
+        Some((i - 1) / 2)
+    }
+}
+
+/// Returns the number of nodes in the subtree rooted at `i`.
+fn subtree_len(n: usize, i: usize) -> usize {
+    let mut len = 0;
+    let (mut first, mut last) = (i, i);
+    while first < n {
+        len += last.min(n - 1) - first + 1;
+        first = 2 * first + 1;
+        last = 2 * last + 2;
+    }
+
+    len
+}
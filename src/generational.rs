@@ -0,0 +1,246 @@
+use crate::{
+    map::Binding,
+    slab::{
+        Node, OccupiedSlots, RawSlots, SlotCapacity, SlotIndex, Storage, VacantSlots, DEFAULT_ORDER,
+    },
+};
+use alloc::vec::Vec;
+
+pub type AugmentedMapStorage<K, V, A, const M: usize = DEFAULT_ORDER> =
+    Storage<Binding<K, V>, GenerationalSlab<Node<Binding<K, V>, M>>, A, M>;
+pub type MapStorage<K, V> = AugmentedMapStorage<K, V, ()>;
+pub type Map<K, V> = crate::Map<MapStorage<K, V>>;
+pub type AugmentedMap<K, V, A, const M: usize = DEFAULT_ORDER> =
+    crate::Map<AugmentedMapStorage<K, V, A, M>>;
+pub type CounterMap<K> = crate::map::CounterMap<MapStorage<K, usize>>;
+
+/// Number of bits of a key used to store the slot index.
+///
+/// The remaining (most significant) bits store the generation.
+const INDEX_BITS: u32 = usize::BITS / 2;
+
+/// Mask selecting the slot index bits of a key.
+const INDEX_MASK: usize = (1 << INDEX_BITS) - 1;
+
+/// Mask selecting the generation bits of a key, once shifted.
+const GENERATION_MASK: usize = usize::MAX >> INDEX_BITS;
+
+/// Slot.
+struct Slot<T> {
+    /// Generation of the slot, incremented each time its value is removed.
+    generation: usize,
+
+    /// Value.
+    value: Option<T>,
+}
+
+/// Generational slab.
+///
+/// Each key is made of a slot index and the generation of the slot
+/// at the time the value was inserted.
+/// When a value is removed, the generation of its slot is incremented,
+/// so that the old key no longer refers to any value, even after the slot is reused.
+///
+/// Used as a node slab, this allows stale [`Address`](crate::Address)es
+/// pointing to released nodes to be detected:
+/// [`Storage::item`](crate::Storage::item) returns `None` instead of
+/// an item of an unrelated node.
+/// Note that an address pointing to a live node may still be invalidated
+/// by mutations moving items inside the node.
+///
+/// # Example
+///
+/// ```
+/// use generic_btree::{generational::Map, Storage};
+///
+/// let mut map: Map<usize, usize> = Map::new();
+/// for i in 0..100 {
+///     map.insert(i, i);
+/// }
+///
+/// let addr = map.btree().address_of(&42).unwrap();
+/// for i in 0..100 {
+///     map.remove(&i);
+/// }
+///
+/// for i in 0..100 {
+///     map.insert(i, i);
+/// }
+///
+/// assert!(map.btree().item(addr).is_none());
+/// ```
+pub struct GenerationalSlab<T> {
+    /// Slots.
+    slots: Vec<Slot<T>>,
+
+    /// Indexes of the free slots.
+    free: Vec<usize>,
+
+    /// Number of occupied slots.
+    len: usize,
+}
+
+impl<T> GenerationalSlab<T> {
+    /// Create a new empty slab.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the slot designated by the given key, if its generation matches.
+    #[inline]
+    fn slot(&self, key: usize) -> Option<&Slot<T>> {
+        self.slots
+            .get(key & INDEX_MASK)
+            .filter(|slot| slot.generation == key >> INDEX_BITS)
+    }
+
+    /// Returns the slot designated by the given key, if its generation matches.
+    #[inline]
+    fn slot_mut(&mut self, key: usize) -> Option<&mut Slot<T>> {
+        self.slots
+            .get_mut(key & INDEX_MASK)
+            .filter(|slot| slot.generation == key >> INDEX_BITS)
+    }
+}
+
+impl<T> Default for GenerationalSlab<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SlotIndex for GenerationalSlab<T> {
+    const GENERATIONAL: bool = true;
+
+    #[inline]
+    fn slot_index(key: usize) -> usize {
+        key & INDEX_MASK
+    }
+}
+
+impl<T> VacantSlots for GenerationalSlab<T> {}
+
+impl<T> OccupiedSlots for GenerationalSlab<T> {
+    #[inline]
+    fn occupied_keys(&self) -> Option<Vec<usize>> {
+        Some(
+            self.slots
+                .iter()
+                .enumerate()
+                .filter(|(_, slot)| slot.value.is_some())
+                .map(|(index, slot)| index | (slot.generation << INDEX_BITS))
+                .collect(),
+        )
+    }
+}
+
+impl<T> SlotCapacity for GenerationalSlab<T> {}
+
+impl<T> RawSlots for GenerationalSlab<T> {}
+
+impl<T> cc_traits::Collection for GenerationalSlab<T> {
+    type Item = T;
+}
+
+impl<T> cc_traits::WithCapacity for GenerationalSlab<T> {
+    #[inline]
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T> cc_traits::Len for GenerationalSlab<T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T> cc_traits::Get<usize> for GenerationalSlab<T> {
+    #[inline]
+    fn get(&self, key: usize) -> Option<&T> {
+        self.slot(key).and_then(|slot| slot.value.as_ref())
+    }
+}
+
+impl<T> cc_traits::GetMut<usize> for GenerationalSlab<T> {
+    #[inline]
+    fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        self.slot_mut(key).and_then(|slot| slot.value.as_mut())
+    }
+}
+
+impl<T> cc_traits::Insert for GenerationalSlab<T> {
+    type Output = usize;
+
+    /// Inserts a value in the slab and returns its key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of slots exceeds the index capacity of a key.
+    #[inline]
+    fn insert(&mut self, value: T) -> usize {
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.slots[index].value = Some(value);
+                index
+            }
+            None => {
+                let index = self.slots.len();
+                if index > INDEX_MASK {
+                    panic!("generational slab capacity overflow")
+                }
+
+                self.slots.push(Slot {
+                    generation: 0,
+                    value: Some(value),
+                });
+                index
+            }
+        };
+
+        self.len += 1;
+        (self.slots[index].generation << INDEX_BITS) | index
+    }
+}
+
+impl<T> cc_traits::Remove<usize> for GenerationalSlab<T> {
+    #[inline]
+    fn remove(&mut self, key: usize) -> Option<T> {
+        let slot = self.slot_mut(key)?;
+        let value = slot.value.take()?;
+        slot.generation = (slot.generation + 1) & GENERATION_MASK;
+        self.free.push(key & INDEX_MASK);
+        self.len -= 1;
+        Some(value)
+    }
+}
+
+impl<T> cc_traits::Clear for GenerationalSlab<T> {
+    /// Removes every value of the slab.
+    ///
+    /// Slots are kept so that keys issued before the call stay invalid.
+    #[inline]
+    fn clear(&mut self) {
+        self.free.clear();
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if slot.value.take().is_some() {
+                slot.generation = (slot.generation + 1) & GENERATION_MASK;
+            }
+
+            self.free.push(index)
+        }
+
+        self.len = 0
+    }
+}
@@ -0,0 +1,492 @@
+use crate::{
+    btree::Storage as _,
+    map::Binding,
+    paged::Codec,
+    slab::{
+        Node, NodeId, OccupiedSlots, RawSlots, SlotCapacity, SlotIndex, Storage, VacantSlots,
+        DEFAULT_ORDER,
+    },
+};
+use alloc::{collections::BTreeMap, vec::Vec};
+
+pub type MapStorage<K, V, S, F = DefaultFormat, const M: usize = DEFAULT_ORDER> =
+    Storage<Binding<K, V>, KvSlab<Node<Binding<K, V>, M>, S, F>, (), M>;
+pub type Map<K, V, S, F = DefaultFormat, const M: usize = DEFAULT_ORDER> =
+    crate::Map<MapStorage<K, V, S, F, M>>;
+
+/// Identifier of the record holding the storage header.
+///
+/// Node records are never stored under this identifier.
+pub const HEADER_RECORD: usize = 0;
+
+/// Magic number identifying the storage header.
+const MAGIC: [u8; 8] = *b"GBTKVSTR";
+
+/// Key-value store.
+///
+/// Records are byte strings identified by an integer.
+pub trait KvStore {
+    /// Error type.
+    type Error;
+
+    /// Returns the record with the given identifier, if any.
+    fn get(&self, id: usize) -> Result<Option<Vec<u8>>, Self::Error>;
+
+    /// Inserts or replaces the record with the given identifier.
+    fn put(&mut self, id: usize, bytes: &[u8]) -> Result<(), Self::Error>;
+
+    /// Deletes the record with the given identifier, if any.
+    fn delete(&mut self, id: usize) -> Result<(), Self::Error>;
+
+    /// Makes every previous modification durable.
+    ///
+    /// Called at the end of each synchronization.
+    /// The default implementation does nothing.
+    #[inline]
+    fn commit(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// In-memory key-value store.
+#[derive(Default)]
+pub struct MemoryStore {
+    /// Records.
+    records: BTreeMap<usize, Vec<u8>>,
+}
+
+impl MemoryStore {
+    /// Creates a new empty store.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of records.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Checks if the store is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+impl KvStore for MemoryStore {
+    type Error = core::convert::Infallible;
+
+    #[inline]
+    fn get(&self, id: usize) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self.records.get(&id).cloned())
+    }
+
+    #[inline]
+    fn put(&mut self, id: usize, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.records.insert(id, bytes.to_vec());
+        Ok(())
+    }
+
+    #[inline]
+    fn delete(&mut self, id: usize) -> Result<(), Self::Error> {
+        self.records.remove(&id);
+        Ok(())
+    }
+}
+
+/// Node serialization format.
+pub trait NodeFormat<N> {
+    /// Appends the encoding of the given node to `buf`.
+    fn encode(&self, node: &N, buf: &mut Vec<u8>);
+
+    /// Decodes a node from the given record.
+    ///
+    /// Returns `None` if the record is not a valid node encoding.
+    fn decode(&self, bytes: &[u8]) -> Option<N>;
+}
+
+/// Default node serialization format.
+///
+/// Encodes items with the [`Codec`] trait,
+/// using the same representation as the paged storage.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct DefaultFormat;
+
+impl<T: Codec, const M: usize, I: NodeId> NodeFormat<Node<T, M, I>> for DefaultFormat {
+    #[inline]
+    fn encode(&self, node: &Node<T, M, I>, buf: &mut Vec<u8>) {
+        node.encode(buf)
+    }
+
+    #[inline]
+    fn decode(&self, mut bytes: &[u8]) -> Option<Node<T, M, I>> {
+        let node = Node::decode(&mut bytes)?;
+        if bytes.is_empty() {
+            Some(node)
+        } else {
+            None
+        }
+    }
+}
+
+/// Key-value storage error.
+#[derive(Debug)]
+pub enum Error<E> {
+    /// Store error.
+    Store(E),
+
+    /// The given record is missing or is not a valid node or header record.
+    Corrupted(usize),
+}
+
+/// Record.
+struct Record<T> {
+    /// Value stored in the record, if any.
+    value: Option<T>,
+
+    /// Whether the value has been modified since the last synchronization.
+    dirty: bool,
+}
+
+impl<T> Default for Record<T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            value: None,
+            dirty: false,
+        }
+    }
+}
+
+/// Key-value store backed slab.
+///
+/// Each value is stored as a record of the store `S`,
+/// serialized with the format `F`.
+/// The key of a value is the identifier of its record.
+/// Values are kept decoded in memory,
+/// and are only written to the store when the slab is synchronized
+/// (see [`Storage::sync`](crate::slab::Storage::sync)).
+/// Records of removed values are deleted on synchronization.
+pub struct KvSlab<T, S, F = DefaultFormat> {
+    /// Store.
+    store: S,
+
+    /// Node format.
+    format: F,
+
+    /// Records, indexed by identifier.
+    records: Vec<Record<T>>,
+
+    /// Identifiers free to reuse.
+    vacant: Vec<usize>,
+
+    /// Records to delete on the next synchronization.
+    freed: Vec<usize>,
+
+    /// Number of values.
+    len: usize,
+}
+
+impl<T, S, F> KvSlab<T, S, F> {
+    /// Creates a new empty slab using the given store and format.
+    #[inline]
+    pub fn new(store: S, format: F) -> Self {
+        Self {
+            store,
+            format,
+            records: Vec::new(),
+            vacant: Vec::new(),
+            freed: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns a reference to the store.
+    #[inline]
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Returns a reference to the node format.
+    #[inline]
+    pub fn format(&self) -> &F {
+        &self.format
+    }
+
+    /// Returns a mutable reference to the given record, creating it if necessary.
+    fn record_mut(&mut self, id: usize) -> &mut Record<T> {
+        if id >= self.records.len() {
+            self.records.resize_with(id + 1, Record::default)
+        }
+
+        &mut self.records[id]
+    }
+}
+
+impl<T, S: KvStore, F: NodeFormat<Node<T, M, I>>, const M: usize, I: NodeId>
+    KvSlab<Node<T, M, I>, S, F>
+{
+    /// Reads the tree recorded in the given store.
+    ///
+    /// Returns the slab with the root and length of the tree.
+    fn load(store: S, format: F) -> Result<(Self, Option<usize>, usize), Error<S::Error>> {
+        let header = store
+            .get(HEADER_RECORD)
+            .map_err(Error::Store)?
+            .ok_or(Error::Corrupted(HEADER_RECORD))?;
+
+        let mut bytes = &header[..];
+        if bytes.get(..MAGIC.len()) != Some(&MAGIC) {
+            return Err(Error::Corrupted(HEADER_RECORD));
+        }
+
+        bytes = &bytes[MAGIC.len()..];
+        let (root, len, next_id) = match (
+            Option::<usize>::decode(&mut bytes),
+            usize::decode(&mut bytes),
+            usize::decode(&mut bytes),
+        ) {
+            (Some(root), Some(len), Some(next_id)) => (root, len, next_id),
+            _ => return Err(Error::Corrupted(HEADER_RECORD)),
+        };
+
+        if next_id == HEADER_RECORD {
+            return Err(Error::Corrupted(HEADER_RECORD));
+        }
+
+        let mut slab = Self::new(store, format);
+        let mut stack: Vec<usize> = root.into_iter().collect();
+        while let Some(id) = stack.pop() {
+            if id == HEADER_RECORD || id >= next_id {
+                return Err(Error::Corrupted(id));
+            }
+
+            let bytes = slab
+                .store
+                .get(id)
+                .map_err(Error::Store)?
+                .ok_or(Error::Corrupted(id))?;
+            let node = slab.format.decode(&bytes).ok_or(Error::Corrupted(id))?;
+            stack.extend(node.child_ids());
+
+            let record = slab.record_mut(id);
+            if record.value.is_some() {
+                return Err(Error::Corrupted(id));
+            }
+
+            record.value = Some(node);
+            slab.len += 1
+        }
+
+        // Identifiers below `next_id` not used by the tree are free to reuse.
+        slab.record_mut(next_id - 1);
+        slab.vacant = (HEADER_RECORD + 1..next_id)
+            .rev()
+            .filter(|id| slab.records[*id].value.is_none())
+            .collect();
+
+        Ok((slab, root, len))
+    }
+
+    /// Writes every modified value and the given tree root and length to the store,
+    /// deletes the records of the removed values, then commits.
+    fn sync(&mut self, root: Option<usize>, len: usize) -> Result<(), Error<S::Error>> {
+        let mut buf = Vec::new();
+        for (id, record) in self.records.iter_mut().enumerate() {
+            if record.dirty {
+                if let Some(node) = &record.value {
+                    buf.clear();
+                    self.format.encode(node, &mut buf);
+                    self.store.put(id, &buf).map_err(Error::Store)?
+                }
+
+                record.dirty = false
+            }
+        }
+
+        buf.clear();
+        buf.extend_from_slice(&MAGIC);
+        root.encode(&mut buf);
+        len.encode(&mut buf);
+        self.next_id().encode(&mut buf);
+        self.store.put(HEADER_RECORD, &buf).map_err(Error::Store)?;
+
+        for id in self.freed.drain(..) {
+            self.store.delete(id).map_err(Error::Store)?;
+            self.vacant.push(id)
+        }
+
+        self.store.commit().map_err(Error::Store)
+    }
+}
+
+impl<T, S, F> KvSlab<T, S, F> {
+    /// Returns the first identifier never used by this slab.
+    #[inline]
+    fn next_id(&self) -> usize {
+        self.records.len().max(HEADER_RECORD + 1)
+    }
+}
+
+impl<T, S, F> SlotIndex for KvSlab<T, S, F> {}
+
+impl<T, S, F> VacantSlots for KvSlab<T, S, F> {}
+
+impl<T, S, F> OccupiedSlots for KvSlab<T, S, F> {
+    #[inline]
+    fn occupied_keys(&self) -> Option<Vec<usize>> {
+        Some(
+            self.records
+                .iter()
+                .enumerate()
+                .filter(|(_, record)| record.value.is_some())
+                .map(|(key, _)| key)
+                .collect(),
+        )
+    }
+}
+
+impl<T, S, F> SlotCapacity for KvSlab<T, S, F> {}
+
+impl<T, S, F> RawSlots for KvSlab<T, S, F> {}
+
+impl<T, S, F> cc_traits::Collection for KvSlab<T, S, F> {
+    type Item = T;
+}
+
+impl<T, S, F> cc_traits::Len for KvSlab<T, S, F> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T, S, F> cc_traits::Get<usize> for KvSlab<T, S, F> {
+    #[inline]
+    fn get(&self, key: usize) -> Option<&T> {
+        self.records
+            .get(key)
+            .and_then(|record| record.value.as_ref())
+    }
+}
+
+impl<T, S, F> cc_traits::GetMut<usize> for KvSlab<T, S, F> {
+    #[inline]
+    fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        let record = self.records.get_mut(key)?;
+        let value = record.value.as_mut()?;
+        record.dirty = true;
+        Some(value)
+    }
+}
+
+impl<T, S, F> cc_traits::Insert for KvSlab<T, S, F> {
+    type Output = usize;
+
+    #[inline]
+    fn insert(&mut self, value: T) -> usize {
+        let id = match self.vacant.pop() {
+            Some(id) => id,
+            None => self.next_id(),
+        };
+
+        let record = self.record_mut(id);
+        record.value = Some(value);
+        record.dirty = true;
+        self.len += 1;
+        id
+    }
+}
+
+impl<T, S, F> cc_traits::Remove<usize> for KvSlab<T, S, F> {
+    #[inline]
+    fn remove(&mut self, key: usize) -> Option<T> {
+        let record = self.records.get_mut(key)?;
+        let value = record.value.take()?;
+        record.dirty = false;
+        self.freed.push(key);
+        self.len -= 1;
+        Some(value)
+    }
+}
+
+impl<T, S, F> cc_traits::Clear for KvSlab<T, S, F> {
+    #[inline]
+    fn clear(&mut self) {
+        for (id, record) in self.records.iter_mut().enumerate() {
+            if record.value.take().is_some() {
+                self.freed.push(id)
+            }
+
+            record.dirty = false
+        }
+
+        self.len = 0
+    }
+}
+
+impl<T, S: KvStore, F: NodeFormat<Node<T, M, I>>, const M: usize, I: NodeId>
+    Storage<T, KvSlab<Node<T, M, I>, S, F>, (), M, I>
+{
+    /// Creates a new empty storage writing its nodes to the given store,
+    /// using the given node format.
+    ///
+    /// Nothing is written until the storage is synchronized
+    /// with [`Storage::sync`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::kv::{DefaultFormat, Map, MapStorage, MemoryStore};
+    ///
+    /// let mut map: Map<u32, String, MemoryStore> =
+    ///     Map::from_btree(MapStorage::create(MemoryStore::new(), DefaultFormat));
+    /// for i in 0..100 {
+    ///     map.insert(i, i.to_string());
+    /// }
+    ///
+    /// map.btree_mut().sync().unwrap();
+    /// let store = map.into_btree().into_store();
+    ///
+    /// let map: Map<u32, String, MemoryStore> =
+    ///     Map::from_btree(MapStorage::open(store, DefaultFormat).unwrap());
+    /// assert_eq!(map.len(), 100);
+    /// assert_eq!(map.get(&42).unwrap(), "42");
+    /// ```
+    #[inline]
+    pub fn create(store: S, format: F) -> Self {
+        Self::from_slab(KvSlab::new(store, format))
+    }
+
+    /// Opens the storage previously synchronized to the given store,
+    /// using the given node format.
+    pub fn open(store: S, format: F) -> Result<Self, Error<S::Error>> {
+        let (slab, root, len) = KvSlab::load(store, format)?;
+        Ok(Self::from_raw_parts(slab, root, len))
+    }
+
+    /// Writes the nodes modified since the last synchronization
+    /// and the tree header to the store,
+    /// and deletes the records of the removed nodes.
+    pub fn sync(&mut self) -> Result<(), Error<S::Error>> {
+        self.trim();
+        let (root, len) = (self.root(), self.len());
+        self.slab_mut().sync(root, len)
+    }
+
+    /// Returns a reference to the store.
+    #[inline]
+    pub fn store(&self) -> &S {
+        self.slab().store()
+    }
+
+    /// Consumes the storage and returns the store.
+    ///
+    /// Modifications made since the last synchronization are lost.
+    #[inline]
+    pub fn into_store(self) -> S {
+        self.into_slab().store
+    }
+}
@@ -1,5 +1,8 @@
-#![feature(generic_associated_types)]
-#![feature(trait_alias)]
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
+#![cfg_attr(feature = "simd", feature(portable_simd, min_specialization))]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 mod btree;
 mod util;
@@ -7,13 +10,63 @@ mod util;
 /// Map components.
 pub mod map;
 
+/// Small map, storing a few bindings inline before allocating tree nodes.
+pub mod small;
+
+/// Frozen, read-optimized immutable map.
+pub mod frozen;
+
+/// B+-tree implementation, storing items in linked leaves.
+pub mod bplus;
+
 /// Graphviz DOT language export features.
 #[cfg(feature = "dot")]
 pub mod dot;
 
+/// Streaming CBOR and MessagePack export features.
+#[cfg(feature = "std")]
+pub mod encode;
+
 /// Default Slab-backed implementation.
 pub mod slab;
 
+/// `Vec`-backed implementation, not depending on the `slab` crate.
+pub mod vec_storage;
+
+/// Fixed-capacity implementation, storing nodes inline without heap allocation.
+pub mod array_storage;
+
+/// Generational slab implementation, detecting stale node identifiers.
+pub mod generational;
+
+/// Persistent implementation, sharing nodes between versions with copy-on-write.
+pub mod persistent;
+
+/// Page-based implementation, serializing nodes into fixed-size pages.
+pub mod paged;
+
+/// Key-value store implementation, storing each node as a record.
+pub mod kv;
+
+/// Memory-mapped file implementation.
+#[cfg(all(feature = "mmap", unix))]
+pub mod mmap;
+
+/// Asynchronous implementation, for nodes fetched from slow or remote sources.
+pub mod asynchronous;
+
+/// Property-testing utilities.
+#[cfg(feature = "testing")]
+pub mod testing;
+
+/// Structured diagnostics of the tree algorithms.
+#[cfg(feature = "tracing")]
+pub mod trace;
+
+/// Multi-index containers.
+#[cfg(feature = "slab")]
+pub mod multi_index;
+
 pub use btree::*;
 
 pub use map::Map;
@@ -1,20 +1,32 @@
 use crate::{
     btree::{
+        augment::{MerkleHash, MerkleHasher},
         node::item::{Read, Replace, Write},
-        Insert, ItemOrd, ItemPartialOrd, KeyPartialOrd, UpdateEntry,
+        Augment, CapacityError, Insert, ItemOrd, ItemPartialOrd, KeyOrd, KeyPartialOrd,
+        UpdateEntry,
     },
     Storage, StorageMut,
 };
-use std::{
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{
     cmp::{Ord, Ordering, PartialOrd},
+    fmt,
     hash::{Hash, Hasher},
     iter::{FromIterator, FusedIterator},
-    ops::RangeBounds,
+    ops::{Bound, ControlFlow, Index, RangeBounds},
 };
 
 mod binding;
+mod counter;
+mod cursor;
+#[cfg(feature = "std")]
+mod encode;
 mod entry;
+#[cfg(feature = "serde")]
+mod serde;
 pub use binding::*;
+pub use counter::*;
+pub use cursor::*;
 pub use entry::*;
 
 /// Inserted item.
@@ -66,6 +78,20 @@ pub trait MapStorage: Storage {
     }
 }
 
+/// Map-like storage whose value references are plain references.
+///
+/// Indexing a map (`map[key]`) requires returning a plain reference
+/// to the value, which is only possible for storages implementing this trait.
+pub trait MapStorageRef: MapStorage {
+    /// Referenced value type.
+    type Target: ?Sized;
+
+    /// Turns a value reference into a plain reference.
+    fn deref_value<'a>(value: Self::ValueRef<'a>) -> &'a Self::Target
+    where
+        Self: 'a;
+}
+
 /// Mutable map-like storage.
 pub trait MapStorageMut: StorageMut + MapStorage {
     /// Key type.
@@ -114,6 +140,11 @@ pub trait MapStorageMut: StorageMut + MapStorage {
     }
 }
 
+/// Result of [`Map::insert_within_capacity`].
+///
+/// On failure, the error holds the key-value pair that was not inserted.
+pub type TryInsertResult<K, V> = Result<Option<V>, CapacityError<(K, V)>>;
+
 /// BTree map.
 pub struct Map<S> {
     btree: S,
@@ -130,6 +161,18 @@ impl<S: MapStorage> Map<S> {
         }
     }
 
+    /// Create a map using the given (empty or not) storage.
+    #[inline]
+    pub fn from_btree(btree: S) -> Self {
+        Self { btree }
+    }
+
+    /// Consumes the map and returns the underlying storage.
+    #[inline]
+    pub fn into_btree(self) -> S {
+        self.btree
+    }
+
     /// Returns `true` if the map contains no elements.
     ///
     /// # Example
@@ -187,6 +230,39 @@ impl<S: MapStorage> Map<S> {
         self.btree.get(key).map(|item| S::split_ref(item).1)
     }
 
+    /// Returns a reference to the value corresponding to the key,
+    /// starting the search from the given finger.
+    ///
+    /// Accessing keys close to each other through the same [`Finger`](crate::Finger)
+    /// avoids searching from the root every time
+    /// (see [`Storage::address_of_with_finger`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{slab::Map, Finger};
+    ///
+    /// let map: Map<usize, usize> = (0..100).map(|i| (i, i * 2)).collect();
+    /// let mut finger = Finger::new();
+    /// for i in 0..100 {
+    ///     assert_eq!(map.get_with_finger(&mut finger, &i), Some(&(i * 2)));
+    /// }
+    /// ```
+    #[inline]
+    pub fn get_with_finger<Q: ?Sized>(
+        &self,
+        finger: &mut crate::Finger,
+        key: &Q,
+    ) -> Option<S::ValueRef<'_>>
+    where
+        S: KeyPartialOrd<Q>,
+    {
+        self.btree
+            .address_of_with_finger(finger, key)
+            .ok()
+            .map(|addr| S::value_ref(self.btree.item(addr).unwrap()))
+    }
+
     /// Returns the key-value pair corresponding to the supplied key.
     ///
     /// The supplied key may be any borrowed form of the map's key type, but the ordering
@@ -249,6 +325,162 @@ impl<S: MapStorage> Map<S> {
         self.btree.last_item().map(S::split_ref)
     }
 
+    /// Returns the first key in the map, that is the minimum key.
+    ///
+    /// Storages caching the first leaf of the tree, such as the
+    /// [slab storage](crate::slab::Storage), answer in constant time
+    /// as long as no node is allocated or released.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map = Map::new();
+    /// assert_eq!(map.first_key(), None);
+    /// map.insert(2, "b");
+    /// map.insert(1, "a");
+    /// assert_eq!(map.first_key(), Some(&1));
+    /// ```
+    #[inline]
+    pub fn first_key(&self) -> Option<S::KeyRef<'_>> {
+        self.btree.first_item().map(S::key_ref)
+    }
+
+    /// Returns the last key in the map, that is the maximum key.
+    ///
+    /// See [`first_key`](Self::first_key) for the complexity of this operation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map = Map::new();
+    /// assert_eq!(map.last_key(), None);
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    /// assert_eq!(map.last_key(), Some(&2));
+    /// ```
+    #[inline]
+    pub fn last_key(&self) -> Option<S::KeyRef<'_>> {
+        self.btree.last_item().map(S::key_ref)
+    }
+
+    /// Returns the `n`-th key-value pair of the map (starting from `0`), in key order.
+    ///
+    /// This takes a logarithmic time when the storage knows the length of each subtree,
+    /// for instance with the [`Count`](crate::augment::Count) augmentation.
+    /// Otherwise, the first `n` entries are visited.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{augment::Count, slab::AugmentedMap};
+    ///
+    /// let mut map: AugmentedMap<usize, char, Count> = AugmentedMap::new();
+    /// map.insert(3, 'c');
+    /// map.insert(1, 'a');
+    /// map.insert(2, 'b');
+    ///
+    /// assert_eq!(map.get_index(0), Some((&1, &'a')));
+    /// assert_eq!(map.get_index(2), Some((&3, &'c')));
+    /// assert_eq!(map.get_index(3), None);
+    /// ```
+    #[inline]
+    pub fn get_index(&self, n: usize) -> Option<(S::KeyRef<'_>, S::ValueRef<'_>)> {
+        let addr = self.btree.nth_address(n)?;
+        self.btree.item(addr).map(S::split_ref)
+    }
+
+    /// Returns the number of entries whose key is strictly less than the given key,
+    /// that is the position of the key in the map.
+    ///
+    /// Returns `Ok(rank)` if the map contains the key, and `Err(rank)` otherwise.
+    /// See [`get_index`](Self::get_index) for the complexity of this operation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{augment::Count, slab::AugmentedMap};
+    ///
+    /// let mut map: AugmentedMap<usize, char, Count> = AugmentedMap::new();
+    /// map.insert(10, 'a');
+    /// map.insert(20, 'b');
+    /// map.insert(30, 'c');
+    ///
+    /// assert_eq!(map.rank_of(&20), Ok(1));
+    /// assert_eq!(map.rank_of(&25), Err(2));
+    /// assert_eq!(map.rank_of(&40), Err(3));
+    /// ```
+    #[inline]
+    pub fn rank_of<Q: ?Sized>(&self, key: &Q) -> Result<usize, usize>
+    where
+        S: KeyPartialOrd<Q>,
+    {
+        self.btree.rank_of(key)
+    }
+
+    /// Returns a cursor positioned before the first entry above the given bound.
+    ///
+    /// The entry after the cursor is the first entry whose key is greater or equal to an
+    /// `Included` bound, or strictly greater than an `Excluded` bound.
+    /// If the bound is `Unbounded`, the cursor is positioned before the first entry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    /// use std::ops::Bound;
+    ///
+    /// let map: Map<i32, &str> = vec![(1, "a"), (2, "b"), (3, "c"), (4, "d")].into_iter().collect();
+    ///
+    /// let cursor = map.lower_bound(Bound::Included(&2));
+    /// assert_eq!(cursor.peek_prev(), Some((&1, &"a")));
+    /// assert_eq!(cursor.peek_next(), Some((&2, &"b")));
+    ///
+    /// let cursor = map.lower_bound(Bound::Excluded(&2));
+    /// assert_eq!(cursor.peek_prev(), Some((&2, &"b")));
+    /// assert_eq!(cursor.peek_next(), Some((&3, &"c")));
+    /// ```
+    #[inline]
+    pub fn lower_bound<Q: ?Sized>(&self, bound: Bound<&Q>) -> Cursor<'_, S>
+    where
+        S: KeyPartialOrd<Q>,
+    {
+        Cursor::new(&self.btree, self.btree.lower_bound(bound))
+    }
+
+    /// Returns a cursor positioned after the last entry below the given bound.
+    ///
+    /// The entry before the cursor is the last entry whose key is less or equal to an
+    /// `Included` bound, or strictly less than an `Excluded` bound.
+    /// If the bound is `Unbounded`, the cursor is positioned after the last entry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    /// use std::ops::Bound;
+    ///
+    /// let map: Map<i32, &str> = vec![(1, "a"), (2, "b"), (3, "c"), (4, "d")].into_iter().collect();
+    ///
+    /// let cursor = map.upper_bound(Bound::Included(&3));
+    /// assert_eq!(cursor.peek_prev(), Some((&3, &"c")));
+    /// assert_eq!(cursor.peek_next(), Some((&4, &"d")));
+    ///
+    /// let cursor = map.upper_bound(Bound::Excluded(&3));
+    /// assert_eq!(cursor.peek_prev(), Some((&2, &"b")));
+    /// assert_eq!(cursor.peek_next(), Some((&3, &"c")));
+    /// ```
+    #[inline]
+    pub fn upper_bound<Q: ?Sized>(&self, bound: Bound<&Q>) -> Cursor<'_, S>
+    where
+        S: KeyPartialOrd<Q>,
+    {
+        Cursor::new(&self.btree, self.btree.upper_bound(bound))
+    }
+
     /// Gets an iterator over the entries of the map, sorted by key.
     ///
     /// # Example
@@ -310,6 +542,171 @@ impl<S: MapStorage> Map<S> {
         Range::new(&self.btree, range)
     }
 
+    /// Constructs a double-ended iterator over the keys of a sub-range of the map,
+    /// in order.
+    ///
+    /// See [`range`](Self::range) for the accepted ranges.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let map: Map<u32, &str> = vec![(3, "a"), (5, "b"), (8, "c")].into_iter().collect();
+    /// let keys: Vec<u32> = map.range_keys(4..).cloned().collect();
+    /// assert_eq!(keys, [5, 8]);
+    /// ```
+    #[inline]
+    pub fn range_keys<T, R>(&self, range: R) -> RangeKeys<'_, S>
+    where
+        T: ?Sized + Ord,
+        S: KeyPartialOrd<T>,
+        R: RangeBounds<T>,
+    {
+        RangeKeys {
+            inner: self.btree.range(range),
+        }
+    }
+
+    /// Constructs a double-ended iterator over the values of a sub-range of the map,
+    /// in order by key.
+    ///
+    /// See [`range`](Self::range) for the accepted ranges.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let map: Map<u32, &str> = vec![(3, "a"), (5, "b"), (8, "c")].into_iter().collect();
+    /// let values: Vec<&str> = map.range_values(..8).cloned().collect();
+    /// assert_eq!(values, ["a", "b"]);
+    /// ```
+    #[inline]
+    pub fn range_values<T, R>(&self, range: R) -> RangeValues<'_, S>
+    where
+        T: ?Sized + Ord,
+        S: KeyPartialOrd<T>,
+        R: RangeBounds<T>,
+    {
+        RangeValues {
+            inner: self.btree.range(range),
+        }
+    }
+
+    /// Returns the number of bindings whose key is in the given range.
+    ///
+    /// This takes a logarithmic time when the storage knows the length of each subtree,
+    /// for instance when it maintains the [`Count`](crate::augment::Count) augmentation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{augment::Count, slab::AugmentedMap};
+    ///
+    /// let mut map: AugmentedMap<u32, &str, Count> = AugmentedMap::new();
+    /// for i in 0..100 {
+    ///     map.insert(i, "a");
+    /// }
+    ///
+    /// assert_eq!(map.range_count(10..20), 10);
+    /// assert_eq!(map.range_count(90..), 10);
+    /// assert_eq!(map.range(..=50).size_hint(), (51, Some(51)));
+    /// ```
+    #[inline]
+    pub fn range_count<T, R>(&self, range: R) -> usize
+    where
+        T: ?Sized + Ord,
+        S: KeyPartialOrd<T>,
+        R: RangeBounds<T>,
+    {
+        self.btree.range_count(range)
+    }
+
+    /// Computes the augmentation of the bindings whose key is in the given range,
+    /// in logarithmic time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{
+    ///     augment::{Max, Sum},
+    ///     slab::AugmentedMap,
+    /// };
+    ///
+    /// let mut map: AugmentedMap<u32, u32, (Sum<u32>, Max<u32>)> = AugmentedMap::new();
+    /// for i in 0..100 {
+    ///     map.insert(i, i % 10);
+    /// }
+    ///
+    /// let (sum, max) = map.range_aggregate(5..15);
+    /// assert_eq!(sum.0, (5..15).map(|i| i % 10).sum());
+    /// assert_eq!(max.0, Some(9));
+    /// ```
+    #[inline]
+    pub fn range_aggregate<T, R>(&self, range: R) -> S::Augmentation
+    where
+        T: ?Sized + Ord,
+        S: Augment + KeyPartialOrd<T>,
+        R: RangeBounds<T>,
+    {
+        self.btree.range_aggregate(range)
+    }
+
+    /// Returns the Merkle hash of the map, or `None` if the map is empty.
+    ///
+    /// The hash only depends on the items of the map, in order
+    /// (see [`MerkleHash`]).
+    /// It is maintained for each node on every mutation,
+    /// so this function runs in constant time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{augment::MerkleHash, slab::AugmentedMap};
+    /// use std::collections::hash_map::DefaultHasher;
+    ///
+    /// let mut a: AugmentedMap<u32, &str, MerkleHash<DefaultHasher>> = AugmentedMap::new();
+    /// let mut b: AugmentedMap<u32, &str, MerkleHash<DefaultHasher>> = AugmentedMap::new();
+    /// assert!(a.root_hash().is_none());
+    ///
+    /// a.insert(1, "a");
+    /// b.insert(1, "a");
+    /// assert_eq!(a.root_hash(), b.root_hash());
+    ///
+    /// b.insert(1, "b");
+    /// assert_ne!(a.root_hash(), b.root_hash());
+    /// ```
+    #[inline]
+    pub fn root_hash<H>(&self) -> Option<H::Digest>
+    where
+        H: MerkleHasher,
+        S: Augment,
+        S::Augmentation: AsRef<MerkleHash<H>>,
+    {
+        self.btree.root_hash()
+    }
+
     /// Gets an iterator over the keys of the map, in sorted order.
     ///
     /// # Example
@@ -390,6 +787,42 @@ impl<S: MapStorage> Map<S> {
 impl<S: MapStorageMut> Map<S> {
     // TODO clear
 
+    /// Creates a new empty map with enough capacity to hold at least
+    /// `capacity` key-value pairs without reallocating the underlying storage.
+    ///
+    /// See [`StorageMut::reserve`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map: Map<usize, usize> = Map::with_capacity(1000);
+    /// for i in 0..1000 {
+    ///     map.insert(i, i);
+    /// }
+    ///
+    /// assert_eq!(map.len(), 1000);
+    /// ```
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self
+    where
+        S: Default,
+    {
+        let mut map = Self::new();
+        map.reserve(capacity);
+        map
+    }
+
+    /// Reserves capacity for at least `additional` more key-value pairs
+    /// to be inserted in the map.
+    ///
+    /// See [`StorageMut::reserve`].
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.btree.reserve(additional)
+    }
+
     /// Returns a mutable reference to the value corresponding to the key.
     ///
     /// The key may be any borrowed form of the map's key type, but the ordering
@@ -408,13 +841,23 @@ impl<S: MapStorageMut> Map<S> {
     /// assert_eq!(*map.get(&1).unwrap(), "b");
     /// ```
     #[inline]
-    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<S::ValueMut<'_>>
+    pub fn get_mut<Q: ?Sized>(&mut self, key: &Q) -> Option<S::ValueMut<'_>>
     where
         S: KeyPartialOrd<Q>,
     {
         self.btree.get_mut(key).map(S::value_mut)
     }
 
+    /// Returns the `n`-th key-value pair of the map (starting from `0`), in key order,
+    /// with a mutable reference to the value.
+    ///
+    /// See [`get_index`](Self::get_index) for the complexity of this operation.
+    #[inline]
+    pub fn get_index_mut(&mut self, n: usize) -> Option<(S::KeyRef<'_>, S::ValueMut<'_>)> {
+        let addr = self.btree.nth_address(n)?;
+        self.btree.item_mut(addr).map(S::split_mut)
+    }
+
     /// Gets the given key's corresponding entry in the map for in-place manipulation.
     #[inline]
     pub fn entry(&mut self, key: S::Key) -> Entry<S>
@@ -434,35 +877,72 @@ impl<S: MapStorageMut> Map<S> {
         }
     }
 
-    /// Returns the first entry in the map for in-place manipulation.
-    /// The key of this entry is the minimum key in the map.
+    /// Gets the entry of the given borrowed key in the map for in-place manipulation.
+    ///
+    /// Contrarily to [`entry`](Self::entry), no owned key is required:
+    /// it is only created from the borrowed key
+    /// when a value is inserted in a vacant entry.
     ///
     /// # Example
     ///
     /// ```
     /// use generic_btree::slab::Map;
     ///
-    /// let mut map = Map::new();
-    /// map.insert(1, "a");
-    /// map.insert(2, "b");
-    /// if let Some(mut entry) = map.first_entry() {
-    ///     if *entry.key() > 0 {
-    ///         entry.insert("first");
-    ///     }
+    /// let mut words: Map<String, usize> = Map::new();
+    /// for word in "the quick brown fox jumps over the lazy dog".split(' ') {
+    ///     *words.entry_ref(word).or_insert(0) += 1;
     /// }
-    /// assert_eq!(*map.get(&1).unwrap(), "first");
-    /// assert_eq!(*map.get(&2).unwrap(), "b");
+    ///
+    /// assert_eq!(words["the"], 2);
+    /// assert_eq!(words.len(), 8);
     /// ```
     #[inline]
-    pub fn first_entry(&mut self) -> Option<OccupiedEntry<S>> {
-        match self.btree.first_item_address() {
-            Some(addr) => Some(OccupiedEntry {
+    pub fn entry_ref<'b, Q: ?Sized>(&mut self, key: &'b Q) -> EntryRef<'_, 'b, S, Q>
+    where
+        S: KeyPartialOrd<Q>,
+    {
+        match self.btree.address_of(key) {
+            Ok(addr) => EntryRef::Occupied(OccupiedEntry {
                 map: &mut self.btree,
                 addr,
             }),
-            None => None,
-        }
-    }
+            Err(addr) => EntryRef::Vacant(VacantEntryRef {
+                map: &mut self.btree,
+                key,
+                addr,
+            }),
+        }
+    }
+
+    /// Returns the first entry in the map for in-place manipulation.
+    /// The key of this entry is the minimum key in the map.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(1, "a");
+    /// map.insert(2, "b");
+    /// if let Some(mut entry) = map.first_entry() {
+    ///     if *entry.key() > 0 {
+    ///         entry.insert("first");
+    ///     }
+    /// }
+    /// assert_eq!(*map.get(&1).unwrap(), "first");
+    /// assert_eq!(*map.get(&2).unwrap(), "b");
+    /// ```
+    #[inline]
+    pub fn first_entry(&mut self) -> Option<OccupiedEntry<S>> {
+        match self.btree.first_item_address() {
+            Some(addr) => Some(OccupiedEntry {
+                map: &mut self.btree,
+                addr,
+            }),
+            None => None,
+        }
+    }
 
     /// Returns the last entry in the map for in-place manipulation.
     /// The key of this entry is the maximum key in the map.
@@ -496,20 +976,110 @@ impl<S: MapStorageMut> Map<S> {
 
     /// Insert a key-value pair in the tree.
     #[inline]
-    pub fn insert<'r>(&'r mut self, key: S::Key, value: S::Value) -> Option<S::Value>
+    pub fn insert(&mut self, key: S::Key, value: S::Value) -> Option<S::Value>
     where
         S: Insert<Inserted<S::Key, S::Value>> + KeyPartialOrd<Inserted<S::Key, S::Value>>,
-        S::ItemMut<'r>: Replace<S, Inserted<S::Key, S::Value>, Output = S::Value>,
+        S: Replace<Inserted<S::Key, S::Value>, Output = S::Value>,
     {
         self.btree.insert(Inserted(key, value)).map(Into::into)
     }
 
+    /// Inserts a key-value pair into the map,
+    /// starting the search from the given finger.
+    ///
+    /// Inserting keys close to each other through the same [`Finger`](crate::Finger)
+    /// avoids searching from the root every time
+    /// (see [`Storage::address_of_with_finger`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{slab::Map, Finger};
+    ///
+    /// let mut map = Map::new();
+    /// let mut finger = Finger::new();
+    /// for i in 0..100 {
+    ///     assert_eq!(map.insert_with_finger(&mut finger, i, i), None);
+    /// }
+    ///
+    /// assert_eq!(map.insert_with_finger(&mut finger, 42, 0), Some(42));
+    /// assert_eq!(map.len(), 100);
+    /// ```
+    #[inline]
+    pub fn insert_with_finger(
+        &mut self,
+        finger: &mut crate::Finger,
+        key: S::Key,
+        value: S::Value,
+    ) -> Option<S::Value>
+    where
+        S: Insert<Inserted<S::Key, S::Value>> + KeyPartialOrd<Inserted<S::Key, S::Value>>,
+        S: Replace<Inserted<S::Key, S::Value>, Output = S::Value>,
+    {
+        self.btree.insert_with_finger(finger, Inserted(key, value))
+    }
+
+    /// Tries to insert a key-value pair into the map, and returns
+    /// a mutable reference to the value in the entry.
+    ///
+    /// If the map already had this key present, nothing is updated, and
+    /// an error containing the occupied entry and the value is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map = Map::new();
+    /// assert_eq!(*map.try_insert(37, "a").unwrap(), "a");
+    ///
+    /// let err = map.try_insert(37, "b").unwrap_err();
+    /// assert_eq!(*err.entry.key(), 37);
+    /// assert_eq!(*err.entry.get(), "a");
+    /// assert_eq!(err.value, "b");
+    /// ```
+    #[inline]
+    pub fn try_insert(
+        &mut self,
+        key: S::Key,
+        value: S::Value,
+    ) -> Result<S::ValueMut<'_>, OccupiedError<'_, S>>
+    where
+        S: KeyPartialOrd<S::Key> + Insert<Inserted<S::Key, S::Value>>,
+    {
+        match self.entry(key) {
+            Entry::Occupied(entry) => Err(OccupiedError { entry, value }),
+            Entry::Vacant(entry) => Ok(entry.insert(value)),
+        }
+    }
+
+    /// Insert a key-value pair in the tree, if the storage has enough capacity.
+    ///
+    /// Replacing the value of an existing key never fails.
+    /// Otherwise, if the underlying storage is bounded and may not be able to
+    /// allocate the nodes required by the insertion,
+    /// the key-value pair is returned in a [`CapacityError`].
+    #[inline]
+    pub fn insert_within_capacity(
+        &mut self,
+        key: S::Key,
+        value: S::Value,
+    ) -> TryInsertResult<S::Key, S::Value>
+    where
+        S: Insert<Inserted<S::Key, S::Value>> + KeyPartialOrd<Inserted<S::Key, S::Value>>,
+        S: Replace<Inserted<S::Key, S::Value>, Output = S::Value>,
+    {
+        self.btree
+            .insert_within_capacity(Inserted(key, value))
+            .map_err(|CapacityError(Inserted(key, value))| CapacityError((key, value)))
+    }
+
     /// Replace a key-value pair in the tree.
     #[inline]
-    pub fn replace<'r>(&'r mut self, key: S::Key, value: S::Value) -> Option<(S::Key, S::Value)>
+    pub fn replace(&mut self, key: S::Key, value: S::Value) -> Option<(S::Key, S::Value)>
     where
         S: Insert<Replacing<S::Key, S::Value>> + KeyPartialOrd<Replacing<S::Key, S::Value>>,
-        S::ItemMut<'r>: Replace<S, Replacing<S::Key, S::Value>, Output = S::Item>,
+        S: Replace<Replacing<S::Key, S::Value>, Output = <S as StorageMut>::Item>,
     {
         self.btree.insert(Replacing(key, value)).map(S::split)
     }
@@ -560,6 +1130,76 @@ impl<S: MapStorageMut> Map<S> {
         self.btree.pop_last().map(S::split)
     }
 
+    /// Removes and returns the first element in the map
+    /// if it satisfies the given predicate.
+    ///
+    /// The predicate is given the key and a mutable reference to the value
+    /// of the first element.
+    /// Contrarily to a [`first_key_value`](Self::first_key_value)
+    /// followed by a [`remove`](Self::remove),
+    /// the tree is only traversed once.
+    ///
+    /// # Example
+    ///
+    /// Popping the expired entries of a queue indexed by deadline.
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut queue: Map<u32, &str> = vec![(10, "a"), (20, "b"), (30, "c")].into_iter().collect();
+    /// let now = 25;
+    /// let mut expired = Vec::new();
+    /// while let Some((_, value)) = queue.pop_first_if(|deadline, _| *deadline <= now) {
+    ///     expired.push(value);
+    /// }
+    /// assert_eq!(expired, ["a", "b"]);
+    /// assert_eq!(queue.len(), 1);
+    /// ```
+    #[inline]
+    pub fn pop_first_if<F>(&mut self, pred: F) -> Option<(S::Key, S::Value)>
+    where
+        F: FnOnce(S::KeyRef<'_>, S::ValueMut<'_>) -> bool,
+    {
+        self.btree
+            .pop_first_if(|item| {
+                let (key, value) = S::split_mut(item);
+                pred(key, value)
+            })
+            .map(S::split)
+    }
+
+    /// Removes and returns the last element in the map
+    /// if it satisfies the given predicate.
+    ///
+    /// The predicate is given the key and a mutable reference to the value
+    /// of the last element.
+    /// Contrarily to a [`last_key_value`](Self::last_key_value)
+    /// followed by a [`remove`](Self::remove),
+    /// the tree is only traversed once.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map: Map<u32, &str> = vec![(1, "a"), (2, "b")].into_iter().collect();
+    /// assert_eq!(map.pop_last_if(|_, value| *value == "a"), None);
+    /// assert_eq!(map.pop_last_if(|_, value| *value == "b"), Some((2, "b")));
+    /// assert_eq!(map.len(), 1);
+    /// ```
+    #[inline]
+    pub fn pop_last_if<F>(&mut self, pred: F) -> Option<(S::Key, S::Value)>
+    where
+        F: FnOnce(S::KeyRef<'_>, S::ValueMut<'_>) -> bool,
+    {
+        self.btree
+            .pop_last_if(|item| {
+                let (key, value) = S::split_mut(item);
+                pred(key, value)
+            })
+            .map(S::split)
+    }
+
     /// Removes a key from the map, returning the value at the key if the key
     /// was previously in the map.
     ///
@@ -584,6 +1224,61 @@ impl<S: MapStorageMut> Map<S> {
         self.btree.remove(key).map(S::value)
     }
 
+    /// Removes a key from the map, starting the search from the given finger.
+    ///
+    /// Removing keys close to each other through the same [`Finger`](crate::Finger)
+    /// avoids searching from the root every time
+    /// (see [`StorageMut::remove_with_finger`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{slab::Map, Finger};
+    ///
+    /// let mut map: Map<usize, usize> = (0..100).map(|i| (i, i)).collect();
+    /// let mut finger = Finger::new();
+    /// for i in 10..20 {
+    ///     assert_eq!(map.remove_with_finger(&mut finger, &i), Some(i));
+    /// }
+    ///
+    /// assert_eq!(map.len(), 90);
+    /// ```
+    #[inline]
+    pub fn remove_with_finger<Q: ?Sized>(
+        &mut self,
+        finger: &mut crate::Finger,
+        key: &Q,
+    ) -> Option<S::Value>
+    where
+        S: KeyPartialOrd<Q>,
+    {
+        self.btree.remove_with_finger(finger, key).map(S::value)
+    }
+
+    /// Removes every given key from the map, with deferred rebalancing.
+    ///
+    /// Returns the number of removed entries.
+    /// This is faster than removing the keys one by one when many keys are removed
+    /// (see [`StorageMut::bulk_remove`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map: Map<usize, usize> = (0..100).map(|i| (i, i)).collect();
+    /// assert_eq!(map.bulk_remove(&[1, 2, 3, 200]), 3);
+    /// assert_eq!(map.len(), 97);
+    /// ```
+    #[inline]
+    pub fn bulk_remove<'q, Q: 'q + ?Sized, I>(&mut self, keys: I) -> usize
+    where
+        S: KeyPartialOrd<Q>,
+        I: IntoIterator<Item = &'q Q>,
+    {
+        self.btree.bulk_remove(keys)
+    }
+
     /// Removes a key from the map, returning the stored key and value if the key
     /// was previously in the map.
     ///
@@ -610,6 +1305,330 @@ impl<S: MapStorageMut> Map<S> {
         self.btree.remove(key).map(S::split)
     }
 
+    /// Splits the collection into two at the given key.
+    /// Returns everything after the given key, including the key.
+    ///
+    /// The returned map uses a new storage, created with
+    /// [`StorageMut::empty`].
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut a = Map::new();
+    /// a.insert(1, "a");
+    /// a.insert(2, "b");
+    /// a.insert(3, "c");
+    /// a.insert(17, "d");
+    /// a.insert(41, "e");
+    ///
+    /// let b = a.split_off(&3);
+    ///
+    /// assert_eq!(a.len(), 2);
+    /// assert_eq!(b.len(), 3);
+    ///
+    /// assert_eq!(a.get(&1), Some(&"a"));
+    /// assert_eq!(a.get(&2), Some(&"b"));
+    ///
+    /// assert_eq!(b.get(&3), Some(&"c"));
+    /// assert_eq!(b.get(&17), Some(&"d"));
+    /// assert_eq!(b.get(&41), Some(&"e"));
+    /// ```
+    #[inline]
+    pub fn split_off<Q: ?Sized>(&mut self, key: &Q) -> Self
+    where
+        S: Default + KeyPartialOrd<Q>,
+    {
+        Self::from_btree(self.btree.split_off(key))
+    }
+
+    /// Removes the entries of the given range from the map, and returns them in a new map.
+    ///
+    /// Whole subtrees are moved to the new map instead of removing the entries one by one,
+    /// which only requires a logarithmic number of comparisons and rebalancing operations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut a: Map<i32, i32> = (0..10).map(|x| (x, x * 10)).collect();
+    /// let b = a.split_off_range(3..=6);
+    ///
+    /// assert_eq!(a.keys().copied().collect::<Vec<_>>(), vec![0, 1, 2, 7, 8, 9]);
+    /// assert_eq!(b.keys().copied().collect::<Vec<_>>(), vec![3, 4, 5, 6]);
+    /// ```
+    #[inline]
+    pub fn split_off_range<T, R>(&mut self, range: R) -> Self
+    where
+        T: ?Sized + Ord,
+        R: RangeBounds<T>,
+        S: Default + KeyPartialOrd<T>,
+    {
+        Self::from_btree(self.btree.split_off_range(range))
+    }
+
+    /// Moves all elements from `other` into `self`, leaving `other` empty.
+    ///
+    /// If a key from `other` is already present in `self`,
+    /// the respective value from `self` will be overwritten
+    /// with the respective value from `other`.
+    ///
+    /// If the key ranges of both maps do not overlap,
+    /// the underlying trees are joined without reinserting any binding
+    /// (see [`StorageMut::try_concat`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut a = Map::new();
+    /// a.insert(1, "a");
+    /// a.insert(2, "b");
+    ///
+    /// let mut b = Map::new();
+    /// b.insert(3, "c");
+    /// b.insert(4, "d");
+    ///
+    /// a.append(&mut b);
+    ///
+    /// assert_eq!(a.len(), 4);
+    /// assert_eq!(b.len(), 0);
+    /// assert_eq!(a.get(&4), Some(&"d"));
+    /// ```
+    #[inline]
+    pub fn append(&mut self, other: &mut Self)
+    where
+        S: Default + KeyOrd,
+        S: Insert<Inserted<S::Key, S::Value>> + KeyPartialOrd<Inserted<S::Key, S::Value>>,
+        S: Replace<Inserted<S::Key, S::Value>, Output = S::Value>,
+    {
+        if !self.btree.try_concat(&mut other.btree) {
+            let empty = other.btree.empty();
+            let mut other = core::mem::replace(&mut other.btree, empty);
+            while let Some(item) = other.pop_first() {
+                let (key, value) = S::split(item);
+                self.insert(key, value);
+            }
+        }
+    }
+
+    /// Moves all elements from `other` into `self`,
+    /// using the given closure to resolve the keys present in both maps.
+    ///
+    /// For each duplicate key, `f` is given the key, the value from `self`
+    /// and the value from `other`, and returns the value to keep,
+    /// or `None` to drop the entry altogether.
+    ///
+    /// Both maps are traversed once, side by side,
+    /// and the merged map is built bottom-up in linear time
+    /// (see [`StorageMut::build_from_sorted`]).
+    /// If the key ranges of both maps do not overlap,
+    /// the underlying trees are joined without reinserting any binding
+    /// (see [`StorageMut::try_concat`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut a: Map<u32, u32> = vec![(1, 10), (2, 20), (3, 30)].into_iter().collect();
+    /// let b: Map<u32, u32> = vec![(2, 2), (3, 3), (4, 4)].into_iter().collect();
+    ///
+    /// a.merge_with(b, |key, left, right| if *key == 3 { None } else { Some(left + right) });
+    ///
+    /// assert_eq!(a.into_sorted_vec(), [(1, 10), (2, 22), (4, 4)]);
+    /// ```
+    pub fn merge_with<F>(&mut self, mut other: Self, mut f: F)
+    where
+        S: Default + KeyOrd + Insert<Inserted<S::Key, S::Value>>,
+        S::Key: Ord,
+        for<'r> S::ItemRef<'r>: Read<S>,
+        F: FnMut(&S::Key, S::Value, S::Value) -> Option<S::Value>,
+    {
+        if self.btree.try_concat(&mut other.btree) {
+            return;
+        }
+
+        let empty = self.btree.empty();
+        let left = core::mem::replace(&mut self.btree, empty);
+        let mut left = Self::from_btree(left).into_iter().peekable();
+        let mut right = other.into_iter().peekable();
+        let merged = core::iter::from_fn(move || loop {
+            let ordering = match (left.peek(), right.peek()) {
+                (Some((a, _)), Some((b, _))) => a.cmp(b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => return None,
+            };
+
+            match ordering {
+                Ordering::Less => return left.next(),
+                Ordering::Greater => return right.next(),
+                Ordering::Equal => {
+                    let (key, a) = left.next().unwrap();
+                    let (_, b) = right.next().unwrap();
+                    if let Some(value) = f(&key, a, b) {
+                        return Some((key, value));
+                    }
+                }
+            }
+        });
+
+        self.btree
+            .build_from_sorted(merged.map(|(key, value)| Inserted(key, value)))
+    }
+
+    /// Visits the entries of this map and `other`, merged in key order.
+    ///
+    /// Both maps are traversed lazily, side by side.
+    /// Each entry is wrapped in a [`Merged`](crate::Merged) value telling from which map it comes.
+    /// Keys present in both maps yield a single [`Merged::Both`](crate::Merged::Both) pair,
+    /// leaving it to the caller to resolve the conflict.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{slab::Map, Merged};
+    ///
+    /// let a: Map<u32, u32> = vec![(1, 10), (2, 20)].into_iter().collect();
+    /// let b: Map<u32, u32> = vec![(2, 2), (3, 3)].into_iter().collect();
+    ///
+    /// let merged: Vec<_> = a
+    ///     .merge_iter(&b)
+    ///     .map(|entry| match entry {
+    ///         Merged::Both((key, a), (_, b)) => (*key, a + b),
+    ///         entry => {
+    ///             let (key, value) = entry.prefer_left();
+    ///             (*key, *value)
+    ///         }
+    ///     })
+    ///     .collect();
+    ///
+    /// assert_eq!(merged, [(1, 10), (2, 22), (3, 3)]);
+    /// ```
+    #[inline]
+    pub fn merge_iter<'a>(&'a self, other: &'a Self) -> MergeIter<'a, S>
+    where
+        S: KeyOrd,
+    {
+        MergeIter {
+            a: self.btree.iter().peekable(),
+            b: other.btree.iter().peekable(),
+        }
+    }
+
+    /// Creates a map from an iterator of key-value pairs sorted by key,
+    /// without duplicate keys.
+    ///
+    /// Unlike [`FromIterator`], the tree is built bottom-up in linear time
+    /// and its nodes are filled to their maximum capacity
+    /// (see [`StorageMut::build_from_sorted`]).
+    /// If the keys are not sorted or have duplicates, the resulting map is not valid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let map: Map<usize, usize> = Map::from_sorted_iter((0..100).map(|i| (i, i * 2)));
+    /// assert_eq!(map.len(), 100);
+    /// assert_eq!(map.get(&42), Some(&84));
+    /// ```
+    #[inline]
+    pub fn from_sorted_iter<I>(iter: I) -> Self
+    where
+        S: Default + Insert<Inserted<S::Key, S::Value>>,
+        I: IntoIterator<Item = (S::Key, S::Value)>,
+    {
+        let mut btree = S::default();
+        btree.build_from_sorted(iter.into_iter().map(|(key, value)| Inserted(key, value)));
+        Self::from_btree(btree)
+    }
+
+    /// Creates a map from a vector of key-value pairs in any order.
+    ///
+    /// The vector is sorted by key, then the tree is built bottom-up in linear time
+    /// (see [`from_sorted_iter`](Self::from_sorted_iter)).
+    /// If a key appears more than once, the map keeps its first occurrence
+    /// associated to the last value, as if the pairs were inserted one by one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let map: Map<usize, &str> = Map::from_unsorted_vec(vec![(2, "b"), (1, "a"), (2, "c")]);
+    /// assert_eq!(map.into_sorted_vec(), vec![(1, "a"), (2, "c")]);
+    /// ```
+    pub fn from_unsorted_vec(mut pairs: Vec<(S::Key, S::Value)>) -> Self
+    where
+        S: Default + Insert<Inserted<S::Key, S::Value>>,
+        S::Key: Ord,
+    {
+        pairs.sort_by(|a, b| a.0.cmp(&b.0));
+        pairs.dedup_by(|next, previous| {
+            if next.0 == previous.0 {
+                core::mem::swap(&mut next.1, &mut previous.1);
+                true
+            } else {
+                false
+            }
+        });
+
+        Self::from_sorted_iter(pairs)
+    }
+
+    /// Inserts a batch of key-value pairs sorted by key, without duplicate keys.
+    ///
+    /// Existing values associated to the same keys are replaced.
+    /// This is faster than inserting the pairs one by one, as the batch is merged
+    /// into the tree in a single pass (see [`StorageMut::insert_sorted_batch`]).
+    /// If the keys are not sorted or have duplicates, the resulting map is not valid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map: Map<usize, usize> = (0..10).map(|i| (i, i)).collect();
+    /// map.insert_sorted_batch((5..15).map(|i| (i, i * 2)));
+    ///
+    /// assert_eq!(map.len(), 15);
+    /// assert_eq!(map.get(&4), Some(&4));
+    /// assert_eq!(map.get(&5), Some(&10));
+    /// ```
+    #[inline]
+    pub fn insert_sorted_batch<I>(&mut self, iter: I)
+    where
+        S: Default + Insert<Inserted<S::Key, S::Value>> + KeyPartialOrd<Inserted<S::Key, S::Value>>,
+        S: Replace<Inserted<S::Key, S::Value>, Output = S::Value>,
+        I: IntoIterator<Item = (S::Key, S::Value)>,
+    {
+        self.btree
+            .insert_sorted_batch(iter.into_iter().map(|(key, value)| Inserted(key, value)))
+    }
+
+    /// Consumes the map and returns its key-value pairs in a vector, sorted by key.
+    #[inline]
+    pub fn into_sorted_vec(self) -> Vec<(S::Key, S::Value)>
+    where
+        for<'r> S::ItemRef<'r>: Read<S>,
+    {
+        let mut pairs = Vec::with_capacity(self.len());
+        pairs.extend(self);
+        pairs
+    }
+
     /// General-purpose update function.
     ///
     /// This can be used to insert, compare, replace or remove the value associated to the given
@@ -623,6 +1642,9 @@ impl<S: MapStorageMut> Map<S> {
     /// `new_value` is the new value to be associated to `key`
     /// (if it is `None` any previous binding is removed) and
     /// `result` is the value returned by the entire `update` function call.
+    ///
+    /// If `action` panics after receiving the current value,
+    /// the binding is removed from the map.
     #[inline]
     pub fn update<T, F>(&mut self, key: S::Key, action: F) -> T
     where
@@ -709,6 +1731,36 @@ impl<S: MapStorageMut> Map<S> {
         IntoValues::new(self.btree)
     }
 
+    /// Creates a consuming iterator visiting all the items of the map, in order by key.
+    /// The map cannot be used after calling this.
+    ///
+    /// Contrarily to [`into_iter`](IntoIterator::into_iter),
+    /// items are not split into key-value pairs:
+    /// the iterator element type is the item type of the storage
+    /// (a [`Binding`] for the slab storage).
+    /// This allows moving items whose key and value are expensive to split
+    /// into another storage as they are.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{map::Binding, slab::Map};
+    ///
+    /// let mut a = Map::new();
+    /// a.insert(2, "b");
+    /// a.insert(1, "a");
+    ///
+    /// let bindings: Vec<Binding<i32, &str>> = a.into_entries().collect();
+    /// assert_eq!(bindings[0].as_pair(), (&1, &"a"));
+    /// assert_eq!(bindings[1].as_pair(), (&2, &"b"));
+    /// ```
+    #[inline]
+    pub fn into_entries(self) -> IntoEntries<S> {
+        IntoEntries {
+            inner: self.btree.into_iter(),
+        }
+    }
+
     /// Gets a mutable iterator over the entries of the map, sorted by key, that allows insertion and deletion of the iterated entries.
     ///
     /// # Correctness
@@ -746,6 +1798,89 @@ impl<S: MapStorageMut> Map<S> {
         EntriesMut::new(&mut self.btree)
     }
 
+    /// Returns a cursor positioned before the first entry of the map.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map = Map::new();
+    /// map.insert(1, "a");
+    /// map.insert(3, "c");
+    ///
+    /// let mut cursor = map.cursor_front_mut();
+    /// cursor.insert_before(0, "z");
+    /// cursor.move_next();
+    /// cursor.insert_after(2, "b");
+    /// assert_eq!(cursor.peek_next().map(|(k, v)| (*k, *v)), Some((2, "b")));
+    ///
+    /// let entries: Vec<_> = map.into_iter().collect();
+    /// assert_eq!(entries, vec![(0, "z"), (1, "a"), (2, "b"), (3, "c")]);
+    /// ```
+    #[inline]
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, S> {
+        let addr = self.btree.first_item_address();
+        CursorMut::new(&mut self.btree, addr)
+    }
+
+    /// Returns a cursor positioned after the last entry of the map.
+    #[inline]
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, S> {
+        CursorMut::new(&mut self.btree, None)
+    }
+
+    /// Returns a cursor positioned before the first entry
+    /// whose key is greater or equal to the given key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map: Map<i32, i32> = (0..10).map(|i| (i * 2, i)).collect();
+    ///
+    /// let mut cursor = map.cursor_mut(&5);
+    /// assert_eq!(cursor.peek_prev().map(|(k, _)| *k), Some(4));
+    /// assert_eq!(cursor.remove_current(), Some((6, 3)));
+    /// assert_eq!(cursor.peek_next().map(|(k, _)| *k), Some(8));
+    /// ```
+    #[inline]
+    pub fn cursor_mut<Q: ?Sized>(&mut self, key: &Q) -> CursorMut<'_, S>
+    where
+        S: KeyPartialOrd<Q>,
+    {
+        self.lower_bound_mut(Bound::Included(key))
+    }
+
+    /// Returns a cursor positioned before the first entry above the given bound.
+    ///
+    /// The entry after the cursor is the first entry whose key is greater or equal to an
+    /// `Included` bound, or strictly greater than an `Excluded` bound.
+    /// If the bound is `Unbounded`, the cursor is positioned before the first entry.
+    #[inline]
+    pub fn lower_bound_mut<Q: ?Sized>(&mut self, bound: Bound<&Q>) -> CursorMut<'_, S>
+    where
+        S: KeyPartialOrd<Q>,
+    {
+        let addr = self.btree.lower_bound(bound);
+        CursorMut::new(&mut self.btree, addr)
+    }
+
+    /// Returns a cursor positioned after the last entry below the given bound.
+    ///
+    /// The entry before the cursor is the last entry whose key is less or equal to an
+    /// `Included` bound, or strictly less than an `Excluded` bound.
+    /// If the bound is `Unbounded`, the cursor is positioned after the last entry.
+    #[inline]
+    pub fn upper_bound_mut<Q: ?Sized>(&mut self, bound: Bound<&Q>) -> CursorMut<'_, S>
+    where
+        S: KeyPartialOrd<Q>,
+    {
+        let addr = self.btree.upper_bound(bound);
+        CursorMut::new(&mut self.btree, addr)
+    }
+
     /// Constructs a mutable double-ended iterator over a sub-range of elements in the map.
     /// The simplest way is to use the range syntax `min..max`, thus `range(min..max)` will
     /// yield elements from min (inclusive) to max (exclusive).
@@ -784,6 +1919,42 @@ impl<S: MapStorageMut> Map<S> {
         RangeMut::new(&mut self.btree, range)
     }
 
+    /// Constructs a mutable double-ended iterator over the values of a sub-range of the map,
+    /// in order by key.
+    ///
+    /// See [`range`](Self::range) for the accepted ranges.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map: Map<u32, u32> = (0..10).map(|i| (i, i)).collect();
+    /// for value in map.range_values_mut(3..6) {
+    ///     *value *= 10
+    /// }
+    ///
+    /// assert_eq!(map.get(&2), Some(&2));
+    /// assert_eq!(map.get(&5), Some(&50));
+    /// assert_eq!(map.get(&6), Some(&6));
+    /// ```
+    #[inline]
+    pub fn range_values_mut<T, R>(&mut self, range: R) -> RangeValuesMut<'_, S>
+    where
+        T: ?Sized + Ord,
+        S: KeyPartialOrd<T>,
+        R: RangeBounds<T>,
+    {
+        RangeValuesMut {
+            inner: self.btree.range_mut(range),
+        }
+    }
+
     /// Gets a mutable iterator over the values of the map, in order by key.
     ///
     /// # Example
@@ -808,6 +1979,40 @@ impl<S: MapStorageMut> Map<S> {
         ValuesMut::new(&mut self.btree)
     }
 
+    /// Removes the entries of the given range from the map, and returns them in an iterator.
+    ///
+    /// The tree is rebalanced after each removal,
+    /// and the entries outside of the range are left untouched.
+    /// If the iterator is only partially consumed or not consumed at all,
+    /// the remaining entries of the range are removed when it is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map: Map<i32, i32> = (0..8).map(|x| (x, x * 10)).collect();
+    /// let drained: Vec<_> = map.drain(2..5).collect();
+    /// assert_eq!(drained, vec![(2, 20), (3, 30), (4, 40)]);
+    /// assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec![0, 1, 5, 6, 7]);
+    /// ```
+    #[inline]
+    pub fn drain<T, R>(&mut self, range: R) -> Drain<'_, S>
+    where
+        T: ?Sized + Ord,
+        R: RangeBounds<T>,
+        S: KeyPartialOrd<T>,
+    {
+        Drain {
+            inner: self.btree.drain(range),
+        }
+    }
+
     /// Creates an iterator which uses a closure to determine if an element should be removed.
     ///
     /// If the closure returns true, the element is removed from the map and yielded.
@@ -820,9 +2025,13 @@ impl<S: MapStorageMut> Map<S> {
     /// If the iterator is only partially consumed or not consumed at all, each of the remaining
     /// elements will still be subjected to the closure and removed and dropped if it returns true.
     ///
+    /// If the closure panics, the element it was called on and every unvisited element
+    /// remain in the map, and the closure is never called again,
+    /// even when the iterator is dropped during unwinding.
+    /// Likewise, if a panic occurs while dropping an element
+    /// removed when the iterator is dropped, the unvisited elements remain in the map.
     /// It is unspecified how many more elements will be subjected to the closure
-    /// if a panic occurs in the closure, or a panic occurs while dropping an element,
-    /// or if the `DrainFilter` value is leaked.
+    /// if the `DrainFilter` value is leaked.
     ///
     /// # Example
     ///
@@ -845,6 +2054,42 @@ impl<S: MapStorageMut> Map<S> {
         DrainFilter::new(&mut self.btree, pred)
     }
 
+    /// Creates an iterator which uses a closure to determine if an element of the given range
+    /// should be removed.
+    ///
+    /// This behaves like [`drain_filter`](Self::drain_filter),
+    /// but only the elements whose key is in the range are subjected to the closure.
+    /// The first element of the range is found by searching the tree,
+    /// and the elements outside of the range are never visited.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end`.
+    /// Panics if range `start == end` and both bounds are `Excluded`.
+    ///
+    /// # Example
+    ///
+    /// Evicting the expired entries of a time window:
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map: Map<u32, bool> = (0..10).map(|t| (t, t % 3 == 0)).collect();
+    /// let evicted: Vec<_> = map.extract_if(2..8, |_t, expired| *expired).collect();
+    /// assert_eq!(evicted, [(3, true), (6, true)]);
+    /// assert_eq!(map.keys().copied().collect::<Vec<_>>(), [0, 1, 2, 4, 5, 7, 8, 9]);
+    /// ```
+    #[inline]
+    pub fn extract_if<T, R, F>(&mut self, range: R, pred: F) -> DrainFilter<'_, S, F>
+    where
+        T: ?Sized + Ord,
+        R: RangeBounds<T>,
+        S: KeyPartialOrd<T>,
+        F: for<'f> FnMut(S::KeyRef<'f>, S::ValueMut<'f>) -> bool,
+    {
+        DrainFilter::with_range(&mut self.btree, range, pred)
+    }
+
     /// Retains only the elements specified by the predicate.
     ///
     /// In other words, remove all pairs `(k, v)` such that `f(&k, &mut v)` returns `false`.
@@ -867,22 +2112,148 @@ impl<S: MapStorageMut> Map<S> {
         self.drain_filter(|k, v| !f(k, v));
     }
 
+    /// Retains only the elements specified by the predicate,
+    /// visiting the entries in key order until the predicate breaks.
+    ///
+    /// The predicate returns `ControlFlow::Continue(keep)` to keep (or remove)
+    /// the entry and visit the next one, or `ControlFlow::Break(())` to stop
+    /// the scan, leaving the current and following entries untouched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use core::ops::ControlFlow;
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map: Map<i32, i32> = (0..8).map(|x| (x, x*10)).collect();
+    /// // Remove the odd keys, up to key 4.
+    /// map.retain_while(|&k, _| {
+    ///     if k > 4 {
+    ///         ControlFlow::Break(())
+    ///     } else {
+    ///         ControlFlow::Continue(k % 2 == 0)
+    ///     }
+    /// });
+    /// assert!(map.into_iter().eq(vec![(0, 0), (2, 20), (4, 40), (5, 50), (6, 60), (7, 70)]));
+    /// ```
+    #[inline]
+    pub fn retain_while<F>(&mut self, f: F)
+    where
+        F: for<'f> FnMut(S::KeyRef<'f>, S::ValueMut<'f>) -> ControlFlow<(), bool>,
+    {
+        let _ = self.try_retain(f);
+    }
+
+    /// Retains only the elements specified by the predicate,
+    /// visiting the entries in key order until the predicate breaks.
+    ///
+    /// This is the same as [`retain_while`](Self::retain_while),
+    /// but returns the value given to `ControlFlow::Break`, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use core::ops::ControlFlow;
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map: Map<i32, i32> = (0..8).map(|x| (x, x*10)).collect();
+    /// let result = map.try_retain(|&k, v| {
+    ///     if *v > 30 {
+    ///         ControlFlow::Break(k)
+    ///     } else {
+    ///         ControlFlow::Continue(k != 1)
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(result, ControlFlow::Break(4));
+    /// assert_eq!(map.len(), 7);
+    /// ```
+    pub fn try_retain<B, F>(&mut self, mut f: F) -> ControlFlow<B>
+    where
+        F: for<'f> FnMut(S::KeyRef<'f>, S::ValueMut<'f>) -> ControlFlow<B, bool>,
+    {
+        let mut addr = self.btree.first_item_address();
+        while let Some(a) = addr {
+            let (key, value) = S::split_mut(self.btree.item_mut(a).unwrap());
+            if f(key, value)? {
+                addr = self.btree.next_item_address(a)
+            } else {
+                let (_, next) = self.btree.remove_at(a).unwrap();
+                addr = self.btree.normalize(next)
+            }
+        }
+
+        ControlFlow::Continue(())
+    }
+
     pub fn btree_mut(&mut self) -> &mut S {
         &mut self.btree
     }
+
+    /// Re-packs the map storage and shrinks its capacity as much as possible.
+    ///
+    /// See [`StorageMut::compact`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map: Map<usize, usize> = (0..1000).map(|i| (i, i)).collect();
+    /// map.retain(|key, _| key % 100 == 0);
+    /// map.shrink_to_fit();
+    ///
+    /// assert_eq!(map.len(), 10);
+    /// assert_eq!(map.get(&500), Some(&500));
+    /// ```
+    #[inline]
+    pub fn shrink_to_fit(&mut self)
+    where
+        S: Default,
+    {
+        self.btree.compact()
+    }
+}
+
+impl<S: MapStorage, T: MapStorage> PartialEq<Map<T>> for Map<S>
+where
+    T: ItemPartialOrd<S>,
+{
+    fn eq(&self, other: &Map<T>) -> bool {
+        self.btree.eq(&other.btree)
+    }
+}
+
+impl<S: MapStorage> Eq for Map<S> where S: ItemOrd {}
+
+impl<S: MapStorageRef, Q: ?Sized> Index<&Q> for Map<S>
+where
+    S: KeyPartialOrd<Q>,
+{
+    type Output = S::Target;
+
+    /// Returns a reference to the value corresponding to the supplied key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not present in the map.
+    #[inline]
+    fn index(&self, key: &Q) -> &S::Target {
+        S::deref_value(self.get(key).expect("no entry found for key"))
+    }
 }
 
-impl<S: MapStorage, T: MapStorage> PartialEq<Map<T>> for Map<S>
+impl<S: MapStorage> fmt::Debug for Map<S>
 where
-    T: ItemPartialOrd<S>,
+    for<'r> S::KeyRef<'r>: fmt::Debug,
+    for<'r> S::ValueRef<'r>: fmt::Debug,
 {
-    fn eq(&self, other: &Map<T>) -> bool {
-        self.btree.eq(&other.btree)
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
     }
 }
 
-impl<S: MapStorage> Eq for Map<S> where S: ItemOrd {}
-
 impl<S: MapStorage + Default> Default for Map<S> {
     #[inline]
     fn default() -> Self {
@@ -890,30 +2261,168 @@ impl<S: MapStorage + Default> Default for Map<S> {
     }
 }
 
+impl<S: Clone> Clone for Map<S> {
+    /// Clones the map by cloning its storage.
+    ///
+    /// The cost of this operation depends on the storage:
+    /// storages sharing their nodes, such as the
+    /// [persistent storage](crate::persistent), do not copy any node.
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            btree: self.btree.clone(),
+        }
+    }
+}
+
 impl<S: MapStorageMut + Default> FromIterator<(S::Key, S::Value)> for Map<S>
 where
     S: Insert<Inserted<S::Key, S::Value>> + KeyPartialOrd<Inserted<S::Key, S::Value>>,
-    for<'a> S::ItemMut<'a>: Replace<S, Inserted<S::Key, S::Value>, Output = S::Value>,
+    S: Replace<Inserted<S::Key, S::Value>, Output = S::Value>,
+    S::Key: Ord,
 {
+    /// Creates a map from an iterator of key-value pairs.
+    ///
+    /// As long as the keys are sorted, the tree is built bottom-up in linear time
+    /// (see [`StorageMut::build_from_sorted`]).
+    /// Once an unsorted or duplicate key is detected,
+    /// the remaining pairs are inserted one by one.
     #[inline]
     fn from_iter<T>(iter: T) -> Self
     where
         T: IntoIterator<Item = (S::Key, S::Value)>,
     {
-        let mut map = Self::new();
+        let mut iter = iter.into_iter();
+        let mut sorted = SortedPrefix {
+            next: iter.next(),
+            iter,
+            unsorted: None,
+        };
+
+        let mut btree = S::default();
+        btree.build_from_sorted(sorted.by_ref().map(|(key, value)| Inserted(key, value)));
+
+        let mut map = Self::from_btree(btree);
+        map.extend(sorted.unsorted.into_iter().chain(sorted.iter));
+        map
+    }
+}
 
-        for (key, value) in iter {
-            map.insert(key, value);
+/// Longest sorted prefix of a sequence of key-value pairs.
+struct SortedPrefix<I, K, V> {
+    /// Sequence.
+    iter: I,
+
+    /// Next pair, read in advance to be compared with the previous one.
+    next: Option<(K, V)>,
+
+    /// First pair whose key is not greater than the previous one, if any.
+    unsorted: Option<(K, V)>,
+}
+
+impl<I: Iterator<Item = (K, V)>, K: Ord, V> Iterator for SortedPrefix<I, K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        let current = self.next.take()?;
+        match self.iter.next() {
+            Some(next) if next.0 > current.0 => self.next = Some(next),
+            next => self.unsorted = next,
         }
 
-        map
+        Some(current)
+    }
+}
+
+impl<S: MapStorageMut + Default> From<Vec<(S::Key, S::Value)>> for Map<S>
+where
+    S: Insert<Inserted<S::Key, S::Value>>,
+    S::Key: Ord,
+{
+    /// Creates a map from a vector of key-value pairs in any order.
+    ///
+    /// See [`from_unsorted_vec`](Map::from_unsorted_vec).
+    #[inline]
+    fn from(pairs: Vec<(S::Key, S::Value)>) -> Self {
+        Self::from_unsorted_vec(pairs)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: MapStorageMut + Default, H> From<std::collections::HashMap<S::Key, S::Value, H>> for Map<S>
+where
+    S: Insert<Inserted<S::Key, S::Value>>,
+    S::Key: Ord,
+{
+    /// Converts a standard library `HashMap` into a map.
+    ///
+    /// The entries are collected and sorted before building the tree bottom-up
+    /// (see [`from_unsorted_vec`](Map::from_unsorted_vec)).
+    #[inline]
+    fn from(map: std::collections::HashMap<S::Key, S::Value, H>) -> Self {
+        Self::from_unsorted_vec(map.into_iter().collect())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: MapStorageMut, H> From<Map<S>> for std::collections::HashMap<S::Key, S::Value, H>
+where
+    S::Key: Eq + Hash,
+    H: core::hash::BuildHasher + Default,
+    for<'r> S::ItemRef<'r>: Read<S>,
+{
+    /// Converts a map into a standard library `HashMap`.
+    #[inline]
+    fn from(map: Map<S>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+impl<S: MapStorageMut + Default> From<BTreeMap<S::Key, S::Value>> for Map<S>
+where
+    S: Insert<Inserted<S::Key, S::Value>>,
+{
+    /// Converts a standard library `BTreeMap` into a map.
+    ///
+    /// The entries of a `BTreeMap` being sorted by key,
+    /// the tree is built bottom-up in linear time
+    /// (see [`from_sorted_iter`](Map::from_sorted_iter)).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let std_map: BTreeMap<usize, usize> = (0..100).map(|i| (i, i * 2)).collect();
+    /// let map: Map<usize, usize> = std_map.into();
+    /// assert_eq!(map.get(&42), Some(&84));
+    ///
+    /// let std_map: BTreeMap<usize, usize> = map.into();
+    /// assert_eq!(std_map.len(), 100);
+    /// ```
+    #[inline]
+    fn from(map: BTreeMap<S::Key, S::Value>) -> Self {
+        Self::from_sorted_iter(map)
+    }
+}
+
+impl<S: MapStorageMut> From<Map<S>> for BTreeMap<S::Key, S::Value>
+where
+    S::Key: Ord,
+    for<'r> S::ItemRef<'r>: Read<S>,
+{
+    /// Converts a map into a standard library `BTreeMap`.
+    #[inline]
+    fn from(map: Map<S>) -> Self {
+        map.into_iter().collect()
     }
 }
 
 impl<S: MapStorageMut> Extend<(S::Key, S::Value)> for Map<S>
 where
     S: Insert<Inserted<S::Key, S::Value>> + KeyPartialOrd<Inserted<S::Key, S::Value>>,
-    for<'a> S::ItemMut<'a>: Replace<S, Inserted<S::Key, S::Value>, Output = S::Value>,
+    S: Replace<Inserted<S::Key, S::Value>, Output = S::Value>,
 {
     #[inline]
     fn extend<T>(&mut self, iter: T)
@@ -988,7 +2497,7 @@ impl<'a, S: 'a + MapStorage> ExactSizeIterator for Iter<'a, S> {}
 impl<'a, S: 'a + MapStorage> DoubleEndedIterator for Iter<'a, S> {
     #[inline]
     fn next_back(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(S::split_ref)
+        self.inner.next_back().map(S::split_ref)
     }
 }
 
@@ -1023,13 +2532,10 @@ impl<'a, S: 'a + MapStorage> FusedIterator for Keys<'a, S> {}
 
 impl<'a, S: 'a + MapStorage> ExactSizeIterator for Keys<'a, S> {}
 
-impl<'a, S: 'a + MapStorage> DoubleEndedIterator for Keys<'a, S>
-where
-    for<'r> S::ItemRef<'r>: Into<(S::KeyRef<'r>, S::ValueRef<'r>)>,
-{
+impl<'a, S: 'a + MapStorage> DoubleEndedIterator for Keys<'a, S> {
     #[inline]
     fn next_back(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(|item| item.into().0)
+        self.inner.next_back().map(S::key_ref)
     }
 }
 
@@ -1067,7 +2573,7 @@ impl<'a, S: 'a + MapStorage> ExactSizeIterator for Values<'a, S> {}
 impl<'a, S: 'a + MapStorage> DoubleEndedIterator for Values<'a, S> {
     #[inline]
     fn next_back(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(S::value_ref)
+        self.inner.next_back().map(S::value_ref)
     }
 }
 
@@ -1105,7 +2611,7 @@ impl<'a, S: 'a + MapStorageMut> ExactSizeIterator for ValuesMut<'a, S> {}
 impl<'a, S: 'a + MapStorageMut> DoubleEndedIterator for ValuesMut<'a, S> {
     #[inline]
     fn next_back(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(S::value_mut)
+        self.inner.next_back().map(S::value_mut)
     }
 }
 
@@ -1153,7 +2659,7 @@ impl<'a, S: 'a + MapStorageMut> ExactSizeIterator for IterMut<'a, S> {}
 impl<'a, S: 'a + MapStorageMut> DoubleEndedIterator for IterMut<'a, S> {
     #[inline]
     fn next_back(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(S::split_mut)
+        self.inner.next_back().map(S::split_mut)
     }
 }
 
@@ -1296,6 +2802,44 @@ where
     }
 }
 
+/// Consuming iterator over the items of a map.
+///
+/// This structure is created by the [`into_entries`](Map::into_entries) method on [`Map`].
+pub struct IntoEntries<S> {
+    inner: crate::btree::IntoIter<S>,
+}
+
+impl<S: MapStorageMut> FusedIterator for IntoEntries<S> where for<'r> S::ItemRef<'r>: Read<S> {}
+
+impl<S: MapStorageMut> ExactSizeIterator for IntoEntries<S> where for<'r> S::ItemRef<'r>: Read<S> {}
+
+impl<S: MapStorageMut> Iterator for IntoEntries<S>
+where
+    for<'r> S::ItemRef<'r>: Read<S>,
+{
+    type Item = S::Item;
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+impl<S: MapStorageMut> DoubleEndedIterator for IntoEntries<S>
+where
+    for<'r> S::ItemRef<'r>: Read<S>,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
 impl<S: MapStorageMut> IntoIterator for Map<S>
 where
     for<'r> S::ItemRef<'r>: Read<S>,
@@ -1309,6 +2853,31 @@ where
     }
 }
 
+/// Range draining iterator.
+///
+/// This structure is created by the [`drain`](Map::drain) method on [`Map`].
+pub struct Drain<'a, S: MapStorageMut> {
+    inner: crate::btree::Drain<'a, S>,
+}
+
+impl<'a, S: MapStorageMut> Iterator for Drain<'a, S> {
+    type Item = (S::Key, S::Value);
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(S::split)
+    }
+}
+
+impl<'a, S: MapStorageMut> FusedIterator for Drain<'a, S> {}
+
+impl<'a, S: MapStorageMut> ExactSizeIterator for Drain<'a, S> {}
+
 pub struct DrainFilter<'a, S: MapStorageMut, F>
 where
     F: for<'f> FnMut(S::KeyRef<'f>, S::ValueMut<'f>) -> bool,
@@ -1328,6 +2897,19 @@ where
             f,
         }
     }
+
+    #[inline]
+    fn with_range<T, R>(btree: &'a mut S, range: R, f: F) -> Self
+    where
+        T: ?Sized + Ord,
+        R: RangeBounds<T>,
+        S: KeyPartialOrd<T>,
+    {
+        Self {
+            inner: crate::btree::DrainFilterInner::with_range(btree, range),
+            f,
+        }
+    }
 }
 
 impl<'a, S: MapStorageMut, F> Iterator for DrainFilter<'a, S, F>
@@ -1350,6 +2932,19 @@ where
     }
 }
 
+impl<'a, S: MapStorageMut, F> DoubleEndedIterator for DrainFilter<'a, S, F>
+where
+    F: for<'f> FnMut(S::KeyRef<'f>, S::ValueMut<'f>) -> bool,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let f = &mut self.f;
+        self.inner
+            .next_back_consume(|item: S::ItemMut<'_>| filter::<S, F>(f, item))
+            .map(S::split)
+    }
+}
+
 fn filter<'i, S: MapStorageMut, F>(f: &mut F, item: S::ItemMut<'i>) -> bool
 where
     F: FnMut(S::KeyRef<'i>, S::ValueMut<'i>) -> bool,
@@ -1369,11 +2964,9 @@ where
 {
     #[inline]
     fn drop(&mut self) {
-        loop {
-            if self.next().is_none() {
-                break;
-            }
-        }
+        let f = &mut self.f;
+        self.inner
+            .finish(|item: S::ItemMut<'_>| filter::<S, F>(f, item))
     }
 }
 
@@ -1393,6 +2986,27 @@ impl<'a, S: MapStorage> Range<'a, S> {
             inner: btree.range(range),
         }
     }
+
+    /// Returns the number of entries remaining in the range.
+    ///
+    /// This takes a logarithmic time when the storage knows the length of each subtree.
+    /// Otherwise, every remaining entry of the range is visited.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let map: Map<i32, i32> = (0..100).map(|i| (i, i)).collect();
+    /// let mut range = map.range(10..20);
+    /// range.next();
+    /// range.next_back();
+    /// assert_eq!(range.remaining(), 8);
+    /// ```
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.inner.remaining()
+    }
 }
 
 impl<'a, S: 'a + MapStorage> Iterator for Range<'a, S> {
@@ -1411,12 +3025,10 @@ impl<'a, S: 'a + MapStorage> Iterator for Range<'a, S> {
 
 impl<'a, S: 'a + MapStorage> FusedIterator for Range<'a, S> {}
 
-impl<'a, S: 'a + MapStorage> ExactSizeIterator for Range<'a, S> {}
-
 impl<'a, S: 'a + MapStorage> DoubleEndedIterator for Range<'a, S> {
     #[inline]
     fn next_back(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(S::split_ref)
+        self.inner.next_back().map(S::split_ref)
     }
 }
 
@@ -1436,6 +3048,15 @@ impl<'a, S: MapStorageMut> RangeMut<'a, S> {
             inner: btree.range_mut(range),
         }
     }
+
+    /// Returns the number of entries remaining in the range.
+    ///
+    /// This takes a constant time when the storage knows the length of each subtree.
+    /// Otherwise, every remaining entry of the range is visited.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.inner.remaining()
+    }
 }
 
 impl<'a, S: 'a + MapStorageMut> Iterator for RangeMut<'a, S> {
@@ -1454,11 +3075,146 @@ impl<'a, S: 'a + MapStorageMut> Iterator for RangeMut<'a, S> {
 
 impl<'a, S: 'a + MapStorageMut> FusedIterator for RangeMut<'a, S> {}
 
-impl<'a, S: 'a + MapStorageMut> ExactSizeIterator for RangeMut<'a, S> {}
-
 impl<'a, S: 'a + MapStorageMut> DoubleEndedIterator for RangeMut<'a, S> {
     #[inline]
     fn next_back(&mut self) -> Option<Self::Item> {
-        self.inner.next().map(S::split_mut)
+        self.inner.next_back().map(S::split_mut)
+    }
+}
+
+/// Iterator over the keys of a sub-range of a map.
+///
+/// This structure is created by the [`range_keys`](Map::range_keys) method on [`Map`].
+pub struct RangeKeys<'a, S: MapStorage> {
+    inner: crate::btree::Range<'a, S>,
+}
+
+impl<'a, S: 'a + MapStorage> Iterator for RangeKeys<'a, S> {
+    type Item = S::KeyRef<'a>;
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(S::key_ref)
+    }
+}
+
+impl<'a, S: 'a + MapStorage> FusedIterator for RangeKeys<'a, S> {}
+
+impl<'a, S: 'a + MapStorage> DoubleEndedIterator for RangeKeys<'a, S> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(S::key_ref)
+    }
+}
+
+/// Iterator over the values of a sub-range of a map.
+///
+/// This structure is created by the [`range_values`](Map::range_values) method on [`Map`].
+pub struct RangeValues<'a, S: MapStorage> {
+    inner: crate::btree::Range<'a, S>,
+}
+
+impl<'a, S: 'a + MapStorage> Iterator for RangeValues<'a, S> {
+    type Item = S::ValueRef<'a>;
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(S::value_ref)
+    }
+}
+
+impl<'a, S: 'a + MapStorage> FusedIterator for RangeValues<'a, S> {}
+
+impl<'a, S: 'a + MapStorage> DoubleEndedIterator for RangeValues<'a, S> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(S::value_ref)
+    }
+}
+
+/// Mutable iterator over the values of a sub-range of a map.
+///
+/// This structure is created by the [`range_values_mut`](Map::range_values_mut) method on [`Map`].
+pub struct RangeValuesMut<'a, S: StorageMut> {
+    inner: crate::btree::RangeMut<'a, S>,
+}
+
+impl<'a, S: 'a + MapStorageMut> Iterator for RangeValuesMut<'a, S> {
+    type Item = S::ValueMut<'a>;
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(S::value_mut)
+    }
+}
+
+impl<'a, S: 'a + MapStorageMut> FusedIterator for RangeValuesMut<'a, S> {}
+
+impl<'a, S: 'a + MapStorageMut> DoubleEndedIterator for RangeValuesMut<'a, S> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(S::value_mut)
+    }
+}
+
+/// Lazy iterator over the entries of two maps, merged in key order.
+///
+/// This structure is created by the [`merge_iter`](Map::merge_iter) method on [`Map`].
+pub struct MergeIter<'a, S: MapStorage> {
+    a: core::iter::Peekable<crate::btree::Iter<'a, S>>,
+    b: core::iter::Peekable<crate::btree::Iter<'a, S>>,
+}
+
+type MergedEntry<'a, S> = (
+    <S as MapStorage>::KeyRef<'a>,
+    <S as MapStorage>::ValueRef<'a>,
+);
+
+impl<'a, S: 'a + MapStorage + KeyOrd> Iterator for MergeIter<'a, S> {
+    type Item = crate::btree::Merged<MergedEntry<'a, S>, MergedEntry<'a, S>>;
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (len, _) = self.a.size_hint();
+        let (other_len, _) = self.b.size_hint();
+        (core::cmp::max(len, other_len), Some(len + other_len))
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        use crate::btree::Merged;
+        let ordering = match (self.a.peek(), self.b.peek()) {
+            (Some(a), Some(b)) => S::key_cmp(a, b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => return None,
+        };
+
+        match ordering {
+            Ordering::Less => self.a.next().map(|a| Merged::Left(S::split_ref(a))),
+            Ordering::Equal => {
+                let a = S::split_ref(self.a.next().unwrap());
+                let b = S::split_ref(self.b.next().unwrap());
+                Some(Merged::Both(a, b))
+            }
+            Ordering::Greater => self.b.next().map(|b| Merged::Right(S::split_ref(b))),
+        }
     }
 }
+
+impl<'a, S: 'a + MapStorage + KeyOrd> FusedIterator for MergeIter<'a, S> {}
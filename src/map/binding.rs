@@ -1,5 +1,6 @@
-use std::{borrow::Borrow, cmp::Ordering};
+use core::{borrow::Borrow, cmp::Ordering};
 
+#[derive(Clone, Debug)]
 pub struct Binding<K, V> {
     pub key: K,
     pub value: V,
@@ -19,16 +20,16 @@ impl<K, V> Binding<K, V> {
     pub fn into_pair(self) -> (K, V) {
         unsafe {
             // This is safe because `self` if never used/dropped after.
-            let key = std::ptr::read(&self.key);
-            let value = std::ptr::read(&self.value);
-            std::mem::forget(self);
+            let key = core::ptr::read(&self.key);
+            let value = core::ptr::read(&self.value);
+            core::mem::forget(self);
             (key, value)
         }
     }
 
     #[inline]
     pub fn replace_value(&mut self, mut value: V) -> V {
-        std::mem::swap(&mut self.value, &mut value);
+        core::mem::swap(&mut self.value, &mut value);
         value
     }
 
@@ -46,8 +47,8 @@ impl<K, V> Binding<K, V> {
     #[inline]
     pub unsafe fn forget_value(self) {
         let (key, value) = self.into_pair();
-        std::mem::drop(key);
-        std::mem::forget(value);
+        core::mem::drop(key);
+        core::mem::forget(value);
     }
 }
 
@@ -111,3 +112,95 @@ impl<K, V> Borrow<K> for Binding<K, V> {
         &self.key
     }
 }
+
+#[cfg(feature = "dot")]
+impl<K: core::fmt::Display, V: core::fmt::Display> crate::dot::Display for Binding<K, V> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{}|{}", self.key, self.value)
+    }
+}
+
+/// Binding reference, made of a key reference and a value reference.
+///
+/// Used by storages that do not store keys and values next to each other.
+#[derive(Debug)]
+pub struct BindingRef<'a, K, V> {
+    pub key: &'a K,
+    pub value: &'a V,
+}
+
+impl<'a, K, V> BindingRef<'a, K, V> {
+    #[inline]
+    pub fn new(key: &'a K, value: &'a V) -> Self {
+        Self { key, value }
+    }
+
+    #[inline]
+    pub fn as_pair(&self) -> (&'a K, &'a V) {
+        (self.key, self.value)
+    }
+}
+
+impl<'a, K, V> Clone for BindingRef<'a, K, V> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, K, V> Copy for BindingRef<'a, K, V> {}
+
+#[cfg(feature = "dot")]
+impl<'a, K: core::fmt::Display, V: core::fmt::Display> crate::dot::Display
+    for BindingRef<'a, K, V>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{}|{}", self.key, self.value)
+    }
+}
+
+impl<'a, K, V> From<BindingRef<'a, K, V>> for (&'a K, &'a V) {
+    fn from(binding: BindingRef<'a, K, V>) -> Self {
+        binding.as_pair()
+    }
+}
+
+/// Binding mutable reference, made of a key reference and a value mutable reference.
+///
+/// Used by storages that do not store keys and values next to each other.
+/// The key cannot be modified through this reference,
+/// except by swapping the whole binding.
+pub struct BindingMut<'a, K, V> {
+    pub(crate) key: &'a mut K,
+    pub value: &'a mut V,
+}
+
+impl<'a, K, V> BindingMut<'a, K, V> {
+    #[inline]
+    pub(crate) fn new(key: &'a mut K, value: &'a mut V) -> Self {
+        Self { key, value }
+    }
+
+    #[inline]
+    pub fn key(&self) -> &K {
+        self.key
+    }
+
+    #[inline]
+    pub fn replace_value(&mut self, value: V) -> V {
+        core::mem::replace(self.value, value)
+    }
+
+    /// Swaps the referenced key and value with the ones of the given binding.
+    #[inline]
+    pub fn swap(&mut self, binding: &mut Binding<K, V>) {
+        core::mem::swap(self.key, &mut binding.key);
+        core::mem::swap(self.value, &mut binding.value)
+    }
+}
+
+impl<'a, K, V> From<BindingMut<'a, K, V>> for (&'a K, &'a mut V) {
+    fn from(binding: BindingMut<'a, K, V>) -> Self {
+        (binding.key, binding.value)
+    }
+}
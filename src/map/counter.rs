@@ -0,0 +1,258 @@
+use super::{Inserted, Iter, Map, MapStorage, MapStorageMut};
+use crate::btree::{
+    node::item::{Read, Write},
+    Insert, KeyPartialOrd,
+};
+use alloc::vec::Vec;
+use core::{iter::FromIterator, ops::Deref};
+
+/// Counter map.
+///
+/// A map associating each key to a positive count.
+/// Keys whose count reaches zero are removed from the map,
+/// so that a missing key and a key with a count of zero are indistinguishable.
+///
+/// Counts are updated with saturating arithmetic.
+pub struct CounterMap<S> {
+    map: Map<S>,
+}
+
+impl<S: MapStorage> CounterMap<S> {
+    /// Create a new empty counter map.
+    pub fn new() -> Self
+    where
+        S: Default,
+    {
+        Self { map: Map::new() }
+    }
+
+    /// Returns `true` if no key has a positive count.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns the number of keys with a positive count.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns the count of the given key.
+    ///
+    /// Returns `0` if the key is not in the map.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::CounterMap;
+    ///
+    /// let mut counter = CounterMap::new();
+    /// counter.increment("a", 2);
+    /// assert_eq!(counter.get("a"), 2);
+    /// assert_eq!(counter.get("b"), 0);
+    /// ```
+    #[inline]
+    pub fn get<Q: ?Sized>(&self, key: &Q) -> usize
+    where
+        S: KeyPartialOrd<Q>,
+        for<'r> S::ValueRef<'r>: Deref<Target = usize>,
+    {
+        self.map.get(key).map(|count| *count).unwrap_or(0)
+    }
+
+    /// Returns the sum of all the counts.
+    #[inline]
+    pub fn total(&self) -> usize
+    where
+        for<'r> S::ValueRef<'r>: Deref<Target = usize>,
+    {
+        self.map
+            .values()
+            .fold(0usize, |total, count| total.saturating_add(*count))
+    }
+
+    /// Gets an iterator over the keys and counts of the map, sorted by key.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, S> {
+        self.map.iter()
+    }
+
+    /// Returns the `n` keys with the highest count, with their count,
+    /// from the most common to the least common.
+    ///
+    /// Keys with the same count are listed in key order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::CounterMap;
+    ///
+    /// let counter: CounterMap<char> = "abracadabra".chars().collect();
+    /// assert_eq!(counter.most_common(2), vec![(&'a', 5), (&'b', 2)]);
+    /// ```
+    #[inline]
+    pub fn most_common(&self, n: usize) -> Vec<(S::KeyRef<'_>, usize)>
+    where
+        for<'r> S::ValueRef<'r>: Deref<Target = usize>,
+    {
+        let mut entries: Vec<_> = self.map.iter().map(|(key, count)| (key, *count)).collect();
+        entries.sort_by(|(_, a), (_, b)| b.cmp(a));
+        entries.truncate(n);
+        entries
+    }
+
+    /// Returns a reference to the underlying map.
+    #[inline]
+    pub fn as_map(&self) -> &Map<S> {
+        &self.map
+    }
+
+    /// Turns this counter into the underlying map.
+    #[inline]
+    pub fn into_map(self) -> Map<S> {
+        self.map
+    }
+}
+
+impl<S: MapStorageMut<Value = usize>> CounterMap<S>
+where
+    S: KeyPartialOrd<S::Key> + Insert<Inserted<S::Key, usize>>,
+    for<'r> S::ItemMut<'r>: Read<S> + Write<S>,
+{
+    /// Increments the count of the given key by `n`.
+    ///
+    /// Returns the new count of the key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::CounterMap;
+    ///
+    /// let mut counter = CounterMap::new();
+    /// assert_eq!(counter.increment("a", 1), 1);
+    /// assert_eq!(counter.increment("a", 2), 3);
+    /// assert_eq!(counter.increment("a", usize::MAX), usize::MAX);
+    /// ```
+    #[inline]
+    pub fn increment(&mut self, key: S::Key, n: usize) -> usize {
+        self.map.update(key, |count| {
+            let count = count.unwrap_or(0).saturating_add(n);
+            if count == 0 {
+                (None, 0)
+            } else {
+                (Some(count), count)
+            }
+        })
+    }
+
+    /// Decrements the count of the given key by `n`.
+    ///
+    /// The key is removed from the map when its count reaches zero.
+    /// Returns the new count of the key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::CounterMap;
+    ///
+    /// let mut counter = CounterMap::new();
+    /// counter.increment("a", 3);
+    /// assert_eq!(counter.decrement("a", 1), 2);
+    /// assert_eq!(counter.decrement("a", 5), 0);
+    /// assert!(counter.is_empty());
+    /// ```
+    #[inline]
+    pub fn decrement(&mut self, key: S::Key, n: usize) -> usize {
+        self.map.update(key, |count| {
+            let count = count.unwrap_or(0).saturating_sub(n);
+            if count == 0 {
+                (None, 0)
+            } else {
+                (Some(count), count)
+            }
+        })
+    }
+
+    /// Removes the given key from the counter.
+    ///
+    /// Returns the count of the key before removal.
+    #[inline]
+    pub fn remove<Q: ?Sized>(&mut self, key: &Q) -> usize
+    where
+        S: KeyPartialOrd<Q>,
+    {
+        self.map.remove(key).unwrap_or(0)
+    }
+
+    /// Adds all the counts of `other` into this counter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::CounterMap;
+    ///
+    /// let mut a: CounterMap<char> = "aab".chars().collect();
+    /// let b: CounterMap<char> = "bbc".chars().collect();
+    /// a.merge(b);
+    /// assert_eq!(a.get(&'a'), 2);
+    /// assert_eq!(a.get(&'b'), 3);
+    /// assert_eq!(a.get(&'c'), 1);
+    /// ```
+    #[inline]
+    pub fn merge(&mut self, other: Self)
+    where
+        for<'r> S::ItemRef<'r>: Read<S>,
+    {
+        for (key, count) in other.map {
+            self.increment(key, count);
+        }
+    }
+}
+
+impl<S: MapStorage + Default> Default for CounterMap<S> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: MapStorageMut<Value = usize>> Extend<(S::Key, usize)> for CounterMap<S>
+where
+    S: KeyPartialOrd<S::Key> + Insert<Inserted<S::Key, usize>>,
+    for<'r> S::ItemMut<'r>: Read<S> + Write<S>,
+{
+    #[inline]
+    fn extend<T>(&mut self, iter: T)
+    where
+        T: IntoIterator<Item = (S::Key, usize)>,
+    {
+        for (key, n) in iter {
+            self.increment(key, n);
+        }
+    }
+}
+
+impl<S: MapStorageMut<Value = usize> + Default> FromIterator<S::Key> for CounterMap<S>
+where
+    S: KeyPartialOrd<S::Key> + Insert<Inserted<S::Key, usize>>,
+    for<'r> S::ItemMut<'r>: Read<S> + Write<S>,
+{
+    /// Counts the occurrences of each key in the given iterator.
+    #[inline]
+    fn from_iter<T>(iter: T) -> Self
+    where
+        T: IntoIterator<Item = S::Key>,
+    {
+        let mut counter = Self::new();
+        counter.extend(iter.into_iter().map(|key| (key, 1)));
+        counter
+    }
+}
+
+impl<S: MapStorageMut> From<CounterMap<S>> for Map<S> {
+    #[inline]
+    fn from(counter: CounterMap<S>) -> Self {
+        counter.map
+    }
+}
@@ -0,0 +1,238 @@
+use super::{Inserted, MapStorage, MapStorageMut};
+use crate::btree::{node::Address, Insert};
+
+/// Cursor over the entries of a map.
+///
+/// A cursor is positioned in a gap between two entries
+/// (or before the first entry, or after the last one),
+/// and can move in both directions and look at the entries
+/// surrounding it.
+///
+/// This structure is created by the [`lower_bound`](`crate::Map::lower_bound`) and
+/// [`upper_bound`](`crate::Map::upper_bound`) methods on [`Map`](`crate::Map`).
+pub struct Cursor<'a, S> {
+    /// The tree reference.
+    btree: &'a S,
+
+    /// Address of the item after the cursor, if any.
+    next: Option<Address>,
+}
+
+impl<'a, S: MapStorage> Cursor<'a, S> {
+    /// Creates a new cursor positioned before the item at the given address,
+    /// or after the last item if `None`.
+    #[inline]
+    pub(crate) fn new(btree: &'a S, next: Option<Address>) -> Cursor<'a, S> {
+        Cursor { btree, next }
+    }
+
+    /// Returns the address of the item after the cursor, if any.
+    #[inline]
+    pub fn address(&self) -> Option<Address> {
+        self.next
+    }
+
+    /// Returns the address of the item before the cursor, if any.
+    #[inline]
+    fn previous(&self) -> Option<Address> {
+        match self.next {
+            Some(addr) => self.btree.previous_item_address(addr),
+            None => self.btree.last_item_address(),
+        }
+    }
+
+    /// Moves the cursor over the next entry, and returns it.
+    ///
+    /// Returns `None` and leaves the cursor in place
+    /// if the cursor is after the last entry.
+    #[inline]
+    pub fn move_next(&mut self) -> Option<(S::KeyRef<'a>, S::ValueRef<'a>)> {
+        let addr = self.next?;
+        self.next = self.btree.next_item_address(addr);
+        self.btree.item(addr).map(S::split_ref)
+    }
+
+    /// Moves the cursor over the previous entry, and returns it.
+    ///
+    /// Returns `None` and leaves the cursor in place
+    /// if the cursor is before the first entry.
+    #[inline]
+    pub fn move_prev(&mut self) -> Option<(S::KeyRef<'a>, S::ValueRef<'a>)> {
+        let addr = self.previous()?;
+        self.next = Some(addr);
+        self.btree.item(addr).map(S::split_ref)
+    }
+
+    /// Returns the entry after the cursor, without moving the cursor.
+    #[inline]
+    pub fn peek_next(&self) -> Option<(S::KeyRef<'a>, S::ValueRef<'a>)> {
+        let addr = self.next?;
+        self.btree.item(addr).map(S::split_ref)
+    }
+
+    /// Returns the entry before the cursor, without moving the cursor.
+    #[inline]
+    pub fn peek_prev(&self) -> Option<(S::KeyRef<'a>, S::ValueRef<'a>)> {
+        let addr = self.previous()?;
+        self.btree.item(addr).map(S::split_ref)
+    }
+}
+
+impl<'a, S> Clone for Cursor<'a, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Cursor {
+            btree: self.btree,
+            next: self.next,
+        }
+    }
+}
+
+/// Mutable cursor over the entries of a map.
+///
+/// A cursor is positioned in a gap between two entries
+/// (or before the first entry, or after the last one),
+/// and can move in both directions, look at the entries
+/// surrounding it, and insert or remove entries at its position.
+///
+/// This structure is created by the [`cursor_front_mut`](`crate::Map::cursor_front_mut`),
+/// [`cursor_back_mut`](`crate::Map::cursor_back_mut`), [`cursor_mut`](`crate::Map::cursor_mut`),
+/// [`lower_bound_mut`](`crate::Map::lower_bound_mut`) and
+/// [`upper_bound_mut`](`crate::Map::upper_bound_mut`) methods on [`Map`](`crate::Map`).
+///
+/// ## Correctness
+///
+/// It is safe to insert any key-value pair with a cursor,
+/// however this might break the well-formedness of the underlying tree,
+/// which relies on several invariants.
+/// To preserve these invariants,
+/// the inserted key must be *strictly greater* than the key of the entry before the cursor,
+/// and *strictly less* than the key of the entry after the cursor.
+/// If this rule is not respected, the data structure will become unusable
+/// (invalidate the specification of every method of the API).
+pub struct CursorMut<'a, S> {
+    /// The tree reference.
+    btree: &'a mut S,
+
+    /// Address of the item after the cursor, if any.
+    next: Option<Address>,
+}
+
+impl<'a, S: MapStorageMut> CursorMut<'a, S> {
+    /// Creates a new cursor positioned before the item at the given address,
+    /// or after the last item if `None`.
+    #[inline]
+    pub(crate) fn new(btree: &'a mut S, next: Option<Address>) -> CursorMut<'a, S> {
+        CursorMut { btree, next }
+    }
+
+    /// Returns the address of the item after the cursor, if any.
+    #[inline]
+    pub fn address(&self) -> Option<Address> {
+        self.next
+    }
+
+    /// Returns the address of the item before the cursor, if any.
+    #[inline]
+    fn previous(&self) -> Option<Address> {
+        match self.next {
+            Some(addr) => self.btree.previous_item_address(addr),
+            None => self.btree.last_item_address(),
+        }
+    }
+
+    /// Moves the cursor over the next entry, and returns it.
+    ///
+    /// Returns `None` and leaves the cursor in place
+    /// if the cursor is after the last entry.
+    #[inline]
+    pub fn move_next(&mut self) -> Option<(S::KeyRef<'_>, S::ValueMut<'_>)> {
+        let addr = self.next?;
+        self.next = self.btree.next_item_address(addr);
+        self.btree.item_mut(addr).map(S::split_mut)
+    }
+
+    /// Moves the cursor over the previous entry, and returns it.
+    ///
+    /// Returns `None` and leaves the cursor in place
+    /// if the cursor is before the first entry.
+    #[inline]
+    pub fn move_prev(&mut self) -> Option<(S::KeyRef<'_>, S::ValueMut<'_>)> {
+        let addr = self.previous()?;
+        self.next = Some(addr);
+        self.btree.item_mut(addr).map(S::split_mut)
+    }
+
+    /// Returns the entry after the cursor, without moving the cursor.
+    #[inline]
+    pub fn peek_next(&mut self) -> Option<(S::KeyRef<'_>, S::ValueMut<'_>)> {
+        let addr = self.next?;
+        self.btree.item_mut(addr).map(S::split_mut)
+    }
+
+    /// Returns the entry before the cursor, without moving the cursor.
+    #[inline]
+    pub fn peek_prev(&mut self) -> Option<(S::KeyRef<'_>, S::ValueMut<'_>)> {
+        let addr = self.previous()?;
+        self.btree.item_mut(addr).map(S::split_mut)
+    }
+
+    /// Inserts the given entry at the position of the cursor.
+    ///
+    /// Returns the address of the inserted item.
+    #[inline]
+    fn insert(&mut self, key: S::Key, value: S::Value) -> Address
+    where
+        S: Insert<Inserted<S::Key, S::Value>>,
+    {
+        let addr = match self.next {
+            Some(addr) => addr,
+            None => self.btree.last_valid_address(),
+        };
+
+        self.btree.insert_at(addr, Inserted(key, value))
+    }
+
+    /// Inserts a new entry before the cursor.
+    ///
+    /// The cursor is moved after the inserted entry,
+    /// which becomes the entry returned by [`peek_prev`](Self::peek_prev).
+    ///
+    /// See the [correctness](#correctness) section
+    /// for the constraints on the inserted key.
+    #[inline]
+    pub fn insert_before(&mut self, key: S::Key, value: S::Value)
+    where
+        S: Insert<Inserted<S::Key, S::Value>>,
+    {
+        let addr = self.insert(key, value);
+        self.next = self.btree.next_item_address(addr)
+    }
+
+    /// Inserts a new entry after the cursor.
+    ///
+    /// The cursor stays in place,
+    /// and the inserted entry becomes the entry returned by [`peek_next`](Self::peek_next).
+    ///
+    /// See the [correctness](#correctness) section
+    /// for the constraints on the inserted key.
+    #[inline]
+    pub fn insert_after(&mut self, key: S::Key, value: S::Value)
+    where
+        S: Insert<Inserted<S::Key, S::Value>>,
+    {
+        self.next = Some(self.insert(key, value))
+    }
+
+    /// Removes the entry after the cursor and returns it.
+    ///
+    /// The cursor stays in place, between the previous entry
+    /// and the entry following the removed one.
+    #[inline]
+    pub fn remove_current(&mut self) -> Option<(S::Key, S::Value)> {
+        let addr = self.next?;
+        let (item, addr) = self.btree.remove_at(addr)?;
+        self.next = self.btree.normalize(addr);
+        Some(S::split(item))
+    }
+}
@@ -0,0 +1,77 @@
+use super::{Map, MapStorage};
+use crate::encode::{Encode, Encoder};
+use std::io;
+
+impl<S: MapStorage> Map<S> {
+    /// Writes the entries of the map with the given encoder,
+    /// as a map in key order.
+    ///
+    /// Keys and values are written by the `encode_key` and `encode_value` functions,
+    /// which makes it possible to export types not implementing [`Encode`],
+    /// or to choose their representation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{encode::{Encoder, MessagePack}, slab::Map};
+    ///
+    /// let map: Map<u32, (u8, u8)> = vec![(1, (2, 3))].into_iter().collect();
+    ///
+    /// let mut encoder = MessagePack::new(Vec::new());
+    /// map.encode_with(
+    ///     &mut encoder,
+    ///     |key, e| e.encode_u64(*key as u64),
+    ///     |(a, b), e| {
+    ///         e.encode_array_header(2)?;
+    ///         e.encode_u64(*a as u64)?;
+    ///         e.encode_u64(*b as u64)
+    ///     },
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(encoder.into_inner(), [0x81, 0x01, 0x92, 0x02, 0x03]);
+    /// ```
+    pub fn encode_with<'a, E: Encoder, K, V>(
+        &'a self,
+        encoder: &mut E,
+        mut encode_key: K,
+        mut encode_value: V,
+    ) -> io::Result<()>
+    where
+        K: FnMut(S::KeyRef<'a>, &mut E) -> io::Result<()>,
+        V: FnMut(S::ValueRef<'a>, &mut E) -> io::Result<()>,
+    {
+        encoder.encode_map_header(self.len())?;
+        for (key, value) in self.iter() {
+            encode_key(key, encoder)?;
+            encode_value(value, encoder)?
+        }
+
+        Ok(())
+    }
+
+    /// Writes the entries of the map with the given encoder,
+    /// as a map in key order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{encode::Cbor, slab::Map};
+    ///
+    /// let map: Map<u32, &str> = vec![(1, "a"), (2, "bc")].into_iter().collect();
+    ///
+    /// let mut encoder = Cbor::new(Vec::new());
+    /// map.encode(&mut encoder).unwrap();
+    /// assert_eq!(
+    ///     encoder.into_inner(),
+    ///     [0xa2, 0x01, 0x61, b'a', 0x02, 0x62, b'b', b'c']
+    /// );
+    /// ```
+    pub fn encode<E: Encoder>(&self, encoder: &mut E) -> io::Result<()>
+    where
+        for<'r> S::KeyRef<'r>: Encode,
+        for<'r> S::ValueRef<'r>: Encode,
+    {
+        self.encode_with(encoder, |key, e| key.encode(e), |value, e| value.encode(e))
+    }
+}
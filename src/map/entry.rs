@@ -1,6 +1,14 @@
 use super::{Inserted, MapStorage, MapStorageMut};
-use crate::btree::{node::item::Replace, node::Address, Insert};
-use std::{fmt, ops::Deref};
+use crate::btree::{
+    node::item::{Read, Replace, Write},
+    node::Address,
+    Insert, KeyPartialOrd,
+};
+use core::{
+    cmp::Ordering,
+    fmt,
+    ops::{Bound, Deref},
+};
 
 /// A view into a single entry in a map, which may either be vacant or occupied.
 ///
@@ -95,7 +103,7 @@ impl<'a, S: MapStorageMut> Entry<'a, S> {
     /// let mut map: Map<&str, usize> = Map::new();
     /// map.entry("poneyland").or_insert(12);
     ///
-    /// assert_eq!(*map.get("poneyland").unwrap(), 12);
+    /// assert_eq!(map["poneyland"], 12);
     /// ```
     #[inline]
     pub fn or_insert(self, default: S::Value) -> S::ValueMut<'a>
@@ -121,7 +129,7 @@ impl<'a, S: MapStorageMut> Entry<'a, S> {
     ///
     /// map.entry("poneyland").or_insert_with(|| s);
     ///
-    /// assert_eq!(*map.get("poneyland").unwrap(), "hoho".to_string());
+    /// assert_eq!(map["poneyland"], "hoho".to_string());
     /// ```
     #[inline]
     pub fn or_insert_with<F: FnOnce() -> S::Value>(self, default: F) -> S::ValueMut<'a>
@@ -141,14 +149,13 @@ impl<'a, S: MapStorageMut> Entry<'a, S> {
     /// # Examples
     ///
     /// ```
-    /// #![feature(or_insert_with_key)]
     /// use generic_btree::slab::Map;
     ///
     /// let mut map: Map<&str, usize> = Map::new();
     ///
     /// map.entry("poneyland").or_insert_with_key(|key| key.chars().count());
     ///
-    /// assert_eq!(*map.get("poneyland").unwrap(), 9);
+    /// assert_eq!(map["poneyland"], 9);
     /// ```
     #[inline]
     pub fn or_insert_with_key<F: FnOnce(&S::Key) -> S::Value>(self, default: F) -> S::ValueMut<'a>
@@ -177,12 +184,12 @@ impl<'a, S: MapStorageMut> Entry<'a, S> {
     /// map.entry("poneyland")
     ///    .and_modify(|e| { *e += 1 })
     ///    .or_insert(42);
-    /// assert_eq!(*map.get("poneyland").unwrap(), 42);
+    /// assert_eq!(map["poneyland"], 42);
     ///
     /// map.entry("poneyland")
     ///    .and_modify(|e| { *e += 1 })
     ///    .or_insert(42);
-    /// assert_eq!(*map.get("poneyland").unwrap(), 43);
+    /// assert_eq!(map["poneyland"], 43);
     /// ```
     #[inline]
     pub fn and_modify<F>(self, f: F) -> Self
@@ -198,6 +205,46 @@ impl<'a, S: MapStorageMut> Entry<'a, S> {
         }
     }
 
+    /// Provides shared access to the key and owned access to the value of
+    /// an occupied entry and allows to replace or remove it based on the
+    /// value of the returned option.
+    ///
+    /// See [`OccupiedEntry::replace_entry_with`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    /// use generic_btree::map::Entry;
+    ///
+    /// let mut map: Map<&str, u32> = Map::new();
+    /// map.insert("poneyland", 42);
+    ///
+    /// let entry = map
+    ///     .entry("poneyland")
+    ///     .and_replace_entry_with(|_k, v| Some(v + 1));
+    /// assert!(matches!(entry, Entry::Occupied(_)));
+    /// assert_eq!(map["poneyland"], 43);
+    ///
+    /// let entry = map
+    ///     .entry("poneyland")
+    ///     .and_replace_entry_with(|_k, _v| None);
+    /// assert!(matches!(entry, Entry::Vacant(_)));
+    /// assert!(!map.contains_key("poneyland"));
+    /// ```
+    #[inline]
+    pub fn and_replace_entry_with<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&S::Key, S::Value) -> Option<S::Value>,
+        S: KeyPartialOrd<S::Key> + Insert<Inserted<S::Key, S::Value>>,
+        for<'r> S::ItemMut<'r>: Read<S> + Write<S>,
+    {
+        match self {
+            Occupied(entry) => entry.replace_entry_with(f),
+            Vacant(entry) => Vacant(entry),
+        }
+    }
+
     /// Ensures a value is in the entry by inserting the default value if empty,
     /// and returns a mutable reference to the value in the entry.
     ///
@@ -209,7 +256,7 @@ impl<'a, S: MapStorageMut> Entry<'a, S> {
     /// let mut map: Map<&str, Option<usize>> = Map::new();
     /// map.entry("poneyland").or_default();
     ///
-    /// assert_eq!(*map.get("poneyland").unwrap(), None);
+    /// assert_eq!(map["poneyland"], None);
     /// ```
     #[inline]
     pub fn or_default(self) -> S::ValueMut<'a>
@@ -304,7 +351,7 @@ where
     /// if let Entry::Vacant(o) = map.entry("poneyland") {
     ///     o.insert(37);
     /// }
-    /// assert_eq!(*map.get("poneyland").unwrap(), 37);
+    /// assert_eq!(map["poneyland"], 37);
     /// ```
     #[inline]
     pub fn insert(self, value: S::Value) -> S::ValueMut<'a> {
@@ -323,6 +370,217 @@ where
     }
 }
 
+/// A view into a single entry in a map, which may either be vacant or occupied,
+/// looked up with a borrowed key.
+///
+/// This enum is constructed from the [`entry_ref`](`Map#entry_ref`) method on [`Map`].
+/// Contrarily to [`Entry`], the owned key is only created
+/// (from the borrowed key, using [`From`]) when a value is inserted in a vacant entry.
+pub enum EntryRef<'a, 'b, S: MapStorageMut, Q: ?Sized> {
+    Vacant(VacantEntryRef<'a, 'b, S, Q>),
+    Occupied(OccupiedEntry<'a, S>),
+}
+
+impl<'a, 'b, S: MapStorageMut, Q: ?Sized> EntryRef<'a, 'b, S, Q> {
+    /// Gets the address of the entry in the B-Tree.
+    #[inline]
+    pub fn address(&self) -> Address {
+        match self {
+            Self::Occupied(entry) => entry.address(),
+            Self::Vacant(entry) => entry.address(),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the default if empty, and returns
+    /// a mutable reference to the value in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map: Map<String, usize> = Map::new();
+    /// map.entry_ref("poneyland").or_insert(12);
+    ///
+    /// assert_eq!(map["poneyland"], 12);
+    /// ```
+    #[inline]
+    pub fn or_insert(self, default: S::Value) -> S::ValueMut<'a>
+    where
+        S: Insert<Inserted<S::Key, S::Value>>,
+        S::Key: From<&'b Q>,
+    {
+        match self {
+            Self::Occupied(entry) => entry.into_mut(),
+            Self::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the result of the default function if empty,
+    /// and returns a mutable reference to the value in the entry.
+    #[inline]
+    pub fn or_insert_with<F: FnOnce() -> S::Value>(self, default: F) -> S::ValueMut<'a>
+    where
+        S: Insert<Inserted<S::Key, S::Value>>,
+        S::Key: From<&'b Q>,
+    {
+        match self {
+            Self::Occupied(entry) => entry.into_mut(),
+            Self::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting, if empty, the result of the default function,
+    /// which takes the borrowed key as its argument, and returns a mutable reference to the value
+    /// in the entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map: Map<String, usize> = Map::new();
+    ///
+    /// map.entry_ref("poneyland").or_insert_with_key(|key| key.chars().count());
+    ///
+    /// assert_eq!(map["poneyland"], 9);
+    /// ```
+    #[inline]
+    pub fn or_insert_with_key<F: FnOnce(&Q) -> S::Value>(self, default: F) -> S::ValueMut<'a>
+    where
+        S: Insert<Inserted<S::Key, S::Value>>,
+        S::Key: From<&'b Q>,
+    {
+        match self {
+            Self::Occupied(entry) => entry.into_mut(),
+            Self::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any
+    /// potential inserts into the map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map: Map<String, usize> = Map::new();
+    ///
+    /// map.entry_ref("poneyland")
+    ///    .and_modify(|e| { *e += 1 })
+    ///    .or_insert(42);
+    /// assert_eq!(map["poneyland"], 42);
+    ///
+    /// map.entry_ref("poneyland")
+    ///    .and_modify(|e| { *e += 1 })
+    ///    .or_insert(42);
+    /// assert_eq!(map["poneyland"], 43);
+    /// ```
+    #[inline]
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(S::ValueMut<'_>),
+    {
+        match self {
+            Self::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Self::Occupied(entry)
+            }
+            Self::Vacant(entry) => Self::Vacant(entry),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting the default value if empty,
+    /// and returns a mutable reference to the value in the entry.
+    #[inline]
+    pub fn or_default(self) -> S::ValueMut<'a>
+    where
+        S::Value: Default,
+        S: Insert<Inserted<S::Key, S::Value>>,
+        S::Key: From<&'b Q>,
+    {
+        match self {
+            Self::Occupied(entry) => entry.into_mut(),
+            Self::Vacant(entry) => entry.insert(Default::default()),
+        }
+    }
+}
+
+impl<'a, 'b, S: MapStorageMut, Q: ?Sized + fmt::Debug> fmt::Debug for EntryRef<'a, 'b, S, Q>
+where
+    for<'r> S::KeyRef<'r>: fmt::Debug,
+    for<'r> S::ValueRef<'r>: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Occupied(entry) => entry.fmt(f),
+            Self::Vacant(entry) => entry.fmt(f),
+        }
+    }
+}
+
+/// A view into a vacant entry in a [`Map`], looked up with a borrowed key.
+/// It is part of the [`EntryRef`] enum.
+pub struct VacantEntryRef<'a, 'b, S, Q: ?Sized> {
+    pub(crate) map: &'a mut S,
+    pub(crate) key: &'b Q,
+    pub(crate) addr: Address,
+}
+
+impl<'a, 'b, S: MapStorageMut, Q: ?Sized> VacantEntryRef<'a, 'b, S, Q> {
+    /// Gets the address of the vacant entry in the B-Tree.
+    #[inline]
+    pub fn address(&self) -> Address {
+        self.addr
+    }
+
+    /// Gets a reference to the borrowed key that would be used
+    /// to create the owned key when inserting a value through the `VacantEntryRef`.
+    #[inline]
+    pub fn key(&self) -> &'b Q {
+        self.key
+    }
+
+    /// Sets the value of the entry with an owned key created from the borrowed key,
+    /// and returns a mutable reference to it.
+    ///
+    /// ## Example
+    /// ```
+    /// use generic_btree::slab::Map;
+    /// use generic_btree::map::EntryRef;
+    ///
+    /// let mut map: Map<String, u32> = Map::new();
+    ///
+    /// if let EntryRef::Vacant(o) = map.entry_ref("poneyland") {
+    ///     o.insert(37);
+    /// }
+    /// assert_eq!(map["poneyland"], 37);
+    /// ```
+    #[inline]
+    pub fn insert(self, value: S::Value) -> S::ValueMut<'a>
+    where
+        S: Insert<Inserted<S::Key, S::Value>>,
+        S::Key: From<&'b Q>,
+    {
+        let addr = self
+            .map
+            .insert_at(self.addr, Inserted(self.key.into(), value));
+        S::value_mut(self.map.item_mut(addr).unwrap())
+    }
+}
+
+impl<'a, 'b, S, Q: ?Sized + fmt::Debug> fmt::Debug for VacantEntryRef<'a, 'b, S, Q> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("VacantEntryRef").field(&self.key).finish()
+    }
+}
+
 /// A view into an occupied entry in a [`Map`].
 /// It is part of the [`Entry`] enum.
 pub struct OccupiedEntry<'a, S> {
@@ -386,7 +644,7 @@ impl<'a, S: MapStorageMut> OccupiedEntry<'a, S> {
     /// let mut map: Map<&str, usize> = Map::new();
     /// map.entry("poneyland").or_insert(12);
     ///
-    /// assert_eq!(*map.get("poneyland").unwrap(), 12);
+    /// assert_eq!(map["poneyland"], 12);
     /// if let Entry::Occupied(mut o) = map.entry("poneyland") {
     ///     *o.get_mut() += 10;
     ///     assert_eq!(*o.get(), 22);
@@ -394,7 +652,7 @@ impl<'a, S: MapStorageMut> OccupiedEntry<'a, S> {
     ///     // We can use the same Entry multiple times.
     ///     *o.get_mut() += 2;
     /// }
-    /// assert_eq!(*map.get("poneyland").unwrap(), 24);
+    /// assert_eq!(map["poneyland"], 24);
     /// ```
     #[inline]
     pub fn get_mut(&mut self) -> S::ValueMut<'_> {
@@ -415,17 +673,14 @@ impl<'a, S: MapStorageMut> OccupiedEntry<'a, S> {
     /// if let Entry::Occupied(mut o) = map.entry("poneyland") {
     ///     assert_eq!(o.insert(15), 12);
     /// }
-    /// assert_eq!(*map.get("poneyland").unwrap(), 15);
+    /// assert_eq!(map["poneyland"], 15);
     /// ```
     #[inline]
-    pub fn insert<'r>(
-        &'r mut self,
-        value: S::Value,
-    ) -> <S::ItemMut<'r> as Replace<S, S::Value>>::Output
+    pub fn insert(&mut self, value: S::Value) -> <S as Replace<S::Value>>::Output
     where
-        S::ItemMut<'r>: Replace<S, S::Value>,
+        S: Replace<S::Value>,
     {
-        self.map.item_mut(self.addr).unwrap().replace(value)
+        self.map.replace_at(self.addr, value)
     }
 
     /// Converts the entry into a mutable reference to its value.
@@ -443,11 +698,11 @@ impl<'a, S: MapStorageMut> OccupiedEntry<'a, S> {
     /// let mut map: Map<&str, usize> = Map::new();
     /// map.entry("poneyland").or_insert(12);
     ///
-    /// assert_eq!(*map.get("poneyland").unwrap(), 12);
+    /// assert_eq!(map["poneyland"], 12);
     /// if let Entry::Occupied(o) = map.entry("poneyland") {
     ///     *o.into_mut() += 10;
     /// }
-    /// assert_eq!(*map.get("poneyland").unwrap(), 22);
+    /// assert_eq!(map["poneyland"], 22);
     /// ```
     #[inline]
     pub fn into_mut(self) -> S::ValueMut<'a> {
@@ -469,13 +724,75 @@ impl<'a, S: MapStorageMut> OccupiedEntry<'a, S> {
     ///     assert_eq!(o.remove(), 12);
     /// }
     /// // If we try to get "poneyland"'s value, it'll panic:
-    /// // println!("{}", *map.get("poneyland").unwrap());
+    /// // println!("{}", map["poneyland"]);
     /// ```
     #[inline]
     pub fn remove(self) -> S::Value {
         S::value(self.map.remove_at(self.addr).unwrap().0)
     }
 
+    /// Provides shared access to the key and owned access to the value of
+    /// the entry and allows to replace or remove it based on the
+    /// value of the returned option.
+    ///
+    /// If `f` returns `Some`, the value is replaced in place
+    /// and an occupied entry is returned.
+    /// Otherwise the entry is removed from the map,
+    /// and the vacant entry of the removed key is returned.
+    ///
+    /// If `f` panics, the entry is removed from the map,
+    /// its key and value being dropped by `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    /// use generic_btree::map::Entry;
+    ///
+    /// let mut map: Map<&str, u32> = Map::new();
+    /// map.insert("poneyland", 42);
+    ///
+    /// if let Entry::Occupied(o) = map.entry("poneyland") {
+    ///     match o.replace_entry_with(|k, v| {
+    ///         assert_eq!(k, &"poneyland");
+    ///         assert_eq!(v, 42);
+    ///         None
+    ///     }) {
+    ///         Entry::Vacant(v) => assert_eq!(v.key(), &"poneyland"),
+    ///         Entry::Occupied(_) => panic!(),
+    ///     }
+    /// }
+    ///
+    /// assert!(!map.contains_key("poneyland"));
+    /// ```
+    #[inline]
+    pub fn replace_entry_with<F>(self, f: F) -> Entry<'a, S>
+    where
+        F: FnOnce(&S::Key, S::Value) -> Option<S::Value>,
+        S: KeyPartialOrd<S::Key> + Insert<Inserted<S::Key, S::Value>>,
+        for<'r> S::ItemMut<'r>: Read<S> + Write<S>,
+    {
+        let removed_key = self.map.update_at(self.addr, |item| {
+            let (key, value) = S::split(item);
+            match f(&key, value) {
+                Some(value) => (Some(Inserted(key, value)), None),
+                None => (None, Some(key)),
+            }
+        });
+
+        match removed_key {
+            None => Occupied(self),
+            Some(key) => {
+                let addr = self.map.address_of(&key).unwrap_err();
+                Vacant(VacantEntry {
+                    map: self.map,
+                    key,
+                    addr,
+                })
+            }
+        }
+    }
+
     /// Take ownership of the key and value from the map.
     ///
     /// # Example
@@ -492,7 +809,7 @@ impl<'a, S: MapStorageMut> OccupiedEntry<'a, S> {
     /// }
     ///
     /// // If now try to get the value, it will panic:
-    /// // println!("{}", *map.get("poneyland").unwrap());
+    /// // println!("{}", map["poneyland"]);
     /// ```
     #[inline]
     pub fn remove_entry(self) -> S::Item {
@@ -514,6 +831,51 @@ where
     }
 }
 
+/// The error returned by [`try_insert`](`Map#try_insert`) when the key already exists.
+///
+/// Contains the occupied entry, and the value that was not inserted.
+pub struct OccupiedError<'a, S: MapStorageMut> {
+    /// The entry in the map that was already occupied.
+    pub entry: OccupiedEntry<'a, S>,
+
+    /// The value which was not inserted, because the entry was already occupied.
+    pub value: S::Value,
+}
+
+impl<'a, S: MapStorageMut> fmt::Debug for OccupiedError<'a, S>
+where
+    S::Value: fmt::Debug,
+    for<'r> S::KeyRef<'r>: fmt::Debug,
+    for<'r> S::ValueRef<'r>: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("OccupiedError")
+            .field("key", &self.entry.key())
+            .field("old_value", &self.entry.get())
+            .field("new_value", &self.value)
+            .finish()
+    }
+}
+
+impl<'a, S: MapStorageMut> fmt::Display for OccupiedError<'a, S>
+where
+    S::Value: fmt::Debug,
+    for<'r> S::KeyRef<'r>: fmt::Debug,
+    for<'r> S::ValueRef<'r>: fmt::Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "failed to insert {:?}, key {:?} already exists with value {:?}",
+            self.value,
+            self.entry.key(),
+            self.entry.get(),
+        )
+    }
+}
+
 /// Iterator that can mutate the tree in place.
 pub struct EntriesMut<'a, S> {
     /// The tree reference.
@@ -552,7 +914,7 @@ impl<'a, S: MapStorageMut> EntriesMut<'a, S> {
     #[inline]
     pub fn next_item(&mut self) -> Option<S::ItemMut<'a>> {
         // this is safe because only one mutable reference to the same item can be emitted.
-        let btree: &'a mut S = unsafe { std::ptr::read(&self.btree) };
+        let btree: &'a mut S = unsafe { core::ptr::read(&self.btree) };
 
         let after_addr = btree.next_item_or_back_address(self.addr);
         match btree.item_mut(self.addr) {
@@ -599,6 +961,77 @@ impl<'a, S: MapStorageMut> EntriesMut<'a, S> {
             None => None,
         }
     }
+
+    /// Removes every item from the next item up to the given bound,
+    /// and returns the number of removed items.
+    ///
+    /// Only the next item is removed individually.
+    /// The rest of the range is detached at once by splitting the tree around it
+    /// (as in [`StorageMut::split_off_range`](crate::StorageMut::split_off_range)),
+    /// which avoids rebalancing the tree after each removal.
+    /// The iterator is then positioned on the first item after the bound.
+    ///
+    /// Nothing is removed if the next item is already past the bound.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use core::ops::Bound;
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map: Map<i32, i32> = (0..100).map(|i| (i, i)).collect();
+    ///
+    /// let mut entries = map.entries_mut();
+    /// entries.next();
+    /// assert_eq!(entries.remove_range(Bound::Included(&50)), 50);
+    /// assert_eq!(entries.next().map(|binding| binding.key), Some(51));
+    ///
+    /// assert_eq!(map.len(), 50);
+    /// assert!(map.keys().copied().eq((0..1).chain(51..100)));
+    /// ```
+    pub fn remove_range<Q: ?Sized>(&mut self, end: Bound<&Q>) -> usize
+    where
+        S: Default + KeyPartialOrd<Q> + KeyPartialOrd<S::Key>,
+    {
+        let in_range = match self.btree.item(self.addr) {
+            Some(item) => match end {
+                Bound::Included(end) => S::key_partial_cmp(&item, end).map(Ordering::is_le),
+                Bound::Excluded(end) => S::key_partial_cmp(&item, end).map(Ordering::is_lt),
+                Bound::Unbounded => Some(true),
+            },
+            None => None,
+        };
+
+        if in_range != Some(true) {
+            return 0;
+        }
+
+        // The key of the next item is needed to split the tree.
+        let (item, _) = self.btree.remove_at(self.addr).unwrap();
+        let (key, _) = S::split(item);
+
+        let mut tail = match end {
+            Bound::Included(end) => self.btree.split_off_at(end, true),
+            Bound::Excluded(end) => self.btree.split_off_at(end, false),
+            Bound::Unbounded => self.btree.empty(),
+        };
+
+        let removed = self.btree.split_off_at(&key, false);
+
+        if self.btree.is_empty() {
+            core::mem::swap(self.btree, &mut tail)
+        } else if let Some(separator) = tail.pop_first() {
+            self.btree.concat_with(separator, tail, false)
+        }
+
+        let count = removed.len() + 1;
+        self.len -= count;
+        self.addr = self
+            .btree
+            .lower_bound(Bound::Excluded(&key))
+            .unwrap_or_else(|| self.btree.last_valid_address());
+        count
+    }
 }
 
 impl<'a, S: MapStorageMut> Iterator for EntriesMut<'a, S> {
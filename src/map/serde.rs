@@ -0,0 +1,128 @@
+use super::{Inserted, Map, MapStorage, MapStorageMut};
+use crate::btree::{node::item::Replace, Insert, KeyPartialOrd};
+use core::{fmt, marker::PhantomData};
+use serde::{
+    de::{Deserialize, Deserializer, SeqAccess, Visitor},
+    ser::{Serialize, Serializer},
+};
+
+/// Serializes the map as a sequence of key-value pairs, in key order.
+impl<S: MapStorage> Serialize for Map<S>
+where
+    for<'r> S::KeyRef<'r>: Serialize,
+    for<'r> S::ValueRef<'r>: Serialize,
+{
+    fn serialize<T: Serializer>(&self, serializer: T) -> Result<T::Ok, T::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+/// Deserializes a map from a sequence of key-value pairs.
+///
+/// As long as the keys are sorted, the tree is built bottom-up in linear time
+/// (see [`StorageMut::build_from_sorted`](crate::StorageMut::build_from_sorted)).
+/// Once an unsorted or duplicate key is detected,
+/// the remaining pairs are inserted one by one.
+impl<'de, S> Deserialize<'de> for Map<S>
+where
+    S: MapStorageMut + Default,
+    S: Insert<Inserted<S::Key, S::Value>> + KeyPartialOrd<Inserted<S::Key, S::Value>>,
+    S: Replace<Inserted<S::Key, S::Value>, Output = S::Value>,
+    S::Key: Deserialize<'de> + Ord,
+    S::Value: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(MapVisitor(PhantomData))
+    }
+}
+
+/// Map visitor.
+struct MapVisitor<S>(PhantomData<S>);
+
+impl<'de, S> Visitor<'de> for MapVisitor<S>
+where
+    S: MapStorageMut + Default,
+    S: Insert<Inserted<S::Key, S::Value>> + KeyPartialOrd<Inserted<S::Key, S::Value>>,
+    S: Replace<Inserted<S::Key, S::Value>, Output = S::Value>,
+    S::Key: Deserialize<'de> + Ord,
+    S::Value: Deserialize<'de>,
+{
+    type Value = Map<S>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a sequence of key-value pairs")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Map<S>, A::Error> {
+        let mut sorted = Sorted {
+            seq,
+            next: None,
+            unsorted: None,
+            error: None,
+        };
+
+        sorted.next = sorted.read();
+        let mut btree = S::default();
+        btree.build_from_sorted(sorted.by_ref().map(|(key, value)| Inserted(key, value)));
+
+        if let Some(error) = sorted.error {
+            return Err(error);
+        }
+
+        let mut map = Map::from_btree(btree);
+        if let Some((key, value)) = sorted.unsorted {
+            map.insert(key, value);
+            while let Some((key, value)) = sorted.seq.next_element()? {
+                map.insert(key, value);
+            }
+        }
+
+        Ok(map)
+    }
+}
+
+/// Sorted prefix of a sequence of key-value pairs.
+///
+/// Yields the pairs of the sequence as long as their keys are strictly increasing.
+struct Sorted<A, K, V, E> {
+    /// Sequence.
+    seq: A,
+
+    /// Next pair, read in advance to be compared with the previous one.
+    next: Option<(K, V)>,
+
+    /// First pair whose key is not greater than the previous one, if any.
+    unsorted: Option<(K, V)>,
+
+    /// Deserialization error, if any.
+    error: Option<E>,
+}
+
+impl<'de, A: SeqAccess<'de>, K: Deserialize<'de>, V: Deserialize<'de>> Sorted<A, K, V, A::Error> {
+    /// Reads the next pair of the sequence, recording any error.
+    fn read(&mut self) -> Option<(K, V)> {
+        match self.seq.next_element() {
+            Ok(pair) => pair,
+            Err(e) => {
+                self.error = Some(e);
+                None
+            }
+        }
+    }
+}
+
+impl<'de, A: SeqAccess<'de>, K: Deserialize<'de> + Ord, V: Deserialize<'de>> Iterator
+    for Sorted<A, K, V, A::Error>
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        let current = self.next.take()?;
+        match self.read() {
+            Some(next) if next.0 > current.0 => self.next = Some(next),
+            next => self.unsorted = next,
+        }
+
+        Some(current)
+    }
+}
@@ -0,0 +1,482 @@
+use crate::{
+    btree::Storage as _,
+    map::Binding,
+    slab::{
+        Node, NodeId, OccupiedSlots, RawSlots, SlotCapacity, SlotIndex, Storage, VacantSlots,
+        DEFAULT_ORDER,
+    },
+};
+use core::{marker::PhantomData, mem, ptr::NonNull, slice};
+use std::{
+    fs::{File, OpenOptions},
+    io,
+    os::unix::io::AsRawFd,
+    path::Path,
+};
+
+pub type MapStorage<K, V, const M: usize = DEFAULT_ORDER> =
+    Storage<Binding<K, V>, MmapSlab<Node<Binding<K, V>, M>>, (), M>;
+pub type Map<K, V, const M: usize = DEFAULT_ORDER> = crate::Map<MapStorage<K, V, M>>;
+
+/// Magic number identifying memory-mapped slab files.
+const MAGIC: [u8; 8] = *b"GBTMMAP\0";
+
+/// Number of slots of a newly created file.
+const INITIAL_CAPACITY: usize = 16;
+
+/// Header value representing the absence of root.
+const NO_ROOT: u64 = u64::MAX;
+
+/// File header.
+#[repr(C)]
+struct Header {
+    /// Magic number.
+    magic: [u8; 8],
+
+    /// Size of a slot, used to detect layout mismatches.
+    slot_size: u64,
+
+    /// Number of slots in the file.
+    capacity: u64,
+
+    /// Number of occupied slots.
+    len: u64,
+
+    /// Index of the first vacant slot, or `capacity` if the slab is full.
+    next_vacant: u64,
+
+    /// Root node of the stored tree.
+    root: u64,
+
+    /// Number of items in the stored tree.
+    tree_len: u64,
+}
+
+/// Slot.
+#[repr(usize)]
+enum Slot<T> {
+    /// Vacant slot, holding the index of the next vacant slot.
+    Vacant(usize),
+
+    /// Occupied slot.
+    Occupied(T),
+}
+
+/// Memory-mapped file slab.
+///
+/// Values are stored in fixed-size slots of a file mapped in memory,
+/// right after a small header.
+/// The file is read in place: opening it requires no deserialization,
+/// and the same file can be mapped by several processes.
+/// The file grows (and is remapped) when the slab is full.
+///
+/// The in-memory representation of the values is written as is,
+/// so a file can only be opened by a program built with the same value types,
+/// and the values must not own any resource outside of the slab
+/// (pointers, heap allocations, file descriptors, etc.).
+/// Values are never dropped when the slab itself is dropped.
+///
+/// Changes are written back to the file by the operating system,
+/// or explicitly with [`MmapSlab::flush`].
+pub struct MmapSlab<T> {
+    /// Mapped file.
+    file: File,
+
+    /// Start of the mapping.
+    ptr: NonNull<u8>,
+
+    /// Length of the mapping.
+    map_len: usize,
+
+    /// Value type.
+    value: PhantomData<T>,
+}
+
+impl<T> MmapSlab<T> {
+    /// Offset of the first slot in the file.
+    fn slots_offset() -> usize {
+        let align = mem::align_of::<Slot<T>>();
+        mem::size_of::<Header>().div_ceil(align) * align
+    }
+
+    /// Length of a file with the given number of slots.
+    fn file_len(capacity: usize) -> usize {
+        Self::slots_offset() + capacity * mem::size_of::<Slot<T>>()
+    }
+
+    /// Creates a new empty slab in the given file.
+    ///
+    /// The file is created if it does not exist, and truncated otherwise.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified by another process or mapping
+    /// while the slab is alive.
+    pub unsafe fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        let len = Self::file_len(INITIAL_CAPACITY);
+        file.set_len(len as u64)?;
+
+        let mut slab = Self::map(file, len)?;
+        core::ptr::write(
+            slab.ptr.as_ptr() as *mut Header,
+            Header {
+                magic: MAGIC,
+                slot_size: mem::size_of::<Slot<T>>() as u64,
+                capacity: 0,
+                len: 0,
+                next_vacant: 0,
+                root: NO_ROOT,
+                tree_len: 0,
+            },
+        );
+        slab.init_slots(0, INITIAL_CAPACITY);
+        Ok(slab)
+    }
+
+    /// Opens a slab previously created with [`MmapSlab::create`].
+    ///
+    /// # Safety
+    ///
+    /// The file must have been written by a program using the same value type
+    /// and built with the same compiler, for the same target.
+    /// It must not be modified by another process or mapping
+    /// while the slab is alive.
+    pub unsafe fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let len = file.metadata()?.len() as usize;
+        if len < Self::slots_offset() {
+            return Err(invalid_file());
+        }
+
+        let slab = Self::map(file, len)?;
+        let header = slab.header();
+        if header.magic != MAGIC
+            || header.slot_size != mem::size_of::<Slot<T>>() as u64
+            || Self::file_len(header.capacity as usize) > len
+        {
+            return Err(invalid_file());
+        }
+
+        Ok(slab)
+    }
+
+    /// Maps the first `len` bytes of the given file.
+    unsafe fn map(file: File, len: usize) -> io::Result<Self> {
+        let ptr = mmap(&file, len)?;
+        Ok(Self {
+            file,
+            ptr,
+            map_len: len,
+            value: PhantomData,
+        })
+    }
+
+    /// Initializes the slots in `from..to` as vacant slots
+    /// and sets the capacity to `to`.
+    ///
+    /// The slots must be in the mapping, and `from` must be the current capacity.
+    unsafe fn init_slots(&mut self, from: usize, to: usize) {
+        let slots = self.ptr.as_ptr().add(Self::slots_offset()) as *mut Slot<T>;
+        for i in from..to {
+            core::ptr::write(slots.add(i), Slot::Vacant(i + 1))
+        }
+
+        self.header_mut().capacity = to as u64
+    }
+
+    /// Doubles the number of slots in the file.
+    fn grow(&mut self) -> io::Result<()> {
+        let capacity = self.capacity();
+        let new_capacity = core::cmp::max(capacity * 2, INITIAL_CAPACITY);
+        let len = Self::file_len(new_capacity);
+        self.file.set_len(len as u64)?;
+
+        unsafe {
+            let ptr = mmap(&self.file, len)?;
+            libc::munmap(self.ptr.as_ptr() as *mut libc::c_void, self.map_len);
+            self.ptr = ptr;
+            self.map_len = len;
+            self.init_slots(capacity, new_capacity)
+        }
+
+        Ok(())
+    }
+
+    fn header(&self) -> &Header {
+        unsafe { &*(self.ptr.as_ptr() as *const Header) }
+    }
+
+    fn header_mut(&mut self) -> &mut Header {
+        unsafe { &mut *(self.ptr.as_ptr() as *mut Header) }
+    }
+
+    /// Returns the number of slots in the file.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.header().capacity as usize
+    }
+
+    fn slots(&self) -> &[Slot<T>] {
+        unsafe {
+            slice::from_raw_parts(
+                self.ptr.as_ptr().add(Self::slots_offset()) as *const Slot<T>,
+                self.capacity(),
+            )
+        }
+    }
+
+    fn slots_mut(&mut self) -> &mut [Slot<T>] {
+        unsafe {
+            slice::from_raw_parts_mut(
+                self.ptr.as_ptr().add(Self::slots_offset()) as *mut Slot<T>,
+                self.capacity(),
+            )
+        }
+    }
+
+    /// Returns the root and length of the tree recorded in the file.
+    fn tree(&self) -> (Option<usize>, usize) {
+        let header = self.header();
+        let root = if header.root == NO_ROOT {
+            None
+        } else {
+            Some(header.root as usize)
+        };
+
+        (root, header.tree_len as usize)
+    }
+
+    /// Records the root and length of the tree in the file.
+    fn set_tree(&mut self, root: Option<usize>, len: usize) {
+        let header = self.header_mut();
+        header.root = root.map(|id| id as u64).unwrap_or(NO_ROOT);
+        header.tree_len = len as u64
+    }
+
+    /// Synchronously writes the mapped memory back to the file.
+    pub fn flush(&self) -> io::Result<()> {
+        let result = unsafe {
+            libc::msync(
+                self.ptr.as_ptr() as *mut libc::c_void,
+                self.map_len,
+                libc::MS_SYNC,
+            )
+        };
+
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+}
+
+impl<T> Drop for MmapSlab<T> {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr.as_ptr() as *mut libc::c_void, self.map_len);
+        }
+    }
+}
+
+/// Maps the first `len` bytes of the given file in memory, shared and writable.
+unsafe fn mmap(file: &File, len: usize) -> io::Result<NonNull<u8>> {
+    let ptr = libc::mmap(
+        core::ptr::null_mut(),
+        len,
+        libc::PROT_READ | libc::PROT_WRITE,
+        libc::MAP_SHARED,
+        file.as_raw_fd(),
+        0,
+    );
+
+    if ptr == libc::MAP_FAILED {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(NonNull::new_unchecked(ptr as *mut u8))
+    }
+}
+
+fn invalid_file() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "invalid memory-mapped slab file",
+    )
+}
+
+impl<T> SlotIndex for MmapSlab<T> {}
+
+impl<T> VacantSlots for MmapSlab<T> {}
+
+impl<T> OccupiedSlots for MmapSlab<T> {
+    #[inline]
+    fn occupied_keys(&self) -> Option<Vec<usize>> {
+        Some(
+            self.slots()
+                .iter()
+                .enumerate()
+                .filter(|(_, slot)| matches!(slot, Slot::Occupied(_)))
+                .map(|(key, _)| key)
+                .collect(),
+        )
+    }
+}
+
+impl<T> SlotCapacity for MmapSlab<T> {}
+
+impl<T> RawSlots for MmapSlab<T> {}
+
+impl<T> cc_traits::Collection for MmapSlab<T> {
+    type Item = T;
+}
+
+impl<T> cc_traits::Len for MmapSlab<T> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.header().len as usize
+    }
+}
+
+impl<T> cc_traits::Get<usize> for MmapSlab<T> {
+    #[inline]
+    fn get(&self, key: usize) -> Option<&T> {
+        match self.slots().get(key) {
+            Some(Slot::Occupied(value)) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl<T> cc_traits::GetMut<usize> for MmapSlab<T> {
+    #[inline]
+    fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        match self.slots_mut().get_mut(key) {
+            Some(Slot::Occupied(value)) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl<T> cc_traits::Insert for MmapSlab<T> {
+    type Output = usize;
+
+    /// Inserts a value in the slab and returns its key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slab is full and the file cannot be extended.
+    #[inline]
+    fn insert(&mut self, value: T) -> usize {
+        if self.header().next_vacant == self.header().capacity {
+            self.grow().expect("unable to grow memory-mapped slab")
+        }
+
+        let key = self.header().next_vacant as usize;
+        match mem::replace(&mut self.slots_mut()[key], Slot::Occupied(value)) {
+            Slot::Vacant(next) => {
+                let header = self.header_mut();
+                header.next_vacant = next as u64;
+                header.len += 1;
+                key
+            }
+            Slot::Occupied(_) => unreachable!(),
+        }
+    }
+}
+
+impl<T> cc_traits::Remove<usize> for MmapSlab<T> {
+    #[inline]
+    fn remove(&mut self, key: usize) -> Option<T> {
+        let next_vacant = self.header().next_vacant as usize;
+        let slot = self.slots_mut().get_mut(key)?;
+        match mem::replace(slot, Slot::Vacant(next_vacant)) {
+            Slot::Occupied(value) => {
+                let header = self.header_mut();
+                header.next_vacant = key as u64;
+                header.len -= 1;
+                Some(value)
+            }
+            vacant => {
+                *slot = vacant;
+                None
+            }
+        }
+    }
+}
+
+impl<T> cc_traits::Clear for MmapSlab<T> {
+    #[inline]
+    fn clear(&mut self) {
+        for (i, slot) in self.slots_mut().iter_mut().enumerate() {
+            *slot = Slot::Vacant(i + 1)
+        }
+
+        let header = self.header_mut();
+        header.next_vacant = 0;
+        header.len = 0
+    }
+}
+
+impl<K: Copy, V: Copy, const M: usize, I: NodeId>
+    Storage<Binding<K, V>, MmapSlab<Node<Binding<K, V>, M, I>>, (), M, I>
+{
+    /// Creates a new empty storage in the given file.
+    ///
+    /// The file is created if it does not exist, and truncated otherwise.
+    ///
+    /// # Safety
+    ///
+    /// See [`MmapSlab::create`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::mmap::{Map, MapStorage};
+    ///
+    /// let path = std::env::temp_dir().join("generic-btree-mmap-example");
+    ///
+    /// let mut map: Map<u32, u64> = Map::from_btree(unsafe { MapStorage::create(&path).unwrap() });
+    /// for i in 0..100 {
+    ///     map.insert(i, i as u64 * 2);
+    /// }
+    ///
+    /// map.btree_mut().flush().unwrap();
+    /// std::mem::drop(map);
+    ///
+    /// let map: Map<u32, u64> = Map::from_btree(unsafe { MapStorage::open(&path).unwrap() });
+    /// assert_eq!(map.len(), 100);
+    /// assert_eq!(map.get(&42), Some(&84));
+    /// # std::fs::remove_file(path).unwrap();
+    /// ```
+    pub unsafe fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        MmapSlab::create(path).map(Self::from_slab)
+    }
+
+    /// Opens a storage previously created with [`Storage::create`]
+    /// and saved with [`Storage::flush`].
+    ///
+    /// # Safety
+    ///
+    /// See [`MmapSlab::open`].
+    pub unsafe fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let slab = MmapSlab::open(path)?;
+        let (root, len) = slab.tree();
+        Ok(Self::from_raw_parts(slab, root, len))
+    }
+
+    /// Records the current state of the tree in the file
+    /// and synchronously writes it back to the disk.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.trim();
+        let (root, len) = (self.root(), self.len());
+        let slab = self.slab_mut();
+        slab.set_tree(root, len);
+        slab.flush()
+    }
+}
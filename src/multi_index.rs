@@ -0,0 +1,340 @@
+use crate::{map, slab::Map};
+use core::{borrow::Borrow, marker::PhantomData};
+
+/// Key extractor.
+///
+/// Defines one ordering of the items of a [`MultiIndex`] container.
+/// The implementing type is used as a tag to select the corresponding index.
+pub trait KeyExtractor<T> {
+    /// Key type.
+    type Key: Ord;
+
+    /// Extracts the key of the given item.
+    fn key(item: &T) -> Self::Key;
+}
+
+/// Index of a [`MultiIndex`] container, ordering the items by the key extracted by `X`.
+pub struct Index<T, X: KeyExtractor<T>> {
+    /// Map from keys to item identifiers.
+    map: Map<X::Key, usize>,
+
+    /// Item type.
+    item: PhantomData<T>,
+}
+
+impl<T, X: KeyExtractor<T>> Default for Index<T, X> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            map: Map::new(),
+            item: PhantomData,
+        }
+    }
+}
+
+impl<T, X: KeyExtractor<T>> Index<T, X> {
+    /// Returns the identifier of the item with the given key, if any.
+    #[inline]
+    fn get<Q>(&self, key: &Q) -> Option<usize>
+    where
+        Q: ?Sized + Ord,
+        X::Key: Borrow<Q>,
+    {
+        self.map.get(key).cloned()
+    }
+}
+
+/// List of index tags.
+///
+/// This trait is implemented for tuples of [`KeyExtractor`]s (up to 4),
+/// each defining an index of the [`MultiIndex`] container.
+pub trait Tags<T> {
+    /// Indexes.
+    type Indexes: Default;
+
+    /// Returns the identifier of an item sharing one of its keys with `item`, if any.
+    fn conflict(indexes: &Self::Indexes, item: &T) -> Option<usize>;
+
+    /// Adds the item with the given identifier to every index.
+    fn insert(indexes: &mut Self::Indexes, id: usize, item: &T);
+
+    /// Removes the given item from every index.
+    fn remove(indexes: &mut Self::Indexes, item: &T);
+}
+
+/// Index position in a list of tags.
+pub struct At<const N: usize>;
+
+/// Index selection.
+///
+/// A tags list `Self` implements `Select<T, X, At<N>>` when its `N`-th tag is `X`.
+/// The position is generally inferred.
+pub trait Select<T, X: KeyExtractor<T>, P>: Tags<T> {
+    /// Returns the index with tag `X`.
+    fn select(indexes: &Self::Indexes) -> &Index<T, X>;
+}
+
+macro_rules! tags {
+    ($($x:ident: $i:tt),*) => {
+        impl<T, $($x: KeyExtractor<T>),*> Tags<T> for ($($x,)*) {
+            type Indexes = ($(Index<T, $x>,)*);
+
+            #[inline]
+            fn conflict(indexes: &Self::Indexes, item: &T) -> Option<usize> {
+                $(
+                    if let Some(id) = indexes.$i.get(&$x::key(item)) {
+                        return Some(id)
+                    }
+                )*
+
+                None
+            }
+
+            #[inline]
+            fn insert(indexes: &mut Self::Indexes, id: usize, item: &T) {
+                $(
+                    indexes.$i.map.insert($x::key(item), id);
+                )*
+            }
+
+            #[inline]
+            fn remove(indexes: &mut Self::Indexes, item: &T) {
+                $(
+                    indexes.$i.map.remove(&$x::key(item));
+                )*
+            }
+        }
+    };
+}
+
+tags!(X0: 0);
+tags!(X0: 0, X1: 1);
+tags!(X0: 0, X1: 1, X2: 2);
+tags!(X0: 0, X1: 1, X2: 2, X3: 3);
+
+macro_rules! select {
+    (($($x:ident),*) $selected:ident: $i:tt) => {
+        impl<T, $($x: KeyExtractor<T>),*> Select<T, $selected, At<$i>> for ($($x,)*) {
+            #[inline]
+            fn select(indexes: &Self::Indexes) -> &Index<T, $selected> {
+                &indexes.$i
+            }
+        }
+    };
+}
+
+select!((X0) X0: 0);
+select!((X0, X1) X0: 0);
+select!((X0, X1) X1: 1);
+select!((X0, X1, X2) X0: 0);
+select!((X0, X1, X2) X1: 1);
+select!((X0, X1, X2) X2: 2);
+select!((X0, X1, X2, X3) X0: 0);
+select!((X0, X1, X2, X3) X1: 1);
+select!((X0, X1, X2, X3) X2: 2);
+select!((X0, X1, X2, X3) X3: 3);
+
+/// Multi-index container.
+///
+/// Items are stored once, in a single slab,
+/// and indexed by one B-Tree for each tag of `I`.
+/// Each tag is a [`KeyExtractor`] defining the ordering of its index.
+/// Every index is unique: two items cannot share the same key in any index.
+///
+/// # Example
+///
+/// ```
+/// use generic_btree::multi_index::{KeyExtractor, MultiIndex};
+///
+/// struct Employee {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// struct ById;
+///
+/// impl KeyExtractor<Employee> for ById {
+///     type Key = u32;
+///
+///     fn key(e: &Employee) -> u32 {
+///         e.id
+///     }
+/// }
+///
+/// struct ByName;
+///
+/// impl KeyExtractor<Employee> for ByName {
+///     type Key = String;
+///
+///     fn key(e: &Employee) -> String {
+///         e.name.clone()
+///     }
+/// }
+///
+/// let mut employees: MultiIndex<Employee, (ById, ByName)> = MultiIndex::new();
+/// assert!(employees.insert(Employee { id: 1, name: "Bob".to_string() }).is_ok());
+/// assert!(employees.insert(Employee { id: 0, name: "Alice".to_string() }).is_ok());
+///
+/// // Conflicting id.
+/// assert!(employees.insert(Employee { id: 1, name: "Carol".to_string() }).is_err());
+///
+/// assert_eq!(employees.index::<ById, _>().get(&1).unwrap().name, "Bob");
+/// assert_eq!(employees.index::<ByName, _>().get("Alice").unwrap().id, 0);
+///
+/// let names: Vec<_> = employees.index::<ById, _>().iter().map(|e| e.name.as_str()).collect();
+/// assert_eq!(names, ["Alice", "Bob"]);
+///
+/// let bob = employees.remove::<ByName, _, _>("Bob").unwrap();
+/// assert_eq!(bob.id, 1);
+/// assert!(employees.index::<ById, _>().get(&1).is_none());
+/// ```
+pub struct MultiIndex<T, I: Tags<T>> {
+    /// Items.
+    items: slab::Slab<T>,
+
+    /// Indexes.
+    indexes: I::Indexes,
+}
+
+impl<T, I: Tags<T>> MultiIndex<T, I> {
+    /// Create a new empty container.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            items: slab::Slab::new(),
+            indexes: I::Indexes::default(),
+        }
+    }
+
+    /// Returns the number of items in the container.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the container contains no items.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Inserts an item in the container, updating every index.
+    ///
+    /// If one of the keys of the item is already used by another item,
+    /// no index is modified and the item is returned back.
+    #[inline]
+    pub fn insert(&mut self, item: T) -> Result<(), T> {
+        if I::conflict(&self.indexes, &item).is_some() {
+            return Err(item);
+        }
+
+        let id = self.items.insert(item);
+        I::insert(&mut self.indexes, id, &self.items[id]);
+        Ok(())
+    }
+
+    /// Returns the index with tag `X`.
+    #[inline]
+    pub fn index<X: KeyExtractor<T>, P>(&self) -> IndexRef<'_, T, X>
+    where
+        I: Select<T, X, P>,
+    {
+        IndexRef {
+            items: &self.items,
+            index: I::select(&self.indexes),
+        }
+    }
+
+    /// Removes the item with the given key in the index with tag `X`,
+    /// updating every index.
+    #[inline]
+    pub fn remove<X: KeyExtractor<T>, P, Q>(&mut self, key: &Q) -> Option<T>
+    where
+        I: Select<T, X, P>,
+        Q: ?Sized + Ord,
+        X::Key: Borrow<Q>,
+    {
+        let id = I::select(&self.indexes).get(key)?;
+        let item = self.items.remove(id);
+        I::remove(&mut self.indexes, &item);
+        Some(item)
+    }
+
+    /// Remove every item from the container.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.indexes = I::Indexes::default()
+    }
+}
+
+impl<T, I: Tags<T>> Default for MultiIndex<T, I> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reference to an index of a [`MultiIndex`] container.
+pub struct IndexRef<'a, T, X: KeyExtractor<T>> {
+    /// Items.
+    items: &'a slab::Slab<T>,
+
+    /// Index.
+    index: &'a Index<T, X>,
+}
+
+impl<'a, T, X: KeyExtractor<T>> IndexRef<'a, T, X> {
+    /// Returns the item with the given key in this index, if any.
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<&'a T>
+    where
+        Q: ?Sized + Ord,
+        X::Key: Borrow<Q>,
+    {
+        self.index.get(key).map(|id| &self.items[id])
+    }
+
+    /// Checks if an item has the given key in this index.
+    #[inline]
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        Q: ?Sized + Ord,
+        X::Key: Borrow<Q>,
+    {
+        self.index.get(key).is_some()
+    }
+
+    /// Gets an iterator over the items, sorted by the key of this index.
+    #[inline]
+    pub fn iter(&self) -> IndexIter<'a, T, X> {
+        IndexIter {
+            items: self.items,
+            ids: self.index.map.values(),
+        }
+    }
+}
+
+/// Iterator over the items of a [`MultiIndex`] container,
+/// sorted by the key of an index.
+pub struct IndexIter<'a, T, X: KeyExtractor<T>> {
+    /// Items.
+    items: &'a slab::Slab<T>,
+
+    /// Item identifiers.
+    ids: map::Values<'a, crate::slab::MapStorage<X::Key, usize>>,
+}
+
+impl<'a, T, X: KeyExtractor<T>> Iterator for IndexIter<'a, T, X> {
+    type Item = &'a T;
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ids.size_hint()
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        self.ids.next().map(|id| &self.items[*id])
+    }
+}
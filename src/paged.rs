@@ -0,0 +1,981 @@
+use crate::{
+    btree::{self, Storage as _},
+    map::Binding,
+    slab::{
+        node::{Internal, Leaf},
+        Node, NodeId, OccupiedSlots, RawSlots, SlotCapacity, SlotIndex, Storage, VacantSlots,
+        DEFAULT_ORDER,
+    },
+};
+use alloc::{string::String, vec, vec::Vec};
+use core::convert::TryInto;
+
+/// Page cache.
+pub mod cache;
+
+/// Write-ahead logging.
+pub mod wal;
+
+pub type MapStorage<K, V, P, const M: usize = DEFAULT_ORDER> =
+    Storage<Binding<K, V>, PagedSlab<Node<Binding<K, V>, M>, P>, (), M>;
+pub type Map<K, V, P, const M: usize = DEFAULT_ORDER> = crate::Map<MapStorage<K, V, P, M>>;
+
+/// Identifier of the page holding the storage header.
+///
+/// This page is reserved: it is never returned by [`Pager::allocate`].
+pub const HEADER_PAGE: usize = 0;
+
+/// Magic number identifying the storage header.
+const MAGIC: [u8; 8] = *b"GBTPAGED";
+
+/// Length of the frame preceding the payload of a page:
+/// the payload length and checksum, both encoded as `u32`.
+const FRAME_LEN: usize = 8;
+
+/// CRC-32 (IEEE) lookup table.
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut k = 0;
+        while k < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb88320
+            } else {
+                crc >> 1
+            };
+            k += 1
+        }
+
+        table[i] = crc;
+        i += 1
+    }
+
+    table
+}
+
+/// Computes the CRC-32 (IEEE) checksum of the given bytes.
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, b| {
+        CRC32_TABLE[((crc ^ *b as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+/// Fills `page` with `payload`, preceded by its length and checksum,
+/// and padded to the page size.
+///
+/// Returns `false` if the framed payload does not fit in a page.
+fn frame(payload: &[u8], page: &mut Vec<u8>, page_size: usize) -> bool {
+    if FRAME_LEN + payload.len() > page_size {
+        return false;
+    }
+
+    page.clear();
+    (payload.len() as u32).encode(page);
+    crc32(payload).encode(page);
+    page.extend_from_slice(payload);
+    page.resize(page_size, 0);
+    true
+}
+
+/// Extracts the payload of the given page, checking its checksum.
+fn unframe<E>(id: usize, mut page: &[u8]) -> Result<&[u8], Error<E>> {
+    let len = u32::decode(&mut page).ok_or(Error::Corrupted(id))?;
+    let checksum = u32::decode(&mut page).ok_or(Error::Corrupted(id))?;
+    let payload = take(&mut page, len as usize).ok_or(Error::Corrupted(id))?;
+    if crc32(payload) == checksum {
+        Ok(payload)
+    } else {
+        Err(Error::Checksum(id))
+    }
+}
+
+/// Encodes the given node and writes it to the given page.
+///
+/// `payload` and `buf` are scratch buffers.
+fn write_node<T: Codec, P: Pager, const M: usize, I: NodeId>(
+    pager: &mut P,
+    id: usize,
+    node: &Node<T, M, I>,
+    payload: &mut Vec<u8>,
+    buf: &mut Vec<u8>,
+) -> Result<(), Error<P::Error>> {
+    payload.clear();
+    node.encode(payload);
+    if !frame(payload, buf, pager.page_size()) {
+        return Err(Error::Overflow(id));
+    }
+
+    pager.write_page(id, buf).map_err(Error::Pager)
+}
+
+/// Distributes `total` units into nodes holding at most `max` units.
+///
+/// Every node is full, except for the last two nodes,
+/// which share the remaining units if the last node would hold less than `min` units.
+fn pack(total: usize, max: usize, min: usize) -> Vec<usize> {
+    let count = total.div_ceil(max);
+    let mut sizes = vec![max; count];
+    if count > 0 {
+        sizes[count - 1] = total - (count - 1) * max;
+        if count > 1 && sizes[count - 1] < min {
+            let shared = sizes[count - 2] + sizes[count - 1];
+            sizes[count - 2] = shared - shared / 2;
+            sizes[count - 1] = shared / 2;
+        }
+    }
+
+    sizes
+}
+
+/// Fixed-size page reader/writer.
+///
+/// A pager gives access to an array of pages of [`Pager::page_size`] bytes,
+/// identified by their index.
+pub trait Pager {
+    /// Error type.
+    type Error;
+
+    /// Size of a page, in bytes.
+    fn page_size(&self) -> usize;
+
+    /// Reads the given page into `buf`, whose length is the page size.
+    fn read_page(&mut self, id: usize, buf: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Writes `buf`, whose length is the page size, into the given page.
+    fn write_page(&mut self, id: usize, buf: &[u8]) -> Result<(), Self::Error>;
+
+    /// Reserves a new page and returns its identifier.
+    ///
+    /// The returned identifier is never [`HEADER_PAGE`].
+    fn allocate(&mut self) -> usize;
+
+    /// Releases the given page, so that it can be returned again by [`Pager::allocate`].
+    fn free(&mut self, id: usize);
+
+    /// Makes every page written since the last commit durable.
+    ///
+    /// Called at the end of each synchronization of a paged storage.
+    /// Does nothing by default.
+    #[inline]
+    fn commit(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Binary encoding of the items stored in pages.
+pub trait Codec: Sized {
+    /// Appends the encoding of the value to the given buffer.
+    fn encode(&self, buf: &mut Vec<u8>);
+
+    /// Decodes a value from the front of `bytes`, advancing it past the value.
+    ///
+    /// Returns `None` if the bytes do not encode a valid value.
+    fn decode(bytes: &mut &[u8]) -> Option<Self>;
+}
+
+/// Splits the first `n` bytes off `bytes`.
+fn take<'a>(bytes: &mut &'a [u8], n: usize) -> Option<&'a [u8]> {
+    if bytes.len() < n {
+        None
+    } else {
+        let (head, tail) = bytes.split_at(n);
+        *bytes = tail;
+        Some(head)
+    }
+}
+
+macro_rules! int_codec {
+    ($($ty:ty),*) => {
+        $(
+            impl Codec for $ty {
+                #[inline]
+                fn encode(&self, buf: &mut Vec<u8>) {
+                    buf.extend_from_slice(&self.to_le_bytes())
+                }
+
+                #[inline]
+                fn decode(bytes: &mut &[u8]) -> Option<Self> {
+                    let head = take(bytes, core::mem::size_of::<$ty>())?;
+                    Some(<$ty>::from_le_bytes(head.try_into().unwrap()))
+                }
+            }
+        )*
+    };
+}
+
+int_codec!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl Codec for usize {
+    #[inline]
+    fn encode(&self, buf: &mut Vec<u8>) {
+        (*self as u64).encode(buf)
+    }
+
+    #[inline]
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        u64::decode(bytes).and_then(|n| n.try_into().ok())
+    }
+}
+
+impl Codec for isize {
+    #[inline]
+    fn encode(&self, buf: &mut Vec<u8>) {
+        (*self as i64).encode(buf)
+    }
+
+    #[inline]
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        i64::decode(bytes).and_then(|n| n.try_into().ok())
+    }
+}
+
+impl Codec for bool {
+    #[inline]
+    fn encode(&self, buf: &mut Vec<u8>) {
+        (*self as u8).encode(buf)
+    }
+
+    #[inline]
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        match u8::decode(bytes)? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl Codec for () {
+    #[inline]
+    fn encode(&self, _buf: &mut Vec<u8>) {}
+
+    #[inline]
+    fn decode(_bytes: &mut &[u8]) -> Option<Self> {
+        Some(())
+    }
+}
+
+impl<T: Codec> Codec for Option<T> {
+    #[inline]
+    fn encode(&self, buf: &mut Vec<u8>) {
+        match self {
+            Some(value) => {
+                true.encode(buf);
+                value.encode(buf)
+            }
+            None => false.encode(buf),
+        }
+    }
+
+    #[inline]
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        if bool::decode(bytes)? {
+            T::decode(bytes).map(Some)
+        } else {
+            Some(None)
+        }
+    }
+}
+
+impl<T: Codec> Codec for Vec<T> {
+    #[inline]
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.len().encode(buf);
+        for item in self {
+            item.encode(buf)
+        }
+    }
+
+    #[inline]
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        let len = usize::decode(bytes)?;
+        let mut result = Vec::new();
+        for _ in 0..len {
+            result.push(T::decode(bytes)?)
+        }
+
+        Some(result)
+    }
+}
+
+impl Codec for String {
+    #[inline]
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.len().encode(buf);
+        buf.extend_from_slice(self.as_bytes())
+    }
+
+    #[inline]
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        let len = usize::decode(bytes)?;
+        let head = take(bytes, len)?;
+        String::from_utf8(head.to_vec()).ok()
+    }
+}
+
+impl<K: Codec, V: Codec> Codec for Binding<K, V> {
+    #[inline]
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.key.encode(buf);
+        self.value.encode(buf)
+    }
+
+    #[inline]
+    fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        let key = K::decode(bytes)?;
+        let value = V::decode(bytes)?;
+        Some(Binding::new(key, value))
+    }
+}
+
+/// Bulk loading statistics.
+///
+/// Returned by [`Storage::bulk_load`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BulkLoadStats {
+    /// Number of written node pages.
+    pub page_count: usize,
+
+    /// Height of the tree.
+    pub height: usize,
+}
+
+/// Paged storage error.
+#[derive(Debug)]
+pub enum Error<E> {
+    /// Pager error.
+    Pager(E),
+
+    /// The encoding of the node stored in the given page exceeds the page size.
+    Overflow(usize),
+
+    /// The given page is not a valid node or header page.
+    Corrupted(usize),
+
+    /// The checksum of the given page does not match its content.
+    Checksum(usize),
+}
+
+/// In-memory pager.
+///
+/// Mostly useful for testing, or to serialize a tree into a list of pages.
+pub struct MemoryPager {
+    /// Size of a page.
+    page_size: usize,
+
+    /// Pages.
+    pages: Vec<Vec<u8>>,
+
+    /// Free pages.
+    free: Vec<usize>,
+}
+
+impl MemoryPager {
+    /// Creates a new pager with the given page size, containing only the header page.
+    #[inline]
+    pub fn new(page_size: usize) -> Self {
+        Self {
+            page_size,
+            pages: vec![vec![0; page_size]],
+            free: Vec::new(),
+        }
+    }
+}
+
+impl Pager for MemoryPager {
+    type Error = core::convert::Infallible;
+
+    #[inline]
+    fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    #[inline]
+    fn read_page(&mut self, id: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        buf.copy_from_slice(&self.pages[id]);
+        Ok(())
+    }
+
+    #[inline]
+    fn write_page(&mut self, id: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.pages[id].copy_from_slice(buf);
+        Ok(())
+    }
+
+    #[inline]
+    fn allocate(&mut self) -> usize {
+        match self.free.pop() {
+            Some(id) => id,
+            None => {
+                self.pages.push(vec![0; self.page_size]);
+                self.pages.len() - 1
+            }
+        }
+    }
+
+    #[inline]
+    fn free(&mut self, id: usize) {
+        self.free.push(id)
+    }
+}
+
+#[cfg(feature = "std")]
+pub use file::FilePager;
+
+#[cfg(feature = "std")]
+mod file {
+    use super::{Pager, HEADER_PAGE};
+    use std::{
+        fs::{File, OpenOptions},
+        io::{self, Read, Seek, SeekFrom, Write},
+        path::Path,
+    };
+
+    /// File pager.
+    ///
+    /// Page `n` is stored at offset `n * page_size` in the file.
+    /// Freed pages are reused by the next allocations,
+    /// but the list of free pages is not persisted:
+    /// pages freed before the file is reopened are lost.
+    pub struct FilePager {
+        /// File.
+        file: File,
+
+        /// Size of a page.
+        page_size: usize,
+
+        /// Number of pages in the file, including allocated pages not yet written.
+        page_count: usize,
+
+        /// Free pages.
+        free: Vec<usize>,
+    }
+
+    impl FilePager {
+        /// Creates a new empty paged file.
+        ///
+        /// The file is created if it does not exist, and truncated otherwise.
+        pub fn create(path: impl AsRef<Path>, page_size: usize) -> io::Result<Self> {
+            let file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(path)?;
+
+            Ok(Self {
+                file,
+                page_size,
+                page_count: HEADER_PAGE + 1,
+                free: Vec::new(),
+            })
+        }
+
+        /// Opens an existing paged file.
+        pub fn open(path: impl AsRef<Path>, page_size: usize) -> io::Result<Self> {
+            let file = OpenOptions::new().read(true).write(true).open(path)?;
+            let len = file.metadata()?.len() as usize;
+
+            Ok(Self {
+                file,
+                page_size,
+                page_count: core::cmp::max(len.div_ceil(page_size), HEADER_PAGE + 1),
+                free: Vec::new(),
+            })
+        }
+    }
+
+    impl Pager for FilePager {
+        type Error = io::Error;
+
+        #[inline]
+        fn page_size(&self) -> usize {
+            self.page_size
+        }
+
+        fn read_page(&mut self, id: usize, buf: &mut [u8]) -> io::Result<()> {
+            self.file
+                .seek(SeekFrom::Start((id * self.page_size) as u64))?;
+            self.file.read_exact(buf)
+        }
+
+        fn write_page(&mut self, id: usize, buf: &[u8]) -> io::Result<()> {
+            self.file
+                .seek(SeekFrom::Start((id * self.page_size) as u64))?;
+            self.file.write_all(buf)
+        }
+
+        #[inline]
+        fn allocate(&mut self) -> usize {
+            match self.free.pop() {
+                Some(id) => id,
+                None => {
+                    self.page_count += 1;
+                    self.page_count - 1
+                }
+            }
+        }
+
+        #[inline]
+        fn free(&mut self, id: usize) {
+            self.free.push(id)
+        }
+
+        /// Synchronously writes the file content to the disk.
+        fn commit(&mut self) -> io::Result<()> {
+            self.file.sync_data()
+        }
+    }
+}
+
+/// Page.
+struct Page<T> {
+    /// Value stored in the page, if any.
+    value: Option<T>,
+
+    /// Whether the value has been modified since the last synchronization.
+    dirty: bool,
+}
+
+impl<T> Default for Page<T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            value: None,
+            dirty: false,
+        }
+    }
+}
+
+/// Page-backed slab.
+///
+/// Each value is assigned a page allocated by the pager `P`,
+/// whose identifier is the key of the value.
+/// Each page starts with the length and CRC-32 checksum of its content,
+/// verified when the page is read.
+/// Values are kept decoded in memory,
+/// and are only written to their page when the slab is synchronized
+/// (see [`Storage::sync`](crate::slab::Storage::sync)).
+/// Pages of removed values are released on synchronization,
+/// so that the previously written tree stays intact until then.
+pub struct PagedSlab<T, P> {
+    /// Pager.
+    pager: P,
+
+    /// Pages, indexed by page identifier.
+    pages: Vec<Page<T>>,
+
+    /// Pages to release on the next synchronization.
+    freed: Vec<usize>,
+
+    /// Number of values.
+    len: usize,
+}
+
+impl<T, P> PagedSlab<T, P> {
+    /// Creates a new empty slab using the given pager.
+    #[inline]
+    pub fn new(pager: P) -> Self {
+        Self {
+            pager,
+            pages: Vec::new(),
+            freed: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns a reference to the pager.
+    #[inline]
+    pub fn pager(&self) -> &P {
+        &self.pager
+    }
+
+    /// Returns a mutable reference to the given page, creating it if necessary.
+    fn page_mut(&mut self, id: usize) -> &mut Page<T> {
+        if id >= self.pages.len() {
+            self.pages.resize_with(id + 1, Page::default)
+        }
+
+        &mut self.pages[id]
+    }
+}
+
+impl<T: Codec, P: Pager, const M: usize, I: NodeId> PagedSlab<Node<T, M, I>, P> {
+    /// Reads the tree recorded in the pages of the given pager.
+    ///
+    /// Returns the slab with the root and length of the tree.
+    fn load(mut pager: P) -> Result<(Self, Option<usize>, usize), Error<P::Error>> {
+        let mut buf = vec![0; pager.page_size()];
+        pager
+            .read_page(HEADER_PAGE, &mut buf)
+            .map_err(Error::Pager)?;
+
+        let mut bytes = unframe(HEADER_PAGE, &buf)?;
+        if take(&mut bytes, MAGIC.len()) != Some(&MAGIC) {
+            return Err(Error::Corrupted(HEADER_PAGE));
+        }
+
+        let (root, len) = match (
+            Option::<usize>::decode(&mut bytes),
+            usize::decode(&mut bytes),
+        ) {
+            (Some(root), Some(len)) => (root, len),
+            _ => return Err(Error::Corrupted(HEADER_PAGE)),
+        };
+
+        let mut slab = Self::new(pager);
+        let mut stack: Vec<usize> = root.into_iter().collect();
+        while let Some(id) = stack.pop() {
+            slab.pager.read_page(id, &mut buf).map_err(Error::Pager)?;
+            let mut bytes = unframe(id, &buf)?;
+            let node = Node::decode(&mut bytes).ok_or(Error::Corrupted(id))?;
+            stack.extend(node.child_ids());
+
+            let page = slab.page_mut(id);
+            if page.value.is_some() {
+                return Err(Error::Corrupted(id));
+            }
+
+            page.value = Some(node);
+            slab.len += 1
+        }
+
+        Ok((slab, root, len))
+    }
+
+    /// Writes every modified value and the given tree root and length to the pager,
+    /// commits them, then releases the pages of the removed values.
+    fn sync(&mut self, root: Option<usize>, len: usize) -> Result<(), Error<P::Error>> {
+        let page_size = self.pager.page_size();
+        let mut payload = Vec::new();
+        let mut buf = Vec::with_capacity(page_size);
+
+        for (id, page) in self.pages.iter_mut().enumerate() {
+            if page.dirty {
+                if let Some(node) = &page.value {
+                    write_node(&mut self.pager, id, node, &mut payload, &mut buf)?;
+                }
+
+                page.dirty = false
+            }
+        }
+
+        payload.clear();
+        payload.extend_from_slice(&MAGIC);
+        root.encode(&mut payload);
+        len.encode(&mut payload);
+        if !frame(&payload, &mut buf, page_size) {
+            return Err(Error::Overflow(HEADER_PAGE));
+        }
+
+        self.pager
+            .write_page(HEADER_PAGE, &buf)
+            .map_err(Error::Pager)?;
+        self.pager.commit().map_err(Error::Pager)?;
+
+        for id in self.freed.drain(..) {
+            self.pager.free(id)
+        }
+
+        Ok(())
+    }
+}
+
+impl<T, P> SlotIndex for PagedSlab<T, P> {}
+
+impl<T, P> VacantSlots for PagedSlab<T, P> {}
+
+impl<T, P> OccupiedSlots for PagedSlab<T, P> {
+    #[inline]
+    fn occupied_keys(&self) -> Option<Vec<usize>> {
+        Some(
+            self.pages
+                .iter()
+                .enumerate()
+                .filter(|(_, page)| page.value.is_some())
+                .map(|(key, _)| key)
+                .collect(),
+        )
+    }
+}
+
+impl<T, P> SlotCapacity for PagedSlab<T, P> {}
+
+impl<T, P> RawSlots for PagedSlab<T, P> {}
+
+impl<T, P> cc_traits::Collection for PagedSlab<T, P> {
+    type Item = T;
+}
+
+impl<T, P> cc_traits::Len for PagedSlab<T, P> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T, P> cc_traits::Get<usize> for PagedSlab<T, P> {
+    #[inline]
+    fn get(&self, key: usize) -> Option<&T> {
+        self.pages.get(key).and_then(|page| page.value.as_ref())
+    }
+}
+
+impl<T, P> cc_traits::GetMut<usize> for PagedSlab<T, P> {
+    #[inline]
+    fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+        let page = self.pages.get_mut(key)?;
+        let value = page.value.as_mut()?;
+        page.dirty = true;
+        Some(value)
+    }
+}
+
+impl<T, P: Pager> cc_traits::Insert for PagedSlab<T, P> {
+    type Output = usize;
+
+    #[inline]
+    fn insert(&mut self, value: T) -> usize {
+        let id = self.pager.allocate();
+        let page = self.page_mut(id);
+        page.value = Some(value);
+        page.dirty = true;
+        self.len += 1;
+        id
+    }
+}
+
+impl<T, P> cc_traits::Remove<usize> for PagedSlab<T, P> {
+    #[inline]
+    fn remove(&mut self, key: usize) -> Option<T> {
+        let page = self.pages.get_mut(key)?;
+        let value = page.value.take()?;
+        page.dirty = false;
+        self.freed.push(key);
+        self.len -= 1;
+        Some(value)
+    }
+}
+
+impl<T, P> cc_traits::Clear for PagedSlab<T, P> {
+    #[inline]
+    fn clear(&mut self) {
+        for (id, page) in self.pages.iter_mut().enumerate() {
+            if page.value.take().is_some() {
+                self.freed.push(id)
+            }
+
+            page.dirty = false
+        }
+
+        self.len = 0
+    }
+}
+
+impl<T: Codec, P: Pager, const M: usize, I: NodeId>
+    Storage<T, PagedSlab<Node<T, M, I>, P>, (), M, I>
+{
+    /// Creates a new empty storage writing its nodes with the given pager.
+    ///
+    /// Nothing is written until the storage is synchronized
+    /// with [`Storage::sync`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::paged::{Map, MapStorage, MemoryPager};
+    ///
+    /// let mut map: Map<u32, String, MemoryPager> =
+    ///     Map::from_btree(MapStorage::create(MemoryPager::new(256)));
+    /// for i in 0..100 {
+    ///     map.insert(i, i.to_string());
+    /// }
+    ///
+    /// map.btree_mut().sync().unwrap();
+    /// let pager = map.into_btree().into_pager();
+    ///
+    /// let map: Map<u32, String, MemoryPager> = Map::from_btree(MapStorage::open(pager).unwrap());
+    /// assert_eq!(map.len(), 100);
+    /// assert_eq!(map.get(&42).unwrap(), "42");
+    /// ```
+    #[inline]
+    pub fn create(pager: P) -> Self {
+        Self::from_slab(PagedSlab::new(pager))
+    }
+
+    /// Creates a new storage from the given items, writing its nodes with the given pager.
+    ///
+    /// The items must be sorted by key, without duplicates,
+    /// otherwise the resulting tree is not valid.
+    /// The pager must not hold any tree.
+    ///
+    /// The tree is built bottom-up, level by level:
+    /// the page of every node is allocated in advance,
+    /// from the leaves to the root,
+    /// and each page is written once, in the same order,
+    /// without reading any page.
+    /// Every node is filled to its maximum capacity,
+    /// except for the last two nodes of each level,
+    /// that share the remaining items if the last one would underflow.
+    /// The storage is then synchronized, and returned along with
+    /// some statistics about the written tree.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{
+    ///     map::Binding,
+    ///     paged::{Map, MapStorage, MemoryPager},
+    /// };
+    ///
+    /// let items = (0..1000u32).map(|i| Binding::new(i, i * 2));
+    /// let (storage, stats) =
+    ///     MapStorage::<u32, u32, MemoryPager>::bulk_load(MemoryPager::new(256), items).unwrap();
+    /// assert!(stats.height > 1);
+    ///
+    /// let map = Map::from_btree(storage);
+    /// assert_eq!(map.get(&42), Some(&84));
+    /// ```
+    pub fn bulk_load<J>(pager: P, items: J) -> Result<(Self, BulkLoadStats), Error<P::Error>>
+    where
+        J: IntoIterator<Item = T>,
+        J::IntoIter: ExactSizeIterator,
+    {
+        let mut items = items.into_iter();
+        let len = items.len();
+        let mut slab = PagedSlab::new(pager);
+
+        let order = M;
+        let leaf_min =
+            btree::node::Ref::<Self>::from(&Node::Leaf(Default::default())).min_capacity();
+        let internal_min =
+            btree::node::Ref::<Self>::from(&Node::Internal(Default::default())).min_capacity();
+
+        // Number of children of each node, level by level, from the leaves.
+        // A leaf is counted with the separator following it.
+        let mut levels = Vec::new();
+        if len > 0 {
+            levels.push(pack(len + 1, order, leaf_min + 1));
+            while levels.last().unwrap().len() > 1 {
+                let child_count = levels.last().unwrap().len();
+                levels.push(pack(child_count, order, internal_min + 1))
+            }
+        }
+
+        let ids: Vec<Vec<usize>> = levels
+            .iter()
+            .map(|sizes| sizes.iter().map(|_| slab.pager.allocate()).collect())
+            .collect();
+
+        let mut payload = Vec::new();
+        let mut buf = Vec::with_capacity(slab.pager.page_size());
+        let mut separators = Vec::new();
+        for (height, sizes) in levels.iter().enumerate() {
+            let mut next_separators = Vec::new();
+            let mut current = core::mem::take(&mut separators).into_iter();
+            let mut children = ids.get(height.wrapping_sub(1)).map(|ids| ids.iter());
+
+            // Parent of each node.
+            let mut parents = ids
+                .get(height + 1)
+                .into_iter()
+                .zip(levels.get(height + 1))
+                .flat_map(|(ids, sizes)| {
+                    ids.iter()
+                        .zip(sizes)
+                        .flat_map(|(id, size)| core::iter::repeat_n(*id, *size))
+                });
+
+            for (i, size) in sizes.iter().enumerate() {
+                let parent = parents.next();
+                let node = match &mut children {
+                    None => {
+                        let mut node = Leaf::default();
+                        btree::node::buffer::Leaf::<Self>::set_parent(&mut node, parent);
+                        for _ in 1..*size {
+                            let item = items.next().unwrap();
+                            btree::node::buffer::Leaf::<Self>::push_right(&mut node, item)
+                        }
+
+                        if i + 1 < sizes.len() {
+                            next_separators.push(items.next().unwrap())
+                        }
+
+                        Node::Leaf(node)
+                    }
+                    Some(children) => {
+                        let mut node = Internal::default();
+                        btree::node::buffer::Internal::<Self>::set_parent(&mut node, parent);
+                        btree::node::buffer::Internal::<Self>::set_first_child_id(
+                            &mut node,
+                            *children.next().unwrap(),
+                        );
+                        for _ in 1..*size {
+                            let item = current.next().unwrap();
+                            btree::node::buffer::Internal::<Self>::push_right(
+                                &mut node,
+                                item,
+                                *children.next().unwrap(),
+                            )
+                        }
+
+                        if i + 1 < sizes.len() {
+                            next_separators.push(current.next().unwrap())
+                        }
+
+                        Node::Internal(node)
+                    }
+                };
+
+                let id = ids[height][i];
+                write_node(&mut slab.pager, id, &node, &mut payload, &mut buf)?;
+                slab.page_mut(id).value = Some(node);
+                slab.len += 1
+            }
+
+            separators = next_separators
+        }
+
+        let root = ids.last().map(|ids| ids[0]);
+        let stats = BulkLoadStats {
+            page_count: slab.len,
+            height: levels.len(),
+        };
+
+        let mut storage = Self::from_raw_parts(slab, root, len);
+        storage.sync()?;
+        Ok((storage, stats))
+    }
+
+    /// Opens the storage previously synchronized to the given pager.
+    pub fn open(pager: P) -> Result<Self, Error<P::Error>> {
+        let (slab, root, len) = PagedSlab::load(pager)?;
+        Ok(Self::from_raw_parts(slab, root, len))
+    }
+
+    /// Writes the nodes modified since the last synchronization
+    /// and the tree header to the pager, and commits them.
+    pub fn sync(&mut self) -> Result<(), Error<P::Error>> {
+        self.trim();
+        let (root, len) = (self.root(), self.len());
+        self.slab_mut().sync(root, len)
+    }
+
+    /// Returns a reference to the pager.
+    #[inline]
+    pub fn pager(&self) -> &P {
+        self.slab().pager()
+    }
+
+    /// Consumes the storage and returns the pager.
+    ///
+    /// Modifications made since the last synchronization are lost.
+    #[inline]
+    pub fn into_pager(self) -> P {
+        self.into_slab().pager
+    }
+}
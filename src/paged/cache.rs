@@ -0,0 +1,281 @@
+use super::Pager;
+use alloc::{collections::BTreeMap, vec::Vec};
+
+/// Cache statistics.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct CacheStats {
+    /// Number of page reads served by the cache.
+    pub hits: u64,
+
+    /// Number of page reads forwarded to the underlying pager.
+    pub misses: u64,
+
+    /// Number of pages evicted from the cache.
+    pub evictions: u64,
+}
+
+impl CacheStats {
+    /// Returns the proportion of page reads served by the cache,
+    /// or `0.0` if no page has been read.
+    #[inline]
+    pub fn hit_rate(&self) -> f64 {
+        let reads = self.hits + self.misses;
+        if reads == 0 {
+            0.0
+        } else {
+            self.hits as f64 / reads as f64
+        }
+    }
+}
+
+/// Cached page.
+struct Entry {
+    /// Page content.
+    data: Vec<u8>,
+
+    /// Last access time, or `None` if the page is pinned.
+    stamp: Option<u64>,
+
+    /// Number of times the page is pinned.
+    pins: usize,
+}
+
+/// Caching pager.
+///
+/// Keeps the most recently used pages of the underlying pager in memory,
+/// within a configurable memory budget.
+/// When the budget is exceeded, the least recently used page is evicted.
+/// Writes go through to the underlying pager.
+///
+/// Pages can be pinned (for instance, the path from the root to the node being processed)
+/// so that they are never evicted.
+/// Pinned pages count toward the budget,
+/// which may be exceeded if every cached page is pinned.
+///
+/// # Example
+///
+/// ```
+/// use generic_btree::paged::{cache::CachedPager, MemoryPager, Pager};
+///
+/// let mut pager = CachedPager::new(MemoryPager::new(64), 128);
+/// let mut page = vec![0; 64];
+///
+/// let id = pager.allocate();
+/// pager.write_page(id, &page).unwrap();
+/// pager.read_page(id, &mut page).unwrap();
+///
+/// assert_eq!(pager.stats().hits, 1);
+/// assert_eq!(pager.stats().misses, 0);
+/// ```
+pub struct CachedPager<P> {
+    /// Underlying pager.
+    pager: P,
+
+    /// Memory budget, in bytes.
+    budget: usize,
+
+    /// Cached pages.
+    entries: BTreeMap<usize, Entry>,
+
+    /// Unpinned cached pages, by last access time.
+    lru: BTreeMap<u64, usize>,
+
+    /// Current time.
+    clock: u64,
+
+    /// Statistics.
+    stats: CacheStats,
+}
+
+impl<P: Pager> CachedPager<P> {
+    /// Creates a new caching pager keeping at most `budget` bytes of pages in memory.
+    #[inline]
+    pub fn new(pager: P, budget: usize) -> Self {
+        Self {
+            pager,
+            budget,
+            entries: BTreeMap::new(),
+            lru: BTreeMap::new(),
+            clock: 0,
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Returns the memory budget, in bytes.
+    #[inline]
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// Sets the memory budget, in bytes, evicting pages if necessary.
+    #[inline]
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        self.evict()
+    }
+
+    /// Returns the number of bytes of cached pages.
+    #[inline]
+    pub fn cached_bytes(&self) -> usize {
+        self.entries.len() * self.pager.page_size()
+    }
+
+    /// Returns the cache statistics.
+    #[inline]
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    /// Resets the cache statistics.
+    #[inline]
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats::default()
+    }
+
+    /// Returns a reference to the underlying pager.
+    #[inline]
+    pub fn pager(&self) -> &P {
+        &self.pager
+    }
+
+    /// Consumes this pager and returns the underlying pager.
+    #[inline]
+    pub fn into_inner(self) -> P {
+        self.pager
+    }
+
+    /// Pins the given page in the cache, reading it if necessary.
+    ///
+    /// A page pinned `n` times stays in the cache until it is unpinned `n` times.
+    pub fn pin(&mut self, id: usize) -> Result<(), P::Error> {
+        if !self.entries.contains_key(&id) {
+            let mut data = alloc::vec![0; self.pager.page_size()];
+            self.pager.read_page(id, &mut data)?;
+            self.insert(id, data);
+        }
+
+        let entry = self.entries.get_mut(&id).unwrap();
+        if let Some(stamp) = entry.stamp.take() {
+            self.lru.remove(&stamp);
+        }
+
+        entry.pins += 1;
+        Ok(())
+    }
+
+    /// Unpins the given page.
+    ///
+    /// Does nothing if the page is not pinned.
+    pub fn unpin(&mut self, id: usize) {
+        if let Some(entry) = self.entries.get_mut(&id) {
+            if entry.pins > 0 {
+                entry.pins -= 1;
+                if entry.pins == 0 {
+                    self.clock += 1;
+                    entry.stamp = Some(self.clock);
+                    self.lru.insert(self.clock, id);
+                    self.evict()
+                }
+            }
+        }
+    }
+
+    /// Marks the given cached page as the most recently used.
+    fn touch(&mut self, id: usize) {
+        if let Some(entry) = self.entries.get_mut(&id) {
+            if let Some(stamp) = entry.stamp {
+                self.clock += 1;
+                self.lru.remove(&stamp);
+                self.lru.insert(self.clock, id);
+                entry.stamp = Some(self.clock)
+            }
+        }
+    }
+
+    /// Adds a page to the cache, evicting pages if necessary.
+    fn insert(&mut self, id: usize, data: Vec<u8>) {
+        self.clock += 1;
+        self.lru.insert(self.clock, id);
+        self.entries.insert(
+            id,
+            Entry {
+                data,
+                stamp: Some(self.clock),
+                pins: 0,
+            },
+        );
+        self.evict()
+    }
+
+    /// Evicts the least recently used unpinned pages until the budget is met.
+    fn evict(&mut self) {
+        while self.cached_bytes() > self.budget {
+            match self.lru.pop_first() {
+                Some((_, id)) => {
+                    self.entries.remove(&id);
+                    self.stats.evictions += 1
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+impl<P: Pager> Pager for CachedPager<P> {
+    type Error = P::Error;
+
+    #[inline]
+    fn page_size(&self) -> usize {
+        self.pager.page_size()
+    }
+
+    fn read_page(&mut self, id: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        match self.entries.get(&id) {
+            Some(entry) => {
+                buf.copy_from_slice(&entry.data);
+                self.stats.hits += 1;
+                self.touch(id)
+            }
+            None => {
+                self.pager.read_page(id, buf)?;
+                self.stats.misses += 1;
+                self.insert(id, buf.to_vec())
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_page(&mut self, id: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        self.pager.write_page(id, buf)?;
+        match self.entries.get_mut(&id) {
+            Some(entry) => {
+                entry.data.copy_from_slice(buf);
+                self.touch(id)
+            }
+            None => self.insert(id, buf.to_vec()),
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn allocate(&mut self) -> usize {
+        self.pager.allocate()
+    }
+
+    fn free(&mut self, id: usize) {
+        if let Some(entry) = self.entries.remove(&id) {
+            if let Some(stamp) = entry.stamp {
+                self.lru.remove(&stamp);
+            }
+        }
+
+        self.pager.free(id)
+    }
+
+    #[inline]
+    fn commit(&mut self) -> Result<(), Self::Error> {
+        self.pager.commit()
+    }
+}
@@ -0,0 +1,288 @@
+use super::{take, Codec, Pager};
+use alloc::{collections::BTreeMap, vec::Vec};
+
+/// Tag of a page record.
+const PAGE_RECORD: u8 = 1;
+
+/// Tag of a commit record.
+const COMMIT_RECORD: u8 = 2;
+
+/// Append-only log.
+pub trait Log {
+    /// Error type.
+    type Error;
+
+    /// Appends the given bytes at the end of the log.
+    fn append(&mut self, bytes: &[u8]) -> Result<(), Self::Error>;
+
+    /// Makes every byte appended so far durable.
+    fn sync(&mut self) -> Result<(), Self::Error>;
+
+    /// Reads the whole content of the log into `buf`.
+    fn read_all(&mut self, buf: &mut Vec<u8>) -> Result<(), Self::Error>;
+
+    /// Removes every byte of the log.
+    fn truncate(&mut self) -> Result<(), Self::Error>;
+}
+
+/// In-memory log.
+#[derive(Default)]
+pub struct MemoryLog {
+    /// Content.
+    bytes: Vec<u8>,
+}
+
+impl MemoryLog {
+    /// Creates a new empty log.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Log for MemoryLog {
+    type Error = core::convert::Infallible;
+
+    #[inline]
+    fn append(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.bytes.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    #[inline]
+    fn sync(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    #[inline]
+    fn read_all(&mut self, buf: &mut Vec<u8>) -> Result<(), Self::Error> {
+        buf.extend_from_slice(&self.bytes);
+        Ok(())
+    }
+
+    #[inline]
+    fn truncate(&mut self) -> Result<(), Self::Error> {
+        self.bytes.clear();
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+pub use file::FileLog;
+
+#[cfg(feature = "std")]
+mod file {
+    use super::Log;
+    use std::{
+        fs::{File, OpenOptions},
+        io::{self, Read, Seek, SeekFrom, Write},
+        path::Path,
+    };
+
+    /// File log.
+    pub struct FileLog {
+        /// File.
+        file: File,
+    }
+
+    impl FileLog {
+        /// Opens the given log file, creating it if it does not exist.
+        pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+            let file = OpenOptions::new()
+                .read(true)
+                .append(true)
+                .create(true)
+                .open(path)?;
+
+            Ok(Self { file })
+        }
+    }
+
+    impl Log for FileLog {
+        type Error = io::Error;
+
+        fn append(&mut self, bytes: &[u8]) -> io::Result<()> {
+            self.file.write_all(bytes)
+        }
+
+        fn sync(&mut self) -> io::Result<()> {
+            self.file.sync_data()
+        }
+
+        fn read_all(&mut self, buf: &mut Vec<u8>) -> io::Result<()> {
+            self.file.seek(SeekFrom::Start(0))?;
+            self.file.read_to_end(buf)?;
+            Ok(())
+        }
+
+        fn truncate(&mut self) -> io::Result<()> {
+            self.file.set_len(0)?;
+            self.file.sync_data()
+        }
+    }
+}
+
+/// Write-ahead logging error.
+#[derive(Debug)]
+pub enum Error<P, L> {
+    /// Pager error.
+    Pager(P),
+
+    /// Log error.
+    Log(L),
+}
+
+/// Write-ahead logging pager.
+///
+/// Pages written to this pager are appended to the log and kept in memory
+/// until [`Pager::commit`] is called.
+/// On commit, a commit record is appended and the log is synchronized
+/// before the pages are written to the underlying pager.
+/// The log is then truncated.
+///
+/// # Example
+///
+/// ```
+/// use generic_btree::paged::{
+///     wal::{MemoryLog, WalPager},
+///     Map, MapStorage, MemoryPager,
+/// };
+///
+/// let pager = WalPager::open(MemoryPager::new(256), MemoryLog::new()).unwrap();
+/// let mut map: Map<u32, u32, _> = Map::from_btree(MapStorage::create(pager));
+/// for i in 0..100 {
+///     map.insert(i, i);
+/// }
+///
+/// // Writes the modified nodes to the log, then to the pages.
+/// map.btree_mut().sync().unwrap();
+/// ```
+pub struct WalPager<P, L> {
+    /// Underlying pager.
+    pager: P,
+
+    /// Log.
+    log: L,
+
+    /// Pages written since the last commit.
+    pending: BTreeMap<usize, Vec<u8>>,
+}
+
+impl<P: Pager, L: Log> WalPager<P, L> {
+    /// Opens a write-ahead logging pager.
+    ///
+    /// Every transaction committed in the log is replayed into the pager,
+    /// and the log is truncated.
+    /// Records following the last commit record are discarded.
+    pub fn open(mut pager: P, mut log: L) -> Result<Self, Error<P::Error, L::Error>> {
+        let mut buf = Vec::new();
+        log.read_all(&mut buf).map_err(Error::Log)?;
+
+        let mut bytes = &buf[..];
+        let mut transaction = Vec::new();
+        while let Some(tag) = u8::decode(&mut bytes) {
+            match tag {
+                PAGE_RECORD => match decode_page_record(&mut bytes) {
+                    Some(record) => transaction.push(record),
+                    None => break,
+                },
+                COMMIT_RECORD => {
+                    for (id, page) in transaction.drain(..) {
+                        pager.write_page(id, page).map_err(Error::Pager)?
+                    }
+
+                    pager.commit().map_err(Error::Pager)?
+                }
+                _ => break,
+            }
+        }
+
+        log.truncate().map_err(Error::Log)?;
+
+        Ok(Self {
+            pager,
+            log,
+            pending: BTreeMap::new(),
+        })
+    }
+
+    /// Returns a reference to the underlying pager.
+    #[inline]
+    pub fn pager(&self) -> &P {
+        &self.pager
+    }
+
+    /// Consumes this pager and returns the underlying pager and log.
+    ///
+    /// Pages written since the last commit are discarded.
+    #[inline]
+    pub fn into_inner(self) -> (P, L) {
+        (self.pager, self.log)
+    }
+}
+
+/// Decodes the page identifier and content of a page record.
+fn decode_page_record<'a>(bytes: &mut &'a [u8]) -> Option<(usize, &'a [u8])> {
+    let id = usize::decode(bytes)?;
+    let len = usize::decode(bytes)?;
+    let page = take(bytes, len)?;
+    Some((id, page))
+}
+
+impl<P: Pager, L: Log> Pager for WalPager<P, L> {
+    type Error = Error<P::Error, L::Error>;
+
+    #[inline]
+    fn page_size(&self) -> usize {
+        self.pager.page_size()
+    }
+
+    fn read_page(&mut self, id: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        match self.pending.get(&id) {
+            Some(page) => {
+                buf.copy_from_slice(page);
+                Ok(())
+            }
+            None => self.pager.read_page(id, buf).map_err(Error::Pager),
+        }
+    }
+
+    fn write_page(&mut self, id: usize, buf: &[u8]) -> Result<(), Self::Error> {
+        let mut record = Vec::with_capacity(buf.len() + 17);
+        PAGE_RECORD.encode(&mut record);
+        id.encode(&mut record);
+        buf.len().encode(&mut record);
+        record.extend_from_slice(buf);
+        self.log.append(&record).map_err(Error::Log)?;
+
+        self.pending.insert(id, buf.to_vec());
+        Ok(())
+    }
+
+    #[inline]
+    fn allocate(&mut self) -> usize {
+        self.pager.allocate()
+    }
+
+    #[inline]
+    fn free(&mut self, id: usize) {
+        self.pager.free(id)
+    }
+
+    /// Commits the pages written since the last commit.
+    ///
+    /// The commit is durable once the log is synchronized:
+    /// if writing the pages to the underlying pager fails afterward,
+    /// they are recovered the next time the pager is opened.
+    fn commit(&mut self) -> Result<(), Self::Error> {
+        self.log.append(&[COMMIT_RECORD]).map_err(Error::Log)?;
+        self.log.sync().map_err(Error::Log)?;
+
+        for (id, page) in core::mem::take(&mut self.pending) {
+            self.pager.write_page(id, &page).map_err(Error::Pager)?
+        }
+
+        self.pager.commit().map_err(Error::Pager)?;
+        self.log.truncate().map_err(Error::Log)
+    }
+}
@@ -0,0 +1,301 @@
+use crate::{
+    map::Binding,
+    slab::{
+        Node, NodeId, OccupiedSlots, RawSlots, SlotCapacity, SlotIndex, Storage, VacantSlots,
+        DEFAULT_ORDER,
+    },
+};
+use alloc::{rc::Rc, sync::Arc, vec::Vec};
+
+pub type AugmentedMapStorage<K, V, A, const M: usize = DEFAULT_ORDER> =
+    Storage<Binding<K, V>, PersistentSlab<Node<Binding<K, V>, M>>, A, M>;
+pub type MapStorage<K, V> = AugmentedMapStorage<K, V, ()>;
+pub type Map<K, V> = crate::Map<MapStorage<K, V>>;
+pub type AugmentedMap<K, V, A, const M: usize = DEFAULT_ORDER> =
+    crate::Map<AugmentedMapStorage<K, V, A, M>>;
+
+pub type AugmentedSyncMapStorage<K, V, A, const M: usize = DEFAULT_ORDER> =
+    Storage<Binding<K, V>, SyncPersistentSlab<Node<Binding<K, V>, M>>, A, M>;
+pub type SyncMapStorage<K, V> = AugmentedSyncMapStorage<K, V, ()>;
+pub type SyncMap<K, V> = crate::Map<SyncMapStorage<K, V>>;
+pub type AugmentedSyncMap<K, V, A, const M: usize = DEFAULT_ORDER> =
+    crate::Map<AugmentedSyncMapStorage<K, V, A, M>>;
+
+/// Slot.
+#[derive(Clone)]
+enum Slot<P> {
+    /// Vacant slot, holding the index of the next vacant slot.
+    Vacant(usize),
+
+    /// Occupied slot, holding a pointer to a possibly shared value.
+    Occupied(P),
+}
+
+macro_rules! persistent_slab {
+    ($(#[$meta:meta])* $name:ident, $ptr:ident) => {
+        $(#[$meta])*
+        pub struct $name<T> {
+            /// Slots.
+            slots: $ptr<Vec<Slot<$ptr<T>>>>,
+
+            /// Index of the first vacant slot, or the number of slots if there is none.
+            next_vacant: usize,
+
+            /// Number of occupied slots.
+            len: usize,
+        }
+
+        impl<T> $name<T> {
+            /// Creates a new empty slab.
+            #[inline]
+            pub fn new() -> Self {
+                Self {
+                    slots: $ptr::new(Vec::new()),
+                    next_vacant: 0,
+                    len: 0,
+                }
+            }
+
+            /// Checks if the value with the given key is shared with another slab.
+            #[inline]
+            pub fn is_shared(&self, key: usize) -> bool {
+                match self.slots.get(key) {
+                    Some(Slot::Occupied(value)) => {
+                        $ptr::strong_count(&self.slots) > 1 || $ptr::strong_count(value) > 1
+                    }
+                    _ => false,
+                }
+            }
+
+            /// Checks if the value with the given key is the same
+            /// (not a copy) in both slabs.
+            #[inline]
+            pub fn shares(&self, other: &Self, key: usize) -> bool {
+                match (self.slots.get(key), other.slots.get(key)) {
+                    (Some(Slot::Occupied(a)), Some(Slot::Occupied(b))) => $ptr::ptr_eq(a, b),
+                    _ => false,
+                }
+            }
+
+            /// Returns the slots, copying the slot table first if it is shared.
+            #[inline]
+            fn slots_mut(&mut self) -> &mut Vec<Slot<$ptr<T>>> {
+                $ptr::make_mut(&mut self.slots)
+            }
+        }
+
+        impl<T> Default for $name<T> {
+            #[inline]
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<T> Clone for $name<T> {
+            /// Clones the slab in constant time, sharing its values.
+            #[inline]
+            fn clone(&self) -> Self {
+                Self {
+                    slots: self.slots.clone(),
+                    next_vacant: self.next_vacant,
+                    len: self.len,
+                }
+            }
+        }
+
+        impl<T> SlotIndex for $name<T> {}
+
+        impl<T> VacantSlots for $name<T> {}
+
+        impl<T> OccupiedSlots for $name<T> {
+            #[inline]
+            fn occupied_keys(&self) -> Option<Vec<usize>> {
+                Some(
+                    self.slots
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, slot)| matches!(slot, Slot::Occupied(_)))
+                        .map(|(key, _)| key)
+                        .collect(),
+                )
+            }
+        }
+
+        impl<T> SlotCapacity for $name<T> {}
+
+        impl<T: Clone> RawSlots for $name<T> {}
+
+        impl<T> cc_traits::Collection for $name<T> {
+            type Item = T;
+        }
+
+        impl<T> cc_traits::Len for $name<T> {
+            #[inline]
+            fn len(&self) -> usize {
+                self.len
+            }
+        }
+
+        impl<T> cc_traits::Get<usize> for $name<T> {
+            #[inline]
+            fn get(&self, key: usize) -> Option<&T> {
+                match self.slots.get(key) {
+                    Some(Slot::Occupied(value)) => Some(value),
+                    _ => None,
+                }
+            }
+        }
+
+        impl<T: Clone> cc_traits::GetMut<usize> for $name<T> {
+            /// Returns a mutable reference to the value with the given key,
+            /// cloning it first if it is shared.
+            #[inline]
+            fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+                match self.slots.get(key) {
+                    Some(Slot::Occupied(_)) => match &mut self.slots_mut()[key] {
+                        Slot::Occupied(value) => Some($ptr::make_mut(value)),
+                        Slot::Vacant(_) => unreachable!(),
+                    },
+                    _ => None,
+                }
+            }
+        }
+
+        impl<T> cc_traits::Insert for $name<T> {
+            type Output = usize;
+
+            #[inline]
+            fn insert(&mut self, value: T) -> usize {
+                let key = self.next_vacant;
+                let value = Slot::Occupied($ptr::new(value));
+                let slots = self.slots_mut();
+                let next_vacant = match slots.get_mut(key) {
+                    Some(slot) => match core::mem::replace(slot, value) {
+                        Slot::Vacant(next) => next,
+                        Slot::Occupied(_) => unreachable!(),
+                    },
+                    None => {
+                        slots.push(value);
+                        slots.len()
+                    }
+                };
+
+                self.next_vacant = next_vacant;
+                self.len += 1;
+                key
+            }
+        }
+
+        impl<T: Clone> cc_traits::Remove<usize> for $name<T> {
+            /// Removes the value with the given key,
+            /// cloning it if it is shared.
+            #[inline]
+            fn remove(&mut self, key: usize) -> Option<T> {
+                match self.slots.get(key) {
+                    Some(Slot::Occupied(_)) => {
+                        let next_vacant = self.next_vacant;
+                        let slot = &mut self.slots_mut()[key];
+                        match core::mem::replace(slot, Slot::Vacant(next_vacant)) {
+                            Slot::Occupied(value) => {
+                                self.next_vacant = key;
+                                self.len -= 1;
+                                Some($ptr::try_unwrap(value).unwrap_or_else(|value| (*value).clone()))
+                            }
+                            Slot::Vacant(_) => unreachable!(),
+                        }
+                    }
+                    _ => None,
+                }
+            }
+        }
+
+        impl<T> cc_traits::Clear for $name<T> {
+            #[inline]
+            fn clear(&mut self) {
+                *self = Self::new()
+            }
+        }
+
+        impl<T, A, const M: usize, I: NodeId> Storage<T, $name<Node<T, M, I>>, A, M, I> {
+            /// Returns a snapshot of the storage.
+            ///
+            /// The snapshot shares every node with this storage:
+            /// nodes are only copied when one of the two storages modifies them.
+            #[inline]
+            pub fn snapshot(&self) -> Self
+            where
+                A: Clone,
+            {
+                self.clone()
+            }
+
+            /// Checks if the node with the given identifier is shared with another storage.
+            #[inline]
+            pub fn is_shared(&self, id: usize) -> bool {
+                self.slab().is_shared(id)
+            }
+        }
+    };
+}
+
+persistent_slab! {
+    /// Persistent slab, sharing its values with its clones.
+    ///
+    /// Cloning the slab takes constant time and does not clone any value:
+    /// values, and the table referencing them, are reference-counted
+    /// and shared between the clones.
+    /// A shared value is only cloned when it is accessed mutably
+    /// (copy-on-write), so that a mutation of the tree only copies the nodes
+    /// it modifies (at most the nodes on the root-to-leaf path, and the
+    /// siblings involved in a rebalancing), and shares the rest.
+    /// The first mutation following a clone also copies the table of
+    /// node pointers.
+    ///
+    /// This makes snapshots cheap, and gives versioned maps:
+    /// each clone of a map is an independent version.
+    /// Use [`SyncPersistentSlab`] to share versions between threads.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::persistent::Map;
+    ///
+    /// let mut map: Map<usize, usize> = Map::new();
+    /// for i in 0..100 {
+    ///     map.insert(i, i);
+    /// }
+    ///
+    /// let snapshot = map.clone();
+    /// map.insert(42, 0);
+    ///
+    /// assert_eq!(map.get(&42), Some(&0));
+    /// assert_eq!(snapshot.get(&42), Some(&42));
+    /// ```
+    PersistentSlab, Rc
+}
+
+persistent_slab! {
+    /// Thread-safe persistent slab, sharing its values with its clones.
+    ///
+    /// Same as [`PersistentSlab`], using atomic reference counting
+    /// so that the clones of a map can be sent to other threads.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::persistent::SyncMap;
+    ///
+    /// let mut map: SyncMap<usize, usize> = SyncMap::new();
+    /// for i in 0..100 {
+    ///     map.insert(i, i);
+    /// }
+    ///
+    /// let snapshot = map.clone();
+    /// let handle = std::thread::spawn(move || snapshot.iter().map(|(_, v)| *v).sum::<usize>());
+    /// map.insert(0, 100);
+    ///
+    /// assert_eq!(handle.join().unwrap(), 4950);
+    /// assert_eq!(map.get(&0), Some(&100));
+    /// ```
+    SyncPersistentSlab, Arc
+}
@@ -1,37 +1,285 @@
 use crate::btree::{
     self,
     node::{Buffer, Mut as NodeMut},
+    Augment, Augmentation, LeafLinks, SubtreeLen,
+};
+use alloc::{boxed::Box, collections::BTreeSet, sync::Arc, vec::Vec};
+use core::{
+    borrow::Borrow,
+    convert::TryFrom,
+    fmt,
+    marker::PhantomData,
+    ptr::NonNull,
+    sync::atomic::{self, AtomicUsize},
 };
-use std::{borrow::Borrow, marker::PhantomData};
 
 pub mod node;
 pub use node::Node;
 
+/// Structure-of-arrays implementation, storing keys and values in separate arrays.
+pub mod soa;
+
+#[cfg(feature = "std")]
+mod snapshot;
+
+/// Slab of nodes.
+///
+/// Node identifiers are the keys of the slab.
+pub trait NodeSlab<T, const M: usize, I>:
+    cc_traits::Collection<Item = Node<T, M, I>>
+    + cc_traits::Len
+    + cc_traits::Get<usize>
+    + SlotIndex
+    + VacantSlots
+    + OccupiedSlots
+{
+}
+
+impl<T, const M: usize, I, S> NodeSlab<T, M, I> for S where
+    S: cc_traits::Collection<Item = Node<T, M, I>>
+        + cc_traits::Len
+        + cc_traits::Get<usize>
+        + SlotIndex
+        + VacantSlots
+        + OccupiedSlots
+{
+}
+
+/// Mutable slab of nodes.
+pub trait NodeSlabMut<T, const M: usize, I>:
+    NodeSlab<T, M, I>
+    + cc_traits::GetMut<usize>
+    + cc_traits::Insert<Output = usize>
+    + cc_traits::Remove<usize>
+    + SlotCapacity
+    + RawSlots
+{
+}
+
+impl<T, const M: usize, I, S> NodeSlabMut<T, M, I> for S where
+    S: NodeSlab<T, M, I>
+        + cc_traits::GetMut<usize>
+        + cc_traits::Insert<Output = usize>
+        + cc_traits::Remove<usize>
+        + SlotCapacity
+        + RawSlots
+{
+}
+
+/// Node identifier representation.
+///
+/// Nodes store the identifiers of their parent and children using this type.
+/// Using a smaller type than `usize` (such as `u32`) reduces the size of
+/// internal nodes, at the cost of limiting the range of slab keys.
+pub trait NodeId: 'static + Copy + Eq {
+    /// Value representing the absence of node.
+    const NONE: Self;
+
+    /// Converts a slab key into a node identifier.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key cannot be represented by this type.
+    fn from_key(key: usize) -> Self;
+
+    /// Converts this node identifier back into a slab key.
+    fn into_key(self) -> usize;
+}
+
+impl NodeId for usize {
+    const NONE: Self = usize::MAX;
+
+    #[inline]
+    fn from_key(key: usize) -> Self {
+        key
+    }
+
+    #[inline]
+    fn into_key(self) -> usize {
+        self
+    }
+}
+
+impl NodeId for u32 {
+    const NONE: Self = u32::MAX;
+
+    #[inline]
+    fn from_key(key: usize) -> Self {
+        match u32::try_from(key) {
+            Ok(id) if id != Self::NONE => id,
+            _ => panic!("node identifier overflow"),
+        }
+    }
+
+    #[inline]
+    fn into_key(self) -> usize {
+        self as usize
+    }
+}
+
+/// Slab key to slot index conversion.
+///
+/// Slab keys are not necessarily slot indexes (a key may also carry a generation, for instance).
+/// Slot indexes are used by the storage to store additional per-node data,
+/// such as augmentations.
+pub trait SlotIndex {
+    /// Whether removing a value invalidates its key for good,
+    /// as in [`GenerationalSlab`](crate::generational::GenerationalSlab).
+    ///
+    /// Released node slots of such slabs are removed instead of being pooled,
+    /// so that the keys of released nodes are never reused.
+    const GENERATIONAL: bool = false;
+
+    /// Returns the index of the slot designated by the given key.
+    ///
+    /// By default, keys are slot indexes.
+    #[inline]
+    fn slot_index(key: usize) -> usize {
+        key
+    }
+}
+
+/// Slab capacity.
+pub trait VacantSlots {
+    /// Returns the number of values that can still be inserted in the slab,
+    /// or `None` if the slab grows as needed.
+    #[inline]
+    fn vacant_slots(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Slab occupied slots enumeration.
+pub trait OccupiedSlots {
+    /// Returns the keys of the values stored in the slab,
+    /// or `None` if the slab cannot enumerate them.
+    #[inline]
+    fn occupied_keys(&self) -> Option<Vec<usize>> {
+        None
+    }
+}
+
+/// Slab capacity management.
+pub trait SlotCapacity {
+    /// Reserves capacity for at least `additional` more values to be inserted in the slab.
+    ///
+    /// Does nothing by default.
+    #[inline]
+    fn reserve_slots(&mut self, _additional: usize) {}
+
+    /// Shrinks the capacity of the slab as much as possible.
+    ///
+    /// Does nothing by default.
+    #[inline]
+    fn shrink_slots(&mut self) {}
+}
+
+/// Raw slab slot access.
+pub trait RawSlots: cc_traits::GetMut<usize> {
+    /// Returns a pointer to the value with the given key, if any,
+    /// from a pointer to the slab.
+    ///
+    /// This is used by the mutable iterators of the storage,
+    /// which keep some values of the slab borrowed while accessing others.
+    /// By default, this goes through [`get_mut`](cc_traits::GetMut::get_mut),
+    /// which may borrow every value of the slab.
+    /// Slabs should override it to only borrow the requested value.
+    ///
+    /// # Safety
+    ///
+    /// The slab pointed to by `this` must be valid,
+    /// and the value with the given key must not be borrowed.
+    #[inline]
+    unsafe fn get_ptr(this: NonNull<Self>, key: usize) -> Option<NonNull<Self::Item>> {
+        (*this.as_ptr()).get_mut(key).map(NonNull::from)
+    }
+}
+
 #[cfg(feature = "slab")]
+impl<T> SlotIndex for slab::Slab<T> {}
+
+#[cfg(feature = "slab")]
+impl<T> VacantSlots for slab::Slab<T> {}
+
+#[cfg(feature = "slab")]
+impl<T> OccupiedSlots for slab::Slab<T> {
+    #[inline]
+    fn occupied_keys(&self) -> Option<Vec<usize>> {
+        Some(self.iter().map(|(key, _)| key).collect())
+    }
+}
+
+#[cfg(feature = "slab")]
+impl<T> SlotCapacity for slab::Slab<T> {
+    #[inline]
+    fn reserve_slots(&mut self, additional: usize) {
+        self.reserve(additional)
+    }
+
+    #[inline]
+    fn shrink_slots(&mut self) {
+        self.shrink_to_fit()
+    }
+}
+
+#[cfg(feature = "slab")]
+impl<T> RawSlots for slab::Slab<T> {
+    /// Only borrows the requested entry of the slab.
+    #[inline]
+    unsafe fn get_ptr(this: NonNull<Self>, key: usize) -> Option<NonNull<T>> {
+        (*this.as_ptr())
+            .get_disjoint_mut([key])
+            .ok()
+            .map(|[value]| NonNull::from(value))
+    }
+}
+
+#[cfg(feature = "slab")]
+pub type AugmentedMapStorage<K, V, A, const M: usize = DEFAULT_ORDER, I = usize> =
+    Storage<crate::map::Binding<K, V>, slab::Slab<Node<crate::map::Binding<K, V>, M, I>>, A, M, I>;
+#[cfg(feature = "slab")]
+pub type MapStorage<K, V> = AugmentedMapStorage<K, V, ()>;
+#[cfg(feature = "slab")]
+pub type Map<K, V> = crate::Map<MapStorage<K, V>>;
+#[cfg(feature = "slab")]
+pub type AugmentedMap<K, V, A, const M: usize = DEFAULT_ORDER, I = usize> =
+    crate::Map<AugmentedMapStorage<K, V, A, M, I>>;
+#[cfg(feature = "slab")]
+pub type CounterMap<K> = crate::map::CounterMap<MapStorage<K, usize>>;
+#[cfg(feature = "slab")]
+pub type CompactMapStorage<K, V> = AugmentedMapStorage<K, V, (), DEFAULT_ORDER, u32>;
+#[cfg(feature = "slab")]
+pub type CompactMap<K, V> = crate::Map<CompactMapStorage<K, V>>;
+
 mod map {
     use super::*;
     use crate::{
-        btree::{ItemOrd, ItemPartialOrd, KeyOrd, KeyPartialOrd},
+        btree::{
+            augment::{HashItem, Measure},
+            ItemOrd, ItemPartialOrd, KeyOrd, KeyPartialOrd,
+        },
         map::Binding,
     };
-    use std::cmp::Ordering;
-
-    pub type MapStorage<K, V> = Storage<Binding<K, V>, slab::Slab<Node<Binding<K, V>>>>;
-    pub type Map<K, V> = crate::Map<MapStorage<K, V>>;
+    use core::{
+        cmp::Ordering,
+        hash::{Hash, Hasher},
+    };
 
-    impl<K, V> crate::map::MapStorage for MapStorage<K, V> {
+    impl<K, V, S: NodeSlab<Binding<K, V>, M, I>, A: SubtreeLen, const M: usize, I: NodeId>
+        crate::map::MapStorage for Storage<Binding<K, V>, S, A, M, I>
+    {
         type KeyRef<'a>
+            = &'a K
         where
             Self: 'a,
             K: 'a,
-            V: 'a,
-        = &'a K;
+            V: 'a;
         type ValueRef<'a>
+            = &'a V
         where
             Self: 'a,
             K: 'a,
-            V: 'a,
-        = &'a V;
+            V: 'a;
 
         fn split_ref<'a>(binding: &'a Binding<K, V>) -> (&'a K, &'a V)
         where
@@ -41,15 +289,36 @@ mod map {
         }
     }
 
-    impl<K, V> crate::map::MapStorageMut for MapStorage<K, V> {
+    impl<K, V, S: NodeSlab<Binding<K, V>, M, I>, A: SubtreeLen, const M: usize, I: NodeId>
+        crate::map::MapStorageRef for Storage<Binding<K, V>, S, A, M, I>
+    {
+        type Target = V;
+
+        fn deref_value<'a>(value: &'a V) -> &'a V
+        where
+            Self: 'a,
+        {
+            value
+        }
+    }
+
+    impl<
+            K,
+            V,
+            S: NodeSlabMut<Binding<K, V>, M, I>,
+            A: Augmentation<Storage<Binding<K, V>, S, A, M, I>>,
+            const M: usize,
+            I: NodeId,
+        > crate::map::MapStorageMut for Storage<Binding<K, V>, S, A, M, I>
+    {
         type Key = K;
         type Value = V;
         type ValueMut<'a>
+            = &'a mut V
         where
             Self: 'a,
             K: 'a,
-            V: 'a,
-        = &'a mut V;
+            V: 'a;
 
         fn split(binding: Binding<K, V>) -> (K, V) {
             binding.into_pair()
@@ -63,7 +332,50 @@ mod map {
         }
     }
 
-    impl<K, V> crate::btree::Insert<crate::map::Inserted<K, V>> for MapStorage<K, V> {
+    impl<
+            K,
+            V: Clone,
+            S: NodeSlab<Binding<K, V>, M, I>,
+            A: SubtreeLen,
+            const M: usize,
+            I: NodeId,
+        > Measure<V> for Storage<Binding<K, V>, S, A, M, I>
+    {
+        fn measure<'r>(binding: &&'r Binding<K, V>) -> V
+        where
+            Self: 'r,
+        {
+            binding.value.clone()
+        }
+    }
+
+    impl<
+            K: Hash,
+            V: Hash,
+            S: NodeSlab<Binding<K, V>, M, I>,
+            A: SubtreeLen,
+            const M: usize,
+            I: NodeId,
+        > HashItem for Storage<Binding<K, V>, S, A, M, I>
+    {
+        fn hash_item<'r, H: Hasher>(binding: &&'r Binding<K, V>, state: &mut H)
+        where
+            Self: 'r,
+        {
+            binding.key.hash(state);
+            binding.value.hash(state)
+        }
+    }
+
+    impl<
+            K,
+            V,
+            S: NodeSlabMut<Binding<K, V>, M, I>,
+            A: Augmentation<Storage<Binding<K, V>, S, A, M, I>>,
+            const M: usize,
+            I: NodeId,
+        > crate::btree::Insert<crate::map::Inserted<K, V>> for Storage<Binding<K, V>, S, A, M, I>
+    {
         fn allocate_item(
             &mut self,
             crate::map::Inserted(key, value): crate::map::Inserted<K, V>,
@@ -72,43 +384,95 @@ mod map {
         }
     }
 
-    impl<'a, K, V> crate::btree::node::item::Replace<MapStorage<K, V>, crate::map::Inserted<K, V>>
-        for &'a mut Binding<K, V>
+    impl<
+            K,
+            V,
+            S: NodeSlabMut<Binding<K, V>, M, I>,
+            A: Augmentation<Storage<Binding<K, V>, S, A, M, I>>,
+            const M: usize,
+            I: NodeId,
+        > crate::btree::node::item::Replace<crate::map::Inserted<K, V>>
+        for Storage<Binding<K, V>, S, A, M, I>
     {
         type Output = V;
 
-        fn replace(&mut self, crate::map::Inserted(_, value): crate::map::Inserted<K, V>) -> V {
-            self.replace_value(value)
+        fn replace(
+            binding: &mut &mut Binding<K, V>,
+            crate::map::Inserted(_, value): crate::map::Inserted<K, V>,
+        ) -> V {
+            binding.replace_value(value)
         }
     }
 
-    impl<'a, K, V> crate::btree::node::item::Replace<MapStorage<K, V>, V> for &'a mut Binding<K, V> {
+    impl<
+            K,
+            V,
+            S: NodeSlabMut<Binding<K, V>, M, I>,
+            A: Augmentation<Storage<Binding<K, V>, S, A, M, I>>,
+            const M: usize,
+            I: NodeId,
+        > crate::btree::node::item::Replace<V> for Storage<Binding<K, V>, S, A, M, I>
+    {
         type Output = V;
 
-        fn replace(&mut self, value: V) -> V {
-            self.replace_value(value)
+        fn replace(binding: &mut &mut Binding<K, V>, value: V) -> V {
+            binding.replace_value(value)
         }
     }
 
-    unsafe impl<'a, K, V> crate::btree::node::item::Read<MapStorage<K, V>> for &'a Binding<K, V> {
+    unsafe impl<
+            K,
+            V,
+            S: NodeSlabMut<Binding<K, V>, M, I>,
+            A: Augmentation<Storage<Binding<K, V>, S, A, M, I>>,
+            const M: usize,
+            I: NodeId,
+        > crate::btree::node::item::Read<Storage<Binding<K, V>, S, A, M, I>> for &Binding<K, V>
+    {
         unsafe fn read(&self) -> Binding<K, V> {
-            std::ptr::read(*self)
+            core::ptr::read(*self)
         }
     }
 
-    unsafe impl<'a, K, V> crate::btree::node::item::Read<MapStorage<K, V>> for &'a mut Binding<K, V> {
+    unsafe impl<
+            K,
+            V,
+            S: NodeSlabMut<Binding<K, V>, M, I>,
+            A: Augmentation<Storage<Binding<K, V>, S, A, M, I>>,
+            const M: usize,
+            I: NodeId,
+        > crate::btree::node::item::Read<Storage<Binding<K, V>, S, A, M, I>>
+        for &mut Binding<K, V>
+    {
         unsafe fn read(&self) -> Binding<K, V> {
-            std::ptr::read(*self)
+            core::ptr::read(*self)
         }
     }
 
-    unsafe impl<'a, K, V> crate::btree::node::item::Write<MapStorage<K, V>> for &'a mut Binding<K, V> {
+    unsafe impl<
+            K,
+            V,
+            S: NodeSlabMut<Binding<K, V>, M, I>,
+            A: Augmentation<Storage<Binding<K, V>, S, A, M, I>>,
+            const M: usize,
+            I: NodeId,
+        > crate::btree::node::item::Write<Storage<Binding<K, V>, S, A, M, I>>
+        for &mut Binding<K, V>
+    {
         unsafe fn write(&mut self, value: Binding<K, V>) {
-            std::ptr::write(*self, value)
+            core::ptr::write(*self, value)
         }
     }
 
-    impl<Q: ?Sized, K, V> KeyPartialOrd<Q> for MapStorage<K, V>
+    impl<
+            Q: ?Sized,
+            K,
+            V,
+            S: NodeSlab<Binding<K, V>, M, I>,
+            A: SubtreeLen,
+            const M: usize,
+            I: NodeId,
+        > KeyPartialOrd<Q> for Storage<Binding<K, V>, S, A, M, I>
     where
         Q: PartialOrd,
         K: Borrow<Q>,
@@ -121,7 +485,8 @@ mod map {
         }
     }
 
-    impl<K, V> KeyPartialOrd<crate::map::Inserted<K, V>> for MapStorage<K, V>
+    impl<K, V, S: NodeSlab<Binding<K, V>, M, I>, A: SubtreeLen, const M: usize, I: NodeId>
+        KeyPartialOrd<crate::map::Inserted<K, V>> for Storage<Binding<K, V>, S, A, M, I>
     where
         K: PartialOrd,
     {
@@ -136,7 +501,8 @@ mod map {
         }
     }
 
-    impl<K, V> KeyOrd for MapStorage<K, V>
+    impl<K, V, S: NodeSlab<Binding<K, V>, M, I>, A: SubtreeLen, const M: usize, I: NodeId> KeyOrd
+        for Storage<Binding<K, V>, S, A, M, I>
     where
         K: Ord,
     {
@@ -148,8 +514,24 @@ mod map {
         }
     }
 
-    impl<K1, K2, V1, V2> ItemPartialOrd<MapStorage<K2, V2>> for MapStorage<K1, V1>
+    impl<
+            K1,
+            K2,
+            V1,
+            V2,
+            S1,
+            S2,
+            A1: SubtreeLen,
+            A2: SubtreeLen,
+            const M1: usize,
+            const M2: usize,
+            I1: NodeId,
+            I2: NodeId,
+        > ItemPartialOrd<Storage<Binding<K2, V2>, S2, A2, M2, I2>>
+        for Storage<Binding<K1, V1>, S1, A1, M1, I1>
     where
+        S1: NodeSlab<Binding<K1, V1>, M1, I1>,
+        S2: NodeSlab<Binding<K2, V2>, M2, I2>,
         K1: PartialOrd<K2>,
         V1: PartialOrd<V2>,
     {
@@ -159,13 +541,14 @@ mod map {
         ) -> Option<Ordering>
         where
             Self: 'r,
-            MapStorage<K2, V2>: 's,
+            Storage<Binding<K2, V2>, S2, A2, M2, I2>: 's,
         {
             (**binding).partial_cmp(*other)
         }
     }
 
-    impl<K, V> ItemOrd for MapStorage<K, V>
+    impl<K, V, S: NodeSlab<Binding<K, V>, M, I>, A: SubtreeLen, const M: usize, I: NodeId> ItemOrd
+        for Storage<Binding<K, V>, S, A, M, I>
     where
         K: Ord,
         V: Ord,
@@ -174,59 +557,928 @@ mod map {
         where
             Self: 'r + 's,
         {
-            binding.key.cmp(&other.key)
+            (**binding).cmp(*other)
         }
     }
 }
 
-#[cfg(feature = "slab")]
-pub use map::*;
-
-/// Knuth-order of the BTree.
-const M: usize = 8; // Must be at least 4.
+/// Default Knuth-order of the BTree.
+pub const DEFAULT_ORDER: usize = 8;
 
 /// Slab storage.
-pub struct Storage<T, S> {
+///
+/// The `A` type parameter is the [`Augmentation`] maintained for each node.
+/// By default, no augmentation is maintained.
+///
+/// The `M` parameter is the default Knuth-order of the tree:
+/// each node holds at most `M - 1` items (and `M` children).
+/// It must be at least 4.
+/// Another order can be chosen at runtime with [`Storage::with_order`].
+///
+/// The `I` parameter is the [`NodeId`] type used by nodes to refer to each other.
+/// Choosing `u32` instead of the default `usize` halves the memory used by
+/// parent and child pointers on 64-bit platforms,
+/// but limits the keys of the slab to `u32::MAX - 1`.
+///
+/// # Example
+///
+/// ```
+/// use generic_btree::slab::CompactMap;
+///
+/// let mut map: CompactMap<usize, usize> = CompactMap::new();
+/// for i in 0..100 {
+///     map.insert(i, i);
+/// }
+///
+/// assert_eq!(map.get(&42), Some(&42));
+/// ```
+pub struct Storage<T, S, A = (), const M: usize = DEFAULT_ORDER, I = usize> {
     /// The internal slab.
     slab: S,
 
+    /// Augmentation of each node, indexed by node slot index.
+    augmentations: Vec<A>,
+
     /// Root node id.
     root: Option<usize>,
 
     /// Size of the collection.
     len: usize,
 
-    /// Item type.
-    item: PhantomData<T>,
+    /// Knuth-order of the nodes.
+    order: usize,
+
+    /// Cached identifiers of the first and last leaves.
+    leaves: LeafCache,
+
+    /// Released node slots kept for reuse.
+    pool: NodePool,
+
+    /// Leaf sibling links.
+    links: LeafLinkTable,
+
+    /// Current generation, if tracked.
+    generation: Option<usize>,
+
+    /// Observer of the structural modifications.
+    observer: Option<Box<dyn btree::Observer + Send + Sync>>,
+
+    /// Capacity policy, if not the standard one.
+    policy: Option<Arc<dyn btree::CapacityPolicy>>,
+
+    /// Item and node identifier types.
+    item: PhantomData<(T, I)>,
 }
 
-impl<T, S: Default> Default for Storage<T, S> {
+/// Global generation counter.
+static GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns a generation never returned before.
+fn new_generation() -> usize {
+    GENERATION.fetch_add(1, atomic::Ordering::Relaxed)
+}
+
+/// Default maximum number of released node slots kept by a [`Storage`] for reuse.
+pub const DEFAULT_NODE_POOL_CAPACITY: usize = 8;
+
+/// Node pool statistics, returned by [`Storage::pool_stats`].
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct PoolStats {
+    /// Number of released node slots currently kept in the pool.
+    pub pooled: usize,
+
+    /// Maximum number of node slots kept in the pool.
+    pub capacity: usize,
+
+    /// Number of node allocations served by the pool.
+    pub hits: usize,
+
+    /// Number of node allocations served by the slab.
+    pub misses: usize,
+}
+
+/// Node slot leak report, returned by [`Storage::leak_report`].
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct LeakReport {
+    /// Number of nodes reachable from the root.
+    pub reachable: usize,
+
+    /// Number of released node slots kept in the pool.
+    pub pooled: usize,
+
+    /// Number of occupied slots in the slab.
+    pub occupied: usize,
+
+    /// Identifiers of the occupied slots that are neither reachable nor pooled,
+    /// or `None` if the slab cannot enumerate its occupied slots
+    /// (see [`OccupiedSlots`]).
+    pub leaked: Option<Vec<usize>>,
+
+    /// Identifiers referenced by a reachable node (or as root)
+    /// that do not designate a live node.
+    pub dangling: Vec<usize>,
+}
+
+impl LeakReport {
+    /// Returns the number of leaked node slots.
+    ///
+    /// This is computed from the number of occupied slots
+    /// even if the slab cannot enumerate them.
+    #[inline]
+    pub fn leaked_count(&self) -> usize {
+        self.occupied
+            .saturating_sub(self.reachable + self.pooled)
+            .max(self.leaked.as_ref().map(Vec::len).unwrap_or(0))
+    }
+
+    /// Checks that no slot is leaked and no identifier is dangling.
+    #[inline]
+    pub fn is_clean(&self) -> bool {
+        self.leaked_count() == 0 && self.dangling.is_empty()
+    }
+}
+
+/// Pool of released node slots.
+///
+/// Instead of being removed from the slab, a released node is replaced by an
+/// empty placeholder and its slot is kept in the pool, as long as the pool is not full.
+/// The next allocated node is then written in a pooled slot,
+/// avoiding a round-trip through the slab (and its allocator, or its backing store).
+#[derive(Clone)]
+struct NodePool {
+    /// Identifiers of the pooled slots.
+    ids: Vec<usize>,
+
+    /// Maximum number of pooled slots.
+    capacity: usize,
+
+    /// Number of allocations served by the pool.
+    hits: usize,
+
+    /// Number of allocations served by the slab.
+    misses: usize,
+}
+
+impl NodePool {
+    fn new(capacity: usize) -> Self {
+        Self {
+            ids: Vec::new(),
+            capacity,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.ids.len() >= self.capacity
+    }
+}
+
+impl Default for NodePool {
     fn default() -> Self {
+        Self::new(DEFAULT_NODE_POOL_CAPACITY)
+    }
+}
+
+/// Leaf sibling links of a tree, maintained once enabled
+/// with [`Storage::set_leaf_links`].
+#[derive(Clone, Default)]
+struct LeafLinkTable {
+    /// Whether the links are maintained.
+    enabled: bool,
+
+    /// Links of each leaf, indexed by node slot index,
+    /// or `None` if the node is not a linked leaf.
+    links: Vec<Option<LeafLinks>>,
+
+    /// Number of allocated leaves not linked yet.
+    ///
+    /// The links are only used when every leaf is linked.
+    unlinked: usize,
+}
+
+impl LeafLinkTable {
+    fn get(&self, index: usize) -> Option<LeafLinks> {
+        self.links.get(index).copied().flatten()
+    }
+
+    fn get_mut(&mut self, index: usize) -> &mut Option<LeafLinks> {
+        if index >= self.links.len() {
+            self.links.resize(index + 1, None)
+        }
+
+        &mut self.links[index]
+    }
+}
+
+/// Cached identifiers of the first and last leaves of a tree.
+///
+/// Leaves are only created or destroyed when nodes are allocated or released,
+/// or when the root changes.
+/// The cache is cleared by these operations,
+/// and filled again the next time the first or last item is looked up.
+#[derive(Default)]
+struct LeafCache {
+    /// First leaf id plus one, or `0` if unknown.
+    first: AtomicUsize,
+
+    /// Last leaf id plus one, or `0` if unknown.
+    last: AtomicUsize,
+}
+
+impl LeafCache {
+    #[inline]
+    fn get(cached: &AtomicUsize) -> Option<usize> {
+        cached.load(atomic::Ordering::Relaxed).checked_sub(1)
+    }
+
+    #[inline]
+    fn set(cached: &AtomicUsize, id: usize) {
+        cached.store(id.wrapping_add(1), atomic::Ordering::Relaxed)
+    }
+
+    #[inline]
+    fn first(&self) -> Option<usize> {
+        Self::get(&self.first)
+    }
+
+    #[inline]
+    fn last(&self) -> Option<usize> {
+        Self::get(&self.last)
+    }
+
+    #[inline]
+    fn set_first(&self, id: usize) {
+        Self::set(&self.first, id)
+    }
+
+    #[inline]
+    fn set_last(&self, id: usize) {
+        Self::set(&self.last, id)
+    }
+
+    /// Forgets the cached leaves.
+    #[inline]
+    fn clear(&mut self) {
+        *self.first.get_mut() = 0;
+        *self.last.get_mut() = 0
+    }
+}
+
+impl Clone for LeafCache {
+    fn clone(&self) -> Self {
+        Self {
+            first: AtomicUsize::new(self.first.load(atomic::Ordering::Relaxed)),
+            last: AtomicUsize::new(self.last.load(atomic::Ordering::Relaxed)),
+        }
+    }
+}
+
+impl<T, S: Default, A, const M: usize, I: NodeId> Storage<T, S, A, M, I> {
+    /// Creates a new empty storage with the given runtime Knuth-order.
+    ///
+    /// Each node holds at most `order - 1` items.
+    /// Nodes are stored inline up to the compile-time order `M`:
+    /// choosing an `order` greater than `M` moves the items of each node to the heap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is less than 4.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{slab::MapStorage, Map, Storage};
+    ///
+    /// let mut map = Map::from_btree(MapStorage::with_order(4));
+    /// for i in 0..100 {
+    ///     map.insert(i, i);
+    /// }
+    ///
+    /// assert_eq!(map.btree().order(), 4);
+    /// map.btree().validate().unwrap();
+    /// ```
+    pub fn with_order(order: usize) -> Self {
+        if order < 4 {
+            panic!("B-Tree order must be at least 4")
+        }
+
+        Self {
+            order,
+            ..Self::default()
+        }
+    }
+}
+
+impl<T, S, A, const M: usize, I: NodeId> Storage<T, S, A, M, I> {
+    /// Creates a new empty storage using the given slab to store the nodes.
+    ///
+    /// This is useful when the slab cannot be built with [`Default`],
+    /// for instance when it is parameterized by a custom allocator.
+    #[inline]
+    pub fn from_slab(slab: S) -> Self {
         Self {
-            slab: S::default(),
+            slab,
+            augmentations: Vec::new(),
             root: None,
             len: 0,
+            order: M,
+            leaves: LeafCache::default(),
+            pool: NodePool::default(),
+            links: LeafLinkTable::default(),
+            generation: None,
+            observer: None,
+            policy: None,
+            item: PhantomData,
+        }
+    }
+
+    /// Creates a storage from a slab already holding a tree
+    /// with the given root and length.
+    ///
+    /// No augmentation is computed.
+    #[inline]
+    pub(crate) fn from_raw_parts(slab: S, root: Option<usize>, len: usize) -> Self {
+        Self {
+            root,
+            len,
+            ..Self::from_slab(slab)
+        }
+    }
+
+    /// Consumes the storage and returns the underlying slab.
+    #[inline]
+    pub(crate) fn into_slab(self) -> S {
+        self.slab
+    }
+
+    /// Returns a reference to the underlying slab.
+    #[inline]
+    pub(crate) fn slab(&self) -> &S {
+        &self.slab
+    }
+
+    /// Returns a mutable reference to the underlying slab.
+    #[inline]
+    pub(crate) fn slab_mut(&mut self) -> &mut S {
+        &mut self.slab
+    }
+
+    /// Returns the Knuth-order of the nodes.
+    #[inline]
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Returns the node pool statistics.
+    ///
+    /// Released nodes keep their slot in the slab, up to the pool capacity,
+    /// so that the next allocated nodes can reuse them.
+    /// The pool is disabled for generational slabs (see [`SlotIndex::GENERATIONAL`]).
+    #[inline]
+    pub fn pool_stats(&self) -> PoolStats
+    where
+        S: SlotIndex,
+    {
+        PoolStats {
+            pooled: self.pool.ids.len(),
+            capacity: if S::GENERATIONAL {
+                0
+            } else {
+                self.pool.capacity
+            },
+            hits: self.pool.hits,
+            misses: self.pool.misses,
+        }
+    }
+}
+
+impl<T, S: NodeSlab<T, M, I>, A, const M: usize, I: NodeId> Storage<T, S, A, M, I> {
+    /// Cross-checks the occupied slots of the slab against the nodes reachable from the root.
+    ///
+    /// A slot is leaked if it is occupied while being neither reachable nor pooled.
+    /// An identifier is dangling if it is referenced as root or child
+    /// but designates no live node.
+    /// Cycles are not followed twice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map: Map<usize, usize> = (0..1000).map(|i| (i, i)).collect();
+    /// map.retain(|key, _| key % 3 == 0);
+    ///
+    /// let report = map.btree().leak_report();
+    /// assert!(report.is_clean());
+    /// assert_eq!(report.leaked, Some(Vec::new()));
+    /// ```
+    pub fn leak_report(&self) -> LeakReport {
+        let pooled: BTreeSet<usize> = self.pool.ids.iter().copied().collect();
+        let mut reachable = BTreeSet::new();
+        let mut dangling = BTreeSet::new();
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        while let Some(id) = stack.pop() {
+            if reachable.contains(&id) {
+                continue;
+            }
+
+            match self.slab.get(id) {
+                Some(node) if !pooled.contains(&id) => {
+                    reachable.insert(id);
+                    stack.extend(node.child_ids())
+                }
+                _ => {
+                    dangling.insert(id);
+                }
+            }
+        }
+
+        let leaked = self.slab.occupied_keys().map(|keys| {
+            keys.into_iter()
+                .filter(|id| !reachable.contains(id) && !pooled.contains(id))
+                .collect()
+        });
+
+        LeakReport {
+            reachable: reachable.len(),
+            pooled: pooled.len(),
+            occupied: self.slab.len(),
+            leaked,
+            dangling: dangling.into_iter().collect(),
+        }
+    }
+}
+
+impl<T, S: NodeSlabMut<T, M, I>, A, const M: usize, I: NodeId> Storage<T, S, A, M, I> {
+    /// Sets the maximum number of released node slots kept for reuse.
+    ///
+    /// The default capacity is [`DEFAULT_NODE_POOL_CAPACITY`].
+    /// A capacity of `0` disables the pool.
+    /// Generational slabs never pool released slots (see [`SlotIndex::GENERATIONAL`]).
+    /// Pooled slots exceeding the new capacity are removed from the slab.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map: Map<usize, usize> = (0..1000).map(|i| (i, i)).collect();
+    /// map.btree_mut().set_pool_capacity(0);
+    /// map.retain(|key, _| key % 2 == 0);
+    ///
+    /// assert_eq!(map.btree().pool_stats().pooled, 0);
+    /// ```
+    pub fn set_pool_capacity(&mut self, capacity: usize) {
+        self.pool.capacity = capacity;
+        while self.pool.ids.len() > capacity {
+            let id = self.pool.ids.pop().unwrap();
+            self.slab.remove(id);
+        }
+    }
+
+    /// Removes every pooled node slot from the slab.
+    ///
+    /// This is done before synchronizing persistent storages,
+    /// so that pooled slots are not persisted,
+    /// and by [`shrink_to_fit`](btree::StorageMut::shrink_to_fit).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::slab::Map;
+    ///
+    /// let mut map: Map<usize, usize> = (0..1000).map(|i| (i, i)).collect();
+    /// map.retain(|key, _| key % 2 == 0);
+    /// assert!(map.btree().pool_stats().pooled > 0);
+    ///
+    /// map.btree_mut().trim();
+    /// assert_eq!(map.btree().pool_stats().pooled, 0);
+    /// ```
+    pub fn trim(&mut self) {
+        for id in self.pool.ids.drain(..) {
+            self.slab.remove(id);
+        }
+    }
+
+    /// Checks if the leaf sibling links are maintained.
+    #[inline]
+    pub fn has_leaf_links(&self) -> bool {
+        self.links.enabled
+    }
+
+    /// Enables or disables the leaf sibling links.
+    ///
+    /// When enabled, each leaf keeps the identifiers of its previous and next
+    /// leaves (see [`LeafLinks`]), updated when leaves are split or merged.
+    /// Iterators then hop from one leaf to the next
+    /// instead of descending again from the separator item.
+    /// Operations reorganizing many leaves at once,
+    /// such as splitting or concatenating trees,
+    /// recompute every link in linear time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{slab::MapStorage, Map, Storage};
+    ///
+    /// let mut btree: MapStorage<usize, usize> = MapStorage::default();
+    /// btree.set_leaf_links(true);
+    ///
+    /// let mut map = Map::from_btree(btree);
+    /// for i in 0..100 {
+    ///     map.insert(i, i);
+    /// }
+    ///
+    /// let first_leaf = map.btree().first_item_address().unwrap().id;
+    /// assert!(map.btree().leaf_links(first_leaf).unwrap().next.is_some());
+    /// assert!(map.keys().copied().eq(0..100));
+    /// ```
+    pub fn set_leaf_links(&mut self, enabled: bool) {
+        self.links.enabled = enabled;
+        if enabled {
+            self.relink()
+        } else {
+            self.links = LeafLinkTable::default()
+        }
+    }
+
+    /// Sets the observer notified of the structural modifications of the tree,
+    /// or removes it if `None`
+    /// (see [`btree::StorageMut::observer`]).
+    ///
+    /// The observer is not cloned with the storage,
+    /// nor kept by operations rebuilding the storage from scratch
+    /// and renumbering every node, such as [`btree::StorageMut::compact`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{slab::MapStorage, Map, Observer};
+    /// use std::sync::{
+    ///     atomic::{AtomicUsize, Ordering},
+    ///     Arc,
+    /// };
+    ///
+    /// struct CountSplits(Arc<AtomicUsize>);
+    ///
+    /// impl Observer for CountSplits {
+    ///     fn on_split(&mut self, _id: usize, _right: usize, _median: generic_btree::node::Position) {
+    ///         self.0.fetch_add(1, Ordering::Relaxed);
+    ///     }
+    /// }
+    ///
+    /// let splits = Arc::new(AtomicUsize::new(0));
+    /// let mut btree: MapStorage<usize, usize> = MapStorage::default();
+    /// btree.set_observer(Some(Box::new(CountSplits(splits.clone()))));
+    ///
+    /// let mut map = Map::from_btree(btree);
+    /// for i in 0..100 {
+    ///     map.insert(i, i);
+    /// }
+    ///
+    /// assert!(splits.load(Ordering::Relaxed) > 0);
+    /// ```
+    pub fn set_observer(&mut self, observer: Option<Box<dyn btree::Observer + Send + Sync>>) {
+        self.observer = observer
+    }
+
+    /// Sets the policy deciding how many items the nodes must hold,
+    /// or `None` to use the [`Standard`](btree::policy::Standard) policy
+    /// (see [`btree::Storage::capacity_policy`]).
+    ///
+    /// The policy is shared with the clones of the storage.
+    /// Changing the policy of a non-empty tree does not refill its nodes:
+    /// nodes holding less items than the new minimum capacity
+    /// are only rebalanced once items are removed from them.
+    ///
+    /// Returns an error, leaving the current policy unchanged,
+    /// if the policy requires an invalid minimum capacity for the nodes of this storage
+    /// (see [`btree::policy::min_capacity`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{
+    ///     policy::{Fill, Lazy},
+    ///     slab::MapStorage,
+    ///     Map, Storage,
+    /// };
+    /// use std::sync::Arc;
+    ///
+    /// let mut btree: MapStorage<usize, usize> = MapStorage::default();
+    /// // Nodes split in two cannot be kept 2/3 full.
+    /// assert!(btree
+    ///     .set_capacity_policy(Some(Arc::new(Fill::new(2, 3))))
+    ///     .is_err());
+    /// btree.set_capacity_policy(Some(Arc::new(Lazy(1)))).unwrap();
+    ///
+    /// let mut lazy = Map::from_btree(btree);
+    /// let mut standard = Map::from_btree(MapStorage::<usize, usize>::default());
+    /// for i in 0..1000 {
+    ///     lazy.insert(i, i);
+    ///     standard.insert(i, i);
+    /// }
+    ///
+    /// lazy.retain(|key, _| key % 4 == 0);
+    /// standard.retain(|key, _| key % 4 == 0);
+    ///
+    /// // Sparse nodes are kept instead of being merged.
+    /// assert!(lazy.btree().stats().node_count() > standard.btree().stats().node_count());
+    /// lazy.btree().validate().unwrap();
+    /// ```
+    pub fn set_capacity_policy(
+        &mut self,
+        policy: Option<Arc<dyn btree::CapacityPolicy>>,
+    ) -> Result<(), btree::InvalidCapacityPolicy> {
+        if let Some(policy) = &policy {
+            // Leaves and internal nodes share the same capacities.
+            btree::policy::min_capacity(policy.as_ref(), self.order, self.order / 2 - 1)?;
+        }
+
+        self.policy = policy;
+        Ok(())
+    }
+
+    /// Checks if the tree generation is tracked.
+    #[inline]
+    pub fn has_generations(&self) -> bool {
+        self.generation.is_some()
+    }
+
+    /// Enables or disables the tracking of the tree generation.
+    ///
+    /// When enabled, the generation (see [`btree::Storage::generation`])
+    /// changes every time the length or the root of the tree changes,
+    /// so that addresses stamped with [`btree::Storage::stamp`]
+    /// are rejected once items have been inserted or removed.
+    /// Generations are drawn from a global counter,
+    /// hence no two trees ever share the same generation,
+    /// even after being swapped, cloned or compacted.
+    pub fn set_generations(&mut self, enabled: bool) {
+        self.generation = enabled.then(new_generation)
+    }
+
+    /// Moves to a new generation, if tracked.
+    #[inline]
+    fn next_generation(&mut self) {
+        if self.generation.is_some() {
+            self.generation = Some(new_generation())
+        }
+    }
+
+    /// Recomputes the links of every leaf, in order.
+    fn relink(&mut self) {
+        self.links.links.clear();
+        self.links.unlinked = 0;
+
+        let mut previous: Option<usize> = None;
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        while let Some(id) = stack.pop() {
+            let node = self.slab.get(id).unwrap();
+            match node {
+                Node::Leaf(_) => {
+                    *self.links.get_mut(S::slot_index(id)) = Some(LeafLinks {
+                        previous,
+                        next: None,
+                    });
+
+                    if let Some(previous_id) = previous {
+                        if let Some(links) = self.links.get_mut(S::slot_index(previous_id)) {
+                            links.next = Some(id)
+                        }
+                    }
+
+                    previous = Some(id)
+                }
+                Node::Internal(_) => stack.extend(node.child_ids().into_iter().rev()),
+            }
+        }
+    }
+
+    /// Links the leaf `id` after the leaf `previous`.
+    ///
+    /// The leaf is left unlinked if `previous` is itself not linked.
+    fn link_leaf_after(&mut self, previous: Option<usize>, id: usize) {
+        let index = S::slot_index(id);
+        let is_unlinked_leaf =
+            matches!(self.slab.get(id), Some(Node::Leaf(_))) && self.links.get(index).is_none();
+        if !is_unlinked_leaf {
+            return;
+        }
+
+        let next = match previous {
+            Some(previous_id) => match self.links.get(S::slot_index(previous_id)) {
+                Some(links) => links.next,
+                None => return,
+            },
+            None => None,
+        };
+
+        *self.links.get_mut(index) = Some(LeafLinks { previous, next });
+        if let Some(previous_id) = previous {
+            if let Some(links) = self.links.get_mut(S::slot_index(previous_id)) {
+                links.next = Some(id)
+            }
+        }
+
+        if let Some(next_id) = next {
+            if let Some(links) = self.links.get_mut(S::slot_index(next_id)) {
+                links.previous = Some(id)
+            }
+        }
+
+        self.links.unlinked -= 1
+    }
+
+    /// Unlinks the leaf `id` from its neighbors, before it is released.
+    fn unlink_leaf(&mut self, id: usize) {
+        match self.links.get_mut(S::slot_index(id)).take() {
+            Some(LeafLinks { previous, next }) => {
+                if let Some(previous_id) = previous {
+                    if let Some(links) = self.links.get_mut(S::slot_index(previous_id)) {
+                        links.next = next
+                    }
+                }
+
+                if let Some(next_id) = next {
+                    if let Some(links) = self.links.get_mut(S::slot_index(next_id)) {
+                        links.previous = previous
+                    }
+                }
+            }
+            None => self.links.unlinked -= 1,
+        }
+    }
+}
+
+impl<T: Clone, S: NodeSlab<T, M, I>, A: Clone + Default, const M: usize, I: NodeId>
+    Storage<T, S, A, M, I>
+{
+    /// Copies the tree into the given empty slab, and returns the copy.
+    ///
+    /// Contrarily to [`Clone::clone`], which clones the slab itself
+    /// (and requires it to implement [`Clone`]),
+    /// the nodes are copied one by one, from the root in depth-first order,
+    /// and given new identifiers by the target slab.
+    /// The copy can hence use another type of slab,
+    /// and is compact even if this slab has many vacant slots.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{
+    ///     slab::Map,
+    ///     vec_storage::{self, VecSlab},
+    /// };
+    ///
+    /// let map: Map<usize, usize> = (0..100).map(|i| (i, i)).collect();
+    /// let copy: vec_storage::Map<usize, usize> =
+    ///     vec_storage::Map::from_btree(map.btree().clone_into_slab(VecSlab::new()));
+    /// assert!(copy.iter().eq(map.iter()));
+    /// ```
+    pub fn clone_into_slab<R: NodeSlabMut<T, M, I>>(&self, slab: R) -> Storage<T, R, A, M, I> {
+        let mut storage = Storage {
+            slab,
+            augmentations: Vec::new(),
+            root: None,
+            len: self.len,
+            order: self.order,
+            leaves: LeafCache::default(),
+            pool: NodePool::new(self.pool.capacity),
+            links: LeafLinkTable::default(),
+            generation: None,
+            observer: None,
+            policy: self.policy.clone(),
+            item: PhantomData,
+        };
+
+        storage.root = self
+            .root
+            .map(|id| self.clone_node_into(id, None, &mut storage));
+        storage
+    }
+
+    /// Copies the subtree rooted at the node `id` into the given storage,
+    /// under the given parent.
+    ///
+    /// Returns the identifier of the copied node.
+    fn clone_node_into<R: NodeSlabMut<T, M, I>>(
+        &self,
+        id: usize,
+        parent: Option<usize>,
+        target: &mut Storage<T, R, A, M, I>,
+    ) -> usize {
+        let node = self.slab.get(id).unwrap();
+        let mut copy = node.clone();
+        copy.set_parent(parent);
+        let new_id = target.slab.insert(copy);
+
+        if let Some(augmentation) = self.augmentations.get(S::slot_index(id)) {
+            let index = R::slot_index(new_id);
+            if index >= target.augmentations.len() {
+                target.augmentations.resize_with(index + 1, A::default)
+            }
+
+            target.augmentations[index] = augmentation.clone()
+        }
+
+        let child_ids: Vec<usize> = node
+            .child_ids()
+            .into_iter()
+            .map(|child_id| self.clone_node_into(child_id, Some(new_id), target))
+            .collect();
+        target
+            .slab
+            .get_mut(new_id)
+            .unwrap()
+            .set_child_ids(child_ids);
+
+        new_id
+    }
+}
+
+impl<T, S: Default, A, const M: usize, I: NodeId> Default for Storage<T, S, A, M, I> {
+    fn default() -> Self {
+        Self::from_slab(S::default())
+    }
+}
+
+impl<T, S: Clone, A: Clone, const M: usize, I> Clone for Storage<T, S, A, M, I> {
+    fn clone(&self) -> Self {
+        Self {
+            slab: self.slab.clone(),
+            augmentations: self.augmentations.clone(),
+            root: self.root,
+            len: self.len,
+            order: self.order,
+            leaves: self.leaves.clone(),
+            pool: self.pool.clone(),
+            links: self.links.clone(),
+            // Addresses stamped by this tree must not be accepted by its clone.
+            generation: self.generation.map(|_| new_generation()),
+            observer: None,
+            policy: self.policy.clone(),
             item: PhantomData,
         }
     }
 }
 
-impl<T, S: cc_traits::Slab<Node<T>>> btree::Storage for Storage<T, S> {
+impl<T: fmt::Debug, S: NodeSlab<T, M, I>, A, const M: usize, I: NodeId> fmt::Debug
+    for Storage<T, S, A, M, I>
+{
+    /// Renders the storage with the graph of its nodes,
+    /// each node being listed with its identifier, from the root in depth-first order.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Storage")
+            .field("root", &self.root)
+            .field("len", &self.len)
+            .field("order", &self.order)
+            .field("nodes", &Nodes(self))
+            .finish()
+    }
+}
+
+/// Nodes of a storage, rendered as a map from node identifiers to nodes.
+struct Nodes<'a, T, S, A, const M: usize, I>(&'a Storage<T, S, A, M, I>);
+
+impl<'a, T: fmt::Debug, S: NodeSlab<T, M, I>, A, const M: usize, I: NodeId> fmt::Debug
+    for Nodes<'a, T, S, A, M, I>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut map = f.debug_map();
+        let mut stack: Vec<usize> = self.0.root.into_iter().collect();
+        while let Some(id) = stack.pop() {
+            let node = self.0.slab.get(id).unwrap();
+            map.entry(&id, node);
+            stack.extend(node.child_ids().into_iter().rev())
+        }
+
+        map.finish()
+    }
+}
+
+impl<T, S: NodeSlab<T, M, I>, A: SubtreeLen, const M: usize, I: NodeId> btree::Storage
+    for Storage<T, S, A, M, I>
+{
     type ItemRef<'r>
+        = &'r T
     where
         S: 'r,
         T: 'r,
-    = &'r T;
+        A: 'r;
     type LeafRef<'r>
+        = &'r node::Leaf<T, M, I>
     where
         S: 'r,
         T: 'r,
-    = &'r node::Leaf<T>;
+        A: 'r;
     type InternalRef<'r>
+        = &'r node::Internal<T, M, I>
     where
         S: 'r,
         T: 'r,
-    = &'r node::Internal<T>;
+        A: 'r;
 
     fn root(&self) -> Option<usize> {
         self.root
@@ -236,55 +1488,310 @@ impl<T, S: cc_traits::Slab<Node<T>>> btree::Storage for Storage<T, S> {
         self.len
     }
 
+    fn generation(&self) -> Option<usize> {
+        self.generation
+    }
+
+    fn capacity_policy(&self) -> &dyn btree::CapacityPolicy {
+        match &self.policy {
+            Some(policy) => policy.as_ref(),
+            None => &btree::policy::Standard,
+        }
+    }
+
     fn node(&self, id: usize) -> Option<btree::node::Ref<'_, Self>> {
         self.slab.get(id).map(|node| node.into())
     }
+
+    fn subtree_len(&self, id: usize) -> Option<usize> {
+        self.augmentations
+            .get(S::slot_index(id))
+            .and_then(SubtreeLen::subtree_len)
+    }
+
+    /// Returns the size of the node and of its heap allocation,
+    /// plus the size of its augmentation.
+    fn node_bytes(&self, id: usize) -> Option<usize> {
+        self.slab.get(id).map(|node| {
+            core::mem::size_of::<Node<T, M, I>>() + node.heap_size() + core::mem::size_of::<A>()
+        })
+    }
+
+    /// Returns the links of the leaf `id`,
+    /// if enabled with [`set_leaf_links`](Storage::set_leaf_links).
+    fn leaf_links(&self, id: usize) -> Option<LeafLinks> {
+        if self.links.enabled && self.links.unlinked == 0 {
+            self.links.get(S::slot_index(id))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the address of the first item in the tree, if any.
+    ///
+    /// The first leaf is cached, so this takes a constant time
+    /// until the next node allocation or release.
+    fn first_item_address(&self) -> Option<btree::node::Address> {
+        let id = match self.leaves.first() {
+            Some(id) => id,
+            None => {
+                let mut id = self.root?;
+                while let Some(child_id) = self.node(id).unwrap().child_id(0) {
+                    id = child_id
+                }
+
+                self.leaves.set_first(id);
+                id
+            }
+        };
+
+        Some(btree::node::Address::new(id, 0.into()))
+    }
+
+    /// Returns the address of the last item in the tree, if any.
+    ///
+    /// The last leaf is cached, so this takes a constant time
+    /// until the next node allocation or release.
+    fn last_item_address(&self) -> Option<btree::node::Address> {
+        let id = match self.leaves.last() {
+            Some(id) => id,
+            None => {
+                let mut id = self.root?;
+                loop {
+                    let node = self.node(id).unwrap();
+                    match node.child_id(node.item_count()) {
+                        Some(child_id) => id = child_id,
+                        None => break,
+                    }
+                }
+
+                self.leaves.set_last(id);
+                id
+            }
+        };
+
+        let item_count = self.node(id).unwrap().item_count();
+        Some(btree::node::Address::new(id, (item_count - 1).into()))
+    }
 }
 
-unsafe impl<T, S: cc_traits::SlabMut<Node<T>>> btree::StorageMut for Storage<T, S> {
+unsafe impl<
+        T,
+        S: NodeSlabMut<T, M, I>,
+        A: Augmentation<Storage<T, S, A, M, I>>,
+        const M: usize,
+        I: NodeId,
+    > btree::StorageMut for Storage<T, S, A, M, I>
+{
     type Item = T;
-    type LeafNode = node::Leaf<T>;
-    type InternalNode = node::Internal<T>;
+    type LeafNode = node::Leaf<T, M, I>;
+    type InternalNode = node::Internal<T, M, I>;
 
     type ItemMut<'r>
+        = &'r mut T
     where
         S: 'r,
         T: 'r,
-    = &'r mut T;
+        A: 'r;
     type LeafMut<'r>
+        = &'r mut node::Leaf<T, M, I>
     where
         S: 'r,
         T: 'r,
-    = &'r mut node::Leaf<T>;
+        A: 'r;
     type InternalMut<'r>
+        = &'r mut node::Internal<T, M, I>
     where
         S: 'r,
         T: 'r,
-    = &'r mut node::Internal<T>;
+        A: 'r;
 
     fn set_root(&mut self, root: Option<usize>) {
-        self.root = root
+        self.leaves.clear();
+        self.root = root;
+        self.next_generation()
     }
 
     fn set_len(&mut self, new_len: usize) {
-        self.len = new_len
+        self.len = new_len;
+        self.next_generation()
     }
 
     fn allocate_node(&mut self, node: Buffer<Self>) -> usize {
-        self.slab.insert(node.into())
+        let mut node: Node<T, M, I> = node.into();
+        node.set_order(self.order);
+        self.leaves.clear();
+        let is_leaf = matches!(node, Node::Leaf(_));
+        let id = match self.pool.ids.pop() {
+            Some(id) => {
+                self.pool.hits += 1;
+                *self.slab.get_mut(id).unwrap() = node;
+                id
+            }
+            None => {
+                self.pool.misses += 1;
+                self.slab.insert(node)
+            }
+        };
+
+        if self.links.enabled && is_leaf {
+            *self.links.get_mut(S::slot_index(id)) = None;
+            self.links.unlinked += 1
+        }
+
+        id
     }
 
     fn release_node(&mut self, id: usize) -> Buffer<Self> {
-        self.slab.remove(id).unwrap().into()
+        self.leaves.clear();
+        if self.links.enabled && matches!(self.slab.get(id), Some(Node::Leaf(_))) {
+            self.unlink_leaf(id)
+        }
+
+        if S::GENERATIONAL || self.pool.is_full() {
+            self.slab.remove(id).unwrap().into()
+        } else {
+            let slot = self.slab.get_mut(id).unwrap();
+            let node = core::mem::replace(slot, Node::Leaf(node::Leaf::default()));
+            self.pool.ids.push(id);
+            node.into()
+        }
+    }
+
+    fn link_leaf(&mut self, previous: Option<usize>, id: usize) {
+        if self.links.enabled {
+            self.link_leaf_after(previous, id)
+        }
+    }
+
+    fn relink_leaves(&mut self) {
+        if self.links.enabled {
+            self.relink()
+        }
     }
 
     fn node_mut(&mut self, id: usize) -> Option<NodeMut<Self>> {
         self.slab.get_mut(id).map(|node| node.into())
     }
+
+    fn observer(&mut self) -> Option<&mut dyn btree::Observer> {
+        match &mut self.observer {
+            Some(observer) => Some(observer.as_mut()),
+            None => None,
+        }
+    }
+
+    /// Only borrows the node being entered, through [`RawSlots::get_ptr`].
+    unsafe fn raw_node(this: NonNull<Self>, id: usize) -> Option<btree::node::Raw> {
+        let slab = NonNull::new_unchecked(core::ptr::addr_of_mut!((*this.as_ptr()).slab));
+        let node = S::get_ptr(slab, id)?;
+        Some((*node.as_ptr()).raw(id))
+    }
+
+    unsafe fn raw_child_id(
+        _this: NonNull<Self>,
+        node: &btree::node::Raw,
+        index: usize,
+    ) -> Option<usize> {
+        if node.is_internal() {
+            node::Internal::<T, M, I>::raw_child_id(node, index)
+        } else {
+            None
+        }
+    }
+
+    unsafe fn raw_item_mut<'r>(
+        _this: NonNull<Self>,
+        node: &btree::node::Raw,
+        offset: usize,
+    ) -> &'r mut T
+    where
+        Self: 'r,
+    {
+        if node.is_internal() {
+            node::Internal::<T, M, I>::raw_item_mut(node, offset)
+        } else {
+            node::Leaf::<T, M, I>::raw_item_mut(node, offset)
+        }
+    }
+
+    fn remaining_node_capacity(&self) -> Option<usize> {
+        self.slab
+            .vacant_slots()
+            .map(|vacant| vacant + self.pool.ids.len())
+    }
+
+    /// Reserves roughly `additional / (order - 1)` node slots,
+    /// the number of nodes required to hold `additional` items when they are full.
+    fn reserve(&mut self, additional: usize) {
+        let nodes = additional.div_ceil(self.order - 1);
+        self.slab.reserve_slots(nodes);
+        if self.is_augmented() {
+            self.augmentations.reserve(nodes)
+        }
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.trim();
+        self.slab.shrink_slots();
+        self.augmentations.shrink_to_fit()
+    }
+
+    fn empty(&self) -> Self
+    where
+        Self: Default,
+    {
+        let mut empty = Self {
+            order: self.order,
+            ..Self::default()
+        };
+
+        empty.links.enabled = self.links.enabled;
+        empty.policy = self.policy.clone();
+        empty.set_generations(self.generation.is_some());
+        empty
+    }
+
+    fn is_augmented(&self) -> bool {
+        core::mem::size_of::<A>() != 0
+    }
+
+    fn recompute(&mut self, id: usize) {
+        if self.is_augmented() {
+            let augmentation = self.compute_augmentation(id);
+            let index = S::slot_index(id);
+            if index >= self.augmentations.len() {
+                self.augmentations.resize_with(index + 1, A::default)
+            }
+
+            self.augmentations[index] = augmentation
+        }
+    }
+}
+
+impl<T, S: NodeSlabMut<T, M, I>, A: Augmentation<Self>, const M: usize, I: NodeId> Augment
+    for Storage<T, S, A, M, I>
+{
+    type Augmentation = A;
+
+    fn augmentation(&self, id: usize) -> Option<&A> {
+        if self.slab.get(id).is_some() {
+            self.augmentations.get(S::slot_index(id))
+        } else {
+            None
+        }
+    }
 }
 
-impl<'a, T, S: cc_traits::SlabMut<Node<T>>> btree::node::item::Mut<Storage<T, S>> for &'a mut T {
+impl<
+        T,
+        S: NodeSlabMut<T, M, I>,
+        A: Augmentation<Storage<T, S, A, M, I>>,
+        const M: usize,
+        I: NodeId,
+    > btree::node::item::Mut<Storage<T, S, A, M, I>> for &mut T
+{
     fn swap(&mut self, other: &mut T) {
-        std::mem::swap(*self, other)
+        core::mem::swap(*self, other)
     }
 }
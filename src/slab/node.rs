@@ -1,5 +1,13 @@
-use super::Storage;
-use crate::btree::node::{Buffer, Mut, Ref};
+use super::{NodeId, NodeSlab, NodeSlabMut, Storage, DEFAULT_ORDER};
+use crate::{
+    btree::{
+        node::{Buffer, Mut, Ref},
+        Augmentation, SubtreeLen,
+    },
+    paged::Codec,
+};
+use alloc::vec::Vec;
+use core::fmt;
 
 mod internal;
 mod leaf;
@@ -7,13 +15,104 @@ mod leaf;
 pub use internal::Internal;
 pub use leaf::Leaf;
 
-pub enum Node<T> {
-    Internal(Internal<T>),
-    Leaf(Leaf<T>),
+#[derive(Clone)]
+pub enum Node<T, const M: usize = DEFAULT_ORDER, I = usize> {
+    Internal(Internal<T, M, I>),
+    Leaf(Leaf<T, M, I>),
 }
 
-impl<T, S: cc_traits::SlabMut<Node<T>>> From<Buffer<Storage<T, S>>> for Node<T> {
-    fn from(node: Buffer<Storage<T, S>>) -> Self {
+impl<T, const M: usize, I: NodeId> Node<T, M, I> {
+    /// Sets the order of the node.
+    pub(crate) fn set_order(&mut self, order: usize) {
+        match self {
+            Self::Internal(node) => node.set_order(order),
+            Self::Leaf(node) => node.set_order(order),
+        }
+    }
+
+    /// Returns the size of the heap allocation owned by the node, in bytes.
+    pub(crate) fn heap_size(&self) -> usize {
+        match self {
+            Self::Internal(node) => node.heap_size(),
+            Self::Leaf(node) => node.heap_size(),
+        }
+    }
+
+    /// Returns a raw handle on the node, pointing to its slots.
+    pub(crate) fn raw(&mut self, id: usize) -> crate::btree::node::Raw {
+        match self {
+            Self::Internal(node) => node.raw(id),
+            Self::Leaf(node) => node.raw(id),
+        }
+    }
+
+    /// Returns the identifiers of the children of the node.
+    pub(crate) fn child_ids(&self) -> Vec<usize> {
+        match self {
+            Self::Internal(node) => node.child_ids().collect(),
+            Self::Leaf(_) => Vec::new(),
+        }
+    }
+
+    /// Sets the parent of the node.
+    pub(crate) fn set_parent(&mut self, parent: Option<usize>) {
+        match self {
+            Self::Internal(node) => node.set_parent(parent),
+            Self::Leaf(node) => node.set_parent(parent),
+        }
+    }
+
+    /// Replaces the identifiers of the children of the node, in order.
+    pub(crate) fn set_child_ids(&mut self, ids: impl IntoIterator<Item = usize>) {
+        if let Self::Internal(node) = self {
+            node.set_child_ids(ids)
+        }
+    }
+}
+
+impl<T: fmt::Debug, const M: usize, I: NodeId> fmt::Debug for Node<T, M, I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Internal(node) => node.fmt(f),
+            Self::Leaf(node) => node.fmt(f),
+        }
+    }
+}
+
+impl<T: Codec, const M: usize, I: NodeId> Node<T, M, I> {
+    /// Appends the encoding of the node to the given buffer.
+    pub(crate) fn encode(&self, buf: &mut Vec<u8>) {
+        match self {
+            Self::Internal(node) => {
+                1u8.encode(buf);
+                node.encode(buf)
+            }
+            Self::Leaf(node) => {
+                0u8.encode(buf);
+                node.encode(buf)
+            }
+        }
+    }
+
+    /// Decodes a node.
+    pub(crate) fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        match u8::decode(bytes)? {
+            0 => Leaf::decode(bytes).map(Self::Leaf),
+            1 => Internal::decode(bytes).map(Self::Internal),
+            _ => None,
+        }
+    }
+}
+
+impl<
+        T,
+        S: NodeSlabMut<T, M, I>,
+        A: Augmentation<Storage<T, S, A, M, I>>,
+        const M: usize,
+        I: NodeId,
+    > From<Buffer<Storage<T, S, A, M, I>>> for Node<T, M, I>
+{
+    fn from(node: Buffer<Storage<T, S, A, M, I>>) -> Self {
         match node {
             Buffer::Internal(node) => Self::Internal(node),
             Buffer::Leaf(node) => Self::Leaf(node),
@@ -21,8 +120,15 @@ impl<T, S: cc_traits::SlabMut<Node<T>>> From<Buffer<Storage<T, S>>> for Node<T>
     }
 }
 
-impl<T, S: cc_traits::SlabMut<Node<T>>> From<Node<T>> for Buffer<Storage<T, S>> {
-    fn from(node: Node<T>) -> Self {
+impl<
+        T,
+        S: NodeSlabMut<T, M, I>,
+        A: Augmentation<Storage<T, S, A, M, I>>,
+        const M: usize,
+        I: NodeId,
+    > From<Node<T, M, I>> for Buffer<Storage<T, S, A, M, I>>
+{
+    fn from(node: Node<T, M, I>) -> Self {
         match node {
             Node::Internal(node) => Self::Internal(node),
             Node::Leaf(node) => Self::Leaf(node),
@@ -30,8 +136,10 @@ impl<T, S: cc_traits::SlabMut<Node<T>>> From<Node<T>> for Buffer<Storage<T, S>>
     }
 }
 
-impl<'r, T, S: 'r + cc_traits::Slab<Node<T>>> From<&'r Node<T>> for Ref<'r, Storage<T, S>> {
-    fn from(n: &'r Node<T>) -> Self {
+impl<'r, T, S: 'r + NodeSlab<T, M, I>, A: 'r + SubtreeLen, const M: usize, I: NodeId>
+    From<&'r Node<T, M, I>> for Ref<'r, Storage<T, S, A, M, I>>
+{
+    fn from(n: &'r Node<T, M, I>) -> Self {
         match n {
             Node::Internal(node) => Self::internal(node),
             Node::Leaf(node) => Self::leaf(node),
@@ -39,8 +147,16 @@ impl<'r, T, S: 'r + cc_traits::Slab<Node<T>>> From<&'r Node<T>> for Ref<'r, Stor
     }
 }
 
-impl<'r, T, S: 'r + cc_traits::SlabMut<Node<T>>> From<&'r mut Node<T>> for Mut<'r, Storage<T, S>> {
-    fn from(n: &'r mut Node<T>) -> Self {
+impl<
+        'r,
+        T,
+        S: 'r + NodeSlabMut<T, M, I>,
+        A: 'r + Augmentation<Storage<T, S, A, M, I>>,
+        const M: usize,
+        I: NodeId,
+    > From<&'r mut Node<T, M, I>> for Mut<'r, Storage<T, S, A, M, I>>
+{
+    fn from(n: &'r mut Node<T, M, I>) -> Self {
         match n {
             Node::Internal(node) => Self::internal(node),
             Node::Leaf(node) => Self::leaf(node),
@@ -1,77 +1,198 @@
 use crate::{
-    btree::{self, node::Offset},
-    slab::{Node, Storage, M},
+    btree::{self, node::Position, Augmentation, SubtreeLen},
+    paged::Codec,
+    slab::{NodeId, NodeSlab, NodeSlabMut, Storage, DEFAULT_ORDER},
 };
+use alloc::vec::Vec;
+use core::{fmt, ptr::NonNull};
 use smallvec::SmallVec;
 
-struct Branch<T> {
+#[derive(Clone)]
+struct Branch<T, I> {
     item: T,
-    child_id: usize,
+    child_id: I,
 }
 
-pub struct Internal<T> {
-    parent: usize,
-    first_child_id: usize,
-    branches: SmallVec<[Branch<T>; M]>,
+#[derive(Clone)]
+pub struct Internal<T, const M: usize = DEFAULT_ORDER, I = usize> {
+    parent: I,
+    order: usize,
+    first_child_id: I,
+    branches: SmallVec<[Branch<T, I>; M]>,
 }
 
-impl<T> Default for Internal<T> {
+impl<T, const M: usize, I: NodeId> Default for Internal<T, M, I> {
     fn default() -> Self {
         Self {
-            parent: usize::MAX,
-            first_child_id: usize::MAX,
+            parent: I::NONE,
+            order: M,
+            first_child_id: I::NONE,
             branches: SmallVec::new(),
         }
     }
 }
 
-impl<T> Internal<T> {
+impl<T, const M: usize, I: NodeId> Internal<T, M, I> {
     fn parent(&self) -> Option<usize> {
-        if self.parent == usize::MAX {
+        if self.parent == I::NONE {
             None
         } else {
-            Some(self.parent)
+            Some(self.parent.into_key())
         }
     }
 
-    fn set_parent(&mut self, parent: Option<usize>) {
-        self.parent = parent.unwrap_or(usize::MAX)
+    pub(crate) fn set_parent(&mut self, parent: Option<usize>) {
+        self.parent = parent.map(I::from_key).unwrap_or(I::NONE)
+    }
+
+    pub(crate) fn set_order(&mut self, order: usize) {
+        self.order = order
+    }
+
+    /// Returns the size of the heap allocation owned by the node, in bytes.
+    pub(crate) fn heap_size(&self) -> usize {
+        if self.branches.spilled() {
+            self.branches.capacity() * core::mem::size_of::<Branch<T, I>>()
+        } else {
+            0
+        }
+    }
+
+    /// Returns a raw handle on this node, pointing to its branches.
+    pub(crate) fn raw(&mut self, id: usize) -> btree::node::Raw {
+        btree::node::Raw {
+            id,
+            item_count: self.branches.len(),
+            first_child_id: Some(self.first_child_id.into_key()),
+            ptr: NonNull::new(self.branches.as_mut_ptr()).unwrap().cast(),
+        }
+    }
+
+    /// Returns the identifier of the child at the given index
+    /// of a raw handle returned by [`raw`](Self::raw), if any.
+    ///
+    /// Only the child identifier is read, not the neighboring items.
+    ///
+    /// # Safety
+    ///
+    /// The node must still be valid and unmodified, and be an internal node.
+    pub(crate) unsafe fn raw_child_id(node: &btree::node::Raw, index: usize) -> Option<usize> {
+        match index {
+            0 => node.first_child_id,
+            i if i <= node.item_count => {
+                let branch = node.ptr.cast::<Branch<T, I>>().as_ptr().add(i - 1);
+                Some(core::ptr::addr_of!((*branch).child_id).read().into_key())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the item at the given offset
+    /// of a raw handle returned by [`raw`](Self::raw).
+    ///
+    /// # Safety
+    ///
+    /// The node must still be valid and unmodified,
+    /// and the offset less than its number of items.
+    pub(crate) unsafe fn raw_item_mut<'r>(node: &btree::node::Raw, offset: usize) -> &'r mut T {
+        let branch = node.ptr.cast::<Branch<T, I>>().as_ptr().add(offset);
+        &mut *core::ptr::addr_of_mut!((*branch).item)
     }
 
     fn item_count(&self) -> usize {
         self.branches.len()
     }
 
-    fn item(&self, offset: Offset) -> Option<&T> {
-        self.branches.get(offset.unwrap()).map(|b| &b.item)
+    fn item(&self, offset: Position) -> Option<&T> {
+        self.branches.get(offset.value()?).map(|b| &b.item)
     }
 
     fn child_id(&self, index: usize) -> Option<usize> {
         if index == 0 {
-            Some(self.first_child_id)
+            Some(self.first_child_id.into_key())
         } else {
-            self.branches.get(index - 1).map(|b| b.child_id)
+            self.branches.get(index - 1).map(|b| b.child_id.into_key())
         }
     }
 
     fn max_capacity(&self) -> usize {
-        M
+        self.order
     }
 
     fn set_first_child_id(&mut self, id: usize) {
-        self.first_child_id = id;
+        self.first_child_id = I::from_key(id);
     }
 
     fn push_right(&mut self, item: T, child: usize) {
         self.branches.push(Branch {
             item,
-            child_id: child,
+            child_id: I::from_key(child),
         })
     }
+
+    /// Returns an iterator over the identifiers of the children of this node.
+    pub(crate) fn child_ids(&self) -> impl '_ + Iterator<Item = usize> {
+        (0..=self.item_count()).filter_map(move |i| self.child_id(i))
+    }
+
+    /// Replaces the identifiers of the children of this node, in order.
+    pub(crate) fn set_child_ids(&mut self, ids: impl IntoIterator<Item = usize>) {
+        let mut ids = ids.into_iter();
+        if let Some(id) = ids.next() {
+            self.set_first_child_id(id)
+        }
+
+        for (branch, id) in self.branches.iter_mut().zip(ids) {
+            branch.child_id = I::from_key(id)
+        }
+    }
+}
+
+impl<T: fmt::Debug, const M: usize, I: NodeId> fmt::Debug for Internal<T, M, I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let items: Vec<_> = self.branches.iter().map(|b| &b.item).collect();
+        let children: Vec<_> = self.child_ids().collect();
+        f.debug_struct("Internal")
+            .field("parent", &self.parent())
+            .field("items", &items)
+            .field("children", &children)
+            .finish()
+    }
 }
 
-impl<'s, T, S: cc_traits::SlabMut<Node<T>>> btree::node::buffer::Internal<Storage<T, S>>
-    for Internal<T>
+impl<T: Codec, const M: usize, I: NodeId> Internal<T, M, I> {
+    /// Appends the encoding of this node to the given buffer.
+    pub(crate) fn encode(&self, buf: &mut Vec<u8>) {
+        self.parent().encode(buf);
+        self.first_child_id.into_key().encode(buf);
+        (self.branches.len() as u32).encode(buf);
+        for branch in &self.branches {
+            branch.item.encode(buf);
+            branch.child_id.into_key().encode(buf)
+        }
+    }
+
+    /// Decodes a node.
+    pub(crate) fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        let mut node = Self::default();
+        node.set_parent(Codec::decode(bytes)?);
+        node.set_first_child_id(usize::decode(bytes)?);
+        for _ in 0..u32::decode(bytes)? {
+            let item = T::decode(bytes)?;
+            node.push_right(item, usize::decode(bytes)?)
+        }
+
+        Some(node)
+    }
+}
+
+impl<
+        T,
+        S: NodeSlabMut<T, M, I>,
+        A: Augmentation<Storage<T, S, A, M, I>>,
+        const M: usize,
+        I: NodeId,
+    > btree::node::buffer::Internal<Storage<T, S, A, M, I>> for Internal<T, M, I>
 {
     fn parent(&self) -> Option<usize> {
         self.parent()
@@ -85,7 +206,7 @@ impl<'s, T, S: cc_traits::SlabMut<Node<T>>> btree::node::buffer::Internal<Storag
         self.item_count()
     }
 
-    // fn item<'a>(&'a self, offset: Offset) -> Option<&'a T> where Storage<T, S>: 'a {
+    // fn item<'a>(&'a self, offset: Position) -> Option<&'a T> where Storage<T, S>: 'a {
     // 	self.item(offset)
     // }
 
@@ -106,12 +227,12 @@ impl<'s, T, S: cc_traits::SlabMut<Node<T>>> btree::node::buffer::Internal<Storag
     }
 
     fn forget(self) {
-        std::mem::forget(self.branches)
+        core::mem::forget(self.branches)
     }
 }
 
-impl<'s, T, S: 's + cc_traits::Slab<Node<T>>> btree::node::ItemAccess<Storage<T, S>>
-    for &'s Internal<T>
+impl<'s, T, S: 's + NodeSlab<T, M, I>, A: 's + SubtreeLen, const M: usize, I: NodeId>
+    btree::node::ItemAccess<Storage<T, S, A, M, I>> for &'s Internal<T, M, I>
 {
     /// Returns the current number of items stored in this node.
     fn item_count(&self) -> usize {
@@ -119,13 +240,13 @@ impl<'s, T, S: 's + cc_traits::Slab<Node<T>>> btree::node::ItemAccess<Storage<T,
     }
 
     /// Returns a reference to the item with the given offset in the node.
-    fn borrow_item(&self, offset: Offset) -> Option<&T> {
+    fn borrow_item(&self, offset: Position) -> Option<&T> {
         (*self).item(offset)
     }
 }
 
-impl<'a, T, S: 'a + cc_traits::Slab<Node<T>>> btree::node::InternalRef<Storage<T, S>>
-    for &'a Internal<T>
+impl<'a, T, S: 'a + NodeSlab<T, M, I>, A: 'a + SubtreeLen, const M: usize, I: NodeId>
+    btree::node::InternalRef<Storage<T, S, A, M, I>> for &'a Internal<T, M, I>
 {
     /// Returns the identifer of the parent node, if any.
     fn parent(&self) -> Option<usize> {
@@ -149,16 +270,16 @@ impl<'a, T, S: 'a + cc_traits::Slab<Node<T>>> btree::node::InternalRef<Storage<T
     }
 }
 
-impl<'a, T, S: 'a + cc_traits::Slab<Node<T>>> btree::node::InternalConst<'a, Storage<T, S>>
-    for &'a Internal<T>
+impl<'a, T, S: 'a + NodeSlab<T, M, I>, A: 'a + SubtreeLen, const M: usize, I: NodeId>
+    btree::node::InternalConst<'a, Storage<T, S, A, M, I>> for &'a Internal<T, M, I>
 {
-    fn item(&self, offset: Offset) -> Option<&'a T> {
+    fn item(&self, offset: Position) -> Option<&'a T> {
         (*self).item(offset)
     }
 }
 
-impl<'a, T, S: 'a + cc_traits::Slab<Node<T>>> btree::node::ItemAccess<Storage<T, S>>
-    for &'a mut Internal<T>
+impl<'a, T, S: 'a + NodeSlab<T, M, I>, A: 'a + SubtreeLen, const M: usize, I: NodeId>
+    btree::node::ItemAccess<Storage<T, S, A, M, I>> for &'a mut Internal<T, M, I>
 {
     /// Returns the current number of items stored in this node.
     fn item_count(&self) -> usize {
@@ -166,24 +287,24 @@ impl<'a, T, S: 'a + cc_traits::Slab<Node<T>>> btree::node::ItemAccess<Storage<T,
     }
 
     /// Returns a reference to the item with the given offset in the node.
-    fn borrow_item(&self, offset: Offset) -> Option<&T> {
+    fn borrow_item(&self, offset: Position) -> Option<&T> {
         (*self).item(offset)
     }
 }
 
-impl<'a, T, S: 'a + cc_traits::Slab<Node<T>>> btree::node::InternalRef<Storage<T, S>>
-    for &'a mut Internal<T>
+impl<'a, T, S: 'a + NodeSlab<T, M, I>, A: 'a + SubtreeLen, const M: usize, I: NodeId>
+    btree::node::InternalRef<Storage<T, S, A, M, I>> for &'a mut Internal<T, M, I>
 {
     /// Returns the identifer of the parent node, if any.
     fn parent(&self) -> Option<usize> {
-        Internal::<T>::parent(self)
+        Internal::<T, M, I>::parent(self)
     }
 
     /// Returns the id of the child with the given index, if any.
     ///
     /// Note that in the case of leaf nodes, this always return `None`.
     fn child_id(&self, index: usize) -> Option<usize> {
-        Internal::<T>::child_id(self, index)
+        Internal::<T, M, I>::child_id(self, index)
     }
 
     /// Returns the maximum capacity of this node.
@@ -192,12 +313,18 @@ impl<'a, T, S: 'a + cc_traits::Slab<Node<T>>> btree::node::InternalRef<Storage<T
     ///
     /// The node is considered overflowing if it contains `max_capacity` items.
     fn max_capacity(&self) -> usize {
-        Internal::<T>::max_capacity(self)
+        Internal::<T, M, I>::max_capacity(self)
     }
 }
 
-impl<'r, T, S: 'r + cc_traits::SlabMut<Node<T>>> btree::node::InternalMut<'r, Storage<T, S>>
-    for &'r mut Internal<T>
+impl<
+        'r,
+        T,
+        S: 'r + NodeSlabMut<T, M, I>,
+        A: 'r + Augmentation<Storage<T, S, A, M, I>>,
+        const M: usize,
+        I: NodeId,
+    > btree::node::InternalMut<'r, Storage<T, S, A, M, I>> for &'r mut Internal<T, M, I>
 {
     fn set_parent(&mut self, parent: Option<usize>) {
         (*self).set_parent(parent)
@@ -208,36 +335,36 @@ impl<'r, T, S: 'r + cc_traits::SlabMut<Node<T>>> btree::node::InternalMut<'r, St
     }
 
     /// Returns a mutable reference to the item with the given offset in the node.
-    fn into_item_mut(self, offset: Offset) -> Option<&'r mut T> {
+    fn into_item_mut(self, offset: Position) -> Option<&'r mut T> {
         self.branches
-            .get_mut(offset.unwrap())
+            .get_mut(offset.value()?)
             .map(|branch| &mut branch.item)
     }
 
-    fn insert(&mut self, offset: Offset, item: T, right_child_id: usize) {
+    fn insert(&mut self, offset: Position, item: T, right_child_id: usize) {
         self.branches.insert(
             offset.unwrap(),
             Branch {
                 item,
-                child_id: right_child_id,
+                child_id: I::from_key(right_child_id),
             },
         )
     }
 
-    fn remove(&mut self, offset: Offset) -> (T, usize) {
+    fn remove(&mut self, offset: Position) -> (T, usize) {
         let b = self.branches.remove(offset.unwrap());
-        (b.item, b.child_id)
+        (b.item, b.child_id.into_key())
     }
 
-    fn replace(&mut self, offset: Offset, mut item: T) -> T {
-        std::mem::swap(
+    fn replace(&mut self, offset: Position, mut item: T) -> T {
+        core::mem::swap(
             &mut self.branches.get_mut(offset.unwrap()).unwrap().item,
             &mut item,
         );
         item
     }
 
-    fn append(&mut self, separator: T, mut other: Internal<T>) -> Offset {
+    fn append(&mut self, separator: T, mut other: Internal<T, M, I>) -> Position {
         let offset = self.branches.len().into();
         self.branches.push(Branch {
             item: separator,
@@ -246,4 +373,13 @@ impl<'r, T, S: 'r + cc_traits::SlabMut<Node<T>>> btree::node::InternalMut<'r, St
         self.branches.append(&mut other.branches);
         offset
     }
+
+    fn drain_from(&mut self, offset: Position) -> Internal<T, M, I> {
+        let mut node = Internal {
+            order: self.order,
+            ..Internal::default()
+        };
+        node.branches.extend(self.branches.drain(offset.unwrap()..));
+        node
+    }
 }
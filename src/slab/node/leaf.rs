@@ -1,46 +1,134 @@
 use crate::{
-    btree::{self, node::Offset},
-    slab::{Node, Storage, M},
+    btree::{self, node::Position, Augmentation, SubtreeLen},
+    paged::Codec,
+    slab::{NodeId, NodeSlab, NodeSlabMut, Storage, DEFAULT_ORDER},
 };
+use alloc::vec::Vec;
+use core::{fmt, ptr::NonNull};
 use smallvec::SmallVec;
 
-pub struct Leaf<T> {
-    parent: usize,
-    items: SmallVec<[T; M + 1]>,
+#[derive(Clone)]
+pub struct Leaf<T, const M: usize = DEFAULT_ORDER, I = usize> {
+    parent: I,
+    order: usize,
+    items: SmallVec<[T; M]>,
 }
 
-impl<T> Default for Leaf<T> {
+impl<T, const M: usize, I: NodeId> Leaf<T, M, I> {
+    fn parent(&self) -> Option<usize> {
+        if self.parent == I::NONE {
+            None
+        } else {
+            Some(self.parent.into_key())
+        }
+    }
+
+    pub(crate) fn set_parent(&mut self, parent: Option<usize>) {
+        self.parent = parent.map(I::from_key).unwrap_or(I::NONE)
+    }
+
+    pub(crate) fn set_order(&mut self, order: usize) {
+        self.order = order
+    }
+
+    /// Returns the size of the heap allocation owned by the node, in bytes.
+    pub(crate) fn heap_size(&self) -> usize {
+        if self.items.spilled() {
+            self.items.capacity() * core::mem::size_of::<T>()
+        } else {
+            0
+        }
+    }
+
+    /// Returns a raw handle on this node, pointing to its items.
+    pub(crate) fn raw(&mut self, id: usize) -> btree::node::Raw {
+        btree::node::Raw {
+            id,
+            item_count: self.items.len(),
+            first_child_id: None,
+            ptr: NonNull::new(self.items.as_mut_ptr()).unwrap().cast(),
+        }
+    }
+
+    /// Returns a mutable reference to the item at the given offset
+    /// of a raw handle returned by [`raw`](Self::raw).
+    ///
+    /// # Safety
+    ///
+    /// The node must still be valid and unmodified,
+    /// and the offset less than its number of items.
+    pub(crate) unsafe fn raw_item_mut<'r>(node: &btree::node::Raw, offset: usize) -> &'r mut T {
+        &mut *node.ptr.cast::<T>().as_ptr().add(offset)
+    }
+}
+
+impl<T: fmt::Debug, const M: usize, I: NodeId> fmt::Debug for Leaf<T, M, I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Leaf")
+            .field("parent", &self.parent())
+            .field("items", &self.items.as_slice())
+            .finish()
+    }
+}
+
+impl<T: Codec, const M: usize, I: NodeId> Leaf<T, M, I> {
+    /// Appends the encoding of this node to the given buffer.
+    pub(crate) fn encode(&self, buf: &mut Vec<u8>) {
+        self.parent().encode(buf);
+        (self.items.len() as u32).encode(buf);
+        for item in &self.items {
+            item.encode(buf)
+        }
+    }
+
+    /// Decodes a node.
+    pub(crate) fn decode(bytes: &mut &[u8]) -> Option<Self> {
+        let mut node = Self::default();
+        node.set_parent(Codec::decode(bytes)?);
+        for _ in 0..u32::decode(bytes)? {
+            node.items.push(T::decode(bytes)?)
+        }
+
+        Some(node)
+    }
+}
+
+impl<T, const M: usize, I: NodeId> Default for Leaf<T, M, I> {
     fn default() -> Self {
         Self {
-            parent: usize::MAX,
+            parent: I::NONE,
+            order: M,
             items: SmallVec::new(),
         }
     }
 }
 
-impl<T, S: cc_traits::SlabMut<Node<T>>> btree::node::buffer::Leaf<Storage<T, S>> for Leaf<T> {
+impl<
+        T,
+        S: NodeSlabMut<T, M, I>,
+        A: Augmentation<Storage<T, S, A, M, I>>,
+        const M: usize,
+        I: NodeId,
+    > btree::node::buffer::Leaf<Storage<T, S, A, M, I>> for Leaf<T, M, I>
+{
     fn parent(&self) -> Option<usize> {
-        if self.parent == usize::MAX {
-            None
-        } else {
-            Some(self.parent)
-        }
+        self.parent()
     }
 
     fn set_parent(&mut self, parent: Option<usize>) {
-        self.parent = parent.unwrap_or(usize::MAX)
+        self.set_parent(parent)
     }
 
     fn item_count(&self) -> usize {
         self.items.len()
     }
 
-    // fn item<'a>(&'a self, offset: Offset) -> Option<&'a T> where Storage<T, S>: 'a {
+    // fn item<'a>(&'a self, offset: Position) -> Option<&'a T> where Storage<T, S>: 'a {
     // 	self.items.get(offset.unwrap())
     // }
 
     fn max_capacity(&self) -> usize {
-        M + 1
+        self.order
     }
 
     fn push_right(&mut self, item: T) {
@@ -48,12 +136,12 @@ impl<T, S: cc_traits::SlabMut<Node<T>>> btree::node::buffer::Leaf<Storage<T, S>>
     }
 
     fn forget(self) {
-        std::mem::forget(self.items)
+        core::mem::forget(self.items)
     }
 }
 
-impl<'a, T, S: 'a + cc_traits::Slab<Node<T>>> btree::node::ItemAccess<Storage<T, S>>
-    for &'a Leaf<T>
+impl<'a, T, S: 'a + NodeSlab<T, M, I>, A: 'a + SubtreeLen, const M: usize, I: NodeId>
+    btree::node::ItemAccess<Storage<T, S, A, M, I>> for &'a Leaf<T, M, I>
 {
     /// Returns the current number of items stored in this node.
     fn item_count(&self) -> usize {
@@ -61,35 +149,37 @@ impl<'a, T, S: 'a + cc_traits::Slab<Node<T>>> btree::node::ItemAccess<Storage<T,
     }
 
     /// Returns a reference to the item with the given offset in the node.
-    fn borrow_item(&self, offset: Offset) -> Option<&T> {
-        self.items.get(offset.unwrap())
+    fn borrow_item(&self, offset: Position) -> Option<&T> {
+        self.items.get(offset.value()?)
     }
 }
 
-impl<'a, T, S: 'a + cc_traits::Slab<Node<T>>> btree::node::LeafRef<Storage<T, S>> for &'a Leaf<T> {
+impl<'a, T, S: 'a + NodeSlab<T, M, I>, A: 'a + SubtreeLen, const M: usize, I: NodeId>
+    btree::node::LeafRef<Storage<T, S, A, M, I>> for &'a Leaf<T, M, I>
+{
     fn parent(&self) -> Option<usize> {
-        if self.parent == usize::MAX {
-            None
-        } else {
-            Some(self.parent)
-        }
+        Leaf::<T, M, I>::parent(self)
     }
 
     fn max_capacity(&self) -> usize {
-        M + 1
+        self.order
+    }
+
+    fn min_capacity(&self) -> usize {
+        self.order / 2 - 1
     }
 }
 
-impl<'a, T, S: 'a + cc_traits::Slab<Node<T>>> btree::node::LeafConst<'a, Storage<T, S>>
-    for &'a Leaf<T>
+impl<'a, T, S: 'a + NodeSlab<T, M, I>, A: 'a + SubtreeLen, const M: usize, I: NodeId>
+    btree::node::LeafConst<'a, Storage<T, S, A, M, I>> for &'a Leaf<T, M, I>
 {
-    fn item(&self, offset: Offset) -> Option<&'a T> {
-        self.items.get(offset.unwrap())
+    fn item(&self, offset: Position) -> Option<&'a T> {
+        self.items.get(offset.value()?)
     }
 }
 
-impl<'a, T, S: 'a + cc_traits::Slab<Node<T>>> btree::node::ItemAccess<Storage<T, S>>
-    for &'a mut Leaf<T>
+impl<'a, T, S: 'a + NodeSlab<T, M, I>, A: 'a + SubtreeLen, const M: usize, I: NodeId>
+    btree::node::ItemAccess<Storage<T, S, A, M, I>> for &'a mut Leaf<T, M, I>
 {
     /// Returns the current number of items stored in this node.
     fn item_count(&self) -> usize {
@@ -97,54 +187,69 @@ impl<'a, T, S: 'a + cc_traits::Slab<Node<T>>> btree::node::ItemAccess<Storage<T,
     }
 
     /// Returns a reference to the item with the given offset in the node.
-    fn borrow_item(&self, offset: Offset) -> Option<&T> {
-        self.items.get(offset.unwrap())
+    fn borrow_item(&self, offset: Position) -> Option<&T> {
+        self.items.get(offset.value()?)
     }
 }
 
-impl<'a, T, S: 'a + cc_traits::Slab<Node<T>>> btree::node::LeafRef<Storage<T, S>>
-    for &'a mut Leaf<T>
+impl<'a, T, S: 'a + NodeSlab<T, M, I>, A: 'a + SubtreeLen, const M: usize, I: NodeId>
+    btree::node::LeafRef<Storage<T, S, A, M, I>> for &'a mut Leaf<T, M, I>
 {
     fn parent(&self) -> Option<usize> {
-        if self.parent == usize::MAX {
-            None
-        } else {
-            Some(self.parent)
-        }
+        Leaf::<T, M, I>::parent(self)
     }
 
     fn max_capacity(&self) -> usize {
-        M + 1
+        self.order
+    }
+
+    fn min_capacity(&self) -> usize {
+        self.order / 2 - 1
     }
 }
 
-impl<'r, T, S: 'r + cc_traits::SlabMut<Node<T>>> btree::node::LeafMut<'r, Storage<T, S>>
-    for &'r mut Leaf<T>
+impl<
+        'r,
+        T,
+        S: 'r + NodeSlabMut<T, M, I>,
+        A: 'r + Augmentation<Storage<T, S, A, M, I>>,
+        const M: usize,
+        I: NodeId,
+    > btree::node::LeafMut<'r, Storage<T, S, A, M, I>> for &'r mut Leaf<T, M, I>
 {
     fn set_parent(&mut self, parent: Option<usize>) {
-        self.parent = parent.unwrap_or(usize::MAX)
+        Leaf::<T, M, I>::set_parent(self, parent)
     }
 
-    fn item_mut(&mut self, offset: Offset) -> Option<&mut T> {
-        self.items.get_mut(offset.unwrap())
+    fn item_mut(&mut self, offset: Position) -> Option<&mut T> {
+        self.items.get_mut(offset.value()?)
     }
 
-    fn into_item_mut(self, offset: Offset) -> Option<&'r mut T> {
-        self.items.get_mut(offset.unwrap())
+    fn into_item_mut(self, offset: Position) -> Option<&'r mut T> {
+        self.items.get_mut(offset.value()?)
     }
 
-    fn insert(&mut self, offset: Offset, item: T) {
+    fn insert(&mut self, offset: Position, item: T) {
         self.items.insert(offset.unwrap(), item)
     }
 
-    fn remove(&mut self, offset: Offset) -> T {
+    fn remove(&mut self, offset: Position) -> T {
         self.items.remove(offset.unwrap())
     }
 
-    fn append(&mut self, separator: T, mut other: Leaf<T>) -> Offset {
+    fn append(&mut self, separator: T, mut other: Leaf<T, M, I>) -> Position {
         let offset = self.items.len().into();
         self.items.push(separator);
         self.items.append(&mut other.items);
         offset
     }
+
+    fn drain_from(&mut self, offset: Position) -> Leaf<T, M, I> {
+        let mut node = Leaf {
+            order: self.order,
+            ..Leaf::default()
+        };
+        node.items.extend(self.items.drain(offset.unwrap()..));
+        node
+    }
 }
@@ -0,0 +1,226 @@
+use super::{Node, NodeId, NodeSlab, NodeSlabMut, Storage};
+use crate::{
+    btree::{Augmentation, StorageMut},
+    paged::Codec,
+    Storage as _,
+};
+use core::marker::PhantomData;
+use std::{collections::BTreeSet, io};
+
+/// Magic number at the beginning of every snapshot.
+const MAGIC: [u8; 8] = *b"GBTSNAPS";
+
+/// Returns the error reported when a snapshot cannot be decoded.
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+impl<T: Codec, S: NodeSlab<T, M, I>, A, const M: usize, I: NodeId> Storage<T, S, A, M, I> {
+    /// Writes a binary snapshot of the storage.
+    ///
+    /// Contrarily to [`clone_into_slab`](Self::clone_into_slab),
+    /// nodes are saved verbatim in their slot,
+    /// so that [`load_snapshot`](Self::load_snapshot) restores the exact same node graph.
+    /// Slots below the last node that are not used by the tree are saved as vacant.
+    /// Every [`Address`](crate::btree::node::Address) valid in this storage
+    /// is hence valid in the restored storage.
+    /// Augmentations are not saved, but recomputed on load.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use generic_btree::{
+    ///     slab::{Map, MapStorage},
+    ///     Storage,
+    /// };
+    ///
+    /// let map: Map<u32, u64> = (0..100).map(|i| (i, i as u64 * 2)).collect();
+    ///
+    /// let mut bytes = Vec::new();
+    /// map.btree().save_snapshot(&mut bytes).unwrap();
+    ///
+    /// let restored = Map::from_btree(MapStorage::load_snapshot(bytes.as_slice()).unwrap());
+    /// assert!(restored.iter().eq(map.iter()));
+    /// assert_eq!(restored.btree().address_of(&42), map.btree().address_of(&42));
+    /// ```
+    pub fn save_snapshot<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        let mut nodes = Vec::new();
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        while let Some(id) = stack.pop() {
+            let node = self.slab.get(id).unwrap();
+            nodes.push((id, node));
+            stack.extend(node.child_ids())
+        }
+
+        nodes.sort_unstable_by_key(|(id, _)| *id);
+        let slot_count = nodes.last().map_or(0, |(id, _)| id + 1);
+
+        let mut buf = MAGIC.to_vec();
+        self.root.encode(&mut buf);
+        self.len.encode(&mut buf);
+        self.order.encode(&mut buf);
+        slot_count.encode(&mut buf);
+        let mut next_id = 0;
+        for (id, node) in nodes {
+            while next_id < id {
+                false.encode(&mut buf);
+                next_id += 1
+            }
+
+            true.encode(&mut buf);
+            node.encode(&mut buf);
+            next_id += 1
+        }
+
+        writer.write_all(&buf)
+    }
+}
+
+impl<
+        T: Codec,
+        S: NodeSlabMut<T, M, I> + Default,
+        A: Augmentation<Self>,
+        const M: usize,
+        I: NodeId,
+    > Storage<T, S, A, M, I>
+{
+    /// Restores a storage from a snapshot written by
+    /// [`save_snapshot`](Self::save_snapshot).
+    ///
+    /// Each node is inserted back in an empty slab in its saved slot.
+    /// This requires the slab to allocate keys sequentially from `0`,
+    /// reusing the lowest vacant key first,
+    /// as do [`slab::Slab`] and [`VecSlab`](crate::vec_storage::VecSlab).
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InvalidData`](io::ErrorKind::InvalidData) error
+    /// if the snapshot is malformed or truncated,
+    /// if the saved nodes do not form a tree of the saved length
+    /// (missing, shared or unreachable nodes, inconsistent parents, leaves at different depths),
+    /// or if the slab cannot reproduce the saved node identifiers.
+    /// Slots are only allocated as they are read,
+    /// so a malformed snapshot cannot allocate more nodes than it has bytes.
+    pub fn load_snapshot<R: io::Read>(mut reader: R) -> io::Result<Self> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        let mut bytes = data.as_slice();
+
+        if bytes.len() < MAGIC.len() || bytes[..MAGIC.len()] != MAGIC {
+            return Err(invalid_data("not a B-Tree snapshot"));
+        }
+
+        bytes = &bytes[MAGIC.len()..];
+        let header = (|| {
+            Some((
+                Option::<usize>::decode(&mut bytes)?,
+                usize::decode(&mut bytes)?,
+                usize::decode(&mut bytes)?,
+                usize::decode(&mut bytes)?,
+            ))
+        })();
+        let (root, len, order, slot_count) =
+            header.ok_or_else(|| invalid_data("truncated snapshot header"))?;
+
+        if order < 4 {
+            return Err(invalid_data("invalid B-Tree order"));
+        }
+
+        let mut slab = S::default();
+        let mut vacant = Vec::new();
+        let mut node_count = 0;
+        for id in 0..slot_count {
+            let occupied =
+                bool::decode(&mut bytes).ok_or_else(|| invalid_data("truncated snapshot"))?;
+            let node = if occupied {
+                let mut node: Node<T, M, I> =
+                    Node::decode(&mut bytes).ok_or_else(|| invalid_data("invalid node"))?;
+                node.set_order(order);
+                node_count += 1;
+                node
+            } else {
+                vacant.push(id);
+                Node::Leaf(Default::default())
+            };
+
+            if slab.insert(node) != id {
+                return Err(invalid_data("slab does not preserve node identifiers"));
+            }
+        }
+
+        if !bytes.is_empty() {
+            return Err(invalid_data("trailing bytes after snapshot"));
+        }
+
+        for id in vacant {
+            slab.remove(id);
+        }
+
+        let mut storage = Self {
+            slab,
+            augmentations: Vec::new(),
+            root,
+            len,
+            order,
+            leaves: Default::default(),
+            pool: Default::default(),
+            links: Default::default(),
+            generation: None,
+            observer: None,
+            policy: None,
+            item: PhantomData,
+        };
+
+        storage.check_snapshot(node_count)?;
+        if let Some(root) = root {
+            storage.recompute_subtree(root)
+        }
+
+        Ok(storage)
+    }
+
+    /// Checks that the `node_count` loaded nodes form a tree
+    /// holding as many items as the saved length.
+    fn check_snapshot(&self, node_count: usize) -> io::Result<()> {
+        let mut visited = BTreeSet::new();
+        let mut leaf_depth = None;
+        let mut len = 0usize;
+        let mut stack: Vec<(usize, Option<usize>, usize)> =
+            self.root.map(|id| (id, None, 0)).into_iter().collect();
+        while let Some((id, parent, depth)) = stack.pop() {
+            let node = self.node(id).ok_or_else(|| invalid_data("missing node"))?;
+            if !visited.insert(id) {
+                return Err(invalid_data("node reachable more than once"));
+            }
+
+            if node.parent() != parent {
+                return Err(invalid_data("inconsistent node parent"));
+            }
+
+            if node.is_overflowing() {
+                return Err(invalid_data("overflowing node"));
+            }
+
+            len = len.saturating_add(node.item_count());
+            if node.child_count() == 0 {
+                if *leaf_depth.get_or_insert(depth) != depth {
+                    return Err(invalid_data("leaves at different depths"));
+                }
+            } else {
+                for index in 0..node.child_count() {
+                    stack.push((node.child_id(index).unwrap(), Some(id), depth + 1))
+                }
+            }
+        }
+
+        if visited.len() != node_count {
+            return Err(invalid_data("unreachable node"));
+        }
+
+        if len != self.len {
+            return Err(invalid_data("invalid tree length"));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,869 @@
+use super::{NodeId, VacantSlots, DEFAULT_ORDER};
+use crate::{
+    btree::{
+        self,
+        node::{Buffer, Mut as NodeMut, Position},
+        ItemOrd, ItemPartialOrd, KeyOrd, KeyPartialOrd,
+    },
+    map::{Binding, BindingMut, BindingRef, Inserted},
+};
+use core::{borrow::Borrow, cmp::Ordering, marker::PhantomData};
+use smallvec::SmallVec;
+
+/// Slab of structure-of-arrays nodes.
+pub trait NodeSlab<K, V, const M: usize, I>:
+    cc_traits::Collection<Item = Node<K, V, M, I>>
+    + cc_traits::Len
+    + cc_traits::Get<usize>
+    + VacantSlots
+{
+}
+
+impl<K, V, const M: usize, I, S> NodeSlab<K, V, M, I> for S where
+    S: cc_traits::Collection<Item = Node<K, V, M, I>>
+        + cc_traits::Len
+        + cc_traits::Get<usize>
+        + VacantSlots
+{
+}
+
+/// Mutable slab of structure-of-arrays nodes.
+pub trait NodeSlabMut<K, V, const M: usize, I>:
+    NodeSlab<K, V, M, I>
+    + cc_traits::GetMut<usize>
+    + cc_traits::Insert<Output = usize>
+    + cc_traits::Remove<usize>
+{
+}
+
+impl<K, V, const M: usize, I, S> NodeSlabMut<K, V, M, I> for S where
+    S: NodeSlab<K, V, M, I>
+        + cc_traits::GetMut<usize>
+        + cc_traits::Insert<Output = usize>
+        + cc_traits::Remove<usize>
+{
+}
+
+#[cfg(feature = "slab")]
+pub type MapStorage<K, V, const M: usize = DEFAULT_ORDER, I = usize> =
+    Storage<K, V, slab::Slab<Node<K, V, M, I>>, M, I>;
+#[cfg(feature = "slab")]
+pub type Map<K, V, const M: usize = DEFAULT_ORDER, I = usize> = crate::Map<MapStorage<K, V, M, I>>;
+
+/// Structure-of-arrays node.
+#[derive(Clone)]
+pub enum Node<K, V, const M: usize = DEFAULT_ORDER, I = usize> {
+    Internal(Internal<K, V, M, I>),
+    Leaf(Leaf<K, V, M, I>),
+}
+
+/// Structure-of-arrays leaf node.
+///
+/// Keys and values are stored in two separate arrays.
+#[derive(Clone)]
+pub struct Leaf<K, V, const M: usize = DEFAULT_ORDER, I = usize> {
+    parent: I,
+    order: usize,
+    keys: SmallVec<[K; M]>,
+    values: SmallVec<[V; M]>,
+}
+
+impl<K, V, const M: usize, I: NodeId> Leaf<K, V, M, I> {
+    fn parent(&self) -> Option<usize> {
+        if self.parent == I::NONE {
+            None
+        } else {
+            Some(self.parent.into_key())
+        }
+    }
+
+    fn set_parent(&mut self, parent: Option<usize>) {
+        self.parent = parent.map(I::from_key).unwrap_or(I::NONE)
+    }
+
+    fn item(&self, offset: Position) -> Option<BindingRef<'_, K, V>> {
+        let i = offset.value()?;
+        Some(BindingRef::new(self.keys.get(i)?, self.values.get(i)?))
+    }
+
+    fn item_mut(&mut self, offset: Position) -> Option<BindingMut<'_, K, V>> {
+        let i = offset.value()?;
+        Some(BindingMut::new(
+            self.keys.get_mut(i)?,
+            self.values.get_mut(i)?,
+        ))
+    }
+
+    fn push_right(&mut self, binding: Binding<K, V>) {
+        let (key, value) = binding.into_pair();
+        self.keys.push(key);
+        self.values.push(value)
+    }
+}
+
+impl<K, V, const M: usize, I: NodeId> Default for Leaf<K, V, M, I> {
+    fn default() -> Self {
+        Self {
+            parent: I::NONE,
+            order: M,
+            keys: SmallVec::new(),
+            values: SmallVec::new(),
+        }
+    }
+}
+
+/// Structure-of-arrays internal node.
+///
+/// Keys, values and children identifiers are stored in three separate arrays.
+#[derive(Clone)]
+pub struct Internal<K, V, const M: usize = DEFAULT_ORDER, I = usize> {
+    parent: I,
+    order: usize,
+    keys: SmallVec<[K; M]>,
+    values: SmallVec<[V; M]>,
+    children: SmallVec<[I; M]>,
+}
+
+impl<K, V, const M: usize, I: NodeId> Internal<K, V, M, I> {
+    fn parent(&self) -> Option<usize> {
+        if self.parent == I::NONE {
+            None
+        } else {
+            Some(self.parent.into_key())
+        }
+    }
+
+    fn set_parent(&mut self, parent: Option<usize>) {
+        self.parent = parent.map(I::from_key).unwrap_or(I::NONE)
+    }
+
+    fn item(&self, offset: Position) -> Option<BindingRef<'_, K, V>> {
+        let i = offset.value()?;
+        Some(BindingRef::new(self.keys.get(i)?, self.values.get(i)?))
+    }
+
+    fn item_mut(&mut self, offset: Position) -> Option<BindingMut<'_, K, V>> {
+        let i = offset.value()?;
+        Some(BindingMut::new(
+            self.keys.get_mut(i)?,
+            self.values.get_mut(i)?,
+        ))
+    }
+
+    fn child_id(&self, index: usize) -> Option<usize> {
+        self.children.get(index).map(|id| id.into_key())
+    }
+
+    fn set_first_child_id(&mut self, id: usize) {
+        match self.children.first_mut() {
+            Some(first) => *first = I::from_key(id),
+            None => self.children.push(I::from_key(id)),
+        }
+    }
+
+    fn push_right(&mut self, binding: Binding<K, V>, child: usize) {
+        let (key, value) = binding.into_pair();
+        self.keys.push(key);
+        self.values.push(value);
+        self.children.push(I::from_key(child))
+    }
+}
+
+impl<K, V, const M: usize, I: NodeId> Default for Internal<K, V, M, I> {
+    fn default() -> Self {
+        Self {
+            parent: I::NONE,
+            order: M,
+            keys: SmallVec::new(),
+            values: SmallVec::new(),
+            children: SmallVec::new(),
+        }
+    }
+}
+
+/// Slab storage using a structure-of-arrays node layout.
+///
+/// Nodes store the keys and values of their bindings in two separate
+/// contiguous arrays instead of an array of [`Binding`] pairs,
+/// so that searching a node only reads keys,
+/// improving cache locality when values are large.
+/// Items are accessed through the composite [`BindingRef`] and [`BindingMut`] references.
+///
+/// This storage does not support augmentations.
+///
+/// # Example
+///
+/// ```
+/// use generic_btree::slab::soa::Map;
+///
+/// let mut map: Map<usize, [u8; 64]> = Map::new();
+/// for i in 0..100 {
+///     map.insert(i, [i as u8; 64]);
+/// }
+///
+/// assert_eq!(map.get(&42), Some(&[42; 64]));
+/// ```
+pub struct Storage<K, V, S, const M: usize = DEFAULT_ORDER, I = usize> {
+    /// The internal slab.
+    slab: S,
+
+    /// Root node id.
+    root: Option<usize>,
+
+    /// Size of the collection.
+    len: usize,
+
+    /// Knuth-order of the nodes.
+    order: usize,
+
+    /// Item and node identifier types.
+    item: PhantomData<(K, V, I)>,
+}
+
+impl<K, V, S: Default, const M: usize, I: NodeId> Storage<K, V, S, M, I> {
+    /// Creates a new empty storage with the given runtime Knuth-order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `order` is less than 4.
+    pub fn with_order(order: usize) -> Self {
+        if order < 4 {
+            panic!("B-Tree order must be at least 4")
+        }
+
+        Self {
+            order,
+            ..Self::default()
+        }
+    }
+}
+
+impl<K, V, S, const M: usize, I: NodeId> Storage<K, V, S, M, I> {
+    /// Creates a new empty storage using the given slab to store the nodes.
+    #[inline]
+    pub fn from_slab(slab: S) -> Self {
+        Self {
+            slab,
+            root: None,
+            len: 0,
+            order: M,
+            item: PhantomData,
+        }
+    }
+
+    /// Returns the Knuth-order of the nodes.
+    #[inline]
+    pub fn order(&self) -> usize {
+        self.order
+    }
+}
+
+impl<K, V, S: Default, const M: usize, I: NodeId> Default for Storage<K, V, S, M, I> {
+    fn default() -> Self {
+        Self::from_slab(S::default())
+    }
+}
+
+impl<K, V, S: Clone, const M: usize, I> Clone for Storage<K, V, S, M, I> {
+    fn clone(&self) -> Self {
+        Self {
+            slab: self.slab.clone(),
+            root: self.root,
+            len: self.len,
+            order: self.order,
+            item: PhantomData,
+        }
+    }
+}
+
+impl<K, V, S: NodeSlab<K, V, M, I>, const M: usize, I: NodeId> btree::Storage
+    for Storage<K, V, S, M, I>
+{
+    type ItemRef<'r>
+        = BindingRef<'r, K, V>
+    where
+        S: 'r,
+        K: 'r,
+        V: 'r;
+    type LeafRef<'r>
+        = &'r Leaf<K, V, M, I>
+    where
+        S: 'r,
+        K: 'r,
+        V: 'r;
+    type InternalRef<'r>
+        = &'r Internal<K, V, M, I>
+    where
+        S: 'r,
+        K: 'r,
+        V: 'r;
+
+    fn root(&self) -> Option<usize> {
+        self.root
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn node(&self, id: usize) -> Option<btree::node::Ref<'_, Self>> {
+        self.slab.get(id).map(|node| match node {
+            Node::Internal(node) => btree::node::Ref::internal(node),
+            Node::Leaf(node) => btree::node::Ref::leaf(node),
+        })
+    }
+}
+
+unsafe impl<K, V, S: NodeSlabMut<K, V, M, I>, const M: usize, I: NodeId> btree::StorageMut
+    for Storage<K, V, S, M, I>
+{
+    type Item = Binding<K, V>;
+    type LeafNode = Leaf<K, V, M, I>;
+    type InternalNode = Internal<K, V, M, I>;
+
+    type ItemMut<'r>
+        = BindingMut<'r, K, V>
+    where
+        S: 'r,
+        K: 'r,
+        V: 'r;
+    type LeafMut<'r>
+        = &'r mut Leaf<K, V, M, I>
+    where
+        S: 'r,
+        K: 'r,
+        V: 'r;
+    type InternalMut<'r>
+        = &'r mut Internal<K, V, M, I>
+    where
+        S: 'r,
+        K: 'r,
+        V: 'r;
+
+    fn set_root(&mut self, root: Option<usize>) {
+        self.root = root
+    }
+
+    fn set_len(&mut self, new_len: usize) {
+        self.len = new_len
+    }
+
+    fn allocate_node(&mut self, node: Buffer<Self>) -> usize {
+        let node = match node {
+            Buffer::Internal(mut node) => {
+                node.order = self.order;
+                Node::Internal(node)
+            }
+            Buffer::Leaf(mut node) => {
+                node.order = self.order;
+                Node::Leaf(node)
+            }
+        };
+
+        self.slab.insert(node)
+    }
+
+    fn release_node(&mut self, id: usize) -> Buffer<Self> {
+        match self.slab.remove(id).unwrap() {
+            Node::Internal(node) => Buffer::Internal(node),
+            Node::Leaf(node) => Buffer::Leaf(node),
+        }
+    }
+
+    fn empty(&self) -> Self
+    where
+        Self: Default,
+    {
+        Self {
+            order: self.order,
+            ..Self::default()
+        }
+    }
+
+    fn node_mut(&mut self, id: usize) -> Option<NodeMut<'_, Self>> {
+        self.slab.get_mut(id).map(|node| match node {
+            Node::Internal(node) => NodeMut::internal(node),
+            Node::Leaf(node) => NodeMut::leaf(node),
+        })
+    }
+
+    fn remaining_node_capacity(&self) -> Option<usize> {
+        self.slab.vacant_slots()
+    }
+}
+
+impl<K, V, S: NodeSlabMut<K, V, M, I>, const M: usize, I: NodeId>
+    btree::node::buffer::Leaf<Storage<K, V, S, M, I>> for Leaf<K, V, M, I>
+{
+    fn parent(&self) -> Option<usize> {
+        self.parent()
+    }
+
+    fn set_parent(&mut self, parent: Option<usize>) {
+        self.set_parent(parent)
+    }
+
+    fn item_count(&self) -> usize {
+        self.keys.len()
+    }
+
+    fn max_capacity(&self) -> usize {
+        self.order
+    }
+
+    fn push_right(&mut self, binding: Binding<K, V>) {
+        self.push_right(binding)
+    }
+
+    fn forget(self) {
+        core::mem::forget(self.keys);
+        core::mem::forget(self.values)
+    }
+}
+
+impl<K, V, S: NodeSlabMut<K, V, M, I>, const M: usize, I: NodeId>
+    btree::node::buffer::Internal<Storage<K, V, S, M, I>> for Internal<K, V, M, I>
+{
+    fn parent(&self) -> Option<usize> {
+        self.parent()
+    }
+
+    fn set_parent(&mut self, parent: Option<usize>) {
+        self.set_parent(parent)
+    }
+
+    fn item_count(&self) -> usize {
+        self.keys.len()
+    }
+
+    fn child_id(&self, index: usize) -> Option<usize> {
+        self.child_id(index)
+    }
+
+    fn max_capacity(&self) -> usize {
+        self.order
+    }
+
+    fn set_first_child_id(&mut self, id: usize) {
+        self.set_first_child_id(id)
+    }
+
+    fn push_right(&mut self, binding: Binding<K, V>, child: usize) {
+        self.push_right(binding, child)
+    }
+
+    fn forget(self) {
+        core::mem::forget(self.keys);
+        core::mem::forget(self.values)
+    }
+}
+
+macro_rules! leaf_ref {
+    ($($ty:ty),*) => {
+        $(
+            impl<'a, K, V, S: 'a + NodeSlab<K, V, M, I>, const M: usize, I: NodeId>
+                btree::node::ItemAccess<Storage<K, V, S, M, I>> for $ty
+            {
+                fn item_count(&self) -> usize {
+                    self.keys.len()
+                }
+
+                fn borrow_item(&self, offset: Position) -> Option<BindingRef<'_, K, V>> {
+                    self.item(offset)
+                }
+
+                fn raw_keys(&self) -> Option<btree::node::RawKeys<'_>> {
+                    crate::util::raw_keys(&self.keys)
+                }
+            }
+
+            impl<'a, K, V, S: 'a + NodeSlab<K, V, M, I>, const M: usize, I: NodeId>
+                btree::node::LeafRef<Storage<K, V, S, M, I>> for $ty
+            {
+                fn parent(&self) -> Option<usize> {
+                    Leaf::parent(self)
+                }
+
+                fn max_capacity(&self) -> usize {
+                    self.order
+                }
+
+                fn min_capacity(&self) -> usize {
+                    self.order / 2 - 1
+                }
+            }
+        )*
+    };
+}
+
+leaf_ref!(&'a Leaf<K, V, M, I>, &'a mut Leaf<K, V, M, I>);
+
+impl<'a, K, V, S: 'a + NodeSlab<K, V, M, I>, const M: usize, I: NodeId>
+    btree::node::LeafConst<'a, Storage<K, V, S, M, I>> for &'a Leaf<K, V, M, I>
+{
+    fn item(&self, offset: Position) -> Option<BindingRef<'a, K, V>> {
+        Leaf::item(self, offset)
+    }
+}
+
+impl<'r, K, V, S: 'r + NodeSlabMut<K, V, M, I>, const M: usize, I: NodeId>
+    btree::node::LeafMut<'r, Storage<K, V, S, M, I>> for &'r mut Leaf<K, V, M, I>
+{
+    fn set_parent(&mut self, parent: Option<usize>) {
+        Leaf::set_parent(self, parent)
+    }
+
+    fn item_mut(&mut self, offset: Position) -> Option<BindingMut<'_, K, V>> {
+        Leaf::item_mut(self, offset)
+    }
+
+    fn into_item_mut(self, offset: Position) -> Option<BindingMut<'r, K, V>> {
+        Leaf::item_mut(self, offset)
+    }
+
+    fn insert(&mut self, offset: Position, binding: Binding<K, V>) {
+        let (key, value) = binding.into_pair();
+        self.keys.insert(offset.unwrap(), key);
+        self.values.insert(offset.unwrap(), value)
+    }
+
+    fn remove(&mut self, offset: Position) -> Binding<K, V> {
+        let key = self.keys.remove(offset.unwrap());
+        let value = self.values.remove(offset.unwrap());
+        Binding::new(key, value)
+    }
+
+    fn append(&mut self, separator: Binding<K, V>, mut other: Leaf<K, V, M, I>) -> Position {
+        let offset = self.keys.len().into();
+        self.push_right(separator);
+        self.keys.append(&mut other.keys);
+        self.values.append(&mut other.values);
+        offset
+    }
+
+    fn drain_from(&mut self, offset: Position) -> Leaf<K, V, M, I> {
+        let mut node = Leaf {
+            order: self.order,
+            ..Leaf::default()
+        };
+        node.keys.extend(self.keys.drain(offset.unwrap()..));
+        node.values.extend(self.values.drain(offset.unwrap()..));
+        node
+    }
+}
+
+macro_rules! internal_ref {
+    ($($ty:ty),*) => {
+        $(
+            impl<'a, K, V, S: 'a + NodeSlab<K, V, M, I>, const M: usize, I: NodeId>
+                btree::node::ItemAccess<Storage<K, V, S, M, I>> for $ty
+            {
+                fn item_count(&self) -> usize {
+                    self.keys.len()
+                }
+
+                fn borrow_item(&self, offset: Position) -> Option<BindingRef<'_, K, V>> {
+                    self.item(offset)
+                }
+
+                fn raw_keys(&self) -> Option<btree::node::RawKeys<'_>> {
+                    crate::util::raw_keys(&self.keys)
+                }
+            }
+
+            impl<'a, K, V, S: 'a + NodeSlab<K, V, M, I>, const M: usize, I: NodeId>
+                btree::node::InternalRef<Storage<K, V, S, M, I>> for $ty
+            {
+                fn parent(&self) -> Option<usize> {
+                    Internal::parent(self)
+                }
+
+                fn child_id(&self, index: usize) -> Option<usize> {
+                    Internal::child_id(self, index)
+                }
+
+                fn max_capacity(&self) -> usize {
+                    self.order
+                }
+            }
+        )*
+    };
+}
+
+internal_ref!(&'a Internal<K, V, M, I>, &'a mut Internal<K, V, M, I>);
+
+impl<'a, K, V, S: 'a + NodeSlab<K, V, M, I>, const M: usize, I: NodeId>
+    btree::node::InternalConst<'a, Storage<K, V, S, M, I>> for &'a Internal<K, V, M, I>
+{
+    fn item(&self, offset: Position) -> Option<BindingRef<'a, K, V>> {
+        Internal::item(self, offset)
+    }
+}
+
+impl<'r, K, V, S: 'r + NodeSlabMut<K, V, M, I>, const M: usize, I: NodeId>
+    btree::node::InternalMut<'r, Storage<K, V, S, M, I>> for &'r mut Internal<K, V, M, I>
+{
+    fn set_parent(&mut self, parent: Option<usize>) {
+        Internal::set_parent(self, parent)
+    }
+
+    fn set_first_child_id(&mut self, id: usize) {
+        Internal::set_first_child_id(self, id)
+    }
+
+    fn into_item_mut(self, offset: Position) -> Option<BindingMut<'r, K, V>> {
+        Internal::item_mut(self, offset)
+    }
+
+    fn insert(&mut self, offset: Position, binding: Binding<K, V>, right_child_id: usize) {
+        let (key, value) = binding.into_pair();
+        self.keys.insert(offset.unwrap(), key);
+        self.values.insert(offset.unwrap(), value);
+        self.children
+            .insert(offset.unwrap() + 1, I::from_key(right_child_id))
+    }
+
+    fn remove(&mut self, offset: Position) -> (Binding<K, V>, usize) {
+        let key = self.keys.remove(offset.unwrap());
+        let value = self.values.remove(offset.unwrap());
+        let child_id = self.children.remove(offset.unwrap() + 1);
+        (Binding::new(key, value), child_id.into_key())
+    }
+
+    fn replace(&mut self, offset: Position, mut binding: Binding<K, V>) -> Binding<K, V> {
+        Internal::item_mut(self, offset).unwrap().swap(&mut binding);
+        binding
+    }
+
+    fn append(&mut self, separator: Binding<K, V>, mut other: Internal<K, V, M, I>) -> Position {
+        let offset = self.keys.len().into();
+        let (key, value) = separator.into_pair();
+        self.keys.push(key);
+        self.values.push(value);
+        self.keys.append(&mut other.keys);
+        self.values.append(&mut other.values);
+        self.children.append(&mut other.children);
+        offset
+    }
+
+    fn drain_from(&mut self, offset: Position) -> Internal<K, V, M, I> {
+        let mut node = Internal {
+            order: self.order,
+            ..Internal::default()
+        };
+        node.keys.extend(self.keys.drain(offset.unwrap()..));
+        node.values.extend(self.values.drain(offset.unwrap()..));
+        node.children.push(I::NONE);
+        node.children
+            .extend(self.children.drain((offset.unwrap() + 1)..));
+        node
+    }
+}
+
+impl<'a, K, V, S: NodeSlabMut<K, V, M, I>, const M: usize, I: NodeId>
+    btree::node::item::Mut<Storage<K, V, S, M, I>> for BindingMut<'a, K, V>
+{
+    fn swap(&mut self, binding: &mut Binding<K, V>) {
+        BindingMut::swap(self, binding)
+    }
+}
+
+unsafe impl<'a, K, V, S: NodeSlabMut<K, V, M, I>, const M: usize, I: NodeId>
+    btree::node::item::Read<Storage<K, V, S, M, I>> for BindingRef<'a, K, V>
+{
+    unsafe fn read(&self) -> Binding<K, V> {
+        Binding::new(core::ptr::read(self.key), core::ptr::read(self.value))
+    }
+}
+
+unsafe impl<'a, K, V, S: NodeSlabMut<K, V, M, I>, const M: usize, I: NodeId>
+    btree::node::item::Read<Storage<K, V, S, M, I>> for BindingMut<'a, K, V>
+{
+    unsafe fn read(&self) -> Binding<K, V> {
+        Binding::new(core::ptr::read(self.key), core::ptr::read(self.value))
+    }
+}
+
+unsafe impl<'a, K, V, S: NodeSlabMut<K, V, M, I>, const M: usize, I: NodeId>
+    btree::node::item::Write<Storage<K, V, S, M, I>> for BindingMut<'a, K, V>
+{
+    unsafe fn write(&mut self, binding: Binding<K, V>) {
+        let (key, value) = binding.into_pair();
+        core::ptr::write(self.key, key);
+        core::ptr::write(self.value, value)
+    }
+}
+
+impl<K, V, S: NodeSlab<K, V, M, I>, const M: usize, I: NodeId> crate::map::MapStorage
+    for Storage<K, V, S, M, I>
+{
+    type KeyRef<'a>
+        = &'a K
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+    type ValueRef<'a>
+        = &'a V
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    fn split_ref<'a>(binding: BindingRef<'a, K, V>) -> (&'a K, &'a V)
+    where
+        Self: 'a,
+    {
+        binding.as_pair()
+    }
+}
+
+impl<K, V, S: NodeSlab<K, V, M, I>, const M: usize, I: NodeId> crate::map::MapStorageRef
+    for Storage<K, V, S, M, I>
+{
+    type Target = V;
+
+    fn deref_value<'a>(value: &'a V) -> &'a V
+    where
+        Self: 'a,
+    {
+        value
+    }
+}
+
+impl<K, V, S: NodeSlabMut<K, V, M, I>, const M: usize, I: NodeId> crate::map::MapStorageMut
+    for Storage<K, V, S, M, I>
+{
+    type Key = K;
+    type Value = V;
+    type ValueMut<'a>
+        = &'a mut V
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    fn split(binding: Binding<K, V>) -> (K, V) {
+        binding.into_pair()
+    }
+
+    fn split_mut<'a>(binding: BindingMut<'a, K, V>) -> (&'a K, &'a mut V)
+    where
+        Self: 'a,
+    {
+        binding.into()
+    }
+}
+
+impl<K, V, S: NodeSlabMut<K, V, M, I>, const M: usize, I: NodeId> btree::Insert<Inserted<K, V>>
+    for Storage<K, V, S, M, I>
+{
+    fn allocate_item(&mut self, Inserted(key, value): Inserted<K, V>) -> Binding<K, V> {
+        Binding::new(key, value)
+    }
+}
+
+impl<K, V, S: NodeSlabMut<K, V, M, I>, const M: usize, I: NodeId>
+    btree::node::item::Replace<Inserted<K, V>> for Storage<K, V, S, M, I>
+{
+    type Output = V;
+
+    fn replace(binding: &mut BindingMut<'_, K, V>, Inserted(_, value): Inserted<K, V>) -> V {
+        binding.replace_value(value)
+    }
+}
+
+impl<K, V, S: NodeSlabMut<K, V, M, I>, const M: usize, I: NodeId> btree::node::item::Replace<V>
+    for Storage<K, V, S, M, I>
+{
+    type Output = V;
+
+    fn replace(binding: &mut BindingMut<'_, K, V>, value: V) -> V {
+        binding.replace_value(value)
+    }
+}
+
+impl<Q: ?Sized, K, V, S: NodeSlab<K, V, M, I>, const M: usize, I: NodeId> KeyPartialOrd<Q>
+    for Storage<K, V, S, M, I>
+where
+    Q: PartialOrd,
+    K: Borrow<Q>,
+{
+    fn key_partial_cmp<'r>(binding: &BindingRef<'r, K, V>, other: &Q) -> Option<Ordering>
+    where
+        Self: 'r,
+    {
+        binding.key.borrow().partial_cmp(other)
+    }
+
+    fn raw_key(key: &Q) -> Option<btree::node::RawKey> {
+        crate::util::raw_key(key)
+    }
+}
+
+impl<K, V, S: NodeSlab<K, V, M, I>, const M: usize, I: NodeId> KeyPartialOrd<Inserted<K, V>>
+    for Storage<K, V, S, M, I>
+where
+    K: PartialOrd,
+{
+    fn key_partial_cmp<'r>(
+        binding: &BindingRef<'r, K, V>,
+        other: &Inserted<K, V>,
+    ) -> Option<Ordering>
+    where
+        Self: 'r,
+    {
+        binding.key.partial_cmp(&other.0)
+    }
+}
+
+impl<K, V, S: NodeSlab<K, V, M, I>, const M: usize, I: NodeId> KeyOrd for Storage<K, V, S, M, I>
+where
+    K: Ord,
+{
+    fn key_cmp<'r, 's>(binding: &BindingRef<'r, K, V>, other: &BindingRef<'s, K, V>) -> Ordering
+    where
+        Self: 'r + 's,
+    {
+        binding.key.cmp(other.key)
+    }
+}
+
+impl<K1, K2, V1, V2, S1, S2, const M1: usize, const M2: usize, I1: NodeId, I2: NodeId>
+    ItemPartialOrd<Storage<K2, V2, S2, M2, I2>> for Storage<K1, V1, S1, M1, I1>
+where
+    S1: NodeSlab<K1, V1, M1, I1>,
+    S2: NodeSlab<K2, V2, M2, I2>,
+    K1: PartialOrd<K2>,
+    V1: PartialOrd<V2>,
+{
+    fn item_partial_cmp<'r, 's>(
+        binding: &BindingRef<'r, K1, V1>,
+        other: &BindingRef<'s, K2, V2>,
+    ) -> Option<Ordering>
+    where
+        Self: 'r,
+        Storage<K2, V2, S2, M2, I2>: 's,
+    {
+        match binding.key.partial_cmp(other.key) {
+            Some(Ordering::Equal) => binding.value.partial_cmp(other.value),
+            o => o,
+        }
+    }
+}
+
+impl<K, V, S: NodeSlab<K, V, M, I>, const M: usize, I: NodeId> ItemOrd for Storage<K, V, S, M, I>
+where
+    K: Ord,
+    V: Ord,
+{
+    fn item_cmp<'r, 's>(binding: &BindingRef<'r, K, V>, other: &BindingRef<'s, K, V>) -> Ordering
+    where
+        Self: 'r + 's,
+    {
+        match binding.key.cmp(other.key) {
+            Ordering::Equal => binding.value.cmp(other.value),
+            o => o,
+        }
+    }
+}
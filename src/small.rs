@@ -0,0 +1,350 @@
+use crate::{
+    map::{self, Binding},
+    slab::{Node, NodeSlab, NodeSlabMut, Storage, DEFAULT_ORDER},
+    vec_storage::VecSlab,
+};
+use core::{
+    borrow::Borrow,
+    iter::{FromIterator, FusedIterator},
+};
+use smallvec::SmallVec;
+
+/// Default number of bindings stored inline.
+pub const DEFAULT_INLINE_CAPACITY: usize = 8;
+
+/// Tree storage used once a small map outgrows its inline capacity.
+pub type TreeStorage<K, V, S> = Storage<Binding<K, V>, S>;
+
+/// Tree map used once a small map outgrows its inline capacity.
+pub type TreeMap<K, V, S> = crate::Map<TreeStorage<K, V, S>>;
+
+/// Map representation.
+enum Repr<K, V, const N: usize, S> {
+    /// Bindings sorted by key, stored inline.
+    Inline(SmallVec<[Binding<K, V>; N]>),
+
+    /// B-Tree.
+    Tree(TreeMap<K, V, S>),
+}
+
+/// Small map.
+///
+/// Stores up to `N` bindings inline, in a sorted array held by the map itself,
+/// without allocating any node.
+/// When a binding is inserted past this threshold, every binding is moved
+/// into a slab storage B-Tree using the `S` slab,
+/// and the map behaves like a regular [`Map`](crate::Map) from then on.
+/// Use [`shrink_to_fit`](SmallMap::shrink_to_fit) to move the bindings back
+/// inline once the map is small again.
+///
+/// This avoids the overhead of tree nodes for maps that usually hold a few entries.
+///
+/// # Example
+///
+/// ```
+/// use generic_btree::small::SmallMap;
+///
+/// let mut map: SmallMap<usize, usize> = SmallMap::new();
+/// for i in 0..8 {
+///     map.insert(i, i);
+/// }
+///
+/// assert!(map.is_inline());
+///
+/// map.insert(8, 8);
+/// assert!(!map.is_inline());
+/// assert_eq!(map.get(&8), Some(&8));
+/// ```
+pub struct SmallMap<
+    K,
+    V,
+    const N: usize = DEFAULT_INLINE_CAPACITY,
+    S = VecSlab<Node<Binding<K, V>>>,
+> {
+    repr: Repr<K, V, N, S>,
+}
+
+impl<K, V, const N: usize, S> SmallMap<K, V, N, S> {
+    /// Creates a new empty map.
+    ///
+    /// No memory is allocated until more than `N` bindings are inserted.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            repr: Repr::Inline(SmallVec::new()),
+        }
+    }
+
+    /// Checks if the bindings are stored inline.
+    #[inline]
+    pub fn is_inline(&self) -> bool {
+        matches!(self.repr, Repr::Inline(_))
+    }
+
+    /// Removes every binding of the map, moving back to the inline representation.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.repr = Repr::Inline(SmallVec::new())
+    }
+}
+
+impl<K, V, const N: usize, S: NodeSlab<Binding<K, V>, DEFAULT_ORDER, usize>> SmallMap<K, V, N, S> {
+    /// Returns the number of bindings in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        match &self.repr {
+            Repr::Inline(bindings) => bindings.len(),
+            Repr::Tree(map) => map.len(),
+        }
+    }
+
+    /// Checks if the map is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over the bindings of the map, in key order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V, S> {
+        match &self.repr {
+            Repr::Inline(bindings) => Iter::Inline(bindings.iter()),
+            Repr::Tree(map) => Iter::Tree(map.iter()),
+        }
+    }
+
+    /// Returns an iterator over the keys of the map, in order.
+    #[inline]
+    pub fn keys(&self) -> impl '_ + Iterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Returns an iterator over the values of the map, in key order.
+    #[inline]
+    pub fn values(&self) -> impl '_ + Iterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// Returns the first binding of the map, if any.
+    #[inline]
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        match &self.repr {
+            Repr::Inline(bindings) => bindings.first().map(Binding::as_pair),
+            Repr::Tree(map) => map.first_key_value(),
+        }
+    }
+
+    /// Returns the last binding of the map, if any.
+    #[inline]
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        match &self.repr {
+            Repr::Inline(bindings) => bindings.last().map(Binding::as_pair),
+            Repr::Tree(map) => map.last_key_value(),
+        }
+    }
+
+    /// Returns a reference to the underlying tree map,
+    /// or `None` if the bindings are stored inline.
+    #[inline]
+    pub fn tree(&self) -> Option<&TreeMap<K, V, S>> {
+        match &self.repr {
+            Repr::Inline(_) => None,
+            Repr::Tree(map) => Some(map),
+        }
+    }
+}
+
+impl<K: Ord, V, const N: usize, S: NodeSlab<Binding<K, V>, DEFAULT_ORDER, usize>>
+    SmallMap<K, V, N, S>
+{
+    /// Returns the key-value pair corresponding to the supplied key.
+    #[inline]
+    pub fn get_key_value<Q: ?Sized + Ord>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        match &self.repr {
+            Repr::Inline(bindings) => bindings
+                .binary_search_by(|b| b.key.borrow().cmp(key))
+                .ok()
+                .map(|i| bindings[i].as_pair()),
+            Repr::Tree(map) => map.get_key_value(key),
+        }
+    }
+
+    /// Returns a reference to the value corresponding to the key.
+    #[inline]
+    pub fn get<Q: ?Sized + Ord>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.get_key_value(key).map(|(_, value)| value)
+    }
+
+    /// Returns `true` if the map contains a value for the specified key.
+    #[inline]
+    pub fn contains_key<Q: ?Sized + Ord>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.get_key_value(key).is_some()
+    }
+}
+
+impl<K: Ord, V, const N: usize, S: NodeSlabMut<Binding<K, V>, DEFAULT_ORDER, usize> + Default>
+    SmallMap<K, V, N, S>
+{
+    /// Returns a mutable reference to the value corresponding to the key.
+    #[inline]
+    pub fn get_mut<Q: ?Sized + Ord>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        match &mut self.repr {
+            Repr::Inline(bindings) => {
+                match bindings.binary_search_by(|b| b.key.borrow().cmp(key)) {
+                    Ok(i) => Some(&mut bindings[i].value),
+                    Err(_) => None,
+                }
+            }
+            Repr::Tree(map) => map.get_mut(key),
+        }
+    }
+
+    /// Insert a key-value pair in the map.
+    ///
+    /// If the map already contains the key, the value is updated and the old value is returned.
+    /// If the map is inline and full, every binding is moved to a B-Tree first.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Repr::Inline(bindings) = &mut self.repr {
+            match bindings.binary_search_by(|b| b.key.cmp(&key)) {
+                Ok(i) => return Some(bindings[i].replace_value(value)),
+                Err(i) if bindings.len() < N => {
+                    bindings.insert(i, Binding::new(key, value));
+                    return None;
+                }
+                Err(_) => {
+                    let mut map = TreeMap::from_btree(TreeStorage::default());
+                    for binding in bindings.drain(..) {
+                        let (key, value) = binding.into_pair();
+                        map.insert(key, value);
+                    }
+
+                    self.repr = Repr::Tree(map)
+                }
+            }
+        }
+
+        match &mut self.repr {
+            Repr::Tree(map) => map.insert(key, value),
+            Repr::Inline(_) => unreachable!(),
+        }
+    }
+
+    /// Removes a key from the map, returning the value at the key if the key was previously in the map.
+    pub fn remove<Q: ?Sized + Ord>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        match &mut self.repr {
+            Repr::Inline(bindings) => {
+                match bindings.binary_search_by(|b| b.key.borrow().cmp(key)) {
+                    Ok(i) => Some(bindings.remove(i).into_value()),
+                    Err(_) => None,
+                }
+            }
+            Repr::Tree(map) => map.remove(key),
+        }
+    }
+
+    /// Moves the bindings back inline if the map holds at most `N` bindings,
+    /// releasing the tree nodes.
+    pub fn shrink_to_fit(&mut self) {
+        if let Repr::Tree(map) = &self.repr {
+            if map.len() <= N {
+                let mut bindings = SmallVec::new();
+                if let Repr::Tree(mut map) =
+                    core::mem::replace(&mut self.repr, Repr::Inline(SmallVec::new()))
+                {
+                    while let Some((key, value)) = map.pop_first() {
+                        bindings.push(Binding::new(key, value))
+                    }
+                }
+
+                self.repr = Repr::Inline(bindings)
+            }
+        }
+    }
+}
+
+impl<K, V, const N: usize, S> Default for SmallMap<K, V, N, S> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V, const N: usize, S: NodeSlabMut<Binding<K, V>, DEFAULT_ORDER, usize> + Default>
+    FromIterator<(K, V)> for SmallMap<K, V, N, S>
+{
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Ord, V, const N: usize, S: NodeSlabMut<Binding<K, V>, DEFAULT_ORDER, usize> + Default>
+    Extend<(K, V)> for SmallMap<K, V, N, S>
+{
+    #[inline]
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<'a, K, V, const N: usize, S: NodeSlab<Binding<K, V>, DEFAULT_ORDER, usize>> IntoIterator
+    for &'a SmallMap<K, V, N, S>
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V, S>;
+
+    #[inline]
+    fn into_iter(self) -> Iter<'a, K, V, S> {
+        self.iter()
+    }
+}
+
+/// Iterator over the bindings of a [`SmallMap`].
+pub enum Iter<'a, K, V, S: NodeSlab<Binding<K, V>, DEFAULT_ORDER, usize>> {
+    Inline(core::slice::Iter<'a, Binding<K, V>>),
+    Tree(map::Iter<'a, TreeStorage<K, V, S>>),
+}
+
+impl<'a, K, V, S: NodeSlab<Binding<K, V>, DEFAULT_ORDER, usize>> Iterator for Iter<'a, K, V, S> {
+    type Item = (&'a K, &'a V);
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::Inline(inner) => inner.size_hint(),
+            Self::Tree(inner) => inner.size_hint(),
+        }
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Inline(inner) => inner.next().map(Binding::as_pair),
+            Self::Tree(inner) => inner.next(),
+        }
+    }
+}
+
+impl<'a, K, V, S: NodeSlab<Binding<K, V>, DEFAULT_ORDER, usize>> FusedIterator
+    for Iter<'a, K, V, S>
+{
+}
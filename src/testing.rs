@@ -0,0 +1,740 @@
+//! Property-testing utilities.
+//!
+//! This module generates random sequences of map operations
+//! (insertions, removals, updates and range queries),
+//! applies them to a [`Map`] while checking the tree invariants after each step,
+//! and shrinks failing sequences down to a minimal reproduction.
+//! It allows the authors of custom storages to test their implementation
+//! without writing their own generators.
+//!
+//! With the `arbitrary` feature, [`Op`] implements
+//! [`arbitrary::Arbitrary`](https://docs.rs/arbitrary), so that fuzzers can drive
+//! sequences of operations.
+//! With the `proptest` feature, [`Op`] implements
+//! [`proptest::arbitrary::Arbitrary`](https://docs.rs/proptest),
+//! and [`op_strategy`] and [`ops_strategy`] build strategies
+//! for operations and sequences of operations, shrunk by `proptest`.
+//!
+//! Without those dependencies, operations can also be generated from raw bytes
+//! (see [`Bytes`] and [`Generate`]) and shrunk with [`minimize`].
+//!
+//! # Example
+//!
+//! ```
+//! use generic_btree::{
+//!     slab::Map,
+//!     testing::{check_ops, generate_ops, minimize, Op},
+//! };
+//!
+//! let data: Vec<u8> = (0..4096u32).map(|i| (i.wrapping_mul(2654435761) >> 24) as u8).collect();
+//! let ops: Vec<Op<u8, u8>> = generate_ops(&data);
+//!
+//! let mut map = Map::new();
+//! check_ops(&mut map, &ops).expect("invariant violated");
+//!
+//! // Finds the smallest sequence inserting the key `42`.
+//! let inserts_42 = |ops: &[Op<u8, u8>]| ops.iter().any(|op| matches!(op, Op::Insert(42, _)));
+//! if inserts_42(&ops) {
+//!     assert_eq!(minimize(ops, inserts_42), [Op::Insert(42, 0)]);
+//! }
+//! ```
+use crate::{
+    btree::{
+        node::item::{Read, Replace, Write},
+        Insert, KeyOrd, KeyPartialOrd,
+    },
+    map::{Entry, Inserted, MapStorageMut, MapStorageRef},
+    Map, ValidationError,
+};
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{borrow::Borrow, convert::TryInto, fmt};
+
+/// Raw bytes from which values are generated.
+///
+/// Values are generated by consuming the bytes from the front,
+/// until none are left.
+#[derive(Clone, Copy, Debug)]
+pub struct Bytes<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Bytes<'a> {
+    /// Creates a new byte source.
+    #[inline]
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Returns the number of remaining bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Checks if all the bytes have been consumed.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Consumes the next `N` bytes, if any.
+    #[inline]
+    pub fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        if self.data.len() < N {
+            return None;
+        }
+
+        let (bytes, rest) = self.data.split_at(N);
+        self.data = rest;
+        bytes.try_into().ok()
+    }
+
+    /// Consumes the next byte, and uses it to choose a number lower than `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is `0`.
+    #[inline]
+    pub fn choose(&mut self, n: u8) -> Option<u8> {
+        self.take::<1>().map(|[b]| b % n)
+    }
+}
+
+/// Value that can be generated from raw bytes, and shrunk.
+pub trait Generate: Sized {
+    /// Generates a value from the given bytes,
+    /// or returns `None` if there are not enough bytes left.
+    fn generate(bytes: &mut Bytes) -> Option<Self>;
+
+    /// Returns simpler variants of this value, simplest first.
+    ///
+    /// The default implementation returns no variant.
+    #[inline]
+    fn shrink(&self) -> Vec<Self> {
+        Vec::new()
+    }
+}
+
+macro_rules! generate_unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl Generate for $ty {
+                #[inline]
+                fn generate(bytes: &mut Bytes) -> Option<Self> {
+                    bytes.take().map(<$ty>::from_le_bytes)
+                }
+
+                fn shrink(&self) -> Vec<Self> {
+                    let mut variants = Vec::new();
+                    for variant in [0, *self / 2, self.saturating_sub(1)] {
+                        if variant < *self && !variants.contains(&variant) {
+                            variants.push(variant)
+                        }
+                    }
+
+                    variants
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! generate_signed {
+    ($($ty:ty),*) => {
+        $(
+            impl Generate for $ty {
+                #[inline]
+                fn generate(bytes: &mut Bytes) -> Option<Self> {
+                    bytes.take().map(<$ty>::from_le_bytes)
+                }
+
+                fn shrink(&self) -> Vec<Self> {
+                    let mut variants = Vec::new();
+                    for variant in [0, *self / 2, *self - self.signum()] {
+                        if variant.unsigned_abs() < self.unsigned_abs() && !variants.contains(&variant) {
+                            variants.push(variant)
+                        }
+                    }
+
+                    variants
+                }
+            }
+        )*
+    };
+}
+
+generate_unsigned!(u8, u16, u32, u64, u128, usize);
+generate_signed!(i8, i16, i32, i64, i128, isize);
+
+impl Generate for bool {
+    #[inline]
+    fn generate(bytes: &mut Bytes) -> Option<Self> {
+        bytes.choose(2).map(|b| b == 1)
+    }
+
+    #[inline]
+    fn shrink(&self) -> Vec<Self> {
+        if *self {
+            alloc::vec![false]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl Generate for () {
+    #[inline]
+    fn generate(_bytes: &mut Bytes) -> Option<Self> {
+        Some(())
+    }
+}
+
+impl<T: Generate> Generate for Option<T> {
+    fn generate(bytes: &mut Bytes) -> Option<Self> {
+        match bytes.choose(2)? {
+            0 => Some(None),
+            _ => T::generate(bytes).map(Some),
+        }
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        match self {
+            Some(value) => core::iter::once(None)
+                .chain(value.shrink().into_iter().map(Some))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Map operation.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Op<K, V> {
+    /// Inserts the given key-value pair, with [`Map::insert`].
+    Insert(K, V),
+
+    /// Removes the given key, with [`Map::remove`].
+    Remove(K),
+
+    /// Updates the value associated to the given key, with [`Map::update`].
+    ///
+    /// The binding is removed if the new value is `None`.
+    Update(K, Option<V>),
+
+    /// Iterates over the items whose keys are between the given bounds, included,
+    /// with [`Storage::range`](crate::Storage::range).
+    ///
+    /// The bounds are swapped if needed.
+    Range(K, K),
+
+    /// Gets the value associated to the given key, with [`Map::get`].
+    Get(K),
+
+    /// Inserts, replaces or removes the value associated to the given key,
+    /// with [`Map::entry`].
+    ///
+    /// The binding is removed if the new value is `None`.
+    Entry(K, Option<V>),
+}
+
+impl<K: Generate + Clone, V: Generate + Clone> Generate for Op<K, V> {
+    fn generate(bytes: &mut Bytes) -> Option<Self> {
+        Some(match bytes.choose(6)? {
+            0 => Self::Insert(K::generate(bytes)?, V::generate(bytes)?),
+            1 => Self::Remove(K::generate(bytes)?),
+            2 => Self::Update(K::generate(bytes)?, Option::<V>::generate(bytes)?),
+            3 => Self::Range(K::generate(bytes)?, K::generate(bytes)?),
+            4 => Self::Get(K::generate(bytes)?),
+            _ => Self::Entry(K::generate(bytes)?, Option::<V>::generate(bytes)?),
+        })
+    }
+
+    fn shrink(&self) -> Vec<Self> {
+        let mut variants = Vec::new();
+        match self {
+            Self::Insert(key, value) => {
+                for k in key.shrink() {
+                    variants.push(Self::Insert(k, value.clone()))
+                }
+
+                for v in value.shrink() {
+                    variants.push(Self::Insert(key.clone(), v))
+                }
+            }
+            Self::Remove(key) => variants.extend(key.shrink().into_iter().map(Self::Remove)),
+            Self::Get(key) => variants.extend(key.shrink().into_iter().map(Self::Get)),
+            Self::Update(key, value) | Self::Entry(key, value) => {
+                let op = match self {
+                    Self::Update(_, _) => Self::Update,
+                    _ => Self::Entry,
+                };
+
+                for k in key.shrink() {
+                    variants.push(op(k, value.clone()))
+                }
+
+                for v in value.shrink() {
+                    variants.push(op(key.clone(), v))
+                }
+            }
+            Self::Range(min, max) => {
+                for k in min.shrink() {
+                    variants.push(Self::Range(k, max.clone()))
+                }
+
+                for k in max.shrink() {
+                    variants.push(Self::Range(min.clone(), k))
+                }
+            }
+        }
+
+        variants
+    }
+}
+
+/// Generates a sequence of operations consuming all the given bytes.
+pub fn generate_ops<K: Generate + Clone, V: Generate + Clone>(data: &[u8]) -> Vec<Op<K, V>> {
+    let mut bytes = Bytes::new(data);
+    let mut ops = Vec::new();
+    while let Some(op) = Op::generate(&mut bytes) {
+        ops.push(op)
+    }
+
+    ops
+}
+
+/// Returns simpler variants of the given sequence of operations, simplest first.
+///
+/// Variants are obtained by removing halves of the sequence,
+/// then single operations, and finally by shrinking each operation.
+pub fn shrink_ops<K: Generate + Clone, V: Generate + Clone>(
+    ops: &[Op<K, V>],
+) -> Vec<Vec<Op<K, V>>> {
+    let mut variants = Vec::new();
+
+    let mut chunk = ops.len() / 2;
+    while chunk > 0 {
+        let mut start = 0;
+        while start < ops.len() {
+            let end = (start + chunk).min(ops.len());
+            variants.push(ops[..start].iter().chain(&ops[end..]).cloned().collect());
+            start = end
+        }
+
+        chunk /= 2;
+    }
+
+    for (i, op) in ops.iter().enumerate() {
+        for variant in op.shrink() {
+            let mut ops = ops.to_vec();
+            ops[i] = variant;
+            variants.push(ops)
+        }
+    }
+
+    variants
+}
+
+/// Shrinks the given failing sequence of operations
+/// until none of its variants fails.
+///
+/// The `fails` function is called on each candidate sequence,
+/// and must return `true` if the sequence still fails.
+pub fn minimize<K, V, F>(mut ops: Vec<Op<K, V>>, mut fails: F) -> Vec<Op<K, V>>
+where
+    K: Generate + Clone,
+    V: Generate + Clone,
+    F: FnMut(&[Op<K, V>]) -> bool,
+{
+    'shrink: loop {
+        for variant in shrink_ops(&ops) {
+            if fails(&variant) {
+                ops = variant;
+                continue 'shrink;
+            }
+        }
+
+        break ops;
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, K: arbitrary::Arbitrary<'a>, V: arbitrary::Arbitrary<'a>> arbitrary::Arbitrary<'a>
+    for Op<K, V>
+{
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(match u.choose_index(6)? {
+            0 => Self::Insert(u.arbitrary()?, u.arbitrary()?),
+            1 => Self::Remove(u.arbitrary()?),
+            2 => Self::Update(u.arbitrary()?, u.arbitrary()?),
+            3 => Self::Range(u.arbitrary()?, u.arbitrary()?),
+            4 => Self::Get(u.arbitrary()?),
+            _ => Self::Entry(u.arbitrary()?, u.arbitrary()?),
+        })
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        let key = K::size_hint(depth);
+        let value = <Option<V>>::size_hint(depth);
+        arbitrary::size_hint::and(
+            (1, Some(1)),
+            arbitrary::size_hint::or_all(&[
+                arbitrary::size_hint::and(key, V::size_hint(depth)),
+                key,
+                arbitrary::size_hint::and(key, value),
+                arbitrary::size_hint::and(key, key),
+            ]),
+        )
+    }
+}
+
+/// Returns a strategy generating operations on keys and values
+/// generated by the given strategies.
+///
+/// Operations are shrunk by shrinking their keys and values.
+#[cfg(feature = "proptest")]
+pub fn op_strategy<K, V>(
+    key: K,
+    value: V,
+) -> impl proptest::strategy::Strategy<Value = Op<K::Value, V::Value>> + Clone
+where
+    K: proptest::strategy::Strategy + Clone,
+    V: proptest::strategy::Strategy + Clone,
+{
+    use proptest::{option, strategy::Strategy};
+
+    proptest::prop_oneof![
+        (key.clone(), value.clone()).prop_map(|(k, v)| Op::Insert(k, v)),
+        key.clone().prop_map(Op::Remove),
+        (key.clone(), option::of(value.clone())).prop_map(|(k, v)| Op::Update(k, v)),
+        (key.clone(), key.clone()).prop_map(|(min, max)| Op::Range(min, max)),
+        key.clone().prop_map(Op::Get),
+        (key, option::of(value)).prop_map(|(k, v)| Op::Entry(k, v)),
+    ]
+}
+
+/// Returns a strategy generating sequences of operations,
+/// whose lengths are in the given range,
+/// on keys and values generated by the given strategies.
+///
+/// Sequences are shrunk by removing operations, then by shrinking each operation.
+///
+/// # Example
+///
+/// ```
+/// use generic_btree::{slab::Map, testing::{check_ops, ops_strategy}};
+/// use proptest::{prelude::any, test_runner::TestRunner};
+///
+/// let mut runner = TestRunner::default();
+/// runner
+///     .run(&ops_strategy(any::<u8>(), any::<u16>(), 0..256), |ops| {
+///         let mut map = Map::new();
+///         check_ops(&mut map, &ops).expect("invariant violated");
+///         Ok(())
+///     })
+///     .unwrap();
+/// ```
+#[cfg(feature = "proptest")]
+pub fn ops_strategy<K, V>(
+    key: K,
+    value: V,
+    len: impl Into<proptest::collection::SizeRange>,
+) -> impl proptest::strategy::Strategy<Value = Vec<Op<K::Value, V::Value>>>
+where
+    K: proptest::strategy::Strategy + Clone,
+    V: proptest::strategy::Strategy + Clone,
+{
+    proptest::collection::vec(op_strategy(key, value), len)
+}
+
+#[cfg(feature = "proptest")]
+impl<K, V> proptest::arbitrary::Arbitrary for Op<K, V>
+where
+    K: proptest::arbitrary::Arbitrary + Clone + 'static,
+    V: proptest::arbitrary::Arbitrary + Clone + 'static,
+{
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: ()) -> Self::Strategy {
+        use proptest::{arbitrary::any, strategy::Strategy};
+        op_strategy(any::<K>().boxed(), any::<V>().boxed()).boxed()
+    }
+}
+
+/// Invariant violation.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Violation {
+    /// The tree is not valid.
+    Invalid(ValidationError),
+
+    /// A range query returned an item outside of the range.
+    OutOfRange,
+
+    /// A range query returned items out of order.
+    Unsorted,
+
+    /// An operation returned a different result than on the reference map.
+    WrongResult,
+
+    /// The map and the reference map have different lengths.
+    ///
+    /// The first parameter is the length of the map,
+    /// then the length of the reference map.
+    WrongLength(usize, usize),
+
+    /// The map and the reference map do not hold the same bindings, in the same order.
+    WrongContent,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Invalid(e) => write!(f, "invalid tree: {}", e),
+            Self::OutOfRange => write!(f, "range returned an item out of the range"),
+            Self::Unsorted => write!(f, "range returned items out of order"),
+            Self::WrongResult => write!(f, "result differs from the reference map"),
+            Self::WrongLength(found, expected) => write!(
+                f,
+                "map has {} bindings but the reference map has {}",
+                found, expected
+            ),
+            Self::WrongContent => write!(f, "bindings differ from the reference map"),
+        }
+    }
+}
+
+/// Invariant violation found by [`check_ops`] or [`check_against_btree_map`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Failure {
+    /// Index of the operation after which the violation was found.
+    pub step: usize,
+
+    /// Violation.
+    pub violation: Violation,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "step {}: {}", self.step, self.violation)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Failure {}
+
+/// Applies the given operation to the map.
+///
+/// Returns the value previously associated to the key
+/// (or the current value for [`Op::Get`]), if any.
+/// Range queries return `None`,
+/// or a violation if they return items out of the range or out of order.
+pub fn apply<S>(map: &mut Map<S>, op: &Op<S::Key, S::Value>) -> Result<Option<S::Value>, Violation>
+where
+    S: MapStorageMut + MapStorageRef<Target = S::Value> + KeyOrd + KeyPartialOrd<S::Key>,
+    S: Insert<Inserted<S::Key, S::Value>> + KeyPartialOrd<Inserted<S::Key, S::Value>>,
+    S: Replace<Inserted<S::Key, S::Value>, Output = S::Value>,
+    S: Replace<S::Value, Output = S::Value>,
+    for<'r> S::ItemMut<'r>: Read<S> + Write<S>,
+    S::Key: Ord + Clone,
+    S::Value: Clone,
+{
+    Ok(match op {
+        Op::Insert(key, value) => map.insert(key.clone(), value.clone()),
+        Op::Remove(key) => map.remove(key),
+        Op::Update(key, value) => map.update(key.clone(), |old| (value.clone(), old)),
+        Op::Range(a, b) => {
+            let (min, max) = if a <= b { (a, b) } else { (b, a) };
+            let mut previous = None;
+            for item in map.btree().range::<S::Key, _>(min..=max) {
+                if !S::key_partial_cmp(&item, min).is_some_and(|o| o.is_ge())
+                    || !S::key_partial_cmp(&item, max).is_some_and(|o| o.is_le())
+                {
+                    return Err(Violation::OutOfRange);
+                }
+
+                if let Some(previous) = &previous {
+                    if S::key_cmp(previous, &item).is_ge() {
+                        return Err(Violation::Unsorted);
+                    }
+                }
+
+                previous = Some(item)
+            }
+
+            None
+        }
+        Op::Get(key) => map.get(key).map(|value| S::deref_value(value).clone()),
+        Op::Entry(key, value) => match (map.entry(key.clone()), value) {
+            (Entry::Occupied(mut entry), Some(value)) => Some(entry.insert(value.clone())),
+            (Entry::Occupied(entry), None) => Some(entry.remove()),
+            (Entry::Vacant(entry), Some(value)) => {
+                entry.insert(value.clone());
+                None
+            }
+            (Entry::Vacant(_), None) => None,
+        },
+    })
+}
+
+/// Applies the given operation to a reference map.
+///
+/// Returns the same value as [`apply`] would on a map holding the same bindings.
+pub fn apply_to_btree_map<K: Ord + Clone, V: Clone>(
+    model: &mut BTreeMap<K, V>,
+    op: &Op<K, V>,
+) -> Option<V> {
+    match op {
+        Op::Insert(key, value) => model.insert(key.clone(), value.clone()),
+        Op::Remove(key) => model.remove(key),
+        Op::Update(key, Some(value)) | Op::Entry(key, Some(value)) => {
+            model.insert(key.clone(), value.clone())
+        }
+        Op::Update(key, None) | Op::Entry(key, None) => model.remove(key),
+        Op::Range(_, _) => None,
+        Op::Get(key) => model.get(key).cloned(),
+    }
+}
+
+/// Applies the given operations to the map,
+/// checking the tree invariants after each of them.
+///
+/// Stops at the first violation found.
+pub fn check_ops<S>(map: &mut Map<S>, ops: &[Op<S::Key, S::Value>]) -> Result<(), Failure>
+where
+    S: MapStorageMut + MapStorageRef<Target = S::Value> + KeyOrd + KeyPartialOrd<S::Key>,
+    S: Insert<Inserted<S::Key, S::Value>> + KeyPartialOrd<Inserted<S::Key, S::Value>>,
+    S: Replace<Inserted<S::Key, S::Value>, Output = S::Value>,
+    S: Replace<S::Value, Output = S::Value>,
+    for<'r> S::ItemMut<'r>: Read<S> + Write<S>,
+    S::Key: Ord + Clone,
+    S::Value: Clone,
+{
+    for (step, op) in ops.iter().enumerate() {
+        apply(map, op)
+            .and_then(|_| map.btree().validate().map_err(Violation::Invalid))
+            .map_err(|violation| Failure { step, violation })?;
+    }
+
+    Ok(())
+}
+
+/// Applies the given operations to both the map and a [`BTreeMap`],
+/// checking that they are observationally equivalent after each of them.
+///
+/// The reference map initially holds the same bindings as the map.
+/// After each operation, this checks that:
+///  - the tree invariants hold, as in [`check_ops`],
+///  - the operation returned the same value on both maps,
+///    and range queries returned the same bindings,
+///  - both maps have the same length,
+///  - iterating over both maps gives the same bindings, in the same order.
+///
+/// Stops at the first violation found, and returns the reference map otherwise.
+///
+/// # Example
+///
+/// ```
+/// use generic_btree::{
+///     slab::Map,
+///     testing::{check_against_btree_map, Op},
+/// };
+///
+/// let mut map: Map<u8, u8> = Map::new();
+/// let ops = [Op::Insert(1, 10), Op::Entry(1, Some(11)), Op::Get(1), Op::Remove(2)];
+/// let model = check_against_btree_map(&mut map, &ops).expect("maps diverged");
+/// assert_eq!(model.get(&1), Some(&11));
+/// ```
+pub fn check_against_btree_map<S>(
+    map: &mut Map<S>,
+    ops: &[Op<S::Key, S::Value>],
+) -> Result<BTreeMap<S::Key, S::Value>, Failure>
+where
+    S: MapStorageMut + MapStorageRef<Target = S::Value> + KeyOrd + KeyPartialOrd<S::Key>,
+    S: Insert<Inserted<S::Key, S::Value>> + KeyPartialOrd<Inserted<S::Key, S::Value>>,
+    S: Replace<Inserted<S::Key, S::Value>, Output = S::Value>,
+    S: Replace<S::Value, Output = S::Value>,
+    for<'r> S::ItemMut<'r>: Read<S> + Write<S>,
+    for<'r> S::KeyRef<'r>: Borrow<S::Key>,
+    S::Key: Ord + Clone,
+    S::Value: Clone + PartialEq,
+{
+    let mut model: BTreeMap<S::Key, S::Value> = map
+        .iter()
+        .map(|(key, value)| (key.borrow().clone(), S::deref_value(value).clone()))
+        .collect();
+
+    for (step, op) in ops.iter().enumerate() {
+        compare(map, &mut model, op).map_err(|violation| Failure { step, violation })?;
+    }
+
+    Ok(model)
+}
+
+/// Applies the given operation to both maps and compares them.
+fn compare<S>(
+    map: &mut Map<S>,
+    model: &mut BTreeMap<S::Key, S::Value>,
+    op: &Op<S::Key, S::Value>,
+) -> Result<(), Violation>
+where
+    S: MapStorageMut + MapStorageRef<Target = S::Value> + KeyOrd + KeyPartialOrd<S::Key>,
+    S: Insert<Inserted<S::Key, S::Value>> + KeyPartialOrd<Inserted<S::Key, S::Value>>,
+    S: Replace<Inserted<S::Key, S::Value>, Output = S::Value>,
+    S: Replace<S::Value, Output = S::Value>,
+    for<'r> S::ItemMut<'r>: Read<S> + Write<S>,
+    S::Key: Ord + Clone,
+    S::Value: Clone + PartialEq,
+{
+    if apply(map, op)? != apply_to_btree_map(model, op) {
+        return Err(Violation::WrongResult);
+    }
+
+    map.btree().validate().map_err(Violation::Invalid)?;
+
+    if let Op::Range(a, b) = op {
+        let (min, max) = if a <= b { (a, b) } else { (b, a) };
+        if !same_bindings::<S, _, _>(
+            map.btree().range::<S::Key, _>(min..=max),
+            model.range(min..=max),
+        ) {
+            return Err(Violation::WrongResult);
+        }
+    }
+
+    if map.len() != model.len() {
+        return Err(Violation::WrongLength(map.len(), model.len()));
+    }
+
+    if !same_bindings::<S, _, _>(map.btree().iter(), model.iter()) {
+        return Err(Violation::WrongContent);
+    }
+
+    Ok(())
+}
+
+/// Checks that the given items hold the expected bindings, in the same order.
+fn same_bindings<'a, 'b, S, K, V>(
+    mut items: impl Iterator<Item = S::ItemRef<'a>>,
+    expected: impl Iterator<Item = (&'b K, &'b V)>,
+) -> bool
+where
+    S: 'a + MapStorageRef<Target = V> + KeyPartialOrd<K>,
+    K: 'b,
+    V: 'a + 'b + PartialEq,
+{
+    for (key, value) in expected {
+        match items.next() {
+            Some(item) => {
+                if !S::key_partial_cmp(&item, key).is_some_and(|o| o.is_eq())
+                    || S::deref_value(S::value_ref(item)) != value
+                {
+                    return false;
+                }
+            }
+            None => return false,
+        }
+    }
+
+    items.next().is_none()
+}
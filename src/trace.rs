@@ -0,0 +1,121 @@
+//! Structured diagnostics of the tree algorithms.
+//!
+//! When the `tracing` feature is enabled, the tree algorithms emit
+//! [`tracing`](https://docs.rs/tracing) events, at the `TRACE` level
+//! and with the `generic_btree::trace` target,
+//! every time they descend into a node, split, merge or rotate nodes,
+//! or step through a rebalance loop.
+//! Each event carries the identifiers and depths of the nodes involved,
+//! as described by [`Event`].
+//!
+//! Events are only built when a subscriber is interested in them:
+//!
+//! ```
+//! use generic_btree::slab::Map;
+//!
+//! // With a subscriber such as `tracing_subscriber::fmt` installed,
+//! // this prints every split of the tree.
+//! let map: Map<usize, usize> = (0..100).map(|i| (i, i)).collect();
+//! ```
+use crate::Storage;
+use core::fmt;
+
+/// Diagnostic event emitted by the tree algorithms.
+///
+/// Depths are counted from the root, at depth `0`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Event {
+    /// A search descended into the node `id`.
+    Descend { id: usize, depth: usize },
+
+    /// The node `id` has been split,
+    /// its upper half being moved to the new node `right`.
+    Split {
+        id: usize,
+        right: usize,
+        depth: usize,
+    },
+
+    /// The node `right` has been merged into its left sibling `left`.
+    Merge {
+        left: usize,
+        right: usize,
+        depth: usize,
+    },
+
+    /// An item has been moved from the node `from` to its sibling `to`,
+    /// through their parent.
+    Rotate {
+        from: usize,
+        to: usize,
+        depth: usize,
+    },
+
+    /// A rebalance loop visited the node `id`.
+    Rebalance { id: usize, depth: usize },
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Descend { id, depth } => write!(f, "descend into n{} (depth {})", id, depth),
+            Self::Split { id, right, depth } => {
+                write!(f, "split n{} into n{} (depth {})", id, right, depth)
+            }
+            Self::Merge { left, right, depth } => {
+                write!(f, "merge n{} into n{} (depth {})", right, left, depth)
+            }
+            Self::Rotate { from, to, depth } => {
+                write!(f, "rotate from n{} to n{} (depth {})", from, to, depth)
+            }
+            Self::Rebalance { id, depth } => write!(f, "rebalance n{} (depth {})", id, depth),
+        }
+    }
+}
+
+impl Event {
+    /// Emits the event as a `tracing` event, at the `TRACE` level.
+    ///
+    /// The event has a `kind` field (`"descend"`, `"split"`, `"merge"`,
+    /// `"rotate"` or `"rebalance"`), one field per node identifier and a `depth` field.
+    pub fn record(&self) {
+        match *self {
+            Self::Descend { id, depth } => {
+                tracing::trace!(kind = "descend", id, depth, "{}", self)
+            }
+            Self::Split { id, right, depth } => {
+                tracing::trace!(kind = "split", id, right, depth, "{}", self)
+            }
+            Self::Merge { left, right, depth } => {
+                tracing::trace!(kind = "merge", left, right, depth, "{}", self)
+            }
+            Self::Rotate { from, to, depth } => {
+                tracing::trace!(kind = "rotate", from, to, depth, "{}", self)
+            }
+            Self::Rebalance { id, depth } => {
+                tracing::trace!(kind = "rebalance", id, depth, "{}", self)
+            }
+        }
+    }
+}
+
+/// Emits the event built from the depth of the node `id`.
+///
+/// The depth is only computed when `TRACE` events of this module are enabled.
+#[inline]
+pub(crate) fn emit<S: Storage>(storage: &S, id: usize, event: impl FnOnce(usize) -> Event) {
+    if tracing::enabled!(tracing::Level::TRACE) {
+        event(depth(storage, id)).record()
+    }
+}
+
+/// Computes the depth of the given node.
+fn depth<S: Storage>(storage: &S, mut id: usize) -> usize {
+    let mut depth = 0;
+    while let Some(parent) = storage.node(id).and_then(|node| node.parent()) {
+        depth += 1;
+        id = parent
+    }
+
+    depth
+}
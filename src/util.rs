@@ -1,19 +1,91 @@
 use crate::btree::{
-    node::{ItemAccess, Offset},
-    KeyPartialOrd, Storage,
+    node::{ItemAccess, Position},
+    KeyPartialOrd,
 };
-use std::cmp::Ordering;
+use core::cmp::Ordering;
+
+#[cfg(feature = "simd")]
+mod simd;
+
+#[cfg(feature = "simd")]
+pub use simd::{raw_key, raw_keys};
+
+/// Returns the given keys as raw keys, if they are primitive integers.
+///
+/// Always `None` unless the `simd` feature is enabled.
+#[cfg(not(feature = "simd"))]
+#[inline]
+pub fn raw_keys<K>(_keys: &[K]) -> Option<crate::btree::node::RawKeys<'_>> {
+    None
+}
+
+/// Returns the given key as a raw key, if it is a primitive integer.
+///
+/// Always `None` unless the `simd` feature is enabled.
+#[cfg(not(feature = "simd"))]
+#[inline]
+pub fn raw_key<Q: ?Sized>(_key: &Q) -> Option<crate::btree::node::RawKey> {
+    None
+}
+
+/// Default value of [`Storage::LINEAR_SEARCH_THRESHOLD`].
+pub const LINEAR_SEARCH_THRESHOLD: usize = 16;
 
 /// Search in `sorted_items` for the item with the nearest key smaller or equal to the given one.
 ///
 /// `sorted_items` is assumed to be sorted.
+/// Nodes with less than [`Storage::LINEAR_SEARCH_THRESHOLD`] items
+/// are scanned linearly instead.
+#[inline]
+pub fn binary_search_min<'r, S, A: ItemAccess<S> + ?Sized, Q: ?Sized>(
+    sorted_items: &'r A,
+    key: &Q,
+) -> Option<(Position, bool)>
+where
+    S: 'r + KeyPartialOrd<Q>,
+{
+    #[cfg(feature = "simd")]
+    if let (Some(keys), Some(key)) = (sorted_items.raw_keys(), S::raw_key(key)) {
+        if let Some(result) = simd::search_min(keys, key) {
+            return result;
+        }
+    }
+
+    if sorted_items.item_count() < S::LINEAR_SEARCH_THRESHOLD {
+        linear_search_min(sorted_items, key)
+    } else {
+        bisect_min(sorted_items, key)
+    }
+}
+
+/// Linear version of [`binary_search_min`].
+#[inline]
+pub fn linear_search_min<'r, S, A: ItemAccess<S> + ?Sized, Q: ?Sized>(
+    sorted_items: &'r A,
+    key: &Q,
+) -> Option<(Position, bool)>
+where
+    S: 'r + KeyPartialOrd<Q>,
+{
+    let mut result = None;
+    for i in 0..sorted_items.item_count() {
+        match S::key_partial_cmp(&sorted_items.borrow_item(i.into()).unwrap(), key) {
+            Some(Ordering::Greater) => break,
+            ordering => result = Some((i.into(), ordering == Some(Ordering::Equal))),
+        }
+    }
+
+    result
+}
+
+/// Binary search version of [`binary_search_min`], regardless of the number of items.
 #[inline]
-pub fn binary_search_min<'r, S: 'r + Storage, A: ItemAccess<S> + ?Sized, Q: ?Sized>(
+pub fn bisect_min<'r, S, A: ItemAccess<S> + ?Sized, Q: ?Sized>(
     sorted_items: &'r A,
     key: &Q,
-) -> Option<(Offset, bool)>
+) -> Option<(Position, bool)>
 where
-    S: KeyPartialOrd<Q>,
+    S: 'r + KeyPartialOrd<Q>,
 {
     if sorted_items.is_empty()
         || S::key_partial_cmp(&sorted_items.borrow_item(0.into()).unwrap(), key)
@@ -22,8 +94,8 @@ where
     {
         None
     } else {
-        let mut i: Offset = 0.into();
-        let mut j: Offset = (sorted_items.item_count() - 1).into();
+        let mut i: Position = 0.into();
+        let mut j: Position = (sorted_items.item_count() - 1).into();
 
         let j_item = sorted_items.borrow_item(j).unwrap();
         if S::key_partial_cmp(&j_item, key)
@@ -0,0 +1,121 @@
+//! SIMD key search, for nodes exposing their keys as primitive integers.
+use crate::btree::node::{Position, RawKey, RawKeys};
+use core::simd::{cmp::SimdPartialOrd, Simd};
+
+/// Number of keys compared at once.
+const LANES: usize = 8;
+
+/// Primitive integer slice, possibly exposed as [`RawKeys`].
+///
+/// Every slice implements this trait, but only slices of primitive integers
+/// return some raw keys.
+trait AsRawKeys {
+    fn as_raw_keys(&self) -> Option<RawKeys<'_>>;
+}
+
+impl<K> AsRawKeys for [K] {
+    #[inline]
+    default fn as_raw_keys(&self) -> Option<RawKeys<'_>> {
+        None
+    }
+}
+
+/// Key, possibly exposed as a [`RawKey`].
+///
+/// Every type implements this trait, but only primitive integers
+/// return some raw key.
+trait AsRawKey {
+    fn as_raw_key(&self) -> Option<RawKey>;
+}
+
+impl<Q: ?Sized> AsRawKey for Q {
+    #[inline]
+    default fn as_raw_key(&self) -> Option<RawKey> {
+        None
+    }
+}
+
+macro_rules! primitives {
+    ($($id:ident: $ty:ty => $search:ident),*) => {
+        $(
+            impl AsRawKeys for [$ty] {
+                #[inline]
+                fn as_raw_keys(&self) -> Option<RawKeys<'_>> {
+                    Some(RawKeys::$id(self))
+                }
+            }
+
+            impl AsRawKey for $ty {
+                #[inline]
+                fn as_raw_key(&self) -> Option<RawKey> {
+                    Some(RawKey::$id(*self))
+                }
+            }
+
+            /// Counts the keys lower or equal to `key`, `LANES` at a time.
+            #[inline]
+            fn $search(keys: &[$ty], key: $ty) -> Option<(Position, bool)> {
+                let splat = Simd::<$ty, LANES>::splat(key);
+                let mut count = 0;
+
+                let mut chunks = keys.chunks_exact(LANES);
+                let mut remainder = true;
+                for chunk in &mut chunks {
+                    let le = Simd::<$ty, LANES>::from_slice(chunk).simd_le(splat);
+                    let n = le.to_bitmask().count_ones() as usize;
+                    count += n;
+                    if n < LANES {
+                        remainder = false;
+                        break;
+                    }
+                }
+
+                if remainder {
+                    count += chunks.remainder().iter().take_while(|k| **k <= key).count();
+                }
+
+                if count == 0 {
+                    None
+                } else {
+                    Some(((count - 1).into(), keys[count - 1] == key))
+                }
+            }
+        )*
+
+        /// Searches `keys` for the nearest key smaller or equal to `key`.
+        ///
+        /// Returns `None` if the key types do not match.
+        #[inline]
+        pub fn search_min(keys: RawKeys, key: RawKey) -> Option<Option<(Position, bool)>> {
+            match (keys, key) {
+                $((RawKeys::$id(keys), RawKey::$id(key)) => Some($search(keys, key)),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+primitives! {
+    U8: u8 => search_u8,
+    U16: u16 => search_u16,
+    U32: u32 => search_u32,
+    U64: u64 => search_u64,
+    Usize: usize => search_usize,
+    I8: i8 => search_i8,
+    I16: i16 => search_i16,
+    I32: i32 => search_i32,
+    I64: i64 => search_i64,
+    Isize: isize => search_isize
+}
+
+/// Returns the given keys as raw keys, if they are primitive integers.
+#[inline]
+pub fn raw_keys<K>(keys: &[K]) -> Option<RawKeys<'_>> {
+    keys.as_raw_keys()
+}
+
+/// Returns the given key as a raw key, if it is a primitive integer.
+#[inline]
+pub fn raw_key<Q: ?Sized>(key: &Q) -> Option<RawKey> {
+    key.as_raw_key()
+}
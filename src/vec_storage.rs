@@ -0,0 +1,223 @@
+use crate::{
+    map::Binding,
+    slab::{
+        Node, OccupiedSlots, RawSlots, SlotCapacity, SlotIndex, Storage, VacantSlots, DEFAULT_ORDER,
+    },
+};
+use alloc::vec::Vec;
+#[cfg(feature = "allocator_api")]
+use core::alloc::Allocator;
+
+pub type AugmentedMapStorage<K, V, A, const M: usize = DEFAULT_ORDER> =
+    Storage<Binding<K, V>, VecSlab<Node<Binding<K, V>, M>>, A, M>;
+pub type MapStorage<K, V> = AugmentedMapStorage<K, V, ()>;
+pub type Map<K, V> = crate::Map<MapStorage<K, V>>;
+pub type AugmentedMap<K, V, A, const M: usize = DEFAULT_ORDER> =
+    crate::Map<AugmentedMapStorage<K, V, A, M>>;
+pub type CounterMap<K> = crate::map::CounterMap<MapStorage<K, usize>>;
+
+#[cfg(feature = "allocator_api")]
+pub type AugmentedMapStorageIn<K, V, A, Al, const M: usize = DEFAULT_ORDER> =
+    Storage<Binding<K, V>, VecSlab<Node<Binding<K, V>, M>, Al>, A, M>;
+#[cfg(feature = "allocator_api")]
+pub type MapStorageIn<K, V, Al> = AugmentedMapStorageIn<K, V, (), Al>;
+#[cfg(feature = "allocator_api")]
+pub type MapIn<K, V, Al> = crate::Map<MapStorageIn<K, V, Al>>;
+#[cfg(feature = "allocator_api")]
+pub type AugmentedMapIn<K, V, A, Al, const M: usize = DEFAULT_ORDER> =
+    crate::Map<AugmentedMapStorageIn<K, V, A, Al, M>>;
+
+/// `Vec`-backed slab.
+///
+/// Values are stored in a vector of slots.
+/// Slots freed by a removal are kept in a free list and reused by the next insertions.
+#[cfg(not(feature = "allocator_api"))]
+pub struct VecSlab<T> {
+    /// Slots.
+    slots: Vec<Option<T>>,
+
+    /// Indexes of the free slots.
+    free: Vec<usize>,
+
+    /// Number of occupied slots.
+    len: usize,
+}
+
+/// `Vec`-backed slab.
+///
+/// Values are stored in a vector of slots, allocated with `Al`.
+/// Slots freed by a removal are kept in a free list and reused by the next insertions.
+///
+/// Used as a node slab, every node is allocated with `Al`,
+/// as long as the runtime order of the storage does not exceed its compile-time order
+/// (otherwise the items of each node are moved to the global heap).
+/// The free list and the augmentations of the storage use the global allocator.
+///
+/// # Example
+///
+/// ```
+/// # #![feature(allocator_api)]
+/// use generic_btree::{
+///     vec_storage::{MapIn, MapStorageIn, VecSlab},
+///     Storage,
+/// };
+/// use std::alloc::System;
+///
+/// let mut map: MapIn<usize, usize, System> =
+///     MapIn::from_btree(MapStorageIn::from_slab(VecSlab::new_in(System)));
+/// for i in 0..100 {
+///     map.insert(i, i);
+/// }
+///
+/// assert_eq!(map.get(&42), Some(&42));
+/// map.btree().validate().unwrap();
+/// ```
+#[cfg(feature = "allocator_api")]
+pub struct VecSlab<T, Al: Allocator = alloc::alloc::Global> {
+    /// Slots.
+    slots: Vec<Option<T>, Al>,
+
+    /// Indexes of the free slots.
+    free: Vec<usize>,
+
+    /// Number of occupied slots.
+    len: usize,
+}
+
+impl<T> VecSlab<T> {
+    /// Create a new empty slab.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+}
+
+#[cfg(feature = "allocator_api")]
+impl<T, Al: Allocator> VecSlab<T, Al> {
+    /// Create a new empty slab allocating its slots with the given allocator.
+    #[inline]
+    pub fn new_in(alloc: Al) -> Self {
+        Self {
+            slots: Vec::new_in(alloc),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T> Default for VecSlab<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> cc_traits::WithCapacity for VecSlab<T> {
+    #[inline]
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+}
+
+macro_rules! vec_slab {
+    ([$($params:tt)*] $ty:ty) => {
+        impl<$($params)*> SlotIndex for $ty {}
+
+        impl<$($params)*> VacantSlots for $ty {}
+
+        impl<$($params)*> OccupiedSlots for $ty {
+            #[inline]
+            fn occupied_keys(&self) -> Option<Vec<usize>> {
+                Some(
+                    self.slots
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, slot)| slot.is_some())
+                        .map(|(key, _)| key)
+                        .collect(),
+                )
+            }
+        }
+
+        impl<$($params)*> SlotCapacity for $ty {}
+
+        impl<$($params)*> RawSlots for $ty {}
+
+        impl<$($params)*> cc_traits::Collection for $ty {
+            type Item = T;
+        }
+
+        impl<$($params)*> cc_traits::Len for $ty {
+            #[inline]
+            fn len(&self) -> usize {
+                self.len
+            }
+        }
+
+        impl<$($params)*> cc_traits::Get<usize> for $ty {
+            #[inline]
+            fn get(&self, key: usize) -> Option<&T> {
+                self.slots.get(key).and_then(Option::as_ref)
+            }
+        }
+
+        impl<$($params)*> cc_traits::GetMut<usize> for $ty {
+            #[inline]
+            fn get_mut(&mut self, key: usize) -> Option<&mut T> {
+                self.slots.get_mut(key).and_then(Option::as_mut)
+            }
+        }
+
+        impl<$($params)*> cc_traits::Insert for $ty {
+            type Output = usize;
+
+            #[inline]
+            fn insert(&mut self, value: T) -> usize {
+                self.len += 1;
+                match self.free.pop() {
+                    Some(key) => {
+                        self.slots[key] = Some(value);
+                        key
+                    }
+                    None => {
+                        self.slots.push(Some(value));
+                        self.slots.len() - 1
+                    }
+                }
+            }
+        }
+
+        impl<$($params)*> cc_traits::Remove<usize> for $ty {
+            #[inline]
+            fn remove(&mut self, key: usize) -> Option<T> {
+                let value = self.slots.get_mut(key)?.take()?;
+                self.free.push(key);
+                self.len -= 1;
+                Some(value)
+            }
+        }
+
+        impl<$($params)*> cc_traits::Clear for $ty {
+            #[inline]
+            fn clear(&mut self) {
+                self.slots.clear();
+                self.free.clear();
+                self.len = 0
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "allocator_api"))]
+vec_slab!([T] VecSlab<T>);
+
+#[cfg(feature = "allocator_api")]
+vec_slab!([T, Al: Allocator] VecSlab<T, Al>);
@@ -0,0 +1,95 @@
+use generic_btree::{
+    array_storage::Map,
+    map::{Binding, Inserted},
+    node::Buffer,
+    Storage, StorageMut, TryStorageMut,
+};
+use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
+
+const SEED: &[u8; 16] = b"testseedtestseed";
+
+#[test]
+pub fn fill() {
+    let mut map: Map<usize, usize, 16> = Map::new();
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..1000).collect();
+    keys.shuffle(&mut rng);
+
+    let mut inserted = Vec::new();
+    for key in &keys {
+        match map.insert_within_capacity(*key, *key) {
+            Ok(None) => inserted.push(*key),
+            Ok(Some(_)) => panic!("duplicate key"),
+            Err(e) => {
+                assert_eq!(e.0, (*key, *key));
+                break;
+            }
+        }
+
+        map.btree().validate().expect("validation failed")
+    }
+
+    assert_eq!(map.len(), inserted.len());
+    assert!(inserted.len() > 16);
+
+    inserted.shuffle(&mut rng);
+    for key in &inserted {
+        assert_eq!(map.remove(key), Some(*key));
+        map.btree().validate().expect("validation failed")
+    }
+
+    assert!(map.is_empty());
+    assert!(map.insert_within_capacity(0, 0).is_ok())
+}
+
+#[test]
+pub fn try_api() {
+    let mut map: Map<usize, usize, 4> = Map::new();
+
+    // Fill the map until it cannot even accept an item that would not split a node.
+    let mut i = 0;
+    while map.btree().remaining_node_capacity() != Some(0) {
+        map.insert_within_capacity(i, i).unwrap();
+        i += 1
+    }
+
+    let btree = map.btree_mut();
+    let leaf = Buffer::leaf(None, Binding::new(0, 0));
+    assert!(btree.try_allocate_node(leaf).is_err());
+
+    let mut inserted = i;
+    while let Ok(addr) = btree.try_insert_at(
+        btree.address_of(&inserted).unwrap_err(),
+        Inserted(inserted, inserted),
+    ) {
+        assert_eq!(btree.item(addr).unwrap().key, inserted);
+        inserted += 1
+    }
+
+    btree.validate().expect("validation failed");
+    assert_eq!(map.len(), inserted);
+    assert!(map.insert_within_capacity(inserted, inserted).is_err());
+    assert_eq!(map.insert_within_capacity(0, 1), Ok(Some(0)))
+}
+
+#[test]
+pub fn pooled_capacity() {
+    let mut map: Map<usize, usize, 4> = Map::new();
+    let mut i = 0;
+    while map.btree().remaining_node_capacity() != Some(0) {
+        map.insert_within_capacity(i, i).unwrap();
+        i += 1
+    }
+
+    // Released nodes are pooled, and still count as available.
+    for key in 0..i {
+        map.remove(&key);
+    }
+    assert!(map.btree().pool_stats().pooled > 0);
+    assert_eq!(map.btree().remaining_node_capacity(), Some(4));
+
+    for key in 0..i {
+        map.insert_within_capacity(key, key).unwrap();
+    }
+    map.btree().validate().expect("validation failed");
+}
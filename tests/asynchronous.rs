@@ -0,0 +1,69 @@
+use generic_btree::asynchronous::{AsyncMap, MemoryStorage};
+use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
+use std::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+const SEED: &[u8; 16] = b"testseedtestseed";
+
+/// Runs a future whose pending states are always immediately resolved.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+#[test]
+pub fn insert_remove() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<u32> = (0..1000).collect();
+    keys.shuffle(&mut rng);
+
+    block_on(async {
+        let mut map = AsyncMap::with_order(MemoryStorage::new(), 4);
+        for key in &keys {
+            assert_eq!(map.insert(*key, *key * 2).await.unwrap(), None);
+        }
+
+        assert_eq!(map.len(), keys.len());
+        assert_eq!(map.insert(0, 1).await.unwrap(), Some(0));
+        assert_eq!(map.insert(0, 0).await.unwrap(), Some(1));
+
+        keys.shuffle(&mut rng);
+        let (removed, kept) = keys.split_at(600);
+        for key in removed {
+            assert_eq!(map.remove(key).await.unwrap(), Some(*key * 2));
+            assert_eq!(map.remove(key).await.unwrap(), None);
+        }
+
+        assert_eq!(map.len(), kept.len());
+        for key in kept {
+            assert_eq!(map.get(key).await.unwrap(), Some(*key * 2))
+        }
+
+        for key in removed {
+            assert_eq!(map.get(key).await.unwrap(), None)
+        }
+
+        let mut sorted = kept.to_vec();
+        sorted.sort_unstable();
+        let mut iter = map.iter();
+        for key in sorted {
+            assert_eq!(iter.next().await.unwrap().unwrap(), (key, key * 2))
+        }
+        assert!(iter.next().await.is_none());
+
+        for key in kept {
+            map.remove(key).await.unwrap();
+        }
+
+        assert!(map.is_empty());
+        assert_eq!(map.storage().node_count(), 0);
+    })
+}
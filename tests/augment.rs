@@ -0,0 +1,436 @@
+#[cfg(feature = "std")]
+use generic_btree::augment::MerkleHash;
+use generic_btree::{
+    augment::{Count, Max, Min},
+    map::Binding,
+    slab::{AugmentedMap, AugmentedMapStorage, Map},
+    Augment, Augmentation, Storage, SubtreeLen,
+};
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng, SeedableRng};
+#[cfg(feature = "std")]
+use std::collections::hash_map::DefaultHasher;
+use std::ops::{Bound, RangeBounds};
+
+const SEED: &[u8; 16] = b"testseedtestseed";
+
+/// Sum of the values.
+#[derive(Clone, Default, PartialEq, Debug)]
+struct Sum(usize);
+
+impl SubtreeLen for Sum {}
+
+impl<A: SubtreeLen> Augmentation<AugmentedMapStorage<usize, usize, A>> for Sum {
+    fn add_item<'r>(&mut self, item: &&'r Binding<usize, usize>)
+    where
+        AugmentedMapStorage<usize, usize, A>: 'r,
+    {
+        self.0 += item.value
+    }
+
+    fn add_child(&mut self, child: &Self) {
+        self.0 += child.0
+    }
+}
+
+type TestMap = AugmentedMap<usize, usize, (Count, Sum)>;
+
+type MinMaxMap = AugmentedMap<usize, usize, (Count, (Min<usize>, Max<usize>))>;
+
+fn check_node<S: Augment>(btree: &S, id: usize)
+where
+    S::Augmentation: PartialEq + std::fmt::Debug,
+{
+    let node = btree.node(id).unwrap();
+    for i in 0..node.child_count() {
+        check_node(btree, node.child_id(i).unwrap())
+    }
+
+    assert_eq!(
+        btree.augmentation(id),
+        Some(&btree.compute_augmentation(id))
+    )
+}
+
+fn check(map: &TestMap) {
+    let btree = map.btree();
+    btree.validate().expect("validation failed");
+
+    match btree.root() {
+        Some(id) => {
+            check_node(btree, id);
+            let (count, sum) = btree.root_augmentation().unwrap();
+            assert_eq!(count.0, map.len());
+            assert_eq!(sum.0, map.values().sum::<usize>())
+        }
+        None => assert!(btree.root_augmentation().is_none()),
+    }
+}
+
+#[test]
+pub fn insert_remove() {
+    let mut map = TestMap::new();
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..200).collect();
+    keys.shuffle(&mut rng);
+
+    for key in &keys {
+        map.insert(*key, key * 2);
+        check(&map)
+    }
+
+    keys.shuffle(&mut rng);
+    for key in &keys {
+        map.insert(*key, key * 3);
+        check(&map)
+    }
+
+    keys.shuffle(&mut rng);
+    for key in &keys {
+        map.remove(key);
+        check(&map)
+    }
+
+    assert!(map.is_empty())
+}
+
+#[test]
+pub fn update() {
+    let mut map = TestMap::new();
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..200).collect();
+    keys.shuffle(&mut rng);
+
+    for key in &keys {
+        map.update(*key, |value| (Some(value.unwrap_or(0) + key), ()));
+        check(&map)
+    }
+
+    keys.shuffle(&mut rng);
+    for key in &keys {
+        map.update(*key, |value| (value.filter(|v| v % 2 == 0), ()));
+        check(&map)
+    }
+}
+
+#[test]
+pub fn range_aggregate() {
+    let mut map = MinMaxMap::new();
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for _ in 0..300 {
+        let key = rng.gen_range(0, 1000);
+        map.insert(key, rng.gen_range(0, 100));
+    }
+
+    for _ in 0..300 {
+        let a = rng.gen_range(0, 1000);
+        let b = rng.gen_range(a, 1001);
+        let bounds = [
+            (Bound::Included(a), Bound::Excluded(b)),
+            (Bound::Included(a), Bound::Included(b)),
+            (Bound::Excluded(a), Bound::Included(b)),
+            (Bound::Unbounded, Bound::Included(b)),
+            (Bound::Included(a), Bound::Unbounded),
+        ];
+
+        for range in &bounds {
+            let (count, (min, max)) = map.range_aggregate(*range);
+            let values: Vec<usize> = map
+                .iter()
+                .filter(|(k, _)| range.contains(*k))
+                .map(|(_, v)| *v)
+                .collect();
+            assert_eq!(count.0, values.len());
+            assert_eq!(min.0, values.iter().min().cloned());
+            assert_eq!(max.0, values.iter().max().cloned());
+        }
+    }
+
+    let (count, _) = map.range_aggregate::<usize, _>(..);
+    assert_eq!(count.0, map.len())
+}
+
+#[test]
+pub fn range_count() {
+    let mut map = MinMaxMap::new();
+    let mut plain_map = Map::new();
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for _ in 0..300 {
+        let key = rng.gen_range(0, 1000);
+        map.insert(key, key);
+        plain_map.insert(key, key);
+    }
+
+    for _ in 0..300 {
+        let a = rng.gen_range(0, 1000);
+        let b = rng.gen_range(a, 1001);
+        let bounds = [
+            (Bound::Included(a), Bound::Excluded(b)),
+            (Bound::Included(a), Bound::Included(b)),
+            (Bound::Excluded(a), Bound::Included(b)),
+            (Bound::Unbounded, Bound::Included(b)),
+            (Bound::Included(a), Bound::Unbounded),
+        ];
+
+        for range in &bounds {
+            let expected = map.keys().filter(|k| range.contains(*k)).count();
+            assert_eq!(map.range_count(*range), expected);
+            assert_eq!(plain_map.range_count(*range), expected);
+
+            let mut iter = map.btree().range(*range);
+            assert_eq!(iter.size_hint(), (expected, Some(expected)));
+            if iter.next().is_some() {
+                assert_eq!(iter.size_hint(), (expected - 1, Some(expected - 1)))
+            }
+
+            let mut range_iter = map.range(*range);
+            let mut plain_range_iter = plain_map.range(*range);
+            let mut remaining = expected;
+            while remaining > 0 {
+                assert_eq!(range_iter.remaining(), remaining);
+                assert_eq!(plain_range_iter.remaining(), remaining);
+                if remaining % 2 == 0 {
+                    range_iter.next();
+                    plain_range_iter.next();
+                } else {
+                    range_iter.next_back();
+                    plain_range_iter.next_back();
+                }
+                remaining -= 1
+            }
+
+            assert_eq!(range_iter.remaining(), 0);
+            assert_eq!(plain_range_iter.remaining(), 0);
+            assert_eq!(plain_map.range_mut(*range).remaining(), expected)
+        }
+    }
+}
+
+#[test]
+pub fn get_index() {
+    let mut map = MinMaxMap::new();
+    let mut plain_map = Map::new();
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for _ in 0..1000 {
+        let key = rng.gen_range(0, 2000);
+        map.insert(key, key);
+        plain_map.insert(key, key);
+    }
+
+    let keys: Vec<usize> = map.keys().cloned().collect();
+    for (n, key) in keys.iter().enumerate() {
+        assert_eq!(map.get_index(n), Some((key, key)));
+        assert_eq!(plain_map.get_index(n), Some((key, key)));
+    }
+
+    assert_eq!(map.get_index(keys.len()), None);
+    assert_eq!(plain_map.get_index(keys.len()), None);
+
+    for n in 0..keys.len() {
+        *map.get_index_mut(n).unwrap().1 += 1;
+    }
+
+    assert!(map.iter().all(|(k, v)| *v == *k + 1))
+}
+
+#[test]
+pub fn rank_of() {
+    let mut map = MinMaxMap::new();
+    let mut plain_map = Map::new();
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for _ in 0..1000 {
+        let key = rng.gen_range(0, 2000);
+        map.insert(key, key);
+        plain_map.insert(key, key);
+    }
+
+    let keys: Vec<usize> = map.keys().cloned().collect();
+    for key in 0..2001 {
+        let expected = keys.binary_search(&key);
+        assert_eq!(map.rank_of(&key), expected);
+        assert_eq!(plain_map.rank_of(&key), expected);
+    }
+}
+
+#[cfg(feature = "std")]
+type MerkleMap = AugmentedMap<usize, usize, MerkleHash<DefaultHasher>>;
+
+#[test]
+#[cfg(feature = "std")]
+pub fn merkle_hash() {
+    let mut a = MerkleMap::new();
+    let mut b = MerkleMap::new();
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..200).collect();
+    keys.shuffle(&mut rng);
+
+    for key in &keys {
+        let hash = a.root_hash();
+        a.insert(*key, *key);
+        assert_ne!(a.root_hash(), hash);
+
+        b.insert(*key, *key);
+        assert_eq!(a.root_hash(), b.root_hash());
+    }
+
+    for key in &keys {
+        let btree = a.btree();
+        check_node(btree, btree.root().unwrap());
+
+        let hash = a.root_hash().unwrap();
+        a.insert(*key, key + 1);
+        assert_ne!(a.root_hash().unwrap(), hash);
+        a.insert(*key, *key);
+        assert_eq!(a.root_hash().unwrap(), hash);
+    }
+
+    for key in &keys {
+        a.remove(key);
+        b.remove(key);
+        assert_eq!(a.root_hash(), b.root_hash());
+    }
+
+    assert!(a.root_hash().is_none())
+}
+
+#[test]
+#[cfg(feature = "std")]
+pub fn merkle_hash_shape() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..500).collect();
+    keys.shuffle(&mut rng);
+
+    let mut a = MerkleMap::new();
+    for key in &keys {
+        a.insert(*key, *key);
+    }
+
+    let mut b = MerkleMap::from_btree(AugmentedMapStorage::with_order(4));
+    for key in 0..800 {
+        b.insert(key, key);
+    }
+
+    for key in 500..800 {
+        b.remove(&key);
+    }
+
+    let c: MerkleMap = (0..500).map(|key| (key, key)).collect();
+
+    // Same items, different trees.
+    assert_ne!(a.btree().stats().leaf_nodes, b.btree().stats().leaf_nodes);
+    assert_eq!(a.root_hash(), b.root_hash());
+    assert_eq!(a.root_hash(), c.root_hash());
+
+    let btree = b.btree();
+    assert_eq!(btree.subtree_len(btree.root().unwrap()), Some(500));
+
+    let right = a.split_off(&250);
+    let left: MerkleMap = (0..250).map(|key| (key, key)).collect();
+    assert_eq!(a.root_hash(), left.root_hash());
+    assert_ne!(a.root_hash(), right.root_hash());
+
+    // The hash depends on the order of the items.
+    let mut d = MerkleMap::new();
+    let mut e = MerkleMap::new();
+    d.insert(0, 1);
+    d.insert(1, 0);
+    e.insert(0, 0);
+    e.insert(1, 1);
+    assert_ne!(d.root_hash(), e.root_hash());
+}
+
+#[test]
+pub fn split_off() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..300).collect();
+
+    for split_key in (0..310).step_by(11) {
+        keys.shuffle(&mut rng);
+        let mut map = TestMap::new();
+        for key in &keys {
+            map.insert(*key, key * 2);
+        }
+
+        let other = map.split_off(&split_key);
+        check(&map);
+        check(&other);
+        assert_eq!(map.len(), split_key.min(300));
+    }
+}
+
+#[test]
+pub fn split_off_range() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..300).collect();
+
+    for start in (0..310).step_by(23) {
+        for end in (start..310).step_by(29) {
+            keys.shuffle(&mut rng);
+            let mut map = TestMap::new();
+            for key in &keys {
+                map.insert(*key, key * 2);
+            }
+
+            let other = map.split_off_range(start..end);
+            check(&map);
+            check(&other);
+            assert_eq!(other.len(), end.min(300) - start.min(300));
+        }
+    }
+}
+
+#[test]
+pub fn clone_into_slab() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..300).collect();
+    keys.shuffle(&mut rng);
+
+    let mut map = TestMap::new();
+    for key in &keys {
+        map.insert(*key, key * 2);
+    }
+
+    for key in &keys[..100] {
+        map.remove(key);
+    }
+
+    let copy = TestMap::from_btree(map.btree().clone_into_slab(Default::default()));
+    check(&copy);
+    assert!(copy.iter().eq(map.iter()));
+}
+
+#[test]
+pub fn append() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..300).collect();
+
+    for split_key in (0..310).step_by(11) {
+        keys.shuffle(&mut rng);
+        let mut map = TestMap::new();
+        let mut other = TestMap::new();
+        for key in &keys {
+            if *key < split_key {
+                other.insert(*key, key * 2);
+            } else {
+                map.insert(*key, key * 2);
+            }
+        }
+
+        map.append(&mut other);
+        check(&map);
+        check(&other);
+        assert_eq!(map.len(), 300);
+    }
+}
+
+#[test]
+pub fn from_sorted_iter() {
+    for len in 0..300 {
+        let map = TestMap::from_sorted_iter((0..len).map(|i| (i, i * 2)));
+        check(&map);
+        assert_eq!(map.len(), len);
+    }
+}
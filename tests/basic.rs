@@ -1,10 +1,16 @@
-#![feature(nll)]
 use generic_btree::{
-    map::{Binding, Inserted},
-    slab::Map,
-    Storage, StorageMut,
+    map::{Binding, Entry, Inserted},
+    node::{Address, Buffer, Position},
+    policy::{Fill, Lazy},
+    slab::{AugmentedMap, AugmentedMapStorage, CompactMap, Map, MapStorage},
+    CapacityPolicy, Finger, Observer, Storage, StorageMut, Traversal,
+};
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng, SeedableRng};
+use std::{
+    ops::ControlFlow,
+    rc::Rc,
+    sync::{Arc, Mutex},
 };
-use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
 
 const SEED: &'static [u8; 16] = b"testseedtestseed";
 
@@ -81,6 +87,105 @@ pub fn item_addresses() {
     }
 }
 
+#[test]
+pub fn address_validity() {
+    use generic_btree::node::Address;
+
+    let mut map: Map<usize, usize> = Map::new();
+    assert!(map.btree().contains_address(Address::nowhere()));
+    assert!(!map.btree().is_item_address(Address::nowhere()));
+
+    for (key, value) in &ITEMS {
+        map.insert(*key, *value);
+    }
+
+    let btree = map.btree();
+    assert!(!btree.contains_address(Address::nowhere()));
+    for (key, _) in &ITEMS {
+        let addr = btree.address_of(key).ok().unwrap();
+        assert!(btree.contains_address(addr));
+        assert!(btree.is_item_address(addr));
+
+        let item_count = btree.node(addr.id).unwrap().item_count();
+        assert!(btree.contains_address(Address::new(addr.id, item_count.into())));
+        assert!(!btree.is_item_address(Address::new(addr.id, item_count.into())));
+        assert!(!btree.contains_address(Address::new(addr.id, (item_count + 1).into())));
+
+        let before = Address::new(addr.id, generic_btree::node::Position::Before);
+        assert!(btree.contains_address(before));
+        assert!(!btree.is_item_address(before));
+        assert!(btree.item(before).is_none());
+    }
+
+    let addresses: Vec<_> = ITEMS
+        .iter()
+        .map(|(key, _)| map.btree().address_of(key).ok().unwrap())
+        .collect();
+    map.btree_mut().clear();
+    for addr in addresses {
+        assert!(!map.btree().contains_address(addr));
+        assert!(!map.btree().is_item_address(addr));
+    }
+}
+
+#[test]
+pub fn positions() {
+    use generic_btree::node::Position;
+
+    let before = Position::Before;
+    assert!(before < 0);
+    assert!(before < Position::At(0));
+    assert_eq!(before + 0, Position::Before);
+    assert_eq!(before + 1, Position::At(0));
+    assert_eq!(Position::At(3) + before, Position::At(2));
+    assert_eq!(Position::At(0) + before, Position::Before);
+    assert_eq!(before - 0, Position::Before);
+    assert_eq!(before - before, Position::At(0));
+    assert_eq!(Position::At(2) - before, Position::At(3));
+    assert_eq!(Position::At(2) - 3, Position::Before);
+    assert_eq!(Position::At(2) - Position::At(3), Position::Before);
+    assert_eq!(Position::At(3) - Position::At(2), Position::At(1));
+    assert_eq!(before.to_string(), "-1");
+
+    let mut position = Position::At(0);
+    position.decr();
+    assert_eq!(position, Position::Before);
+    position.decr();
+    assert_eq!(position, Position::Before);
+    position.incr();
+    assert_eq!(position, Position::At(0));
+}
+
+#[test]
+pub fn before_addresses() {
+    use generic_btree::node::{Address, Position};
+
+    let map: Map<usize, usize> = ITEMS.iter().copied().collect();
+    let btree = map.btree();
+
+    let result = btree.visit_nodes(Traversal::DepthFirst, |node| -> ControlFlow<()> {
+        let mut leaf_id = node.id();
+        while let Some(child_id) = btree.node(leaf_id).unwrap().child_id(0) {
+            leaf_id = child_id
+        }
+
+        let first = Address::new(leaf_id, 0.into());
+        let before = Address::new(node.id(), Position::Before);
+        assert_eq!(
+            btree.leaf_address(before),
+            Address::new(leaf_id, Position::Before)
+        );
+        assert_eq!(btree.next_item_address(before), Some(first));
+        assert_eq!(
+            btree.previous_item_address(before),
+            btree.previous_item_address(first)
+        );
+        ControlFlow::Continue(())
+    });
+
+    assert_eq!(result, ControlFlow::Continue(()))
+}
+
 #[test]
 pub fn insert_addresses() {
     let mut map: Map<usize, usize> = Map::new();
@@ -122,6 +227,660 @@ pub fn remove_addresses() {
     }
 }
 
+#[test]
+pub fn index() {
+    let map: Map<String, usize> = (0..100).map(|i| (i.to_string(), i)).collect();
+    for i in 0..100 {
+        assert_eq!(map[i.to_string().as_str()], i);
+        assert_eq!(map[&i.to_string()], i);
+    }
+}
+
+#[test]
+#[should_panic]
+pub fn index_missing_key() {
+    let map: Map<usize, usize> = (0..100).map(|i| (i, i)).collect();
+    let _ = map[&100];
+}
+
+#[test]
+pub fn debug() {
+    let mut map: Map<usize, &str> = Map::new();
+    assert_eq!(format!("{:?}", map), "{}");
+
+    map.insert(2, "b");
+    map.insert(1, "a");
+    assert_eq!(format!("{:?}", map), r#"{1: "a", 2: "b"}"#);
+    assert_eq!(
+        format!("{:?}", map.btree()),
+        r#"Storage { root: Some(0), len: 2, order: 8, nodes: {0: Leaf { parent: None, items: [Binding { key: 1, value: "a" }, Binding { key: 2, value: "b" }] }} }"#
+    );
+
+    let map: Map<usize, usize> = (0..100).map(|i| (i, i)).collect();
+    let debug = format!("{:?}", map.btree());
+    assert!(debug.contains("Internal { parent: None"));
+    assert!(debug.matches("Leaf").count() > 1);
+}
+
+#[test]
+pub fn clone() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..500).collect();
+    keys.shuffle(&mut rng);
+
+    let mut map: Map<usize, usize> = keys.iter().map(|k| (*k, *k)).collect();
+    for key in &keys[..250] {
+        map.remove(key);
+    }
+
+    let mut copy = map.clone();
+    copy.btree().validate().expect("validation failed");
+    assert!(copy.iter().eq(map.iter()));
+
+    copy.insert(1000, 1000);
+    assert!(map.get(&1000).is_none());
+
+    let compact: Map<usize, usize> =
+        Map::from_btree(map.btree().clone_into_slab(Default::default()));
+    compact.btree().validate().expect("validation failed");
+    assert!(compact.iter().eq(map.iter()));
+}
+
+#[cfg(feature = "std")]
+#[test]
+pub fn snapshot() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..500).collect();
+    keys.shuffle(&mut rng);
+
+    let mut map: Map<usize, usize> = keys.iter().map(|k| (*k, *k)).collect();
+    for key in &keys[..250] {
+        map.remove(key);
+    }
+
+    let mut bytes = Vec::new();
+    map.btree().save_snapshot(&mut bytes).unwrap();
+
+    let restored: Map<usize, usize> =
+        Map::from_btree(AugmentedMapStorage::load_snapshot(&bytes[..]).unwrap());
+    restored.btree().validate().expect("validation failed");
+    assert_eq!(restored.btree().root(), map.btree().root());
+    assert!(restored.iter().eq(map.iter()));
+    for key in &keys {
+        assert_eq!(
+            restored.btree().address_of(key),
+            map.btree().address_of(key)
+        )
+    }
+
+    assert!(AugmentedMapStorage::<usize, usize, ()>::load_snapshot(&bytes[1..]).is_err());
+    assert!(
+        AugmentedMapStorage::<usize, usize, ()>::load_snapshot(&bytes[..bytes.len() - 1]).is_err()
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+pub fn corrupted_snapshot() {
+    use generic_btree::paged::Codec;
+    use std::io;
+
+    let load = |bytes: &[u8]| AugmentedMapStorage::<usize, usize, ()>::load_snapshot(bytes);
+    let header = |root: Option<usize>, len: usize, order: usize, slots: usize| {
+        let mut bytes = b"GBTSNAPS".to_vec();
+        root.encode(&mut bytes);
+        len.encode(&mut bytes);
+        order.encode(&mut bytes);
+        slots.encode(&mut bytes);
+        bytes
+    };
+
+    let map: Map<usize, usize> = (0..100).map(|i| (i, i)).collect();
+    let mut bytes = Vec::new();
+    map.btree().save_snapshot(&mut bytes).unwrap();
+    assert!(load(&bytes).is_ok());
+
+    // Wrong length.
+    let mut patched = bytes.clone();
+    let offset = header(map.btree().root(), 0, 0, 0).len() - 24;
+    let mut len = Vec::new();
+    999usize.encode(&mut len);
+    patched[offset..(offset + 8)].copy_from_slice(&len);
+    let error = load(&patched).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+    // Truncated snapshots.
+    for end in 0..bytes.len() {
+        assert_eq!(
+            load(&bytes[..end]).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    // Huge node identifiers do not allocate anything.
+    let huge = header(Some(1 << 40), 1, 8, 1 << 40);
+    assert!(huge.len() < 60);
+    assert_eq!(load(&huge).unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+    // Root out of the saved slots.
+    let mut bytes = header(Some(1), 0, 8, 1);
+    false.encode(&mut bytes);
+    assert_eq!(load(&bytes).unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+    // Every byte flip is either rejected, or results in a valid tree.
+    let mut bytes = Vec::new();
+    map.btree().save_snapshot(&mut bytes).unwrap();
+    for i in 0..bytes.len() {
+        let mut patched = bytes.clone();
+        patched[i] ^= 0x55;
+        if let Ok(btree) = load(&patched) {
+            assert_eq!(btree.len(), 100);
+            assert_eq!(btree.iter().count(), 100);
+        }
+    }
+}
+
+#[test]
+pub fn std_btree_map() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..500).collect();
+    keys.shuffle(&mut rng);
+
+    let std_map: std::collections::BTreeMap<usize, usize> =
+        keys.iter().map(|k| (*k, *k * 2)).collect();
+    let map: Map<usize, usize> = std_map.clone().into();
+    map.btree().validate().expect("validation failed");
+    assert!(map.iter().eq(std_map.iter()));
+
+    let back: std::collections::BTreeMap<usize, usize> = map.into();
+    assert_eq!(back, std_map);
+}
+
+#[test]
+pub fn sorted_vec() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut pairs: Vec<(usize, usize)> = (0..500).map(|i| (i, i)).collect();
+
+    let map: Map<usize, usize> = pairs.iter().cloned().collect();
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.into_sorted_vec(), pairs);
+
+    let sorted = pairs.clone();
+    pairs[..250].shuffle(&mut rng);
+    pairs.push((42, 0));
+
+    let map: Map<usize, usize> = pairs.iter().cloned().collect();
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.get(&42), Some(&0));
+    assert_eq!(map.len(), 500);
+
+    let map: Map<usize, usize> = Map::from_unsorted_vec(pairs);
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.get(&42), Some(&0));
+
+    let mut map = map.into_sorted_vec();
+    map[42].1 = 42;
+    assert_eq!(map, sorted);
+}
+
+#[cfg(feature = "std")]
+#[test]
+pub fn hash_map_conversions() {
+    let sorted: Vec<(usize, usize)> = (0..500).map(|i| (i, i)).collect();
+    let hash_map: std::collections::HashMap<usize, usize> = sorted.iter().cloned().collect();
+    let map: Map<usize, usize> = hash_map.clone().into();
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.into_sorted_vec(), sorted);
+
+    let map: Map<usize, usize> = sorted.clone().into();
+    let back: std::collections::HashMap<usize, usize> = map.into();
+    assert_eq!(back, hash_map);
+}
+
+#[test]
+pub fn entry_ref() {
+    let mut map: Map<String, usize> = Map::new();
+
+    for (key, value) in &ITEMS {
+        let key = key.to_string();
+        *map.entry_ref(key.as_str()).or_default() += *value;
+    }
+
+    map.btree().validate().expect("validation failed");
+    for (key, value) in &ITEMS {
+        let key = key.to_string();
+        let total: usize = ITEMS
+            .iter()
+            .filter(|(k, _)| k.to_string() == key)
+            .map(|(_, v)| *v)
+            .sum();
+        assert_eq!(map.get(key.as_str()), Some(&total));
+        assert!(*value <= total);
+    }
+}
+
+#[test]
+pub fn try_insert() {
+    let mut map: Map<usize, usize> = Map::new();
+
+    for (key, value) in &ITEMS {
+        let existing = map.get(key).cloned();
+        match map.try_insert(*key, *value) {
+            Ok(inserted) => {
+                assert!(existing.is_none());
+                assert_eq!(*inserted, *value)
+            }
+            Err(e) => {
+                assert_eq!(existing, Some(*e.entry.get()));
+                assert_eq!(*e.entry.key(), *key);
+                assert_eq!(e.value, *value)
+            }
+        }
+    }
+
+    map.btree().validate().expect("validation failed");
+}
+
+#[test]
+pub fn insert_with_hint() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut btree: AugmentedMapStorage<usize, usize, ()> = Default::default();
+
+    let mut hint = generic_btree::node::Address::nowhere();
+    for i in (0..1000).step_by(2) {
+        let (addr, replaced) = btree.insert_with_hint(hint, Inserted(i, i));
+        assert!(replaced.is_none());
+        assert_eq!(btree.item(addr).unwrap().key, i);
+        hint = addr
+    }
+
+    btree.validate().expect("validation failed");
+
+    let mut keys: Vec<usize> = (0..1000).collect();
+    keys.shuffle(&mut rng);
+    for (i, key) in keys.iter().enumerate() {
+        let hint = btree
+            .address_of(&keys[(i * 7) % keys.len()])
+            .unwrap_or_else(|a| a);
+        let (addr, replaced) = btree.insert_with_hint(hint, Inserted(*key, *key + 1));
+        assert_eq!(replaced, if key % 2 == 0 { Some(*key) } else { None });
+        assert_eq!(btree.item(addr).unwrap().key, *key);
+        btree.validate().expect("validation failed")
+    }
+
+    let map = generic_btree::Map::from_btree(btree);
+    assert!(map
+        .iter()
+        .map(|(k, v)| (*k, *v))
+        .eq((0..1000).map(|i| (i, i + 1))));
+}
+
+#[test]
+pub fn get_with_hint() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut map: Map<usize, usize> = (0..1000).step_by(2).map(|i| (i, i + 1)).collect();
+
+    // Valid hints, near and far from the key.
+    for _ in 0..1000 {
+        let key: usize = rng.gen_range(0, 1100);
+        let near = key.saturating_sub(rng.gen_range(0, 3)).min(998) & !1;
+        let far = rng.gen_range(0, 500) * 2;
+        for hint_key in [near, far].iter() {
+            let hint = map.btree().address_of(hint_key).unwrap();
+            let expected = if key % 2 == 0 && key < 1000 {
+                Some(key + 1)
+            } else {
+                None
+            };
+            assert_eq!(
+                map.btree().get_with_hint(hint, &key).map(|b| b.value),
+                expected
+            )
+        }
+    }
+
+    // Stale hints.
+    let hint = map.btree().address_of(&998).unwrap();
+    for i in (500..1000).step_by(2) {
+        map.remove(&i);
+    }
+    map.btree().validate().expect("validation failed");
+    for key in (0..1000).step_by(2) {
+        let expected = if key < 500 { Some(key + 1) } else { None };
+        assert_eq!(
+            map.btree().get_with_hint(hint, &key).map(|b| b.value),
+            expected
+        )
+    }
+}
+
+#[test]
+pub fn address_of_with_hint() {
+    let map: Map<usize, usize> = (0..1000).step_by(2).map(|i| (i, i)).collect();
+    let btree = map.btree();
+
+    // Hints before the first item, and after the last item, of each node.
+    let mut hints = Vec::new();
+    for key in (0..1000).step_by(2) {
+        let addr = btree.address_of(&key).unwrap();
+        let count = btree.node(addr.id).unwrap().item_count();
+        hints.push(Address::new(addr.id, Position::Before));
+        hints.push(Address::new(addr.id, count.into()));
+    }
+
+    let first = btree.first_item_address().unwrap();
+    hints.push(btree.previous_front_address(first).unwrap());
+
+    for hint in hints {
+        assert!(btree.contains_address(hint));
+        for key in (0..1001).step_by(50) {
+            let expected = btree.address_of(&key);
+            match btree.address_of_with_hint(hint, &key) {
+                Ok(addr) => assert_eq!(Ok(addr), expected),
+                Err(addr) => {
+                    assert!(expected.is_err());
+                    assert!(btree.contains_address(addr))
+                }
+            }
+        }
+    }
+}
+
+#[test]
+pub fn hints_outside_items() {
+    let mut map: Map<usize, usize> = (0..1000).step_by(2).map(|i| (i, i + 1)).collect();
+
+    // Hints before the first item, and after the last item, of each node.
+    let hints = |map: &Map<usize, usize>| {
+        let btree = map.btree();
+        let mut hints = vec![Address::nowhere()];
+        for key in (0..1000).step_by(14) {
+            let addr = btree.address_of(&key).unwrap();
+            let count = btree.node(addr.id).unwrap().item_count();
+            hints.push(Address::new(addr.id, Position::Before));
+            hints.push(Address::new(addr.id, count.into()));
+        }
+        hints
+    };
+
+    for hint in hints(&map) {
+        assert!(!map.btree().contains_item_address(hint));
+        for key in (0..1001).step_by(25) {
+            let expected = if key % 2 == 0 && key < 1000 {
+                Some(key + 1)
+            } else {
+                None
+            };
+            assert_eq!(
+                map.btree().get_with_hint(hint, &key).map(|b| b.value),
+                expected
+            )
+        }
+    }
+
+    for (i, key) in (1..1000).step_by(2).enumerate() {
+        let hints = hints(&map);
+        let hint = hints[i % hints.len()];
+        let (addr, replaced) = map
+            .btree_mut()
+            .insert_with_hint(hint, Inserted(key, key + 1));
+        assert!(replaced.is_none());
+        assert_eq!(map.btree().item(addr).unwrap().key, key);
+    }
+
+    map.btree().validate().expect("validation failed");
+    assert!(map
+        .iter()
+        .map(|(k, v)| (*k, *v))
+        .eq((0..1000).map(|i| (i, i + 1))));
+}
+
+#[test]
+pub fn insert_sorted_batch() {
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for _ in 0..50 {
+        let initial: Vec<usize> = (0..rng.gen_range(0, 500))
+            .map(|_| rng.gen_range(0, 1000))
+            .collect();
+        let mut batch: Vec<usize> = (0..rng.gen_range(0, 500))
+            .map(|_| rng.gen_range(0, 1500))
+            .collect();
+        batch.sort_unstable();
+        batch.dedup();
+
+        let mut map: Map<usize, usize> = initial.iter().map(|k| (*k, *k)).collect();
+        let mut reference: std::collections::BTreeMap<usize, usize> =
+            initial.iter().map(|k| (*k, *k)).collect();
+
+        map.insert_sorted_batch(batch.iter().map(|k| (*k, *k + 1)));
+        reference.extend(batch.iter().map(|k| (*k, *k + 1)));
+
+        map.btree().validate().expect("validation failed");
+        assert_eq!(map.len(), reference.len());
+        assert!(map.iter().eq(reference.iter()))
+    }
+}
+
+#[test]
+pub fn insert_sorted_batch_runs() {
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for _ in 0..50 {
+        // Long runs of new keys between the existing keys.
+        let initial: Vec<usize> = (0..rng.gen_range(0, 100)).map(|i| i * 1000).collect();
+        let mut batch: Vec<usize> = (0..rng.gen_range(0, 50))
+            .flat_map(|_| {
+                let start = rng.gen_range(0, 100 * 1000);
+                start..(start + rng.gen_range(0, 200))
+            })
+            .collect();
+        batch.sort_unstable();
+        batch.dedup();
+
+        let mut map: Map<usize, usize> = initial.iter().map(|k| (*k, *k)).collect();
+        let mut reference: std::collections::BTreeMap<usize, usize> =
+            initial.iter().map(|k| (*k, *k)).collect();
+
+        map.insert_sorted_batch(batch.iter().map(|k| (*k, *k + 1)));
+        reference.extend(batch.iter().map(|k| (*k, *k + 1)));
+
+        map.btree().validate().expect("validation failed");
+        assert_eq!(map.len(), reference.len());
+        assert!(map.iter().eq(reference.iter()))
+    }
+}
+
+#[test]
+pub fn replace_entry_with() {
+    let mut map: Map<usize, usize> = (0..500).map(|i| (i, i)).collect();
+
+    for key in 0..500 {
+        let entry =
+            map.entry(key)
+                .and_replace_entry_with(|k, v| if k % 2 == 0 { Some(v + 1) } else { None });
+        match entry {
+            Entry::Occupied(_) => assert_eq!(key % 2, 0),
+            Entry::Vacant(v) => {
+                assert_eq!(*v.key(), key);
+                assert_eq!(key % 2, 1)
+            }
+        }
+
+        map.btree().validate().expect("validation failed")
+    }
+
+    assert!(map
+        .iter()
+        .map(|(k, v)| (*k, *v))
+        .eq((0..500).step_by(2).map(|i| (i, i + 1))));
+}
+
+#[test]
+pub fn pop_if() {
+    let mut map: Map<usize, usize> = (0..500).map(|i| (i, i)).collect();
+
+    let mut first = 0;
+    let mut end = 500;
+    while !map.is_empty() {
+        match map.pop_first_if(|k, v| {
+            *v += 1;
+            k % 3 != 0
+        }) {
+            Some(entry) => assert_eq!(entry, (first, first + 1)),
+            None => {
+                assert_eq!(first % 3, 0);
+                assert_eq!(map.pop_first(), Some((first, first + 1)))
+            }
+        }
+        first += 1;
+
+        if !map.is_empty() {
+            let last = end - 1;
+            match map.pop_last_if(|k, _| k % 3 == 0) {
+                Some(entry) => assert_eq!(entry, (last, last)),
+                None => {
+                    assert_ne!(last % 3, 0);
+                    assert_eq!(map.pop_last(), Some((last, last)))
+                }
+            }
+            end -= 1
+        }
+
+        map.btree().validate().expect("validation failed");
+        assert_eq!(map.len(), end - first)
+    }
+
+    assert_eq!(map.pop_first_if(|_, _| true), None);
+    assert_eq!(map.pop_last_if(|_, _| true), None);
+}
+
+#[test]
+pub fn first_last_key() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..1000).collect();
+    keys.shuffle(&mut rng);
+
+    let mut map: Map<usize, usize> = Map::new();
+    let mut reference = std::collections::BTreeMap::new();
+    for key in &keys {
+        map.insert(*key, *key);
+        reference.insert(*key, *key);
+        assert_eq!(map.first_key(), reference.keys().next());
+        assert_eq!(map.last_key(), reference.keys().next_back());
+    }
+
+    keys.shuffle(&mut rng);
+    for (i, key) in keys.iter().enumerate() {
+        if i % 2 == 0 {
+            map.remove(key);
+            reference.remove(key);
+        } else if i % 3 == 0 {
+            map.pop_first();
+            reference.pop_first();
+        } else {
+            map.pop_last();
+            reference.pop_last();
+        }
+
+        assert_eq!(map.first_key(), reference.keys().next());
+        assert_eq!(map.last_key(), reference.keys().next_back());
+    }
+
+    let b = map.split_off(&500);
+    assert_eq!(
+        map.last_key(),
+        reference.range(..500).next_back().map(|(k, _)| k)
+    );
+    assert_eq!(b.first_key(), reference.range(500..).next().map(|(k, _)| k));
+}
+
+#[test]
+pub fn stamped_addresses() {
+    let mut map: Map<usize, usize> = (0..100).map(|i| (i, i)).collect();
+
+    // Generations are not tracked by default.
+    assert_eq!(map.btree().generation(), None);
+    let addr = map.btree().stamp(map.btree().address_of(&50).unwrap());
+    map.remove(&0);
+    assert!(map.btree().is_current(addr));
+
+    map.btree_mut().set_generations(true);
+    let addr = map.btree().stamp(map.btree().address_of(&50).unwrap());
+    assert_eq!(map.btree().item_stamped(addr).unwrap().key, 50);
+
+    // Modifying items in place keeps addresses valid.
+    map.insert(50, 0);
+    *map.get_mut(&60).unwrap() = 0;
+    assert!(map.btree().is_current(addr));
+    map.btree_mut().item_mut_stamped(addr).unwrap().value = 500;
+    assert_eq!(map[&50], 500);
+
+    map.remove(&0);
+    assert!(map.btree().is_current(addr));
+    map.remove(&1);
+    assert!(!map.btree().is_current(addr));
+    assert!(map.btree().item_stamped(addr).is_none());
+    assert!(map.btree_mut().item_mut_stamped(addr).is_none());
+
+    // Generations are never shared between trees.
+    let addr = map.btree().stamp(map.btree().address_of(&50).unwrap());
+    let mut other: Map<usize, usize> = Map::new();
+    other.btree_mut().set_generations(true);
+    other.insert(0, 0);
+    std::mem::swap(&mut map, &mut other);
+    assert!(!map.btree().is_current(addr));
+    std::mem::swap(&mut map, &mut other);
+    assert!(map.btree().is_current(addr));
+
+    let clone = map.clone();
+    assert!(clone.btree().has_generations());
+    assert!(!clone.btree().is_current(addr));
+    assert!(clone.btree().item_stamped(addr).is_none());
+    assert!(map.btree().is_current(addr));
+
+    let mut btree = map.into_btree();
+    btree.compact();
+    assert!(btree.has_generations());
+    assert!(!btree.is_current(addr));
+}
+
+#[test]
+pub fn update_panic() {
+    let value = Rc::new(());
+    let mut map: Map<usize, Rc<()>> = (0..100).map(|i| (i, value.clone())).collect();
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        map.update(50, |_| -> (Option<Rc<()>>, ()) { panic!("action panic") })
+    }));
+    assert!(result.is_err());
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.len(), 99);
+    assert!(!map.contains_key(&50));
+    assert_eq!(Rc::strong_count(&value), 100);
+
+    // Vacant entries are left untouched.
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        map.update(50, |_| -> (Option<Rc<()>>, ()) { panic!("action panic") })
+    }));
+    assert!(result.is_err());
+    assert_eq!(map.len(), 99);
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        if let Entry::Occupied(entry) = map.entry(10) {
+            entry.replace_entry_with(|_, _| panic!("action panic"));
+        }
+    }));
+    assert!(result.is_err());
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.len(), 98);
+    assert!(!map.contains_key(&10));
+    assert_eq!(Rc::strong_count(&value), 99);
+
+    std::mem::drop(map);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
 #[test]
 pub fn update() {
     let mut map: Map<usize, usize> = Map::new();
@@ -273,3 +1032,1191 @@ const ITEMS: [(usize, usize); 100] = [
     (1553, 5964),
     (4493, 3677),
 ];
+
+fn insert_remove_with_order<const M: usize>(order: usize) {
+    let mut map: AugmentedMap<usize, usize, (), M> =
+        AugmentedMap::from_btree(AugmentedMapStorage::with_order(order));
+
+    let mut items = ITEMS;
+    for (key, value) in &items {
+        map.insert(*key, *value);
+        map.btree().validate().expect("validation failed")
+    }
+
+    let mut rng = SmallRng::from_seed(*SEED);
+    items.shuffle(&mut rng);
+
+    for (key, _) in &items {
+        map.remove(key);
+        map.btree().validate().expect("validation failed")
+    }
+
+    assert!(map.is_empty())
+}
+
+#[test]
+pub fn orders() {
+    insert_remove_with_order::<4>(4);
+    insert_remove_with_order::<5>(5);
+    insert_remove_with_order::<32>(32);
+}
+
+#[test]
+pub fn runtime_orders() {
+    insert_remove_with_order::<8>(4);
+    insert_remove_with_order::<8>(5);
+    insert_remove_with_order::<8>(16);
+}
+
+#[test]
+pub fn compact_ids() {
+    let mut map: CompactMap<usize, usize> = CompactMap::new();
+
+    let mut items = ITEMS;
+    for (key, value) in &items {
+        map.insert(*key, *value);
+        map.btree().validate().expect("validation failed")
+    }
+
+    let mut rng = SmallRng::from_seed(*SEED);
+    items.shuffle(&mut rng);
+
+    for (key, _) in &items {
+        map.remove(key);
+        map.btree().validate().expect("validation failed")
+    }
+
+    assert!(map.is_empty())
+}
+
+fn split_off_with_order<const M: usize>(order: usize) {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..500).map(|i| i * 2).collect();
+
+    for split_key in (0..1002).step_by(7) {
+        keys.shuffle(&mut rng);
+        let mut map: AugmentedMap<usize, usize, (), M> =
+            AugmentedMap::from_btree(AugmentedMapStorage::with_order(order));
+        for key in &keys {
+            map.insert(*key, *key);
+        }
+
+        let other = map.split_off(&split_key);
+        map.btree().validate().expect("validation failed");
+        other.btree().validate().expect("validation failed");
+
+        assert_eq!(other.btree().order(), order);
+        assert_eq!(map.len() + other.len(), keys.len());
+        assert_eq!(map.len(), map.iter().count());
+        assert_eq!(other.len(), other.iter().count());
+        assert!(map.keys().all(|key| *key < split_key));
+        assert!(other.keys().all(|key| *key >= split_key));
+    }
+}
+
+#[test]
+pub fn split_off() {
+    split_off_with_order::<8>(4);
+    split_off_with_order::<5>(5);
+    split_off_with_order::<8>(8);
+}
+
+fn split_off_range_with_order<const M: usize>(order: usize) {
+    use std::ops::{Bound, RangeBounds};
+
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..500).map(|i| i * 2).collect();
+
+    for start in (0..1002).step_by(61) {
+        for end in (start..1002).step_by(73) {
+            let bounds = [
+                (Bound::Included(start), Bound::Excluded(end)),
+                (Bound::Excluded(start), Bound::Included(end)),
+                (Bound::Unbounded, Bound::Included(end)),
+                (Bound::Included(start), Bound::Unbounded),
+            ];
+
+            for range in bounds {
+                if start == end && range == (Bound::Excluded(start), Bound::Included(end)) {
+                    continue;
+                }
+
+                keys.shuffle(&mut rng);
+                let mut map: AugmentedMap<usize, usize, (), M> =
+                    AugmentedMap::from_btree(AugmentedMapStorage::with_order(order));
+                for key in &keys {
+                    map.insert(*key, *key);
+                }
+
+                let other = map.split_off_range(range);
+                map.btree().validate().expect("validation failed");
+                other.btree().validate().expect("validation failed");
+
+                assert_eq!(other.btree().order(), order);
+                assert_eq!(map.len() + other.len(), keys.len());
+                assert_eq!(map.len(), map.iter().count());
+                assert_eq!(other.len(), other.iter().count());
+                assert!(map.keys().all(|key| !range.contains(key)));
+                assert!(other.keys().all(|key| range.contains(key)));
+            }
+        }
+    }
+}
+
+#[test]
+pub fn split_off_range() {
+    split_off_range_with_order::<8>(4);
+    split_off_range_with_order::<5>(5);
+}
+
+fn append_ranges(a: std::ops::Range<usize>, b: std::ops::Range<usize>) {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut a_keys: Vec<usize> = a.collect();
+    let mut b_keys: Vec<usize> = b.collect();
+    a_keys.shuffle(&mut rng);
+    b_keys.shuffle(&mut rng);
+
+    let mut map: Map<usize, usize> = Map::new();
+    let mut other: Map<usize, usize> = Map::new();
+    let mut expected = std::collections::BTreeMap::new();
+    for key in &a_keys {
+        map.insert(*key, *key);
+        expected.insert(*key, *key);
+    }
+    for key in &b_keys {
+        other.insert(*key, *key + 1);
+        expected.insert(*key, *key + 1);
+    }
+
+    map.append(&mut other);
+    map.btree().validate().expect("validation failed");
+    other.btree().validate().expect("validation failed");
+
+    assert!(other.is_empty());
+    assert_eq!(map.len(), expected.len());
+    assert!(map.iter().eq(expected.iter()))
+}
+
+#[test]
+pub fn append() {
+    for (a, b) in &[
+        (0, 0),
+        (0, 1),
+        (1, 0),
+        (1, 1),
+        (5, 300),
+        (300, 5),
+        (300, 300),
+    ] {
+        // Disjoint ranges.
+        append_ranges(0..*a, *a..(a + b));
+        append_ranges(*b..(a + b), 0..*b);
+
+        // Overlapping ranges.
+        append_ranges(0..*a, (a / 2)..(a / 2 + b));
+    }
+}
+
+fn merge_ranges(a: std::ops::Range<usize>, b: std::ops::Range<usize>) {
+    let mut map: Map<usize, usize> = a.clone().map(|i| (i, i)).collect();
+    let other: Map<usize, usize> = b.clone().map(|i| (i, i + 1)).collect();
+
+    let mut expected: std::collections::BTreeMap<usize, usize> = a.map(|i| (i, i)).collect();
+    for key in b {
+        match expected.remove(&key) {
+            Some(value) if key % 2 == 0 => {
+                expected.insert(key, value + key + 1);
+            }
+            Some(_) => (),
+            None => {
+                expected.insert(key, key + 1);
+            }
+        }
+    }
+
+    map.merge_with(other, |key, left, right| {
+        assert_eq!(left + 1, right);
+        if key % 2 == 0 {
+            Some(left + right)
+        } else {
+            None
+        }
+    });
+    map.btree().validate().expect("validation failed");
+
+    assert_eq!(map.len(), expected.len());
+    assert!(map.iter().eq(expected.iter()))
+}
+
+#[test]
+pub fn merge_with() {
+    for (a, b) in &[
+        (0, 0),
+        (0, 1),
+        (1, 0),
+        (1, 1),
+        (5, 300),
+        (300, 5),
+        (300, 300),
+    ] {
+        // Disjoint ranges.
+        merge_ranges(0..*a, *a..(a + b));
+        merge_ranges(*b..(a + b), 0..*b);
+
+        // Overlapping ranges.
+        merge_ranges(0..*a, (a / 2)..(a / 2 + b));
+    }
+}
+
+#[test]
+pub fn append_mismatched_orders() {
+    let small = |range: std::ops::Range<usize>| {
+        let mut map = Map::from_btree(MapStorage::with_order(4));
+        for i in range {
+            map.insert(i, i);
+        }
+        map
+    };
+    let large =
+        |range: std::ops::Range<usize>| -> Map<usize, usize> { range.map(|i| (i, i)).collect() };
+
+    for (a, b) in &[(0..3, 100..400), (300..400, 0..3), (0..300, 300..305)] {
+        let mut map = small(a.clone());
+        map.append(&mut large(b.clone()));
+        map.btree().validate().expect("validation failed");
+        assert_eq!(map.btree().order(), 4);
+        assert!(map.keys().cloned().eq(a
+            .clone()
+            .chain(b.clone())
+            .collect::<std::collections::BTreeSet<_>>()));
+
+        let mut map = large(a.clone());
+        map.append(&mut small(b.clone()));
+        map.btree().validate().expect("validation failed");
+        assert_eq!(map.len(), a.len() + b.len());
+
+        let mut map = small(a.clone());
+        map.merge_with(large(b.clone()), |_, left, _| Some(left));
+        map.btree().validate().expect("validation failed");
+        assert_eq!(map.len(), a.len() + b.len());
+
+        let mut map = large(a.clone());
+        map.merge_with(small(b.clone()), |_, left, _| Some(left));
+        map.btree().validate().expect("validation failed");
+        assert_eq!(map.len(), a.len() + b.len());
+    }
+}
+
+fn from_sorted_iter_with_order<const M: usize>(order: usize) {
+    for len in (0..200).chain((200..2000).step_by(97)) {
+        let mut storage = AugmentedMapStorage::with_order(order);
+        storage.build_from_sorted((0..len).map(|i| Inserted(i * 2, i)));
+        let mut map: AugmentedMap<usize, usize, (), M> = AugmentedMap::from_btree(storage);
+
+        map.btree().validate().expect("validation failed");
+        assert_eq!(map.len(), len);
+        assert!(map
+            .iter()
+            .map(|(k, v)| (*k, *v))
+            .eq((0..len).map(|i| (i * 2, i))));
+
+        for i in 0..len {
+            map.insert(i * 2 + 1, i);
+        }
+        map.btree().validate().expect("validation failed");
+        assert_eq!(map.len(), len * 2);
+    }
+}
+
+#[test]
+pub fn from_sorted_iter() {
+    from_sorted_iter_with_order::<8>(4);
+    from_sorted_iter_with_order::<5>(5);
+    from_sorted_iter_with_order::<8>(8);
+
+    let map: Map<usize, usize> = Map::from_sorted_iter(
+        ITEMS
+            .iter()
+            .map(|(k, _)| (*k, *k))
+            .collect::<std::collections::BTreeMap<_, _>>(),
+    );
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.len(), 100)
+}
+
+#[test]
+pub fn visit_nodes() {
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for len in [0, 1, 10, 1000] {
+        let mut map: Map<usize, usize> = Map::new();
+        for _ in 0..len {
+            let key = rng.gen_range(0, 10000);
+            map.insert(key, key);
+        }
+
+        let mut depth_first = Vec::new();
+        let mut leaf_depths = Vec::new();
+        let mut item_count = 0;
+        let _ = map
+            .btree()
+            .visit_nodes(Traversal::DepthFirst, |node| -> ControlFlow<()> {
+                match node.parent() {
+                    Some(parent) => assert!(depth_first
+                        .iter()
+                        .any(|(id, depth)| { *id == parent && *depth + 1 == node.depth() })),
+                    None => assert_eq!(node.depth(), 0),
+                }
+
+                if node.is_leaf() {
+                    assert_eq!(node.child_count(), 0);
+                    leaf_depths.push(node.depth())
+                } else {
+                    assert_eq!(node.children().count(), node.item_count() + 1)
+                }
+
+                item_count += node.item_count();
+                depth_first.push((node.id(), node.depth()));
+                ControlFlow::Continue(())
+            });
+
+        assert_eq!(item_count, map.len());
+        assert!(leaf_depths.windows(2).all(|w| w[0] == w[1]));
+
+        let mut breadth_first = Vec::new();
+        let _ = map
+            .btree()
+            .visit_nodes(Traversal::BreadthFirst, |node| -> ControlFlow<()> {
+                breadth_first.push((node.id(), node.depth()));
+                ControlFlow::Continue(())
+            });
+
+        assert!(breadth_first.windows(2).all(|w| w[0].1 <= w[1].1));
+        let mut sorted_depth_first = depth_first.clone();
+        sorted_depth_first.sort_unstable();
+        breadth_first.sort_unstable();
+        assert_eq!(sorted_depth_first, breadth_first);
+
+        // Early termination.
+        let mut visited = 0;
+        let result = map.btree().visit_nodes(Traversal::BreadthFirst, |node| {
+            visited += 1;
+            if node.is_leaf() {
+                ControlFlow::Break(node.id())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+
+        match result {
+            ControlFlow::Break(id) => {
+                assert!(map.btree().node(id).is_some());
+                assert!(visited <= depth_first.len())
+            }
+            ControlFlow::Continue(()) => assert!(map.is_empty()),
+        }
+    }
+}
+
+#[test]
+pub fn addresses() {
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for len in [0, 1, 10, 1000] {
+        let mut map: Map<usize, usize> = Map::new();
+        for _ in 0..len {
+            let key = rng.gen_range(0, 10000);
+            map.insert(key, key * 2);
+        }
+
+        let btree = map.btree();
+        assert_eq!(btree.addresses().len(), map.len());
+        for (addr, binding) in btree.addresses() {
+            assert!(btree.is_item_address(addr));
+            assert_eq!(btree.address_of(&binding.key), Ok(addr));
+            assert_eq!(btree.item(addr).map(|b| b.key), Some(binding.key))
+        }
+
+        let forward: Vec<_> = btree.addresses().map(|(addr, _)| addr).collect();
+        let mut backward: Vec<_> = btree.addresses().rev().map(|(addr, _)| addr).collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+
+        let mut addresses = btree.addresses();
+        let mut both_ends = Vec::new();
+        let mut back = Vec::new();
+        loop {
+            let next = if rng.gen::<bool>() {
+                addresses.next().map(|(addr, _)| both_ends.push(addr))
+            } else {
+                addresses.next_back().map(|(addr, _)| back.push(addr))
+            };
+
+            if next.is_none() {
+                break;
+            }
+        }
+
+        both_ends.extend(back.into_iter().rev());
+        assert_eq!(forward, both_ends);
+    }
+}
+
+#[test]
+pub fn finger() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut map: Map<usize, usize> = Map::new();
+    let mut reference = std::collections::BTreeMap::new();
+    let mut finger = Finger::new();
+
+    // Sequential accesses.
+    for i in 0..1000 {
+        assert_eq!(map.insert_with_finger(&mut finger, i, i), None);
+        reference.insert(i, i);
+    }
+    map.btree().validate().expect("validation failed");
+
+    for i in 0..1000 {
+        assert_eq!(map.get_with_finger(&mut finger, &i), Some(&i));
+    }
+
+    // Random accesses, mixed with accesses bypassing the finger.
+    for _ in 0..10000 {
+        let key = rng.gen_range(0, 2000);
+        match rng.gen_range(0, 4) {
+            0 => assert_eq!(
+                map.insert_with_finger(&mut finger, key, key + 1),
+                reference.insert(key, key + 1)
+            ),
+            1 => assert_eq!(
+                map.remove_with_finger(&mut finger, &key),
+                reference.remove(&key)
+            ),
+            2 => assert_eq!(map.remove(&key), reference.remove(&key)),
+            _ => assert_eq!(map.get_with_finger(&mut finger, &key), reference.get(&key)),
+        }
+
+        map.btree().validate().expect("validation failed")
+    }
+
+    assert!(map.iter().eq(reference.iter()));
+
+    // Stale finger.
+    for i in 0..2000 {
+        map.remove(&i);
+    }
+    assert_eq!(map.get_with_finger(&mut finger, &0), None);
+    assert_eq!(map.insert_with_finger(&mut finger, 0, 0), None);
+    assert_eq!(map.get_with_finger(&mut finger, &0), Some(&0));
+}
+
+#[test]
+pub fn compact() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut map: Map<usize, usize> = (0..2000).map(|i| (i, i)).collect();
+
+    let mut keys: Vec<usize> = (0..2000).collect();
+    keys.shuffle(&mut rng);
+    for key in &keys[..1900] {
+        map.remove(key);
+    }
+
+    let mut remaining = keys[1900..].to_vec();
+    remaining.sort_unstable();
+
+    map.shrink_to_fit();
+    map.btree().validate().expect("validation failed");
+    assert!(map.keys().copied().eq(remaining.iter().copied()));
+
+    let mut node_count = 0;
+    let mut max_id = 0;
+    let _: ControlFlow<()> = map.btree().visit_nodes(Traversal::DepthFirst, |node| {
+        node_count += 1;
+        max_id = max_id.max(node.id());
+        ControlFlow::Continue(())
+    });
+    assert_eq!(max_id + 1, node_count);
+
+    // The compacted map is still usable.
+    for i in 2000..3000 {
+        map.insert(i, i);
+    }
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.len(), 1100);
+}
+
+#[test]
+pub fn with_capacity() {
+    let mut map: Map<usize, usize> = Map::with_capacity(1000);
+    for i in 0..1000 {
+        map.insert(i, i);
+    }
+
+    map.reserve(10000);
+    for i in 0..10000 {
+        map.insert(1000 + i, i);
+    }
+
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.len(), 11000);
+
+    let mut augmented: AugmentedMap<usize, usize, generic_btree::augment::Count> =
+        AugmentedMap::with_capacity(100);
+    for i in 0..100 {
+        augmented.insert(i, i);
+    }
+    augmented.btree().validate().expect("validation failed");
+}
+
+/// Builds a degenerate tree made of a chain of `depth` binary internal nodes.
+///
+/// Such a tree is not balanced, but it is deep enough to overflow the stack
+/// of any recursive traversal.
+fn degenerate_tree(depth: usize) -> MapStorage<usize, usize> {
+    let mut btree = MapStorage::default();
+    let mut id = btree.allocate_node(Buffer::leaf(None, Binding::new(0, 0)));
+    for i in 1..depth {
+        let leaf_id = btree.allocate_node(Buffer::leaf(None, Binding::new(2 * i, 0)));
+        id = btree.allocate_node(Buffer::binary(
+            None,
+            id,
+            Binding::new(2 * i - 1, 0),
+            leaf_id,
+        ));
+    }
+
+    btree.set_root(Some(id));
+    btree.set_len(2 * depth - 1);
+    btree
+}
+
+#[test]
+pub fn deep_clear() {
+    const DEPTH: usize = 100_000;
+
+    let mut btree = degenerate_tree(DEPTH);
+    btree.clear();
+    assert!(btree.is_empty());
+    assert_eq!(btree.root(), None);
+
+    let mut btree = degenerate_tree(DEPTH);
+    btree.forget_all();
+    assert!(btree.is_empty());
+    assert_eq!(btree.root(), None);
+}
+
+#[test]
+pub fn node_pool() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut map: Map<usize, usize> = Map::new();
+    let mut keys: Vec<usize> = (0..2000).collect();
+
+    for _ in 0..3 {
+        keys.shuffle(&mut rng);
+        for key in &keys {
+            map.insert(*key, *key);
+        }
+
+        keys.shuffle(&mut rng);
+        for key in &keys[..1500] {
+            map.remove(key);
+        }
+
+        map.btree().validate().expect("validation failed");
+    }
+
+    let stats = map.btree().pool_stats();
+    assert!(stats.hits > 0);
+    assert!(stats.pooled <= stats.capacity);
+
+    map.btree_mut().trim();
+    assert_eq!(map.btree().pool_stats().pooled, 0);
+
+    map.btree_mut().set_pool_capacity(0);
+    let stats = map.btree().pool_stats();
+    for key in &keys {
+        map.remove(key);
+    }
+    assert!(map.is_empty());
+    assert_eq!(map.btree().pool_stats().pooled, 0);
+    assert_eq!(map.btree().pool_stats().hits, stats.hits);
+}
+
+#[test]
+pub fn insert_split_on_descent() {
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for order in 4..12 {
+        let mut map = Map::from_btree(MapStorage::with_order(order));
+        let mut keys: Vec<usize> = (0..1000).collect();
+        keys.shuffle(&mut rng);
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(map.insert(*key, *key), None);
+            if i % 50 == 0 {
+                map.btree().validate().expect("validation failed")
+            }
+        }
+
+        map.btree().validate().expect("validation failed");
+        assert!(map.iter().map(|(k, _)| *k).eq(0..1000));
+
+        // Replacing an item does not modify the structure of the tree.
+        for key in &keys {
+            let addr = map.btree().address_of(key);
+            assert_eq!(map.insert(*key, key + 1), Some(*key));
+            assert_eq!(map.btree().address_of(key), addr);
+        }
+    }
+}
+
+#[test]
+pub fn bulk_remove() {
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for order in &[4, 5, 8, 16] {
+        for ratio in &[10, 50, 90, 100] {
+            let mut map = Map::from_btree(MapStorage::with_order(*order));
+            let mut reference = std::collections::BTreeMap::new();
+            for _ in 0..2000 {
+                let key = rng.gen_range(0, 4000);
+                map.insert(key, key);
+                reference.insert(key, key);
+            }
+
+            let keys: Vec<usize> = (0..4000)
+                .filter(|_| rng.gen_range(0, 100) < *ratio)
+                .collect();
+            let expected = keys
+                .iter()
+                .filter(|key| reference.remove(key).is_some())
+                .count();
+
+            assert_eq!(map.bulk_remove(&keys), expected);
+            map.btree().validate().expect("validation failed");
+            assert_eq!(map.len(), reference.len());
+            assert!(map.iter().eq(reference.iter()));
+
+            // The tree is still usable.
+            for key in 0..100 {
+                map.insert(key, key);
+                reference.insert(key, key);
+            }
+            map.btree().validate().expect("validation failed");
+            assert!(map.iter().eq(reference.iter()));
+        }
+    }
+
+    // Augmentations are recomputed.
+    let mut map: AugmentedMap<usize, usize, generic_btree::augment::Count> =
+        (0..1000).map(|i| (i, i)).collect();
+    let keys: Vec<usize> = (0..1000).filter(|i| i % 7 != 0).collect();
+    map.bulk_remove(&keys);
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.range(0usize..).remaining(), 143);
+}
+
+/// Checks that the leaf sibling links of the tree follow the order of the leaves.
+fn check_leaf_links(btree: &MapStorage<usize, usize>) {
+    let mut leaves = Vec::new();
+    let mut stack: Vec<usize> = btree.root().into_iter().collect();
+    while let Some(id) = stack.pop() {
+        let node = btree.node(id).unwrap();
+        if node.is_internal() {
+            stack.extend(
+                (0..node.child_count())
+                    .rev()
+                    .map(|i| node.child_id(i).unwrap()),
+            )
+        } else {
+            leaves.push(id)
+        }
+    }
+
+    for (i, id) in leaves.iter().enumerate() {
+        let links = btree.leaf_links(*id).expect("missing leaf links");
+        assert_eq!(links.previous, i.checked_sub(1).map(|i| leaves[i]));
+        assert_eq!(links.next, leaves.get(i + 1).copied());
+    }
+}
+
+#[test]
+pub fn leaf_links() {
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for order in &[4, 5, 8] {
+        let mut btree = MapStorage::with_order(*order);
+        btree.set_leaf_links(true);
+        let mut map = Map::from_btree(btree);
+        let mut reference = std::collections::BTreeMap::new();
+
+        for _ in 0..20 {
+            for _ in 0..200 {
+                let key = rng.gen_range(0, 1000);
+                if rng.gen_range(0, 3) == 0 {
+                    assert_eq!(map.remove(&key), reference.remove(&key));
+                } else {
+                    assert_eq!(map.insert(key, key), reference.insert(key, key));
+                }
+            }
+
+            map.btree().validate().expect("validation failed");
+            check_leaf_links(map.btree());
+            assert!(map.iter().eq(reference.iter()));
+            assert!(map.iter().rev().eq(reference.iter().rev()));
+            assert!(map.range(100..900).eq(reference.range(100..900)));
+            assert!(map
+                .range(100..900)
+                .rev()
+                .eq(reference.range(100..900).rev()));
+        }
+
+        let mut other = map.split_off(&500);
+        let mut reference_other = reference.split_off(&500);
+        check_leaf_links(map.btree());
+        check_leaf_links(other.btree());
+        assert!(other.iter().eq(reference_other.iter()));
+
+        other.retain(|key, _| key % 2 == 0);
+        reference_other.retain(|key, _| key % 2 == 0);
+        check_leaf_links(other.btree());
+
+        map.append(&mut other);
+        reference.append(&mut reference_other);
+        map.btree().validate().expect("validation failed");
+        check_leaf_links(map.btree());
+        assert!(map.iter().eq(reference.iter()));
+        assert!(map.iter().rev().eq(reference.iter().rev()));
+    }
+}
+
+#[test]
+pub fn compare_by() {
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for _ in 0..100 {
+        let a: std::collections::BTreeMap<usize, usize> = (0..rng.gen_range(0, 50))
+            .map(|_| (rng.gen_range(0, 20), rng.gen_range(0, 3)))
+            .collect();
+        let b: std::collections::BTreeMap<usize, usize> = (0..rng.gen_range(0, 50))
+            .map(|_| (rng.gen_range(0, 20), rng.gen_range(0, 3)))
+            .collect();
+
+        let map_a: Map<usize, usize> = a.iter().map(|(k, v)| (*k, *v)).collect();
+        let map_b: Map<usize, usize> = b.iter().map(|(k, v)| (*k, *v)).collect();
+        let map_c: Map<u64, String> = b.iter().map(|(k, v)| (*k as u64, v.to_string())).collect();
+
+        assert_eq!(map_a == map_b, a == b);
+        assert_eq!(map_a.partial_cmp(&map_b), a.partial_cmp(&b));
+        assert_eq!(map_a.cmp(&map_b), a.cmp(&b));
+
+        let eq = map_a.btree().eq_by(map_c.btree(), |x, y| {
+            let ((k1, v1), (k2, v2)) = (x.as_pair(), y.as_pair());
+            *k1 as u64 == *k2 && v1.to_string() == *v2
+        });
+        assert_eq!(eq, a == b);
+
+        let ordering = map_a.btree().cmp_by(map_c.btree(), |x, y| {
+            let ((k1, v1), (k2, v2)) = (x.as_pair(), y.as_pair());
+            (*k1 as u64, v1.to_string()).cmp(&(*k2, v2.clone()))
+        });
+        assert_eq!(ordering, a.cmp(&b));
+    }
+}
+
+#[test]
+pub fn validation_report() {
+    let map: Map<usize, usize> = Map::from_btree(MapStorage::with_order(4));
+    let mut btree = map.into_btree();
+    for i in 0..1000 {
+        btree.insert(Inserted(i, i));
+    }
+
+    let report = btree.validation_report();
+    assert!(report.is_valid());
+    assert_eq!(report.item_count, 1000);
+    let (min_depth, max_depth) = report.leaf_depths.unwrap();
+    assert_eq!(min_depth, max_depth);
+    assert_eq!(max_depth + 1, btree.height());
+
+    // Break the order of two items in different leaves,
+    // and the parent of some node.
+    let first = btree.first_item_address().unwrap();
+    let last = btree.last_item_address().unwrap();
+    btree.item_mut(first).unwrap().key = 2000;
+    btree.item_mut(last).unwrap().key = 0;
+    let root_id = btree.root().unwrap();
+    let child_id = btree.node(root_id).unwrap().child_id(1).unwrap();
+    btree.node_mut(child_id).unwrap().set_parent(None);
+
+    let report = btree.validation_report();
+    assert!(!report.is_valid());
+    assert_eq!(report.issues.len(), 3);
+    assert_eq!(report.issues[0].node(), first.id);
+    assert_eq!(report.issues[0].depth(), max_depth);
+    assert_eq!(report.issues[1].path, vec![root_id, child_id]);
+    assert_eq!(report.issues[2].node(), last.id);
+    assert!(report.issues.iter().all(|issue| issue.path[0] == root_id));
+    assert!(btree.validate().is_err());
+}
+
+#[test]
+pub fn stats() {
+    let map: Map<usize, usize> = Map::new();
+    let stats = map.btree().stats();
+    assert_eq!(stats.height, 0);
+    assert_eq!(stats.node_count(), 0);
+    assert_eq!(stats.bytes, None);
+    assert_eq!(stats.occupancy(), 0.0);
+
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut map: Map<usize, usize> = Map::from_btree(MapStorage::with_order(8));
+    for _ in 0..1000 {
+        let i = rng.gen_range(0, 2000);
+        map.insert(i, i);
+    }
+
+    let stats = map.btree().stats();
+    assert_eq!(stats.items, map.len());
+    assert_eq!(stats.height, map.btree().height());
+    assert_eq!(stats.levels.len(), stats.height);
+    assert_eq!(stats.levels[0].nodes, 1);
+    assert_eq!(
+        stats.levels.last().unwrap().nodes,
+        map.btree().leaves().count()
+    );
+    assert_eq!(stats.leaf_nodes, stats.levels.last().unwrap().nodes);
+    assert_eq!(
+        stats.node_count(),
+        stats.levels.iter().map(|level| level.nodes).sum::<usize>()
+    );
+    assert_eq!(stats.capacity, stats.node_count() * 7);
+    assert!(stats.occupancy() >= 0.5 && stats.occupancy() <= 1.0);
+    assert!(stats.average_items() >= 3.0 && stats.average_items() <= 7.0);
+    assert!(stats.bytes.unwrap() >= stats.items * std::mem::size_of::<(usize, usize)>());
+}
+
+#[test]
+pub fn leak_report() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut map: Map<usize, usize> = Map::from_btree(MapStorage::with_order(4));
+    for _ in 0..1000 {
+        let i = rng.gen_range(0, 2000);
+        map.insert(i, i);
+    }
+
+    let mut other: Map<usize, usize> = (500..1500).map(|i| (i, i)).collect();
+    map.merge_with(other.split_off(&1000), |_, a, b| Some(a + b));
+    map.append(&mut other);
+    map.retain(|key, _| key % 3 != 0);
+    let mut rest = map.split_off(&1200);
+    rest.retain(|key, _| key % 2 == 0);
+
+    let report = map.btree().leak_report();
+    assert!(report.is_clean(), "{:?}", report);
+    assert_eq!(report.reachable, map.btree().stats().node_count());
+    assert_eq!(report.occupied, report.reachable + report.pooled);
+    assert!(rest.btree().leak_report().is_clean());
+
+    // Allocate a node that is not attached to the tree.
+    let mut btree = map.into_btree();
+    btree.set_pool_capacity(0);
+    let leaked_id = btree.allocate_node(Buffer::leaf(None, Binding::new(0, 0)));
+    let report = btree.leak_report();
+    assert!(!report.is_clean());
+    assert_eq!(report.leaked, Some(vec![leaked_id]));
+    assert_eq!(report.leaked_count(), 1);
+    assert!(report.dangling.is_empty());
+
+    // Release a leaf still referenced by its parent.
+    let leaf_id = btree.first_item_address().unwrap().id;
+    btree.release_node(leaf_id);
+    let report = btree.leak_report();
+    assert_eq!(report.leaked, Some(vec![leaked_id]));
+    assert_eq!(report.dangling, vec![leaf_id]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+pub fn validation_error_display() {
+    let map: Map<usize, usize> = (0..100).map(|i| (i, i)).collect();
+    let mut btree = map.into_btree();
+
+    let addr = btree.first_item_address().unwrap();
+    btree.item_mut(addr).unwrap().key = 1000;
+    let error = btree.validate().unwrap_err();
+
+    assert_eq!(error.node(), Some(addr.id));
+    let expected = btree.next_item_address(addr).unwrap();
+    assert_eq!(expected.id, addr.id);
+    assert_eq!(error.address(), Some(expected));
+    assert_eq!(
+        error.to_string(),
+        format!("item {} is smaller than its predecessor", expected)
+    );
+
+    let boxed: Box<dyn std::error::Error> = Box::new(error);
+    assert!(boxed.to_string().starts_with("item @"));
+
+    #[cfg(feature = "dot")]
+    {
+        let mut dot = Vec::new();
+        error.render_dot(&btree, &mut dot).unwrap();
+        let dot = String::from_utf8(dot).unwrap();
+        assert!(dot.starts_with("digraph tree {"));
+        assert!(dot.contains("{<bad> 1|1}"));
+        assert!(dot.contains(&format!("error -> n{}:bad", addr.id)));
+        assert!(dot.contains("color=red"));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Modification {
+    Split(usize, usize, Position),
+    Merge(usize, usize, Position),
+    Rotate(usize, usize),
+    Move(Address, Address),
+}
+
+struct Recorder(Arc<Mutex<Vec<Modification>>>);
+
+impl Observer for Recorder {
+    fn on_split(&mut self, id: usize, right: usize, median: Position) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(Modification::Split(id, right, median))
+    }
+
+    fn on_merge(&mut self, left: usize, right: usize, separator: Position) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(Modification::Merge(left, right, separator))
+    }
+
+    fn on_rotate(&mut self, from: usize, to: usize) {
+        self.0.lock().unwrap().push(Modification::Rotate(from, to))
+    }
+
+    fn on_move(&mut self, from: Address, to: Address) {
+        self.0.lock().unwrap().push(Modification::Move(from, to))
+    }
+}
+
+#[test]
+pub fn observer() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let modifications = Arc::new(Mutex::new(Vec::new()));
+    let mut btree = MapStorage::default();
+    btree.set_observer(Some(Box::new(Recorder(modifications.clone()))));
+    let mut map: Map<usize, usize> = Map::from_btree(btree);
+
+    let mut keys: Vec<usize> = (0..1000).collect();
+    keys.shuffle(&mut rng);
+    for key in &keys {
+        map.insert(*key, *key);
+    }
+
+    keys.shuffle(&mut rng);
+    for key in &keys[..900] {
+        map.remove(key);
+    }
+
+    map.btree().validate().expect("validation failed");
+    let modifications = modifications.lock().unwrap();
+    let mut counts = [0; 4];
+    let mut iter = modifications.iter();
+    while let Some(m) = iter.next() {
+        match *m {
+            Modification::Split(id, right, median) => {
+                counts[0] += 1;
+                assert_ne!(id, right);
+                // The median item moves to the parent.
+                assert!(matches!(
+                    iter.next(),
+                    Some(Modification::Move(from, _)) if *from == Address::new(id, median)
+                ))
+            }
+            Modification::Merge(left, right, separator) => {
+                counts[1] += 1;
+                assert_ne!(left, right);
+                // The separator item moves down from the parent.
+                assert!(matches!(
+                    iter.next(),
+                    Some(Modification::Move(_, to)) if *to == Address::new(left, separator)
+                ))
+            }
+            Modification::Rotate(from, to) => {
+                counts[2] += 1;
+                // The pivot moves to the deficient node, and is replaced by an item of the sibling.
+                assert!(matches!(iter.next(), Some(Modification::Move(_, t)) if t.id == to));
+                assert!(matches!(iter.next(), Some(Modification::Move(f, _)) if f.id == from));
+            }
+            Modification::Move(from, to) => {
+                // An item removed from an internal node is replaced by its predecessor.
+                counts[3] += 1;
+                assert_ne!(from.id, to.id)
+            }
+        }
+    }
+
+    assert!(counts.iter().all(|count| *count > 0));
+}
+
+/// Observer following the address of a single item.
+struct Tracker(Arc<Mutex<Address>>);
+
+impl Observer for Tracker {
+    fn on_split(&mut self, id: usize, right: usize, median: Position) {
+        let mut tracked = self.0.lock().unwrap();
+        if tracked.id == id && tracked.offset > median {
+            *tracked = Address::new(right, tracked.offset - median - 1)
+        }
+    }
+
+    fn on_merge(&mut self, left: usize, right: usize, separator: Position) {
+        let mut tracked = self.0.lock().unwrap();
+        if tracked.id == right {
+            *tracked = Address::new(left, separator + 1 + tracked.offset)
+        }
+    }
+
+    fn on_move(&mut self, from: Address, to: Address) {
+        let mut tracked = self.0.lock().unwrap();
+        if *tracked == from {
+            *tracked = to
+        }
+    }
+}
+
+#[test]
+pub fn observer_tracks_moves() {
+    let tracked = Arc::new(Mutex::new(Address::nowhere()));
+    let mut btree = MapStorage::default();
+    btree.set_capacity_policy(Some(Arc::new(Lazy(1)))).unwrap();
+    btree.set_observer(Some(Box::new(Tracker(tracked.clone()))));
+    let mut map: Map<usize, usize> = Map::from_btree(btree);
+    for i in 0..1000 {
+        map.insert(i, i);
+    }
+
+    // Keys held by internal nodes, replaced by their predecessor on removal.
+    let internal_keys: Vec<usize> = (1..1000)
+        .filter(|key| {
+            let addr = map.btree().address_of(key).unwrap();
+            map.btree().node(addr.id).unwrap().child_count() > 0
+        })
+        .collect();
+    assert!(internal_keys.len() > 4);
+
+    for (i, key) in internal_keys.iter().enumerate() {
+        let predecessor = key - 1;
+        *tracked.lock().unwrap() = map.btree().address_of(&predecessor).unwrap();
+        if i % 2 == 0 {
+            map.remove(key);
+        } else {
+            map.btree_mut().bulk_remove(std::iter::once(key));
+        }
+
+        assert_eq!(
+            *tracked.lock().unwrap(),
+            map.btree().address_of(&predecessor).unwrap()
+        );
+    }
+
+    map.btree().validate().expect("validation failed");
+}
+
+#[test]
+pub fn observer_tracks_split_moves() {
+    for at in (1..1000).step_by(37) {
+        let tracked = Arc::new(Mutex::new(Address::nowhere()));
+        let mut btree = MapStorage::with_order(8);
+        btree.set_observer(Some(Box::new(Tracker(tracked.clone()))));
+        let mut map: Map<usize, usize> = Map::from_btree(btree);
+        for i in 0..1000 {
+            map.insert(i, i);
+        }
+
+        // The last item of the left part is moved up by the joins.
+        let last = at - 1;
+        *tracked.lock().unwrap() = map.btree().address_of(&last).unwrap();
+        let other = map.split_off(&at);
+        assert_eq!(other.len(), 1000 - at);
+        assert_eq!(
+            *tracked.lock().unwrap(),
+            map.btree().address_of(&last).unwrap()
+        );
+        map.btree().validate().expect("validation failed");
+    }
+}
+
+fn insert_remove_with_policy(policy: Arc<dyn CapacityPolicy>) -> usize {
+    let mut btree: MapStorage<usize, usize> = MapStorage::with_order(8);
+    btree.set_capacity_policy(Some(policy)).unwrap();
+    let mut map = Map::from_btree(btree);
+
+    let mut items = ITEMS;
+    for (key, value) in &items {
+        map.insert(*key, *value);
+        map.btree().validate().expect("validation failed")
+    }
+
+    let mut rng = SmallRng::from_seed(*SEED);
+    items.shuffle(&mut rng);
+
+    let (removed, kept) = items.split_at(items.len() / 2);
+    for (key, _) in removed {
+        map.remove(key);
+        map.btree().validate().expect("validation failed")
+    }
+
+    let result = map
+        .btree()
+        .visit_nodes(Traversal::DepthFirst, |node| -> ControlFlow<()> {
+            let min_capacity = map.btree().node_min_capacity(node.id());
+            assert!((1..=8 / 2 - 1).contains(&min_capacity));
+            ControlFlow::Continue(())
+        });
+    assert_eq!(result, ControlFlow::Continue(()));
+
+    let node_count = map.btree().stats().node_count();
+    let mut expected: Vec<_> = kept.to_vec();
+    expected.sort();
+    assert!(map.iter().map(|(k, v)| (*k, *v)).eq(expected));
+
+    map.retain(|key, _| key % 2 == 0);
+    map.btree().validate().expect("validation failed");
+    node_count
+}
+
+#[test]
+pub fn capacity_policies() {
+    struct TwoThirds;
+
+    impl CapacityPolicy for TwoThirds {
+        fn min_capacity(&self, max_capacity: usize, _default: usize) -> usize {
+            max_capacity * 2 / 3
+        }
+    }
+
+    let lazy = insert_remove_with_policy(Arc::new(Lazy(1)));
+    let quarter = insert_remove_with_policy(Arc::new(Fill::new(1, 4)));
+    let standard = insert_remove_with_policy(Arc::new(generic_btree::policy::Standard));
+
+    assert!(lazy > standard);
+    assert!(quarter >= standard);
+
+    // Out of range policies are rejected.
+    let mut btree: MapStorage<usize, usize> = MapStorage::with_order(8);
+    for policy in [
+        Arc::new(TwoThirds) as Arc<dyn CapacityPolicy>,
+        Arc::new(Fill::new(1, 2)),
+        Arc::new(Lazy(0)),
+        Arc::new(Lazy(4)),
+    ] {
+        let error = btree.set_capacity_policy(Some(policy)).unwrap_err();
+        assert_eq!(error.max_capacity, 8)
+    }
+    assert!(btree.set_capacity_policy(Some(Arc::new(Lazy(3)))).is_ok());
+}
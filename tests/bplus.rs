@@ -0,0 +1,118 @@
+use generic_btree::bplus::{BPlusMap, SeparatorPolicy, SuffixTruncation};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+use std::collections::BTreeMap;
+
+const SEED: &[u8; 16] = b"testseedtestseed";
+
+#[test]
+pub fn insert_remove() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    for order in 4..8 {
+        let mut map = BPlusMap::with_order(order);
+        let mut reference = BTreeMap::new();
+        for _ in 0..2000 {
+            let key: u32 = rng.gen_range(0, 300);
+            if rng.gen_bool(0.6) {
+                let value: u32 = rng.gen();
+                assert_eq!(map.insert(key, value), reference.insert(key, value));
+            } else {
+                assert_eq!(map.remove(&key), reference.remove(&key));
+            }
+
+            map.validate().unwrap();
+            assert_eq!(map.len(), reference.len());
+        }
+
+        assert!(map.iter().eq(reference.iter()));
+        assert!(map.iter().rev().eq(reference.iter().rev()));
+        for key in 0..300 {
+            assert_eq!(map.get(&key), reference.get(&key))
+        }
+
+        let keys: Vec<u32> = reference.keys().copied().collect();
+        for key in keys {
+            assert_eq!(map.remove(&key), reference.remove(&key));
+            map.validate().unwrap()
+        }
+
+        assert!(map.is_empty());
+        assert_eq!(map.iter().next(), None)
+    }
+}
+
+#[test]
+pub fn range() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut map = BPlusMap::new();
+    let mut reference = BTreeMap::new();
+    for _ in 0..500 {
+        let key: u32 = rng.gen_range(0, 1000);
+        map.insert(key, key);
+        reference.insert(key, key);
+    }
+
+    for _ in 0..500 {
+        let a: u32 = rng.gen_range(0, 1100);
+        let b: u32 = rng.gen_range(0, 1100);
+        let (a, b) = if a <= b { (a, b) } else { (b, a) };
+
+        assert!(map.range(a..b).eq(reference.range(a..b)));
+        assert!(map.range(a..=b).eq(reference.range(a..=b)));
+        assert!(map.range(a..).eq(reference.range(a..)));
+        assert!(map.range(..b).rev().eq(reference.range(..b).rev()));
+
+        let mut iter = map.range(a..=b);
+        let mut expected = reference.range(a..=b);
+        loop {
+            let item = if rng.gen() {
+                let item = iter.next();
+                assert_eq!(item, expected.next());
+                item
+            } else {
+                let item = iter.next_back();
+                assert_eq!(item, expected.next_back());
+                item
+            };
+
+            if item.is_none() {
+                break;
+            }
+        }
+    }
+}
+
+#[test]
+pub fn suffix_truncation() {
+    assert_eq!(
+        SuffixTruncation.separator(&b"abcdef".to_vec(), &b"abdxyz".to_vec()),
+        b"abd".to_vec()
+    );
+    assert_eq!(
+        SuffixTruncation.separator(&b"abc".to_vec(), &b"abcd".to_vec()),
+        b"abcd".to_vec()
+    );
+    assert_eq!(
+        SuffixTruncation.separator(&"aè".to_string(), &"aéz".to_string()),
+        "aé".to_string()
+    );
+
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut map = BPlusMap::with_policy(5, SuffixTruncation);
+    let mut reference = BTreeMap::new();
+    for i in 0..2000u32 {
+        let len = rng.gen_range(0, 64);
+        let key: Vec<u8> = (0..len).map(|_| rng.gen_range(b'a', b'e')).collect();
+        if rng.gen_bool(0.7) {
+            assert_eq!(map.insert(key.clone(), i), reference.insert(key, i));
+        } else {
+            assert_eq!(map.remove(&key), reference.remove(&key));
+        }
+
+        map.validate().unwrap();
+    }
+
+    assert!(map.iter().eq(reference.iter()));
+    for key in reference.keys() {
+        assert_eq!(map.get(key), reference.get(key))
+    }
+}
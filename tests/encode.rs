@@ -0,0 +1,75 @@
+#![cfg(feature = "std")]
+
+use generic_btree::{
+    encode::{Cbor, Encode, Encoder, MessagePack},
+    slab::Map,
+};
+
+fn cbor<T: Encode>(value: T) -> Vec<u8> {
+    let mut encoder = Cbor::new(Vec::new());
+    value.encode(&mut encoder).unwrap();
+    encoder.into_inner()
+}
+
+fn msgpack<T: Encode>(value: T) -> Vec<u8> {
+    let mut encoder = MessagePack::new(Vec::new());
+    value.encode(&mut encoder).unwrap();
+    encoder.into_inner()
+}
+
+#[test]
+pub fn values() {
+    assert_eq!(cbor(23u8), [0x17]);
+    assert_eq!(cbor(24u8), [0x18, 0x18]);
+    assert_eq!(cbor(1000u32), [0x19, 0x03, 0xe8]);
+    assert_eq!(cbor(1_000_000u32), [0x1a, 0x00, 0x0f, 0x42, 0x40]);
+    assert_eq!(cbor(-1i32), [0x20]);
+    assert_eq!(cbor(-100i32), [0x38, 0x63]);
+    assert_eq!(cbor(true), [0xf5]);
+    assert_eq!(cbor(None::<u8>), [0xf6]);
+    assert_eq!(cbor(1.5f64), [0xfb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(cbor(vec![1u8, 2]), [0x82, 0x01, 0x02]);
+
+    assert_eq!(msgpack(127u8), [0x7f]);
+    assert_eq!(msgpack(200u8), [0xcc, 0xc8]);
+    assert_eq!(msgpack(1000u32), [0xcd, 0x03, 0xe8]);
+    assert_eq!(msgpack(-1i32), [0xff]);
+    assert_eq!(msgpack(-33i32), [0xd0, 0xdf]);
+    assert_eq!(msgpack(-1000i32), [0xd1, 0xfc, 0x18]);
+    assert_eq!(msgpack(false), [0xc2]);
+    assert_eq!(msgpack(None::<u8>), [0xc0]);
+    assert_eq!(msgpack("a".repeat(40)).len(), 42);
+    assert_eq!(msgpack(vec![1u8, 2]), [0x92, 0x01, 0x02]);
+}
+
+#[test]
+pub fn map() {
+    let map: Map<u32, String> = (0..100).map(|i| (i, i.to_string())).collect();
+
+    let mut encoder = Cbor::new(Vec::new());
+    map.encode(&mut encoder).unwrap();
+    let bytes = encoder.into_inner();
+    assert_eq!(bytes[..2], [0xb8, 100]);
+    assert_eq!(bytes[2..5], [0x00, 0x61, b'0']);
+
+    let mut encoder = MessagePack::new(Vec::new());
+    map.encode(&mut encoder).unwrap();
+    let bytes = encoder.into_inner();
+    assert_eq!(bytes[..3], [0xde, 0x00, 100]);
+    assert_eq!(bytes[3..6], [0x00, 0xa1, b'0']);
+
+    let mut encoder = MessagePack::new(Vec::new());
+    map.encode_with(
+        &mut encoder,
+        |key, e| e.encode_str(&key.to_string()),
+        |value, e| e.encode_bytes(value.as_bytes()),
+    )
+    .unwrap();
+    let bytes = encoder.into_inner();
+    assert_eq!(bytes[3..8], [0xa1, b'0', 0xc4, 0x01, b'0']);
+
+    let empty: Map<u32, u32> = Map::new();
+    let mut encoder = Cbor::new(Vec::new());
+    empty.encode(&mut encoder).unwrap();
+    assert_eq!(encoder.into_inner(), [0xa0]);
+}
@@ -0,0 +1,78 @@
+use generic_btree::{frozen::FrozenMap, slab::Map, Storage};
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng, SeedableRng};
+use std::collections::BTreeMap;
+
+const SEED: &[u8; 16] = b"testseedtestseed";
+
+#[test]
+pub fn lookup() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    for n in 0..200 {
+        let mut keys: Vec<usize> = (0..n).map(|i| i * 2).collect();
+        keys.shuffle(&mut rng);
+
+        let frozen: FrozenMap<usize, usize> = keys.iter().map(|k| (*k, *k + 1)).collect();
+        assert_eq!(frozen.len(), n);
+        for i in 0..(2 * n + 1) {
+            let expected = if i % 2 == 0 && i < 2 * n {
+                Some(i + 1)
+            } else {
+                None
+            };
+            assert_eq!(frozen.get(&i).copied(), expected)
+        }
+
+        assert!(frozen.keys().copied().eq((0..n).map(|i| i * 2)));
+        assert!(frozen.keys().rev().copied().eq((0..n).rev().map(|i| i * 2)));
+        assert_eq!(
+            frozen.first_key_value().map(|(k, _)| *k),
+            keys.iter().min().copied()
+        );
+        assert_eq!(
+            frozen.last_key_value().map(|(k, _)| *k),
+            keys.iter().max().copied()
+        );
+    }
+}
+
+#[test]
+pub fn range() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let reference: BTreeMap<u32, u32> = (0..500).map(|_| (rng.gen_range(0, 1000), 0)).collect();
+    let frozen: FrozenMap<u32, u32> = reference.iter().map(|(k, v)| (*k, *v)).collect();
+
+    for _ in 0..1000 {
+        let a = rng.gen_range(0, 1100);
+        let b = rng.gen_range(a, 1100);
+
+        let range = frozen.range(a..b);
+        assert_eq!(range.len(), reference.range(a..b).count());
+        assert!(range
+            .map(|(k, _)| *k)
+            .eq(reference.range(a..b).map(|(k, _)| *k)));
+        assert!(frozen
+            .range(a..=b)
+            .rev()
+            .map(|(k, _)| *k)
+            .eq(reference.range(a..=b).rev().map(|(k, _)| *k)));
+    }
+}
+
+#[test]
+pub fn conversions() {
+    let mut map = Map::new();
+    for i in 0..1000 {
+        map.insert(i, i.to_string());
+    }
+
+    let frozen: FrozenMap<_, _> = map.into();
+    assert_eq!(frozen.get(&42).map(String::as_str), Some("42"));
+
+    let mut map: Map<_, _> = frozen.into_map();
+    map.btree().validate().expect("validation failed");
+    map.insert(1000, "1000".to_string());
+    assert_eq!(map.len(), 1001);
+
+    let frozen: FrozenMap<_, _> = vec![(1, 'a'), (0, 'b'), (1, 'c')].into_iter().collect();
+    assert!(frozen.into_iter().eq(vec![(0, 'b'), (1, 'c')]))
+}
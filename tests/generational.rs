@@ -0,0 +1,118 @@
+use generic_btree::{
+    augment::Count,
+    generational::{AugmentedMap, Map, MapStorage},
+    node::Position,
+    Augment, Observer, Storage,
+};
+use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
+use std::sync::{Arc, Mutex};
+
+const SEED: &[u8; 16] = b"testseedtestseed";
+
+#[test]
+pub fn insert_remove() {
+    let mut map: AugmentedMap<usize, usize, Count> = AugmentedMap::new();
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..200).collect();
+
+    for _ in 0..3 {
+        keys.shuffle(&mut rng);
+        for key in &keys {
+            assert!(map.insert(*key, key * 2).is_none());
+            map.btree().validate().expect("validation failed");
+            assert_eq!(
+                map.btree().root_augmentation().map(|c| c.0),
+                Some(map.len())
+            )
+        }
+
+        keys.shuffle(&mut rng);
+        for key in &keys[..150] {
+            assert_eq!(map.remove(key), Some(key * 2));
+            map.btree().validate().expect("validation failed");
+            assert_eq!(map.range_count::<usize, _>(..), map.len())
+        }
+
+        for key in &keys[150..] {
+            map.remove(key);
+        }
+
+        assert!(map.is_empty())
+    }
+}
+
+#[test]
+pub fn leak_report() {
+    let mut map: AugmentedMap<usize, usize, Count> = AugmentedMap::new();
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..1000).collect();
+
+    for _ in 0..3 {
+        keys.shuffle(&mut rng);
+        for key in &keys {
+            map.insert(*key, *key);
+        }
+
+        keys.shuffle(&mut rng);
+        for key in &keys[..700] {
+            map.remove(key);
+        }
+
+        let report = map.btree().leak_report();
+        assert!(report.is_clean(), "{:?}", report);
+        assert_eq!(report.leaked, Some(Vec::new()));
+    }
+}
+
+/// Observer recording the merged (released) nodes, and the number of splits.
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<(Vec<usize>, usize)>>);
+
+impl Observer for Recorder {
+    fn on_split(&mut self, _id: usize, _right: usize, _median: Position) {
+        self.0.lock().unwrap().1 += 1
+    }
+
+    fn on_merge(&mut self, _left: usize, right: usize, _separator: Position) {
+        self.0.lock().unwrap().0.push(right)
+    }
+}
+
+#[test]
+pub fn stale_addresses() {
+    let recorder = Recorder::default();
+    let mut btree = MapStorage::default();
+    btree.set_observer(Some(Box::new(recorder.clone())));
+    let mut map: Map<usize, usize> = Map::from_btree(btree);
+    for i in 0..1000 {
+        map.insert(i, i);
+    }
+
+    let addresses: Vec<_> = (0..1000)
+        .map(|i| map.btree().address_of(&i).unwrap())
+        .collect();
+
+    // Merge nodes, then split nodes again.
+    for i in (0..1000).filter(|i| i % 4 != 0) {
+        map.remove(&i);
+    }
+
+    let merges = recorder.0.lock().unwrap().0.clone();
+    recorder.0.lock().unwrap().1 = 0;
+    for i in (0..1000).filter(|i| i % 4 != 0) {
+        map.insert(i, i);
+    }
+
+    assert!(!merges.is_empty());
+    assert!(recorder.0.lock().unwrap().1 > 0);
+    map.btree().validate().expect("validation failed");
+
+    // Addresses of released nodes are rejected.
+    let mut stale = 0;
+    for addr in addresses.iter().filter(|addr| merges.contains(&addr.id)) {
+        assert!(map.btree().item(*addr).is_none());
+        stale += 1
+    }
+
+    assert!(stale > 0)
+}
@@ -1,5 +1,16 @@
-use generic_btree::slab::Map;
-use std::{cell::Cell, rc::Rc};
+use generic_btree::{
+    augment::Count,
+    slab::{AugmentedMap, AugmentedMapStorage, Map},
+    Augmentation, Merged, Storage, StorageMut, SubtreeLen,
+};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+use std::{
+    cell::Cell,
+    ops::{Bound, ControlFlow},
+    rc::Rc,
+};
+
+const SEED: &[u8; 16] = b"testseedtestseed";
 
 #[test]
 pub fn iter() {
@@ -201,3 +212,746 @@ fn retain() {
 
     assert!(map.into_iter().eq(vec![(0, 0), (2, 20), (4, 40), (6, 60)]));
 }
+
+#[test]
+fn try_retain() {
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for len in [0, 10, 500] {
+        let mut map: Map<usize, usize> = (0..len).map(|i| (i, rng.gen_range(0, 10))).collect();
+        let mut reference: std::collections::BTreeMap<usize, usize> =
+            map.iter().map(|(k, v)| (*k, *v)).collect();
+        let threshold = rng.gen_range(0, len + 1);
+
+        let mut visited = 0;
+        let result = map.try_retain(|&k, v| {
+            visited += 1;
+            if k >= threshold {
+                ControlFlow::Break(k)
+            } else {
+                *v += 1;
+                ControlFlow::Continue(*v % 2 == 0)
+            }
+        });
+
+        assert_eq!(visited, (threshold + 1).min(len));
+        if threshold < len {
+            assert_eq!(result, ControlFlow::Break(threshold))
+        } else {
+            assert_eq!(result, ControlFlow::Continue(()))
+        }
+
+        reference.retain(|k, v| {
+            if *k < threshold {
+                *v += 1;
+                *v % 2 == 0
+            } else {
+                true
+            }
+        });
+
+        map.btree().validate().expect("validation failed");
+        assert!(map.iter().map(|(k, v)| (*k, *v)).eq(reference.into_iter()))
+    }
+}
+
+#[test]
+fn cursor_mut() {
+    for len in [0, 3, 500] {
+        cursor_mut_with_len(len)
+    }
+}
+
+fn cursor_mut_with_len(len: u64) {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut map: Map<u64, u64> = (1..=len).map(|i| (i << 20, i)).collect();
+    let mut model: Vec<(u64, u64)> = map.iter().map(|(k, v)| (*k, *v)).collect();
+    let mut pos = 0;
+
+    let mut cursor = map.cursor_front_mut();
+    for _ in 0..5000 {
+        match rng.gen_range(0, 7) {
+            0 => {
+                let entry = cursor.move_next().map(|(k, v)| (*k, *v));
+                assert_eq!(entry, model.get(pos).cloned());
+                if entry.is_some() {
+                    pos += 1
+                }
+            }
+            1 => {
+                let entry = cursor.move_prev().map(|(k, v)| (*k, *v));
+                if pos > 0 {
+                    pos -= 1;
+                    assert_eq!(entry, Some(model[pos]))
+                } else {
+                    assert_eq!(entry, None)
+                }
+            }
+            2 => {
+                if let Some((_, v)) = cursor.peek_next() {
+                    *v += 1;
+                    model[pos].1 += 1
+                }
+            }
+            3 | 4 => {
+                let low = if pos > 0 { model[pos - 1].0 } else { 0 };
+                let high = model.get(pos).map(|(k, _)| *k).unwrap_or(1 << 40);
+                let key = (low + high) / 2;
+                if key > low {
+                    if rng.gen() {
+                        cursor.insert_before(key, 0);
+                        model.insert(pos, (key, 0));
+                        pos += 1
+                    } else {
+                        cursor.insert_after(key, 0);
+                        model.insert(pos, (key, 0));
+                    }
+                }
+            }
+            5 => {
+                let entry = cursor.remove_current();
+                if pos < model.len() {
+                    assert_eq!(entry, Some(model.remove(pos)))
+                } else {
+                    assert_eq!(entry, None)
+                }
+            }
+            _ => {
+                if rng.gen_range(0, 100) == 0 {
+                    while cursor.remove_current().is_some() {}
+                    model.truncate(pos)
+                }
+            }
+        }
+
+        assert_eq!(
+            cursor.peek_prev().map(|(k, v)| (*k, *v)),
+            pos.checked_sub(1).map(|i| model[i])
+        );
+        assert_eq!(
+            cursor.peek_next().map(|(k, v)| (*k, *v)),
+            model.get(pos).cloned()
+        );
+    }
+
+    map.btree().validate().expect("validation failed");
+    assert!(map.into_iter().eq(model))
+}
+
+#[test]
+fn bounds() {
+    let mut map: Map<i32, i32> = (0..500).map(|i| (i * 2, i)).collect();
+    let keys: Vec<i32> = map.keys().cloned().collect();
+
+    let first_above = |pred: &dyn Fn(i32) -> bool| keys.iter().position(|k| pred(*k));
+    for key in -2..1002 {
+        let bounds = [
+            (
+                Bound::Included(&key),
+                first_above(&|k| k >= key),
+                first_above(&|k| k > key),
+            ),
+            (
+                Bound::Excluded(&key),
+                first_above(&|k| k > key),
+                first_above(&|k| k >= key),
+            ),
+        ];
+
+        for (bound, lower, upper) in bounds {
+            let cursor = map.lower_bound(bound);
+            assert_eq!(cursor.peek_next().map(|(k, _)| *k), lower.map(|i| keys[i]));
+            let expected_prev = lower.unwrap_or(keys.len()).checked_sub(1).map(|i| keys[i]);
+            assert_eq!(cursor.peek_prev().map(|(k, _)| *k), expected_prev);
+
+            let cursor = map.upper_bound(bound);
+            assert_eq!(cursor.peek_next().map(|(k, _)| *k), upper.map(|i| keys[i]));
+            let expected_prev = upper.unwrap_or(keys.len()).checked_sub(1).map(|i| keys[i]);
+            assert_eq!(cursor.peek_prev().map(|(k, _)| *k), expected_prev);
+        }
+    }
+
+    assert_eq!(
+        map.lower_bound::<i32>(Bound::Unbounded).peek_next(),
+        Some((&0, &0))
+    );
+    assert_eq!(map.upper_bound::<i32>(Bound::Unbounded).peek_next(), None);
+    assert_eq!(
+        map.upper_bound::<i32>(Bound::Unbounded).peek_prev(),
+        Some((&998, &499))
+    );
+
+    let mut cursor = map.upper_bound_mut(Bound::Excluded(&100));
+    for _ in 0..5 {
+        cursor.move_prev();
+    }
+
+    assert_eq!(cursor.peek_next().map(|(k, _)| *k), Some(90));
+    while cursor.peek_next().map(|(k, _)| *k < 100).unwrap_or(false) {
+        cursor.remove_current();
+    }
+
+    assert_eq!(map.len(), 495);
+    assert_eq!(
+        map.lower_bound(Bound::Included(&90)).peek_next(),
+        Some((&100, &50))
+    );
+    assert_eq!(
+        map.lower_bound(Bound::Included(&90)).peek_prev(),
+        Some((&88, &44))
+    );
+}
+
+#[test]
+fn drain() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    for _ in 0..100 {
+        let mut map: Map<i32, i32> = (0..300).map(|i| (i, i * 10)).collect();
+        let a = rng.gen_range(-10, 310);
+        let b = rng.gen_range(a, 311);
+
+        let drained: Vec<_> = map.drain(a..b).collect();
+        let expected: Vec<_> = (a.max(0)..b.min(300)).map(|i| (i, i * 10)).collect();
+        assert_eq!(drained, expected);
+
+        map.btree().validate().expect("validation failed");
+        assert!(map
+            .into_iter()
+            .eq((0..300).filter(|i| *i < a || *i >= b).map(|i| (i, i * 10))));
+    }
+
+    let mut map: Map<i32, i32> = (0..300).map(|i| (i, i)).collect();
+    let mut drain = map.drain(100..=200);
+    assert_eq!(drain.len(), 101);
+    assert_eq!(drain.next(), Some((100, 100)));
+    std::mem::drop(drain);
+
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.len(), 199);
+    assert!(map.keys().all(|k| *k < 100 || *k > 200))
+}
+
+#[test]
+fn range_keys_values() {
+    let mut map: Map<usize, usize> = (0..100).map(|i| (i * 2, i)).collect();
+
+    assert!(map.range_keys(10..20).cloned().eq((10..20).step_by(2)));
+    assert!(map.range_keys(..=7).rev().cloned().eq([6, 4, 2, 0]));
+    assert!(map.range_values(50..).cloned().eq(25..100));
+    assert!(map
+        .range_values((Bound::Excluded(10), Bound::Included(20)))
+        .rev()
+        .cloned()
+        .eq((6..=10).rev()));
+
+    for value in map.range_values_mut(100..) {
+        *value = 0
+    }
+
+    assert!(map
+        .values()
+        .cloned()
+        .eq((0..50).chain(std::iter::repeat_n(0, 50))));
+    map.btree().validate().expect("validation failed");
+}
+
+#[test]
+fn rev() {
+    let mut map: Map<usize, usize> = (0..100).map(|i| (i, i * 2)).collect();
+
+    assert!(map
+        .iter()
+        .rev()
+        .map(|(k, v)| (*k, *v))
+        .eq((0..100).rev().map(|i| (i, i * 2))));
+    assert!(map.keys().rev().cloned().eq((0..100).rev()));
+    assert!(map
+        .values()
+        .rev()
+        .cloned()
+        .eq((0..100).rev().map(|i| i * 2)));
+    assert!(map.range(10..20).rev().map(|(k, _)| *k).eq((10..20).rev()));
+
+    for (i, value) in map.values_mut().rev().enumerate() {
+        *value = i
+    }
+
+    assert!(map.values().cloned().eq((0..100).rev()));
+
+    for (i, (_, value)) in map.iter_mut().rev().enumerate() {
+        *value = i
+    }
+
+    assert!(map.values().cloned().eq((0..100).rev()));
+
+    for (key, value) in map.range_mut(..50).rev() {
+        *value = *key
+    }
+
+    assert!(map.values().take(50).cloned().eq(0..50));
+    map.btree().validate().expect("validation failed");
+}
+
+#[test]
+fn iter_mut_aliasing() {
+    let mut map: Map<usize, usize> = (0..100).map(|i| (i, i)).collect();
+
+    // Keep every emitted reference alive while others are emitted.
+    let mut values: Vec<&mut usize> = Vec::new();
+    let mut iter = map.values_mut();
+    while let Some(front) = iter.next() {
+        values.push(front);
+        values.extend(iter.next_back());
+    }
+
+    for value in values {
+        *value += 1
+    }
+
+    assert!(map.values().cloned().eq(1..101));
+
+    let mut range = map.range_mut(10..20);
+    assert_eq!(range.remaining(), 10);
+    let first = range.next().unwrap();
+    let last = range.next_back().unwrap();
+    assert_eq!(range.remaining(), 8);
+    std::mem::swap(first.1, last.1);
+    for (_, value) in range {
+        *value = 0
+    }
+
+    assert_eq!(map[&10], 20);
+    assert_eq!(map[&19], 11);
+    assert!(map.range(11..19).all(|(_, v)| *v == 0));
+}
+
+#[test]
+fn both_ends() {
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for len in [0, 1, 10, 500] {
+        let mut map: Map<usize, usize> = (0..len).map(|i| (i, i)).collect();
+        let (start, end) = (len / 4, len * 3 / 4);
+
+        check_both_ends(map.iter().map(|(k, _)| *k), 0, len, &mut rng);
+        check_both_ends(map.keys().cloned(), 0, len, &mut rng);
+        check_both_ends(map.values().cloned(), 0, len, &mut rng);
+        check_both_ends(map.range(start..end).map(|(k, _)| *k), start, end, &mut rng);
+        check_both_ends(map.iter_mut().map(|(k, _)| *k), 0, len, &mut rng);
+        check_both_ends(map.values_mut().map(|v| *v), 0, len, &mut rng);
+        check_both_ends(
+            map.range_mut(start..end).map(|(k, _)| *k),
+            start,
+            end,
+            &mut rng,
+        );
+    }
+}
+
+/// Consumes `iter` from randomly chosen ends, checking that it yields exactly
+/// `start..end` and that the two ends never cross.
+fn check_both_ends<I>(mut iter: I, mut start: usize, mut end: usize, rng: &mut SmallRng)
+where
+    I: DoubleEndedIterator<Item = usize>,
+{
+    while start < end {
+        if rng.gen::<bool>() {
+            assert_eq!(iter.next(), Some(start));
+            start += 1
+        } else {
+            end -= 1;
+            assert_eq!(iter.next_back(), Some(end))
+        }
+    }
+
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None)
+}
+
+#[test]
+fn chunks() {
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for len in [0, 1, 10, 500] {
+        let mut map: Map<usize, usize> = Map::new();
+        for _ in 0..len {
+            let key = rng.gen_range(0, 1000);
+            map.insert(key, key);
+        }
+
+        let mut keys = map.keys();
+        let mut chunk_count = 0;
+        for chunk in map.btree().chunks() {
+            assert!(chunk.len() > 0);
+            if !chunk.is_leaf() {
+                assert_eq!(chunk.len(), 1)
+            }
+
+            let first = &map.btree().item(chunk.address()).unwrap().key;
+            let chunk_keys: Vec<_> = chunk.map(|binding| &binding.key).collect();
+            assert_eq!(chunk_keys[0], first);
+            for key in chunk_keys {
+                assert_eq!(Some(key), keys.next())
+            }
+
+            chunk_count += 1
+        }
+
+        assert_eq!(keys.next(), None);
+        assert!(chunk_count <= map.len())
+    }
+}
+
+#[test]
+fn entries_mut_remove_range() {
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for _ in 0..100 {
+        let len = rng.gen_range(0, 500);
+        let mut map: Map<usize, usize> = (0..len).map(|i| (i * 2, i)).collect();
+        let mut reference: std::collections::BTreeMap<usize, usize> =
+            map.iter().map(|(k, v)| (*k, *v)).collect();
+
+        let skip = rng.gen_range(0, len + 1);
+        let end = rng.gen_range(0, 2 * len + 2);
+        let bound = match rng.gen_range(0, 3) {
+            0 => Bound::Included(end),
+            1 => Bound::Excluded(end),
+            _ => Bound::Unbounded,
+        };
+
+        let mut entries = map.entries_mut();
+        for _ in 0..skip {
+            entries.next();
+        }
+
+        let removed = entries.remove_range(bound.as_ref());
+        let next = entries.next().map(|binding| binding.key);
+        assert_eq!(
+            entries.size_hint().0,
+            len - skip - removed - next.map_or(0, |_| 1)
+        );
+
+        let in_bound = |key: usize| match bound {
+            Bound::Included(end) => key <= end,
+            Bound::Excluded(end) => key < end,
+            Bound::Unbounded => true,
+        };
+
+        let start = reference.keys().nth(skip).copied();
+        let expected: Vec<usize> = match start {
+            Some(start) if in_bound(start) => reference
+                .range((Bound::Included(start), bound))
+                .map(|(k, _)| *k)
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        assert_eq!(removed, expected.len());
+        for key in &expected {
+            reference.remove(key);
+        }
+
+        assert_eq!(next, reference.keys().nth(skip).copied());
+        map.btree().validate().expect("validation failed");
+        assert!(map.iter().map(|(k, v)| (*k, *v)).eq(reference.into_iter()))
+    }
+}
+
+#[test]
+fn leaves() {
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for len in [0, 1, 10, 500] {
+        let mut map: Map<usize, usize> = Map::new();
+        for _ in 0..len {
+            let key = rng.gen_range(0, 1000);
+            map.insert(key, key);
+        }
+
+        let btree = map.btree();
+        let expected: Vec<_> = btree
+            .chunks()
+            .filter(|chunk| chunk.is_leaf())
+            .map(|chunk| (chunk.node_id(), chunk.map(|b| b.key).collect::<Vec<_>>()))
+            .collect();
+        let leaves: Vec<_> = btree
+            .leaves()
+            .map(|leaf| {
+                assert!(leaf.is_leaf());
+                assert_eq!(leaf.len(), leaf.node().item_count());
+                (leaf.node_id(), leaf.map(|b| b.key).collect::<Vec<_>>())
+            })
+            .collect();
+
+        assert_eq!(leaves, expected);
+        assert_eq!(leaves.is_empty(), map.is_empty())
+    }
+}
+
+#[test]
+fn into_entries() {
+    let map: Map<usize, usize> = (0..100).map(|i| (i, i * 2)).collect();
+    let mut entries = map.into_entries();
+    assert_eq!(entries.len(), 100);
+
+    let last = entries.next_back().unwrap();
+    assert_eq!(last.as_pair(), (&99, &198));
+
+    assert!(entries
+        .map(|binding| binding.into_pair())
+        .eq((0..99).map(|i| (i, i * 2))));
+}
+
+#[test]
+fn drain_filter_panic() {
+    let mut map: Map<usize, usize> = (0..100).map(|i| (i, i)).collect();
+
+    // The predicate panics while the iterator is dropped.
+    let calls = Cell::new(0);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let mut drain = map.drain_filter(|k, _| {
+            calls.set(calls.get() + 1);
+            if *k == 50 {
+                panic!("predicate panic")
+            }
+
+            k % 2 == 0
+        });
+
+        assert_eq!(drain.next(), Some((0, 0)));
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(calls.get(), 51);
+    map.btree().validate().expect("validation failed");
+    assert!(map
+        .keys()
+        .cloned()
+        .eq((0..50).filter(|i| i % 2 != 0).chain(50..100)));
+
+    // The predicate panics while iterating from the back.
+    let calls = Cell::new(0);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        map.btree_mut()
+            .drain_filter(|item| {
+                calls.set(calls.get() + 1);
+                if item.key == 90 {
+                    panic!("predicate panic")
+                }
+
+                true
+            })
+            .rev()
+            .count()
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(calls.get(), 10);
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.len(), 66);
+    assert_eq!(map.last_key_value(), Some((&90, &90)));
+}
+
+#[test]
+fn drain_filter_both_ends() {
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for len in [0, 10, 500] {
+        let mut map: Map<usize, usize> = (0..len).map(|i| (i, i)).collect();
+
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        {
+            let mut drain = map.drain_filter(|k, _| k % 3 != 0);
+            loop {
+                let item = if rng.gen() {
+                    drain.next().inspect(|item| front.push(*item))
+                } else {
+                    drain.next_back().inspect(|item| back.push(*item))
+                };
+
+                if item.is_none() {
+                    break;
+                }
+            }
+        }
+
+        map.btree().validate().expect("validation failed");
+        assert!(map.keys().cloned().eq((0..len).step_by(3)));
+
+        back.reverse();
+        front.extend(back);
+        assert!(front
+            .into_iter()
+            .eq((0..len).filter(|i| i % 3 != 0).map(|i| (i, i))));
+    }
+}
+
+fn check_extract_if<A>(mut map: AugmentedMap<usize, usize, A>, rng: &mut SmallRng)
+where
+    A: 'static + SubtreeLen + Augmentation<AugmentedMapStorage<usize, usize, A>>,
+{
+    let len = map.len();
+    let start = rng.gen_range(0, len + 1);
+    let end = rng.gen_range(start, len + 1);
+
+    let mut visited = Vec::new();
+    let mut front = Vec::new();
+    let mut back = Vec::new();
+    {
+        let mut extract = map.extract_if(start..end, |k, _| {
+            visited.push(*k);
+            k % 3 != 0
+        });
+        loop {
+            let item = if rng.gen() {
+                extract.next().inspect(|item| front.push(*item))
+            } else {
+                extract.next_back().inspect(|item| back.push(*item))
+            };
+
+            if item.is_none() {
+                break;
+            }
+        }
+    }
+
+    map.btree().validate().expect("validation failed");
+    assert!(map
+        .keys()
+        .cloned()
+        .eq((0..len).filter(|i| !(start..end).contains(i) || i % 3 == 0)));
+
+    visited.sort_unstable();
+    assert!(visited.into_iter().eq(start..end));
+
+    back.reverse();
+    front.extend(back);
+    assert!(front
+        .into_iter()
+        .eq((start..end).filter(|i| i % 3 != 0).map(|i| (i, i))));
+}
+
+#[test]
+fn extract_if() {
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for len in [0, 10, 500] {
+        for _ in 0..10 {
+            let map: Map<usize, usize> = (0..len).map(|i| (i, i)).collect();
+            check_extract_if(map, &mut rng);
+
+            let map: AugmentedMap<usize, usize, Count> = (0..len).map(|i| (i, i)).collect();
+            check_extract_if(map, &mut rng);
+        }
+    }
+}
+
+#[test]
+fn set_operations() {
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for len in [0, 10, 500] {
+        let a: std::collections::BTreeSet<usize> =
+            (0..len).map(|_| rng.gen_range(0, 2 * len)).collect();
+        let b: std::collections::BTreeSet<usize> =
+            (0..len).map(|_| rng.gen_range(0, 2 * len)).collect();
+
+        let map_a: Map<usize, ()> = a.iter().map(|k| (*k, ())).collect();
+        let map_b: Map<usize, ()> = b.iter().map(|k| (*k, ())).collect();
+        let (a_tree, b_tree) = (map_a.btree(), map_b.btree());
+
+        assert!(a_tree
+            .difference(b_tree)
+            .map(|binding| binding.key)
+            .eq(a.difference(&b).copied()));
+        assert!(a_tree
+            .intersection(b_tree)
+            .map(|binding| binding.key)
+            .eq(a.intersection(&b).copied()));
+        assert!(a_tree
+            .union(b_tree)
+            .map(|binding| binding.key)
+            .eq(a.union(&b).copied()));
+        assert!(a_tree
+            .symmetric_difference(b_tree)
+            .map(|binding| binding.key)
+            .eq(a.symmetric_difference(&b).copied()));
+        assert!(a_tree
+            .merge_iter(b_tree)
+            .map(|merged| match merged {
+                Merged::Left(a) => (a.key, true, false),
+                Merged::Right(b) => (b.key, false, true),
+                Merged::Both(a, b) => {
+                    assert_eq!(a.key, b.key);
+                    (a.key, true, true)
+                }
+            })
+            .eq(a.union(&b).map(|k| (*k, a.contains(k), b.contains(k)))));
+    }
+}
+
+#[test]
+fn merge_iter() {
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for len in [0, 10, 500] {
+        let a: std::collections::BTreeMap<usize, usize> = (0..len)
+            .map(|_| (rng.gen_range(0, 2 * len), rng.gen()))
+            .collect();
+        let b: std::collections::BTreeMap<usize, usize> = (0..len)
+            .map(|_| (rng.gen_range(0, 2 * len), rng.gen()))
+            .collect();
+
+        let map_a: Map<usize, usize> = a.clone().into_iter().collect();
+        let map_b: Map<usize, usize> = b.clone().into_iter().collect();
+
+        let mut expected = b.clone();
+        for (key, value) in &a {
+            expected
+                .entry(*key)
+                .and_modify(|v| *v = v.wrapping_add(*value))
+                .or_insert(*value);
+        }
+
+        let merged = map_a.merge_iter(&map_b).map(|merged| match merged {
+            Merged::Both((ka, va), (kb, vb)) => {
+                assert_eq!(ka, kb);
+                (*ka, va.wrapping_add(*vb))
+            }
+            merged => {
+                let (key, value) = merged.prefer_right();
+                (*key, *value)
+            }
+        });
+
+        assert!(merged.eq(expected.into_iter()))
+    }
+}
+
+#[test]
+fn set_predicates() {
+    let mut rng = SmallRng::from_seed(*SEED);
+
+    for len in [0, 1, 10, 100] {
+        for _ in 0..50 {
+            let a: std::collections::BTreeSet<usize> =
+                (0..len).map(|_| rng.gen_range(0, 2 * len + 1)).collect();
+            let b: std::collections::BTreeSet<usize> = if rng.gen() {
+                a.iter().copied().filter(|_| rng.gen()).collect()
+            } else {
+                (0..len).map(|_| rng.gen_range(0, 4 * len + 1)).collect()
+            };
+
+            let map_a: Map<usize, ()> = a.iter().map(|k| (*k, ())).collect();
+            let map_b: Map<usize, ()> = b.iter().map(|k| (*k, ())).collect();
+            let (a_tree, b_tree) = (map_a.btree(), map_b.btree());
+
+            assert_eq!(a_tree.is_subset(b_tree), a.is_subset(&b));
+            assert_eq!(b_tree.is_subset(a_tree), b.is_subset(&a));
+            assert_eq!(a_tree.is_superset(b_tree), a.is_superset(&b));
+            assert_eq!(a_tree.is_disjoint(b_tree), a.is_disjoint(&b));
+        }
+    }
+}
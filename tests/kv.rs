@@ -0,0 +1,118 @@
+use generic_btree::{
+    kv::{DefaultFormat, Error, KvStore, Map, MapStorage, MemoryStore, NodeFormat},
+    Storage,
+};
+use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
+
+const SEED: &[u8; 16] = b"testseedtestseed";
+
+#[test]
+pub fn reopen() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<u32> = (0..1000).collect();
+    keys.shuffle(&mut rng);
+
+    let mut map: Map<u32, u64, MemoryStore> =
+        Map::from_btree(MapStorage::create(MemoryStore::new(), DefaultFormat));
+    for key in &keys {
+        map.insert(*key, *key as u64);
+    }
+
+    map.btree_mut().sync().unwrap();
+    let records = map.btree().store().len();
+
+    keys.shuffle(&mut rng);
+    let (removed, kept) = keys.split_at(500);
+    for key in removed {
+        map.remove(key);
+    }
+
+    map.btree_mut().sync().unwrap();
+    assert!(map.btree().store().len() < records);
+    let store = map.into_btree().into_store();
+
+    let mut map: Map<u32, u64, MemoryStore> =
+        Map::from_btree(MapStorage::open(store, DefaultFormat).unwrap());
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.len(), kept.len());
+    for key in kept {
+        assert_eq!(map.get(key), Some(&(*key as u64)))
+    }
+
+    // Identifiers released before reopening are reused.
+    for key in removed {
+        map.insert(*key, 0);
+    }
+
+    map.btree_mut().sync().unwrap();
+    let map: Map<u32, u64, MemoryStore> =
+        Map::from_btree(MapStorage::open(map.into_btree().into_store(), DefaultFormat).unwrap());
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.len(), keys.len());
+}
+
+/// Format reversing the bytes of the default format.
+struct Reversed;
+
+impl<N> NodeFormat<N> for Reversed
+where
+    DefaultFormat: NodeFormat<N>,
+{
+    fn encode(&self, node: &N, buf: &mut Vec<u8>) {
+        let start = buf.len();
+        DefaultFormat.encode(node, buf);
+        buf[start..].reverse()
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Option<N> {
+        let mut bytes = bytes.to_vec();
+        bytes.reverse();
+        DefaultFormat.decode(&bytes)
+    }
+}
+
+#[test]
+pub fn custom_format() {
+    let mut map: Map<u32, String, MemoryStore, Reversed> =
+        Map::from_btree(MapStorage::create(MemoryStore::new(), Reversed));
+    for i in 0..100 {
+        map.insert(i, i.to_string());
+    }
+
+    map.btree_mut().sync().unwrap();
+    let store = map.into_btree().into_store();
+
+    assert!(matches!(
+        MapStorage::<u32, String, _>::open(store, DefaultFormat),
+        Err(Error::Corrupted(_))
+    ));
+}
+
+#[test]
+pub fn corrupted() {
+    let mut store = MemoryStore::new();
+    assert!(matches!(
+        MapStorage::<u32, u32, _>::open(MemoryStore::new(), DefaultFormat),
+        Err(Error::Corrupted(0))
+    ));
+
+    let mut map: Map<u32, u32, MemoryStore> =
+        Map::from_btree(MapStorage::create(MemoryStore::new(), DefaultFormat));
+    for i in 0..100 {
+        map.insert(i, i);
+    }
+
+    map.btree_mut().sync().unwrap();
+    let source = map.into_btree().into_store();
+    for id in 0..source.len() {
+        if let Some(bytes) = source.get(id).unwrap() {
+            store.put(id, &bytes).unwrap()
+        }
+    }
+
+    store.delete(1).unwrap();
+    assert!(matches!(
+        MapStorage::<u32, u32, _>::open(store, DefaultFormat),
+        Err(Error::Corrupted(1))
+    ));
+}
@@ -0,0 +1,48 @@
+#![cfg(all(feature = "mmap", unix))]
+use generic_btree::{
+    mmap::{Map, MapStorage},
+    Storage,
+};
+use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
+
+const SEED: &[u8; 16] = b"testseedtestseed";
+
+#[test]
+pub fn reopen() {
+    let path = std::env::temp_dir().join(format!("generic-btree-mmap-{}", std::process::id()));
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..1000).collect();
+    keys.shuffle(&mut rng);
+
+    let mut map: Map<usize, usize> = Map::from_btree(unsafe { MapStorage::create(&path).unwrap() });
+    for key in &keys {
+        map.insert(*key, *key * 2);
+    }
+
+    keys.shuffle(&mut rng);
+    let (removed, kept) = keys.split_at(500);
+    for key in removed {
+        assert_eq!(map.remove(key), Some(*key * 2));
+    }
+
+    map.btree_mut().flush().unwrap();
+    std::mem::drop(map);
+
+    let mut map: Map<usize, usize> = Map::from_btree(unsafe { MapStorage::open(&path).unwrap() });
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.len(), kept.len());
+    for key in kept {
+        assert_eq!(map.get(key), Some(&(*key * 2)))
+    }
+
+    for key in removed {
+        assert!(map.get(key).is_none());
+        map.insert(*key, *key);
+    }
+
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.len(), 1000);
+
+    std::mem::drop(map);
+    std::fs::remove_file(path).unwrap()
+}
@@ -0,0 +1,84 @@
+use generic_btree::multi_index::{KeyExtractor, MultiIndex};
+use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
+
+const SEED: &[u8; 16] = b"testseedtestseed";
+
+#[derive(Debug, PartialEq)]
+struct Item {
+    id: usize,
+    square: usize,
+}
+
+struct ById;
+
+impl KeyExtractor<Item> for ById {
+    type Key = usize;
+
+    fn key(item: &Item) -> usize {
+        item.id
+    }
+}
+
+struct ByReversedSquare;
+
+impl KeyExtractor<Item> for ByReversedSquare {
+    type Key = std::cmp::Reverse<usize>;
+
+    fn key(item: &Item) -> std::cmp::Reverse<usize> {
+        std::cmp::Reverse(item.square)
+    }
+}
+
+#[test]
+pub fn insert_remove() {
+    let mut container: MultiIndex<Item, (ById, ByReversedSquare)> = MultiIndex::new();
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut ids: Vec<usize> = (0..100).collect();
+    ids.shuffle(&mut rng);
+
+    for id in &ids {
+        let item = Item {
+            id: *id,
+            square: id * id,
+        };
+        assert!(container.insert(item).is_ok())
+    }
+
+    // conflicts in the second index only.
+    assert!(container
+        .insert(Item {
+            id: 1000,
+            square: 4
+        })
+        .is_err());
+    assert_eq!(container.len(), 100);
+    assert!(!container.index::<ById, _>().contains_key(&1000));
+
+    let by_id: Vec<usize> = container.index::<ById, _>().iter().map(|i| i.id).collect();
+    assert_eq!(by_id, (0..100).collect::<Vec<_>>());
+
+    let by_square: Vec<usize> = container
+        .index::<ByReversedSquare, _>()
+        .iter()
+        .map(|i| i.id)
+        .collect();
+    assert_eq!(by_square, (0..100).rev().collect::<Vec<_>>());
+
+    ids.shuffle(&mut rng);
+    for (n, id) in ids.iter().enumerate() {
+        let item = if n % 2 == 0 {
+            container.remove::<ById, _, _>(id)
+        } else {
+            container.remove::<ByReversedSquare, _, _>(&std::cmp::Reverse(id * id))
+        };
+
+        assert_eq!(item.map(|i| i.id), Some(*id));
+        assert!(container.index::<ById, _>().get(id).is_none());
+        assert!(!container
+            .index::<ByReversedSquare, _>()
+            .contains_key(&std::cmp::Reverse(id * id)));
+        assert_eq!(container.len(), 99 - n)
+    }
+
+    assert!(container.is_empty())
+}
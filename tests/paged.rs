@@ -0,0 +1,243 @@
+use generic_btree::{
+    map::Binding,
+    paged::{
+        cache::CachedPager,
+        wal::{MemoryLog, WalPager},
+        Error, Map, MapStorage, MemoryPager, Pager,
+    },
+    Storage,
+};
+use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
+
+const SEED: &[u8; 16] = b"testseedtestseed";
+
+#[test]
+pub fn reopen() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<u32> = (0..1000).collect();
+    keys.shuffle(&mut rng);
+
+    let mut map: Map<u32, u64, MemoryPager> =
+        Map::from_btree(MapStorage::create(MemoryPager::new(256)));
+    for key in &keys {
+        map.insert(*key, *key as u64);
+    }
+
+    map.btree_mut().sync().unwrap();
+
+    keys.shuffle(&mut rng);
+    let (removed, kept) = keys.split_at(500);
+    for key in removed {
+        map.remove(key);
+    }
+
+    map.btree_mut().sync().unwrap();
+    let pager = map.into_btree().into_pager();
+
+    let mut map: Map<u32, u64, MemoryPager> = Map::from_btree(MapStorage::open(pager).unwrap());
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.len(), kept.len());
+    for key in kept {
+        assert_eq!(map.get(key), Some(&(*key as u64)))
+    }
+
+    for key in removed {
+        assert!(map.get(key).is_none());
+        map.insert(*key, 0);
+    }
+
+    map.btree_mut().sync().unwrap();
+    let pager = map.into_btree().into_pager();
+
+    let map: Map<u32, u64, MemoryPager> = Map::from_btree(MapStorage::open(pager).unwrap());
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.len(), 1000)
+}
+
+#[test]
+pub fn bulk_load() {
+    for len in [0u32, 1, 7, 8, 100, 1000, 5000] {
+        let items = (0..len).map(|i| Binding::new(i, i as u64));
+        let (storage, stats) =
+            MapStorage::<u32, u64, MemoryPager>::bulk_load(MemoryPager::new(256), items).unwrap();
+        storage.validate().expect("validation failed");
+        assert_eq!(storage.len(), len as usize);
+        assert_eq!(stats.height, storage.height());
+        assert_eq!(stats.page_count == 0, len == 0);
+
+        let pager = storage.into_pager();
+        let map: Map<u32, u64, MemoryPager> = Map::from_btree(MapStorage::open(pager).unwrap());
+        map.btree().validate().expect("validation failed");
+        assert_eq!(map.len(), len as usize);
+        for key in 0..len {
+            assert_eq!(map.get(&key), Some(&(key as u64)))
+        }
+    }
+}
+
+#[test]
+pub fn overflow() {
+    let mut map: Map<u32, String, MemoryPager> =
+        Map::from_btree(MapStorage::create(MemoryPager::new(64)));
+    map.insert(
+        0,
+        "a string too long to fit in a single page of 64 bytes".to_string(),
+    );
+
+    assert!(matches!(map.btree_mut().sync(), Err(Error::Overflow(_))))
+}
+
+#[cfg(feature = "std")]
+#[test]
+pub fn file() {
+    use generic_btree::paged::FilePager;
+
+    let path = std::env::temp_dir().join(format!("generic-btree-paged-{}", std::process::id()));
+
+    let mut map: Map<u32, u32, FilePager> =
+        Map::from_btree(MapStorage::create(FilePager::create(&path, 512).unwrap()));
+    for i in 0..1000 {
+        map.insert(i, i * 2);
+    }
+
+    map.btree_mut().sync().unwrap();
+    std::mem::drop(map);
+
+    let map: Map<u32, u32, FilePager> =
+        Map::from_btree(MapStorage::open(FilePager::open(&path, 512).unwrap()).unwrap());
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.len(), 1000);
+    assert_eq!(map.get(&42), Some(&84));
+
+    std::fs::remove_file(path).unwrap()
+}
+
+/// Pager failing every write once its write budget is exhausted.
+struct CrashingPager {
+    inner: MemoryPager,
+    writes: usize,
+}
+
+impl Pager for CrashingPager {
+    type Error = ();
+
+    fn page_size(&self) -> usize {
+        self.inner.page_size()
+    }
+
+    fn read_page(&mut self, id: usize, buf: &mut [u8]) -> Result<(), ()> {
+        self.inner.read_page(id, buf).map_err(|_| ())
+    }
+
+    fn write_page(&mut self, id: usize, buf: &[u8]) -> Result<(), ()> {
+        if self.writes == 0 {
+            return Err(());
+        }
+
+        self.writes -= 1;
+        self.inner.write_page(id, buf).map_err(|_| ())
+    }
+
+    fn allocate(&mut self) -> usize {
+        self.inner.allocate()
+    }
+
+    fn free(&mut self, id: usize) {
+        self.inner.free(id)
+    }
+}
+
+#[test]
+pub fn wal_recovery() {
+    let pager = CrashingPager {
+        inner: MemoryPager::new(256),
+        writes: 3,
+    };
+
+    let wal = WalPager::open(pager, MemoryLog::new()).unwrap();
+    let mut map: Map<u32, u32, _> = Map::from_btree(MapStorage::create(wal));
+    for i in 0..1000 {
+        map.insert(i, i * 2);
+    }
+
+    // The log is committed, but the pages cannot all be written.
+    assert!(map.btree_mut().sync().is_err());
+
+    let (pager, log) = map.into_btree().into_pager().into_inner();
+    let wal = WalPager::open(pager.inner, log).unwrap();
+    let map: Map<u32, u32, _> = Map::from_btree(MapStorage::open(wal).unwrap());
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.len(), 1000);
+    assert_eq!(map.get(&42), Some(&84))
+}
+
+#[test]
+pub fn checksum() {
+    let mut map: Map<u32, u32, MemoryPager> =
+        Map::from_btree(MapStorage::create(MemoryPager::new(256)));
+    for i in 0..1000 {
+        map.insert(i, i);
+    }
+
+    map.btree_mut().sync().unwrap();
+    let root = map.btree().root().unwrap();
+    let mut pager = map.into_btree().into_pager();
+
+    let mut page = vec![0; pager.page_size()];
+    pager.read_page(root, &mut page).unwrap();
+    page[12] ^= 1;
+    pager.write_page(root, &page).unwrap();
+
+    assert!(
+        matches!(MapStorage::<u32, u32, _>::open(pager), Err(Error::Checksum(id)) if id == root)
+    )
+}
+
+#[test]
+pub fn cache() {
+    let mut pager = CachedPager::new(MemoryPager::new(64), 3 * 64);
+    let mut page = vec![0; 64];
+
+    let ids: Vec<usize> = (0..4).map(|_| pager.allocate()).collect();
+    for (i, id) in ids.iter().enumerate() {
+        page[0] = i as u8;
+        pager.write_page(*id, &page).unwrap();
+    }
+
+    // The first page has been evicted.
+    assert_eq!(pager.stats().evictions, 1);
+    assert_eq!(pager.cached_bytes(), 3 * 64);
+
+    pager.read_page(ids[0], &mut page).unwrap();
+    assert_eq!(page[0], 0);
+    assert_eq!(pager.stats().misses, 1);
+
+    // Pinned pages are never evicted.
+    pager.pin(ids[0]).unwrap();
+    for id in &ids[1..] {
+        pager.read_page(*id, &mut page).unwrap();
+    }
+
+    let misses = pager.stats().misses;
+    pager.read_page(ids[0], &mut page).unwrap();
+    assert_eq!(pager.stats().misses, misses);
+    pager.unpin(ids[0]);
+
+    let map: Map<u32, u32, _> = {
+        let mut map: Map<u32, u32, _> = Map::from_btree(MapStorage::create(CachedPager::new(
+            MemoryPager::new(256),
+            16 * 256,
+        )));
+        for i in 0..1000 {
+            map.insert(i, i);
+        }
+
+        map.btree_mut().sync().unwrap();
+        let pager = map.into_btree().into_pager();
+        Map::from_btree(MapStorage::open(pager).unwrap())
+    };
+
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.len(), 1000);
+    assert!(map.btree().pager().stats().hits > 0)
+}
@@ -0,0 +1,96 @@
+use generic_btree::{
+    persistent::{Map, SyncMap},
+    Storage,
+};
+use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
+
+const SEED: &[u8; 16] = b"testseedtestseed";
+
+#[test]
+pub fn versions() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..1000).collect();
+    keys.shuffle(&mut rng);
+
+    let mut map: Map<usize, usize> = Map::new();
+    let mut versions = Vec::new();
+    for (i, key) in keys.iter().enumerate() {
+        if i % 100 == 0 {
+            versions.push((i, map.clone()))
+        }
+
+        map.insert(*key, *key);
+    }
+
+    keys.shuffle(&mut rng);
+    for key in &keys[..500] {
+        map.remove(key);
+        map.btree().validate().expect("validation failed");
+    }
+
+    assert_eq!(map.len(), 500);
+    for (len, version) in &versions {
+        version.btree().validate().expect("validation failed");
+        assert_eq!(version.len(), *len);
+    }
+}
+
+#[test]
+pub fn sharing() {
+    let mut map: Map<usize, usize> = Map::new();
+    for i in 0..1000 {
+        map.insert(i, i);
+    }
+
+    let snapshot = Map::from_btree(map.btree().snapshot());
+    let root = map.btree().root().unwrap();
+    assert!(map.btree().is_shared(root));
+
+    // Only the modified node is copied.
+    *map.get_mut(&0).unwrap() = 1;
+    let modified = map.btree().address_of(&0).unwrap();
+    assert!(!map.btree().is_shared(modified.id));
+    assert!(map.btree().is_shared(root));
+
+    let address = map.btree().address_of(&999).unwrap();
+    assert!(map.btree().is_shared(address.id));
+
+    assert_eq!(map.get(&0), Some(&1));
+    assert_eq!(snapshot.get(&0), Some(&0));
+}
+
+#[test]
+pub fn sync_clones() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..1000).collect();
+    keys.shuffle(&mut rng);
+
+    let mut map: SyncMap<usize, usize> = SyncMap::new();
+    for key in &keys {
+        map.insert(*key, *key);
+    }
+
+    let handles: Vec<_> = (0..4)
+        .map(|t| {
+            let mut map = map.clone();
+            let keys = keys.clone();
+            std::thread::spawn(move || {
+                for key in keys.iter().filter(|key| *key % 4 == t) {
+                    map.remove(key);
+                }
+
+                map.btree().validate().expect("validation failed");
+                map
+            })
+        })
+        .collect();
+
+    for (t, handle) in handles.into_iter().enumerate() {
+        let version = handle.join().unwrap();
+        assert_eq!(version.len(), 750);
+        assert!(version.iter().all(|(key, _)| key % 4 != t))
+    }
+
+    assert_eq!(map.len(), 1000);
+    map.btree().validate().expect("validation failed");
+}
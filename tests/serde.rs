@@ -0,0 +1,45 @@
+#![cfg(feature = "serde")]
+use generic_btree::{
+    slab::{soa, Map},
+    Storage,
+};
+use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
+
+const SEED: &[u8; 16] = b"testseedtestseed";
+
+#[test]
+pub fn round_trip() {
+    let map: Map<u32, String> = (0..500).map(|i| (i, i.to_string())).collect();
+    let json = serde_json::to_string(&map).unwrap();
+    assert!(json.starts_with(r#"[[0,"0"],[1,"1"],"#));
+
+    let copy: Map<u32, String> = serde_json::from_str(&json).unwrap();
+    copy.btree().validate().expect("validation failed");
+    assert!(copy.iter().eq(map.iter()));
+
+    let copy: soa::Map<u32, String> = serde_json::from_str(&json).unwrap();
+    copy.btree().validate().expect("validation failed");
+    assert!(copy.iter().eq(map.iter()));
+}
+
+#[test]
+pub fn unsorted() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut pairs: Vec<(u32, u32)> = (0..500).map(|i| (i, i)).collect();
+    pairs[..250].sort();
+    pairs[250..].shuffle(&mut rng);
+    pairs.push((42, 0));
+
+    let json = serde_json::to_string(&pairs).unwrap();
+    let map: Map<u32, u32> = serde_json::from_str(&json).unwrap();
+    map.btree().validate().expect("validation failed");
+    assert_eq!(map.len(), 500);
+    assert_eq!(map.get(&42), Some(&0));
+    assert!(map.keys().copied().eq(0..500));
+}
+
+#[test]
+pub fn invalid() {
+    assert!(serde_json::from_str::<Map<u32, u32>>("[[0, 0], [1, \"a\"]]").is_err());
+    assert!(serde_json::from_str::<Map<u32, u32>>("[[0, 0], [0, 1], [1]]").is_err());
+}
@@ -0,0 +1,49 @@
+#![cfg(feature = "sha2")]
+use generic_btree::{
+    augment::{MerkleHash, Sha256},
+    slab::{AugmentedMap, AugmentedMapStorage},
+};
+use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
+
+const SEED: &[u8; 16] = b"testseedtestseed";
+
+type MerkleMap = AugmentedMap<usize, usize, MerkleHash<Sha256>>;
+
+#[test]
+pub fn merkle_hash() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..300).collect();
+    keys.shuffle(&mut rng);
+
+    let mut a = MerkleMap::new();
+    let mut b = MerkleMap::from_btree(AugmentedMapStorage::with_order(4));
+    assert!(a.root_hash().is_none());
+
+    for key in &keys {
+        let hash = a.root_hash();
+        a.insert(*key, *key);
+        assert_ne!(a.root_hash(), hash);
+    }
+
+    for key in 0..300 {
+        b.insert(key, key);
+    }
+
+    let hash: [u8; 32] = a.root_hash().unwrap();
+    assert_eq!(b.root_hash(), Some(hash));
+
+    for key in &keys {
+        a.insert(*key, key + 1);
+        assert_ne!(a.root_hash(), Some(hash));
+        a.insert(*key, *key);
+        assert_eq!(a.root_hash(), Some(hash));
+    }
+
+    for key in &keys {
+        a.remove(key);
+        b.remove(key);
+        assert_eq!(a.root_hash(), b.root_hash());
+    }
+
+    assert!(a.root_hash().is_none())
+}
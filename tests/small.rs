@@ -0,0 +1,47 @@
+use generic_btree::{small::SmallMap, Storage};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+use std::collections::BTreeMap;
+
+const SEED: &[u8; 16] = b"testseedtestseed";
+
+#[test]
+pub fn insert_remove() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut map: SmallMap<u32, u32, 4> = SmallMap::new();
+    let mut reference = BTreeMap::new();
+    for _ in 0..2000 {
+        let key: u32 = rng.gen_range(0, 12);
+        if rng.gen_bool(0.5) {
+            let value: u32 = rng.gen();
+            assert_eq!(map.insert(key, value), reference.insert(key, value));
+        } else {
+            assert_eq!(map.remove(&key), reference.remove(&key));
+        }
+
+        if rng.gen_bool(0.1) {
+            map.shrink_to_fit()
+        }
+
+        assert_eq!(map.len(), reference.len());
+        assert!(map.iter().eq(reference.iter()));
+        assert_eq!(map.first_key_value(), reference.iter().next());
+        if let Some(tree) = map.tree() {
+            tree.btree().validate().unwrap()
+        }
+    }
+}
+
+#[test]
+pub fn inline_threshold() {
+    let mut map: SmallMap<usize, usize> = (0..8).map(|i| (i, i)).collect();
+    assert!(map.is_inline());
+
+    map.insert(8, 8);
+    assert!(!map.is_inline());
+    assert!(map.keys().copied().eq(0..9));
+
+    map.remove(&0);
+    map.shrink_to_fit();
+    assert!(map.is_inline());
+    assert!(map.keys().copied().eq(1..9));
+}
@@ -0,0 +1,85 @@
+use generic_btree::{
+    slab::soa::{Map, MapStorage},
+    Storage,
+};
+use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
+
+const SEED: &[u8; 16] = b"testseedtestseed";
+
+#[test]
+pub fn insert_remove() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..200).collect();
+
+    for order in 4..8 {
+        let mut map: Map<usize, String> = Map::from_btree(MapStorage::with_order(order));
+        for _ in 0..2 {
+            keys.shuffle(&mut rng);
+            for key in &keys {
+                assert!(map.insert(*key, key.to_string()).is_none());
+                map.btree().validate().expect("validation failed")
+            }
+
+            assert_eq!(map.len(), keys.len());
+            assert!(map.iter().all(|(k, v)| *v == k.to_string()));
+            assert!(map.keys().copied().eq(0..keys.len()));
+
+            for (_, v) in map.iter_mut() {
+                v.push('!')
+            }
+
+            assert_eq!(map[&42], "42!");
+            assert_eq!(map.insert(42, "x".to_string()), Some("42!".to_string()));
+            map.insert(42, "42!".to_string());
+
+            keys.shuffle(&mut rng);
+            for key in &keys {
+                assert_eq!(map.remove(key), Some(format!("{}!", key)));
+                map.btree().validate().expect("validation failed")
+            }
+
+            assert!(map.is_empty())
+        }
+    }
+}
+
+#[test]
+pub fn into_iter() {
+    let map: Map<usize, String> = (0..100).map(|i| (i, i.to_string())).collect();
+    let pairs: Vec<_> = map.into_iter().collect();
+    assert!(pairs.into_iter().eq((0..100).map(|i| (i, i.to_string()))));
+}
+
+/// Searches maps with primitive integer keys,
+/// which are searched with SIMD instructions when the `simd` feature is enabled.
+#[test]
+pub fn integer_keys() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<i64> = (-100..100).map(|i| i * 3).collect();
+
+    for order in [4, 8, 17, 32] {
+        let mut map: Map<i64, i64, 32> = Map::from_btree(MapStorage::with_order(order));
+        keys.shuffle(&mut rng);
+        for key in &keys {
+            map.insert(*key, -key);
+        }
+
+        map.btree().validate().expect("validation failed");
+        for key in -301..301 {
+            let expected = if key % 3 == 0 && key < 300 {
+                Some(-key)
+            } else {
+                None
+            };
+            assert_eq!(map.get(&key).copied(), expected);
+        }
+
+        assert!(map
+            .range(-10..10)
+            .map(|(k, _)| *k)
+            .eq([-9, -6, -3, 0, 3, 6, 9]));
+    }
+
+    let small: Map<u8, ()> = (0..=255u8).step_by(2).map(|k| (k, ())).collect();
+    assert!((0..=255u8).all(|k| small.contains_key(&k) == (k % 2 == 0)));
+}
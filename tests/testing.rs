@@ -0,0 +1,191 @@
+#![cfg(feature = "testing")]
+use generic_btree::{
+    slab::{CompactMap, Map},
+    testing::{
+        check_against_btree_map, check_ops, generate_ops, minimize, shrink_ops, Bytes, Failure,
+        Generate, Op, Violation,
+    },
+};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+const SEED: &[u8; 16] = b"testseedtestseed";
+
+fn random_bytes(rng: &mut SmallRng, len: usize) -> Vec<u8> {
+    (0..len).map(|_| rng.gen()).collect()
+}
+
+#[test]
+pub fn generate() {
+    let mut bytes = Bytes::new(&[1, 42, 0, 7, 3, 2]);
+    assert_eq!(Op::<u8, u8>::generate(&mut bytes), Some(Op::Remove(42)));
+    assert_eq!(Op::<u8, u8>::generate(&mut bytes), Some(Op::Insert(7, 3)));
+    assert_eq!(bytes.len(), 1);
+    assert_eq!(Op::<u8, u8>::generate(&mut bytes), None);
+
+    assert_eq!(5u8.shrink(), [0, 2, 4]);
+    assert_eq!((-5i8).shrink(), [0, -2, -4]);
+    assert_eq!(Some(1u8).shrink(), [None, Some(0)]);
+    assert!(0u8.shrink().is_empty());
+}
+
+#[test]
+pub fn check_random_ops() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    for _ in 0..10 {
+        let data = random_bytes(&mut rng, 8192);
+
+        let ops: Vec<Op<u8, u16>> = generate_ops(&data);
+        let mut map = Map::new();
+        check_ops(&mut map, &ops).expect("invariant violated");
+
+        let ops: Vec<Op<u16, u8>> = generate_ops(&data);
+        let mut map = CompactMap::new();
+        check_ops(&mut map, &ops).expect("invariant violated");
+    }
+}
+
+#[test]
+pub fn shrink() {
+    let ops: Vec<Op<u8, u8>> = vec![Op::Insert(4, 1), Op::Remove(2)];
+    let variants = shrink_ops(&ops);
+    assert_eq!(variants[0], [Op::Remove(2)]);
+    assert_eq!(variants[1], [Op::Insert(4, 1)]);
+    assert!(variants.contains(&vec![Op::Insert(0, 1), Op::Remove(2)]));
+    assert!(variants.contains(&vec![Op::Insert(4, 1), Op::Remove(1)]));
+
+    // Finds a minimal sequence leaving more than 3 bindings with keys above 100.
+    let fails = |ops: &[Op<u8, u8>]| {
+        let mut map: Map<u8, u8> = Map::new();
+        check_ops(&mut map, ops).unwrap();
+        map.range(101..).count() > 3
+    };
+
+    let mut rng = SmallRng::from_seed(*SEED);
+    let ops: Vec<Op<u8, u8>> = generate_ops(&random_bytes(&mut rng, 4096));
+    assert!(fails(&ops));
+
+    let ops = minimize(ops, fails);
+    assert_eq!(ops.len(), 4);
+    for op in &ops {
+        match op {
+            Op::Insert(key, 0) | Op::Update(key, Some(0)) | Op::Entry(key, Some(0)) => {
+                assert!(*key > 100)
+            }
+            op => panic!("unexpected operation {:?}", op),
+        }
+    }
+}
+
+#[test]
+pub fn differential() {
+    let mut rng = SmallRng::from_seed(*SEED);
+    for _ in 0..10 {
+        let data = random_bytes(&mut rng, 8192);
+
+        let ops: Vec<Op<u8, u16>> = generate_ops(&data);
+        let mut map = Map::new();
+        let model = check_against_btree_map(&mut map, &ops).expect("maps diverged");
+        assert!(map.iter().map(|(k, v)| (*k, *v)).eq(model.into_iter()));
+
+        let ops: Vec<Op<u16, u8>> = generate_ops(&data);
+        let mut map: CompactMap<u16, u8> = (0..100).map(|i| (i * 7, i as u8)).collect();
+        check_against_btree_map(&mut map, &ops).expect("maps diverged");
+    }
+}
+
+#[test]
+pub fn differential_entry() {
+    let mut map: Map<u8, u8> = Map::new();
+    let ops = [
+        Op::Entry(1, Some(10)),
+        Op::Entry(1, Some(11)),
+        Op::Get(1),
+        Op::Entry(2, None),
+        Op::Entry(1, None),
+        Op::Get(1),
+    ];
+    let model = check_against_btree_map(&mut map, &ops).unwrap();
+    assert!(model.is_empty());
+    assert!(map.is_empty());
+
+    // The reference map starts with the bindings of the map.
+    let mut map: Map<u8, u8> = (0..10).map(|i| (i, i)).collect();
+    assert_eq!(
+        check_against_btree_map(&mut map, &[Op::Get(4), Op::Range(8, 2)])
+            .unwrap()
+            .len(),
+        10
+    );
+    assert_eq!(
+        Failure {
+            step: 0,
+            violation: Violation::WrongLength(11, 10)
+        }
+        .to_string(),
+        "step 0: map has 11 bindings but the reference map has 10"
+    );
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+pub fn arbitrary_ops() {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    let mut rng = SmallRng::from_seed(*SEED);
+    for _ in 0..10 {
+        let data = random_bytes(&mut rng, 8192);
+        let mut u = Unstructured::new(&data);
+        let len = u.arbitrary_len::<Op<u8, u16>>().unwrap();
+        let ops: Vec<Op<u8, u16>> = (0..len).map(|_| u.arbitrary().unwrap()).collect();
+        assert!(len > 100);
+
+        let mut map = Map::new();
+        check_against_btree_map(&mut map, &ops).expect("invariant violated");
+    }
+
+    let mut u = Unstructured::new(&[1, 42]);
+    assert_eq!(Op::<u8, u8>::arbitrary(&mut u).unwrap(), Op::Remove(42));
+}
+
+#[cfg(feature = "proptest")]
+#[test]
+pub fn proptest_ops() {
+    use generic_btree::testing::ops_strategy;
+    use proptest::{
+        prelude::any,
+        test_runner::{Config, TestError, TestRunner},
+    };
+
+    let mut runner = TestRunner::new(Config::with_cases(64));
+    runner
+        .run(&ops_strategy(any::<u8>(), any::<u16>(), 0..512), |ops| {
+            let mut map = Map::new();
+            check_against_btree_map(&mut map, &ops).expect("invariant violated");
+            Ok(())
+        })
+        .unwrap();
+
+    // Shrinks down to a minimal sequence leaving more than 3 bindings with keys above 100.
+    let mut runner = TestRunner::new(Config::with_cases(256));
+    let result = runner.run(&ops_strategy(any::<u8>(), any::<u8>(), 0..512), |ops| {
+        let mut map: Map<u8, u8> = Map::new();
+        check_ops(&mut map, &ops).unwrap();
+        proptest::prop_assert!(map.range(101..).count() <= 3);
+        Ok(())
+    });
+
+    match result {
+        Err(TestError::Fail(_, ops)) => {
+            assert_eq!(ops.len(), 4);
+            for op in &ops {
+                match op {
+                    Op::Insert(key, _) | Op::Update(key, Some(_)) | Op::Entry(key, Some(_)) => {
+                        assert!(*key > 100)
+                    }
+                    op => panic!("unexpected operation {:?}", op),
+                }
+            }
+        }
+        result => panic!("unexpected result {:?}", result),
+    }
+}
@@ -0,0 +1,160 @@
+#![cfg(feature = "tracing")]
+use generic_btree::{slab::Map, trace::Event, Storage};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+use tracing::{
+    field::{Field, Visit},
+    span, Metadata, Subscriber,
+};
+
+/// Fields of a recorded event.
+#[derive(Default)]
+struct Fields(BTreeMap<&'static str, String>);
+
+impl Fields {
+    fn get(&self, name: &str) -> usize {
+        self.0[name].parse().unwrap()
+    }
+
+    fn into_event(self) -> Event {
+        match self.0["kind"].as_str() {
+            "descend" => Event::Descend {
+                id: self.get("id"),
+                depth: self.get("depth"),
+            },
+            "split" => Event::Split {
+                id: self.get("id"),
+                right: self.get("right"),
+                depth: self.get("depth"),
+            },
+            "merge" => Event::Merge {
+                left: self.get("left"),
+                right: self.get("right"),
+                depth: self.get("depth"),
+            },
+            "rotate" => Event::Rotate {
+                from: self.get("from"),
+                to: self.get("to"),
+                depth: self.get("depth"),
+            },
+            "rebalance" => Event::Rebalance {
+                id: self.get("id"),
+                depth: self.get("depth"),
+            },
+            kind => panic!("unknown event kind `{}`", kind),
+        }
+    }
+}
+
+impl Visit for Fields {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name(), value.to_string());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name(), format!("{:?}", value));
+    }
+}
+
+/// Subscriber recording the events of the tree algorithms.
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<Event>>>);
+
+impl Recorder {
+    fn take_events(&self) -> Vec<Event> {
+        std::mem::take(&mut self.0.lock().unwrap())
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.target() == "generic_btree::trace"
+    }
+
+    fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &tracing::Event<'_>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        self.0.lock().unwrap().push(fields.into_event())
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+#[test]
+pub fn events() {
+    let recorder = Recorder::default();
+    let mut map: Map<usize, usize> = Map::new();
+
+    tracing::subscriber::with_default(recorder.clone(), || {
+        for i in 0..1000 {
+            map.insert(i, i);
+        }
+    });
+
+    let height = map.btree().stats().height;
+    let events = recorder.take_events();
+    assert!(events.iter().any(|e| matches!(e, Event::Descend { .. })));
+    assert!(events
+        .iter()
+        .any(|e| matches!(e, Event::Split { depth, .. } if *depth == 0)));
+    for event in &events {
+        if let Event::Split { depth, .. } = event {
+            assert!(*depth < height)
+        }
+    }
+
+    tracing::subscriber::with_default(recorder.clone(), || {
+        map.get(&500);
+    });
+    let events = recorder.take_events();
+    assert!(!events.is_empty() && events.len() <= height);
+    for (i, event) in events.iter().enumerate() {
+        assert!(matches!(event, Event::Descend { depth, .. } if *depth == i))
+    }
+
+    tracing::subscriber::with_default(recorder.clone(), || {
+        for i in (0..1000).step_by(2) {
+            map.remove(&i);
+        }
+
+        for i in 0..500 {
+            map.remove(&(i * 2 + 1));
+        }
+    });
+
+    let events = recorder.take_events();
+    assert!(events.iter().any(|e| matches!(e, Event::Rebalance { .. })));
+    assert!(events.iter().any(|e| matches!(e, Event::Merge { .. })));
+    assert!(events.iter().any(|e| matches!(e, Event::Rotate { .. })));
+
+    // No event outside of the subscriber scope.
+    map.insert(0, 0);
+    assert!(recorder.take_events().is_empty());
+
+    assert_eq!(
+        Event::Split {
+            id: 3,
+            right: 7,
+            depth: 1
+        }
+        .to_string(),
+        "split n3 into n7 (depth 1)"
+    );
+}
@@ -0,0 +1,45 @@
+use generic_btree::{vec_storage::Map, Storage};
+use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
+
+const SEED: &[u8; 16] = b"testseedtestseed";
+
+#[test]
+pub fn insert_remove() {
+    let mut map: Map<usize, usize> = Map::new();
+    let mut rng = SmallRng::from_seed(*SEED);
+    let mut keys: Vec<usize> = (0..200).collect();
+
+    for _ in 0..2 {
+        keys.shuffle(&mut rng);
+        for key in &keys {
+            assert!(map.insert(*key, key * 2).is_none());
+            map.btree().validate().expect("validation failed")
+        }
+
+        assert_eq!(map.len(), keys.len());
+        assert!(map.iter().all(|(k, v)| *v == k * 2));
+
+        keys.shuffle(&mut rng);
+        for key in &keys {
+            assert_eq!(map.remove(key), Some(key * 2));
+            map.btree().validate().expect("validation failed")
+        }
+
+        assert!(map.is_empty())
+    }
+}
+
+#[test]
+pub fn rev() {
+    let map: Map<usize, usize> = (0..100).map(|i| (i, i * 2)).collect();
+
+    assert!(map.keys().rev().cloned().eq((0..100).rev()));
+    assert!(map.values().rev().cloned().eq((0..100).rev().map(|i| i * 2)));
+    assert!(map.range_keys(10..20).rev().cloned().eq((10..20).rev()));
+
+    let mut keys = map.keys();
+    assert_eq!(keys.next_back(), Some(&99));
+    assert_eq!(keys.next(), Some(&0));
+    assert_eq!(keys.len(), 98);
+    assert!(keys.rev().cloned().eq((1..99).rev()))
+}